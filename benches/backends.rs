@@ -0,0 +1,47 @@
+//! Sparse against dense statevectors, as a circuit spreads over more basis states.
+//!
+//! Every circuit has 14 qubits and puts the first `k` of them in superposition before entangling
+//! them, so that `2^k` amplitudes are nonzero. The sparse backend wins while `k` is small; the
+//! dense backend wins once most of the `2^14` amplitudes are in play.
+//!
+//! ```text
+//! cargo bench --bench backends
+//! ```
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use zana::circuit::backend::Backend;
+use zana::circuit::{gates, QuantumCircuit};
+
+const QUBITS: usize = 14;
+
+/// Hadamards on the first `superposed` qubits, then layers of rotations and CNOTs over them.
+fn circuit(superposed: usize) -> QuantumCircuit {
+    let mut circuit = QuantumCircuit::new(QUBITS);
+    for qubit in 0..superposed {
+        circuit.add_gate(gates::hadamard(), vec![qubit]);
+    }
+    for layer in 0..3 {
+        for qubit in 1..superposed {
+            circuit.add_gate(gates::cnot(), vec![qubit, qubit - 1]);
+            circuit.add_gate(gates::rotation_z(0.1 * (layer + qubit) as f64), vec![qubit]);
+        }
+    }
+    circuit
+}
+
+fn backends(c: &mut Criterion) {
+    let mut group = c.benchmark_group("backends");
+    group.sample_size(10);
+    for superposed in (2..=QUBITS).step_by(2) {
+        let circuit = circuit(superposed);
+        for backend in Backend::ALL {
+            group.bench_with_input(BenchmarkId::new(backend.name(), superposed), &circuit, |b, circuit| {
+                b.iter(|| circuit.simulate_with(backend))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, backends);
+criterion_main!(benches);
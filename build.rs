@@ -0,0 +1,36 @@
+//! Generates the C header of the `ffi` feature as `zana.h` in `OUT_DIR`, and copies it to the
+//! directory named by `ZANA_HEADER_DIR` if that is set.
+
+fn main() {
+    #[cfg(feature = "ffi")]
+    write_c_header();
+}
+
+#[cfg(feature = "ffi")]
+fn write_c_header() {
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-env-changed=ZANA_HEADER_DIR");
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("cargo sets CARGO_MANIFEST_DIR");
+    let out_dir = std::env::var("OUT_DIR").expect("cargo sets OUT_DIR");
+    let config = cbindgen::Config {
+        language: cbindgen::Language::C,
+        include_guard: Some("ZANA_H".to_string()),
+        header: Some("/* Generated from src/ffi.rs by build.rs. Do not edit. */".to_string()),
+        cpp_compat: true,
+        usize_is_size_t: true,
+        documentation_style: cbindgen::DocumentationStyle::C99,
+        enumeration: cbindgen::EnumConfig { prefix_with_name: true, ..Default::default() },
+        ..Default::default()
+    };
+    let bindings = cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(format!("{}/src/ffi.rs", crate_dir))
+        .generate()
+        .expect("src/ffi.rs should be valid for cbindgen");
+    bindings.write_to_file(format!("{}/zana.h", out_dir));
+
+    // Only written on request, so that builds from a read-only checkout leave the sources alone
+    if let Ok(header_dir) = std::env::var("ZANA_HEADER_DIR") {
+        bindings.write_to_file(std::path::Path::new(&header_dir).join("zana.h"));
+    }
+}
@@ -0,0 +1,30 @@
+use zana::algorithms::classifier::{moons_dataset, xor_dataset, AngleEncoding, VariationalClassifier, ZZFeatureMap};
+
+/// Trains variational quantum classifiers on the XOR and moons toy datasets.
+fn main() {
+    let xor = xor_dataset(5, 0.1, 7);
+    let mut classifier = VariationalClassifier::new(Box::new(AngleEncoding::new(2))).with_seed(1);
+    let history = classifier.fit(&xor);
+    println!(
+        "XOR:   loss {:.4} → {:.4}, accuracy {:.0}%",
+        history.first().unwrap(),
+        history.last().unwrap(),
+        100.0 * classifier.accuracy(&xor)
+    );
+
+    let moons = moons_dataset(60, 0.1, 11);
+    let (train, test) = moons.split_at(40);
+    for (name, mut classifier) in [
+        ("angle", VariationalClassifier::new(Box::new(AngleEncoding::new(2)))),
+        ("zz", VariationalClassifier::new(Box::new(ZZFeatureMap::new(2, 1)))),
+    ] {
+        classifier = classifier.with_seed(5).with_epochs(40);
+        classifier.fit(train);
+        println!(
+            "Moons ({} encoding): train accuracy {:.0}%, test accuracy {:.0}%",
+            name,
+            100.0 * classifier.accuracy(train),
+            100.0 * classifier.accuracy(test)
+        );
+    }
+}
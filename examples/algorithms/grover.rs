@@ -0,0 +1,27 @@
+use zana::algorithms::grover::{grover, grover_circuit, optimal_iterations};
+
+/// Demonstrates Grover's search on a 4-qubit search space.
+fn main() {
+    let n_qubits = 4;
+    let target = 11;
+    let iterations = optimal_iterations(n_qubits, 1);
+
+    println!("Searching {} items for |{:04b}⟩ with {} Grover iterations...", 1 << n_qubits, target, iterations);
+
+    let probabilities = grover_circuit(|x| x == target, n_qubits, iterations)
+        .simulate().statevector
+        .probabilities();
+    let mut states: Vec<_> = probabilities.into_iter().collect();
+    states.sort_by_key(|&(state, _)| state);
+    for (state, probability) in states {
+        println!("|{:04b}⟩: {:.4}", state, probability);
+    }
+
+    let found = grover(|x| x == target, n_qubits, iterations);
+    println!("Most probable states: {:?}", found);
+
+    // Two marked items need fewer iterations
+    let marked = [2, 13];
+    let found = grover(|x| marked.contains(&x), n_qubits, optimal_iterations(n_qubits, marked.len()));
+    println!("Marked {:?}, found {:?}", marked, found);
+}
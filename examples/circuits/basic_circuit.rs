@@ -31,7 +31,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Execute based on the `result` flag
     match result.as_str() {
         "raw" => {
-            let final_state = circuit.simulate();
+            let final_state = circuit.simulate().statevector;
             println!("Final statevector: {:?}", final_state);
         }
         "visual" => {
@@ -49,7 +49,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("Heatmap saved to 'examples/circuits/basic_circuit_heatmap.png'.");
         }
         "both" => {
-            let final_state = circuit.simulate();
+            let final_state = circuit.simulate().statevector;
             println!("RAW Final statevector: {:?}", final_state);
 
             println!("Now running Visual Circuit...");
@@ -34,7 +34,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Execute based on the `result` flag
     match result.as_str() {
         "raw" => {
-            let final_state = circuit.simulate();
+            let final_state = circuit.simulate().statevector;
             println!("Final statevector: {:?}", final_state);
         }
         "visual" => {
@@ -53,7 +53,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
         "both" => {
             // Run simulation first
-            let final_state = circuit.simulate();
+            let final_state = circuit.simulate().statevector;
             println!("RAW Final statevector: {:?}", final_state);
 
             // Delay before visualization
@@ -0,0 +1,203 @@
+/* Generated from src/ffi.rs by build.rs. Do not edit. */
+
+#ifndef ZANA_H
+#define ZANA_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The bytes AES-GCM adds to a plaintext for its authentication tag.
+#define ZANA_AES_GCM_TAG_LENGTH 16
+
+// The result of a call.
+typedef enum ZanaStatus {
+  ZanaStatus_Ok = 0,
+  // A required pointer was null.
+  ZanaStatus_NullPointer = 1,
+  // A qubit index, key or nonce was invalid.
+  ZanaStatus_InvalidArgument = 2,
+  // An output buffer had the wrong length.
+  ZanaStatus_BufferLength = 3,
+  // A signature did not verify.
+  ZanaStatus_InvalidSignature = 4,
+  // A ciphertext did not authenticate.
+  ZanaStatus_DecryptionFailed = 5,
+  // zana panicked; this is a bug.
+  ZanaStatus_Internal = 6,
+} ZanaStatus;
+
+// A quantum circuit, simulated from |0…0⟩.
+typedef struct ZanaCircuit ZanaCircuit;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Describes a status, as a static NUL-terminated string that must not be freed.
+const char *zana_status_message(enum ZanaStatus status);
+
+// Creates a circuit of `qubits` qubits, to be released with [`zana_circuit_free`].
+struct ZanaCircuit *zana_circuit_new(size_t qubits);
+
+// Releases a circuit. Does nothing for null.
+//
+// # Safety
+// `circuit` must be null or come from [`zana_circuit_new`], and must not be used afterwards.
+void zana_circuit_free(struct ZanaCircuit *circuit);
+
+// The number of qubits of a circuit, or 0 for null.
+//
+// # Safety
+// `circuit` must be null or a live circuit.
+size_t zana_circuit_qubits(const struct ZanaCircuit *circuit);
+
+// Adds a Hadamard gate.
+//
+// # Safety
+// `circuit` must be null or a live circuit.
+enum ZanaStatus zana_circuit_h(struct ZanaCircuit *circuit, size_t qubit);
+
+// Adds a Pauli-X gate.
+//
+// # Safety
+// `circuit` must be null or a live circuit.
+enum ZanaStatus zana_circuit_x(struct ZanaCircuit *circuit, size_t qubit);
+
+// Adds a Pauli-Y gate.
+//
+// # Safety
+// `circuit` must be null or a live circuit.
+enum ZanaStatus zana_circuit_y(struct ZanaCircuit *circuit, size_t qubit);
+
+// Adds a Pauli-Z gate.
+//
+// # Safety
+// `circuit` must be null or a live circuit.
+enum ZanaStatus zana_circuit_z(struct ZanaCircuit *circuit, size_t qubit);
+
+// Adds a rotation of `theta` radians about the X axis.
+//
+// # Safety
+// `circuit` must be null or a live circuit.
+enum ZanaStatus zana_circuit_rx(struct ZanaCircuit *circuit, size_t qubit, double theta);
+
+// Adds a rotation of `theta` radians about the Y axis.
+//
+// # Safety
+// `circuit` must be null or a live circuit.
+enum ZanaStatus zana_circuit_ry(struct ZanaCircuit *circuit, size_t qubit, double theta);
+
+// Adds a rotation of `theta` radians about the Z axis.
+//
+// # Safety
+// `circuit` must be null or a live circuit.
+enum ZanaStatus zana_circuit_rz(struct ZanaCircuit *circuit, size_t qubit, double theta);
+
+// Adds a CNOT flipping `target` when `control` is set.
+//
+// # Safety
+// `circuit` must be null or a live circuit.
+enum ZanaStatus zana_circuit_cnot(struct ZanaCircuit *circuit, size_t control, size_t target);
+
+// Adds a SWAP gate.
+//
+// # Safety
+// `circuit` must be null or a live circuit.
+enum ZanaStatus zana_circuit_swap(struct ZanaCircuit *circuit, size_t first, size_t second);
+
+// Simulates the circuit and writes the probability of measuring each basis state, indexed by
+// the state, to `out`, which holds `len` = 2^qubits values.
+//
+// # Safety
+// `circuit` must be null or a live circuit, and `out` must be null or point to `len` doubles.
+enum ZanaStatus zana_circuit_probabilities(const struct ZanaCircuit *circuit,
+                                           double *out,
+                                           size_t len);
+
+// Writes the SHA-256 digest of `len` bytes at `data` to the 32 bytes at `out`.
+//
+// # Safety
+// `data` must point to `len` bytes (or be null if `len` is 0), and `out` to 32 writable bytes.
+enum ZanaStatus zana_sha256(const uint8_t *data, size_t len, uint8_t *out);
+
+// Writes the SHA3-256 digest of `len` bytes at `data` to the 32 bytes at `out`.
+//
+// # Safety
+// `data` must point to `len` bytes (or be null if `len` is 0), and `out` to 32 writable bytes.
+enum ZanaStatus zana_sha3_256(const uint8_t *data, size_t len, uint8_t *out);
+
+// Writes the BLAKE2b-512 digest of `len` bytes at `data` to the 64 bytes at `out`.
+//
+// # Safety
+// `data` must point to `len` bytes (or be null if `len` is 0), and `out` to 64 writable bytes.
+enum ZanaStatus zana_blake2b512(const uint8_t *data, size_t len, uint8_t *out);
+
+// Generates an Ed25519 key, writing its 32-byte secret to `secret_out` and its 32-byte public
+// key to `public_out`.
+//
+// # Safety
+// `secret_out` and `public_out` must each point to 32 writable bytes.
+enum ZanaStatus zana_ed25519_generate(uint8_t *secret_out, uint8_t *public_out);
+
+// Signs `len` bytes at `message` with a 32-byte secret, writing the 64-byte signature to
+// `signature_out`.
+//
+// # Safety
+// `secret` must point to 32 bytes, `message` to `len` bytes (or be null if `len` is 0), and
+// `signature_out` to 64 writable bytes.
+enum ZanaStatus zana_ed25519_sign(const uint8_t *secret,
+                                  const uint8_t *message,
+                                  size_t len,
+                                  uint8_t *signature_out);
+
+// Checks a 64-byte signature of `len` bytes at `message` against a 32-byte public key, returning
+// [`ZanaStatus::Ok`] if it verifies and [`ZanaStatus::InvalidSignature`] otherwise.
+//
+// # Safety
+// `public_key` must point to 32 bytes, `message` to `len` bytes (or be null if `len` is 0), and
+// `signature` to 64 bytes.
+enum ZanaStatus zana_ed25519_verify(const uint8_t *public_key,
+                                    const uint8_t *message,
+                                    size_t len,
+                                    const uint8_t *signature);
+
+// Encrypts `len` bytes at `plaintext` with AES-256-GCM under a 32-byte key and a 12-byte nonce,
+// writing the ciphertext to `out`, which holds `out_len` = `len` + 16 bytes.
+//
+// # Safety
+// `key` must point to 32 bytes, `nonce` to 12, `plaintext` to `len` bytes (or be null if `len`
+// is 0), and `out` to `out_len` writable bytes.
+enum ZanaStatus zana_aes_gcm_encrypt(const uint8_t *key,
+                                     const uint8_t *nonce,
+                                     const uint8_t *plaintext,
+                                     size_t len,
+                                     uint8_t *out,
+                                     size_t out_len);
+
+// Decrypts `len` bytes at `ciphertext` from [`zana_aes_gcm_encrypt`], writing the plaintext to
+// `out`, which holds `out_len` = `len` - 16 bytes.
+//
+// # Safety
+// `key` must point to 32 bytes, `nonce` to 12, `ciphertext` to `len` bytes, and `out` to
+// `out_len` writable bytes.
+enum ZanaStatus zana_aes_gcm_decrypt(const uint8_t *key,
+                                     const uint8_t *nonce,
+                                     const uint8_t *ciphertext,
+                                     size_t len,
+                                     uint8_t *out,
+                                     size_t out_len);
+
+// Writes `len` random bytes from the operating system to `out`, for keys and nonces.
+//
+// # Safety
+// `out` must point to `len` writable bytes (or be null if `len` is 0).
+enum ZanaStatus zana_random_bytes(uint8_t *out, size_t len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ZANA_H */
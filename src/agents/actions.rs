@@ -1,31 +1,208 @@
 use super::agent::Agent;
+use super::value::Value;
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Parameters for actions.
 pub type ActionParams = HashMap<String, String>;
 
+/// The result of an action that succeeded.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ActionOutcome {
+    pub outputs: HashMap<String, String>, // Values the action produced
+    pub state_changes: HashMap<String, Value>, // Environment entries the action changes
+    pub duration: Duration, // Time spent executing, filled in by `Action::execute`
+}
+
+impl ActionOutcome {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an output value.
+    pub fn with_output(mut self, key: &str, value: &str) -> Self {
+        self.outputs.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Adds a change to apply to the environment.
+    pub fn with_state_change(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.state_changes.insert(key.to_string(), value.into());
+        self
+    }
+}
+
+/// An error raised by an action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActionError {
+    /// The agent has no action with this name.
+    NotFound(String),
+    /// A required parameter was not given.
+    MissingParameter(String),
+    /// A parameter could not be used, with its name and value.
+    InvalidParameter { name: String, value: String },
+    /// The action ran but could not complete, with a description of the problem.
+    Failed(String),
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionError::NotFound(action) => write!(f, "action '{}' not found", action),
+            ActionError::MissingParameter(name) => write!(f, "missing parameter '{}'", name),
+            ActionError::InvalidParameter { name, value } => {
+                write!(f, "invalid value {:?} for parameter '{}'", value, name)
+            }
+            ActionError::Failed(reason) => write!(f, "action failed: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ActionError {}
+
+/// The type of value a parameter takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamKind {
+    Text,
+    /// A whole number, such as `-3`.
+    Integer,
+    Number,
+    /// `true` or `false`.
+    Bool,
+}
+
+impl ParamKind {
+    /// Returns `true` if `value` is of this type.
+    pub fn accepts(self, value: &str) -> bool {
+        match self {
+            ParamKind::Text => true,
+            ParamKind::Integer => value.parse::<i64>().is_ok(),
+            ParamKind::Number => value.parse::<f64>().is_ok(),
+            ParamKind::Bool => value.parse::<bool>().is_ok(),
+        }
+    }
+}
+
+/// The schema of an action parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamSpec {
+    pub name: String,
+    pub description: String,
+    pub kind: ParamKind,
+    pub required: bool,
+}
+
+/// Reads a required parameter.
+pub fn required_param<'a>(params: &'a ActionParams, name: &str) -> Result<&'a str, ActionError> {
+    params
+        .get(name)
+        .map(String::as_str)
+        .ok_or_else(|| ActionError::MissingParameter(name.to_string()))
+}
+
+/// Execution logic of an action. Closures may capture configuration such as clients, channels
+/// or keys, and are shared between clones of the action.
+pub type ActionFn = Arc<dyn Fn(&mut Agent, ActionParams) -> Result<ActionOutcome, ActionError> + Send + Sync>;
+
 /// Represents an action an agent can perform.
+///
+/// Preconditions and effects describe the action to the [planner](super::planner): the action
+/// can only run when every precondition matches the environment, and afterwards the environment
+/// holds its effects.
 #[derive(Clone)]
 pub struct Action {
     pub name: String,
     pub description: String,
-    pub execute: fn(&mut Agent, ActionParams), // Execution logic
+    pub execute: ActionFn, // Execution logic
+    pub preconditions: HashMap<String, Value>, // Environment entries required to run
+    pub effects: HashMap<String, Value>, // Environment entries set by running
+    pub cost: f64, // Planning cost, 1 by default
+    pub params: Vec<ParamSpec>, // Declared parameters, checked before the action runs
 }
 
 impl Action {
-    pub fn new(
-        name: &str,
-        description: &str,
-        execute: fn(&mut Agent, ActionParams),
-    ) -> Self {
+    pub fn new<F>(name: &str, description: &str, execute: F) -> Self
+    where
+        F: Fn(&mut Agent, ActionParams) -> Result<ActionOutcome, ActionError> + Send + Sync + 'static,
+    {
         Self {
             name: name.to_string(),
             description: description.to_string(),
-            execute,
+            execute: Arc::new(execute),
+            preconditions: HashMap::new(),
+            effects: HashMap::new(),
+            cost: 1.0,
+            params: Vec::new(),
         }
     }
 
-    pub fn execute(&self, agent: &mut Agent, params: ActionParams) {
-        (self.execute)(agent, params);
+    /// Declares a required parameter.
+    pub fn with_param(self, name: &str, kind: ParamKind, description: &str) -> Self {
+        self.with_param_spec(name, kind, description, true)
+    }
+
+    /// Declares a parameter that may be left out.
+    pub fn with_optional_param(self, name: &str, kind: ParamKind, description: &str) -> Self {
+        self.with_param_spec(name, kind, description, false)
+    }
+
+    fn with_param_spec(mut self, name: &str, kind: ParamKind, description: &str, required: bool) -> Self {
+        self.params.retain(|param| param.name != name);
+        self.params.push(ParamSpec { name: name.to_string(), description: description.to_string(), kind, required });
+        self
+    }
+
+    /// Checks parameters against the declared ones: every required parameter must be given, and
+    /// every declared parameter must have a value of its type. Undeclared parameters are allowed.
+    pub fn validate(&self, params: &ActionParams) -> Result<(), ActionError> {
+        for spec in &self.params {
+            match params.get(&spec.name) {
+                Some(value) if !spec.kind.accepts(value) => {
+                    return Err(ActionError::InvalidParameter { name: spec.name.clone(), value: value.clone() });
+                }
+                None if spec.required => return Err(ActionError::MissingParameter(spec.name.clone())),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Requires the environment entry `key` to equal `value` before the action runs.
+    pub fn with_precondition(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.preconditions.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Declares that the action sets the environment entry `key` to `value`.
+    pub fn with_effect(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.effects.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Sets the cost the planner minimizes. Costs must not be negative.
+    pub fn with_cost(mut self, cost: f64) -> Self {
+        self.cost = cost;
+        self
+    }
+
+    /// Returns `true` if every precondition holds in `state`.
+    pub fn is_applicable(&self, state: &HashMap<String, Value>) -> bool {
+        self.preconditions.iter().all(|(key, value)| state.get(key) == Some(value))
+    }
+
+    /// Validates the parameters, runs the action and records how long it took. The declared
+    /// effects are added to the state changes, unless the action reported a different value for
+    /// the same entry.
+    pub fn execute(&self, agent: &mut Agent, params: ActionParams) -> Result<ActionOutcome, ActionError> {
+        self.validate(&params)?;
+        let start = Instant::now();
+        let mut outcome = (self.execute)(agent, params)?;
+        outcome.duration = start.elapsed();
+        for (key, value) in &self.effects {
+            outcome.state_changes.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+        Ok(outcome)
     }
 }
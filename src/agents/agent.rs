@@ -1,15 +1,62 @@
 use super::memory::Memory;
+use super::hooks::{AgentObserver, MemoryTier};
 use super::environment::Environment;
-use super::actions::{Action, ActionParams};
-use super::model_provider::ModelProvider;
-use std::collections::HashMap;
+use super::actions::{Action, ActionError, ActionOutcome, ActionParams};
+use super::models::{parse_choice, ChatMessage, DecisionRequest, ModelConfig, ModelProvider, TokenCallback};
+use super::perception::Perception;
+use super::policies::{ActionChoice, Policy, Transition};
+use super::react::{ReactError, ReactLoop, ReactRun};
+use super::system::Message;
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::sync::Arc;
+use std::io::{Error, ErrorKind};
+use serde::{Deserialize, Serialize};
+use super::skills::SkillRegistry;
+
+/// The version of the agent file format written by [`Agent::save`].
+pub const AGENT_FILE_VERSION: u64 = 2;
+
+const AGENT_FILE_FORMAT: &str = "zana-agent";
+
+/// The parts of an agent that are written to disk.
+#[derive(Serialize, Deserialize)]
+pub(super) struct SavedAgent {
+    format: String,
+    version: u64,
+    name: String,
+    memory: Memory,
+    actions: Vec<String>,
+    #[serde(default)]
+    skills: BTreeSet<String>,
+    #[serde(default)]
+    denied_skills: BTreeSet<String>,
+    model_provider: Option<SavedModel>,
+}
+
+/// A model provider as written by each file version: an endpoint URL in version 1, the
+/// provider settings since version 2.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum SavedModel {
+    Url(String),
+    Config(ModelConfig),
+}
 
 /// Represents an AGI Agent.
 pub struct Agent {
     pub name: String,
     pub memory: Memory,
     pub actions: HashMap<String, Action>,
-    pub model_provider: Option<ModelProvider>, // Optional AI model provider
+    pub model_provider: Option<Box<dyn ModelProvider>>, // Optional AI model provider
+    pub policy: Option<Box<dyn Policy>>, // Optional local decision policy
+    pub perception: Option<Perception>, // What the agent sees of the environment, everything if unset
+    pub inbox: Vec<Message>, // Messages received and not yet taken
+    pub outbox: Vec<Message>, // Messages waiting to be delivered by the agent system
+    pub observers: Vec<Arc<dyn AgentObserver>>, // Told about decisions, actions and memory writes
+    pub skills: BTreeSet<String>, // Skills granted from a skill registry
+    pub denied_skills: BTreeSet<String>, // Skills that cannot be granted
 }
 
 impl Agent {
@@ -19,6 +66,45 @@ impl Agent {
             memory: Memory::new(),
             actions: HashMap::new(),
             model_provider: None,
+            policy: None,
+            perception: None,
+            inbox: Vec::new(),
+            outbox: Vec::new(),
+            observers: Vec::new(),
+            skills: BTreeSet::new(),
+            denied_skills: BTreeSet::new(),
+        }
+    }
+
+    /// Adds an observer of the agent's decisions, actions and memory writes.
+    pub fn observe_with(&mut self, observer: Arc<dyn AgentObserver>) {
+        self.observers.push(observer);
+    }
+
+    /// Stores an observation in short-term memory and tells the observers.
+    pub fn remember(&mut self, key: &str, value: &str) {
+        self.memory.remember(key, value);
+        self.memory_written(MemoryTier::ShortTerm, key, value);
+    }
+
+    /// Stores knowledge in long-term memory and tells the observers.
+    pub fn store(&mut self, key: &str, value: &str) {
+        self.memory.store(key, value);
+        self.memory_written(MemoryTier::LongTerm, key, value);
+    }
+
+    fn memory_written(&self, tier: MemoryTier, key: &str, value: &str) {
+        tracing::trace!(agent = %self.name, ?tier, key, value, "memory write");
+        for observer in &self.observers {
+            observer.on_memory_write(&self.name, tier, key, value);
+        }
+    }
+
+    /// Tells the observers which action the policy or model provider chose.
+    pub fn notify_decision(&self, choice: &ActionChoice) {
+        tracing::debug!(agent = %self.name, action = choice.action().unwrap_or("idle"), "decision");
+        for observer in &self.observers {
+            observer.on_decision(&self.name, choice);
         }
     }
 
@@ -26,26 +112,400 @@ impl Agent {
         self.actions.insert(action.name.clone(), action);
     }
 
-    pub fn set_model_provider(&mut self, provider: ModelProvider) {
-        self.model_provider = Some(provider);
+    /// The names of the agent's actions, in sorted order.
+    pub fn action_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.actions.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
     }
 
-    pub async fn decide(&self, environment: &Environment) -> Option<String> {
-        if let Some(provider) = &self.model_provider {
-            provider
-                .decide(&self.memory.short_term, &environment.state)
-                .await
-                .ok()
-        } else {
-            None
+    pub fn set_model_provider(&mut self, provider: impl ModelProvider + 'static) {
+        self.model_provider = Some(Box::new(provider));
+    }
+
+    /// What the model provider is told about the agent when it decides.
+    pub fn decision_request(&self, environment: &Environment) -> DecisionRequest {
+        DecisionRequest {
+            agent: self.name.clone(),
+            actions: self.actions.values().map(|action| (action.name.clone(), action.description.clone())).collect(),
+            memory: self.memory.short_term.to_map(),
+            environment: environment.state.clone(),
         }
     }
 
-    pub fn execute_action(&mut self, action_name: &str, params: ActionParams) {
-        if let Some(action) = self.actions.get(action_name).cloned() {
-            action.execute(self, params);
+    /// Asks the model provider for the next action.
+    ///
+    /// Chat models are also sent the agent's conversation memory, and their answer is added to
+    /// it. Once the conversation outgrows its window, a summarizing conversation memory has the
+    /// model summarize the older messages.
+    ///
+    /// # Returns
+    /// `None` if the agent has no model provider or the request failed.
+    pub async fn decide(&mut self, environment: &Environment) -> Option<ActionChoice> {
+        self.decide_with(environment, None).await
+    }
+
+    /// Like [`Agent::decide`], passing each piece of the model's answer to `on_token` as it
+    /// arrives.
+    pub async fn decide_streaming(&mut self, environment: &Environment, on_token: &mut TokenCallback<'_>) -> Option<ActionChoice> {
+        self.decide_with(environment, Some(on_token)).await
+    }
+
+    async fn decide_with(&mut self, environment: &Environment, on_token: Option<&mut TokenCallback<'_>>) -> Option<ActionChoice> {
+        let provider = self.model_provider.as_ref()?;
+        let request = self.decision_request(environment);
+        let choice = if provider.config().backend.is_chat() {
+            let messages = self.memory.conversation.with_history(request.messages(provider.config()));
+            let answer = match on_token {
+                Some(on_token) => provider.complete_streaming(&messages, on_token).await,
+                None => provider.complete(&messages).await,
+            };
+            let answer = answer.ok()?;
+            self.memory.conversation.push(ChatMessage::assistant(&answer));
+            if self.memory.conversation.needs_compaction() {
+                // A failed summary keeps the messages, to be summarized next time.
+                let _ = self.memory.conversation.compact(provider.as_ref()).await;
+            }
+            parse_choice(&answer).unwrap_or_default()
         } else {
-            println!("Action '{}' not found.", action_name);
+            let choice = match on_token {
+                Some(on_token) => provider.decide_streaming(&request, on_token).await,
+                None => provider.decide(&request).await,
+            };
+            choice.ok()?
+        };
+        self.notify_decision(&choice);
+        Some(choice)
+    }
+
+    /// Works on a task with the model provider over several steps, executing the actions it
+    /// proposes, until it gives a final answer. See [`ReactLoop`].
+    pub async fn react(&mut self, environment: &mut Environment, task: &str) -> Result<ReactRun, ReactError> {
+        ReactLoop::new().run(self, environment, task).await
+    }
+
+    /// Replaces the agent's policy.
+    pub fn set_policy(&mut self, policy: impl Policy + 'static) {
+        self.policy = Some(Box::new(policy));
+    }
+
+    /// Limits what the agent sees of the environment.
+    pub fn set_perception(&mut self, perception: Perception) {
+        self.perception = Some(perception);
+    }
+
+    /// The environment as the agent perceives it.
+    pub fn observe<'a>(&self, environment: &'a Environment) -> Cow<'a, Environment> {
+        match &self.perception {
+            Some(perception) => Cow::Owned(perception.observe(environment)),
+            None => Cow::Borrowed(environment),
         }
     }
+
+    /// Asks the policy for the next action, given an environment the agent has observed. Agents
+    /// without a policy idle.
+    pub fn choose_action(&self, environment: &Environment) -> ActionChoice {
+        let choice = self
+            .policy
+            .as_ref()
+            .map_or(ActionChoice::Idle, |policy| policy.decide(self, environment));
+        self.notify_decision(&choice);
+        choice
+    }
+
+    /// Observes the environment, chooses the next action with the policy and executes it.
+    ///
+    /// # Returns
+    /// The choice that was made and the result of executing it. Idling always succeeds with an
+    /// empty outcome.
+    pub fn act(&mut self, environment: &Environment) -> (ActionChoice, Result<ActionOutcome, ActionError>) {
+        let choice = self.choose_action(&self.observe(environment));
+        let result = self.perform(&choice);
+        (choice, result)
+    }
+
+    /// Executes a choice made by a policy or model. Idling always succeeds with an empty outcome.
+    pub fn perform(&mut self, choice: &ActionChoice) -> Result<ActionOutcome, ActionError> {
+        match choice {
+            ActionChoice::Act { action, params } => self.execute_action(action, params.clone()),
+            ActionChoice::Idle => Ok(ActionOutcome::new()),
+        }
+    }
+
+    /// Lets the policy learn from the outcome of an action.
+    pub fn learn(&mut self, transition: &Transition) {
+        if let Some(policy) = self.policy.take() {
+            policy.learn(self, transition);
+            self.policy = Some(policy);
+        }
+    }
+
+    /// Executes the named action and records the result in short-term memory: `last_action`,
+    /// `last_action_status` (`"ok"` or the error), and each output as `"<action>.<output>"`.
+    ///
+    /// The observers are told when the action starts and ends, and the action runs inside an
+    /// `action` tracing span.
+    pub fn execute_action(&mut self, action_name: &str, params: ActionParams) -> Result<ActionOutcome, ActionError> {
+        let span = tracing::info_span!("action", agent = %self.name, action = action_name);
+        let _entered = span.enter();
+        for observer in &self.observers {
+            observer.on_action_start(&self.name, action_name, &params);
+        }
+        let result = match self.actions.get(action_name).cloned() {
+            Some(action) => action.execute(self, params),
+            None => Err(ActionError::NotFound(action_name.to_string())),
+        };
+        match &result {
+            Ok(outcome) => tracing::debug!(duration = ?outcome.duration, "action succeeded"),
+            Err(error) => tracing::warn!(%error, "action failed"),
+        }
+        for observer in &self.observers {
+            observer.on_action_end(&self.name, action_name, &result);
+        }
+        self.remember("last_action", action_name);
+        match &result {
+            Ok(outcome) => {
+                self.remember("last_action_status", "ok");
+                for (key, value) in &outcome.outputs {
+                    self.remember(&format!("{}.{}", action_name, key), value);
+                }
+            }
+            Err(error) => self.remember("last_action_status", &error.to_string()),
+        }
+        result
+    }
+
+    /// Accepts a delivered message: it is added to the inbox, remembered in short-term memory
+    /// as `"message_from.<sender>"` and added to the conversation as `"<sender>: <content>"`.
+    pub fn receive(&mut self, message: Message) {
+        self.remember(&format!("message_from.{}", message.from), &message.content);
+        self.memory.conversation.push(ChatMessage::user(&format!("{}: {}", message.from, message.content)));
+        self.inbox.push(message);
+    }
+
+    /// Takes every message from the inbox, oldest first.
+    pub fn take_messages(&mut self) -> Vec<Message> {
+        std::mem::take(&mut self.inbox)
+    }
+
+    /// Saves the agent's name, memory, action names, skills and model provider settings to a JSON
+    /// file.
+    ///
+    /// Action code, the policy and the perception cannot be written to disk, so only the action
+    /// names are kept.
+    pub fn save(&self, file_path: &str) -> std::io::Result<()> {
+        fs::write(file_path, serde_json::to_string_pretty(&self.to_saved())?)
+    }
+
+    /// Loads an agent saved by [`Agent::save`].
+    ///
+    /// Predefined actions are restored by name; other actions and the policy have to be added
+    /// again, for example by granting the agent's skills from a
+    /// [`SkillRegistry`](super::skills::SkillRegistry). Returns an `InvalidData` error if the file is not a supported agent file.
+    pub fn load(file_path: &str) -> std::io::Result<Self> {
+        let saved: SavedAgent = serde_json::from_str(&fs::read_to_string(file_path)?)
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid agent format"))?;
+        Self::from_saved(saved)
+    }
+
+    /// The parts of the agent written by [`Agent::save`].
+    pub(super) fn to_saved(&self) -> SavedAgent {
+        SavedAgent {
+            format: AGENT_FILE_FORMAT.to_string(),
+            version: AGENT_FILE_VERSION,
+            name: self.name.clone(),
+            memory: self.memory.clone(),
+            actions: self.action_names().into_iter().map(str::to_string).collect(),
+            skills: self.skills.clone(),
+            denied_skills: self.denied_skills.clone(),
+            model_provider: self.model_provider.as_ref().map(|provider| SavedModel::Config(provider.config().clone())),
+        }
+    }
+
+    /// Rebuilds an agent from the parts written by [`Agent::save`].
+    pub(super) fn from_saved(saved: SavedAgent) -> std::io::Result<Self> {
+        if saved.format != AGENT_FILE_FORMAT {
+            return Err(Error::new(ErrorKind::InvalidData, "Invalid agent format"));
+        }
+        if !(1..=AGENT_FILE_VERSION).contains(&saved.version) {
+            return Err(Error::new(ErrorKind::InvalidData, "Unsupported agent file version"));
+        }
+
+        let mut agent = Agent::new(&saved.name);
+        agent.memory = saved.memory;
+        agent.skills = saved.skills;
+        agent.denied_skills = saved.denied_skills;
+        let registry = SkillRegistry::predefined();
+        for action in saved.actions.iter().filter_map(|name| registry.action(name)) {
+            agent.add_action(action.clone());
+        }
+        agent.model_provider = saved.model_provider.map(|model| match model {
+            SavedModel::Url(api_url) => ModelConfig::endpoint(&api_url).connect(),
+            SavedModel::Config(config) => config.connect(),
+        });
+        Ok(agent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::actions::Action;
+    use crate::agents::predefined_actions;
+    use std::future::Future;
+    use std::sync::Mutex;
+    use std::task::{Context, Poll, Waker};
+    use crate::agents::memory::ConversationMemory;
+    use crate::agents::models::ollama::OllamaProvider;
+    use crate::agents::models::{ModelError, ModelFuture};
+
+    #[test]
+    fn test_save_and_load() {
+        let mut agent = Agent::new("archivist");
+        agent.add_action(predefined_actions::learn_action());
+        agent.add_action(Action::new("custom", "", |_, _| Ok(ActionOutcome::new())));
+        agent.set_model_provider(OllamaProvider::new(ModelConfig::ollama("llama3").with_system_prompt("Be curious.")));
+        agent.execute_action("learn", ActionParams::from([("concept".to_string(), "entropy".to_string())])).unwrap();
+        agent.memory.remember("mood", "curious");
+
+        let file_path = "test_agent.json";
+        agent.save(file_path).unwrap();
+        let loaded = Agent::load(file_path).unwrap();
+
+        assert_eq!(loaded.name, "archivist");
+        assert_eq!(loaded.memory, agent.memory);
+        assert_eq!(loaded.action_names(), vec!["learn"]);
+        assert_eq!(loaded.model_provider.unwrap().config(), agent.model_provider.unwrap().config());
+
+        let mut version_1: serde_json::Value = serde_json::from_str(&fs::read_to_string(file_path).unwrap()).unwrap();
+        version_1["version"] = 1.into();
+        version_1["model_provider"] = "http://localhost:8080/decide".into();
+        fs::write(file_path, version_1.to_string()).unwrap();
+        let loaded = Agent::load(file_path).unwrap();
+        fs::remove_file(file_path).unwrap();
+        assert_eq!(loaded.model_provider.unwrap().config(), &ModelConfig::endpoint("http://localhost:8080/decide"));
+    }
+
+    #[test]
+    fn test_load_version_1_file() {
+        // Written by version 1, when short-term memory was a plain map.
+        let version_1 = r#"{
+  "format": "zana-agent",
+  "version": 1,
+  "name": "archivist",
+  "memory": {
+    "long_term": {
+      "knowledge": "entropy"
+    },
+    "short_term": {
+      "last_action": "learn",
+      "last_action_status": "ok"
+    }
+  },
+  "actions": [
+    "learn"
+  ],
+  "model_provider": "http://localhost:8080/decide"
+}"#;
+        let file_path = "test_agent_version_1.json";
+        fs::write(file_path, version_1).unwrap();
+        let loaded = Agent::load(file_path);
+        fs::remove_file(file_path).unwrap();
+
+        let mut loaded = loaded.unwrap();
+        assert_eq!(loaded.action_names(), vec!["learn"]);
+        assert_eq!(loaded.memory.recall("knowledge"), Some(&"entropy".to_string()));
+        assert_eq!(loaded.memory.short_term.len(), 2);
+        assert_eq!(loaded.memory.recall_recent("last_action"), Some("learn".to_string()));
+        assert!(loaded.memory.conversation.is_empty());
+    }
+
+    #[test]
+    fn test_execute_action_records_outcomes() {
+        let mut agent = Agent::new("recorder");
+        agent.add_action(predefined_actions::gather_resources_action());
+        agent.add_action(Action::new("open_door", "", |_, _| {
+            Ok(ActionOutcome::new().with_state_change("door", "open"))
+        }));
+
+        let params = ActionParams::from([("resource".to_string(), "wood".to_string())]);
+        let outcome = agent.execute_action("gather_resources", params).unwrap();
+        assert_eq!(outcome.outputs["quantity"], "1");
+        assert_eq!(agent.memory.short_term.get("gather_resources.resource"), Some("wood"));
+        assert_eq!(agent.memory.short_term.get("last_action_status"), Some("ok"));
+
+        let params = ActionParams::from([("quantity".to_string(), "many".to_string())]);
+        let error = agent.execute_action("gather_resources", params).unwrap_err();
+        assert_eq!(error, ActionError::InvalidParameter { name: "quantity".to_string(), value: "many".to_string() });
+        assert_eq!(agent.memory.short_term.get("last_action_status"), Some(error.to_string().as_str()));
+
+        assert_eq!(agent.execute_action("fly", ActionParams::new()), Err(ActionError::NotFound("fly".to_string())));
+        assert_eq!(agent.memory.short_term.get("last_action"), Some("fly"));
+
+        let mut environment = Environment::new(HashMap::new());
+        environment.apply(&agent.execute_action("open_door", ActionParams::new()).unwrap());
+        assert_eq!(environment.text("door"), Some("open"));
+    }
+
+    /// Answers with scripted replies and records the conversations it was sent.
+    struct ScriptedModel {
+        config: ModelConfig,
+        replies: Mutex<Vec<&'static str>>,
+        conversations: Arc<Mutex<Vec<Vec<ChatMessage>>>>,
+    }
+
+    impl ModelProvider for ScriptedModel {
+        fn config(&self) -> &ModelConfig {
+            &self.config
+        }
+
+        fn complete<'a>(&'a self, messages: &'a [ChatMessage]) -> ModelFuture<'a, Result<String, ModelError>> {
+            self.conversations.lock().unwrap().push(messages.to_vec());
+            let reply = self.replies.lock().unwrap().remove(0);
+            Box::pin(async move { Ok(reply.to_string()) })
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("the scripted model never waits"),
+        }
+    }
+
+    #[test]
+    fn test_decide_with_conversation() {
+        let conversations = Arc::default();
+        let mut agent = Agent::new("trader");
+        agent.memory.conversation = ConversationMemory::new().with_window(2).with_summarization();
+        agent.set_model_provider(ScriptedModel {
+            config: ModelConfig::ollama("llama3"),
+            replies: Mutex::new(vec![r#"{"action": "offer"}"#, "Bob asked for wood; the trader offered some."]),
+            conversations: Arc::clone(&conversations),
+        });
+        agent.receive(Message::new("bob", Some("trader"), "wood?"));
+        agent.receive(Message::new("bob", Some("trader"), "please"));
+
+        let environment = Environment::new(HashMap::new());
+        assert_eq!(block_on(agent.decide(&environment)), Some(ActionChoice::act("offer")));
+        let conversations = conversations.lock().unwrap();
+        let decision = &conversations[0];
+        assert_eq!(decision[1], ChatMessage::user("bob: wood?"));
+        assert_eq!(decision[2], ChatMessage::user("bob: please"));
+        assert!(conversations[1][1].content.contains("user: bob: wood?"));
+
+        let conversation = &agent.memory.conversation;
+        assert_eq!(conversation.summary(), Some("Bob asked for wood; the trader offered some."));
+        assert_eq!(conversation.messages(), &[ChatMessage::user("bob: please"), ChatMessage::assistant(r#"{"action": "offer"}"#)]);
+    }
+
+    #[test]
+    fn test_load_rejects_other_files() {
+        let file_path = "test_agent_memory.json";
+        Memory::new().save(file_path).unwrap();
+        assert_eq!(Memory::load(file_path).unwrap(), Memory::new());
+        assert_eq!(Agent::load(file_path).err().unwrap().kind(), ErrorKind::InvalidData);
+        fs::remove_file(file_path).unwrap();
+    }
 }
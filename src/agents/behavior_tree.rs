@@ -0,0 +1,290 @@
+//! Behavior Trees
+//!
+//! A [`BehaviorTree`] drives an agent with a tree of nodes that is ticked once per step:
+//! - Sequences run their children in order until one does not succeed.
+//! - Selectors run their children in order until one does not fail.
+//! - Decorators change the result of a single child ([`Node::Inverter`], [`Node::Succeeder`],
+//!   [`Node::Repeat`]).
+//! - Conditions check the environment, and action leaves execute one of the agent's actions,
+//!   applying its state changes to the environment.
+//!
+//! Each tick evaluates the tree from the root, so a higher-priority branch takes over as soon as
+//! its condition holds. Trees are plain data and can be written as JSON:
+//!
+//! ```json
+//! { "type": "selector", "children": [
+//!     { "type": "sequence", "children": [
+//!         { "type": "condition", "key": "threat", "check": { "above": 0 } },
+//!         { "type": "action", "action": "retreat" } ] },
+//!     { "type": "action", "action": "gather_resources", "params": { "resource": "wood" } } ] }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use super::actions::ActionParams;
+use super::agent::Agent;
+use super::environment::Environment;
+use super::value::Value;
+
+/// The output an action sets to `"running"` to report that it has not finished yet.
+pub const STATUS_OUTPUT: &str = "status";
+
+/// The result of ticking a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Success,
+    Failure,
+    /// The node has not finished and should be ticked again.
+    Running,
+}
+
+/// A test on an environment entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Check {
+    /// The entry is present.
+    Exists,
+    /// The entry equals the value.
+    Equals(Value),
+    /// The entry is a number greater than the value.
+    Above(f64),
+    /// The entry is a number less than the value.
+    Below(f64),
+}
+
+/// A node of a behavior tree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Node {
+    /// Succeeds if every child succeeds, stopping at the first that does not.
+    Sequence { children: Vec<Node> },
+    /// Fails if every child fails, stopping at the first that does not.
+    Selector { children: Vec<Node> },
+    /// Swaps success and failure.
+    Inverter { child: Box<Node> },
+    /// Succeeds unless the child is still running.
+    Succeeder { child: Box<Node> },
+    /// Ticks the child up to `times` times in a row while it succeeds.
+    Repeat { times: u32, child: Box<Node> },
+    /// Succeeds if the check on the environment entry `key` holds.
+    Condition { key: String, check: Check },
+    /// Executes one of the agent's actions. Fails if the action is missing or returns an error.
+    Action {
+        action: String,
+        #[serde(default, skip_serializing_if = "ActionParams::is_empty")]
+        params: ActionParams,
+    },
+}
+
+impl Node {
+    /// A sequence of `children`.
+    pub fn sequence(children: Vec<Node>) -> Self {
+        Node::Sequence { children }
+    }
+
+    /// A selector over `children`.
+    pub fn selector(children: Vec<Node>) -> Self {
+        Node::Selector { children }
+    }
+
+    /// Inverts the result of `child`.
+    pub fn inverter(child: Node) -> Self {
+        Node::Inverter { child: Box::new(child) }
+    }
+
+    /// Turns failures of `child` into success.
+    pub fn succeeder(child: Node) -> Self {
+        Node::Succeeder { child: Box::new(child) }
+    }
+
+    /// Repeats `child` up to `times` times.
+    pub fn repeat(times: u32, child: Node) -> Self {
+        Node::Repeat { times, child: Box::new(child) }
+    }
+
+    /// Checks the environment entry `key`.
+    pub fn condition(key: &str, check: Check) -> Self {
+        Node::Condition { key: key.to_string(), check }
+    }
+
+    /// Executes the named action without parameters.
+    pub fn action(action: &str) -> Self {
+        Node::Action { action: action.to_string(), params: ActionParams::new() }
+    }
+
+    /// Executes the named action with parameters.
+    pub fn action_with(action: &str, params: ActionParams) -> Self {
+        Node::Action { action: action.to_string(), params }
+    }
+
+    fn tick(&self, agent: &mut Agent, environment: &mut Environment) -> Status {
+        match self {
+            Node::Sequence { children } => children
+                .iter()
+                .map(|child| child.tick(agent, environment))
+                .find(|status| *status != Status::Success)
+                .unwrap_or(Status::Success),
+            Node::Selector { children } => children
+                .iter()
+                .map(|child| child.tick(agent, environment))
+                .find(|status| *status != Status::Failure)
+                .unwrap_or(Status::Failure),
+            Node::Inverter { child } => match child.tick(agent, environment) {
+                Status::Success => Status::Failure,
+                Status::Failure => Status::Success,
+                Status::Running => Status::Running,
+            },
+            Node::Succeeder { child } => match child.tick(agent, environment) {
+                Status::Running => Status::Running,
+                _ => Status::Success,
+            },
+            Node::Repeat { times, child } => (0..*times)
+                .map(|_| child.tick(agent, environment))
+                .find(|status| *status != Status::Success)
+                .unwrap_or(Status::Success),
+            Node::Condition { key, check } => {
+                let holds = match check {
+                    Check::Exists => environment.state.contains_key(key),
+                    Check::Equals(value) => environment.state.get(key) == Some(value),
+                    Check::Above(threshold) => environment.number(key).is_some_and(|value| value > *threshold),
+                    Check::Below(threshold) => environment.number(key).is_some_and(|value| value < *threshold),
+                };
+                if holds { Status::Success } else { Status::Failure }
+            }
+            Node::Action { action, params } => match agent.execute_action(action, params.clone()) {
+                Ok(outcome) => {
+                    environment.apply(&outcome);
+                    match outcome.outputs.get(STATUS_OUTPUT).map(String::as_str) {
+                        Some("running") => Status::Running,
+                        _ => Status::Success,
+                    }
+                }
+                Err(_) => Status::Failure,
+            },
+        }
+    }
+}
+
+/// A behavior tree that drives an agent.
+///
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use zana::agents::{Agent, Environment, Value};
+/// use zana::agents::actions::{Action, ActionOutcome};
+/// use zana::agents::behavior_tree::{BehaviorTree, Status};
+///
+/// let tree = BehaviorTree::from_json(r#"{ "type": "sequence", "children": [
+///     { "type": "condition", "key": "door", "check": { "equals": "closed" } },
+///     { "type": "action", "action": "open_door" } ] }"#).unwrap();
+///
+/// let mut agent = Agent::new("butler");
+/// agent.add_action(Action::new("open_door", "", |_, _| Ok(ActionOutcome::new())).with_effect("door", "open"));
+/// let mut environment = Environment::new(HashMap::from([("door".to_string(), Value::from("closed"))]));
+///
+/// assert_eq!(tree.tick(&mut agent, &mut environment), Status::Success);
+/// assert_eq!(tree.tick(&mut agent, &mut environment), Status::Failure);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct BehaviorTree {
+    pub root: Node,
+}
+
+impl BehaviorTree {
+    pub fn new(root: Node) -> Self {
+        Self { root }
+    }
+
+    /// Parses a tree from its JSON definition.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Writes the tree as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("behavior trees always serialize")
+    }
+
+    /// Ticks the tree once, executing actions on `agent` and applying their state changes to
+    /// `environment`.
+    pub fn tick(&self, agent: &mut Agent, environment: &mut Environment) -> Status {
+        self.root.tick(agent, environment)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::agents::actions::{Action, ActionOutcome};
+
+    fn guard() -> BehaviorTree {
+        BehaviorTree::new(Node::selector(vec![
+            Node::sequence(vec![Node::condition("threat", Check::Above(0.0)), Node::action("retreat")]),
+            Node::sequence(vec![
+                Node::inverter(Node::condition("wood", Check::Equals(Value::from("full")))),
+                Node::action_with("gather", ActionParams::from([("resource".to_string(), "wood".to_string())])),
+            ]),
+            Node::action("rest"),
+        ]))
+    }
+
+    fn guard_agent() -> Agent {
+        let mut agent = Agent::new("guard");
+        agent.add_action(Action::new("retreat", "", |_, _| Ok(ActionOutcome::new())).with_effect("threat", 0));
+        agent.add_action(Action::new("gather", "", |_, params| {
+            Ok(ActionOutcome::new().with_state_change(&params["resource"], "full"))
+        }));
+        agent.add_action(Action::new("rest", "", |_, _| {
+            Ok(ActionOutcome::new().with_output(STATUS_OUTPUT, "running"))
+        }));
+        agent
+    }
+
+    #[test]
+    fn test_tick_drives_agent() {
+        let tree = guard();
+        let mut agent = guard_agent();
+        let mut environment = Environment::new(HashMap::from([("threat".to_string(), Value::from(2))]));
+
+        assert_eq!(tree.tick(&mut agent, &mut environment), Status::Success);
+        assert_eq!(agent.memory.short_term.get("last_action"), Some("retreat"));
+        assert_eq!(tree.tick(&mut agent, &mut environment), Status::Success);
+        assert_eq!(agent.memory.short_term.get("last_action"), Some("gather"));
+        assert_eq!(environment.text("wood"), Some("full"));
+        assert_eq!(tree.tick(&mut agent, &mut environment), Status::Running);
+        assert_eq!(agent.memory.short_term.get("last_action"), Some("rest"));
+    }
+
+    #[test]
+    fn test_decorators_and_missing_actions() {
+        let mut agent = guard_agent();
+        let mut environment = Environment::new(HashMap::new());
+        assert_eq!(Node::action("fly").tick(&mut agent, &mut environment), Status::Failure);
+        assert_eq!(Node::succeeder(Node::action("fly")).tick(&mut agent, &mut environment), Status::Success);
+        assert_eq!(Node::repeat(3, Node::action("retreat")).tick(&mut agent, &mut environment), Status::Success);
+        assert_eq!(Node::condition("threat", Check::Exists).tick(&mut agent, &mut environment), Status::Success);
+        assert_eq!(Node::condition("threat", Check::Below(0.0)).tick(&mut agent, &mut environment), Status::Failure);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let tree = guard();
+        assert_eq!(BehaviorTree::from_json(&tree.to_json()).unwrap(), tree);
+
+        let json = r#"{ "type": "selector", "children": [
+            { "type": "sequence", "children": [
+                { "type": "condition", "key": "threat", "check": { "above": 0 } },
+                { "type": "action", "action": "retreat" } ] },
+            { "type": "repeat", "times": 2, "child": { "type": "action", "action": "rest" } } ] }"#;
+        let parsed = BehaviorTree::from_json(json).unwrap();
+        assert_eq!(
+            parsed.root,
+            Node::selector(vec![
+                Node::sequence(vec![Node::condition("threat", Check::Above(0.0)), Node::action("retreat")]),
+                Node::repeat(2, Node::action("rest")),
+            ])
+        );
+        assert!(BehaviorTree::from_json(r#"{ "type": "parallel", "children": [] }"#).is_err());
+    }
+}
@@ -1,16 +1,57 @@
 use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use super::actions::ActionOutcome;
+use super::value::Value;
 
 /// Represents the environment the agent operates in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Environment {
-    pub state: HashMap<String, String>, // Environmental data
+    pub state: HashMap<String, Value>, // Environmental data
+    pub reward: f64, // Reward earned by the last action, for learning policies
 }
 
 impl Environment {
-    pub fn new(state: HashMap<String, String>) -> Self {
-        Self { state }
+    pub fn new(state: HashMap<String, Value>) -> Self {
+        Self { state, reward: 0.0 }
     }
 
-    pub fn update(&mut self, key: &str, value: &str) {
-        self.state.insert(key.to_string(), value.to_string());
+    pub fn update(&mut self, key: &str, value: impl Into<Value>) {
+        self.state.insert(key.to_string(), value.into());
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.state.get(key)
+    }
+
+    /// Applies the state changes of an action.
+    pub fn apply(&mut self, outcome: &ActionOutcome) {
+        for (key, value) in &outcome.state_changes {
+            self.update(key, value.clone());
+        }
+    }
+
+    /// Records the reward for the action that was just taken.
+    pub fn set_reward(&mut self, reward: f64) {
+        self.reward = reward;
+    }
+
+    /// Returns the pending reward and resets it to zero.
+    pub fn take_reward(&mut self) -> f64 {
+        std::mem::take(&mut self.reward)
+    }
+
+    /// Reads a number, or `None` if the entry is missing or not a number.
+    pub fn number(&self, key: &str) -> Option<f64> {
+        self.state.get(key).and_then(Value::as_number)
+    }
+
+    /// Reads a boolean, or `None` if the entry is missing or not a boolean.
+    pub fn bool(&self, key: &str) -> Option<bool> {
+        self.state.get(key).and_then(Value::as_bool)
+    }
+
+    /// Reads text, or `None` if the entry is missing or not text.
+    pub fn text(&self, key: &str) -> Option<&str> {
+        self.state.get(key).and_then(Value::as_text)
     }
 }
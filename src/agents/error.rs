@@ -0,0 +1,23 @@
+//! Agent Errors
+//!
+//! [`AgentError`] gathers the errors raised by agents, so that callers can handle a failed
+//! action, model request, skill grant or ReAct run in one place.
+
+use thiserror::Error;
+use super::actions::ActionError;
+use super::models::ModelError;
+use super::react::ReactError;
+use super::skills::SkillError;
+
+/// An error raised by an agent.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AgentError {
+    #[error(transparent)]
+    Action(#[from] ActionError),
+    #[error(transparent)]
+    Model(#[from] ModelError),
+    #[error(transparent)]
+    Skill(#[from] SkillError),
+    #[error(transparent)]
+    React(#[from] ReactError),
+}
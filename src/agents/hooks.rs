@@ -0,0 +1,185 @@
+//! Lifecycle Hooks
+//!
+//! An [`AgentObserver`] is told about everything an agent does: the decisions of its policy and
+//! model provider, the start and end of each action, and every memory write made through
+//! [`Agent::remember`](super::Agent::remember) and [`Agent::store`](super::Agent::store).
+//! Observers are added with [`Agent::observe_with`](super::Agent::observe_with) and shared, so one
+//! observer can watch many agents.
+//!
+//! [`EventLog`] is an observer that records every event, to be audited or written to disk as
+//! JSON lines and replayed later. The same events are also emitted as `tracing` events, inside an
+//! `action` span while an action runs.
+
+use std::fs;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use super::actions::{ActionError, ActionOutcome, ActionParams};
+use super::policies::ActionChoice;
+
+/// The memory an agent wrote to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryTier {
+    ShortTerm,
+    LongTerm,
+}
+
+/// Watches what agents do. Every method does nothing by default.
+pub trait AgentObserver: Send + Sync {
+    /// A policy or model provider chose the agent's next action.
+    fn on_decision(&self, _agent: &str, _choice: &ActionChoice) {}
+
+    /// An action is about to run.
+    fn on_action_start(&self, _agent: &str, _action: &str, _params: &ActionParams) {}
+
+    /// An action finished, successfully or not.
+    fn on_action_end(&self, _agent: &str, _action: &str, _result: &Result<ActionOutcome, ActionError>) {}
+
+    /// The agent wrote a value to its memory.
+    fn on_memory_write(&self, _agent: &str, _tier: MemoryTier, _key: &str, _value: &str) {}
+}
+
+/// What happened, as recorded by an [`EventLog`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AgentEventKind {
+    /// `action` is `None` when the agent chose to idle.
+    Decision { action: Option<String>, params: ActionParams },
+    ActionStart { action: String, params: ActionParams },
+    /// `error` is `None` if the action succeeded.
+    ActionEnd { action: String, outputs: ActionParams, error: Option<String>, duration_ms: f64 },
+    MemoryWrite { tier: MemoryTier, key: String, value: String },
+}
+
+/// An event recorded by an [`EventLog`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentEvent {
+    /// Milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    pub agent: String,
+    #[serde(flatten)]
+    pub kind: AgentEventKind,
+}
+
+/// An observer that records every event in order.
+#[derive(Debug, Default)]
+pub struct EventLog {
+    events: Mutex<Vec<AgentEvent>>,
+}
+
+impl EventLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The events recorded so far, oldest first.
+    pub fn events(&self) -> Vec<AgentEvent> {
+        self.lock().clone()
+    }
+
+    /// Removes and returns every recorded event.
+    pub fn take(&self) -> Vec<AgentEvent> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    /// Writes the recorded events to a file, one JSON object per line.
+    pub fn save(&self, file_path: &str) -> std::io::Result<()> {
+        let mut lines = String::new();
+        for event in self.lock().iter() {
+            lines.push_str(&serde_json::to_string(event)?);
+            lines.push('\n');
+        }
+        fs::write(file_path, lines)
+    }
+
+    /// Reads events written by [`EventLog::save`].
+    pub fn load(file_path: &str) -> std::io::Result<Vec<AgentEvent>> {
+        fs::read_to_string(file_path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(std::io::Error::from))
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<AgentEvent>> {
+        self.events.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn record(&self, agent: &str, kind: AgentEventKind) {
+        let timestamp_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+        self.lock().push(AgentEvent { timestamp_ms, agent: agent.to_string(), kind });
+    }
+}
+
+impl AgentObserver for EventLog {
+    fn on_decision(&self, agent: &str, choice: &ActionChoice) {
+        let kind = match choice {
+            ActionChoice::Act { action, params } => AgentEventKind::Decision { action: Some(action.clone()), params: params.clone() },
+            ActionChoice::Idle => AgentEventKind::Decision { action: None, params: ActionParams::new() },
+        };
+        self.record(agent, kind);
+    }
+
+    fn on_action_start(&self, agent: &str, action: &str, params: &ActionParams) {
+        self.record(agent, AgentEventKind::ActionStart { action: action.to_string(), params: params.clone() });
+    }
+
+    fn on_action_end(&self, agent: &str, action: &str, result: &Result<ActionOutcome, ActionError>) {
+        let kind = match result {
+            Ok(outcome) => AgentEventKind::ActionEnd {
+                action: action.to_string(),
+                outputs: outcome.outputs.clone(),
+                error: None,
+                duration_ms: outcome.duration.as_secs_f64() * 1000.0,
+            },
+            Err(error) => AgentEventKind::ActionEnd {
+                action: action.to_string(),
+                outputs: ActionParams::new(),
+                error: Some(error.to_string()),
+                duration_ms: 0.0,
+            },
+        };
+        self.record(agent, kind);
+    }
+
+    fn on_memory_write(&self, agent: &str, tier: MemoryTier, key: &str, value: &str) {
+        self.record(agent, AgentEventKind::MemoryWrite { tier, key: key.to_string(), value: value.to_string() });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::agents::{predefined_actions, Agent, Environment};
+
+    #[test]
+    fn test_event_log() {
+        let log = Arc::new(EventLog::new());
+        let mut agent = Agent::new("scribe");
+        agent.add_action(predefined_actions::learn_action());
+        agent.set_policy(|_: &Agent, _: &Environment| ActionChoice::act("learn").with_param("concept", "entropy"));
+        agent.observe_with(log.clone());
+
+        let (_, result) = agent.act(&Environment::new(Default::default()));
+        assert!(result.is_ok());
+        let kinds: Vec<AgentEventKind> = log.events().into_iter().map(|event| event.kind).collect();
+        let params = ActionParams::from([("concept".to_string(), "entropy".to_string())]);
+        assert_eq!(kinds[0], AgentEventKind::Decision { action: Some("learn".to_string()), params: params.clone() });
+        assert_eq!(kinds[1], AgentEventKind::ActionStart { action: "learn".to_string(), params });
+        assert_eq!(
+            kinds[2],
+            AgentEventKind::MemoryWrite { tier: MemoryTier::LongTerm, key: "knowledge".to_string(), value: "entropy".to_string() }
+        );
+        assert!(matches!(&kinds[3], AgentEventKind::ActionEnd { error: None, .. }));
+        assert!(kinds[4..].iter().all(|kind| matches!(kind, AgentEventKind::MemoryWrite { tier: MemoryTier::ShortTerm, .. })));
+
+        let file_path = "test_event_log.jsonl";
+        log.save(file_path).unwrap();
+        let loaded = EventLog::load(file_path).unwrap();
+        fs::remove_file(file_path).unwrap();
+        assert_eq!(loaded, log.take());
+        assert!(log.events().is_empty());
+    }
+}
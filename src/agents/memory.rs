@@ -1,19 +1,43 @@
 use std::collections::HashMap;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use super::models::{ChatMessage, ModelError, ModelProvider, Role};
 
 /// Represents an AGI Agent's memory.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct Memory {
     pub long_term: HashMap<String, String>, // Long-term knowledge
-    pub short_term: HashMap<String, String>, // Short-term observations
+    #[serde(deserialize_with = "deserialize_short_term")]
+    pub short_term: ShortTermMemory, // Short-term observations
+    #[serde(default)]
+    pub conversation: ConversationMemory, // Dialogue with chat models and other agents
 }
 
 impl Memory {
     pub fn new() -> Self {
         Self {
             long_term: HashMap::new(),
-            short_term: HashMap::new(),
+            short_term: ShortTermMemory::new(),
+            conversation: ConversationMemory::new(),
         }
     }
 
+    /// Replaces the short-term memory, typically with one configured with a TTL, capacity or
+    /// promotion threshold.
+    pub fn with_short_term(mut self, short_term: ShortTermMemory) -> Self {
+        self.short_term = short_term;
+        self
+    }
+
+    /// Replaces the conversation memory, typically with one configured with another window or
+    /// summarization.
+    pub fn with_conversation(mut self, conversation: ConversationMemory) -> Self {
+        self.conversation = conversation;
+        self
+    }
+
     pub fn store(&mut self, key: &str, value: &str) {
         self.long_term.insert(key.to_string(), value.to_string());
     }
@@ -21,4 +45,419 @@ impl Memory {
     pub fn recall(&self, key: &str) -> Option<&String> {
         self.long_term.get(key)
     }
+
+    /// Stores an observation in short-term memory.
+    pub fn remember(&mut self, key: &str, value: &str) {
+        self.short_term.insert(key, value);
+    }
+
+    /// Recalls an observation from short-term memory, counting the access.
+    ///
+    /// Once an entry has been recalled as often as the promotion threshold, it moves to long-term
+    /// memory, where it no longer expires. Entries that were already promoted are recalled from
+    /// long-term memory.
+    pub fn recall_recent(&mut self, key: &str) -> Option<String> {
+        let Some(entry) = self.short_term.access(key) else {
+            return self.long_term.get(key).cloned();
+        };
+        let (value, accesses) = (entry.value.clone(), entry.accesses);
+        if self.short_term.promotion_threshold.is_some_and(|threshold| accesses >= threshold) {
+            self.promote(key);
+        }
+        Some(value)
+    }
+
+    /// Moves an observation from short-term to long-term memory.
+    ///
+    /// # Returns
+    /// `true` if the observation was in short-term memory.
+    pub fn promote(&mut self, key: &str) -> bool {
+        match self.short_term.remove(key) {
+            Some(value) => {
+                self.long_term.insert(key.to_string(), value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Saves the memory to a JSON file.
+    pub fn save(&self, file_path: &str) -> std::io::Result<()> {
+        fs::write(file_path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// Loads memory saved by [`Memory::save`].
+    ///
+    /// Returns an `InvalidData` error if the file does not hold a memory.
+    pub fn load(file_path: &str) -> std::io::Result<Self> {
+        serde_json::from_str(&fs::read_to_string(file_path)?)
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid memory format"))
+    }
+}
+
+/// An observation held in short-term memory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShortTermEntry {
+    pub value: String,
+    pub stored_at: u64, // Milliseconds since the Unix epoch
+    pub accesses: u32, // Number of recalls since it was stored
+    last_used: u64, // Position in the order of stores and recalls, for eviction
+}
+
+/// Recent observations that expire after a time-to-live and are evicted, least recently used
+/// first, once the capacity is reached.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ShortTermMemory {
+    entries: HashMap<String, ShortTermEntry>,
+    ttl: Option<Duration>,
+    capacity: Option<usize>,
+    promotion_threshold: Option<u32>,
+    clock: u64,
+}
+
+/// Short-term memory as written by each version: a plain map of observations before entries
+/// could expire, the entries with their settings since.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedShortTerm {
+    Memory(ShortTermMemory),
+    Map(HashMap<String, String>),
+}
+
+impl From<SavedShortTerm> for ShortTermMemory {
+    fn from(saved: SavedShortTerm) -> Self {
+        match saved {
+            SavedShortTerm::Memory(memory) => memory,
+            SavedShortTerm::Map(values) => {
+                let mut memory = ShortTermMemory::new();
+                for (key, value) in &values {
+                    memory.insert(key, value);
+                }
+                memory
+            }
+        }
+    }
+}
+
+fn deserialize_short_term<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<ShortTermMemory, D::Error> {
+    SavedShortTerm::deserialize(deserializer).map(ShortTermMemory::from)
+}
+
+impl ShortTermMemory {
+    /// Creates a short-term memory whose entries never expire or get evicted.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how long entries live after they are stored.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Sets the maximum number of entries.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// Sets how many recalls through [`Memory::recall_recent`] promote an entry to long-term
+    /// memory.
+    pub fn with_promotion_threshold(mut self, accesses: u32) -> Self {
+        self.promotion_threshold = Some(accesses);
+        self
+    }
+
+    /// Stores a value, replacing any earlier value and resetting its age and access count.
+    pub fn insert(&mut self, key: &str, value: &str) {
+        let now = now();
+        self.remove_expired_at(now);
+        self.clock += 1;
+        self.entries.insert(
+            key.to_string(),
+            ShortTermEntry { value: value.to_string(), stored_at: now, accesses: 0, last_used: self.clock },
+        );
+        if let Some(capacity) = self.capacity {
+            while self.entries.len() > capacity {
+                let oldest = self
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.last_used)
+                    .map(|(key, _)| key.clone())
+                    .expect("over capacity, so not empty");
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    /// Returns a value without counting the access.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        let now = now();
+        self.entries
+            .get(key)
+            .filter(|entry| !self.is_expired(entry, now))
+            .map(|entry| entry.value.as_str())
+    }
+
+    /// Returns an entry and counts the access.
+    fn access(&mut self, key: &str) -> Option<&ShortTermEntry> {
+        self.remove_expired_at(now());
+        self.clock += 1;
+        let entry = self.entries.get_mut(key)?;
+        entry.accesses += 1;
+        entry.last_used = self.clock;
+        Some(entry)
+    }
+
+    /// Removes a value.
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.entries.remove(key).map(|entry| entry.value)
+    }
+
+    /// Drops every expired entry.
+    pub fn remove_expired(&mut self) {
+        self.remove_expired_at(now());
+    }
+
+    fn remove_expired_at(&mut self, now: u64) {
+        if self.ttl.is_some() {
+            let entries = std::mem::take(&mut self.entries);
+            self.entries = entries.into_iter().filter(|(_, entry)| !self.is_expired(entry, now)).collect();
+        }
+    }
+
+    fn is_expired(&self, entry: &ShortTermEntry, now: u64) -> bool {
+        self.ttl
+            .is_some_and(|ttl| now.saturating_sub(entry.stored_at) >= ttl.as_millis().min(u64::MAX as u128) as u64)
+    }
+
+    /// The entries that have not expired.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &ShortTermEntry)> {
+        let now = now();
+        self.entries
+            .iter()
+            .filter(move |(_, entry)| !self.is_expired(entry, now))
+            .map(|(key, entry)| (key.as_str(), entry))
+    }
+
+    /// The values that have not expired.
+    pub fn to_map(&self) -> HashMap<String, String> {
+        self.entries().map(|(key, entry)| (key.to_string(), entry.value.clone())).collect()
+    }
+
+    /// The number of entries that have not expired.
+    pub fn len(&self) -> usize {
+        self.entries().count()
+    }
+
+    /// Returns `true` if every entry has expired.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// The instructions for summarizing older turns of a conversation.
+pub const SUMMARY_PROMPT: &str = "Summarize the conversation below in a few sentences. Keep every fact, \
+     request and commitment that later turns may depend on.";
+
+/// The messages of a conversation, oldest first.
+///
+/// Only the latest messages, up to the window size, are kept word for word. Older messages are
+/// dropped once the window is full, or, with summarization turned on, folded into a running
+/// summary by a model when [`ConversationMemory::compact`] runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConversationMemory {
+    messages: Vec<ChatMessage>,
+    summary: Option<String>,
+    window: usize,
+    summarize: bool,
+}
+
+impl Default for ConversationMemory {
+    fn default() -> Self {
+        Self { messages: Vec::new(), summary: None, window: 20, summarize: false }
+    }
+}
+
+impl ConversationMemory {
+    /// Creates a conversation keeping the latest 20 messages, without summarization.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how many of the latest messages are kept word for word.
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Summarizes messages that leave the window instead of dropping them.
+    pub fn with_summarization(mut self) -> Self {
+        self.summarize = true;
+        self
+    }
+
+    /// Adds a message. Without summarization, the oldest messages beyond the window are dropped
+    /// right away.
+    pub fn push(&mut self, message: ChatMessage) {
+        self.messages.push(message);
+        if !self.summarize {
+            let excess = self.messages.len().saturating_sub(self.window);
+            self.messages.drain(..excess);
+        }
+    }
+
+    /// The messages kept word for word, oldest first.
+    pub fn messages(&self) -> &[ChatMessage] {
+        &self.messages
+    }
+
+    /// The summary of the messages that left the window, if any were summarized.
+    pub fn summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Forgets every message and the summary.
+    pub fn clear(&mut self) {
+        self.messages.clear();
+        self.summary = None;
+    }
+
+    /// The messages to send a chat model: the system messages of `prompt`, the summary, the
+    /// messages in the window, and the rest of `prompt`.
+    pub fn with_history(&self, prompt: Vec<ChatMessage>) -> Vec<ChatMessage> {
+        let (system, rest): (Vec<ChatMessage>, Vec<ChatMessage>) =
+            prompt.into_iter().partition(|message| message.role == Role::System);
+        let mut messages = system;
+        if let Some(summary) = &self.summary {
+            messages.push(ChatMessage::system(&format!("Summary of the earlier conversation: {}", summary)));
+        }
+        messages.extend(self.messages.iter().cloned());
+        messages.extend(rest);
+        messages
+    }
+
+    /// Returns `true` if there are messages beyond the window waiting to be summarized.
+    pub fn needs_compaction(&self) -> bool {
+        self.messages.len() > self.window
+    }
+
+    /// Folds the messages beyond the window into the summary, asking `provider` to write it.
+    ///
+    /// The messages are kept if the model cannot be reached.
+    pub async fn compact(&mut self, provider: &dyn ModelProvider) -> Result<(), ModelError> {
+        if !self.needs_compaction() {
+            return Ok(());
+        }
+        let excess = self.messages.len() - self.window;
+        let mut transcript = String::new();
+        if let Some(summary) = &self.summary {
+            transcript.push_str(&format!("Earlier summary: {}\n\n", summary));
+        }
+        for message in &self.messages[..excess] {
+            let role = match message.role {
+                Role::System => "system",
+                Role::User => "user",
+                Role::Assistant => "assistant",
+            };
+            transcript.push_str(&format!("{}: {}\n", role, message.content));
+        }
+        let request = [ChatMessage::system(SUMMARY_PROMPT), ChatMessage::user(&transcript)];
+        let summary = provider.complete(&request).await?;
+        self.summary = Some(summary.trim().to_string());
+        self.messages.drain(..excess);
+        Ok(())
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ttl() {
+        let mut memory = Memory::new().with_short_term(ShortTermMemory::new().with_ttl(Duration::ZERO));
+        memory.remember("noise", "loud");
+        assert_eq!(memory.short_term.get("noise"), None);
+        assert_eq!(memory.recall_recent("noise"), None);
+        assert!(memory.short_term.is_empty());
+
+        let mut memory = Memory::new().with_short_term(ShortTermMemory::new().with_ttl(Duration::from_secs(3600)));
+        memory.remember("noise", "loud");
+        assert_eq!(memory.short_term.get("noise"), Some("loud"));
+    }
+
+    #[test]
+    fn test_capacity_evicts_least_recently_used() {
+        let mut memory = Memory::new().with_short_term(ShortTermMemory::new().with_capacity(2));
+        memory.remember("a", "1");
+        memory.remember("b", "2");
+        memory.recall_recent("a");
+        memory.remember("c", "3");
+
+        assert_eq!(memory.short_term.len(), 2);
+        assert_eq!(memory.short_term.get("a"), Some("1"));
+        assert_eq!(memory.short_term.get("b"), None);
+        assert_eq!(memory.short_term.to_map(), HashMap::from([("a".into(), "1".into()), ("c".into(), "3".into())]));
+    }
+
+    #[test]
+    fn test_conversation_window() {
+        let mut conversation = ConversationMemory::new().with_window(2);
+        conversation.push(ChatMessage::user("hello"));
+        conversation.push(ChatMessage::assistant("hi"));
+        conversation.push(ChatMessage::user("trade wood?"));
+        assert_eq!(conversation.messages(), &[ChatMessage::assistant("hi"), ChatMessage::user("trade wood?")]);
+        assert!(!conversation.needs_compaction());
+
+        let prompt = vec![ChatMessage::system("Be fair."), ChatMessage::user("Decide.")];
+        assert_eq!(
+            conversation.with_history(prompt),
+            vec![
+                ChatMessage::system("Be fair."),
+                ChatMessage::assistant("hi"),
+                ChatMessage::user("trade wood?"),
+                ChatMessage::user("Decide."),
+            ]
+        );
+
+        let summarizing = ConversationMemory::new().with_window(1).with_summarization();
+        let mut memory = Memory::new().with_conversation(summarizing);
+        memory.conversation.push(ChatMessage::user("hello"));
+        memory.conversation.push(ChatMessage::user("trade wood?"));
+        assert!(memory.conversation.needs_compaction());
+        let json = serde_json::to_string(&memory).unwrap();
+        assert_eq!(serde_json::from_str::<Memory>(&json).unwrap(), memory);
+    }
+
+    #[test]
+    fn test_promotion() {
+        let mut memory = Memory::new().with_short_term(ShortTermMemory::new().with_promotion_threshold(2));
+        memory.remember("password_hint", "blue");
+        assert_eq!(memory.recall_recent("password_hint").as_deref(), Some("blue"));
+        assert_eq!(memory.recall("password_hint"), None);
+        assert_eq!(memory.recall_recent("password_hint").as_deref(), Some("blue"));
+        assert_eq!(memory.recall("password_hint").map(String::as_str), Some("blue"));
+        assert!(memory.short_term.is_empty());
+        assert_eq!(memory.recall_recent("password_hint").as_deref(), Some("blue"));
+
+        memory.remember("door", "open");
+        assert!(memory.promote("door"));
+        assert!(!memory.promote("door"));
+    }
 }
@@ -0,0 +1,297 @@
+//! Run Metrics
+//!
+//! A [`MetricsRegistry`] keeps per-agent counters for a run: how often each action was taken and
+//! failed, the reward collected, how long decisions took, and the tokens the agent's model
+//! provider used. Every [`Simulation`](super::simulation::Simulation) fills one in as it runs,
+//! and at the end it can be exported as JSON, CSV or the Prometheus text format.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use super::models::TokenUsage;
+use super::system::Turn;
+
+/// The counters of one agent.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct AgentMetrics {
+    /// How often each action was taken, whether it succeeded or not.
+    pub actions: BTreeMap<String, u64>,
+    /// How often each action failed.
+    pub failures: BTreeMap<String, u64>,
+    /// Turns in which the agent idled.
+    pub idle_turns: u64,
+    pub total_reward: f64,
+    pub decisions: u64,
+    /// The time spent deciding, over all decisions.
+    pub decision_seconds: f64,
+    /// The longest decision.
+    pub max_decision_seconds: f64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+impl AgentMetrics {
+    /// The number of turns, idle or not.
+    pub fn turns(&self) -> u64 {
+        self.actions.values().sum::<u64>() + self.idle_turns
+    }
+
+    /// The average time a decision took, or zero without decisions.
+    pub fn mean_decision_seconds(&self) -> f64 {
+        if self.decisions == 0 {
+            0.0
+        } else {
+            self.decision_seconds / self.decisions as f64
+        }
+    }
+}
+
+/// The format of an exported registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsFormat {
+    Json,
+    /// One row per agent and action.
+    Csv,
+    /// The Prometheus text exposition format.
+    Prometheus,
+}
+
+/// Per-agent metrics of a run, in agent name order.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MetricsRegistry {
+    agents: BTreeMap<String, AgentMetrics>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The metrics of an agent, or `None` if nothing was recorded for it.
+    pub fn agent(&self, name: &str) -> Option<&AgentMetrics> {
+        self.agents.get(name)
+    }
+
+    /// Every agent's metrics, in name order.
+    pub fn agents(&self) -> impl Iterator<Item = (&str, &AgentMetrics)> {
+        self.agents.iter().map(|(name, metrics)| (name.as_str(), metrics))
+    }
+
+    fn entry(&mut self, agent: &str) -> &mut AgentMetrics {
+        self.agents.entry(agent.to_string()).or_default()
+    }
+
+    /// Counts the action and reward of a turn.
+    pub fn record_turn(&mut self, turn: &Turn) {
+        let metrics = self.entry(&turn.agent);
+        match turn.choice.action() {
+            Some(action) => {
+                *metrics.actions.entry(action.to_string()).or_default() += 1;
+                if turn.result.is_err() {
+                    *metrics.failures.entry(action.to_string()).or_default() += 1;
+                }
+            }
+            None => metrics.idle_turns += 1,
+        }
+        metrics.total_reward += turn.reward;
+    }
+
+    /// Counts a decision and how long it took.
+    pub fn record_decision(&mut self, agent: &str, latency: Duration) {
+        let metrics = self.entry(agent);
+        let seconds = latency.as_secs_f64();
+        metrics.decisions += 1;
+        metrics.decision_seconds += seconds;
+        metrics.max_decision_seconds = metrics.max_decision_seconds.max(seconds);
+    }
+
+    /// Sets the tokens the agent's model provider has used so far.
+    pub fn record_token_usage(&mut self, agent: &str, usage: TokenUsage) {
+        let metrics = self.entry(agent);
+        metrics.prompt_tokens = usage.prompt_tokens;
+        metrics.completion_tokens = usage.completion_tokens;
+    }
+
+    /// Forgets everything recorded.
+    pub fn clear(&mut self) {
+        self.agents.clear();
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!(self.agents)
+    }
+
+    /// One row per agent and action, with the agent's totals repeated on every row. Agents that
+    /// never acted have a single row with an empty action.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "agent,action,count,failures,idle_turns,total_reward,decisions,mean_decision_seconds,\
+             max_decision_seconds,prompt_tokens,completion_tokens\n",
+        );
+        for (name, metrics) in &self.agents {
+            let totals = format!(
+                "{},{},{},{},{},{},{}",
+                metrics.idle_turns,
+                metrics.total_reward,
+                metrics.decisions,
+                metrics.mean_decision_seconds(),
+                metrics.max_decision_seconds,
+                metrics.prompt_tokens,
+                metrics.completion_tokens
+            );
+            if metrics.actions.is_empty() {
+                let _ = writeln!(csv, "{},,0,0,{}", csv_field(name), totals);
+            }
+            for (action, count) in &metrics.actions {
+                let failures = metrics.failures.get(action).copied().unwrap_or(0);
+                let _ = writeln!(csv, "{},{},{},{},{}", csv_field(name), csv_field(action), count, failures, totals);
+            }
+        }
+        csv
+    }
+
+    /// The metrics as Prometheus counters and gauges prefixed with `zana_agent_`, labelled by
+    /// agent and action.
+    pub fn to_prometheus(&self) -> String {
+        let mut text = String::new();
+        let mut family = |name: &str, kind: &str, help: &str, samples: Vec<(String, String)>| {
+            let _ = writeln!(text, "# HELP zana_agent_{} {}", name, help);
+            let _ = writeln!(text, "# TYPE zana_agent_{} {}", name, kind);
+            for (labels, value) in samples {
+                let _ = writeln!(text, "zana_agent_{}{{{}}} {}", name, labels, value);
+            }
+        };
+        let per_agent = |value: fn(&AgentMetrics) -> String| -> Vec<(String, String)> {
+            self.agents
+                .iter()
+                .map(|(name, metrics)| (format!("agent=\"{}\"", label(name)), value(metrics)))
+                .collect()
+        };
+        let per_action = |counts: fn(&AgentMetrics) -> &BTreeMap<String, u64>| -> Vec<(String, String)> {
+            self.agents
+                .iter()
+                .flat_map(|(name, metrics)| {
+                    counts(metrics).iter().map(move |(action, count)| {
+                        (format!("agent=\"{}\",action=\"{}\"", label(name), label(action)), count.to_string())
+                    })
+                })
+                .collect()
+        };
+        family("actions_total", "counter", "Actions taken.", per_action(|metrics| &metrics.actions));
+        family("action_failures_total", "counter", "Actions that failed.", per_action(|metrics| &metrics.failures));
+        family("idle_turns_total", "counter", "Turns spent idling.", per_agent(|metrics| metrics.idle_turns.to_string()));
+        family("reward_total", "counter", "Reward collected.", per_agent(|metrics| metrics.total_reward.to_string()));
+        family("decisions_total", "counter", "Decisions made.", per_agent(|metrics| metrics.decisions.to_string()));
+        family(
+            "decision_seconds_total",
+            "counter",
+            "Time spent deciding.",
+            per_agent(|metrics| metrics.decision_seconds.to_string()),
+        );
+        family(
+            "decision_seconds_max",
+            "gauge",
+            "The longest decision.",
+            per_agent(|metrics| metrics.max_decision_seconds.to_string()),
+        );
+        family("prompt_tokens_total", "counter", "Prompt tokens sent to the model.", per_agent(|metrics| metrics.prompt_tokens.to_string()));
+        family(
+            "completion_tokens_total",
+            "counter",
+            "Tokens the model answered with.",
+            per_agent(|metrics| metrics.completion_tokens.to_string()),
+        );
+        text
+    }
+
+    /// The metrics in the given format.
+    pub fn export(&self, format: MetricsFormat) -> String {
+        match format {
+            MetricsFormat::Json => serde_json::to_string_pretty(&self.to_json()).unwrap_or_default(),
+            MetricsFormat::Csv => self.to_csv(),
+            MetricsFormat::Prometheus => self.to_prometheus(),
+        }
+    }
+
+    /// Writes the metrics to a file in the given format.
+    pub fn save(&self, file_path: &str, format: MetricsFormat) -> std::io::Result<()> {
+        fs::write(file_path, self.export(format))
+    }
+}
+
+/// Quotes a CSV field that holds a comma, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escapes a Prometheus label value.
+fn label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::actions::{ActionError, ActionOutcome};
+    use crate::agents::policies::ActionChoice;
+
+    fn registry() -> MetricsRegistry {
+        let turn = |agent: &str, choice: ActionChoice, failed: bool, reward: f64| Turn {
+            agent: agent.to_string(),
+            choice,
+            result: if failed { Err(ActionError::Failed("tired".to_string())) } else { Ok(ActionOutcome::new()) },
+            reward,
+        };
+        let mut registry = MetricsRegistry::new();
+        registry.record_turn(&turn("farmer", ActionChoice::act("harvest"), false, 1.0));
+        registry.record_turn(&turn("farmer", ActionChoice::act("harvest"), true, 0.0));
+        registry.record_turn(&turn("farmer", ActionChoice::Idle, false, 0.5));
+        registry.record_turn(&turn("miner, senior", ActionChoice::Idle, false, 0.0));
+        registry.record_decision("farmer", Duration::from_millis(10));
+        registry.record_decision("farmer", Duration::from_millis(30));
+        registry.record_token_usage("farmer", TokenUsage { prompt_tokens: 120, completion_tokens: 15 });
+        registry
+    }
+
+    #[test]
+    fn test_record() {
+        let registry = registry();
+        let farmer = registry.agent("farmer").unwrap();
+        assert_eq!(farmer.actions["harvest"], 2);
+        assert_eq!(farmer.failures["harvest"], 1);
+        assert_eq!(farmer.turns(), 3);
+        assert_eq!(farmer.total_reward, 1.5);
+        assert_eq!(farmer.decisions, 2);
+        assert!((farmer.mean_decision_seconds() - 0.02).abs() < 1e-9);
+        assert_eq!(farmer.max_decision_seconds, 0.03);
+        assert_eq!(farmer.prompt_tokens, 120);
+        assert_eq!(registry.agents().count(), 2);
+    }
+
+    #[test]
+    fn test_export() {
+        let registry = registry();
+        let json = registry.to_json();
+        assert_eq!(json["farmer"]["actions"]["harvest"], 2);
+        assert_eq!(serde_json::from_value::<BTreeMap<String, AgentMetrics>>(json).unwrap()["farmer"], registry.agents["farmer"]);
+
+        let csv = registry.to_csv();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[1].starts_with("farmer,harvest,2,1,1,1.5,2,"));
+        assert!(rows[1].ends_with(",0.03,120,15"));
+        assert_eq!(rows[2], "\"miner, senior\",,0,0,1,0,0,0,0,0,0");
+
+        let prometheus = registry.to_prometheus();
+        assert!(prometheus.contains("# TYPE zana_agent_actions_total counter\n"));
+        assert!(prometheus.contains("zana_agent_actions_total{agent=\"farmer\",action=\"harvest\"} 2\n"));
+        assert!(prometheus.contains("zana_agent_idle_turns_total{agent=\"miner, senior\"} 1\n"));
+        assert!(prometheus.contains("zana_agent_prompt_tokens_total{agent=\"farmer\"} 120\n"));
+    }
+}
@@ -1,6 +1,26 @@
 pub mod actions;
+pub mod behavior_tree;
+pub mod error;
+pub mod hooks;
+pub mod metrics;
+pub mod models;
+pub mod perception;
+pub mod planner;
+pub mod policies;
 pub mod predefined_actions;
+pub mod q_learning;
+pub mod react;
+pub mod simulation;
+pub mod skills;
+pub mod system;
 mod agent;
 mod environment;
 mod memory;
-mod model_provider;
+mod value;
+
+pub use agent::Agent;
+pub use environment::Environment;
+pub use error::AgentError;
+pub use memory::{ConversationMemory, Memory, ShortTermEntry, ShortTermMemory};
+pub use models::ModelProvider;
+pub use value::Value;
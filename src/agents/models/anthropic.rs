@@ -0,0 +1,171 @@
+//! The Anthropic Messages API.
+
+use serde_json::json;
+use super::transport::Transport;
+use super::{stream, ChatMessage, ModelConfig, ModelError, ModelFuture, ModelProvider, Role, TokenCallback, TokenUsage, UsageCounter};
+
+/// The API version sent with every request.
+pub const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// A provider for the Anthropic Messages API.
+pub struct AnthropicProvider {
+    config: ModelConfig,
+    api_key: Option<String>,
+    transport: Transport,
+    usage: UsageCounter,
+}
+
+impl AnthropicProvider {
+    pub fn new(config: ModelConfig) -> Self {
+        Self { api_key: None, transport: Transport::new(&config), usage: UsageCounter::default(), config }
+    }
+
+    pub fn with_api_key(self, api_key: &str) -> Self {
+        self.with_optional_api_key(Some(api_key.to_string()))
+    }
+
+    pub(super) fn with_optional_api_key(mut self, api_key: Option<String>) -> Self {
+        self.api_key = api_key;
+        self
+    }
+
+    /// The API takes system prompts separately from the conversation, so system messages are
+    /// joined into the `system` field.
+    fn body(&self, messages: &[ChatMessage]) -> serde_json::Value {
+        let (system, conversation): (Vec<&ChatMessage>, Vec<&ChatMessage>) =
+            messages.iter().partition(|message| message.role == Role::System);
+        let system: Vec<&str> = system.iter().map(|message| message.content.as_str()).collect();
+        let mut body = json!({
+            "model": self.config.model,
+            "max_tokens": self.config.max_tokens,
+            "messages": conversation,
+        });
+        if !system.is_empty() {
+            body["system"] = json!(system.join("\n\n"));
+        }
+        if let Some(temperature) = self.config.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        body
+    }
+
+    fn request(&self, body: &serde_json::Value) -> reqwest::RequestBuilder {
+        let request = self
+            .transport
+            .post(&self.config.api_url)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .json(body);
+        match &self.api_key {
+            Some(api_key) => request.header("x-api-key", api_key),
+            None => request,
+        }
+    }
+}
+
+impl ModelProvider for AnthropicProvider {
+    fn config(&self) -> &ModelConfig {
+        &self.config
+    }
+
+    fn token_usage(&self) -> TokenUsage {
+        self.usage.total()
+    }
+
+    fn complete<'a>(&'a self, messages: &'a [ChatMessage]) -> ModelFuture<'a, Result<String, ModelError>> {
+        Box::pin(async move {
+            let request = self.request(&self.body(messages));
+            let response: serde_json::Value = self.transport.send(request).await?.json().await?;
+            self.usage.add(usage(&response["usage"]));
+            Ok(answer_text(&response))
+        })
+    }
+
+    fn complete_streaming<'a>(
+        &'a self,
+        messages: &'a [ChatMessage],
+        on_token: &'a mut TokenCallback<'_>,
+    ) -> ModelFuture<'a, Result<String, ModelError>> {
+        Box::pin(async move {
+            let mut body = self.body(messages);
+            body["stream"] = json!(true);
+            let response = self.transport.send(self.request(&body)).await?;
+            let (answer, used) = stream::read_tokens(response, stream_token, stream_usage, on_token).await?;
+            self.usage.add(used);
+            Ok(answer)
+        })
+    }
+}
+
+/// Joins the text blocks of the answer.
+fn answer_text(response: &serde_json::Value) -> String {
+    let Some(blocks) = response["content"].as_array() else {
+        return String::new();
+    };
+    blocks
+        .iter()
+        .filter(|block| block["type"] == "text")
+        .filter_map(|block| block["text"].as_str())
+        .collect()
+}
+
+fn usage(usage: &serde_json::Value) -> TokenUsage {
+    TokenUsage {
+        prompt_tokens: usage["input_tokens"].as_u64().unwrap_or_default(),
+        completion_tokens: usage["output_tokens"].as_u64().unwrap_or_default(),
+    }
+}
+
+/// The usage reported by streamed `message_start` and `message_delta` events. The output count
+/// of the last `message_delta` is the total.
+fn stream_usage(line: &str) -> Option<TokenUsage> {
+    let event: serde_json::Value = serde_json::from_str(stream::event_data(line)?).ok()?;
+    match event["type"].as_str()? {
+        "message_start" => Some(usage(&event["message"]["usage"])),
+        "message_delta" => Some(usage(&event["usage"])),
+        _ => None,
+    }
+}
+
+/// The text of a streamed `content_block_delta` event. Other events carry no text.
+fn stream_token(line: &str) -> Option<String> {
+    let event: serde_json::Value = serde_json::from_str(stream::event_data(line)?).ok()?;
+    if event["type"] != "content_block_delta" || event["delta"]["type"] != "text_delta" {
+        return None;
+    }
+    Some(event["delta"]["text"].as_str()?.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_and_response() {
+        let provider = AnthropicProvider::new(ModelConfig::anthropic("claude-3-5-haiku-latest").with_max_tokens(64));
+        let body = provider.body(&[ChatMessage::system("Be brief."), ChatMessage::user("Hi")]);
+        assert_eq!(body, json!({
+            "model": "claude-3-5-haiku-latest",
+            "max_tokens": 64,
+            "system": "Be brief.",
+            "messages": [{ "role": "user", "content": "Hi" }],
+        }));
+
+        let response = json!({ "content": [
+            { "type": "text", "text": "{\"action\": " },
+            { "type": "text", "text": "\"rest\"}" },
+        ] });
+        assert_eq!(answer_text(&response), r#"{"action": "rest"}"#);
+        assert_eq!(answer_text(&json!({ "type": "error" })), "");
+
+        let delta = r#"data: {"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "rest"}}"#;
+        assert_eq!(stream_token(delta), Some("rest".to_string()));
+        assert_eq!(stream_token(r#"data: {"type": "message_stop"}"#), None);
+        assert_eq!(stream_token("event: content_block_delta"), None);
+
+        let start = r#"data: {"type": "message_start", "message": {"usage": {"input_tokens": 20, "output_tokens": 1}}}"#;
+        let delta = r#"data: {"type": "message_delta", "usage": {"output_tokens": 9}}"#;
+        assert_eq!(stream_usage(start), Some(TokenUsage { prompt_tokens: 20, completion_tokens: 1 }));
+        assert_eq!(stream_usage(delta), Some(TokenUsage { prompt_tokens: 0, completion_tokens: 9 }));
+        assert_eq!(usage(&json!({ "input_tokens": 20, "output_tokens": 9 })).total(), 29);
+    }
+}
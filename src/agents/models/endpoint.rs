@@ -0,0 +1,60 @@
+//! Custom decision services.
+
+use serde_json::json;
+use crate::agents::policies::ActionChoice;
+use super::transport::Transport;
+use super::{parse_choice, ChatMessage, DecisionRequest, ModelConfig, ModelError, ModelFuture, ModelProvider, TokenCallback};
+
+/// A provider for a custom service.
+///
+/// Decisions are posted as `{"agent", "actions", "memory", "environment"}` and the service answers
+/// with `{"action": "<name>", "params": {..}}`. Completions are posted as `{"messages": [..]}`
+/// and answered with `{"content": "<text>"}`. The service does not stream, so streamed calls
+/// pass its whole answer at once.
+pub struct EndpointProvider {
+    config: ModelConfig,
+    transport: Transport,
+}
+
+impl EndpointProvider {
+    pub fn new(config: ModelConfig) -> Self {
+        Self { transport: Transport::new(&config), config }
+    }
+
+    async fn post(&self, payload: &serde_json::Value) -> Result<serde_json::Value, ModelError> {
+        let request = self.transport.post(&self.config.api_url).json(payload);
+        Ok(self.transport.send(request).await?.json().await?)
+    }
+}
+
+impl ModelProvider for EndpointProvider {
+    fn config(&self) -> &ModelConfig {
+        &self.config
+    }
+
+    fn complete<'a>(&'a self, messages: &'a [ChatMessage]) -> ModelFuture<'a, Result<String, ModelError>> {
+        Box::pin(async move {
+            let response = self.post(&json!({ "messages": messages })).await?;
+            Ok(response["content"].as_str().unwrap_or_default().to_string())
+        })
+    }
+
+    fn decide<'a>(&'a self, request: &'a DecisionRequest) -> ModelFuture<'a, Result<ActionChoice, ModelError>> {
+        Box::pin(async move {
+            let response = self.post(&json!(request)).await?;
+            Ok(parse_choice(&response.to_string()).unwrap_or_default())
+        })
+    }
+
+    fn decide_streaming<'a>(
+        &'a self,
+        request: &'a DecisionRequest,
+        on_token: &'a mut TokenCallback<'_>,
+    ) -> ModelFuture<'a, Result<ActionChoice, ModelError>> {
+        Box::pin(async move {
+            let answer = self.post(&json!(request)).await?.to_string();
+            on_token(&answer);
+            Ok(parse_choice(&answer).unwrap_or_default())
+        })
+    }
+}
@@ -0,0 +1,63 @@
+//! Model Errors
+
+use std::fmt;
+
+/// An error raised while asking a model for an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelError {
+    /// The request took longer than the configured timeout.
+    Timeout,
+    /// The API answered with an error status, with the body of its answer.
+    Status { status: u16, message: String },
+    /// The request could not be sent or its answer could not be read.
+    Connection(String),
+    /// The answer was not in the format the API should send.
+    InvalidResponse(String),
+    /// A local model could not be loaded or run.
+    Inference(String),
+}
+
+impl ModelError {
+    /// Whether sending the request again may succeed: timeouts, connection failures, rate limits
+    /// (status 429) and server errors (status 5xx).
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ModelError::Timeout | ModelError::Connection(_) => true,
+            ModelError::Status { status, .. } => *status == 429 || *status >= 500,
+            ModelError::InvalidResponse(_) | ModelError::Inference(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for ModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelError::Timeout => write!(f, "model request timed out"),
+            ModelError::Status { status, message } if message.is_empty() => {
+                write!(f, "model API answered with status {}", status)
+            }
+            ModelError::Status { status, message } => {
+                write!(f, "model API answered with status {}: {}", status, message)
+            }
+            ModelError::Connection(reason) => write!(f, "model request failed: {}", reason),
+            ModelError::InvalidResponse(reason) => write!(f, "invalid model response: {}", reason),
+            ModelError::Inference(reason) => write!(f, "local inference failed: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ModelError {}
+
+impl From<reqwest::Error> for ModelError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            ModelError::Timeout
+        } else if error.is_decode() {
+            ModelError::InvalidResponse(error.to_string())
+        } else if let Some(status) = error.status() {
+            ModelError::Status { status: status.as_u16(), message: String::new() }
+        } else {
+            ModelError::Connection(error.to_string())
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! Models run in-process, without a network connection.
+//!
+//! A [`LocalProvider`] runs a quantized Llama-family model from a GGUF file on the CPU with
+//! [candle](https://github.com/huggingface/candle), using the `tokenizer.json` of the model.
+//! Running models needs the `local-inference` feature; without it, every request fails with
+//! [`ModelError::Inference`].
+//!
+//! Generation runs on a blocking thread of the Tokio runtime, so requests must be awaited inside
+//! one, like those of the HTTP providers; the answer is streamed back as it decodes.
+//!
+//! Conversations are written in the Llama 3 chat format, and answers are sampled with the
+//! configured temperature, or greedily if it has none, so the same conversation gets the same
+//! answer.
+
+use super::{ChatMessage, ModelConfig, ModelError, ModelFuture, ModelProvider, Role, TokenCallback, TokenUsage, UsageCounter};
+
+/// Ends a message in the Llama 3 chat format.
+pub const END_OF_TURN: &str = "<|eot_id|>";
+
+/// The seed of the sampler, so that sampling with a temperature is repeatable too.
+pub const SAMPLING_SEED: u64 = 299_792_458;
+
+/// A provider that runs a model file in-process.
+///
+/// The model is loaded by the first request and kept for the following ones.
+pub struct LocalProvider {
+    config: ModelConfig,
+    usage: UsageCounter,
+    #[cfg(feature = "local-inference")]
+    engine: std::sync::Arc<std::sync::Mutex<Option<engine::Engine>>>,
+}
+
+impl LocalProvider {
+    pub fn new(config: ModelConfig) -> Self {
+        Self {
+            config,
+            usage: UsageCounter::default(),
+            #[cfg(feature = "local-inference")]
+            engine: std::sync::Arc::new(std::sync::Mutex::new(None)),
+        }
+    }
+
+    /// The tokenizer file: the configured one, or `tokenizer.json` next to the model.
+    pub fn tokenizer_path(&self) -> std::path::PathBuf {
+        match &self.config.tokenizer {
+            Some(tokenizer) => tokenizer.into(),
+            None => std::path::Path::new(&self.config.model).with_file_name("tokenizer.json"),
+        }
+    }
+
+    /// Generates the answer on a blocking thread of the Tokio runtime, so that other tasks keep
+    /// running, and passes its pieces to `on_token` as they arrive.
+    #[cfg(feature = "local-inference")]
+    async fn generate(&self, messages: &[ChatMessage], on_token: &mut TokenCallback<'_>) -> Result<String, ModelError> {
+        let engine = std::sync::Arc::clone(&self.engine);
+        let (model, tokenizer) = (self.config.model.clone(), self.tokenizer_path());
+        let (max_tokens, temperature) = (self.config.max_tokens, self.config.temperature);
+        let prompt = prompt(messages);
+        let (sender, mut pieces) = tokio::sync::mpsc::unbounded_channel::<String>();
+        let generation = tokio::task::spawn_blocking(move || {
+            let mut engine = engine.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            if engine.is_none() {
+                *engine = Some(engine::Engine::load(&model, &tokenizer)?);
+            }
+            let engine = engine.as_mut().expect("the engine was just loaded");
+            // The receiver only goes away if the request was dropped, and then nobody is listening
+            engine.generate(&prompt, max_tokens, temperature, &mut |text| {
+                let _ = sender.send(text.to_string());
+            })
+        });
+
+        // The channel closes once the generation ends and drops its sender
+        while let Some(text) = pieces.recv().await {
+            on_token(&text);
+        }
+        let (answer, used) = generation.await.map_err(|error| ModelError::Inference(error.to_string()))??;
+        self.usage.add(used);
+        Ok(answer)
+    }
+
+    #[cfg(not(feature = "local-inference"))]
+    async fn generate(&self, _messages: &[ChatMessage], _on_token: &mut TokenCallback<'_>) -> Result<String, ModelError> {
+        Err(ModelError::Inference("zana was built without the local-inference feature".to_string()))
+    }
+}
+
+impl ModelProvider for LocalProvider {
+    fn config(&self) -> &ModelConfig {
+        &self.config
+    }
+
+    fn token_usage(&self) -> TokenUsage {
+        self.usage.total()
+    }
+
+    fn complete<'a>(&'a self, messages: &'a [ChatMessage]) -> ModelFuture<'a, Result<String, ModelError>> {
+        Box::pin(async move { self.generate(messages, &mut |_| {}).await })
+    }
+
+    fn complete_streaming<'a>(
+        &'a self,
+        messages: &'a [ChatMessage],
+        on_token: &'a mut TokenCallback<'_>,
+    ) -> ModelFuture<'a, Result<String, ModelError>> {
+        Box::pin(async move { self.generate(messages, on_token).await })
+    }
+}
+
+/// Writes a conversation in the Llama 3 chat format, ending with the header of the answer.
+pub fn prompt(messages: &[ChatMessage]) -> String {
+    let mut prompt = String::from("<|begin_of_text|>");
+    for message in messages {
+        let role = match message.role {
+            Role::System => "system",
+            Role::User => "user",
+            Role::Assistant => "assistant",
+        };
+        prompt.push_str(&format!("<|start_header_id|>{}<|end_header_id|>\n\n{}{}", role, message.content, END_OF_TURN));
+    }
+    prompt.push_str("<|start_header_id|>assistant<|end_header_id|>\n\n");
+    prompt
+}
+
+#[cfg(feature = "local-inference")]
+mod engine {
+    use std::fs::File;
+    use std::path::Path;
+    use candle_core::quantized::gguf_file;
+    use candle_core::{Device, Tensor};
+    use candle_transformers::generation::LogitsProcessor;
+    use candle_transformers::models::quantized_llama::ModelWeights;
+    use tokenizers::Tokenizer;
+    use super::{ModelError, TokenCallback, TokenUsage, END_OF_TURN, SAMPLING_SEED};
+
+    /// The tokens that end an answer.
+    const STOP_TOKENS: [&str; 2] = [END_OF_TURN, "<|end_of_text|>"];
+
+    /// A loaded model and its tokenizer.
+    pub struct Engine {
+        model: ModelWeights,
+        tokenizer: Tokenizer,
+        device: Device,
+    }
+
+    fn inference_error(error: impl std::fmt::Display) -> ModelError {
+        ModelError::Inference(error.to_string())
+    }
+
+    impl Engine {
+        pub fn load(model_path: &str, tokenizer_path: &Path) -> Result<Self, ModelError> {
+            let device = Device::Cpu;
+            let mut file = File::open(model_path).map_err(inference_error)?;
+            let content = gguf_file::Content::read(&mut file).map_err(inference_error)?;
+            let model = ModelWeights::from_gguf(content, &mut file, &device).map_err(inference_error)?;
+            let tokenizer = Tokenizer::from_file(tokenizer_path).map_err(inference_error)?;
+            Ok(Self { model, tokenizer, device })
+        }
+
+        /// Continues `prompt` until a stop token or `max_tokens` tokens, passing each piece of
+        /// text to `on_token` as soon as it decodes. Returns the answer and the tokens used.
+        pub fn generate(
+            &mut self,
+            prompt: &str,
+            max_tokens: u32,
+            temperature: Option<f64>,
+            on_token: &mut TokenCallback<'_>,
+        ) -> Result<(String, TokenUsage), ModelError> {
+            let stop_tokens: Vec<u32> = STOP_TOKENS.iter().filter_map(|token| self.tokenizer.token_to_id(token)).collect();
+            let mut context = self.tokenizer.encode(prompt, false).map_err(inference_error)?.get_ids().to_vec();
+            let mut sampler = LogitsProcessor::new(SAMPLING_SEED, temperature, None);
+            let mut decoder = self.tokenizer.decode_stream(true);
+            let mut answer = String::new();
+            let mut usage = TokenUsage { prompt_tokens: context.len() as u64, completion_tokens: 0 };
+            let mut position = 0;
+            for _ in 0..max_tokens {
+                let input = Tensor::new(context.as_slice(), &self.device).and_then(|input| input.unsqueeze(0));
+                let logits = input
+                    .and_then(|input| self.model.forward(&input, position))
+                    .and_then(|logits| logits.squeeze(0))
+                    .map_err(inference_error)?;
+                position += context.len();
+                let token = sampler.sample(&logits).map_err(inference_error)?;
+                usage.completion_tokens += 1;
+                if stop_tokens.contains(&token) {
+                    break;
+                }
+                if let Some(text) = decoder.step(token).map_err(inference_error)? {
+                    on_token(&text);
+                    answer.push_str(&text);
+                }
+                context = vec![token];
+            }
+            Ok((answer, usage))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt() {
+        let prompt = prompt(&[ChatMessage::system("Be brief."), ChatMessage::user("Hi")]);
+        assert_eq!(
+            prompt,
+            "<|begin_of_text|><|start_header_id|>system<|end_header_id|>\n\nBe brief.<|eot_id|>\
+             <|start_header_id|>user<|end_header_id|>\n\nHi<|eot_id|>\
+             <|start_header_id|>assistant<|end_header_id|>\n\n"
+        );
+    }
+
+    #[test]
+    fn test_tokenizer_path() {
+        let provider = LocalProvider::new(ModelConfig::local("models/llama-3.2-1b.gguf"));
+        assert_eq!(provider.tokenizer_path(), std::path::Path::new("models/tokenizer.json"));
+        let provider = LocalProvider::new(ModelConfig::local("llama.gguf").with_tokenizer("llama/tokenizer.json"));
+        assert_eq!(provider.tokenizer_path(), std::path::Path::new("llama/tokenizer.json"));
+    }
+
+    #[cfg(feature = "local-inference")]
+    #[test]
+    fn test_missing_model_fails_on_blocking_thread() {
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let provider = LocalProvider::new(ModelConfig::local("missing/model.gguf"));
+        let mut pieces = Vec::new();
+        let answer = runtime.block_on(provider.complete_streaming(&[ChatMessage::user("Hi")], &mut |text| {
+            pieces.push(text.to_string())
+        }));
+        assert!(matches!(answer, Err(ModelError::Inference(_))));
+        assert!(pieces.is_empty());
+    }
+}
@@ -0,0 +1,479 @@
+//! Model Providers
+//!
+//! A [`ModelProvider`] lets a language model make an agent's decisions. Each provider talks to
+//! one kind of API:
+//! - [`openai::OpenAiProvider`]: OpenAI and other OpenAI-compatible chat completion APIs.
+//! - [`anthropic::AnthropicProvider`]: the Anthropic Messages API.
+//! - [`ollama::OllamaProvider`]: a local Ollama server.
+//! - [`local::LocalProvider`]: a model file run in-process, for agents without network access.
+//! - [`endpoint::EndpointProvider`]: a custom service that takes the agent's memory and
+//!   environment as JSON and answers with an action.
+//!
+//! Providers are configured with a [`ModelConfig`], which is plain data: it is what
+//! [`Agent::save`](crate::agents::Agent::save) writes to disk, and [`ModelConfig::connect`] turns
+//! it back into a provider. API keys are never part of the configuration.
+//!
+//! To decide, a provider sends the model a [`DecisionRequest`] describing the agent's actions,
+//! memory and environment, and asks for a JSON answer such as
+//! `{"action": "gather_resources", "params": {"resource": "wood"}}`, which [`parse_choice`] turns
+//! into an [`ActionChoice`].
+//!
+//! Requests time out after [`ModelConfig::timeout_ms`], are retried with backoff when the API is
+//! rate limited or fails, and can be throttled with a [`RateLimit`]; see [`transport`]. Failures
+//! are reported as a [`ModelError`].
+//!
+//! Answers can also be streamed: [`ModelProvider::complete_streaming`] and
+//! [`ModelProvider::decide_streaming`] pass each piece of text to a callback as it arrives, so a
+//! frontend can show the model's answer while it is being written.
+
+pub mod anthropic;
+pub mod endpoint;
+mod error;
+pub mod local;
+pub mod ollama;
+pub mod openai;
+mod stream;
+pub mod transport;
+
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use serde::{Deserialize, Serialize};
+use super::policies::ActionChoice;
+use super::value::Value;
+
+pub use error::ModelError;
+pub use transport::{RateLimit, RetryPolicy};
+
+/// A boxed future returned by model providers.
+pub type ModelFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Receives the pieces of a streamed answer as they arrive.
+pub type TokenCallback<'a> = dyn FnMut(&str) + Send + 'a;
+
+/// The instructions sent when the configuration has no system prompt.
+pub const DEFAULT_SYSTEM_PROMPT: &str = "You decide what an autonomous agent does next.";
+
+/// The API a provider talks to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Backend {
+    Endpoint,
+    #[serde(rename = "openai")]
+    OpenAi,
+    Anthropic,
+    Ollama,
+    Local,
+}
+
+impl Backend {
+    /// The URL of the public API, or of a local server for Ollama. Custom endpoints and local
+    /// models have none.
+    pub fn default_api_url(self) -> &'static str {
+        match self {
+            Backend::Endpoint | Backend::Local => "",
+            Backend::OpenAi => "https://api.openai.com/v1/chat/completions",
+            Backend::Anthropic => "https://api.anthropic.com/v1/messages",
+            Backend::Ollama => "http://localhost:11434/api/chat",
+        }
+    }
+
+    /// Whether the backend is a chat model, which is sent the agent's conversation memory.
+    /// Custom endpoints are not.
+    pub fn is_chat(self) -> bool {
+        self != Backend::Endpoint
+    }
+
+    /// The environment variable the API key is read from by [`ModelConfig::connect`].
+    pub fn api_key_variable(self) -> Option<&'static str> {
+        match self {
+            Backend::OpenAi => Some("OPENAI_API_KEY"),
+            Backend::Anthropic => Some("ANTHROPIC_API_KEY"),
+            Backend::Endpoint | Backend::Ollama | Backend::Local => None,
+        }
+    }
+}
+
+/// The settings of a model provider.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelConfig {
+    pub backend: Backend,
+    pub api_url: String,
+    /// The model name, empty for custom endpoints. For local models, the path of the model file.
+    #[serde(default)]
+    pub model: String,
+    /// The tokenizer file of a local model, if it is not `tokenizer.json` next to the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokenizer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    pub max_tokens: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    /// How long a request may take, including reading the answer.
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimit>,
+}
+
+fn default_timeout_ms() -> u64 {
+    60_000
+}
+
+impl ModelConfig {
+    /// Settings for `model` on `backend`, using the backend's default URL and up to 1,024 tokens
+    /// per answer. Requests time out after a minute and are retried as [`RetryPolicy::default`]
+    /// says, without a rate limit.
+    pub fn new(backend: Backend, model: &str) -> Self {
+        Self {
+            backend,
+            api_url: backend.default_api_url().to_string(),
+            model: model.to_string(),
+            tokenizer: None,
+            system_prompt: None,
+            max_tokens: 1024,
+            temperature: None,
+            timeout_ms: default_timeout_ms(),
+            retry: RetryPolicy::default(),
+            rate_limit: None,
+        }
+    }
+
+    /// Settings for a custom decision endpoint.
+    pub fn endpoint(api_url: &str) -> Self {
+        Self::new(Backend::Endpoint, "").with_api_url(api_url)
+    }
+
+    /// Settings for an OpenAI model.
+    pub fn openai(model: &str) -> Self {
+        Self::new(Backend::OpenAi, model)
+    }
+
+    /// Settings for an Anthropic model.
+    pub fn anthropic(model: &str) -> Self {
+        Self::new(Backend::Anthropic, model)
+    }
+
+    /// Settings for a model served by Ollama.
+    pub fn ollama(model: &str) -> Self {
+        Self::new(Backend::Ollama, model)
+    }
+
+    /// Settings for a GGUF model file run in-process, which needs the `local-inference` feature.
+    pub fn local(model_path: &str) -> Self {
+        Self::new(Backend::Local, model_path)
+    }
+
+    /// Sends requests to another URL, such as an OpenAI-compatible server.
+    pub fn with_api_url(mut self, api_url: &str) -> Self {
+        self.api_url = api_url.to_string();
+        self
+    }
+
+    pub fn with_tokenizer(mut self, tokenizer_path: &str) -> Self {
+        self.tokenizer = Some(tokenizer_path.to_string());
+        self
+    }
+
+    pub fn with_system_prompt(mut self, system_prompt: &str) -> Self {
+        self.system_prompt = Some(system_prompt.to_string());
+        self
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout_ms = timeout.as_millis() as u64;
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// The system prompt, or [`DEFAULT_SYSTEM_PROMPT`].
+    pub fn system_prompt(&self) -> &str {
+        self.system_prompt.as_deref().unwrap_or(DEFAULT_SYSTEM_PROMPT)
+    }
+
+    /// Creates a provider for these settings, reading the API key from the backend's
+    /// environment variable if it has one.
+    pub fn connect(self) -> Box<dyn ModelProvider> {
+        let api_key = self.backend.api_key_variable().and_then(|variable| std::env::var(variable).ok());
+        self.connect_with_api_key(api_key)
+    }
+
+    /// Creates a provider for these settings with the given API key, for backends that use one.
+    pub fn connect_with_api_key(self, api_key: Option<String>) -> Box<dyn ModelProvider> {
+        match self.backend {
+            Backend::Endpoint => Box::new(endpoint::EndpointProvider::new(self)),
+            Backend::OpenAi => Box::new(openai::OpenAiProvider::new(self).with_optional_api_key(api_key)),
+            Backend::Anthropic => Box::new(anthropic::AnthropicProvider::new(self).with_optional_api_key(api_key)),
+            Backend::Ollama => Box::new(ollama::OllamaProvider::new(self)),
+            Backend::Local => Box::new(local::LocalProvider::new(self)),
+        }
+    }
+}
+
+/// Who wrote a chat message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+}
+
+/// A message of a conversation with a model.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: Role,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn system(content: &str) -> Self {
+        Self { role: Role::System, content: content.to_string() }
+    }
+
+    pub fn user(content: &str) -> Self {
+        Self { role: Role::User, content: content.to_string() }
+    }
+
+    pub fn assistant(content: &str) -> Self {
+        Self { role: Role::Assistant, content: content.to_string() }
+    }
+}
+
+/// What a model is told when asked to decide for an agent.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DecisionRequest {
+    pub agent: String,
+    /// The agent's actions and their descriptions.
+    pub actions: BTreeMap<String, String>,
+    pub memory: HashMap<String, String>,
+    pub environment: HashMap<String, Value>,
+}
+
+impl DecisionRequest {
+    /// The conversation asking the model for the agent's next action.
+    pub fn messages(&self, config: &ModelConfig) -> Vec<ChatMessage> {
+        let mut prompt = format!("You are deciding for the agent \"{}\". Its actions are:\n", self.agent);
+        for (name, description) in &self.actions {
+            if description.is_empty() {
+                prompt.push_str(&format!("- {}\n", name));
+            } else {
+                prompt.push_str(&format!("- {}: {}\n", name, description));
+            }
+        }
+        let memory: BTreeMap<&String, &String> = self.memory.iter().collect();
+        let environment: BTreeMap<&String, &Value> = self.environment.iter().collect();
+        prompt.push_str(&format!("\nIts memory: {}\n", serde_json::json!(memory)));
+        prompt.push_str(&format!("The environment: {}\n\n", serde_json::json!(environment)));
+        prompt.push_str(
+            "Answer with only a JSON object naming one action and its parameters, such as \
+             {\"action\": \"<action>\", \"params\": {\"<name>\": \"<value>\"}}, \
+             or {\"action\": \"idle\"} to do nothing.",
+        );
+        vec![ChatMessage::system(config.system_prompt()), ChatMessage::user(&prompt)]
+    }
+}
+
+/// The tokens used by a model's requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn total(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+
+    /// The larger count of each kind, for streams that report the counts so far.
+    pub(crate) fn max(self, other: TokenUsage) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens: self.prompt_tokens.max(other.prompt_tokens),
+            completion_tokens: self.completion_tokens.max(other.completion_tokens),
+        }
+    }
+}
+
+/// Adds up the tokens used by a provider's requests.
+#[derive(Debug, Default)]
+pub struct UsageCounter {
+    prompt_tokens: AtomicU64,
+    completion_tokens: AtomicU64,
+}
+
+impl UsageCounter {
+    pub fn add(&self, usage: TokenUsage) {
+        self.prompt_tokens.fetch_add(usage.prompt_tokens, Ordering::Relaxed);
+        self.completion_tokens.fetch_add(usage.completion_tokens, Ordering::Relaxed);
+    }
+
+    pub fn total(&self) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens: self.prompt_tokens.load(Ordering::Relaxed),
+            completion_tokens: self.completion_tokens.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A language model that can make an agent's decisions.
+pub trait ModelProvider: Send + Sync {
+    /// The provider's settings.
+    fn config(&self) -> &ModelConfig;
+
+    /// The tokens used by every request so far. Providers whose API does not report usage
+    /// return zero.
+    fn token_usage(&self) -> TokenUsage {
+        TokenUsage::default()
+    }
+
+    /// Sends a conversation to the model and returns its answer. Answers without text are empty.
+    fn complete<'a>(&'a self, messages: &'a [ChatMessage]) -> ModelFuture<'a, Result<String, ModelError>>;
+
+    /// Sends a conversation to the model and passes each piece of its answer to `on_token` as it
+    /// arrives. Returns the whole answer.
+    ///
+    /// Providers that cannot stream pass the whole answer at once.
+    fn complete_streaming<'a>(
+        &'a self,
+        messages: &'a [ChatMessage],
+        on_token: &'a mut TokenCallback<'_>,
+    ) -> ModelFuture<'a, Result<String, ModelError>> {
+        Box::pin(async move {
+            let answer = self.complete(messages).await?;
+            if !answer.is_empty() {
+                on_token(&answer);
+            }
+            Ok(answer)
+        })
+    }
+
+    /// Asks the model for the agent's next action. Answers that are not a valid choice are
+    /// treated as idling.
+    fn decide<'a>(&'a self, request: &'a DecisionRequest) -> ModelFuture<'a, Result<ActionChoice, ModelError>> {
+        Box::pin(async move {
+            let messages = request.messages(self.config());
+            let answer = self.complete(&messages).await?;
+            Ok(parse_choice(&answer).unwrap_or_default())
+        })
+    }
+
+    /// Like [`ModelProvider::decide`], passing each piece of the answer to `on_token` as it
+    /// arrives.
+    fn decide_streaming<'a>(
+        &'a self,
+        request: &'a DecisionRequest,
+        on_token: &'a mut TokenCallback<'_>,
+    ) -> ModelFuture<'a, Result<ActionChoice, ModelError>> {
+        Box::pin(async move {
+            let messages = request.messages(self.config());
+            let answer = self.complete_streaming(&messages, on_token).await?;
+            Ok(parse_choice(&answer).unwrap_or_default())
+        })
+    }
+}
+
+/// Parses a model's answer of the form `{"action": "<name>", "params": {..}}`.
+///
+/// Text around the JSON object, such as a code fence, is ignored, and parameter values that are
+/// not strings are kept as JSON. An action of `"idle"` is [`ActionChoice::Idle`].
+///
+/// # Returns
+/// `None` if the answer holds no JSON object with an action.
+pub fn parse_choice(answer: &str) -> Option<ActionChoice> {
+    let start = answer.find('{')?;
+    let end = answer.rfind('}')?;
+    let json: serde_json::Value = serde_json::from_str(answer.get(start..=end)?).ok()?;
+    let action = json.get("action")?.as_str()?.trim();
+    if action.is_empty() || action == "idle" {
+        return Some(ActionChoice::Idle);
+    }
+    let mut choice = ActionChoice::act(action);
+    if let Some(params) = json.get("params").and_then(serde_json::Value::as_object) {
+        for (name, value) in params {
+            match value {
+                serde_json::Value::String(text) => choice = choice.with_param(name, text),
+                other => choice = choice.with_param(name, &other.to_string()),
+            }
+        }
+    }
+    Some(choice)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_choice() {
+        let answer =
+            "Sure:\n```json\n{\"action\": \"gather\", \"params\": {\"resource\": \"wood\", \"quantity\": 3}}\n```";
+        assert_eq!(
+            parse_choice(answer),
+            Some(ActionChoice::act("gather").with_param("resource", "wood").with_param("quantity", "3"))
+        );
+        assert_eq!(parse_choice(r#"{"action": "idle"}"#), Some(ActionChoice::Idle));
+        assert_eq!(parse_choice(r#"{"action": "rest"}"#), Some(ActionChoice::act("rest")));
+        assert_eq!(parse_choice("rest"), None);
+        assert_eq!(parse_choice(r#"{"choice": "rest"}"#), None);
+    }
+
+    #[test]
+    fn test_decision_messages() {
+        let request = DecisionRequest {
+            agent: "farmer".to_string(),
+            actions: BTreeMap::from([("harvest".to_string(), "Harvests ripe crops".to_string())]),
+            memory: HashMap::from([("mood".to_string(), "calm".to_string())]),
+            environment: HashMap::from([("crops".to_string(), Value::from(3))]),
+        };
+        let messages = request.messages(&ModelConfig::ollama("llama3").with_system_prompt("You farm."));
+        assert_eq!(messages[0], ChatMessage::system("You farm."));
+        assert_eq!(messages[1].role, Role::User);
+        assert!(messages[1].content.contains("- harvest: Harvests ripe crops"));
+        assert!(messages[1].content.contains(r#"Its memory: {"mood":"calm"}"#));
+        assert!(messages[1].content.contains(r#"The environment: {"crops":3.0}"#));
+    }
+
+    #[test]
+    fn test_config_round_trip() {
+        let config = ModelConfig::anthropic("claude-3-5-haiku-latest")
+            .with_temperature(0.2)
+            .with_rate_limit(RateLimit::new(0.5, 4));
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains(r#""backend":"anthropic""#));
+        assert_eq!(serde_json::from_str::<ModelConfig>(&json).unwrap(), config);
+
+        let saved = r#"{"backend": "ollama", "api_url": "http://localhost:11434/api/chat", "model": "llama3", "max_tokens": 64}"#;
+        let config: ModelConfig = serde_json::from_str(saved).unwrap();
+        assert_eq!(config.timeout_ms, 60_000);
+        assert_eq!(config.retry, RetryPolicy::default());
+        assert_eq!(config.rate_limit, None);
+        assert_eq!(config.system_prompt(), DEFAULT_SYSTEM_PROMPT);
+
+        let provider = ModelConfig::openai("gpt-4o-mini").connect();
+        assert_eq!(provider.config().api_url, Backend::OpenAi.default_api_url());
+    }
+}
@@ -0,0 +1,112 @@
+//! Models served by Ollama.
+
+use serde_json::json;
+use super::transport::Transport;
+use super::{stream, ChatMessage, ModelConfig, ModelError, ModelFuture, ModelProvider, TokenCallback, TokenUsage, UsageCounter};
+
+/// A provider for the chat API of an Ollama server.
+pub struct OllamaProvider {
+    config: ModelConfig,
+    transport: Transport,
+    usage: UsageCounter,
+}
+
+impl OllamaProvider {
+    pub fn new(config: ModelConfig) -> Self {
+        Self { transport: Transport::new(&config), usage: UsageCounter::default(), config }
+    }
+
+    fn body(&self, messages: &[ChatMessage]) -> serde_json::Value {
+        let mut options = json!({ "num_predict": self.config.max_tokens });
+        if let Some(temperature) = self.config.temperature {
+            options["temperature"] = json!(temperature);
+        }
+        json!({
+            "model": self.config.model,
+            "messages": messages,
+            "stream": false,
+            "options": options,
+        })
+    }
+}
+
+impl ModelProvider for OllamaProvider {
+    fn config(&self) -> &ModelConfig {
+        &self.config
+    }
+
+    fn token_usage(&self) -> TokenUsage {
+        self.usage.total()
+    }
+
+    fn complete<'a>(&'a self, messages: &'a [ChatMessage]) -> ModelFuture<'a, Result<String, ModelError>> {
+        Box::pin(async move {
+            let request = self.transport.post(&self.config.api_url).json(&self.body(messages));
+            let response: serde_json::Value = self.transport.send(request).await?.json().await?;
+            self.usage.add(usage(&response));
+            Ok(response["message"]["content"].as_str().unwrap_or_default().to_string())
+        })
+    }
+
+    fn complete_streaming<'a>(
+        &'a self,
+        messages: &'a [ChatMessage],
+        on_token: &'a mut TokenCallback<'_>,
+    ) -> ModelFuture<'a, Result<String, ModelError>> {
+        Box::pin(async move {
+            let mut body = self.body(messages);
+            body["stream"] = json!(true);
+            let request = self.transport.post(&self.config.api_url).json(&body);
+            let response = self.transport.send(request).await?;
+            let (answer, used) = stream::read_tokens(response, stream_token, stream_usage, on_token).await?;
+            self.usage.add(used);
+            Ok(answer)
+        })
+    }
+}
+
+/// The prompt and answer token counts, reported with the whole answer or its last streamed line.
+fn usage(response: &serde_json::Value) -> TokenUsage {
+    TokenUsage {
+        prompt_tokens: response["prompt_eval_count"].as_u64().unwrap_or_default(),
+        completion_tokens: response["eval_count"].as_u64().unwrap_or_default(),
+    }
+}
+
+fn stream_usage(line: &str) -> Option<TokenUsage> {
+    let piece: serde_json::Value = serde_json::from_str(line).ok()?;
+    Some(usage(&piece))
+}
+
+/// The text of a streamed line, which holds a whole `{"message": {"content": ..}}` object.
+fn stream_token(line: &str) -> Option<String> {
+    let piece: serde_json::Value = serde_json::from_str(line).ok()?;
+    Some(piece["message"]["content"].as_str()?.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request() {
+        let provider = OllamaProvider::new(ModelConfig::ollama("llama3").with_temperature(0.0));
+        assert_eq!(provider.body(&[ChatMessage::user("Hi")]), json!({
+            "model": "llama3",
+            "messages": [{ "role": "user", "content": "Hi" }],
+            "stream": false,
+            "options": { "num_predict": 1024, "temperature": 0.0 },
+        }));
+    }
+
+    #[test]
+    fn test_stream_token() {
+        let line = r#"{"model": "llama3", "message": {"role": "assistant", "content": "rest"}, "done": false}"#;
+        assert_eq!(stream_token(line), Some("rest".to_string()));
+        assert_eq!(stream_token(r#"{"model": "llama3", "done": true}"#), None);
+        assert_eq!(stream_token(""), None);
+
+        let last = r#"{"model": "llama3", "done": true, "prompt_eval_count": 26, "eval_count": 8}"#;
+        assert_eq!(stream_usage(last), Some(TokenUsage { prompt_tokens: 26, completion_tokens: 8 }));
+    }
+}
@@ -0,0 +1,142 @@
+//! OpenAI-compatible chat completion APIs.
+
+use serde_json::json;
+use super::transport::Transport;
+use super::{stream, ChatMessage, ModelConfig, ModelError, ModelFuture, ModelProvider, TokenCallback, TokenUsage, UsageCounter};
+
+/// A provider for the OpenAI chat completions API and servers compatible with it.
+pub struct OpenAiProvider {
+    config: ModelConfig,
+    api_key: Option<String>,
+    transport: Transport,
+    usage: UsageCounter,
+}
+
+impl OpenAiProvider {
+    pub fn new(config: ModelConfig) -> Self {
+        Self { api_key: None, transport: Transport::new(&config), usage: UsageCounter::default(), config }
+    }
+
+    /// Sends the key as a bearer token. Local compatible servers usually need none.
+    pub fn with_api_key(self, api_key: &str) -> Self {
+        self.with_optional_api_key(Some(api_key.to_string()))
+    }
+
+    pub(super) fn with_optional_api_key(mut self, api_key: Option<String>) -> Self {
+        self.api_key = api_key;
+        self
+    }
+
+    fn body(&self, messages: &[ChatMessage]) -> serde_json::Value {
+        let mut body = json!({
+            "model": self.config.model,
+            "messages": messages,
+            "max_tokens": self.config.max_tokens,
+        });
+        if let Some(temperature) = self.config.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        body
+    }
+
+    fn request(&self, body: &serde_json::Value) -> reqwest::RequestBuilder {
+        let request = self.transport.post(&self.config.api_url).json(body);
+        match &self.api_key {
+            Some(api_key) => request.bearer_auth(api_key),
+            None => request,
+        }
+    }
+}
+
+impl ModelProvider for OpenAiProvider {
+    fn config(&self) -> &ModelConfig {
+        &self.config
+    }
+
+    fn token_usage(&self) -> TokenUsage {
+        self.usage.total()
+    }
+
+    fn complete<'a>(&'a self, messages: &'a [ChatMessage]) -> ModelFuture<'a, Result<String, ModelError>> {
+        Box::pin(async move {
+            let request = self.request(&self.body(messages));
+            let response: serde_json::Value = self.transport.send(request).await?.json().await?;
+            self.usage.add(usage(&response));
+            Ok(answer_text(&response))
+        })
+    }
+
+    fn complete_streaming<'a>(
+        &'a self,
+        messages: &'a [ChatMessage],
+        on_token: &'a mut TokenCallback<'_>,
+    ) -> ModelFuture<'a, Result<String, ModelError>> {
+        Box::pin(async move {
+            let mut body = self.body(messages);
+            body["stream"] = json!(true);
+            body["stream_options"] = json!({ "include_usage": true });
+            let response = self.transport.send(self.request(&body)).await?;
+            let (answer, used) = stream::read_tokens(response, stream_token, stream_usage, on_token).await?;
+            self.usage.add(used);
+            Ok(answer)
+        })
+    }
+}
+
+fn answer_text(response: &serde_json::Value) -> String {
+    response["choices"][0]["message"]["content"].as_str().unwrap_or_default().to_string()
+}
+
+fn usage(response: &serde_json::Value) -> TokenUsage {
+    TokenUsage {
+        prompt_tokens: response["usage"]["prompt_tokens"].as_u64().unwrap_or_default(),
+        completion_tokens: response["usage"]["completion_tokens"].as_u64().unwrap_or_default(),
+    }
+}
+
+/// The usage of the last streamed event, which has no choices.
+fn stream_usage(line: &str) -> Option<TokenUsage> {
+    let event: serde_json::Value = serde_json::from_str(stream::event_data(line)?).ok()?;
+    event.get("usage").filter(|usage| usage.is_object()).map(|_| usage(&event))
+}
+
+/// The text of a streamed `data: {"choices": [{"delta": {"content": ..}}]}` event.
+fn stream_token(line: &str) -> Option<String> {
+    let event: serde_json::Value = serde_json::from_str(stream::event_data(line)?).ok()?;
+    Some(event["choices"][0]["delta"]["content"].as_str()?.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_and_response() {
+        let provider = OpenAiProvider::new(ModelConfig::openai("gpt-4o-mini").with_temperature(0.5));
+        let body = provider.body(&[ChatMessage::system("Be brief."), ChatMessage::user("Hi")]);
+        assert_eq!(body, json!({
+            "model": "gpt-4o-mini",
+            "messages": [{ "role": "system", "content": "Be brief." }, { "role": "user", "content": "Hi" }],
+            "max_tokens": 1024,
+            "temperature": 0.5,
+        }));
+
+        let message = json!({ "role": "assistant", "content": "{\"action\": \"rest\"}" });
+        let response = json!({
+            "choices": [{ "message": message }],
+            "usage": { "prompt_tokens": 12, "completion_tokens": 5, "total_tokens": 17 },
+        });
+        assert_eq!(answer_text(&response), r#"{"action": "rest"}"#);
+        assert_eq!(usage(&response), TokenUsage { prompt_tokens: 12, completion_tokens: 5 });
+        assert_eq!(answer_text(&json!({})), "");
+
+        assert_eq!(stream_token(r#"data: {"choices": [{"delta": {"content": "{\"act"}}]}"#), Some(r#"{"act"#.to_string()));
+        assert_eq!(stream_token(r#"data: {"choices": [{"delta": {"role": "assistant"}}]}"#), None);
+        assert_eq!(stream_token("data: [DONE]"), None);
+        assert_eq!(
+            stream_usage(r#"data: {"choices": [], "usage": {"prompt_tokens": 12, "completion_tokens": 5}}"#),
+            Some(TokenUsage { prompt_tokens: 12, completion_tokens: 5 })
+        );
+        assert_eq!(stream_usage(r#"data: {"choices": [], "usage": null}"#), None);
+    }
+}
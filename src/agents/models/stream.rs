@@ -0,0 +1,88 @@
+//! Reading streamed answers.
+//!
+//! Streaming APIs send their answer in pieces: OpenAI and Anthropic as server-sent events, one
+//! `data: {..}` line per piece, and Ollama as one JSON object per line. Either way the body is
+//! read line by line as chunks arrive.
+
+use reqwest::Response;
+use super::{ModelError, TokenUsage};
+
+/// Collects chunks of a body and splits them into complete lines.
+#[derive(Debug, Default)]
+pub(super) struct LineBuffer {
+    pending: Vec<u8>,
+}
+
+impl LineBuffer {
+    /// Adds a chunk and returns the lines it completes, without their line endings.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(chunk);
+        let mut lines = Vec::new();
+        while let Some(end) = self.pending.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            lines.push(String::from_utf8_lossy(&line).trim_end_matches(['\r', '\n']).to_string());
+        }
+        lines
+    }
+
+    /// The last line, if the body did not end with a line break.
+    pub fn finish(self) -> Option<String> {
+        (!self.pending.is_empty()).then(|| String::from_utf8_lossy(&self.pending).trim_end().to_string())
+    }
+}
+
+/// The data of a server-sent event line, or `None` for comments, event names and blank lines.
+pub(super) fn event_data(line: &str) -> Option<&str> {
+    let data = line.strip_prefix("data:")?;
+    Some(data.strip_prefix(' ').unwrap_or(data))
+}
+
+/// Reads a streamed body, passes the text of each line to `on_token` as `token` extracts it, and
+/// returns the whole answer with the largest token counts `usage` found in a line.
+pub(super) async fn read_tokens(
+    mut response: Response,
+    token: fn(&str) -> Option<String>,
+    usage: fn(&str) -> Option<TokenUsage>,
+    on_token: &mut (dyn FnMut(&str) + Send),
+) -> Result<(String, TokenUsage), ModelError> {
+    let mut buffer = LineBuffer::default();
+    let mut answer = String::new();
+    let mut used = TokenUsage::default();
+    let mut emit = |line: &str| {
+        if let Some(text) = token(line).filter(|text| !text.is_empty()) {
+            on_token(&text);
+            answer.push_str(&text);
+        }
+        if let Some(line_usage) = usage(line) {
+            used = used.max(line_usage);
+        }
+    };
+    while let Some(chunk) = response.chunk().await? {
+        for line in buffer.push(&chunk) {
+            emit(&line);
+        }
+    }
+    if let Some(line) = buffer.finish() {
+        emit(&line);
+    }
+    Ok((answer, used))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_buffer() {
+        let mut buffer = LineBuffer::default();
+        assert!(buffer.push(b"data: {\"a\"").is_empty());
+        assert_eq!(buffer.push(b": 1}\r\n\r\ndata: [DONE]\n{\"b\""), vec!["data: {\"a\": 1}", "", "data: [DONE]"]);
+        assert_eq!(buffer.finish(), Some("{\"b\"".to_string()));
+        assert_eq!(LineBuffer::default().finish(), None);
+
+        assert_eq!(event_data("data: [DONE]"), Some("[DONE]"));
+        assert_eq!(event_data("data:{}"), Some("{}"));
+        assert_eq!(event_data("event: ping"), None);
+        assert_eq!(event_data(""), None);
+    }
+}
@@ -0,0 +1,194 @@
+//! Sending model requests.
+//!
+//! Every provider sends its requests through a [`Transport`], which applies the
+//! [`ModelConfig`]'s timeout, waits for the [`RateLimit`] and retries requests that failed with
+//! a rate limit, a server error, a timeout or a connection failure, as the [`RetryPolicy`] says.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Client, RequestBuilder, Response};
+use serde::{Deserialize, Serialize};
+use super::{ModelConfig, ModelError};
+
+/// How failed requests are retried, with exponential backoff between attempts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// The number of retries after the first attempt.
+    pub max_retries: u32,
+    /// The wait before the first retry.
+    pub initial_backoff_ms: u64,
+    /// The longest wait between attempts.
+    pub max_backoff_ms: u64,
+    /// How much the wait grows after each retry.
+    pub multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    /// Three retries, waiting 0.5, 1 and 2 seconds.
+    fn default() -> Self {
+        Self { max_retries: 3, initial_backoff_ms: 500, max_backoff_ms: 30_000, multiplier: 2.0 }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self { max_retries: 0, ..Self::default() }
+    }
+
+    /// The wait before retry number `retry`, counting from 0.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self.initial_backoff_ms as f64 * self.multiplier.powi(retry as i32);
+        Duration::from_millis(backoff.min(self.max_backoff_ms as f64) as u64)
+    }
+}
+
+/// A limit on how often requests are sent: a token bucket holding up to `burst` requests and
+/// refilled at `requests_per_second`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub requests_per_second: f64,
+    pub burst: u32,
+}
+
+impl RateLimit {
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        Self { requests_per_second, burst: burst.max(1) }
+    }
+}
+
+/// The state of a [`RateLimit`].
+#[derive(Debug)]
+struct TokenBucket {
+    limit: RateLimit,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    /// A full bucket.
+    fn new(limit: RateLimit, now: Instant) -> Self {
+        Self { tokens: limit.burst as f64, limit, refilled_at: now }
+    }
+
+    /// Takes a token if one is available, or returns how long to wait for the next one.
+    fn take(&mut self, now: Instant) -> Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.limit.requests_per_second).min(self.limit.burst as f64);
+        self.refilled_at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / self.limit.requests_per_second))
+        }
+    }
+}
+
+/// An HTTP client that applies a provider's timeout, retry policy and rate limit.
+pub struct Transport {
+    client: Client,
+    timeout: Duration,
+    retry: RetryPolicy,
+    bucket: Option<Mutex<TokenBucket>>,
+}
+
+impl Transport {
+    pub fn new(config: &ModelConfig) -> Self {
+        Self {
+            client: Client::new(),
+            timeout: Duration::from_millis(config.timeout_ms),
+            retry: config.retry.clone(),
+            bucket: config.rate_limit.clone().map(|limit| Mutex::new(TokenBucket::new(limit, Instant::now()))),
+        }
+    }
+
+    /// Starts a POST request to `url`, to be sent with [`Transport::send`].
+    pub fn post(&self, url: &str) -> RequestBuilder {
+        self.client.post(url)
+    }
+
+    /// Sends a request, retrying it as the retry policy says.
+    ///
+    /// A `Retry-After` header on a failed answer replaces the backoff before the next attempt.
+    ///
+    /// # Returns
+    /// The answer if it has a success status, or the error of the last attempt.
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, ModelError> {
+        let mut retry = 0;
+        loop {
+            self.wait_for_rate_limit().await;
+            let attempt = request
+                .try_clone()
+                .ok_or_else(|| ModelError::Connection("request body cannot be resent".to_string()))?;
+            let (error, retry_after) = match attempt.timeout(self.timeout).send().await {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) => {
+                    let retry_after = response
+                        .headers()
+                        .get(RETRY_AFTER)
+                        .and_then(|value| value.to_str().ok()?.trim().parse().ok())
+                        .map(Duration::from_secs);
+                    let status = response.status().as_u16();
+                    let message = response.text().await.unwrap_or_default();
+                    (ModelError::Status { status, message }, retry_after)
+                }
+                Err(error) => (ModelError::from(error), None),
+            };
+            if retry >= self.retry.max_retries || !error.is_retryable() {
+                return Err(error);
+            }
+            tokio::time::sleep(retry_after.unwrap_or_else(|| self.retry.backoff(retry))).await;
+            retry += 1;
+        }
+    }
+
+    async fn wait_for_rate_limit(&self) {
+        let Some(bucket) = &self.bucket else {
+            return;
+        };
+        loop {
+            let wait = bucket.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).take(Instant::now());
+            match wait {
+                Ok(()) => return,
+                Err(wait) => tokio::time::sleep(wait).await,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy::default();
+        let waits: Vec<u64> = (0..8).map(|retry| policy.backoff(retry).as_millis() as u64).collect();
+        assert_eq!(waits, vec![500, 1000, 2000, 4000, 8000, 16000, 30000, 30000]);
+        assert_eq!(RetryPolicy::none().max_retries, 0);
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(RateLimit::new(2.0, 2), start);
+        assert_eq!(bucket.take(start), Ok(()));
+        assert_eq!(bucket.take(start), Ok(()));
+        assert_eq!(bucket.take(start), Err(Duration::from_millis(500)));
+        assert_eq!(bucket.take(start + Duration::from_millis(500)), Ok(()));
+        assert!(bucket.take(start + Duration::from_millis(600)).is_err());
+        assert_eq!(bucket.take(start + Duration::from_secs(10)), Ok(()));
+        assert_eq!(bucket.take(start + Duration::from_secs(10)), Ok(()));
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        assert!(ModelError::Timeout.is_retryable());
+        assert!(ModelError::Status { status: 429, message: String::new() }.is_retryable());
+        assert!(ModelError::Status { status: 503, message: String::new() }.is_retryable());
+        assert!(!ModelError::Status { status: 401, message: "invalid key".to_string() }.is_retryable());
+        assert!(!ModelError::InvalidResponse("expected JSON".to_string()).is_retryable());
+    }
+}
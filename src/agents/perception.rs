@@ -0,0 +1,194 @@
+//! Agent Perception
+//!
+//! Agents share one [`Environment`], but an agent with a [`Perception`] does not see all of it.
+//! Before it decides, the environment is passed through its perception, which:
+//! - drops the entries the agent cannot see, by key ([`Perception::with_visible_keys`],
+//!   [`Perception::hide`]) or by custom rule ([`Perception::with_rule`]);
+//! - adds sensor noise to numeric entries ([`Perception::with_noise`]).
+//!
+//! Policies, models and learning only ever get the observed environment. Actions still change the
+//! shared one.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Mutex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use crate::config;
+use super::environment::Environment;
+use super::value::Value;
+
+type VisibilityRule = Box<dyn Fn(&str, &Value) -> bool + Send>;
+
+/// What an agent can observe of its environment.
+///
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use zana::agents::{Environment, Value};
+/// use zana::agents::perception::Perception;
+///
+/// let environment = Environment::new(HashMap::from([
+///     ("temperature".to_string(), Value::from(20)),
+///     ("treasure.location".to_string(), Value::from("cave")),
+/// ]));
+/// let perception = Perception::new().hide("treasure").with_noise("temperature", 0.5).with_seed(1);
+///
+/// let observed = perception.observe(&environment);
+/// assert_eq!(observed.get("treasure.location"), None);
+/// assert!((observed.number("temperature").unwrap() - 20.0).abs() <= 0.5);
+/// ```
+pub struct Perception {
+    visible_keys: Option<BTreeSet<String>>,
+    hidden: BTreeSet<String>,
+    rules: Vec<VisibilityRule>,
+    noise: BTreeMap<String, f64>,
+    rng: Mutex<StdRng>,
+}
+
+impl Perception {
+    /// Creates a perception that sees every entry exactly.
+    pub fn new() -> Self {
+        Self {
+            visible_keys: None,
+            hidden: BTreeSet::new(),
+            rules: Vec::new(),
+            noise: BTreeMap::new(),
+            rng: Mutex::new(config::std_rng()),
+        }
+    }
+
+    /// Only lets the agent see the given keys.
+    pub fn with_visible_keys<'a>(mut self, keys: impl IntoIterator<Item = &'a str>) -> Self {
+        self.visible_keys = Some(keys.into_iter().map(str::to_string).collect());
+        self
+    }
+
+    /// Hides the entry `key` and every entry under it (`"<key>.<name>"`).
+    pub fn hide(mut self, key: &str) -> Self {
+        self.hidden.insert(key.to_string());
+        self
+    }
+
+    /// Adds a visibility rule. An entry is only seen if every rule returns `true` for its key and
+    /// value.
+    pub fn with_rule(mut self, rule: impl Fn(&str, &Value) -> bool + Send + 'static) -> Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// Adds uniform noise of up to `amplitude` in either direction to the numeric entry `key`.
+    pub fn with_noise(mut self, key: &str, amplitude: f64) -> Self {
+        self.noise.insert(key.to_string(), amplitude.abs());
+        self
+    }
+
+    /// Seeds the noise, for reproducible runs.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = Mutex::new(StdRng::seed_from_u64(seed));
+        self
+    }
+
+    /// Returns `true` if the agent can see the entry.
+    pub fn is_visible(&self, key: &str, value: &Value) -> bool {
+        self.visible_keys.as_ref().is_none_or(|keys| keys.contains(key))
+            && !self.hidden.iter().any(|hidden| is_under(key, hidden))
+            && self.rules.iter().all(|rule| rule(key, value))
+    }
+
+    /// Projects the environment onto what the agent perceives. The reward is passed through.
+    pub fn observe(&self, environment: &Environment) -> Environment {
+        let mut rng = self.rng.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        // Sorted, so that seeded noise is drawn in the same order on every run
+        let mut entries: Vec<(&String, &Value)> =
+            environment.state.iter().filter(|(key, value)| self.is_visible(key, value)).collect();
+        entries.sort_unstable_by_key(|(key, _)| *key);
+
+        let mut observed = Environment::new(Default::default());
+        observed.reward = environment.reward;
+        for (key, value) in entries {
+            let value = match (value, self.noise.get(key)) {
+                (Value::Number(number), Some(&amplitude)) if amplitude > 0.0 => {
+                    Value::Number(number + rng.gen_range(-amplitude..=amplitude))
+                }
+                _ => value.clone(),
+            };
+            observed.update(key, value);
+        }
+        observed
+    }
+}
+
+impl Default for Perception {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_under(key: &str, prefix: &str) -> bool {
+    key.strip_prefix(prefix).is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::agents::Agent;
+    use crate::agents::actions::{Action, ActionOutcome};
+    use crate::agents::policies::{ActionChoice, GreedyPolicy};
+
+    fn environment() -> Environment {
+        Environment::new(HashMap::from([
+            ("position".to_string(), Value::from(4)),
+            ("enemy".to_string(), Value::from("orc")),
+            ("enemy.position".to_string(), Value::from(9)),
+            ("enemy_count".to_string(), Value::from(1)),
+        ]))
+    }
+
+    #[test]
+    fn test_visibility() {
+        let observed = Perception::new().hide("enemy").observe(&environment());
+        let mut keys: Vec<&str> = observed.state.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, ["enemy_count", "position"]);
+
+        let observed = Perception::new().with_visible_keys(["position", "enemy"]).observe(&environment());
+        assert_eq!(observed.state.len(), 2);
+
+        let near = Perception::new().with_rule(|key, value| {
+            !key.ends_with("position") || value.as_number().is_some_and(|position| (position - 4.0).abs() <= 2.0)
+        });
+        let observed = near.observe(&environment());
+        assert_eq!(observed.number("position"), Some(4.0));
+        assert_eq!(observed.get("enemy.position"), None);
+    }
+
+    #[test]
+    fn test_noise() {
+        let perception = || Perception::new().with_noise("position", 1.0).with_noise("enemy", 1.0).with_seed(3);
+        let first = perception();
+        let readings: Vec<f64> = (0..20).map(|_| first.observe(&environment()).number("position").unwrap()).collect();
+        assert!(readings.iter().all(|reading| (reading - 4.0).abs() <= 1.0));
+        assert!(readings.iter().any(|reading| *reading != 4.0));
+
+        let second = perception();
+        assert_eq!(second.observe(&environment()).number("position"), Some(readings[0]));
+        assert_eq!(second.observe(&environment()).text("enemy"), Some("orc"));
+    }
+
+    #[test]
+    fn test_agent_acts_on_observation() {
+        let mut agent = Agent::new("scout");
+        agent.add_action(Action::new("flee", "", |_, _| Ok(ActionOutcome::new())));
+        agent.add_action(Action::new("scout", "", |_, _| Ok(ActionOutcome::new())));
+        agent.set_policy(GreedyPolicy);
+        let environment = Environment::new(HashMap::from([
+            ("flee_value".to_string(), Value::from(5)),
+            ("scout_value".to_string(), Value::from(1)),
+        ]));
+
+        assert_eq!(agent.act(&environment).0, ActionChoice::act("flee"));
+        agent.set_perception(Perception::new().hide("flee_value"));
+        assert_eq!(agent.act(&environment).0, ActionChoice::act("scout"));
+    }
+}
@@ -0,0 +1,283 @@
+//! Goal-Oriented Action Planning
+//!
+//! The [`Planner`] searches for the cheapest sequence of actions that turns the current
+//! environment into one satisfying a goal, using the preconditions, effects and costs declared on
+//! each [`Action`]. The search is a uniform-cost search over the environment entries the actions
+//! mention, so the plan it returns is optimal within the depth limit.
+//!
+//! [`PlanningPolicy`] plugs the planner into an agent: every step it plans from the current
+//! environment and takes the first action of the plan, so the agent re-plans as the world
+//! changes under it.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+use super::actions::Action;
+use super::agent::Agent;
+use super::environment::Environment;
+use super::policies::{ActionChoice, Policy};
+use super::value::Value;
+
+/// The environment entries a goal requires.
+pub type Goal = HashMap<String, Value>;
+
+/// A sequence of actions reaching a goal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    /// The names of the actions, in the order they run.
+    pub actions: Vec<String>,
+    /// The sum of the action costs.
+    pub cost: f64,
+}
+
+/// A goal-oriented action planner.
+///
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use zana::agents::{Environment, Value};
+/// use zana::agents::actions::{Action, ActionOutcome};
+/// use zana::agents::planner::{Goal, Planner};
+///
+/// let actions = [
+///     Action::new("get_axe", "", |_, _| Ok(ActionOutcome::new())).with_effect("has_axe", true),
+///     Action::new("chop", "", |_, _| Ok(ActionOutcome::new()))
+///         .with_precondition("has_axe", true)
+///         .with_effect("has_wood", true),
+/// ];
+/// let environment = Environment::new(HashMap::new());
+/// let goal = Goal::from([("has_wood".to_string(), Value::from(true))]);
+///
+/// let plan = Planner::new().plan(actions.iter(), &environment, &goal).unwrap();
+/// assert_eq!(plan.actions, ["get_axe", "chop"]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Planner {
+    max_depth: usize,
+    max_expansions: usize,
+}
+
+impl Planner {
+    /// Creates a planner for plans of up to 16 actions, exploring at most 10,000 states.
+    pub fn new() -> Self {
+        Self { max_depth: 16, max_expansions: 10_000 }
+    }
+
+    /// Sets the maximum number of actions in a plan.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets how many states the search may expand before giving up.
+    pub fn with_max_expansions(mut self, max_expansions: usize) -> Self {
+        self.max_expansions = max_expansions;
+        self
+    }
+
+    /// Finds the cheapest plan reaching `goal` from `environment`.
+    ///
+    /// # Returns
+    /// The plan, which is empty if the goal already holds, or `None` if no plan within the
+    /// limits reaches the goal.
+    pub fn plan<'a>(
+        &self,
+        actions: impl IntoIterator<Item = &'a Action>,
+        environment: &Environment,
+        goal: &Goal,
+    ) -> Option<Plan> {
+        let mut actions: Vec<&Action> = actions.into_iter().collect();
+        actions.sort_by(|a, b| a.name.cmp(&b.name));
+
+        // Only the entries some action or the goal mentions affect the search
+        let relevant = |key: &String| {
+            goal.contains_key(key)
+                || actions
+                    .iter()
+                    .any(|action| action.preconditions.contains_key(key) || action.effects.contains_key(key))
+        };
+        let start: WorldState = environment
+            .state
+            .iter()
+            .filter(|(key, _)| relevant(key))
+            .map(|(key, value)| (key.clone(), value.to_json()))
+            .collect();
+
+        let mut best_cost: HashMap<WorldState, f64> = HashMap::from([(start.clone(), 0.0)]);
+        let mut frontier = BinaryHeap::from([Node { cost: 0.0, state: start, actions: Vec::new() }]);
+        let mut expansions = 0;
+        while let Some(node) = frontier.pop() {
+            if holds(&node.state, goal) {
+                let actions = node.actions.iter().map(|&index| actions[index].name.clone()).collect();
+                return Some(Plan { actions, cost: node.cost });
+            }
+            if best_cost.get(&node.state).is_some_and(|&cost| cost < node.cost) {
+                continue;
+            }
+            expansions += 1;
+            if expansions > self.max_expansions || node.actions.len() >= self.max_depth {
+                continue;
+            }
+
+            for (index, action) in actions.iter().enumerate() {
+                if !holds(&node.state, &action.preconditions) {
+                    continue;
+                }
+                let mut state = node.state.clone();
+                state.extend(action.effects.iter().map(|(key, value)| (key.clone(), value.to_json())));
+                let cost = node.cost + action.cost.max(0.0);
+                if best_cost.get(&state).is_some_and(|&known| known <= cost) {
+                    continue;
+                }
+                best_cost.insert(state.clone(), cost);
+                let mut path = node.actions.clone();
+                path.push(index);
+                frontier.push(Node { cost, state, actions: path });
+            }
+        }
+        None
+    }
+}
+
+impl Default for Planner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The environment entries relevant to a search, with values as JSON so states can be hashed.
+type WorldState = BTreeMap<String, String>;
+
+fn holds(state: &WorldState, required: &HashMap<String, Value>) -> bool {
+    required.iter().all(|(key, value)| state.get(key).is_some_and(|actual| *actual == value.to_json()))
+}
+
+/// A search node, ordered so the cheapest comes first out of the max-heap.
+struct Node {
+    cost: f64,
+    state: WorldState,
+    actions: Vec<usize>,
+}
+
+impl PartialEq for Node {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Node {}
+
+impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Node {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Cheaper first, then shorter, then in the order the actions are sorted
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.actions.len().cmp(&self.actions.len()))
+            .then_with(|| other.actions.cmp(&self.actions))
+    }
+}
+
+/// A policy that plans towards a goal and takes the first step of the plan.
+///
+/// Idles once the goal holds or when no plan reaches it.
+#[derive(Debug, Clone)]
+pub struct PlanningPolicy {
+    goal: Goal,
+    planner: Planner,
+}
+
+impl PlanningPolicy {
+    /// Creates a policy pursuing `goal` with the default planner.
+    pub fn new(goal: Goal) -> Self {
+        Self { goal, planner: Planner::new() }
+    }
+
+    /// Replaces the planner.
+    pub fn with_planner(mut self, planner: Planner) -> Self {
+        self.planner = planner;
+        self
+    }
+}
+
+impl Policy for PlanningPolicy {
+    fn decide(&self, agent: &Agent, environment: &Environment) -> ActionChoice {
+        self.planner
+            .plan(agent.actions.values(), environment, &self.goal)
+            .and_then(|plan| plan.actions.into_iter().next())
+            .map_or(ActionChoice::Idle, |action| ActionChoice::act(&action))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::actions::ActionOutcome;
+
+    fn action(name: &str, cost: f64) -> Action {
+        Action::new(name, "", |_, _| Ok(ActionOutcome::new())).with_cost(cost)
+    }
+
+    fn goal(entries: &[(&str, &str)]) -> Goal {
+        entries.iter().map(|(k, v)| (k.to_string(), Value::from(*v))).collect()
+    }
+
+    fn campfire_actions() -> Vec<Action> {
+        vec![
+            action("buy_wood", 5.0).with_effect("has_wood", "true"),
+            action("get_axe", 1.0).with_effect("has_axe", "true"),
+            action("chop_wood", 2.0).with_precondition("has_axe", "true").with_effect("has_wood", "true"),
+            action("light_fire", 1.0).with_precondition("has_wood", "true").with_effect("fire", "lit"),
+        ]
+    }
+
+    #[test]
+    fn test_finds_cheapest_plan() {
+        let actions = campfire_actions();
+        let environment = Environment::new(HashMap::new());
+        let plan = Planner::new().plan(&actions, &environment, &goal(&[("fire", "lit")])).unwrap();
+        assert_eq!(plan, Plan { actions: vec!["get_axe".into(), "chop_wood".into(), "light_fire".into()], cost: 4.0 });
+
+        let mut environment = environment;
+        environment.update("has_axe", "true");
+        environment.update("weather", "dry");
+        let plan = Planner::new().plan(&actions, &environment, &goal(&[("fire", "lit")])).unwrap();
+        assert_eq!(plan.actions, ["chop_wood", "light_fire"]);
+
+        environment.update("fire", "lit");
+        assert!(Planner::new().plan(&actions, &environment, &goal(&[("fire", "lit")])).unwrap().actions.is_empty());
+    }
+
+    #[test]
+    fn test_unreachable_goals() {
+        let actions = campfire_actions();
+        let environment = Environment::new(HashMap::new());
+        assert!(Planner::new().plan(&actions, &environment, &goal(&[("fire", "out")])).is_none());
+        assert!(Planner::new().with_max_depth(1).plan(&actions, &environment, &goal(&[("fire", "lit")])).is_none());
+    }
+
+    #[test]
+    fn test_planning_policy_drives_agent() {
+        let mut agent = Agent::new("camper");
+        for action in campfire_actions() {
+            agent.add_action(action);
+        }
+        agent.set_policy(PlanningPolicy::new(goal(&[("fire", "lit")])));
+
+        let mut environment = Environment::new(HashMap::new());
+        let mut taken = Vec::new();
+        loop {
+            let (choice, result) = agent.act(&environment);
+            let Some(action) = choice.action() else { break };
+            taken.push(action.to_string());
+            environment.apply(&result.unwrap());
+        }
+        assert_eq!(taken, ["get_axe", "chop_wood", "light_fire"]);
+        assert_eq!(environment.text("fire"), Some("lit"));
+    }
+}
@@ -1,53 +1,635 @@
-use rand::Rng;
-use crate::agents::core::{Agent, Environment};
+//! Decision Policies
+//!
+//! A [`Policy`] looks at an agent and its environment and picks the next action. Policies are
+//! stored on the agent as trait objects, so the strategy can be swapped at runtime with
+//! [`Agent::set_policy`], and combined: [`FallbackPolicy`] asks several policies in turn, and any
+//! closure taking `(&Agent, &Environment)` is a policy too.
+//!
+//! [`GreedyPolicy`], [`EpsilonGreedyPolicy`] and [`SoftmaxPolicy`] choose by the value of each
+//! action, read from the environment entry `"<action>_value"` (see [`action_values`]). The
+//! exploring ones take an [`ExplorationSchedule`], so that they explore less as they make more
+//! decisions.
 
-/// Random decision-making policy: Chooses an action at random.
-pub fn random_policy<T>(agent: &Agent<T>, _environment: &Environment<T>) -> String {
-    let mut rng = rand::thread_rng();
-    let idx = rng.gen_range(0..agent.actions.len());
-    agent.actions[idx].name.clone()
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use crate::config;
+use super::actions::ActionParams;
+use super::agent::Agent;
+use super::environment::Environment;
+use super::value::Value;
+
+/// The action a policy picked, or `Idle` if there is nothing worth doing.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ActionChoice {
+    /// Run the named action with the given parameters.
+    Act { action: String, params: ActionParams },
+    /// Do nothing this step.
+    #[default]
+    Idle,
 }
 
-/// Greedy policy: Always chooses the action with the highest immediate benefit (scoring-based).
-pub fn greedy_policy<T>(agent: &Agent<T>, environment: &Environment<T>) -> String
-where
-    T: PartialOrd + Default,
-{
-    let mut best_action = "idle".to_string();
-    let mut best_score = T::default();
+impl ActionChoice {
+    /// Chooses the named action without parameters.
+    pub fn act(action: &str) -> Self {
+        ActionChoice::Act { action: action.to_string(), params: ActionParams::new() }
+    }
 
-    for action in &agent.actions {
-        let score = environment
-            .state
-            .get(&format!("{}_value", action.name))
-            .cloned()
-            .unwrap_or(T::default());
+    /// Adds a parameter to the chosen action. Has no effect on `Idle`.
+    pub fn with_param(mut self, key: &str, value: &str) -> Self {
+        if let ActionChoice::Act { params, .. } = &mut self {
+            params.insert(key.to_string(), value.to_string());
+        }
+        self
+    }
 
-        if score > best_score {
-            best_score = score;
-            best_action = action.name.clone();
+    /// The name of the chosen action, or `None` for `Idle`.
+    pub fn action(&self) -> Option<&str> {
+        match self {
+            ActionChoice::Act { action, .. } => Some(action),
+            ActionChoice::Idle => None,
         }
     }
 
-    best_action
+    /// Returns `true` if no action was chosen.
+    pub fn is_idle(&self) -> bool {
+        matches!(self, ActionChoice::Idle)
+    }
+}
+
+/// What happened when an agent acted: the environment before and after, and the reward earned.
+#[derive(Clone, Copy)]
+pub struct Transition<'a> {
+    /// The environment the action was chosen in.
+    pub previous: &'a Environment,
+    /// The action that was taken.
+    pub choice: &'a ActionChoice,
+    /// The reward the environment gave for it.
+    pub reward: f64,
+    /// The environment after the action.
+    pub next: &'a Environment,
+    /// Whether the action ended the episode.
+    pub done: bool,
+}
+
+/// A strategy for choosing an agent's next action.
+pub trait Policy: Send {
+    /// Picks the next action for `agent` in `environment`.
+    fn decide(&self, agent: &Agent, environment: &Environment) -> ActionChoice;
+
+    /// Updates what the agent has learned from a transition. Fixed policies ignore it.
+    fn learn(&self, _agent: &mut Agent, _transition: &Transition) {}
+
+    /// The policy's internal state, such as its random number generator, for a simulation
+    /// checkpoint. Stateless policies have none.
+    fn save_state(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Restores state returned by [`Policy::save_state`].
+    fn restore_state(&self, _state: &serde_json::Value) {}
 }
 
-/// Rule-based policy: Uses predefined rules to choose an action.
-pub fn rule_based_policy<T>(agent: &Agent<T>, environment: &Environment<T>) -> String
+impl<F> Policy for F
 where
-    T: PartialOrd + Default,
+    F: Fn(&Agent, &Environment) -> ActionChoice + Send,
 {
-    if let Some(threat_level) = environment.state.get("threat") {
-        if *threat_level > T::default() {
-            return "retreat".to_string();
+    fn decide(&self, agent: &Agent, environment: &Environment) -> ActionChoice {
+        self(agent, environment)
+    }
+}
+
+impl Policy for Box<dyn Policy> {
+    fn decide(&self, agent: &Agent, environment: &Environment) -> ActionChoice {
+        self.as_ref().decide(agent, environment)
+    }
+
+    fn learn(&self, agent: &mut Agent, transition: &Transition) {
+        self.as_ref().learn(agent, transition)
+    }
+
+    fn save_state(&self) -> Option<serde_json::Value> {
+        self.as_ref().save_state()
+    }
+
+    fn restore_state(&self, state: &serde_json::Value) {
+        self.as_ref().restore_state(state)
+    }
+}
+
+/// Saves the state of a random number generator as a seed.
+///
+/// `StdRng` cannot be written out, so the generator is reseeded with a seed drawn from itself,
+/// and that seed is the saved state. A policy restored from it draws the same numbers as the one
+/// that was saved.
+pub(crate) fn save_rng(rng: &Mutex<StdRng>) -> serde_json::Value {
+    let mut rng = rng.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let seed: u64 = rng.gen();
+    *rng = StdRng::seed_from_u64(seed);
+    serde_json::json!({ "seed": seed })
+}
+
+/// Restores a random number generator saved by [`save_rng`].
+pub(crate) fn restore_rng(rng: &Mutex<StdRng>, state: &serde_json::Value) {
+    if let Some(seed) = state["seed"].as_u64() {
+        *rng.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = StdRng::seed_from_u64(seed);
+    }
+}
+
+/// Chooses uniformly among the agent's actions.
+pub struct RandomPolicy {
+    rng: Mutex<StdRng>,
+}
+
+impl RandomPolicy {
+    /// Creates a policy seeded from the operating system.
+    pub fn new() -> Self {
+        Self { rng: Mutex::new(config::std_rng()) }
+    }
+
+    /// Creates a policy with a fixed seed, for reproducible runs.
+    pub fn seeded(seed: u64) -> Self {
+        Self { rng: Mutex::new(StdRng::seed_from_u64(seed)) }
+    }
+}
+
+impl Default for RandomPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Policy for RandomPolicy {
+    fn decide(&self, agent: &Agent, _environment: &Environment) -> ActionChoice {
+        let names = agent.action_names();
+        if names.is_empty() {
+            return ActionChoice::Idle;
+        }
+        let mut rng = self.rng.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        ActionChoice::act(names[rng.gen_range(0..names.len())])
+    }
+
+    fn save_state(&self) -> Option<serde_json::Value> {
+        Some(save_rng(&self.rng))
+    }
+
+    fn restore_state(&self, state: &serde_json::Value) {
+        restore_rng(&self.rng, state)
+    }
+}
+
+/// The value of each of the agent's actions, in name order, read from the environment entry
+/// `"<action>_value"`.
+///
+/// Entries holding numbers count, and so do text entries holding one, such as `"2.5"` in an
+/// environment loaded from strings. Actions without such an entry have no value and are left out.
+pub fn action_values<'a>(agent: &'a Agent, environment: &Environment) -> Vec<(&'a str, f64)> {
+    agent
+        .action_names()
+        .into_iter()
+        .filter_map(|name| {
+            let value: f64 = match environment.get(&format!("{}_value", name))? {
+                Value::Text(text) => text.trim().parse().ok()?,
+                value => value.as_number()?,
+            };
+            value.is_finite().then_some((name, value))
+        })
+        .collect()
+}
+
+/// The action with the highest value, with ties going to the alphabetically first action.
+fn best_valued<'a>(values: &[(&'a str, f64)]) -> Option<&'a str> {
+    let mut best: Option<(&str, f64)> = None;
+    for &(name, value) in values {
+        if best.is_none_or(|(_, best_value)| value > best_value) {
+            best = Some((name, value));
         }
     }
+    best.map(|(name, _)| name)
+}
+
+/// Chooses the action with the highest immediate value.
+///
+/// Values are read by [`action_values`]. Actions without a value are never chosen, and ties go to
+/// the alphabetically first action.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GreedyPolicy;
+
+impl Policy for GreedyPolicy {
+    fn decide(&self, agent: &Agent, environment: &Environment) -> ActionChoice {
+        best_valued(&action_values(agent, environment)).map_or(ActionChoice::Idle, ActionChoice::act)
+    }
+}
 
-    if let Some(resources) = environment.state.get("resources") {
-        if *resources > T::default() {
-            return "gather".to_string();
+/// How an exploration parameter, such as ε or a temperature, changes with the number of
+/// decisions made.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExplorationSchedule {
+    /// The same value for every decision.
+    Constant(f64),
+    /// Starts at `initial` and moves in a straight line to `end` over `steps` decisions.
+    Linear { initial: f64, end: f64, steps: u64 },
+    /// Starts at `initial`, is multiplied by `decay` after each decision, and never drops below
+    /// `min`.
+    Exponential { initial: f64, decay: f64, min: f64 },
+}
+
+impl ExplorationSchedule {
+    /// The value for the decision after `step` earlier ones.
+    pub fn value(&self, step: u64) -> f64 {
+        match *self {
+            ExplorationSchedule::Constant(value) => value,
+            ExplorationSchedule::Linear { initial, end, steps } => {
+                if step >= steps {
+                    end
+                } else {
+                    initial + (end - initial) * step as f64 / steps as f64
+                }
+            }
+            ExplorationSchedule::Exponential { initial, decay, min } => {
+                (initial * decay.powi(step.min(i32::MAX as u64) as i32)).max(min)
+            }
         }
     }
+}
 
-    "idle".to_string()
+/// The random number generator and decision count of an exploring policy.
+struct Exploration {
+    schedule: ExplorationSchedule,
+    rng: Mutex<StdRng>,
+    steps: AtomicU64,
+}
+
+impl Exploration {
+    fn new(schedule: ExplorationSchedule) -> Self {
+        Self { schedule, rng: Mutex::new(config::std_rng()), steps: AtomicU64::new(0) }
+    }
+
+    /// The schedule's value for this decision, counting it.
+    fn next_value(&self) -> f64 {
+        self.schedule.value(self.steps.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn rng(&self) -> std::sync::MutexGuard<'_, StdRng> {
+        self.rng.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn save_state(&self) -> serde_json::Value {
+        let mut state = save_rng(&self.rng);
+        state["steps"] = self.steps.load(Ordering::Relaxed).into();
+        state
+    }
+
+    fn restore_state(&self, state: &serde_json::Value) {
+        restore_rng(&self.rng, state);
+        if let Some(steps) = state["steps"].as_u64() {
+            self.steps.store(steps, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Chooses a random action with probability ε, and the highest-valued action otherwise.
+///
+/// Exploring chooses uniformly among all of the agent's actions; exploiting idles if no action
+/// has a value, like [`GreedyPolicy`].
+pub struct EpsilonGreedyPolicy {
+    exploration: Exploration,
+}
+
+impl EpsilonGreedyPolicy {
+    /// Creates a policy exploring with a constant probability `epsilon`.
+    pub fn new(epsilon: f64) -> Self {
+        Self::with_schedule(ExplorationSchedule::Constant(epsilon))
+    }
+
+    /// Creates a policy whose ε follows `schedule`.
+    pub fn with_schedule(schedule: ExplorationSchedule) -> Self {
+        Self { exploration: Exploration::new(schedule) }
+    }
+
+    /// Seeds the exploration, for reproducible runs.
+    pub fn with_seed(self, seed: u64) -> Self {
+        *self.exploration.rng() = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// The ε of the next decision.
+    pub fn epsilon(&self) -> f64 {
+        self.exploration.schedule.value(self.exploration.steps.load(Ordering::Relaxed))
+    }
+}
+
+impl Policy for EpsilonGreedyPolicy {
+    fn decide(&self, agent: &Agent, environment: &Environment) -> ActionChoice {
+        let names = agent.action_names();
+        if names.is_empty() {
+            return ActionChoice::Idle;
+        }
+        let epsilon = self.exploration.next_value().clamp(0.0, 1.0);
+        let mut rng = self.exploration.rng();
+        if rng.gen_bool(epsilon) {
+            return ActionChoice::act(names[rng.gen_range(0..names.len())]);
+        }
+        best_valued(&action_values(agent, environment)).map_or(ActionChoice::Idle, ActionChoice::act)
+    }
+
+    fn save_state(&self) -> Option<serde_json::Value> {
+        Some(self.exploration.save_state())
+    }
+
+    fn restore_state(&self, state: &serde_json::Value) {
+        self.exploration.restore_state(state)
+    }
+}
+
+/// Chooses among the valued actions with probabilities weighted by `exp(value / temperature)`.
+///
+/// High temperatures choose almost uniformly, low ones almost always take the highest-valued
+/// action, and a temperature of zero or below is greedy. Actions without a value are never
+/// chosen.
+pub struct SoftmaxPolicy {
+    exploration: Exploration,
+}
+
+impl SoftmaxPolicy {
+    /// Creates a policy with a constant temperature.
+    pub fn new(temperature: f64) -> Self {
+        Self::with_schedule(ExplorationSchedule::Constant(temperature))
+    }
+
+    /// Creates a policy whose temperature follows `schedule`.
+    pub fn with_schedule(schedule: ExplorationSchedule) -> Self {
+        Self { exploration: Exploration::new(schedule) }
+    }
+
+    /// Seeds the sampling, for reproducible runs.
+    pub fn with_seed(self, seed: u64) -> Self {
+        *self.exploration.rng() = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// The temperature of the next decision.
+    pub fn temperature(&self) -> f64 {
+        self.exploration.schedule.value(self.exploration.steps.load(Ordering::Relaxed))
+    }
+
+    /// The probability of choosing each valued action at `temperature`, in name order.
+    pub fn probabilities<'a>(values: &[(&'a str, f64)], temperature: f64) -> Vec<(&'a str, f64)> {
+        if temperature <= 0.0 {
+            let best = best_valued(values);
+            return values.iter().map(|&(name, _)| (name, if Some(name) == best { 1.0 } else { 0.0 })).collect();
+        }
+        let max = values.iter().map(|(_, value)| *value).fold(f64::NEG_INFINITY, f64::max);
+        let weights: Vec<f64> = values.iter().map(|(_, value)| ((value - max) / temperature).exp()).collect();
+        let total: f64 = weights.iter().sum();
+        values.iter().zip(weights).map(|(&(name, _), weight)| (name, weight / total)).collect()
+    }
+}
+
+impl Policy for SoftmaxPolicy {
+    fn decide(&self, agent: &Agent, environment: &Environment) -> ActionChoice {
+        let values = action_values(agent, environment);
+        let temperature = self.exploration.next_value();
+        if values.is_empty() {
+            return ActionChoice::Idle;
+        }
+        let probabilities = Self::probabilities(&values, temperature);
+        let mut sample = self.exploration.rng().gen::<f64>();
+        for &(name, probability) in &probabilities {
+            if sample < probability {
+                return ActionChoice::act(name);
+            }
+            sample -= probability;
+        }
+        // Rounding can leave a sliver past the last probability.
+        probabilities
+            .iter()
+            .rev()
+            .find(|(_, probability)| *probability > 0.0)
+            .map_or(ActionChoice::Idle, |(name, _)| ActionChoice::act(name))
+    }
+
+    fn save_state(&self) -> Option<serde_json::Value> {
+        Some(self.exploration.save_state())
+    }
+
+    fn restore_state(&self, state: &serde_json::Value) {
+        self.exploration.restore_state(state)
+    }
+}
+
+/// A condition on a numeric environment entry and the action it triggers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    /// The environment entry to check.
+    pub key: String,
+    /// The rule fires when the entry is strictly greater than this value.
+    pub threshold: f64,
+    /// The action to take when the rule fires.
+    pub action: String,
+}
+
+/// Chooses the action of the first rule whose condition holds.
+///
+/// The default rules retreat when `"threat"` is positive and gather when `"resources"` is
+/// positive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleBasedPolicy {
+    rules: Vec<Rule>,
+}
+
+impl RuleBasedPolicy {
+    /// Creates a policy without rules, which always idles.
+    pub fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// Adds a rule choosing `action` when the entry `key` is above `threshold`. Rules are checked
+    /// in the order they were added.
+    pub fn when_above(mut self, key: &str, threshold: f64, action: &str) -> Self {
+        self.rules.push(Rule { key: key.to_string(), threshold, action: action.to_string() });
+        self
+    }
+
+    /// The rules, in the order they are checked.
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+}
+
+impl Default for RuleBasedPolicy {
+    fn default() -> Self {
+        Self::new().when_above("threat", 0.0, "retreat").when_above("resources", 0.0, "gather")
+    }
+}
+
+impl Policy for RuleBasedPolicy {
+    fn decide(&self, _agent: &Agent, environment: &Environment) -> ActionChoice {
+        self.rules
+            .iter()
+            .find(|rule| environment.number(&rule.key).is_some_and(|value| value > rule.threshold))
+            .map_or(ActionChoice::Idle, |rule| ActionChoice::act(&rule.action))
+    }
+}
+
+/// Asks each policy in turn and takes the first choice that is not `Idle`.
+///
+/// # Example
+/// ```
+/// use zana::agents::policies::{FallbackPolicy, GreedyPolicy, RandomPolicy, RuleBasedPolicy};
+///
+/// // Follow the rules, then the best-valued action, and explore otherwise
+/// let policy = FallbackPolicy::new()
+///     .then(RuleBasedPolicy::default())
+///     .then(GreedyPolicy)
+///     .then(RandomPolicy::new());
+/// ```
+#[derive(Default)]
+pub struct FallbackPolicy {
+    policies: Vec<Box<dyn Policy>>,
+}
+
+impl FallbackPolicy {
+    /// Creates an empty chain, which always idles.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a policy to the chain.
+    pub fn then(mut self, policy: impl Policy + 'static) -> Self {
+        self.policies.push(Box::new(policy));
+        self
+    }
+}
+
+impl Policy for FallbackPolicy {
+    fn decide(&self, agent: &Agent, environment: &Environment) -> ActionChoice {
+        self.policies
+            .iter()
+            .map(|policy| policy.decide(agent, environment))
+            .find(|choice| !choice.is_idle())
+            .unwrap_or_default()
+    }
+
+    fn learn(&self, agent: &mut Agent, transition: &Transition) {
+        for policy in &self.policies {
+            policy.learn(agent, transition);
+        }
+    }
+
+    /// The state of every policy in the chain, in order.
+    fn save_state(&self) -> Option<serde_json::Value> {
+        let states: Vec<serde_json::Value> =
+            self.policies.iter().map(|policy| policy.save_state().unwrap_or_default()).collect();
+        Some(serde_json::Value::Array(states))
+    }
+
+    fn restore_state(&self, state: &serde_json::Value) {
+        for (policy, state) in self.policies.iter().zip(state.as_array().into_iter().flatten()) {
+            if !state.is_null() {
+                policy.restore_state(state);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::agents::actions::{Action, ActionOutcome};
+    use crate::agents::value::Value;
+
+    fn agent_with(actions: &[&str]) -> Agent {
+        let mut agent = Agent::new("tester");
+        for name in actions {
+            agent.add_action(Action::new(name, "", |_, _| Ok(ActionOutcome::new())));
+        }
+        agent
+    }
+
+    fn environment(entries: &[(&str, Value)]) -> Environment {
+        Environment::new(entries.iter().map(|(k, v)| (k.to_string(), v.clone())).collect::<HashMap<_, _>>())
+    }
+
+    #[test]
+    fn test_random_policy() {
+        let agent = agent_with(&["a", "b", "c"]);
+        let env = environment(&[]);
+        let first: Vec<_> = (0..8).map(|_| RandomPolicy::seeded(7).decide(&agent, &env)).collect();
+        assert!(first.iter().all(|choice| choice == &first[0]));
+        assert!(["a", "b", "c"].contains(&first[0].action().unwrap()));
+        assert!(RandomPolicy::seeded(7).decide(&agent_with(&[]), &env).is_idle());
+    }
+
+    #[test]
+    fn test_greedy_policy() {
+        let agent = agent_with(&["gather", "rest", "explore"]);
+        let env =
+            environment(&[("gather_value", 2.5.into()), ("rest_value", 10.into()), ("explore_value", "high".into())]);
+        assert_eq!(GreedyPolicy.decide(&agent, &env), ActionChoice::act("rest"));
+        assert!(GreedyPolicy.decide(&agent, &environment(&[])).is_idle());
+        let loaded = environment(&[("gather_value", "12".into()), ("rest_value", 10.into())]);
+        assert_eq!(GreedyPolicy.decide(&agent, &loaded), ActionChoice::act("gather"));
+    }
+
+    #[test]
+    fn test_exploration_policies() {
+        let schedule = ExplorationSchedule::Linear { initial: 1.0, end: 0.0, steps: 4 };
+        assert_eq!((0..6).map(|step| schedule.value(step)).collect::<Vec<_>>(), [1.0, 0.75, 0.5, 0.25, 0.0, 0.0]);
+        let schedule = ExplorationSchedule::Exponential { initial: 1.0, decay: 0.5, min: 0.2 };
+        assert_eq!(schedule.value(1), 0.5);
+        assert_eq!(schedule.value(10), 0.2);
+
+        let agent = agent_with(&["gather", "rest", "explore"]);
+        let env = environment(&[("gather_value", 1.into()), ("rest_value", 3.into())]);
+        let policy = EpsilonGreedyPolicy::with_schedule(ExplorationSchedule::Linear { initial: 1.0, end: 0.0, steps: 50 })
+            .with_seed(3);
+        let choices: Vec<_> = (0..100).map(|_| policy.decide(&agent, &env)).collect();
+        assert!(choices[..50].iter().any(|choice| choice.action() != Some("rest")));
+        assert!(choices[50..].iter().all(|choice| choice.action() == Some("rest")));
+        assert_eq!(policy.epsilon(), 0.0);
+
+        let probabilities = SoftmaxPolicy::probabilities(&[("a", 1.0), ("b", 1.0 + 2f64.ln())], 1.0);
+        assert!((probabilities[0].1 - 1.0 / 3.0).abs() < 1e-12);
+        assert_eq!(SoftmaxPolicy::probabilities(&[("a", 1.0), ("b", 2.0)], 0.0), [("a", 0.0), ("b", 1.0)]);
+        let policy = SoftmaxPolicy::new(0.5).with_seed(8);
+        let state = policy.save_state().unwrap();
+        let first: Vec<_> = (0..20).map(|_| policy.decide(&agent, &env)).collect();
+        assert!(first.iter().all(|choice| choice.action() != Some("explore")));
+        policy.restore_state(&state);
+        assert_eq!((0..20).map(|_| policy.decide(&agent, &env)).collect::<Vec<_>>(), first);
+    }
+
+    #[test]
+    fn test_rule_based_policy() {
+        let agent = agent_with(&[]);
+        let policy = RuleBasedPolicy::default();
+        let threatened = environment(&[("threat", 3.into()), ("resources", 5.into())]);
+        assert_eq!(policy.decide(&agent, &threatened).action(), Some("retreat"));
+        let safe = environment(&[("threat", 0.into()), ("resources", 5.into())]);
+        assert_eq!(policy.decide(&agent, &safe).action(), Some("gather"));
+        assert!(policy.decide(&agent, &environment(&[("threat", "none".into())])).is_idle());
+    }
+
+    #[test]
+    fn test_fallback_and_swapping() {
+        let mut agent = agent_with(&["gather", "rest"]);
+        let calm = environment(&[("rest_value", 1.into())]);
+        assert!(agent.choose_action(&calm).is_idle());
+
+        agent.set_policy(FallbackPolicy::new().then(RuleBasedPolicy::default()).then(GreedyPolicy));
+        assert_eq!(agent.choose_action(&calm).action(), Some("rest"));
+        assert_eq!(agent.choose_action(&environment(&[("threat", 1.into())])).action(), Some("retreat"));
+
+        agent.set_policy(|_: &Agent, _: &Environment| ActionChoice::act("gather").with_param("resource", "wood"));
+        assert_eq!(
+            agent.choose_action(&calm),
+            ActionChoice::Act {
+                action: "gather".to_string(),
+                params: HashMap::from([("resource".to_string(), "wood".to_string())]),
+            }
+        );
+    }
 }
@@ -1,7 +1,7 @@
-use crate::agents::actions::{Action, ActionParams};
-use crate::agents::agent::Agent;
-use crate::agents::environment::Environment;
-use std::collections::HashMap;
+use ed25519_dalek::SigningKey;
+use crate::agents::actions::{required_param, Action, ActionError, ActionOutcome, ParamKind};
+use crate::agents::system::Message;
+use crate::crypto::signatures::sign_message;
 
 /// Action: Learn something and save it in memory.
 pub fn learn_action() -> Action {
@@ -9,14 +9,12 @@ pub fn learn_action() -> Action {
         "learn",
         "Learn and store knowledge in the agent's memory.",
         |agent, params| {
-            if let Some(concept) = params.get("concept") {
-                agent.memory.store("knowledge", concept);
-                println!("{} learned about: {}", agent.name, concept);
-            } else {
-                println!("{} failed to learn due to missing 'concept' parameter.", agent.name);
-            }
+            let concept = required_param(&params, "concept")?;
+            agent.store("knowledge", concept);
+            Ok(ActionOutcome::new().with_output("learned", concept))
         },
     )
+    .with_param("concept", ParamKind::Text, "What to learn.")
 }
 
 /// Action: Forget something by removing it from memory.
@@ -25,39 +23,54 @@ pub fn forget_action() -> Action {
         "forget",
         "Forget and remove knowledge from memory.",
         |agent, params| {
-            if let Some(concept) = params.get("concept") {
-                agent.memory.long_term.remove(concept);
-                println!("{} forgot: {}", agent.name, concept);
-            } else {
-                println!(
-                    "{} failed to forget due to missing 'concept' parameter.",
-                    agent.name
-                );
-            }
+            let concept = required_param(&params, "concept")?;
+            let forgotten = agent.memory.long_term.remove(concept).is_some();
+            Ok(ActionOutcome::new()
+                .with_output("forgot", concept)
+                .with_output("was_known", &forgotten.to_string()))
         },
     )
+    .with_param("concept", ParamKind::Text, "What to forget.")
 }
 
 /// Action: Send a message to a specified channel with a given tone.
 pub fn send_message_action() -> Action {
+    send_message_action_on("general")
+}
+
+/// Action: Send a message, to `default_channel` unless a channel is given.
+///
+/// The message goes to the agent named by the `to` parameter, or to every other agent without
+/// one. It waits in the sender's outbox until an [`AgentSystem`](crate::agents::system::AgentSystem)
+/// delivers it.
+pub fn send_message_action_on(default_channel: &str) -> Action {
+    let default_channel = default_channel.to_string();
     Action::new(
         "send_message",
         "Send a message to a specific channel.",
-        |agent, params| {
-            let channel = params.get("channel").unwrap_or(&"general".to_string()).clone();
-            let tone = params.get("tone").unwrap_or(&"neutral".to_string()).clone();
-            let message = params
-                .get("message")
-                .unwrap_or(&"Hello, world!".to_string())
-                .clone();
-
-            println!(
-                "{} sent a message to '{}': [{}] {}",
-                agent.name, channel, tone, message
-            );
-            agent.memory.store("last_message", &message);
+        move |agent, params| {
+            let channel = params.get("channel").unwrap_or(&default_channel);
+            let tone = params.get("tone").map_or("neutral", String::as_str);
+            let message = params.get("message").map_or("Hello, world!", String::as_str);
+
+            agent.remember("last_message", message);
+            agent.outbox.push(Message {
+                from: agent.name.clone(),
+                to: params.get("to").cloned(),
+                channel: channel.clone(),
+                tone: tone.to_string(),
+                content: message.to_string(),
+            });
+            Ok(ActionOutcome::new()
+                .with_output("channel", channel)
+                .with_output("tone", tone)
+                .with_output("message", message))
         },
     )
+    .with_optional_param("message", ParamKind::Text, "The message to send.")
+    .with_optional_param("to", ParamKind::Text, "The agent to send it to, every other agent if unset.")
+    .with_optional_param("channel", ParamKind::Text, "The channel to send it on.")
+    .with_optional_param("tone", ParamKind::Text, "The tone of the message.")
 }
 
 /// Action: Perform a cryptographic operation.
@@ -66,16 +79,38 @@ pub fn cryptography_action() -> Action {
         "cryptography",
         "Perform a cryptographic operation using specified parameters.",
         |_agent, params| {
-            let crypto_type = params.get("crypto_type").unwrap_or(&"sign".to_string()).clone();
-            let keys = params.get("keys").unwrap_or(&"default_keys".to_string()).clone();
-            let owner = params.get("owner").unwrap_or(&"anonymous".to_string()).clone();
-
-            println!(
-                "Performed '{}' cryptographic operation with keys '{}' for owner '{}'.",
-                crypto_type, keys, owner
-            );
+            let crypto_type = params.get("crypto_type").map_or("sign", String::as_str);
+            let keys = params.get("keys").map_or("default_keys", String::as_str);
+            let owner = params.get("owner").map_or("anonymous", String::as_str);
+
+            Ok(ActionOutcome::new()
+                .with_output("crypto_type", crypto_type)
+                .with_output("keys", keys)
+                .with_output("owner", owner))
+        },
+    )
+    .with_optional_param("crypto_type", ParamKind::Text, "The operation to perform.")
+    .with_optional_param("keys", ParamKind::Text, "The keys to use.")
+    .with_optional_param("owner", ParamKind::Text, "The owner of the keys.")
+}
+
+/// Action: Sign the `message` parameter with the given Ed25519 key.
+///
+/// The hex-encoded signature is returned as the `signature` output. The key stays inside the
+/// action and is never written to memory.
+pub fn signing_action(keypair: SigningKey) -> Action {
+    Action::new(
+        "sign",
+        "Sign a message with the agent's key.",
+        move |_agent, params| {
+            let message = required_param(&params, "message")?;
+            let signature = sign_message(&keypair, message.as_bytes());
+            Ok(ActionOutcome::new()
+                .with_output("signature", &hex::encode(signature.to_bytes()))
+                .with_output("public_key", &hex::encode(keypair.verifying_key().to_bytes())))
         },
     )
+    .with_param("message", ParamKind::Text, "The message to sign.")
 }
 
 /// Action: Gather resources from the environment.
@@ -84,19 +119,22 @@ pub fn gather_resources_action() -> Action {
         "gather_resources",
         "Gather resources from the environment.",
         |_agent, params| {
-            let resource = params.get("resource").unwrap_or(&"unknown".to_string()).clone();
-            let quantity = params
-                .get("quantity")
-                .unwrap_or(&"1".to_string())
-                .parse::<u32>()
-                .unwrap_or(1);
-
-            println!(
-                "Gathered {} of resource '{}' from the environment.",
-                quantity, resource
-            );
+            let resource = params.get("resource").map_or("unknown", String::as_str);
+            let quantity = match params.get("quantity") {
+                Some(quantity) => quantity.parse::<u32>().map_err(|_| ActionError::InvalidParameter {
+                    name: "quantity".to_string(),
+                    value: quantity.clone(),
+                })?,
+                None => 1,
+            };
+
+            Ok(ActionOutcome::new()
+                .with_output("resource", resource)
+                .with_output("quantity", &quantity.to_string()))
         },
     )
+    .with_optional_param("resource", ParamKind::Text, "The resource to gather.")
+    .with_optional_param("quantity", ParamKind::Integer, "How much to gather, 1 if unset.")
 }
 
 /// Action: Analyze environmental data.
@@ -104,14 +142,13 @@ pub fn analyze_environment_action() -> Action {
     Action::new(
         "analyze_environment",
         "Analyze the current environment and log findings.",
-        |_agent, params| {
-            if let Some(environment) = params.get("environment_state") {
-                println!("Analyzing environment: {}", environment);
-            } else {
-                println!("No environment data provided for analysis.");
-            }
+        |agent, params| {
+            let environment = required_param(&params, "environment_state")?;
+            agent.remember("last_analysis", environment);
+            Ok(ActionOutcome::new().with_output("analyzed", environment))
         },
     )
+    .with_param("environment_state", ParamKind::Text, "The state to analyze.")
 }
 
 /// Action: Collaborate with another agent.
@@ -120,12 +157,14 @@ pub fn collaborate_action() -> Action {
         "collaborate",
         "Collaborate with another agent on a task.",
         |_agent, params| {
-            let partner = params.get("partner").unwrap_or(&"unknown".to_string()).clone();
-            let task = params.get("task").unwrap_or(&"unspecified".to_string()).clone();
+            let partner = params.get("partner").map_or("unknown", String::as_str);
+            let task = params.get("task").map_or("unspecified", String::as_str);
 
-            println!("Collaborated with '{}' on task '{}'.", partner, task);
+            Ok(ActionOutcome::new().with_output("partner", partner).with_output("task", task))
         },
     )
+    .with_optional_param("partner", ParamKind::Text, "The agent to work with.")
+    .with_optional_param("task", ParamKind::Text, "The task to work on.")
 }
 
 /// Action: Train a skill.
@@ -134,12 +173,57 @@ pub fn train_skill_action() -> Action {
         "train_skill",
         "Train and improve a skill over time.",
         |agent, params| {
-            if let Some(skill) = params.get("skill") {
-                agent.memory.store("current_training", skill);
-                println!("{} is training to improve skill: {}", agent.name, skill);
-            } else {
-                println!("{} failed to train due to missing 'skill' parameter.", agent.name);
-            }
+            let skill = required_param(&params, "skill")?;
+            agent.store("current_training", skill);
+            Ok(ActionOutcome::new().with_output("training", skill))
         },
     )
+    .with_param("skill", ParamKind::Text, "The skill to train.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use ed25519_dalek::Signature;
+    use crate::agents::actions::ActionParams;
+    use crate::agents::Agent;
+    use crate::crypto::signatures::{generate_keypair, verify_message};
+
+    #[test]
+    fn test_signing_action() {
+        let keypair = generate_keypair();
+        let public_key = keypair.verifying_key();
+        let mut agent = Agent::new("notary");
+        agent.add_action(signing_action(keypair));
+
+        let params = ActionParams::from([("message".to_string(), "deed".to_string())]);
+        let outcome = agent.execute_action("sign", params).unwrap();
+        let signature = Signature::from_slice(&hex::decode(&outcome.outputs["signature"]).unwrap()).unwrap();
+        assert!(verify_message(&public_key, b"deed", &signature));
+        assert_eq!(
+            agent.execute_action("sign", ActionParams::new()),
+            Err(ActionError::MissingParameter("message".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parameterized_and_capturing_actions() {
+        let mut agent = Agent::new("herald");
+        agent.add_action(send_message_action_on("alerts"));
+        let outcome = agent.execute_action("send_message", ActionParams::new()).unwrap();
+        assert_eq!(outcome.outputs["channel"], "alerts");
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        agent.add_action(Action::new("count", "Count invocations.", move |_, _| {
+            let count = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(ActionOutcome::new().with_output("count", &count.to_string()))
+        }));
+        agent.execute_action("count", ActionParams::new()).unwrap();
+        agent.execute_action("count", ActionParams::new()).unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(agent.memory.short_term.get("count.count"), Some("2"));
+    }
 }
@@ -0,0 +1,280 @@
+//! Tabular Q-Learning
+//!
+//! [`QLearningPolicy`] learns the value of taking each action in each observed state from the
+//! rewards the environment hands out. After every action the estimate is moved towards
+//!
+//! `reward + discount · max Q(next state, ·)`
+//!
+//! and the agent explores with probability ε, which decays after every finished episode. The
+//! Q-table and the episode count live in the agent's long-term memory, so what the agent learned
+//! stays with the agent rather than with the policy object.
+
+use std::sync::Mutex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use crate::config;
+use super::agent::Agent;
+use super::environment::Environment;
+use super::policies::{restore_rng, save_rng, ActionChoice, Policy, Transition};
+use super::value::Value;
+
+/// The prefix of the memory keys holding Q-values, `"q::<state>::<action>"`.
+pub const Q_VALUE_PREFIX: &str = "q::";
+
+/// The memory key counting finished episodes.
+pub const EPISODES_KEY: &str = "q_learning::episodes";
+
+/// An ε-greedy policy that learns action values with Q-learning.
+///
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use zana::agents::{Agent, Environment};
+/// use zana::agents::actions::{Action, ActionOutcome};
+/// use zana::agents::policies::Transition;
+/// use zana::agents::q_learning::QLearningPolicy;
+///
+/// let mut agent = Agent::new("learner");
+/// agent.add_action(Action::new("work", "", |_, _| Ok(ActionOutcome::new())));
+/// agent.add_action(Action::new("rest", "", |_, _| Ok(ActionOutcome::new())));
+/// agent.set_policy(QLearningPolicy::new().with_seed(1));
+///
+/// let mut environment = Environment::new(HashMap::new());
+/// for _ in 0..50 {
+///     let previous = environment.clone();
+///     let (choice, _) = agent.act(&environment);
+///     environment.set_reward(if choice.action() == Some("work") { 1.0 } else { 0.0 });
+///     let reward = environment.take_reward();
+///     agent.learn(&Transition { previous: &previous, choice: &choice, reward, next: &environment, done: true });
+/// }
+/// ```
+pub struct QLearningPolicy {
+    learning_rate: f64,
+    discount: f64,
+    initial_epsilon: f64,
+    epsilon_decay: f64,
+    min_epsilon: f64,
+    state_keys: Option<Vec<String>>,
+    rng: Mutex<StdRng>,
+}
+
+impl QLearningPolicy {
+    /// Creates a policy with a learning rate of 0.1, a discount of 0.9, and exploration decaying
+    /// from 1.0 by a factor of 0.99 per episode down to 0.05.
+    pub fn new() -> Self {
+        Self {
+            learning_rate: 0.1,
+            discount: 0.9,
+            initial_epsilon: 1.0,
+            epsilon_decay: 0.99,
+            min_epsilon: 0.05,
+            state_keys: None,
+            rng: Mutex::new(config::std_rng()),
+        }
+    }
+
+    /// Sets how far each update moves a Q-value towards its target, in `(0, 1]`.
+    pub fn with_learning_rate(mut self, learning_rate: f64) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Sets how much future rewards count compared to the immediate one, in `[0, 1]`.
+    pub fn with_discount(mut self, discount: f64) -> Self {
+        self.discount = discount;
+        self
+    }
+
+    /// Sets the exploration schedule: ε starts at `initial`, is multiplied by `decay` after each
+    /// episode, and never drops below `min`.
+    pub fn with_exploration(mut self, initial: f64, decay: f64, min: f64) -> Self {
+        self.initial_epsilon = initial;
+        self.epsilon_decay = decay;
+        self.min_epsilon = min;
+        self
+    }
+
+    /// Restricts the state to the given environment entries. By default every entry is part of
+    /// the state, which only works well when the entries take few distinct values.
+    pub fn with_state_keys(mut self, keys: &[&str]) -> Self {
+        self.state_keys = Some(keys.iter().map(|key| key.to_string()).collect());
+        self
+    }
+
+    /// Seeds the exploration, for reproducible runs.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = Mutex::new(StdRng::seed_from_u64(seed));
+        self
+    }
+
+    /// The state an environment is in, as used for the Q-table: the selected entries as sorted
+    /// `key=value` pairs.
+    pub fn state_of(&self, environment: &Environment) -> String {
+        let mut entries: Vec<(&String, &Value)> = match &self.state_keys {
+            Some(keys) => keys.iter().filter_map(|key| environment.state.get_key_value(key)).collect(),
+            None => environment.state.iter().collect(),
+        };
+        entries.sort_unstable_by_key(|(key, _)| *key);
+        entries.iter().map(|(key, value)| format!("{}={}", key, value)).collect::<Vec<_>>().join(",")
+    }
+
+    /// The current exploration rate of `agent`.
+    pub fn epsilon(&self, agent: &Agent) -> f64 {
+        let episodes = episodes(agent);
+        (self.initial_epsilon * self.epsilon_decay.powi(episodes.min(i32::MAX as u64) as i32)).max(self.min_epsilon)
+    }
+
+    /// The learned value of taking `action` in `state`, or zero if it was never tried.
+    pub fn q_value(&self, agent: &Agent, state: &str, action: &str) -> f64 {
+        agent
+            .memory
+            .recall(&q_key(state, action))
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(0.0)
+    }
+
+    /// The action with the highest value in `state`, with ties going to the alphabetically first.
+    pub fn best_action<'a>(&self, agent: &'a Agent, state: &str) -> Option<(&'a str, f64)> {
+        let mut best: Option<(&str, f64)> = None;
+        for name in agent.action_names() {
+            let value = self.q_value(agent, state, name);
+            if best.is_none_or(|(_, best_value)| value > best_value) {
+                best = Some((name, value));
+            }
+        }
+        best
+    }
+}
+
+impl Default for QLearningPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Policy for QLearningPolicy {
+    fn decide(&self, agent: &Agent, environment: &Environment) -> ActionChoice {
+        let names = agent.action_names();
+        if names.is_empty() {
+            return ActionChoice::Idle;
+        }
+        let mut rng = self.rng.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if rng.gen_bool(self.epsilon(agent).clamp(0.0, 1.0)) {
+            return ActionChoice::act(names[rng.gen_range(0..names.len())]);
+        }
+        drop(rng);
+        let state = self.state_of(environment);
+        self.best_action(agent, &state).map_or(ActionChoice::Idle, |(name, _)| ActionChoice::act(name))
+    }
+
+    fn learn(&self, agent: &mut Agent, transition: &Transition) {
+        if let Some(action) = transition.choice.action() {
+            let state = self.state_of(transition.previous);
+            let future = if transition.done {
+                0.0
+            } else {
+                let next_state = self.state_of(transition.next);
+                self.best_action(agent, &next_state).map_or(0.0, |(_, value)| value)
+            };
+            let current = self.q_value(agent, &state, action);
+            let target = transition.reward + self.discount * future;
+            let updated = current + self.learning_rate * (target - current);
+            agent.store(&q_key(&state, action), &updated.to_string());
+        }
+        if transition.done {
+            let episodes = episodes(agent) + 1;
+            agent.store(EPISODES_KEY, &episodes.to_string());
+        }
+    }
+
+    /// The Q-table lives in the agent's memory, so only the exploration state is saved.
+    fn save_state(&self) -> Option<serde_json::Value> {
+        Some(save_rng(&self.rng))
+    }
+
+    fn restore_state(&self, state: &serde_json::Value) {
+        restore_rng(&self.rng, state)
+    }
+}
+
+fn q_key(state: &str, action: &str) -> String {
+    format!("{}{}::{}", Q_VALUE_PREFIX, state, action)
+}
+
+fn episodes(agent: &Agent) -> u64 {
+    agent.memory.recall(EPISODES_KEY).and_then(|value| value.parse().ok()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::agents::actions::{Action, ActionOutcome};
+
+    const GOAL: i32 = 4;
+
+    fn corridor(position: i32) -> Environment {
+        Environment::new(HashMap::from([("position".to_string(), Value::from(position))]))
+    }
+
+    #[test]
+    fn test_learns_to_walk_to_the_goal() {
+        let mut agent = Agent::new("walker");
+        agent.add_action(Action::new("left", "", |_, _| Ok(ActionOutcome::new())));
+        agent.add_action(Action::new("right", "", |_, _| Ok(ActionOutcome::new())));
+        agent.set_policy(QLearningPolicy::new().with_learning_rate(0.5).with_exploration(1.0, 0.95, 0.0).with_seed(3));
+
+        for _ in 0..200 {
+            let mut environment = corridor(0);
+            for _ in 0..50 {
+                let previous = environment.clone();
+                let (choice, _) = agent.act(&environment);
+                let position = previous.number("position").unwrap() as i32;
+                let position = match choice.action() {
+                    Some("right") => position + 1,
+                    _ => (position - 1).max(0),
+                };
+                environment = corridor(position);
+                environment.set_reward(if position == GOAL { 1.0 } else { -0.01 });
+                let reward = environment.take_reward();
+                let done = position == GOAL;
+                agent.learn(&Transition { previous: &previous, choice: &choice, reward, next: &environment, done });
+                if done {
+                    break;
+                }
+            }
+        }
+
+        let policy = QLearningPolicy::new().with_exploration(0.0, 1.0, 0.0);
+        assert_eq!(episodes(&agent), 200);
+        for position in 0..GOAL {
+            assert_eq!(policy.decide(&agent, &corridor(position)).action(), Some("right"));
+        }
+        assert!(policy.q_value(&agent, "position=3", "right") > policy.q_value(&agent, "position=0", "right"));
+    }
+
+    #[test]
+    fn test_exploration_decays_per_episode() {
+        let mut agent = Agent::new("learner");
+        let policy = QLearningPolicy::new().with_exploration(1.0, 0.5, 0.2);
+        assert_eq!(policy.epsilon(&agent), 1.0);
+
+        let environment = corridor(0);
+        let choice = ActionChoice::Idle;
+        let transition =
+            Transition { previous: &environment, choice: &choice, reward: 0.0, next: &environment, done: true };
+        policy.learn(&mut agent, &transition);
+        assert_eq!(policy.epsilon(&agent), 0.5);
+        policy.learn(&mut agent, &transition);
+        policy.learn(&mut agent, &transition);
+        assert_eq!(policy.epsilon(&agent), 0.2);
+    }
+
+    #[test]
+    fn test_state_keys() {
+        let mut environment = corridor(2);
+        environment.update("weather", "rain");
+        assert_eq!(QLearningPolicy::new().state_of(&environment), "position=2,weather=rain");
+        assert_eq!(QLearningPolicy::new().with_state_keys(&["weather"]).state_of(&environment), "weather=rain");
+    }
+}
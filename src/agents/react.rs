@@ -0,0 +1,338 @@
+//! Reasoning Loops
+//!
+//! A [`ReactLoop`] lets a model work on a task over several steps, in the style of ReAct
+//! (reasoning and acting). Each step the model either proposes one of the agent's actions,
+//!
+//! `{"thought": "I need wood first", "action": "gather_resources", "params": {"resource": "wood"}}`
+//!
+//! which the agent executes, applying its state changes to the environment and reporting what
+//! happened back to the model as an observation, or ends the task with
+//!
+//! `{"thought": "The house is built", "final_answer": "Built a house from 3 wood"}`.
+//!
+//! Proposals naming an action the agent does not have are not executed; the model is told which
+//! actions exist instead. Answers that are not such a JSON object are reported back the same way.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use super::actions::ActionOutcome;
+use super::agent::Agent;
+use super::environment::Environment;
+use super::models::{parse_choice, ChatMessage, ModelError, ModelProvider};
+use super::policies::ActionChoice;
+use super::value::Value;
+
+/// How the model is asked to answer at every step.
+pub const REACT_INSTRUCTIONS: &str = "Work on the task step by step. At every step, answer with only a JSON object: \
+     either {\"thought\": \"<reasoning>\", \"action\": \"<action>\", \"params\": {\"<name>\": \"<value>\"}} \
+     to run one of the agent's actions, after which you are told what happened, \
+     or {\"thought\": \"<reasoning>\", \"final_answer\": \"<answer>\"} once the task is done.";
+
+/// What the model answered at one step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReactTurn {
+    /// Run an action and report what happened.
+    Act { thought: Option<String>, choice: ActionChoice },
+    /// End the task with an answer.
+    Finish { thought: Option<String>, answer: String },
+}
+
+/// Parses an answer of the form `{"thought": .., "action": .., "params": {..}}` or
+/// `{"thought": .., "final_answer": ..}`. A final answer wins if both are given.
+///
+/// # Returns
+/// `None` if the answer holds no JSON object with an action or a final answer.
+pub fn parse_turn(answer: &str) -> Option<ReactTurn> {
+    let start = answer.find('{')?;
+    let end = answer.rfind('}')?;
+    let json: serde_json::Value = serde_json::from_str(answer.get(start..=end)?).ok()?;
+    let thought = json.get("thought").and_then(serde_json::Value::as_str).map(str::to_string);
+    if let Some(final_answer) = json.get("final_answer") {
+        let answer = match final_answer {
+            serde_json::Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        return Some(ReactTurn::Finish { thought, answer });
+    }
+    match parse_choice(answer)? {
+        ActionChoice::Idle => None,
+        choice => Some(ReactTurn::Act { thought, choice }),
+    }
+}
+
+/// One step of a run: what the model proposed and what it was told happened.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReactStep {
+    pub thought: Option<String>,
+    /// The proposed action, or `Idle` if the answer could not be understood.
+    pub choice: ActionChoice,
+    /// The result of the action, or `None` if it was not executed.
+    pub result: Option<Result<ActionOutcome, String>>,
+    /// The observation sent back to the model.
+    pub observation: String,
+}
+
+/// The result of [`ReactLoop::run`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReactRun {
+    /// Every step that ran an action or was rejected, in order.
+    pub steps: Vec<ReactStep>,
+    /// The model's final answer, or `None` if the step limit was reached first.
+    pub answer: Option<String>,
+}
+
+/// An error that ended a run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReactError {
+    /// The agent has no model provider.
+    NoModelProvider,
+    /// The model could not be asked for its next step.
+    Model(ModelError),
+}
+
+impl fmt::Display for ReactError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReactError::NoModelProvider => write!(f, "agent has no model provider"),
+            ReactError::Model(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ReactError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReactError::Model(error) => Some(error),
+            ReactError::NoModelProvider => None,
+        }
+    }
+}
+
+impl From<ModelError> for ReactError {
+    fn from(error: ModelError) -> Self {
+        ReactError::Model(error)
+    }
+}
+
+/// A reason-and-act loop between an agent's model provider and its actions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReactLoop {
+    /// The most steps the model may take before the run ends without an answer.
+    pub max_steps: usize,
+}
+
+impl Default for ReactLoop {
+    fn default() -> Self {
+        Self { max_steps: 8 }
+    }
+}
+
+impl ReactLoop {
+    /// A loop of at most 8 steps.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Works on `task` with the agent's model provider until the model gives a final answer or
+    /// the step limit is reached.
+    ///
+    /// The model sees the environment as the agent perceives it, and the state changes of every
+    /// action are applied to `environment`. Executed actions are recorded in the agent's memory
+    /// as by [`Agent::execute_action`].
+    pub async fn run(&self, agent: &mut Agent, environment: &mut Environment, task: &str) -> Result<ReactRun, ReactError> {
+        let provider = agent.model_provider.take().ok_or(ReactError::NoModelProvider)?;
+        let run = self.run_with(provider.as_ref(), agent, environment, task).await;
+        agent.model_provider = Some(provider);
+        run
+    }
+
+    async fn run_with(
+        &self,
+        provider: &dyn ModelProvider,
+        agent: &mut Agent,
+        environment: &mut Environment,
+        task: &str,
+    ) -> Result<ReactRun, ReactError> {
+        let system_prompt = format!("{}\n\n{}", provider.config().system_prompt(), REACT_INSTRUCTIONS);
+        let mut messages = vec![ChatMessage::system(&system_prompt), ChatMessage::user(&task_prompt(agent, environment, task))];
+        let mut steps = Vec::new();
+        while steps.len() < self.max_steps {
+            let answer = provider.complete(&messages).await?;
+            messages.push(ChatMessage::assistant(&answer));
+            let step = match parse_turn(&answer) {
+                Some(ReactTurn::Finish { answer, .. }) => return Ok(ReactRun { steps, answer: Some(answer) }),
+                Some(ReactTurn::Act { thought, choice }) => act(agent, environment, thought, choice),
+                None => ReactStep {
+                    thought: None,
+                    choice: ActionChoice::Idle,
+                    result: None,
+                    observation: "Your answer was not understood. ".to_string() + REACT_INSTRUCTIONS,
+                },
+            };
+            messages.push(ChatMessage::user(&format!("Observation: {}", step.observation)));
+            steps.push(step);
+        }
+        Ok(ReactRun { steps, answer: None })
+    }
+}
+
+/// The first message: the task, the agent's actions and what it perceives.
+fn task_prompt(agent: &Agent, environment: &Environment, task: &str) -> String {
+    let mut prompt = format!("Task: {}\n\nYou act for the agent \"{}\". Its actions are:\n", task, agent.name);
+    for name in agent.action_names() {
+        match agent.actions[name].description.as_str() {
+            "" => prompt.push_str(&format!("- {}\n", name)),
+            description => prompt.push_str(&format!("- {}: {}\n", name, description)),
+        }
+    }
+    prompt.push_str(&format!("\nThe environment: {}", state_json(&agent.observe(environment).state)));
+    prompt
+}
+
+/// Runs a proposed action if the agent has it, and describes what happened.
+fn act(agent: &mut Agent, environment: &mut Environment, thought: Option<String>, choice: ActionChoice) -> ReactStep {
+    agent.notify_decision(&choice);
+    let action = choice.action().unwrap_or_default();
+    if !agent.actions.contains_key(action) {
+        let observation = format!("Unknown action \"{}\". The actions are: {}.", action, agent.action_names().join(", "));
+        return ReactStep { thought, choice, result: None, observation };
+    }
+    let result = agent.perform(&choice);
+    let observation = match &result {
+        Ok(outcome) => {
+            environment.apply(outcome);
+            let outputs: BTreeMap<&String, &String> = outcome.outputs.iter().collect();
+            format!(
+                "{} succeeded with outputs {}. The environment is now: {}",
+                action,
+                serde_json::json!(outputs),
+                state_json(&agent.observe(environment).state)
+            )
+        }
+        Err(error) => format!("{} failed: {}", action, error),
+    };
+    let result = Some(result.map_err(|error| error.to_string()));
+    ReactStep { thought, choice, result, observation }
+}
+
+fn state_json(state: &std::collections::HashMap<String, Value>) -> serde_json::Value {
+    let state: BTreeMap<&String, &Value> = state.iter().collect();
+    serde_json::json!(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::future::Future;
+    use std::sync::Mutex;
+    use std::task::{Context, Poll, Waker};
+    use crate::agents::models::{ModelConfig, ModelFuture};
+    use crate::agents::predefined_actions;
+
+    /// Answers with scripted replies and records the conversations it was sent.
+    struct ScriptedModel {
+        config: ModelConfig,
+        replies: Mutex<Vec<&'static str>>,
+        conversations: std::sync::Arc<Mutex<Vec<Vec<ChatMessage>>>>,
+    }
+
+    impl ModelProvider for ScriptedModel {
+        fn config(&self) -> &ModelConfig {
+            &self.config
+        }
+
+        fn complete<'a>(&'a self, messages: &'a [ChatMessage]) -> ModelFuture<'a, Result<String, ModelError>> {
+            self.conversations.lock().unwrap().push(messages.to_vec());
+            let reply = self.replies.lock().unwrap().remove(0);
+            Box::pin(async move { Ok(reply.to_string()) })
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("the scripted model never waits"),
+        }
+    }
+
+    #[test]
+    fn test_parse_turn() {
+        assert_eq!(
+            parse_turn(r#"{"thought": "need wood", "action": "gather", "params": {"resource": "wood"}}"#),
+            Some(ReactTurn::Act {
+                thought: Some("need wood".to_string()),
+                choice: ActionChoice::act("gather").with_param("resource", "wood"),
+            })
+        );
+        assert_eq!(
+            parse_turn("```json\n{\"final_answer\": 3}\n```"),
+            Some(ReactTurn::Finish { thought: None, answer: "3".to_string() })
+        );
+        assert_eq!(parse_turn(r#"{"action": "idle"}"#), None);
+        assert_eq!(parse_turn("done"), None);
+    }
+
+    #[test]
+    fn test_run() {
+        let conversations = std::sync::Arc::default();
+        let mut agent = Agent::new("builder");
+        agent.add_action(predefined_actions::gather_resources_action());
+        agent.set_model_provider(ScriptedModel {
+            config: ModelConfig::ollama("llama3"),
+            replies: Mutex::new(vec![
+                r#"{"thought": "Fly there", "action": "fly"}"#,
+                "Let me think.",
+                r#"{"action": "gather_resources", "params": {"resource": "wood", "quantity": "two"}}"#,
+                r#"{"action": "gather_resources", "params": {"resource": "wood", "quantity": "2"}}"#,
+                r#"{"thought": "Enough wood", "final_answer": "Gathered 2 wood"}"#,
+            ]),
+            conversations: std::sync::Arc::clone(&conversations),
+        });
+        let mut environment = Environment::new(HashMap::from([("weather".to_string(), Value::from("sunny"))]));
+
+        let run = block_on(ReactLoop::new().run(&mut agent, &mut environment, "Gather wood")).unwrap();
+        assert_eq!(run.answer.as_deref(), Some("Gathered 2 wood"));
+        assert_eq!(run.steps.len(), 4);
+        assert_eq!(run.steps[0].observation, "Unknown action \"fly\". The actions are: gather_resources.");
+        assert_eq!(run.steps[1].choice, ActionChoice::Idle);
+        assert!(matches!(run.steps[2].result, Some(Err(_))));
+        assert_eq!(run.steps[3].result.as_ref().unwrap().as_ref().unwrap().outputs["quantity"], "2");
+        assert!(agent.model_provider.is_some());
+        assert_eq!(agent.memory.short_term.get("gather_resources.quantity"), Some("2"));
+
+        let conversations = conversations.lock().unwrap();
+        let first = &conversations[0];
+        assert!(first[0].content.ends_with(REACT_INSTRUCTIONS));
+        assert!(first[1].content.starts_with("Task: Gather wood"));
+        assert!(first[1].content.contains(r#"The environment: {"weather":"sunny"}"#));
+        let last = conversations.last().unwrap();
+        assert_eq!(last.len(), 10);
+        assert!(last[9].content.starts_with("Observation: gather_resources succeeded"));
+    }
+
+    #[test]
+    fn test_step_limit_and_missing_model() {
+        let mut agent = Agent::new("stuck");
+        let mut environment = Environment::new(HashMap::new());
+        assert_eq!(
+            block_on(ReactLoop::new().run(&mut agent, &mut environment, "Wait")),
+            Err(ReactError::NoModelProvider)
+        );
+
+        agent.set_model_provider(ScriptedModel {
+            config: ModelConfig::ollama("llama3"),
+            replies: Mutex::new(vec![r#"{"action": "wait"}"#; 2]),
+            conversations: std::sync::Arc::default(),
+        });
+        let run = block_on(ReactLoop::new().with_max_steps(2).run(&mut agent, &mut environment, "Wait")).unwrap();
+        assert_eq!(run.answer, None);
+        assert_eq!(run.steps.len(), 2);
+    }
+}
@@ -0,0 +1,746 @@
+//! Tick-Based Simulation
+//!
+//! A [`Simulation`] runs the agents of an [`AgentSystem`] in a shared [`Environment`]. Every tick,
+//! each agent in turn (in name order):
+//! 1. observes the environment through its perception and decides, with its policy or, in [`Simulation::step_async`],
+//!    its model provider;
+//! 2. acts, and the state changes of its action are applied to the environment;
+//! 3. is rewarded by the transition rules, and its policy learns from the transition between
+//!    what it observed before and after acting.
+//!
+//! After every agent has acted, the environment dynamics run, the tick hooks see what happened,
+//! and the stop conditions are checked. Given seeded policies, a run is fully reproducible.
+//!
+//! Each simulation records [metrics](super::metrics) of its agents as it runs: actions, rewards,
+//! decision latency and model token usage, available from [`Simulation::metrics`].
+//!
+//! Large populations can run their ticks in parallel with [`Simulation::with_parallelism`]: every
+//! agent observes the environment as it was at the start of the tick, decides and acts on a
+//! thread pool, and the state changes are merged afterwards. Agents that write different values
+//! to the same entry are settled by the simulation's [`ConflictResolution`].
+//!
+//! [`Simulation::checkpoint`] writes the agents, the environment and the tick counter to a JSON
+//! file, and [`Simulation::resume`] continues from it, in the same process or another one.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+use std::time::Instant;
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use serde::{Deserialize, Serialize};
+use super::agent::{Agent, SavedAgent};
+use super::environment::Environment;
+use super::metrics::MetricsRegistry;
+use super::policies::{ActionChoice, Transition};
+use super::system::{AgentSystem, Message, Turn};
+use super::value::Value;
+
+/// The version of the checkpoint format written by [`Simulation::checkpoint`].
+pub const CHECKPOINT_VERSION: u64 = 1;
+
+const CHECKPOINT_FORMAT: &str = "zana-checkpoint";
+
+type RewardRule = Box<dyn FnMut(&mut Environment, &Turn) -> f64>;
+type Dynamics = Box<dyn FnMut(&mut Environment, u64)>;
+type StopCondition = Box<dyn Fn(&Environment, u64) -> bool>;
+type TickHook = Box<dyn FnMut(&TickReport)>;
+type Resolver = Arc<dyn Fn(&str, &[EnvironmentWrite]) -> Option<Value> + Send + Sync>;
+
+/// A value an agent's action wrote to the environment during a parallel tick.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvironmentWrite {
+    pub agent: String,
+    pub value: Value,
+}
+
+/// Agents that wrote different values to the same entry during a parallel tick.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub key: String,
+    /// The competing writes, in agent name order.
+    pub writes: Vec<EnvironmentWrite>,
+    /// The value the entry was given, or `None` if it kept its previous value.
+    pub resolved: Option<Value>,
+}
+
+/// How a parallel tick settles agents writing different values to the same entry.
+#[derive(Clone, Default)]
+pub enum ConflictResolution {
+    /// The agent last in name order wins, as if the agents had acted one after another.
+    #[default]
+    LastWriterWins,
+    /// The agent first in name order wins.
+    FirstWriterWins,
+    /// The entry keeps its previous value.
+    Discard,
+    /// A function picks the value from the key and the competing writes; `None` keeps the
+    /// previous value.
+    Custom(Resolver),
+}
+
+impl ConflictResolution {
+    /// Uses a function to settle conflicts.
+    pub fn custom(resolver: impl Fn(&str, &[EnvironmentWrite]) -> Option<Value> + Send + Sync + 'static) -> Self {
+        ConflictResolution::Custom(Arc::new(resolver))
+    }
+
+    /// The value a conflicting entry is given.
+    pub fn resolve(&self, key: &str, writes: &[EnvironmentWrite]) -> Option<Value> {
+        match self {
+            ConflictResolution::LastWriterWins => writes.last().map(|write| write.value.clone()),
+            ConflictResolution::FirstWriterWins => writes.first().map(|write| write.value.clone()),
+            ConflictResolution::Discard => None,
+            ConflictResolution::Custom(resolver) => resolver(key, writes),
+        }
+    }
+}
+
+impl fmt::Debug for ConflictResolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConflictResolution::LastWriterWins => write!(f, "LastWriterWins"),
+            ConflictResolution::FirstWriterWins => write!(f, "FirstWriterWins"),
+            ConflictResolution::Discard => write!(f, "Discard"),
+            ConflictResolution::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+/// What happened during one tick.
+#[derive(Debug)]
+pub struct TickReport<'a> {
+    /// The tick number, starting at 1.
+    pub tick: u64,
+    /// Each agent's turn, in the order they acted.
+    pub turns: &'a [Turn],
+    /// The environment at the end of the tick.
+    pub environment: &'a Environment,
+    /// The conflicting environment writes of a parallel tick, in key order. Always empty for
+    /// ticks where the agents act one after another.
+    pub conflicts: &'a [Conflict],
+}
+
+/// Why a run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// A stop condition held.
+    Condition,
+    /// The tick limit was reached.
+    MaxTicks,
+}
+
+/// The result of [`Simulation::run`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunSummary {
+    /// The number of ticks run.
+    pub ticks: u64,
+    pub stop_reason: StopReason,
+    /// The reward each agent collected during the run.
+    pub total_rewards: BTreeMap<String, f64>,
+}
+
+/// The state of a simulation written to disk.
+#[derive(Serialize, Deserialize)]
+struct Checkpoint {
+    format: String,
+    version: u64,
+    tick: u64,
+    max_ticks: u64,
+    environment: Environment,
+    undeliverable: Vec<Message>,
+    agents: Vec<AgentCheckpoint>,
+}
+
+/// An agent in a checkpoint: its agent file, its mailboxes and the state of its policy.
+#[derive(Serialize, Deserialize)]
+struct AgentCheckpoint {
+    agent: SavedAgent,
+    inbox: Vec<Message>,
+    outbox: Vec<Message>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    policy: Option<serde_json::Value>,
+}
+
+/// A simulation of agents acting in a shared environment.
+///
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use zana::agents::{Agent, Environment, Value};
+/// use zana::agents::actions::{Action, ActionOutcome};
+/// use zana::agents::policies::ActionChoice;
+/// use zana::agents::simulation::{Simulation, StopReason};
+///
+/// let mut farmer = Agent::new("farmer");
+/// farmer.add_action(Action::new("harvest", "", |_, _| Ok(ActionOutcome::new())));
+/// farmer.set_policy(|_: &Agent, _: &Environment| ActionChoice::act("harvest"));
+///
+/// let environment = Environment::new(HashMap::from([("crops".to_string(), Value::from(3))]));
+/// let mut simulation = Simulation::new(environment)
+///     .with_agent(farmer)
+///     .with_reward_rule(|environment, turn| {
+///         let crops = environment.number("crops").unwrap_or(0.0);
+///         if turn.choice.action() == Some("harvest") && crops > 0.0 {
+///             environment.update("crops", crops - 1.0);
+///             1.0
+///         } else {
+///             0.0
+///         }
+///     })
+///     .with_stop_condition(|environment, _| environment.number("crops") == Some(0.0));
+///
+/// let summary = simulation.run();
+/// assert_eq!(summary.stop_reason, StopReason::Condition);
+/// assert_eq!(summary.total_rewards["farmer"], 3.0);
+/// ```
+pub struct Simulation {
+    system: AgentSystem,
+    environment: Environment,
+    tick: u64,
+    max_ticks: u64,
+    reward_rules: Vec<RewardRule>,
+    dynamics: Vec<Dynamics>,
+    stop_conditions: Vec<StopCondition>,
+    hooks: Vec<TickHook>,
+    metrics: MetricsRegistry,
+    parallel: bool,
+    pool: Option<ThreadPool>,
+    conflict_resolution: ConflictResolution,
+}
+
+impl Simulation {
+    /// Creates a simulation of `environment` without agents, limited to 1,000 ticks per run.
+    pub fn new(environment: Environment) -> Self {
+        Self {
+            system: AgentSystem::new(),
+            environment,
+            tick: 0,
+            max_ticks: 1000,
+            reward_rules: Vec::new(),
+            dynamics: Vec::new(),
+            stop_conditions: Vec::new(),
+            hooks: Vec::new(),
+            metrics: MetricsRegistry::new(),
+            parallel: false,
+            pool: None,
+            conflict_resolution: ConflictResolution::default(),
+        }
+    }
+
+    /// Uses the agents of an existing system.
+    pub fn with_system(mut self, system: AgentSystem) -> Self {
+        self.system = system;
+        self
+    }
+
+    /// Adds an agent.
+    pub fn with_agent(mut self, agent: Agent) -> Self {
+        self.system.add_agent(agent);
+        self
+    }
+
+    /// Sets how many ticks [`Simulation::run`] runs at most.
+    pub fn with_max_ticks(mut self, max_ticks: u64) -> Self {
+        self.max_ticks = max_ticks;
+        self
+    }
+
+    /// Adds a transition rule, run after each agent's action. It may change the environment and
+    /// returns the reward for the turn; the rewards of all rules are added up.
+    pub fn with_reward_rule(mut self, rule: impl FnMut(&mut Environment, &Turn) -> f64 + 'static) -> Self {
+        self.reward_rules.push(Box::new(rule));
+        self
+    }
+
+    /// Adds environment dynamics, run once per tick after every agent has acted.
+    pub fn with_dynamics(mut self, dynamics: impl FnMut(&mut Environment, u64) + 'static) -> Self {
+        self.dynamics.push(Box::new(dynamics));
+        self
+    }
+
+    /// Adds a condition that ends the run when it holds after a tick. It also marks the end of
+    /// the episode for learning policies.
+    pub fn with_stop_condition(mut self, condition: impl Fn(&Environment, u64) -> bool + 'static) -> Self {
+        self.stop_conditions.push(Box::new(condition));
+        self
+    }
+
+    /// Makes [`Simulation::run`] use [`Simulation::step_parallel`], on a pool of `threads`
+    /// threads, or on the global rayon pool if `threads` is 0.
+    pub fn with_parallelism(mut self, threads: usize) -> Self {
+        self.parallel = true;
+        self.pool = match threads {
+            0 => None,
+            threads => rayon::ThreadPoolBuilder::new().num_threads(threads).build().ok(),
+        };
+        self
+    }
+
+    /// Sets how parallel ticks settle conflicting environment writes.
+    pub fn with_conflict_resolution(mut self, resolution: ConflictResolution) -> Self {
+        self.conflict_resolution = resolution;
+        self
+    }
+
+    /// Adds a hook called at the end of every tick.
+    pub fn on_tick(mut self, hook: impl FnMut(&TickReport) + 'static) -> Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    pub fn system(&self) -> &AgentSystem {
+        &self.system
+    }
+
+    pub fn system_mut(&mut self) -> &mut AgentSystem {
+        &mut self.system
+    }
+
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    pub fn environment_mut(&mut self) -> &mut Environment {
+        &mut self.environment
+    }
+
+    /// The metrics recorded since the simulation was created.
+    pub fn metrics(&self) -> &MetricsRegistry {
+        &self.metrics
+    }
+
+    pub fn metrics_mut(&mut self) -> &mut MetricsRegistry {
+        &mut self.metrics
+    }
+
+    /// The number of ticks run so far.
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Returns `true` if a stop condition holds.
+    pub fn should_stop(&self) -> bool {
+        self.stop_conditions.iter().any(|condition| condition(&self.environment, self.tick))
+    }
+
+    /// Runs one tick with every agent deciding through its policy.
+    pub fn step(&mut self) -> Vec<Turn> {
+        let names: Vec<String> = self.system.names().into_iter().map(str::to_string).collect();
+        let mut turns = Vec::with_capacity(names.len());
+        for name in names {
+            let Some(agent) = self.system.agent(&name) else {
+                continue;
+            };
+            let observation = agent.observe(&self.environment).into_owned();
+            let start = Instant::now();
+            let choice = agent.choose_action(&observation);
+            self.metrics.record_decision(&name, start.elapsed());
+            turns.push(self.take_turn(name, choice, observation));
+        }
+        self.finish_tick(&turns, &[]);
+        turns
+    }
+
+    /// Runs one tick with every agent deciding through its policy and acting at the same time.
+    ///
+    /// All agents observe the environment as it was at the start of the tick. Their state
+    /// changes are then merged, settling conflicting writes with the simulation's
+    /// [`ConflictResolution`], and the reward rules run for each turn in name order. Policies
+    /// learn from the environment at the end of the tick. With seeded policies and actions that
+    /// only change their own agent, a parallel run is as reproducible as a sequential one.
+    pub fn step_parallel(&mut self) -> Vec<Turn> {
+        let environment = &self.environment;
+        let mut agents: Vec<&mut Agent> = self.system.agents_mut().collect();
+        let act = |agent: &mut &mut Agent| {
+            let observation = agent.observe(environment).into_owned();
+            let start = Instant::now();
+            let choice = agent.choose_action(&observation);
+            let latency = start.elapsed();
+            let result = agent.perform(&choice);
+            (Turn { agent: agent.name.clone(), choice, result, reward: 0.0 }, observation, latency)
+        };
+        let acted: Vec<_> = match &self.pool {
+            Some(pool) => pool.install(|| agents.par_iter_mut().map(act).collect()),
+            None => agents.par_iter_mut().map(act).collect(),
+        };
+        self.system.deliver_messages();
+
+        let conflicts = self.merge_state_changes(acted.iter().map(|(turn, _, _)| turn));
+        let mut turns = Vec::with_capacity(acted.len());
+        let mut observations = Vec::with_capacity(acted.len());
+        for (mut turn, observation, latency) in acted {
+            let reward: f64 = self.reward_rules.iter_mut().map(|rule| rule(&mut self.environment, &turn)).sum();
+            self.environment.set_reward(reward);
+            turn.reward = self.environment.take_reward();
+            self.metrics.record_decision(&turn.agent, latency);
+            self.metrics.record_turn(&turn);
+            turns.push(turn);
+            observations.push(observation);
+        }
+
+        let done = self.stop_conditions.iter().any(|condition| condition(&self.environment, self.tick + 1));
+        let environment = &self.environment;
+        let learn = |((agent, turn), observation): ((&mut Agent, &Turn), &Environment)| {
+            let next = agent.observe(environment).into_owned();
+            let transition = Transition { previous: observation, choice: &turn.choice, reward: turn.reward, next: &next, done };
+            agent.learn(&transition);
+        };
+        let learning = self.system.agents_mut().zip(&turns).zip(&observations).par_bridge();
+        match &self.pool {
+            Some(pool) => pool.install(|| learning.for_each(learn)),
+            None => learning.for_each(learn),
+        }
+        self.finish_tick(&turns, &conflicts);
+        turns
+    }
+
+    /// Applies the state changes of a parallel tick's turns, and returns the conflicts.
+    fn merge_state_changes<'t>(&mut self, turns: impl Iterator<Item = &'t Turn>) -> Vec<Conflict> {
+        let mut writes: BTreeMap<&str, Vec<EnvironmentWrite>> = BTreeMap::new();
+        for turn in turns {
+            if let Ok(outcome) = &turn.result {
+                for (key, value) in &outcome.state_changes {
+                    writes.entry(key).or_default().push(EnvironmentWrite { agent: turn.agent.clone(), value: value.clone() });
+                }
+            }
+        }
+        let mut conflicts = Vec::new();
+        for (key, writes) in writes {
+            if writes.iter().all(|write| write.value == writes[0].value) {
+                self.environment.update(key, writes[0].value.clone());
+                continue;
+            }
+            let resolved = self.conflict_resolution.resolve(key, &writes);
+            if let Some(value) = &resolved {
+                self.environment.update(key, value.clone());
+            }
+            conflicts.push(Conflict { key: key.to_string(), writes, resolved });
+        }
+        conflicts
+    }
+
+    /// Runs one tick, letting agents without a policy decide through their model provider.
+    ///
+    /// Model answers that do not name one of the agent's actions are treated as idling.
+    pub async fn step_async(&mut self) -> Vec<Turn> {
+        let names: Vec<String> = self.system.names().into_iter().map(str::to_string).collect();
+        let mut turns = Vec::with_capacity(names.len());
+        for name in names {
+            let Some(agent) = self.system.agent_mut(&name) else {
+                continue;
+            };
+            let observation = agent.observe(&self.environment).into_owned();
+            let start = Instant::now();
+            let choice = if agent.policy.is_none() && agent.model_provider.is_some() {
+                match agent.decide(&observation).await {
+                    Some(choice) if choice.action().is_some_and(|action| agent.actions.contains_key(action)) => choice,
+                    _ => ActionChoice::Idle,
+                }
+            } else {
+                agent.choose_action(&observation)
+            };
+            self.metrics.record_decision(&name, start.elapsed());
+            turns.push(self.take_turn(name, choice, observation));
+        }
+        self.finish_tick(&turns, &[]);
+        turns
+    }
+
+    /// Runs ticks until a stop condition holds or the tick limit is reached, in parallel if the
+    /// simulation was given a [parallelism](Simulation::with_parallelism).
+    pub fn run(&mut self) -> RunSummary {
+        let mut total_rewards: BTreeMap<String, f64> =
+            self.system.names().into_iter().map(|name| (name.to_string(), 0.0)).collect();
+        for ticks in 1..=self.max_ticks {
+            let turns = if self.parallel { self.step_parallel() } else { self.step() };
+            for turn in turns {
+                *total_rewards.entry(turn.agent).or_default() += turn.reward;
+            }
+            if self.should_stop() {
+                return RunSummary { ticks, stop_reason: StopReason::Condition, total_rewards };
+            }
+        }
+        RunSummary { ticks: self.max_ticks, stop_reason: StopReason::MaxTicks, total_rewards }
+    }
+
+    /// Writes the tick counter, the environment and every agent's memory, mailboxes, action
+    /// names, model provider settings and policy state to a JSON file.
+    ///
+    /// Closures cannot be written to disk: actions are kept by name, and the reward rules,
+    /// dynamics, stop conditions, hooks, policies and perceptions stay with the simulation that
+    /// resumes. Saving the state of a policy that draws random numbers reseeds it, so a run
+    /// continued after a checkpoint and a run resumed from it make the same choices.
+    pub fn checkpoint(&self, file_path: &str) -> std::io::Result<()> {
+        let checkpoint = Checkpoint {
+            format: CHECKPOINT_FORMAT.to_string(),
+            version: CHECKPOINT_VERSION,
+            tick: self.tick,
+            max_ticks: self.max_ticks,
+            environment: self.environment.clone(),
+            undeliverable: self.system.undeliverable().to_vec(),
+            agents: self
+                .system
+                .agents()
+                .map(|agent| AgentCheckpoint {
+                    agent: agent.to_saved(),
+                    inbox: agent.inbox.clone(),
+                    outbox: agent.outbox.clone(),
+                    policy: agent.policy.as_ref().and_then(|policy| policy.save_state()),
+                })
+                .collect(),
+        };
+        fs::write(file_path, serde_json::to_string_pretty(&checkpoint)?)
+    }
+
+    /// Continues from a checkpoint written by [`Simulation::checkpoint`].
+    ///
+    /// The simulation should be set up as the checkpointed one was, with the same rules and
+    /// agents. Agents that have a checkpointed agent's name keep their actions, policy and
+    /// perception and take over its memory, mailboxes and policy state; checkpointed agents
+    /// the simulation lacks are restored as by [`Agent::load`], and agents the checkpoint lacks
+    /// are removed. Returns an `InvalidData` error if the file is not a supported checkpoint.
+    pub fn resume(&mut self, file_path: &str) -> std::io::Result<()> {
+        let checkpoint: Checkpoint = serde_json::from_str(&fs::read_to_string(file_path)?)
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid checkpoint format"))?;
+        if checkpoint.format != CHECKPOINT_FORMAT {
+            return Err(Error::new(ErrorKind::InvalidData, "Invalid checkpoint format"));
+        }
+        if checkpoint.version != CHECKPOINT_VERSION {
+            return Err(Error::new(ErrorKind::InvalidData, "Unsupported checkpoint version"));
+        }
+
+        let mut system = AgentSystem::new();
+        for saved in checkpoint.agents {
+            let restored = Agent::from_saved(saved.agent)?;
+            let mut agent = match self.system.remove_agent(&restored.name) {
+                Some(mut agent) => {
+                    agent.memory = restored.memory;
+                    agent
+                }
+                None => restored,
+            };
+            agent.inbox = saved.inbox;
+            agent.outbox = saved.outbox;
+            if let (Some(policy), Some(state)) = (&agent.policy, &saved.policy) {
+                policy.restore_state(state);
+            }
+            system.add_agent(agent);
+        }
+        *system.undeliverable_mut() = checkpoint.undeliverable;
+        self.system = system;
+        self.environment = checkpoint.environment;
+        self.tick = checkpoint.tick;
+        self.max_ticks = checkpoint.max_ticks;
+        Ok(())
+    }
+
+    fn take_turn(&mut self, name: String, choice: ActionChoice, observation: Environment) -> Turn {
+        let agent = self.system.agent_mut(&name).expect("agent names come from the system");
+        let result = agent.perform(&choice);
+        self.system.deliver_messages();
+        if let Ok(outcome) = &result {
+            self.environment.apply(outcome);
+        }
+
+        let mut turn = Turn { agent: name, choice, result, reward: 0.0 };
+        let reward: f64 = self.reward_rules.iter_mut().map(|rule| rule(&mut self.environment, &turn)).sum();
+        self.environment.set_reward(reward);
+        turn.reward = self.environment.take_reward();
+
+        let done = self.stop_conditions.iter().any(|condition| condition(&self.environment, self.tick + 1));
+        let agent = self.system.agent_mut(&turn.agent).expect("agent names come from the system");
+        let next = agent.observe(&self.environment).into_owned();
+        let transition = Transition {
+            previous: &observation,
+            choice: &turn.choice,
+            reward: turn.reward,
+            next: &next,
+            done,
+        };
+        agent.learn(&transition);
+        self.metrics.record_turn(&turn);
+        turn
+    }
+
+    fn finish_tick(&mut self, turns: &[Turn], conflicts: &[Conflict]) {
+        self.tick += 1;
+        for agent in self.system.agents() {
+            if let Some(provider) = &agent.model_provider {
+                self.metrics.record_token_usage(&agent.name, provider.token_usage());
+            }
+        }
+        for dynamics in &mut self.dynamics {
+            dynamics(&mut self.environment, self.tick);
+        }
+        let report = TickReport { tick: self.tick, turns, environment: &self.environment, conflicts };
+        for hook in &mut self.hooks {
+            hook(&report);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+    use crate::agents::actions::{Action, ActionOutcome};
+    use crate::agents::q_learning::QLearningPolicy;
+    use crate::agents::value::Value;
+
+    fn walker(seed: u64) -> Agent {
+        let mut agent = Agent::new("walker");
+        agent.add_action(Action::new("left", "", |_, _| Ok(ActionOutcome::new())));
+        agent.add_action(Action::new("right", "", |_, _| Ok(ActionOutcome::new())));
+        let policy = QLearningPolicy::new().with_learning_rate(0.5).with_exploration(1.0, 0.9, 0.0);
+        agent.set_policy(policy.with_seed(seed));
+        agent
+    }
+
+    fn corridor(agent: Agent) -> Simulation {
+        Simulation::new(Environment::new(HashMap::from([("position".to_string(), Value::from(0))])))
+            .with_agent(agent)
+            .with_reward_rule(|environment, turn| {
+                let position = environment.number("position").unwrap();
+                let position = match turn.choice.action() {
+                    Some("right") => position + 1.0,
+                    _ => (position - 1.0).max(0.0),
+                };
+                environment.update("position", position);
+                if position == 3.0 { 1.0 } else { -0.1 }
+            })
+            .with_stop_condition(|environment, _| environment.number("position") == Some(3.0))
+            .with_max_ticks(100)
+    }
+
+    #[test]
+    fn test_run_learns_across_episodes() {
+        let mut agent = walker(5);
+        let mut lengths = Vec::new();
+        for _ in 0..60 {
+            let mut simulation = corridor(agent);
+            let summary = simulation.run();
+            assert_eq!(summary.stop_reason, StopReason::Condition);
+            lengths.push(summary.ticks);
+            agent = simulation.system_mut().remove_agent("walker").unwrap();
+        }
+        assert_eq!(lengths.last(), Some(&3));
+    }
+
+    #[test]
+    fn test_runs_are_reproducible() {
+        let run = |seed| {
+            let ticks = Rc::new(RefCell::new(Vec::new()));
+            let recorded = Rc::clone(&ticks);
+            let mut simulation = corridor(walker(seed)).on_tick(move |report| {
+                let turn = &report.turns[0];
+                recorded.borrow_mut().push((report.tick, turn.choice.clone(), turn.reward));
+            });
+            simulation.run();
+            ticks.take()
+        };
+        assert_eq!(run(9), run(9));
+    }
+
+    #[test]
+    fn test_checkpoint_and_resume() {
+        let record = |simulation: Simulation| {
+            let ticks = Rc::new(RefCell::new(Vec::new()));
+            let recorded = Rc::clone(&ticks);
+            let mut simulation = simulation.on_tick(move |report| {
+                let turn = &report.turns[0];
+                recorded.borrow_mut().push((report.tick, turn.choice.clone(), report.environment.clone()));
+            });
+            simulation.run();
+            ticks.take()
+        };
+
+        let file_path = "test_checkpoint.json";
+        let mut original = corridor(walker(3));
+        for _ in 0..4 {
+            original.step();
+        }
+        original.system_mut().agent_mut("walker").unwrap().outbox.push(Message::new("walker", None, "hi"));
+        original.checkpoint(file_path).unwrap();
+        let memory = original.system().agent("walker").unwrap().memory.clone();
+        let continued = record(original);
+
+        let mut resumed = corridor(walker(40)).with_agent(Agent::new("stranger"));
+        resumed.resume(file_path).unwrap();
+        fs::remove_file(file_path).unwrap();
+        assert_eq!(resumed.tick(), 4);
+        assert_eq!(resumed.system().names(), vec!["walker"]);
+        assert_eq!(resumed.system().agent("walker").unwrap().memory, memory);
+        assert_eq!(resumed.system().agent("walker").unwrap().outbox[0].content, "hi");
+        assert_eq!(record(resumed), continued);
+
+        let mut simulation = corridor(walker(3));
+        Agent::new("walker").save(file_path).unwrap();
+        assert_eq!(simulation.resume(file_path).unwrap_err().kind(), ErrorKind::InvalidData);
+        fs::remove_file(file_path).unwrap();
+    }
+
+    #[test]
+    fn test_step_parallel_resolves_conflicts() {
+        let claimer = |name: &str| {
+            let mut agent = Agent::new(name);
+            let owner = name.to_string();
+            agent.add_action(Action::new("claim", "", move |_, _| {
+                Ok(ActionOutcome::new().with_state_change("flag", owner.as_str()).with_state_change("seen", true))
+            }));
+            agent.set_policy(|_: &Agent, _: &Environment| ActionChoice::act("claim"));
+            agent
+        };
+        let simulation = |resolution: ConflictResolution| {
+            let conflicts = Rc::new(RefCell::new(Vec::new()));
+            let recorded = Rc::clone(&conflicts);
+            let mut simulation = Simulation::new(Environment::new(HashMap::new()))
+                .with_agent(claimer("ann"))
+                .with_agent(claimer("bob"))
+                .with_agent(claimer("cid"))
+                .with_parallelism(2)
+                .with_conflict_resolution(resolution)
+                .with_reward_rule(|_, turn| if turn.result.is_ok() { 1.0 } else { 0.0 })
+                .with_max_ticks(1)
+                .on_tick(move |report| recorded.borrow_mut().extend_from_slice(report.conflicts));
+            let summary = simulation.run();
+            assert_eq!(summary.total_rewards.values().sum::<f64>(), 3.0);
+            (simulation.environment().clone(), conflicts.take())
+        };
+
+        let (environment, conflicts) = simulation(ConflictResolution::LastWriterWins);
+        assert_eq!(environment.get("flag"), Some(&Value::from("cid")));
+        assert_eq!(environment.get("seen"), Some(&Value::from(true)));
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].writes.iter().map(|write| write.agent.as_str()).collect::<Vec<_>>(), ["ann", "bob", "cid"]);
+        let (environment, _) = simulation(ConflictResolution::FirstWriterWins);
+        assert_eq!(environment.get("flag"), Some(&Value::from("ann")));
+        let (environment, conflicts) = simulation(ConflictResolution::Discard);
+        assert_eq!(environment.get("flag"), None);
+        assert_eq!(conflicts[0].resolved, None);
+        let (environment, _) = simulation(ConflictResolution::custom(|_, writes| Some(Value::from(writes.len() as u64))));
+        assert_eq!(environment.number("flag"), Some(3.0));
+    }
+
+    #[test]
+    fn test_dynamics_and_tick_limit() {
+        let mut simulation = Simulation::new(Environment::new(HashMap::new()))
+            .with_agent(Agent::new("idler"))
+            .with_dynamics(|environment, tick| environment.update("clock", tick))
+            .with_max_ticks(5);
+        let summary = simulation.run();
+        assert_eq!(summary, RunSummary {
+            ticks: 5,
+            stop_reason: StopReason::MaxTicks,
+            total_rewards: BTreeMap::from([("idler".to_string(), 0.0)]),
+        });
+        assert_eq!(simulation.environment().number("clock"), Some(5.0));
+        assert_eq!(simulation.tick(), 5);
+        let metrics = simulation.metrics().agent("idler").unwrap();
+        assert_eq!((metrics.idle_turns, metrics.decisions), (5, 5));
+    }
+}
@@ -0,0 +1,216 @@
+//! Skills
+//!
+//! A [`Skill`] is a named bundle of actions, such as everything an agent needs to communicate.
+//! Crates and applications register their skills in a [`SkillRegistry`] at runtime, and agents
+//! get actions by being granted skills from it. A skill that was denied to an agent cannot be
+//! granted to it until the denial is lifted.
+//!
+//! [`SkillRegistry::predefined`] holds the actions of
+//! [`predefined_actions`](super::predefined_actions), grouped into the `knowledge`,
+//! `communication`, `cryptography` and `resources` skills.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use super::actions::Action;
+use super::agent::Agent;
+use super::predefined_actions::*;
+
+/// An error raised while granting a skill.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkillError {
+    /// No skill with this name is registered.
+    NotFound(String),
+    /// The skill was denied to the agent, with the names of the agent and the skill.
+    Denied { agent: String, skill: String },
+}
+
+impl fmt::Display for SkillError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkillError::NotFound(skill) => write!(f, "skill '{}' not found", skill),
+            SkillError::Denied { agent, skill } => write!(f, "skill '{}' is denied to agent '{}'", skill, agent),
+        }
+    }
+}
+
+impl std::error::Error for SkillError {}
+
+/// A named bundle of actions.
+#[derive(Clone)]
+pub struct Skill {
+    pub name: String,
+    pub description: String,
+    pub actions: Vec<Action>,
+}
+
+impl Skill {
+    pub fn new(name: &str, description: &str) -> Self {
+        Self { name: name.to_string(), description: description.to_string(), actions: Vec::new() }
+    }
+
+    /// Adds an action to the skill, replacing one with the same name.
+    pub fn with_action(mut self, action: Action) -> Self {
+        self.actions.retain(|existing| existing.name != action.name);
+        self.actions.push(action);
+        self
+    }
+
+    /// The names of the skill's actions, in the order they were added.
+    pub fn action_names(&self) -> Vec<&str> {
+        self.actions.iter().map(|action| action.name.as_str()).collect()
+    }
+}
+
+/// The skills agents can be granted, by name.
+#[derive(Clone, Default)]
+pub struct SkillRegistry {
+    skills: BTreeMap<String, Skill>,
+}
+
+impl SkillRegistry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry with the predefined actions, grouped into skills.
+    pub fn predefined() -> Self {
+        let mut registry = Self::new();
+        registry.register(
+            Skill::new("knowledge", "Learn, forget and train.")
+                .with_action(learn_action())
+                .with_action(forget_action())
+                .with_action(train_skill_action()),
+        );
+        registry.register(
+            Skill::new("communication", "Message and work with other agents.")
+                .with_action(send_message_action())
+                .with_action(collaborate_action()),
+        );
+        registry.register(Skill::new("cryptography", "Perform cryptographic operations.").with_action(cryptography_action()));
+        registry.register(
+            Skill::new("resources", "Gather resources and analyze the environment.")
+                .with_action(gather_resources_action())
+                .with_action(analyze_environment_action()),
+        );
+        registry
+    }
+
+    /// Registers a skill, replacing one with the same name.
+    ///
+    /// # Returns
+    /// The skill that was replaced, if any.
+    pub fn register(&mut self, skill: Skill) -> Option<Skill> {
+        self.skills.insert(skill.name.clone(), skill)
+    }
+
+    /// Removes a skill. Agents keep the actions they were already granted.
+    pub fn unregister(&mut self, name: &str) -> Option<Skill> {
+        self.skills.remove(name)
+    }
+
+    pub fn skill(&self, name: &str) -> Option<&Skill> {
+        self.skills.get(name)
+    }
+
+    /// Every registered skill, in name order.
+    pub fn skills(&self) -> impl Iterator<Item = &Skill> {
+        self.skills.values()
+    }
+
+    /// Looks up an action by name in every registered skill.
+    pub fn action(&self, name: &str) -> Option<&Action> {
+        self.skills.values().flat_map(|skill| &skill.actions).find(|action| action.name == name)
+    }
+
+    /// Gives the agent every action of a skill and records the grant.
+    pub fn grant(&self, agent: &mut Agent, name: &str) -> Result<(), SkillError> {
+        if agent.denied_skills.contains(name) {
+            return Err(SkillError::Denied { agent: agent.name.clone(), skill: name.to_string() });
+        }
+        let skill = self.skills.get(name).ok_or_else(|| SkillError::NotFound(name.to_string()))?;
+        for action in &skill.actions {
+            agent.add_action(action.clone());
+        }
+        agent.skills.insert(name.to_string());
+        Ok(())
+    }
+
+    /// Denies a skill to the agent, taking away its actions if the skill was granted.
+    pub fn deny(&self, agent: &mut Agent, name: &str) {
+        if agent.skills.remove(name) {
+            self.revoke_actions(agent, name);
+        }
+        agent.denied_skills.insert(name.to_string());
+    }
+
+    /// Takes a granted skill and its actions away from the agent, without denying it.
+    pub fn revoke(&self, agent: &mut Agent, name: &str) {
+        if agent.skills.remove(name) {
+            self.revoke_actions(agent, name);
+        }
+    }
+
+    /// Lifts the denial of a skill, so that it can be granted again.
+    pub fn allow(&self, agent: &mut Agent, name: &str) {
+        agent.denied_skills.remove(name);
+    }
+
+    /// Removes the actions of a skill that no other granted skill provides.
+    fn revoke_actions(&self, agent: &mut Agent, name: &str) {
+        let Some(skill) = self.skills.get(name) else { return };
+        for action in &skill.actions {
+            let still_granted = agent
+                .skills
+                .iter()
+                .filter_map(|granted| self.skills.get(granted))
+                .any(|granted| granted.actions.iter().any(|other| other.name == action.name));
+            if !still_granted {
+                agent.actions.remove(&action.name);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::actions::{ActionOutcome, ActionParams, ParamKind};
+
+    #[test]
+    fn test_grant_and_deny() {
+        let registry = SkillRegistry::predefined();
+        let mut agent = Agent::new("courier");
+        registry.grant(&mut agent, "communication").unwrap();
+        assert_eq!(agent.action_names(), ["collaborate", "send_message"]);
+        assert_eq!(registry.grant(&mut agent, "flying"), Err(SkillError::NotFound("flying".to_string())));
+
+        registry.deny(&mut agent, "communication");
+        assert!(agent.actions.is_empty());
+        assert!(agent.skills.is_empty());
+        assert_eq!(
+            registry.grant(&mut agent, "communication"),
+            Err(SkillError::Denied { agent: "courier".to_string(), skill: "communication".to_string() })
+        );
+        registry.allow(&mut agent, "communication");
+        assert!(registry.grant(&mut agent, "communication").is_ok());
+    }
+
+    #[test]
+    fn test_register_runtime_skill() {
+        let mut registry = SkillRegistry::predefined();
+        let harvest = Action::new("harvest", "Harvest a field.", |_, params| {
+            Ok(ActionOutcome::new().with_output("acres", &params["acres"]))
+        })
+        .with_param("acres", ParamKind::Integer, "The acres to harvest.");
+        assert!(registry.register(Skill::new("farming", "Work the land.").with_action(harvest)).is_none());
+        assert_eq!(registry.action("harvest").unwrap().params[0].kind, ParamKind::Integer);
+
+        let mut agent = Agent::new("farmer");
+        registry.grant(&mut agent, "farming").unwrap();
+        let params = ActionParams::from([("acres".to_string(), "3".to_string())]);
+        assert_eq!(agent.execute_action("harvest", params).unwrap().outputs["acres"], "3");
+        let params = ActionParams::from([("acres".to_string(), "many".to_string())]);
+        assert!(agent.execute_action("harvest", params).is_err());
+    }
+}
@@ -0,0 +1,258 @@
+//! Multi-Agent Runtime
+//!
+//! An [`AgentSystem`] owns a group of agents and the message bus between them. Actions cannot
+//! reach other agents directly, so an agent sends a [`Message`] by putting it in its outbox (the
+//! predefined `send_message` action does this); the system delivers outboxes after every action
+//! it runs. Delivered messages land in the recipient's inbox and short-term memory, where its
+//! policy or model can react to them.
+
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use super::actions::{ActionError, ActionOutcome, ActionParams};
+use super::agent::Agent;
+use super::environment::Environment;
+use super::policies::ActionChoice;
+
+/// A message between agents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Message {
+    /// The name of the sending agent.
+    pub from: String,
+    /// The name of the receiving agent, or `None` to broadcast to every other agent.
+    pub to: Option<String>,
+    pub channel: String,
+    pub tone: String,
+    pub content: String,
+}
+
+impl Message {
+    /// A neutral message on the `general` channel.
+    pub fn new(from: &str, to: Option<&str>, content: &str) -> Self {
+        Self {
+            from: from.to_string(),
+            to: to.map(str::to_string),
+            channel: "general".to_string(),
+            tone: "neutral".to_string(),
+            content: content.to_string(),
+        }
+    }
+}
+
+/// The result of one agent's turn in [`AgentSystem::step`].
+#[derive(Debug)]
+pub struct Turn {
+    pub agent: String,
+    pub choice: ActionChoice,
+    pub result: Result<ActionOutcome, ActionError>,
+    pub reward: f64, // Reward the environment gave, zero outside a simulation
+}
+
+/// A group of named agents that exchange messages.
+///
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use zana::agents::Agent;
+/// use zana::agents::predefined_actions::send_message_action;
+/// use zana::agents::system::AgentSystem;
+///
+/// let mut alice = Agent::new("alice");
+/// alice.add_action(send_message_action());
+/// let mut system = AgentSystem::new();
+/// system.add_agent(alice);
+/// system.add_agent(Agent::new("bob"));
+///
+/// let params = HashMap::from([
+///     ("to".to_string(), "bob".to_string()),
+///     ("message".to_string(), "split the work?".to_string()),
+/// ]);
+/// system.execute_action("alice", "send_message", params).unwrap();
+/// assert_eq!(system.agent_mut("bob").unwrap().take_messages()[0].content, "split the work?");
+/// ```
+#[derive(Default)]
+pub struct AgentSystem {
+    agents: BTreeMap<String, Agent>,
+    undeliverable: Vec<Message>,
+}
+
+impl AgentSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an agent, replacing any agent with the same name.
+    pub fn add_agent(&mut self, agent: Agent) {
+        self.agents.insert(agent.name.clone(), agent);
+    }
+
+    /// Removes an agent and returns it.
+    pub fn remove_agent(&mut self, name: &str) -> Option<Agent> {
+        self.agents.remove(name)
+    }
+
+    pub fn agent(&self, name: &str) -> Option<&Agent> {
+        self.agents.get(name)
+    }
+
+    pub fn agent_mut(&mut self, name: &str) -> Option<&mut Agent> {
+        self.agents.get_mut(name)
+    }
+
+    /// The names of the agents, in sorted order.
+    pub fn names(&self) -> Vec<&str> {
+        self.agents.keys().map(String::as_str).collect()
+    }
+
+    /// The agents, in name order.
+    pub fn agents(&self) -> impl Iterator<Item = &Agent> {
+        self.agents.values()
+    }
+
+    /// The agents, in name order, for modification.
+    pub fn agents_mut(&mut self) -> impl Iterator<Item = &mut Agent> {
+        self.agents.values_mut()
+    }
+
+    /// Messages addressed to agents that are not in the system.
+    pub fn undeliverable(&self) -> &[Message] {
+        &self.undeliverable
+    }
+
+    pub(super) fn undeliverable_mut(&mut self) -> &mut Vec<Message> {
+        &mut self.undeliverable
+    }
+
+    /// Puts a message on the bus, as if the named sender had sent it, and delivers it.
+    pub fn send(&mut self, message: Message) {
+        self.deliver(message);
+    }
+
+    /// Executes an action of the named agent, then delivers the messages it sent.
+    pub fn execute_action(
+        &mut self,
+        agent: &str,
+        action: &str,
+        params: ActionParams,
+    ) -> Result<ActionOutcome, ActionError> {
+        let result = self
+            .agents
+            .get_mut(agent)
+            .ok_or_else(|| ActionError::Failed(format!("no agent named '{}'", agent)))?
+            .execute_action(action, params);
+        self.deliver_messages();
+        result
+    }
+
+    /// Lets every agent act once with its policy, in name order.
+    ///
+    /// Messages are delivered after each agent's turn, so agents later in the order see them in
+    /// the same step.
+    pub fn step(&mut self, environment: &Environment) -> Vec<Turn> {
+        let names: Vec<String> = self.agents.keys().cloned().collect();
+        let mut turns = Vec::with_capacity(names.len());
+        for name in names {
+            let Some(agent) = self.agents.get_mut(&name) else {
+                continue;
+            };
+            let (choice, result) = agent.act(environment);
+            self.deliver_messages();
+            turns.push(Turn { agent: name, choice, result, reward: 0.0 });
+        }
+        turns
+    }
+
+    /// Delivers every message waiting in an outbox.
+    ///
+    /// # Returns
+    /// The number of messages taken from outboxes.
+    pub fn deliver_messages(&mut self) -> usize {
+        let outgoing: Vec<Message> =
+            self.agents.values_mut().flat_map(|agent| std::mem::take(&mut agent.outbox)).collect();
+        let count = outgoing.len();
+        for message in outgoing {
+            self.deliver(message);
+        }
+        count
+    }
+
+    fn deliver(&mut self, message: Message) {
+        match &message.to {
+            Some(recipient) => match self.agents.get_mut(recipient) {
+                Some(agent) => agent.receive(message),
+                None => self.undeliverable.push(message),
+            },
+            None => {
+                for agent in self.agents.values_mut().filter(|agent| agent.name != message.from) {
+                    agent.receive(message.clone());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::predefined_actions::send_message_action;
+
+    fn params(entries: &[(&str, &str)]) -> ActionParams {
+        entries.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_direct_and_broadcast_messages() {
+        let mut system = AgentSystem::new();
+        for name in ["alice", "bob", "carol"] {
+            let mut agent = Agent::new(name);
+            agent.add_action(send_message_action());
+            system.add_agent(agent);
+        }
+
+        system.execute_action("alice", "send_message", params(&[("to", "bob"), ("message", "offer: 10")])).unwrap();
+        let bob = system.agent_mut("bob").unwrap();
+        assert_eq!(bob.memory.short_term.get("message_from.alice"), Some("offer: 10"));
+        assert_eq!(bob.take_messages(), vec![Message::new("alice", Some("bob"), "offer: 10")]);
+        assert!(system.agent_mut("carol").unwrap().take_messages().is_empty());
+
+        system.execute_action("bob", "send_message", params(&[("message", "deal")])).unwrap();
+        assert_eq!(system.agent_mut("alice").unwrap().take_messages()[0].content, "deal");
+        assert_eq!(system.agent_mut("carol").unwrap().take_messages()[0].content, "deal");
+        assert!(system.agent_mut("bob").unwrap().take_messages().is_empty());
+
+        system.execute_action("carol", "send_message", params(&[("to", "dave")])).unwrap();
+        assert_eq!(system.undeliverable()[0].to.as_deref(), Some("dave"));
+        assert!(system.execute_action("dave", "send_message", ActionParams::new()).is_err());
+    }
+
+    #[test]
+    fn test_step_lets_agents_negotiate() {
+        let mut system = AgentSystem::new();
+        let mut buyer = Agent::new("buyer");
+        buyer.add_action(send_message_action());
+        buyer.set_policy(|agent: &Agent, _: &Environment| {
+            if agent.memory.short_term.get("message_from.seller") == Some("accept") {
+                ActionChoice::Idle
+            } else {
+                ActionChoice::act("send_message").with_param("to", "seller").with_param("message", "bid 5")
+            }
+        });
+        let mut seller = Agent::new("seller");
+        seller.add_action(send_message_action());
+        seller.set_policy(|agent: &Agent, _: &Environment| {
+            match agent.memory.short_term.get("message_from.buyer") {
+                Some("bid 5") => {
+                    ActionChoice::act("send_message").with_param("to", "buyer").with_param("message", "accept")
+                }
+                _ => ActionChoice::Idle,
+            }
+        });
+        system.add_agent(buyer);
+        system.add_agent(seller);
+
+        let environment = Environment::new(Default::default());
+        let first = system.step(&environment);
+        assert_eq!(first.iter().map(|turn| turn.choice.action()).collect::<Vec<_>>(), [Some("send_message"); 2]);
+        let second = system.step(&environment);
+        assert!(second[0].choice.is_idle());
+    }
+}
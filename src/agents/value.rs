@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+/// A typed environment value.
+///
+/// Values serialize to plain JSON (`3`, `true`, `"text"`, `[..]`, `{..}`), so environments and
+/// behavior trees stay readable when written as JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Value {
+    Number(f64),
+    Bool(bool),
+    Text(String),
+    List(Vec<Value>),
+    Map(BTreeMap<String, Value>),
+}
+
+impl Value {
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Value::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(flag) => Some(*flag),
+            _ => None,
+        }
+    }
+
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Value::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Value]> {
+        match self {
+            Value::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_map(&self) -> Option<&BTreeMap<String, Value>> {
+        match self {
+            Value::Map(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    /// The value as compact JSON, which tells apart values that display the same, such as the
+    /// number `3` and the text `"3"`.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("values always serialize")
+    }
+}
+
+/// Displays numbers, booleans and text without quotes, and lists and maps as JSON.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number(number) => write!(f, "{}", number),
+            Value::Bool(flag) => write!(f, "{}", flag),
+            Value::Text(text) => f.write_str(text),
+            Value::List(_) | Value::Map(_) => f.write_str(&self.to_json()),
+        }
+    }
+}
+
+impl From<f64> for Value {
+    fn from(number: f64) -> Self {
+        Value::Number(number)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(number: i64) -> Self {
+        Value::Number(number as f64)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(number: i32) -> Self {
+        Value::Number(number.into())
+    }
+}
+
+impl From<u64> for Value {
+    fn from(number: u64) -> Self {
+        Value::Number(number as f64)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(flag: bool) -> Self {
+        Value::Bool(flag)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(text: &str) -> Self {
+        Value::Text(text.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(text: String) -> Self {
+        Value::Text(text)
+    }
+}
+
+impl From<&String> for Value {
+    fn from(text: &String) -> Self {
+        Value::Text(text.clone())
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(items: Vec<T>) -> Self {
+        Value::List(items.into_iter().map(Into::into).collect())
+    }
+}
+
+impl From<BTreeMap<String, Value>> for Value {
+    fn from(entries: BTreeMap<String, Value>) -> Self {
+        Value::Map(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_getters_and_display() {
+        assert_eq!(Value::from(3).as_number(), Some(3.0));
+        assert_eq!(Value::from("3").as_number(), None);
+        assert_eq!(Value::from(true).as_bool(), Some(true));
+        assert_eq!(Value::from("wood").as_text(), Some("wood"));
+        assert_eq!(Value::from(vec![1, 2]).as_list().map(<[Value]>::len), Some(2));
+
+        assert_eq!(Value::from(2.5).to_string(), "2.5");
+        assert_eq!(Value::from(3).to_string(), Value::from("3").to_string());
+        assert_ne!(Value::from(3).to_json(), Value::from("3").to_json());
+        assert_eq!(Value::from(vec!["a", "b"]).to_string(), r#"["a","b"]"#);
+    }
+
+    #[test]
+    fn test_json() {
+        let map = Value::Map(BTreeMap::from([
+            ("hp".to_string(), Value::from(10)),
+            ("alive".to_string(), Value::from(true)),
+            ("name".to_string(), Value::from("orc")),
+            ("loot".to_string(), Value::from(vec!["axe"])),
+        ]));
+        let json = map.to_json();
+        assert_eq!(json, r#"{"alive":true,"hp":10.0,"loot":["axe"],"name":"orc"}"#);
+        assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), map);
+        assert_eq!(serde_json::from_str::<Value>("7").unwrap(), Value::Number(7.0));
+    }
+}
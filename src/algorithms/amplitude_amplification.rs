@@ -0,0 +1,163 @@
+//! Amplitude Amplification
+//!
+//! Generalizes Grover's search to an arbitrary state-preparation circuit `A`. If `A|0⟩` has
+//! success probability `a = sin²θ` on the marked states, each application of
+//! `Q = A (2|0⟩⟨0| − I) A† S_χ` rotates the state by `2θ` towards the marked subspace.
+
+use std::f64::consts::PI;
+use num_complex::Complex;
+use crate::circuit::statevector::Statevector;
+use crate::circuit::{gates, QuantumCircuit};
+
+/// The outcome of an amplitude amplification run.
+#[derive(Debug, Clone)]
+pub struct AmplificationResult {
+    /// The final state after all amplification rounds.
+    pub statevector: Statevector,
+    /// The total probability of measuring a marked state.
+    pub success_probability: f64,
+}
+
+/// Returns the number of rounds maximizing the success probability.
+///
+/// # Arguments
+/// - `initial_success_probability`: The probability `a` of a marked state in `A|0⟩`.
+///
+/// # Returns
+/// `⌊π / (4θ)⌋` with `sin²θ = a`, or `0` if `a` is `0` or `1`.
+pub fn optimal_iterations(initial_success_probability: f64) -> usize {
+    if initial_success_probability <= 0.0 || initial_success_probability >= 1.0 {
+        return 0;
+    }
+    let theta = initial_success_probability.sqrt().asin();
+    (PI / (4.0 * theta)).floor() as usize
+}
+
+/// Builds the amplitude amplification circuit.
+///
+/// The circuit applies `state_prep` once and then `iterations` rounds of the phase oracle
+/// followed by the reflection `A (2|0⟩⟨0| − I) A†`, where `A†` is `state_prep.inverse()`.
+///
+/// # Arguments
+/// - `state_prep`: The state-preparation circuit `A`.
+/// - `oracle`: Returns `true` for every marked basis state.
+/// - `iterations`: The number of amplification rounds.
+///
+/// # Panics
+/// - If `state_prep` has measurements, which `A†` cannot undo.
+pub fn amplitude_amplification_circuit<F: Fn(usize) -> bool>(
+    state_prep: &QuantumCircuit,
+    oracle: F,
+    iterations: usize,
+) -> QuantumCircuit {
+    assert!(
+        state_prep.measurements.is_empty(),
+        "Amplitude amplification requires a state preparation without measurements."
+    );
+    let n_qubits = state_prep.qubits;
+    let all_qubits: Vec<usize> = (0..n_qubits).collect();
+    let unprepare = state_prep.inverse();
+
+    let oracle_gate = gates::phase_oracle(n_qubits, oracle);
+    let reflection = gates::diagonal(
+        (0..1usize << n_qubits)
+            .map(|x| if x == 0 { Complex::new(1.0, 0.0) } else { Complex::new(-1.0, 0.0) })
+            .collect(),
+    );
+
+    let mut circuit = QuantumCircuit::new(n_qubits);
+    circuit.append(state_prep, &all_qubits);
+    for _ in 0..iterations {
+        circuit.add_gate(oracle_gate.clone(), all_qubits.clone());
+        circuit.append(&unprepare, &all_qubits);
+        circuit.add_gate(reflection.clone(), all_qubits.clone());
+        circuit.append(state_prep, &all_qubits);
+    }
+
+    circuit
+}
+
+/// Runs amplitude amplification and reports the resulting success probability.
+///
+/// # Example
+/// ```
+/// use zana::algorithms::amplitude_amplification;
+/// use zana::circuit::{gates, QuantumCircuit};
+///
+/// // Ry(π/3)|0⟩ has success probability 1/4 on |1⟩; one round amplifies it to 1
+/// let mut state_prep = QuantumCircuit::new(1);
+/// state_prep.add_gate(gates::rotation_y(std::f64::consts::PI / 3.0), vec![0]);
+///
+/// let result = amplitude_amplification(&state_prep, |x| x == 1, 1);
+/// assert!((result.success_probability - 1.0).abs() < 1e-9);
+/// ```
+pub fn amplitude_amplification<F: Fn(usize) -> bool>(
+    state_prep: &QuantumCircuit,
+    oracle: F,
+    iterations: usize,
+) -> AmplificationResult {
+    let statevector = amplitude_amplification_circuit(state_prep, &oracle, iterations).simulate().statevector;
+    let success_probability = statevector
+        .probabilities()
+        .into_iter()
+        .filter(|&(state, _)| oracle(state))
+        .map(|(_, prob)| prob)
+        .sum();
+
+    AmplificationResult {
+        statevector,
+        success_probability,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_optimal_iterations() {
+        assert_eq!(optimal_iterations(0.25), 1);
+        assert_eq!(optimal_iterations(1.0 / 64.0), 6);
+        assert_eq!(optimal_iterations(0.0), 0);
+    }
+
+    #[test]
+    fn test_single_qubit_amplification_is_exact() {
+        let mut state_prep = QuantumCircuit::new(1);
+        state_prep.add_gate(gates::rotation_y(PI / 3.0), vec![0]);
+
+        let before = amplitude_amplification(&state_prep, |x| x == 1, 0);
+        assert!((before.success_probability - 0.25).abs() < 1e-9);
+
+        let after = amplitude_amplification(&state_prep, |x| x == 1, 1);
+        assert!((after.success_probability - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_non_uniform_state_preparation() {
+        let mut state_prep = QuantumCircuit::new(3);
+        state_prep.add_gate(gates::rotation_y(0.9), vec![0]);
+        state_prep.add_gate(gates::rotation_y(1.7), vec![1]);
+        state_prep.add_gate(gates::hadamard(), vec![2]);
+
+        let oracle = |x: usize| x == 0b111;
+        let initial = amplitude_amplification(&state_prep, oracle, 0).success_probability;
+        let iterations = optimal_iterations(initial);
+        let amplified = amplitude_amplification(&state_prep, oracle, iterations).success_probability;
+
+        // sin²((2k + 1)θ) with sin²θ = a
+        let theta = initial.sqrt().asin();
+        let expected = ((2 * iterations + 1) as f64 * theta).sin().powi(2);
+        assert!((amplified - expected).abs() < 1e-9);
+        assert!(amplified > initial);
+    }
+
+    #[test]
+    #[should_panic(expected = "Amplitude amplification requires a state preparation without measurements.")]
+    fn test_rejects_measured_state_preparation() {
+        let mut state_prep = QuantumCircuit::new(1);
+        state_prep.add_gate(gates::hadamard(), vec![0]);
+        state_prep.add_measurement(0, 0);
+        amplitude_amplification_circuit(&state_prep, |x| x == 1, 1);
+    }
+}
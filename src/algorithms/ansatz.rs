@@ -0,0 +1,474 @@
+//! Variational Ansätze
+//!
+//! An ansatz is a parameterized circuit template: binding a parameter vector produces a concrete
+//! `QuantumCircuit` whose final state is used by variational algorithms such as VQE.
+//!
+//! Besides the [`Ansatz`] trait, this module provides reusable templates parameterized by qubit
+//! count and depth: [`HardwareEfficientAnsatz`], [`RealAmplitudes`], and the excitation-based
+//! [`UccAnsatz`].
+
+use std::f64::consts::FRAC_PI_2;
+use crate::algorithms::add_cnot;
+use crate::circuit::observable::{Pauli, PauliString};
+use crate::circuit::{gates, QuantumCircuit};
+
+/// A parameterized circuit template.
+pub trait Ansatz {
+    /// The number of qubits of the circuits produced by this ansatz.
+    fn num_qubits(&self) -> usize;
+
+    /// The number of free parameters the ansatz expects.
+    fn num_parameters(&self) -> usize;
+
+    /// Binds `parameters` and returns the resulting circuit.
+    ///
+    /// # Panics
+    /// - If `parameters.len()` differs from [`Ansatz::num_parameters`].
+    fn build(&self, parameters: &[f64]) -> QuantumCircuit;
+}
+
+/// An ansatz defined by a user-supplied circuit builder.
+pub struct CustomAnsatz<F: Fn(&[f64]) -> QuantumCircuit> {
+    num_qubits: usize,
+    num_parameters: usize,
+    builder: F,
+}
+
+impl<F: Fn(&[f64]) -> QuantumCircuit> CustomAnsatz<F> {
+    /// Creates an ansatz from a closure that builds the circuit for a parameter vector.
+    ///
+    /// # Arguments
+    /// - `num_qubits`: The number of qubits of the produced circuits.
+    /// - `num_parameters`: The number of parameters the closure expects.
+    /// - `builder`: Builds the circuit for a given parameter vector.
+    pub fn new(num_qubits: usize, num_parameters: usize, builder: F) -> Self {
+        Self {
+            num_qubits,
+            num_parameters,
+            builder,
+        }
+    }
+}
+
+impl<F: Fn(&[f64]) -> QuantumCircuit> Ansatz for CustomAnsatz<F> {
+    fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    fn num_parameters(&self) -> usize {
+        self.num_parameters
+    }
+
+    fn build(&self, parameters: &[f64]) -> QuantumCircuit {
+        check_parameter_count(self.num_parameters, parameters);
+        (self.builder)(parameters)
+    }
+}
+
+/// Checks the parameter count shared by all built-in templates.
+fn check_parameter_count(expected: usize, parameters: &[f64]) {
+    assert_eq!(
+        parameters.len(),
+        expected,
+        "Ansatz expects {} parameters, got {}.",
+        expected,
+        parameters.len()
+    );
+}
+
+/// Which qubit pairs the entangling layer of a template connects.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Entanglement {
+    /// CNOTs between neighbours: `(0, 1), (1, 2), …, (n−2, n−1)`.
+    Linear,
+    /// Linear entanglement plus a CNOT closing the ring `(n−1, 0)`.
+    Circular,
+    /// CNOTs between every pair `(i, j)` with `i < j`.
+    Full,
+}
+
+impl Entanglement {
+    /// Returns the `(control, target)` pairs for `num_qubits` qubits.
+    pub fn pairs(&self, num_qubits: usize) -> Vec<(usize, usize)> {
+        let linear = (1..num_qubits).map(|q| (q - 1, q));
+        match self {
+            Entanglement::Linear => linear.collect(),
+            Entanglement::Circular => {
+                let mut pairs: Vec<_> = linear.collect();
+                if num_qubits > 2 {
+                    pairs.push((num_qubits - 1, 0));
+                }
+                pairs
+            }
+            Entanglement::Full => (0..num_qubits)
+                .flat_map(|i| (i + 1..num_qubits).map(move |j| (i, j)))
+                .collect(),
+        }
+    }
+
+    fn apply(&self, circuit: &mut QuantumCircuit) {
+        for (control, target) in self.pairs(circuit.qubits) {
+            add_cnot(circuit, control, target);
+        }
+    }
+}
+
+/// Appends `exp(−iθ/2 · P)` for a Pauli string `P` to the circuit.
+///
+/// Every non-identity factor is rotated into the Z basis (`H` for X, `Rx(π/2)` for Y), the
+/// parity of the involved qubits is collected with a CNOT ladder, a Z rotation is applied to the
+/// last qubit, and the basis changes are undone. The identity string only contributes a global
+/// phase and adds no gates.
+pub fn add_pauli_rotation(circuit: &mut QuantumCircuit, pauli_string: &PauliString, theta: f64) {
+    let factors: Vec<(usize, Pauli)> = pauli_string
+        .factors
+        .iter()
+        .copied()
+        .filter(|&(_, pauli)| pauli != Pauli::I)
+        .collect();
+    if factors.is_empty() {
+        return;
+    }
+
+    let change_basis = |circuit: &mut QuantumCircuit, inverse: bool| {
+        for &(qubit, pauli) in &factors {
+            match pauli {
+                Pauli::X => circuit.add_gate(gates::hadamard(), vec![qubit]),
+                Pauli::Y => {
+                    let angle = if inverse { -FRAC_PI_2 } else { FRAC_PI_2 };
+                    circuit.add_gate(gates::rotation_x(angle), vec![qubit]);
+                }
+                _ => (),
+            }
+        }
+    };
+
+    change_basis(circuit, false);
+    for pair in factors.windows(2) {
+        add_cnot(circuit, pair[0].0, pair[1].0);
+    }
+    // gates::rotation_z(φ) is diag(e^(−iφ), e^(iφ)), i.e. exp(−iφ·Z)
+    let last = factors[factors.len() - 1].0;
+    circuit.add_gate(gates::rotation_z(theta / 2.0), vec![last]);
+    for pair in factors.windows(2).rev() {
+        add_cnot(circuit, pair[0].0, pair[1].0);
+    }
+    change_basis(circuit, true);
+}
+
+/// Appends a qubit excitation moving the occupation of `from` onto `to`.
+///
+/// The operation rotates `|x⟩` (qubits in `from` set, qubits in `to` clear) into its partner
+/// `|x̄⟩` as `cos(θ/2)|x⟩ + sin(θ/2)|x̄⟩`, and leaves every other configuration of the involved
+/// qubits untouched, so the number of set qubits is conserved. It is implemented as a product
+/// of commuting Pauli rotations over all X/Y strings with an odd number of Y factors.
+///
+/// # Panics
+/// - If `from` and `to` have different lengths or share a qubit.
+pub fn add_excitation(circuit: &mut QuantumCircuit, from: &[usize], to: &[usize], theta: f64) {
+    assert_eq!(from.len(), to.len(), "Excitations must move as many qubits as they fill.");
+    assert!(from.iter().all(|q| !to.contains(q)), "Excitation source and target qubits must differ.");
+
+    let qubits: Vec<usize> = from.iter().chain(to).copied().collect();
+    let k = qubits.len();
+    let weight = 1.0 / (1usize << (k - 1)) as f64;
+
+    for y_mask in (0..1usize << k).filter(|mask| mask.count_ones() % 2 == 1) {
+        let factors: Vec<(usize, Pauli)> = qubits
+            .iter()
+            .enumerate()
+            .map(|(i, &qubit)| (qubit, if (y_mask >> i) & 1 == 1 { Pauli::Y } else { Pauli::X }))
+            .collect();
+
+        // Sign that makes every term add up constructively on |x⟩ → |x̄⟩
+        let y_on_occupied = (0..from.len()).filter(|&i| (y_mask >> i) & 1 == 1).count();
+        let y_count = y_mask.count_ones() as usize;
+        let sign = if (y_on_occupied + (y_count - 1) / 2).is_multiple_of(2) { 1.0 } else { -1.0 };
+
+        add_pauli_rotation(circuit, &PauliString::new(factors), sign * weight * theta);
+    }
+}
+
+/// The hardware-efficient ansatz: layers of `Ry`/`Rz` rotations on every qubit separated by
+/// CNOT entangling layers.
+///
+/// With depth `d` on `n` qubits there are `d` entangling layers and `d + 1` rotation layers,
+/// for `2n(d + 1)` parameters.
+#[derive(Debug, Clone)]
+pub struct HardwareEfficientAnsatz {
+    pub num_qubits: usize,
+    pub depth: usize,
+    pub entanglement: Entanglement,
+}
+
+impl HardwareEfficientAnsatz {
+    /// Creates a hardware-efficient ansatz with linear entanglement.
+    pub fn new(num_qubits: usize, depth: usize) -> Self {
+        Self {
+            num_qubits,
+            depth,
+            entanglement: Entanglement::Linear,
+        }
+    }
+
+    /// Uses a different entangling pattern.
+    pub fn with_entanglement(mut self, entanglement: Entanglement) -> Self {
+        self.entanglement = entanglement;
+        self
+    }
+}
+
+impl Ansatz for HardwareEfficientAnsatz {
+    fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    fn num_parameters(&self) -> usize {
+        2 * self.num_qubits * (self.depth + 1)
+    }
+
+    fn build(&self, parameters: &[f64]) -> QuantumCircuit {
+        check_parameter_count(self.num_parameters(), parameters);
+        let mut circuit = QuantumCircuit::new(self.num_qubits);
+
+        for (layer, angles) in parameters.chunks(2 * self.num_qubits).enumerate() {
+            if layer > 0 {
+                self.entanglement.apply(&mut circuit);
+            }
+            for (qubit, pair) in angles.chunks(2).enumerate() {
+                circuit.add_gate(gates::rotation_y(pair[0]), vec![qubit]);
+                circuit.add_gate(gates::rotation_z(pair[1]), vec![qubit]);
+            }
+        }
+
+        circuit
+    }
+}
+
+/// The real-amplitudes ansatz: layers of `Ry` rotations separated by CNOT entangling layers.
+///
+/// Only real gates are used, so the prepared state always has real amplitudes. With `reps`
+/// repetitions on `n` qubits there are `n(reps + 1)` parameters.
+#[derive(Debug, Clone)]
+pub struct RealAmplitudes {
+    pub num_qubits: usize,
+    pub reps: usize,
+    pub entanglement: Entanglement,
+}
+
+impl RealAmplitudes {
+    /// Creates a real-amplitudes ansatz with linear entanglement.
+    pub fn new(num_qubits: usize, reps: usize) -> Self {
+        Self {
+            num_qubits,
+            reps,
+            entanglement: Entanglement::Linear,
+        }
+    }
+
+    /// Uses a different entangling pattern.
+    pub fn with_entanglement(mut self, entanglement: Entanglement) -> Self {
+        self.entanglement = entanglement;
+        self
+    }
+}
+
+impl Ansatz for RealAmplitudes {
+    fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    fn num_parameters(&self) -> usize {
+        self.num_qubits * (self.reps + 1)
+    }
+
+    fn build(&self, parameters: &[f64]) -> QuantumCircuit {
+        check_parameter_count(self.num_parameters(), parameters);
+        let mut circuit = QuantumCircuit::new(self.num_qubits);
+
+        for (layer, angles) in parameters.chunks(self.num_qubits).enumerate() {
+            if layer > 0 {
+                self.entanglement.apply(&mut circuit);
+            }
+            for (qubit, &angle) in angles.iter().enumerate() {
+                circuit.add_gate(gates::rotation_y(angle), vec![qubit]);
+            }
+        }
+
+        circuit
+    }
+}
+
+/// A UCC-style ansatz built from single and double qubit excitations.
+///
+/// The circuit starts from the Hartree–Fock reference (the first `num_electrons` qubits set) and
+/// applies one parameterized excitation per `(occupied → virtual)` single and
+/// `(occupied pair → virtual pair)` double, repeated `depth` times as a Trotterized product.
+/// Excitations act directly on qubits (no Jordan–Wigner parity strings), so the particle
+/// number is conserved.
+#[derive(Debug, Clone)]
+pub struct UccAnsatz {
+    pub num_qubits: usize,
+    pub num_electrons: usize,
+    pub depth: usize,
+    /// The excitations, stored as `(from, to)` qubit lists.
+    pub excitations: Vec<(Vec<usize>, Vec<usize>)>,
+}
+
+impl UccAnsatz {
+    /// Creates the singles-and-doubles ansatz with depth `1`.
+    ///
+    /// # Panics
+    /// - If `num_electrons` exceeds `num_qubits`.
+    pub fn singles_doubles(num_qubits: usize, num_electrons: usize) -> Self {
+        assert!(num_electrons <= num_qubits, "Cannot place {} electrons on {} qubits.", num_electrons, num_qubits);
+        let occupied: Vec<usize> = (0..num_electrons).collect();
+        let virtuals: Vec<usize> = (num_electrons..num_qubits).collect();
+
+        let mut excitations = Vec::new();
+        for &i in &occupied {
+            for &a in &virtuals {
+                excitations.push((vec![i], vec![a]));
+            }
+        }
+        for (x, &i) in occupied.iter().enumerate() {
+            for &j in &occupied[x + 1..] {
+                for (y, &a) in virtuals.iter().enumerate() {
+                    for &b in &virtuals[y + 1..] {
+                        excitations.push((vec![i, j], vec![a, b]));
+                    }
+                }
+            }
+        }
+
+        Self {
+            num_qubits,
+            num_electrons,
+            depth: 1,
+            excitations,
+        }
+    }
+
+    /// Repeats the excitation layer `depth` times, each with its own parameters.
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+}
+
+impl Ansatz for UccAnsatz {
+    fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    fn num_parameters(&self) -> usize {
+        self.excitations.len() * self.depth
+    }
+
+    fn build(&self, parameters: &[f64]) -> QuantumCircuit {
+        check_parameter_count(self.num_parameters(), parameters);
+        let mut circuit = QuantumCircuit::new(self.num_qubits);
+
+        for qubit in 0..self.num_electrons {
+            circuit.add_gate(gates::pauli_x(), vec![qubit]);
+        }
+        let excitations = self.excitations.iter().cycle();
+        for ((from, to), &theta) in excitations.zip(parameters) {
+            add_excitation(&mut circuit, from, to, theta);
+        }
+
+        circuit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::gates;
+
+    #[test]
+    fn test_custom_ansatz_binds_parameters() {
+        let ansatz = CustomAnsatz::new(1, 1, |params: &[f64]| {
+            let mut circuit = QuantumCircuit::new(1);
+            circuit.add_gate(gates::rotation_y(params[0]), vec![0]);
+            circuit
+        });
+
+        assert_eq!(ansatz.num_parameters(), 1);
+        let probabilities = ansatz.build(&[std::f64::consts::PI]).simulate().statevector.probabilities();
+        assert!((probabilities[&1] - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    #[should_panic(expected = "Ansatz expects 2 parameters, got 1.")]
+    fn test_custom_ansatz_rejects_wrong_parameter_count() {
+        let ansatz = CustomAnsatz::new(1, 2, |_: &[f64]| QuantumCircuit::new(1));
+        ansatz.build(&[0.0]);
+    }
+
+    #[test]
+    fn test_entanglement_pairs() {
+        assert_eq!(Entanglement::Linear.pairs(3), vec![(0, 1), (1, 2)]);
+        assert_eq!(Entanglement::Circular.pairs(3), vec![(0, 1), (1, 2), (2, 0)]);
+        assert_eq!(Entanglement::Full.pairs(3), vec![(0, 1), (0, 2), (1, 2)]);
+    }
+
+    #[test]
+    fn test_parameter_counts() {
+        assert_eq!(HardwareEfficientAnsatz::new(3, 2).num_parameters(), 18);
+        assert_eq!(RealAmplitudes::new(4, 3).num_parameters(), 16);
+        // 2 electrons on 4 qubits: 4 singles and 1 double
+        assert_eq!(UccAnsatz::singles_doubles(4, 2).num_parameters(), 5);
+        assert_eq!(UccAnsatz::singles_doubles(4, 2).with_depth(2).num_parameters(), 10);
+    }
+
+    #[test]
+    fn test_pauli_rotation_matches_single_qubit_rotation() {
+        let theta = 0.7;
+        let mut circuit = QuantumCircuit::new(1);
+        add_pauli_rotation(&mut circuit, &PauliString::new(vec![(0, Pauli::Y)]), theta);
+        let probabilities = circuit.simulate().statevector.probabilities();
+        // exp(−iθ/2·Y)|0⟩ = Ry(θ)|0⟩
+        assert!((probabilities[&1] - (theta / 2.0).sin().powi(2)).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_real_amplitudes_are_real() {
+        let ansatz = RealAmplitudes::new(3, 2).with_entanglement(Entanglement::Full);
+        let parameters: Vec<f64> = (0..ansatz.num_parameters()).map(|i| 0.3 * i as f64 + 0.1).collect();
+        let state = ansatz.build(&parameters).simulate().statevector;
+        assert!(state.vector.values().all(|amp| amp.im.abs() < 1e-10));
+    }
+
+    #[test]
+    fn test_hardware_efficient_state_is_normalized() {
+        let ansatz = HardwareEfficientAnsatz::new(3, 2).with_entanglement(Entanglement::Circular);
+        let parameters: Vec<f64> = (0..ansatz.num_parameters()).map(|i| 0.2 * i as f64).collect();
+        let total: f64 = ansatz.build(&parameters).simulate().statevector.probabilities().values().sum();
+        assert!((total - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_double_excitation_rotates_reference_state() {
+        let mut circuit = QuantumCircuit::new(4);
+        circuit.add_gate(gates::pauli_x(), vec![0]);
+        circuit.add_gate(gates::pauli_x(), vec![1]);
+        let theta = 1.1;
+        add_excitation(&mut circuit, &[0, 1], &[2, 3], theta);
+
+        let state = circuit.simulate().statevector;
+        let probabilities = state.probabilities();
+        assert!((probabilities[&0b0011] - (theta / 2.0).cos().powi(2)).abs() < 1e-10);
+        assert!((probabilities[&0b1100] - (theta / 2.0).sin().powi(2)).abs() < 1e-10);
+        assert_eq!(probabilities.len(), 2);
+    }
+
+    #[test]
+    fn test_ucc_conserves_particle_number() {
+        let ansatz = UccAnsatz::singles_doubles(4, 2);
+        let parameters: Vec<f64> = (0..ansatz.num_parameters()).map(|i| 0.4 + 0.25 * i as f64).collect();
+        let state = ansatz.build(&parameters).simulate().statevector;
+        assert!(state.vector.keys().all(|state| state.count_ones() == 2));
+        assert!(state.vector.len() > 2, "Excitations should spread the reference state");
+    }
+}
@@ -0,0 +1,114 @@
+//! Bernstein–Vazirani Algorithm
+//!
+//! Recovers a hidden bitstring `s` from the oracle `f(x) = s · x (mod 2)` with a single query.
+
+use crate::algorithms::add_cnot;
+use crate::circuit::{gates, QuantumCircuit};
+
+/// Parses a bitstring such as `"1011"` into its integer value.
+///
+/// The leftmost character is the most significant bit, so character `i` from the right
+/// corresponds to qubit `i`.
+///
+/// # Panics
+/// - If the string is empty or contains characters other than `0` and `1`.
+fn parse_bitstring(bits: &str) -> usize {
+    assert!(!bits.is_empty(), "Secret bitstring must not be empty.");
+    bits.chars().fold(0, |acc, c| match c {
+        '0' => acc << 1,
+        '1' => (acc << 1) | 1,
+        _ => panic!("Invalid character '{}' in secret bitstring.", c),
+    })
+}
+
+/// Builds the Bernstein–Vazirani circuit for a secret bitstring.
+///
+/// Qubits `0..n` form the input register and qubit `n` is the ancilla, prepared in `|−⟩`.
+/// The oracle is implemented with one CNOT from every input qubit whose secret bit is `1`
+/// onto the ancilla, so the secret is kicked back as phases onto the input register.
+///
+/// # Arguments
+/// - `secret_bitstring`: The hidden string, most significant bit first (e.g. `"1011"`).
+///
+/// # Returns
+/// A circuit on `n + 1` qubits whose input register ends in `|s⟩`.
+pub fn bernstein_vazirani_circuit(secret_bitstring: &str) -> QuantumCircuit {
+    let secret = parse_bitstring(secret_bitstring);
+    let n = secret_bitstring.len();
+    let ancilla = n;
+    let mut circuit = QuantumCircuit::new(n + 1);
+
+    // Prepare the ancilla in |−⟩ and the input register in |+⟩^n
+    circuit.add_gate(gates::pauli_x(), vec![ancilla]);
+    for qubit in 0..=n {
+        circuit.add_gate(gates::hadamard(), vec![qubit]);
+    }
+
+    // Oracle: |x⟩|y⟩ → |x⟩|y ⊕ s·x⟩
+    for qubit in (0..n).filter(|&q| (secret >> q) & 1 == 1) {
+        add_cnot(&mut circuit, qubit, ancilla);
+    }
+
+    for qubit in 0..n {
+        circuit.add_gate(gates::hadamard(), vec![qubit]);
+    }
+
+    circuit
+}
+
+/// Runs the Bernstein–Vazirani algorithm and returns the recovered secret.
+///
+/// The input register is measured once; the outcome is deterministic, so a single shot
+/// recovers the whole string.
+///
+/// # Example
+/// ```
+/// use zana::algorithms::bernstein_vazirani;
+///
+/// assert_eq!(bernstein_vazirani("1011"), "1011");
+/// ```
+pub fn bernstein_vazirani(secret_bitstring: &str) -> String {
+    let n = secret_bitstring.len();
+    let mut statevector = bernstein_vazirani_circuit(secret_bitstring).simulate().statevector;
+
+    (0..n)
+        .rev()
+        .map(|qubit| if statevector.measure(qubit) == 1 { '1' } else { '0' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bitstring() {
+        assert_eq!(parse_bitstring("1011"), 0b1011);
+        assert_eq!(parse_bitstring("0001"), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid character")]
+    fn test_parse_invalid_bitstring() {
+        parse_bitstring("10a1");
+    }
+
+    #[test]
+    fn test_recovers_secret() {
+        for secret in ["1", "0", "10", "1011", "0110", "11111", "100001"] {
+            assert_eq!(bernstein_vazirani(secret), secret);
+        }
+    }
+
+    #[test]
+    fn test_input_register_is_deterministic() {
+        let probabilities = bernstein_vazirani_circuit("101").simulate().statevector.probabilities();
+        let input_mask = 0b111;
+        let total: f64 = probabilities
+            .iter()
+            .filter(|&(&state, _)| state & input_mask == 0b101)
+            .map(|(_, &prob)| prob)
+            .sum();
+        assert!((total - 1.0).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,411 @@
+//! Variational Quantum Classifier
+//!
+//! A binary classifier built from the crate's variational stack: a [`FeatureMap`] encodes a
+//! classical feature vector into a state, a trainable [`Ansatz`] transforms it, and the
+//! expectation of an observable (by default `Z` on qubit 0) is the model output `f(x) ∈ [−1, 1]`.
+//! Class `1` is predicted when `f(x) < 0`.
+//!
+//! Training minimizes the mean squared error between `f(x)` and the targets `±1` by gradient
+//! descent, with exact gradients from the parameter-shift rule.
+
+use std::f64::consts::PI;
+use num_complex::Complex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use crate::algorithms::ansatz::{Ansatz, RealAmplitudes};
+use crate::circuit::observable::{Pauli, PauliString};
+use crate::circuit::{gates, QuantumCircuit};
+use crate::config;
+
+/// A labelled training example: features and a class label (`0` or `1`).
+pub type Sample = (Vec<f64>, usize);
+
+/// Encodes classical features into a quantum state.
+pub trait FeatureMap {
+    /// The number of qubits the encoding acts on.
+    fn num_qubits(&self) -> usize;
+
+    /// Builds the circuit preparing the encoded state from `|0…0⟩`.
+    fn encode(&self, features: &[f64]) -> QuantumCircuit;
+}
+
+/// Checks that a feature vector has one entry per qubit.
+fn check_feature_count(expected: usize, features: &[f64]) {
+    assert_eq!(
+        features.len(),
+        expected,
+        "Feature map expects {} features, got {}.",
+        expected,
+        features.len()
+    );
+}
+
+/// Encodes feature `i` as an `Ry(xᵢ)` rotation on qubit `i`.
+#[derive(Debug, Clone)]
+pub struct AngleEncoding {
+    pub num_qubits: usize,
+}
+
+impl AngleEncoding {
+    /// Creates an angle encoding for `num_qubits` features.
+    pub fn new(num_qubits: usize) -> Self {
+        Self { num_qubits }
+    }
+}
+
+impl FeatureMap for AngleEncoding {
+    fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    fn encode(&self, features: &[f64]) -> QuantumCircuit {
+        check_feature_count(self.num_qubits, features);
+        let mut circuit = QuantumCircuit::new(self.num_qubits);
+        for (qubit, &x) in features.iter().enumerate() {
+            circuit.add_gate(gates::rotation_y(x), vec![qubit]);
+        }
+        circuit
+    }
+}
+
+/// The second-order Pauli-Z feature map.
+///
+/// Each repetition applies Hadamards, a phase `e^{−i·xᵢ·Z}` on every qubit, and a phase
+/// `e^{−i·(π − xᵢ)(π − xⱼ)·ZᵢZⱼ}` on every pair of qubits, so features interact through
+/// entanglement.
+#[derive(Debug, Clone)]
+pub struct ZZFeatureMap {
+    pub num_qubits: usize,
+    pub reps: usize,
+}
+
+impl ZZFeatureMap {
+    /// Creates a ZZ feature map for `num_qubits` features.
+    pub fn new(num_qubits: usize, reps: usize) -> Self {
+        Self { num_qubits, reps }
+    }
+}
+
+impl FeatureMap for ZZFeatureMap {
+    fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    fn encode(&self, features: &[f64]) -> QuantumCircuit {
+        check_feature_count(self.num_qubits, features);
+        let mut circuit = QuantumCircuit::new(self.num_qubits);
+
+        for _ in 0..self.reps {
+            for (qubit, &x) in features.iter().enumerate() {
+                circuit.add_gate(gates::hadamard(), vec![qubit]);
+                circuit.add_gate(gates::rotation_z(x), vec![qubit]);
+            }
+            for i in 0..self.num_qubits {
+                for j in i + 1..self.num_qubits {
+                    let angle = (PI - features[i]) * (PI - features[j]);
+                    let same = Complex::from_polar(1.0, -angle);
+                    let different = Complex::from_polar(1.0, angle);
+                    circuit.add_gate(gates::diagonal(vec![same, different, different, same]), vec![i, j]);
+                }
+            }
+        }
+
+        circuit
+    }
+}
+
+/// Returns the gradient of `objective` by the parameter-shift rule.
+///
+/// `∂f/∂θₖ = [f(θ + π/2·eₖ) − f(θ − π/2·eₖ)] / 2` is exact when every parameter enters the circuit
+/// once as a rotation `exp(−iθP/2)`, as in `gates::rotation_x`, `gates::rotation_y` and
+/// [`RealAmplitudes`].
+pub fn parameter_shift_gradient(objective: &mut dyn FnMut(&[f64]) -> f64, parameters: &[f64]) -> Vec<f64> {
+    let mut shifted = parameters.to_vec();
+    (0..parameters.len())
+        .map(|k| {
+            shifted[k] = parameters[k] + PI / 2.0;
+            let plus = objective(&shifted);
+            shifted[k] = parameters[k] - PI / 2.0;
+            let minus = objective(&shifted);
+            shifted[k] = parameters[k];
+            (plus - minus) / 2.0
+        })
+        .collect()
+}
+
+/// A binary variational quantum classifier.
+pub struct VariationalClassifier {
+    feature_map: Box<dyn FeatureMap>,
+    ansatz: Box<dyn Ansatz>,
+    observable: PauliString,
+    /// The trainable ansatz parameters.
+    pub parameters: Vec<f64>,
+    /// The gradient descent step size.
+    pub learning_rate: f64,
+    /// The number of full passes over the training data.
+    pub epochs: usize,
+}
+
+impl VariationalClassifier {
+    /// Creates a classifier with the given feature map and a two-repetition [`RealAmplitudes`]
+    /// ansatz, reading out `Z` on qubit 0.
+    ///
+    /// Parameters are initialized uniformly in `[−π, π)`.
+    pub fn new(feature_map: Box<dyn FeatureMap>) -> Self {
+        let num_qubits = feature_map.num_qubits();
+        let mut classifier = Self {
+            feature_map,
+            ansatz: Box::new(RealAmplitudes::new(num_qubits, 2)),
+            observable: PauliString::new(vec![(0, Pauli::Z)]),
+            parameters: Vec::new(),
+            learning_rate: 0.5,
+            epochs: 50,
+        };
+        classifier.initialize_parameters(&mut config::std_rng());
+        classifier
+    }
+
+    fn initialize_parameters(&mut self, rng: &mut StdRng) {
+        self.parameters = (0..self.ansatz.num_parameters())
+            .map(|_| rng.gen_range(-PI..PI))
+            .collect();
+    }
+
+    /// Uses a different trainable ansatz and reinitializes the parameters.
+    ///
+    /// Gradients use the parameter-shift rule, so every parameter should enter the ansatz as a
+    /// single `exp(−iθP/2)` rotation.
+    ///
+    /// # Panics
+    /// - If the ansatz and feature map act on different numbers of qubits.
+    pub fn with_ansatz(mut self, ansatz: Box<dyn Ansatz>) -> Self {
+        assert_eq!(
+            ansatz.num_qubits(),
+            self.feature_map.num_qubits(),
+            "Ansatz and feature map must act on the same qubits."
+        );
+        self.ansatz = ansatz;
+        self.initialize_parameters(&mut config::std_rng());
+        self
+    }
+
+    /// Reads out a different observable.
+    pub fn with_observable(mut self, observable: PauliString) -> Self {
+        self.observable = observable;
+        self
+    }
+
+    /// Reinitializes the parameters from a seeded generator for reproducible training.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.initialize_parameters(&mut StdRng::seed_from_u64(seed));
+        self
+    }
+
+    /// Sets the gradient descent step size.
+    pub fn with_learning_rate(mut self, learning_rate: f64) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Sets the number of training epochs.
+    pub fn with_epochs(mut self, epochs: usize) -> Self {
+        self.epochs = epochs;
+        self
+    }
+
+    /// Builds the full model circuit: feature encoding followed by the ansatz.
+    pub fn circuit(&self, features: &[f64], parameters: &[f64]) -> QuantumCircuit {
+        self.feature_map.encode(features).compose(&self.ansatz.build(parameters))
+    }
+
+    fn output(&self, features: &[f64], parameters: &[f64]) -> f64 {
+        self.circuit(features, parameters).expectation(&self.observable)
+    }
+
+    /// Returns the model output `f(x) ∈ [−1, 1]` for the current parameters.
+    pub fn decision_function(&self, features: &[f64]) -> f64 {
+        self.output(features, &self.parameters)
+    }
+
+    /// Returns the probability `(1 − f(x)) / 2` that `features` belong to class `1`.
+    pub fn predict_proba(&self, features: &[f64]) -> f64 {
+        (1.0 - self.decision_function(features)) / 2.0
+    }
+
+    /// Predicts the class label (`0` or `1`) of `features`.
+    pub fn predict(&self, features: &[f64]) -> usize {
+        usize::from(self.decision_function(features) < 0.0)
+    }
+
+    /// Returns the fraction of `data` classified correctly.
+    pub fn accuracy(&self, data: &[Sample]) -> f64 {
+        let correct = data
+            .iter()
+            .filter(|(features, label)| self.predict(features) == *label)
+            .count();
+        correct as f64 / data.len() as f64
+    }
+
+    /// Returns the mean squared error between the outputs and the `±1` targets.
+    pub fn loss(&self, data: &[Sample]) -> f64 {
+        mean_squared_error(self, data, &self.parameters)
+    }
+
+    /// Trains the classifier on `data` with full-batch gradient descent.
+    ///
+    /// # Returns
+    /// The training loss before each epoch, followed by the final loss.
+    ///
+    /// # Panics
+    /// - If `data` is empty or contains a label other than `0` or `1`.
+    pub fn fit(&mut self, data: &[Sample]) -> Vec<f64> {
+        assert!(!data.is_empty(), "Training data must not be empty.");
+        assert!(data.iter().all(|&(_, label)| label <= 1), "Labels must be 0 or 1.");
+
+        let mut history = Vec::with_capacity(self.epochs + 1);
+        for _ in 0..self.epochs {
+            history.push(self.loss(data));
+
+            // dL/dθ = mean of 2 (f(x) − y) · ∂f/∂θ
+            let mut gradient = vec![0.0; self.parameters.len()];
+            for (features, label) in data {
+                let error = self.decision_function(features) - target(*label);
+                let mut output = |parameters: &[f64]| self.output(features, parameters);
+                let sample_gradient = parameter_shift_gradient(&mut output, &self.parameters);
+                for (total, g) in gradient.iter_mut().zip(sample_gradient) {
+                    *total += 2.0 * error * g / data.len() as f64;
+                }
+            }
+
+            for (parameter, g) in self.parameters.iter_mut().zip(gradient) {
+                *parameter -= self.learning_rate * g;
+            }
+        }
+        history.push(self.loss(data));
+
+        history
+    }
+}
+
+/// Maps a class label to its regression target: `0 → +1`, `1 → −1`.
+fn target(label: usize) -> f64 {
+    1.0 - 2.0 * label as f64
+}
+
+fn mean_squared_error(classifier: &VariationalClassifier, data: &[Sample], parameters: &[f64]) -> f64 {
+    data.iter()
+        .map(|(features, label)| (classifier.output(features, parameters) - target(*label)).powi(2))
+        .sum::<f64>()
+        / data.len() as f64
+}
+
+/// Draws a standard normal sample with the Box–Muller transform.
+fn gaussian(rng: &mut StdRng) -> f64 {
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+}
+
+/// Generates a noisy XOR dataset with two features in `[0, π]`.
+///
+/// Points are scattered around the corners `(0, 0)`, `(0, π)`, `(π, 0)` and `(π, π)`; the label is
+/// `1` when exactly one coordinate is near `π`.
+pub fn xor_dataset(samples_per_corner: usize, noise: f64, seed: u64) -> Vec<Sample> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut data = Vec::with_capacity(4 * samples_per_corner);
+    for corner in 0..4 {
+        let (a, b) = (corner & 1, corner >> 1);
+        for _ in 0..samples_per_corner {
+            let x = (a as f64 * PI + noise * gaussian(&mut rng)).clamp(0.0, PI);
+            let y = (b as f64 * PI + noise * gaussian(&mut rng)).clamp(0.0, PI);
+            data.push((vec![x, y], a ^ b));
+        }
+    }
+    data
+}
+
+/// Generates the two interleaving half-circles ("moons") dataset, scaled into `[0, π]²`.
+///
+/// Class `0` follows `(cos t, sin t)` and class `1` follows `(1 − cos t, 0.5 − sin t)` for
+/// `t ∈ [0, π]`, with Gaussian noise of standard deviation `noise` added before scaling.
+pub fn moons_dataset(samples: usize, noise: f64, seed: u64) -> Vec<Sample> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..samples)
+        .map(|i| {
+            let label = i % 2;
+            let t = rng.gen_range(0.0..PI);
+            let (x, y) = if label == 0 {
+                (t.cos(), t.sin())
+            } else {
+                (1.0 - t.cos(), 0.5 - t.sin())
+            };
+            let x = x + noise * gaussian(&mut rng);
+            let y = y + noise * gaussian(&mut rng);
+
+            // The noiseless moons span x ∈ [−1, 2] and y ∈ [−0.5, 1]
+            let scaled_x = ((x + 1.0) / 3.0 * PI).clamp(0.0, PI);
+            let scaled_y = ((y + 0.5) / 1.5 * PI).clamp(0.0, PI);
+            (vec![scaled_x, scaled_y], label)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parameter_shift_matches_finite_differences() {
+        let classifier = VariationalClassifier::new(Box::new(ZZFeatureMap::new(2, 1))).with_seed(3);
+        let features = [0.4, 2.1];
+        let mut output = |parameters: &[f64]| classifier.output(&features, parameters);
+        let gradient = parameter_shift_gradient(&mut output, &classifier.parameters);
+
+        let epsilon = 1e-6;
+        for (k, g) in gradient.iter().enumerate() {
+            let mut plus = classifier.parameters.clone();
+            let mut minus = classifier.parameters.clone();
+            plus[k] += epsilon;
+            minus[k] -= epsilon;
+            let numeric = (output(&plus) - output(&minus)) / (2.0 * epsilon);
+            assert!((g - numeric).abs() < 1e-6, "Parameter {}: {} vs {}", k, g, numeric);
+        }
+    }
+
+    #[test]
+    fn test_learns_xor() {
+        let data = xor_dataset(5, 0.1, 7);
+        let mut classifier = VariationalClassifier::new(Box::new(AngleEncoding::new(2))).with_seed(1);
+        let history = classifier.fit(&data);
+
+        assert!(history.last().unwrap() < history.first().unwrap());
+        assert_eq!(classifier.accuracy(&data), 1.0);
+    }
+
+    #[test]
+    fn test_learns_moons() {
+        let data = moons_dataset(40, 0.1, 11);
+        let mut classifier = VariationalClassifier::new(Box::new(AngleEncoding::new(2)))
+            .with_seed(5)
+            .with_epochs(40);
+        classifier.fit(&data);
+
+        assert!(classifier.accuracy(&data) >= 0.8, "Accuracy {}", classifier.accuracy(&data));
+    }
+
+    #[test]
+    fn test_predict_proba_matches_prediction() {
+        let classifier = VariationalClassifier::new(Box::new(AngleEncoding::new(2))).with_seed(2);
+        for features in [[0.1, 0.2], [3.0, 1.0], [1.5, 2.5]] {
+            let probability = classifier.predict_proba(&features);
+            assert!((0.0..=1.0).contains(&probability));
+            assert_eq!(classifier.predict(&features), usize::from(probability > 0.5));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Feature map expects 2 features, got 3.")]
+    fn test_rejects_wrong_feature_count() {
+        AngleEncoding::new(2).encode(&[0.0, 1.0, 2.0]);
+    }
+}
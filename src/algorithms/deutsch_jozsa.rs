@@ -0,0 +1,125 @@
+//! Deutsch–Jozsa Algorithm
+//!
+//! Decides with a single oracle query whether a boolean function `f: {0,1}^n → {0,1}` is constant
+//! or balanced, given the promise that it is one of the two.
+
+use crate::circuit::{gates, QuantumCircuit};
+
+/// The two kinds of oracle the Deutsch–Jozsa promise allows.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OracleKind {
+    /// `f` returns the same value for every input.
+    Constant,
+    /// `f` returns `1` for exactly half of the inputs.
+    Balanced,
+}
+
+/// Builds the Deutsch–Jozsa circuit for a boolean function.
+///
+/// The oracle is applied in its phase-kickback form `|x⟩ → (-1)^f(x) |x⟩`, so no ancilla qubit
+/// is needed: the circuit is `H^⊗n · O_f · H^⊗n`.
+///
+/// # Arguments
+/// - `oracle`: The boolean function `f`, evaluated on basis state indices.
+/// - `n_qubits`: The number of input bits of `f`.
+pub fn deutsch_jozsa_circuit<F: Fn(usize) -> bool>(oracle: F, n_qubits: usize) -> QuantumCircuit {
+    let mut circuit = QuantumCircuit::new(n_qubits);
+    let all_qubits: Vec<usize> = (0..n_qubits).collect();
+
+    for &qubit in &all_qubits {
+        circuit.add_gate(gates::hadamard(), vec![qubit]);
+    }
+    circuit.add_gate(gates::phase_oracle(n_qubits, oracle), all_qubits.clone());
+    for &qubit in &all_qubits {
+        circuit.add_gate(gates::hadamard(), vec![qubit]);
+    }
+
+    circuit
+}
+
+/// Builds the Deutsch–Jozsa circuit from a truth table.
+///
+/// # Arguments
+/// - `truth_table`: `f(x)` for every input `x`; its length must be `2^n`.
+///
+/// # Panics
+/// - If the truth table length is not a power of two.
+pub fn deutsch_jozsa_circuit_from_truth_table(truth_table: &[bool]) -> QuantumCircuit {
+    assert!(
+        truth_table.len().is_power_of_two() && truth_table.len() > 1,
+        "Truth table must have 2^n entries (n ≥ 1), got {}.",
+        truth_table.len()
+    );
+    let n_qubits = truth_table.len().trailing_zeros() as usize;
+    deutsch_jozsa_circuit(|x| truth_table[x], n_qubits)
+}
+
+/// Runs a Deutsch–Jozsa circuit and reports whether its oracle is constant or balanced.
+///
+/// A constant oracle leaves the register in `|0…0⟩` with certainty, while a balanced oracle
+/// makes `|0…0⟩` impossible.
+pub fn classify(circuit: &QuantumCircuit) -> OracleKind {
+    let probability_zero = circuit.simulate().statevector.probabilities().get(&0).copied().unwrap_or(0.0);
+    if probability_zero > 0.5 {
+        OracleKind::Constant
+    } else {
+        OracleKind::Balanced
+    }
+}
+
+/// Decides whether `oracle` is constant or balanced using a single query.
+///
+/// # Example
+/// ```
+/// use zana::algorithms::deutsch_jozsa::{deutsch_jozsa, OracleKind};
+///
+/// assert_eq!(deutsch_jozsa(|_| true, 3), OracleKind::Constant);
+/// assert_eq!(deutsch_jozsa(|x| x & 1 == 1, 3), OracleKind::Balanced);
+/// ```
+pub fn deutsch_jozsa<F: Fn(usize) -> bool>(oracle: F, n_qubits: usize) -> OracleKind {
+    classify(&deutsch_jozsa_circuit(oracle, n_qubits))
+}
+
+/// Decides whether the function described by `truth_table` is constant or balanced.
+pub fn deutsch_jozsa_truth_table(truth_table: &[bool]) -> OracleKind {
+    classify(&deutsch_jozsa_circuit_from_truth_table(truth_table))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_oracles() {
+        assert_eq!(deutsch_jozsa(|_| false, 3), OracleKind::Constant);
+        assert_eq!(deutsch_jozsa(|_| true, 3), OracleKind::Constant);
+    }
+
+    #[test]
+    fn test_balanced_oracles() {
+        assert_eq!(deutsch_jozsa(|x| x & 1 == 1, 3), OracleKind::Balanced);
+        assert_eq!(deutsch_jozsa(|x| x >= 4, 3), OracleKind::Balanced);
+        assert_eq!(deutsch_jozsa(|x| (x.count_ones() % 2) == 1, 4), OracleKind::Balanced);
+    }
+
+    #[test]
+    fn test_truth_tables() {
+        assert_eq!(deutsch_jozsa_truth_table(&[true; 8]), OracleKind::Constant);
+        assert_eq!(
+            deutsch_jozsa_truth_table(&[true, false, false, true]),
+            OracleKind::Balanced
+        );
+    }
+
+    #[test]
+    fn test_constant_oracle_returns_to_zero_state() {
+        let probabilities = deutsch_jozsa_circuit(|_| true, 2).simulate().statevector.probabilities();
+        assert!((probabilities[&0] - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    #[should_panic(expected = "Truth table must have 2^n entries")]
+    fn test_invalid_truth_table_length() {
+        deutsch_jozsa_circuit_from_truth_table(&[true, false, true]);
+    }
+}
@@ -0,0 +1,360 @@
+//! Quantum Error Correction
+//!
+//! Encoding, syndrome measurement and recovery for the 3-qubit bit-flip and phase-flip codes and
+//! the 7-qubit Steane code. Each code lays out its data qubits first, followed by the ancillas
+//! used for syndrome extraction. Ancillas are measured mid-circuit, reset to `|0⟩`, and the
+//! resulting syndrome selects a Pauli correction.
+
+use crate::circuit::gates;
+use crate::circuit::noise::inject_error;
+use crate::circuit::observable::Pauli;
+use crate::circuit::statevector::Statevector;
+use crate::circuit::QuantumCircuit;
+use super::add_cnot;
+
+/// A stabilizer code protecting one logical qubit.
+pub trait QuantumCode {
+    /// The number of physical data qubits.
+    fn num_data_qubits(&self) -> usize;
+
+    /// The number of ancillas used for syndrome extraction.
+    fn num_ancillas(&self) -> usize;
+
+    /// The data qubit holding the logical state before encoding and after decoding.
+    fn logical_qubit(&self) -> usize;
+
+    /// Builds the circuit mapping the logical state on [`Self::logical_qubit`] into the code.
+    fn encoding_circuit(&self) -> QuantumCircuit;
+
+    /// Builds the circuit copying the stabilizer parities onto the ancillas.
+    ///
+    /// Ancilla `i` (qubit `num_data_qubits() + i`) holds bit `i` of the syndrome.
+    fn syndrome_circuit(&self) -> QuantumCircuit;
+
+    /// Returns the Pauli corrections for a measured syndrome.
+    fn correction(&self, syndrome: usize) -> Vec<(usize, Pauli)>;
+
+    /// The total number of qubits, data and ancillas.
+    fn num_qubits(&self) -> usize {
+        self.num_data_qubits() + self.num_ancillas()
+    }
+}
+
+/// Applies every gate of `circuit` to `state`.
+fn run(circuit: &QuantumCircuit, state: &mut Statevector) {
+    for (gate, qubits) in &circuit.gates {
+        state.apply_gate(gate.clone(), qubits);
+    }
+}
+
+/// Encodes the single-qubit state prepared by `input` into `code`.
+///
+/// # Panics
+/// - If `input` does not act on exactly one qubit.
+pub fn encode(code: &dyn QuantumCode, input: &QuantumCircuit) -> Statevector {
+    assert_eq!(input.qubits, 1, "The logical input must be a single-qubit circuit.");
+    let mut state = Statevector::new(code.num_qubits());
+    for (gate, _) in &input.gates {
+        state.apply_gate(gate.clone(), &[code.logical_qubit()]);
+    }
+    run(&code.encoding_circuit(), &mut state);
+    state
+}
+
+/// Measures the syndrome and resets the ancillas to `|0⟩`.
+pub fn measure_syndrome(code: &dyn QuantumCode, state: &mut Statevector) -> usize {
+    run(&code.syndrome_circuit(), state);
+
+    let mut syndrome = 0;
+    for i in 0..code.num_ancillas() {
+        let ancilla = code.num_data_qubits() + i;
+        if state.measure(ancilla) == 1 {
+            syndrome |= 1 << i;
+            state.apply_gate(gates::pauli_x(), &[ancilla]);
+        }
+    }
+    syndrome
+}
+
+/// Applies the correction for `syndrome`.
+pub fn recover(code: &dyn QuantumCode, state: &mut Statevector, syndrome: usize) {
+    for (qubit, pauli) in code.correction(syndrome) {
+        inject_error(state, qubit, pauli);
+    }
+}
+
+/// Measures the syndrome and applies the matching correction.
+///
+/// # Returns
+/// The measured syndrome (`0` if no error was detected).
+pub fn correct(code: &dyn QuantumCode, state: &mut Statevector) -> usize {
+    let syndrome = measure_syndrome(code, state);
+    recover(code, state, syndrome);
+    syndrome
+}
+
+/// Undoes the encoding, returning the logical state to [`QuantumCode::logical_qubit`].
+pub fn decode(code: &dyn QuantumCode, state: &mut Statevector) {
+    run(&code.encoding_circuit().inverse(), state);
+}
+
+/// Measures the parities `q₀⊕q₁` and `q₁⊕q₂` of three data qubits onto ancillas 3 and 4.
+fn add_repetition_parities(circuit: &mut QuantumCircuit) {
+    add_cnot(circuit, 0, 3);
+    add_cnot(circuit, 1, 3);
+    add_cnot(circuit, 1, 4);
+    add_cnot(circuit, 2, 4);
+}
+
+/// Maps a repetition-code syndrome to the data qubit it flags.
+fn repetition_error_location(syndrome: usize) -> Option<usize> {
+    match syndrome {
+        0b01 => Some(0),
+        0b11 => Some(1),
+        0b10 => Some(2),
+        _ => None,
+    }
+}
+
+/// The 3-qubit bit-flip code `|0⟩ → |000⟩, |1⟩ → |111⟩`, correcting one X error.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BitFlipCode;
+
+impl QuantumCode for BitFlipCode {
+    fn num_data_qubits(&self) -> usize {
+        3
+    }
+
+    fn num_ancillas(&self) -> usize {
+        2
+    }
+
+    fn logical_qubit(&self) -> usize {
+        0
+    }
+
+    fn encoding_circuit(&self) -> QuantumCircuit {
+        let mut circuit = QuantumCircuit::new(self.num_qubits());
+        add_cnot(&mut circuit, 0, 1);
+        add_cnot(&mut circuit, 0, 2);
+        circuit
+    }
+
+    fn syndrome_circuit(&self) -> QuantumCircuit {
+        let mut circuit = QuantumCircuit::new(self.num_qubits());
+        add_repetition_parities(&mut circuit);
+        circuit
+    }
+
+    fn correction(&self, syndrome: usize) -> Vec<(usize, Pauli)> {
+        repetition_error_location(syndrome)
+            .map(|qubit| vec![(qubit, Pauli::X)])
+            .unwrap_or_default()
+    }
+}
+
+/// The 3-qubit phase-flip code `|0⟩ → |+++⟩, |1⟩ → |−−−⟩`, correcting one Z error.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PhaseFlipCode;
+
+impl QuantumCode for PhaseFlipCode {
+    fn num_data_qubits(&self) -> usize {
+        3
+    }
+
+    fn num_ancillas(&self) -> usize {
+        2
+    }
+
+    fn logical_qubit(&self) -> usize {
+        0
+    }
+
+    fn encoding_circuit(&self) -> QuantumCircuit {
+        let mut circuit = BitFlipCode.encoding_circuit();
+        for qubit in 0..3 {
+            circuit.add_gate(gates::hadamard(), vec![qubit]);
+        }
+        circuit
+    }
+
+    fn syndrome_circuit(&self) -> QuantumCircuit {
+        // The bit-flip parities measured in the Hadamard basis
+        let mut circuit = QuantumCircuit::new(self.num_qubits());
+        for qubit in 0..3 {
+            circuit.add_gate(gates::hadamard(), vec![qubit]);
+        }
+        add_repetition_parities(&mut circuit);
+        for qubit in 0..3 {
+            circuit.add_gate(gates::hadamard(), vec![qubit]);
+        }
+        circuit
+    }
+
+    fn correction(&self, syndrome: usize) -> Vec<(usize, Pauli)> {
+        repetition_error_location(syndrome)
+            .map(|qubit| vec![(qubit, Pauli::Z)])
+            .unwrap_or_default()
+    }
+}
+
+/// The Steane `[[7, 1, 3]]` code, correcting any single-qubit error.
+///
+/// Its stabilizers are the rows of the `[7, 4]` Hamming parity-check matrix, used once as Z-type
+/// checks (detecting X errors) and once as X-type checks (detecting Z errors). Each 3-bit
+/// syndrome is the binary position `j + 1` of the faulty qubit `j`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SteaneCode;
+
+impl SteaneCode {
+    /// The Hamming parity checks; check `i` gives bit `i` of the error position.
+    const CHECKS: [[usize; 4]; 3] = [[0, 2, 4, 6], [1, 2, 5, 6], [3, 4, 5, 6]];
+}
+
+impl QuantumCode for SteaneCode {
+    fn num_data_qubits(&self) -> usize {
+        7
+    }
+
+    fn num_ancillas(&self) -> usize {
+        6
+    }
+
+    fn logical_qubit(&self) -> usize {
+        2
+    }
+
+    fn encoding_circuit(&self) -> QuantumCircuit {
+        let mut circuit = QuantumCircuit::new(self.num_qubits());
+
+        // X on {2, 4, 5} is a logical X avoiding the pivot qubits 0, 1 and 3
+        add_cnot(&mut circuit, 2, 4);
+        add_cnot(&mut circuit, 2, 5);
+
+        // Superpose over the stabilizer group; each check's first qubit appears in no other check
+        for check in Self::CHECKS {
+            let pivot = check[0];
+            circuit.add_gate(gates::hadamard(), vec![pivot]);
+            for &qubit in &check[1..] {
+                add_cnot(&mut circuit, pivot, qubit);
+            }
+        }
+
+        circuit
+    }
+
+    fn syndrome_circuit(&self) -> QuantumCircuit {
+        let mut circuit = QuantumCircuit::new(self.num_qubits());
+
+        for (i, check) in Self::CHECKS.iter().enumerate() {
+            // Z-type check: parity of the data qubits onto ancilla i
+            let ancilla = 7 + i;
+            for &qubit in check {
+                add_cnot(&mut circuit, qubit, ancilla);
+            }
+        }
+        for (i, check) in Self::CHECKS.iter().enumerate() {
+            // X-type check: phase kickback onto ancilla 10 + i prepared in |+⟩
+            let ancilla = 10 + i;
+            circuit.add_gate(gates::hadamard(), vec![ancilla]);
+            for &qubit in check {
+                add_cnot(&mut circuit, ancilla, qubit);
+            }
+            circuit.add_gate(gates::hadamard(), vec![ancilla]);
+        }
+
+        circuit
+    }
+
+    fn correction(&self, syndrome: usize) -> Vec<(usize, Pauli)> {
+        let x_position = syndrome & 0b111;
+        let z_position = syndrome >> 3;
+
+        match (x_position, z_position) {
+            (0, 0) => vec![],
+            (x, 0) => vec![(x - 1, Pauli::X)],
+            (0, z) => vec![(z - 1, Pauli::Z)],
+            (x, z) if x == z => vec![(x - 1, Pauli::Y)],
+            (x, z) => vec![(x - 1, Pauli::X), (z - 1, Pauli::Z)],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::noise::NoiseChannel;
+    use crate::circuit::observable::bloch_vector;
+
+    fn input_state() -> QuantumCircuit {
+        let mut input = QuantumCircuit::new(1);
+        input.add_gate(gates::rotation_y(1.1), vec![0]);
+        input.add_gate(gates::rotation_z(0.4), vec![0]);
+        input
+    }
+
+    fn assert_recovers(code: &dyn QuantumCode, errors: &[(usize, Pauli)], expected_syndrome: Option<usize>) {
+        let expected = bloch_vector(&input_state().simulate().statevector, 0);
+
+        let mut state = encode(code, &input_state());
+        for &(qubit, pauli) in errors {
+            inject_error(&mut state, qubit, pauli);
+        }
+        let syndrome = correct(code, &mut state);
+        if let Some(expected_syndrome) = expected_syndrome {
+            assert_eq!(syndrome, expected_syndrome);
+        }
+        decode(code, &mut state);
+
+        let actual = bloch_vector(&state, code.logical_qubit());
+        for axis in 0..3 {
+            assert!(
+                (actual[axis] - expected[axis]).abs() < 1e-9,
+                "Errors {:?}: expected {:?}, got {:?}",
+                errors,
+                expected,
+                actual
+            );
+        }
+    }
+
+    #[test]
+    fn test_bit_flip_code() {
+        assert_recovers(&BitFlipCode, &[], Some(0));
+        for qubit in 0..3 {
+            assert_recovers(&BitFlipCode, &[(qubit, Pauli::X)], None);
+        }
+    }
+
+    #[test]
+    fn test_phase_flip_code() {
+        assert_recovers(&PhaseFlipCode, &[], Some(0));
+        for qubit in 0..3 {
+            assert_recovers(&PhaseFlipCode, &[(qubit, Pauli::Z)], None);
+        }
+    }
+
+    #[test]
+    fn test_steane_corrects_any_single_qubit_error() {
+        assert_recovers(&SteaneCode, &[], Some(0));
+        for qubit in 0..7 {
+            let position = qubit + 1;
+            assert_recovers(&SteaneCode, &[(qubit, Pauli::X)], Some(position));
+            assert_recovers(&SteaneCode, &[(qubit, Pauli::Z)], Some(position << 3));
+            assert_recovers(&SteaneCode, &[(qubit, Pauli::Y)], Some(position | position << 3));
+        }
+    }
+
+    #[test]
+    fn test_steane_corrects_separate_x_and_z_errors() {
+        assert_recovers(&SteaneCode, &[(1, Pauli::X), (5, Pauli::Z)], None);
+    }
+
+    #[test]
+    fn test_noise_channel_injection() {
+        let mut state = encode(&BitFlipCode, &input_state());
+        let error = NoiseChannel::BitFlip(1.0).apply(&mut state, 2);
+        assert_eq!(error, Pauli::X);
+        assert_eq!(correct(&BitFlipCode, &mut state), 0b10);
+    }
+}
@@ -0,0 +1,126 @@
+//! Grover's Search
+//!
+//! Grover's algorithm finds the inputs marked by an oracle among `2^n` candidates using
+//! roughly `π/4 · √(2^n / M)` oracle queries, where `M` is the number of marked items.
+
+use std::f64::consts::PI;
+use crate::algorithms::amplitude_amplification::amplitude_amplification_circuit;
+use crate::circuit::{gates, QuantumCircuit};
+
+/// Tolerance used when comparing the probabilities of candidate states.
+const PROBABILITY_TOLERANCE: f64 = 1e-9;
+
+/// Returns the number of Grover iterations that maximizes the success probability.
+///
+/// # Arguments
+/// - `n_qubits`: The number of search qubits (the search space has `2^n_qubits` items).
+/// - `marked_count`: The number of marked items.
+///
+/// # Returns
+/// `⌊π/4 · √(N/M)⌋`, or `0` if nothing is marked.
+pub fn optimal_iterations(n_qubits: usize, marked_count: usize) -> usize {
+    if marked_count == 0 {
+        return 0;
+    }
+    let ratio = (1usize << n_qubits) as f64 / marked_count as f64;
+    (PI / 4.0 * ratio.sqrt()).floor() as usize
+}
+
+/// Builds the Grover search circuit.
+///
+/// Grover's search is amplitude amplification with the uniform superposition as state
+/// preparation: the circuit applies Hadamards on every qubit and then `iterations` rounds of the
+/// phase oracle followed by the diffusion operator `H^⊗n (2|0⟩⟨0| − I) H^⊗n`.
+///
+/// # Arguments
+/// - `oracle`: Returns `true` for every basis state that should be marked.
+/// - `n_qubits`: The number of search qubits.
+/// - `iterations`: The number of oracle + diffusion rounds.
+///
+/// # Returns
+/// A `QuantumCircuit` ready to be simulated.
+pub fn grover_circuit<F: Fn(usize) -> bool>(oracle: F, n_qubits: usize, iterations: usize) -> QuantumCircuit {
+    let mut uniform = QuantumCircuit::new(n_qubits);
+    for qubit in 0..n_qubits {
+        uniform.add_gate(gates::hadamard(), vec![qubit]);
+    }
+    amplitude_amplification_circuit(&uniform, oracle, iterations)
+}
+
+/// Runs Grover's search and returns the most probable basis states.
+///
+/// # Arguments
+/// - `oracle`: Returns `true` for every basis state that should be marked.
+/// - `n_qubits`: The number of search qubits.
+/// - `iterations`: The number of oracle + diffusion rounds (see [`optimal_iterations`]).
+///
+/// # Returns
+/// The basis states sharing the highest final probability, in ascending order. With a suitable
+/// number of iterations these are exactly the marked states.
+///
+/// # Example
+/// ```
+/// use zana::algorithms::{grover, grover::optimal_iterations};
+///
+/// let iterations = optimal_iterations(3, 1);
+/// let found = grover(|x| x == 5, 3, iterations);
+/// assert_eq!(found, vec![5]);
+/// ```
+pub fn grover<F: Fn(usize) -> bool>(oracle: F, n_qubits: usize, iterations: usize) -> Vec<usize> {
+    let probabilities = grover_circuit(oracle, n_qubits, iterations)
+        .simulate().statevector
+        .probabilities();
+
+    let max_probability = probabilities.values().copied().fold(0.0, f64::max);
+    let mut most_probable: Vec<usize> = probabilities
+        .into_iter()
+        .filter(|&(_, prob)| max_probability - prob < PROBABILITY_TOLERANCE)
+        .map(|(state, _)| state)
+        .collect();
+    most_probable.sort_unstable();
+    most_probable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_optimal_iterations() {
+        assert_eq!(optimal_iterations(2, 1), 1);
+        assert_eq!(optimal_iterations(3, 1), 2);
+        assert_eq!(optimal_iterations(4, 1), 3);
+        assert_eq!(optimal_iterations(4, 0), 0);
+    }
+
+    #[test]
+    fn test_two_qubit_search_is_exact() {
+        for target in 0..4 {
+            let probabilities = grover_circuit(|x| x == target, 2, 1).simulate().statevector.probabilities();
+            let success = probabilities.get(&target).copied().unwrap_or(0.0);
+            assert!((success - 1.0).abs() < 1e-9, "Target {} found with probability {}", target, success);
+        }
+    }
+
+    #[test]
+    fn test_single_marked_state() {
+        let found = grover(|x| x == 5, 3, optimal_iterations(3, 1));
+        assert_eq!(found, vec![5]);
+    }
+
+    #[test]
+    fn test_multiple_marked_states() {
+        let marked = [3, 12];
+        let found = grover(|x| marked.contains(&x), 4, optimal_iterations(4, marked.len()));
+        assert_eq!(found, vec![3, 12]);
+    }
+
+    #[test]
+    fn test_success_probability_is_high() {
+        let target = 9;
+        let probabilities = grover_circuit(|x| x == target, 4, optimal_iterations(4, 1))
+            .simulate().statevector
+            .probabilities();
+        assert!(probabilities[&target] > 0.9, "Success probability too low: {}", probabilities[&target]);
+    }
+}
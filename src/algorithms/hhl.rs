@@ -0,0 +1,397 @@
+//! HHL Linear System Solver
+//!
+//! A small-scale implementation of the Harrow–Hassidim–Lloyd algorithm for Hermitian systems
+//! `A|x⟩ = |b⟩` with `A` of size 2×2 or 4×4. The circuit runs phase estimation of `e^{iAt}` on a
+//! clock register, rotates an ancilla by an angle proportional to `1/λ`, and uncomputes the
+//! phase estimation. Postselecting the ancilla on `|1⟩` leaves the system register in `|x⟩`.
+//!
+//! This is a toy: the controlled evolutions are built from a classical eigendecomposition of `A`,
+//! and the evolution time is chosen so that the smallest eigenvalue lands exactly on clock value
+//! `1`. The result is exact when every eigenvalue is an integer multiple of the smallest one.
+
+use std::f64::consts::PI;
+use num_complex::Complex;
+use crate::circuit::gates;
+use crate::circuit::statevector::Statevector;
+use crate::circuit::QuantumCircuit;
+use super::qft::add_inverse_qft;
+
+/// A dense complex matrix stored row by row.
+pub type Matrix = Vec<Vec<Complex<f64>>>;
+
+/// The outcome of an HHL run.
+#[derive(Debug, Clone)]
+pub struct HhlResult {
+    /// The normalized solution state `|x⟩`, indexed like the system register.
+    pub solution: Vec<Complex<f64>>,
+    /// The probability of the ancilla postselection succeeding.
+    pub success_probability: f64,
+    /// The full statevector before postselection.
+    pub statevector: Statevector,
+}
+
+/// Checks that `matrix` is a 2×2 or 4×4 Hermitian matrix matching `b`.
+fn validate(matrix: &[Vec<Complex<f64>>], b: &[Complex<f64>]) {
+    let dim = matrix.len();
+    assert!(dim == 2 || dim == 4, "HHL supports 2x2 and 4x4 systems, got {}x{}.", dim, dim);
+    assert_eq!(b.len(), dim, "Right-hand side must have {} entries.", dim);
+    assert!(b.iter().any(|v| v.norm() > 1e-12), "Right-hand side must be non-zero.");
+
+    for (i, row) in matrix.iter().enumerate() {
+        assert_eq!(row.len(), dim, "Matrix must be square.");
+        for (j, value) in row.iter().enumerate() {
+            assert!((value - matrix[j][i].conj()).norm() < 1e-10, "Matrix must be Hermitian.");
+        }
+    }
+}
+
+/// Diagonalizes a Hermitian matrix with cyclic Jacobi rotations.
+///
+/// # Returns
+/// The eigenvalues and a unitary whose columns are the matching eigenvectors.
+fn hermitian_eigen(matrix: &[Vec<Complex<f64>>]) -> (Vec<f64>, Matrix) {
+    let dim = matrix.len();
+    let mut a: Matrix = matrix.to_vec();
+    let mut v: Matrix = (0..dim)
+        .map(|i| (0..dim).map(|j| Complex::new(if i == j { 1.0 } else { 0.0 }, 0.0)).collect())
+        .collect();
+
+    for _ in 0..100 {
+        let off_diagonal: f64 = (0..dim)
+            .flat_map(|i| (0..dim).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i][j].norm_sqr())
+            .sum();
+        if off_diagonal < 1e-24 {
+            break;
+        }
+
+        for p in 0..dim {
+            for q in p + 1..dim {
+                let magnitude = a[p][q].norm();
+                if magnitude < 1e-15 {
+                    continue;
+                }
+
+                // Rotating the phase of `a[p][q]` away reduces this to a real 2x2 Jacobi step
+                let phase = Complex::from_polar(1.0, -a[p][q].arg());
+                let theta = 0.5 * (2.0 * magnitude).atan2(a[p][p].re - a[q][q].re);
+                let (c, s) = (theta.cos(), theta.sin());
+
+                // J is the identity except for the (p, q) block [[c, -s], [e^{-iφ}s, e^{-iφ}c]]
+                let j_pp = Complex::new(c, 0.0);
+                let j_pq = Complex::new(-s, 0.0);
+                let j_qp = phase * s;
+                let j_qq = phase * c;
+
+                // A ← A J
+                for row in a.iter_mut() {
+                    let (x, y) = (row[p], row[q]);
+                    row[p] = x * j_pp + y * j_qp;
+                    row[q] = x * j_pq + y * j_qq;
+                }
+                // A ← J† A
+                let (row_p, row_q) = (a[p].clone(), a[q].clone());
+                a[p] = row_p.iter().zip(&row_q).map(|(x, y)| j_pp.conj() * x + j_qp.conj() * y).collect();
+                a[q] = row_p.iter().zip(&row_q).map(|(x, y)| j_pq.conj() * x + j_qq.conj() * y).collect();
+                // V ← V J
+                for row in v.iter_mut() {
+                    let (x, y) = (row[p], row[q]);
+                    row[p] = x * j_pp + y * j_qp;
+                    row[q] = x * j_pq + y * j_qq;
+                }
+            }
+        }
+    }
+
+    ((0..dim).map(|i| a[i][i].re).collect(), v)
+}
+
+/// Returns the conjugate transpose of a square matrix.
+fn adjoint(matrix: &[Vec<Complex<f64>>]) -> Matrix {
+    let dim = matrix.len();
+    (0..dim).map(|i| (0..dim).map(|j| matrix[j][i].conj()).collect()).collect()
+}
+
+/// Builds a unitary whose first column is the normalized `state`, completed by Gram–Schmidt.
+fn state_preparation(state: &[Complex<f64>]) -> Matrix {
+    let dim = state.len();
+    let norm = state.iter().map(|v| v.norm_sqr()).sum::<f64>().sqrt();
+    let mut columns: Vec<Vec<Complex<f64>>> = vec![state.iter().map(|v| v / norm).collect()];
+
+    for e in 0..dim {
+        if columns.len() == dim {
+            break;
+        }
+        let mut candidate: Vec<Complex<f64>> =
+            (0..dim).map(|i| Complex::new(if i == e { 1.0 } else { 0.0 }, 0.0)).collect();
+        for column in &columns {
+            let overlap: Complex<f64> = column.iter().zip(&candidate).map(|(c, x)| c.conj() * x).sum();
+            for (x, c) in candidate.iter_mut().zip(column) {
+                *x -= overlap * c;
+            }
+        }
+        let length = candidate.iter().map(|v| v.norm_sqr()).sum::<f64>().sqrt();
+        if length > 1e-8 {
+            columns.push(candidate.iter().map(|v| v / length).collect());
+        }
+    }
+
+    (0..dim).map(|i| (0..dim).map(|j| columns[j][i]).collect()).collect()
+}
+
+/// Builds the HHL circuit for `A|x⟩ = |b⟩`.
+///
+/// Qubits are laid out as the system register (`log₂ dim` qubits) followed by `clock_qubits`
+/// clock qubits and a single ancilla. Clock values are read as two's-complement integers, so
+/// negative eigenvalues are supported.
+///
+/// # Panics
+/// - If `matrix` is not a 2×2 or 4×4 Hermitian matrix, or `b` does not match it.
+/// - If `matrix` is singular.
+/// - If the eigenvalue ratios do not fit in the clock register.
+pub fn hhl_circuit(matrix: &[Vec<Complex<f64>>], b: &[Complex<f64>], clock_qubits: usize) -> QuantumCircuit {
+    validate(matrix, b);
+    assert!(clock_qubits >= 2, "HHL needs at least 2 clock qubits.");
+
+    let dim = matrix.len();
+    let system_qubits = dim.trailing_zeros() as usize;
+    let clock: Vec<usize> = (system_qubits..system_qubits + clock_qubits).collect();
+    let ancilla = system_qubits + clock_qubits;
+    let system: Vec<usize> = (0..system_qubits).collect();
+    let clock_size = 1usize << clock_qubits;
+
+    let (eigenvalues, eigenvectors) = hermitian_eigen(matrix);
+    let smallest = eigenvalues.iter().map(|l| l.abs()).fold(f64::INFINITY, f64::min);
+    assert!(smallest > 1e-10, "Matrix must be non-singular.");
+    let largest_ratio = eigenvalues.iter().map(|l| (l / smallest).round().abs()).fold(0.0, f64::max);
+    assert!(
+        largest_ratio < (clock_size / 2) as f64,
+        "Clock register of {} qubits cannot resolve an eigenvalue ratio of {}.",
+        clock_qubits,
+        largest_ratio
+    );
+
+    // The smallest eigenvalue maps to clock value 1
+    let time = 2.0 * PI / (clock_size as f64 * smallest);
+
+    let mut circuit = QuantumCircuit::new(ancilla + 1);
+    circuit.add_gate(gates::matrix(state_preparation(b)), system.clone());
+
+    // Phase estimation of U = e^{iAt}, with each controlled U^{2^k} applied in the eigenbasis
+    let mut estimation = QuantumCircuit::new(ancilla + 1);
+    let to_eigenbasis = gates::matrix(adjoint(&eigenvectors));
+    let from_eigenbasis = gates::matrix(eigenvectors);
+    for &qubit in &clock {
+        estimation.add_gate(gates::hadamard(), vec![qubit]);
+    }
+    for (k, &control) in clock.iter().enumerate() {
+        let power = (1usize << k) as f64;
+        let phases = (0..2 * dim)
+            .map(|index| {
+                if index < dim {
+                    Complex::new(1.0, 0.0)
+                } else {
+                    Complex::from_polar(1.0, eigenvalues[index - dim] * time * power)
+                }
+            })
+            .collect();
+
+        let mut targets = system.clone();
+        targets.push(control);
+        estimation.add_gate(to_eigenbasis.clone(), system.clone());
+        estimation.add_gate(gates::diagonal(phases), targets);
+        estimation.add_gate(from_eigenbasis.clone(), system.clone());
+    }
+    add_inverse_qft(&mut estimation, &clock);
+    let all_qubits: Vec<usize> = (0..=ancilla).collect();
+    circuit.append(&estimation, &all_qubits);
+
+    // Ry(θ_j) on the ancilla for each clock value j with sin(θ_j/2) = 1/λ̃_j, written as
+    // W Rz(θ_j) W† with W = S·H so that the clock-controlled part is diagonal
+    let s_gate = gates::diagonal(vec![Complex::new(1.0, 0.0), Complex::new(0.0, 1.0)]);
+    let rotations = (0..2 * clock_size)
+        .map(|index| {
+            let j = index >> 1;
+            let signed = if j < clock_size / 2 { j as f64 } else { j as f64 - clock_size as f64 };
+            let theta = if j == 0 { 0.0 } else { 2.0 * (1.0 / signed).asin() };
+            let sign = if index & 1 == 0 { -1.0 } else { 1.0 };
+            Complex::from_polar(1.0, sign * theta / 2.0)
+        })
+        .collect();
+    let mut rotation_targets = vec![ancilla];
+    rotation_targets.extend(&clock);
+
+    circuit.add_gate(s_gate.adjoint(), vec![ancilla]);
+    circuit.add_gate(gates::hadamard(), vec![ancilla]);
+    circuit.add_gate(gates::diagonal(rotations), rotation_targets);
+    circuit.add_gate(gates::hadamard(), vec![ancilla]);
+    circuit.add_gate(s_gate, vec![ancilla]);
+
+    circuit.append(&estimation.inverse(), &all_qubits);
+    circuit
+}
+
+/// Solves the Hermitian system `A|x⟩ = |b⟩` with HHL.
+///
+/// # Arguments
+/// - `matrix`: A 2×2 or 4×4 Hermitian matrix `A`, stored row by row.
+/// - `b`: The right-hand side; it does not need to be normalized.
+/// - `clock_qubits`: The size of the phase estimation register.
+///
+/// # Returns
+/// The normalized solution state and the postselection success probability.
+///
+/// # Example
+/// ```
+/// use num_complex::Complex;
+/// use zana::algorithms::hhl;
+///
+/// let c = |re: f64| Complex::new(re, 0.0);
+/// let matrix = vec![vec![c(1.5), c(0.5)], vec![c(0.5), c(1.5)]];
+/// let result = hhl(&matrix, &[c(1.0), c(0.0)], 3);
+///
+/// // x ∝ A⁻¹b = (0.75, -0.25)
+/// let norm = 0.75_f64.hypot(0.25);
+/// assert!((result.solution[0] - c(0.75 / norm)).norm() < 1e-9);
+/// assert!((result.solution[1] - c(-0.25 / norm)).norm() < 1e-9);
+/// ```
+pub fn hhl(matrix: &[Vec<Complex<f64>>], b: &[Complex<f64>], clock_qubits: usize) -> HhlResult {
+    let dim = matrix.len();
+    let statevector = hhl_circuit(matrix, b, clock_qubits).simulate().statevector;
+
+    // Keep the branch with the ancilla in |1⟩ and the clock uncomputed to |0⟩
+    let ancilla_bit = 1usize << (dim.trailing_zeros() as usize + clock_qubits);
+    let mut solution: Vec<Complex<f64>> = (0..dim)
+        .map(|i| statevector.vector.get(&(i | ancilla_bit)).copied().unwrap_or_default())
+        .collect();
+    let success_probability: f64 = solution.iter().map(|v| v.norm_sqr()).sum();
+
+    let norm = success_probability.sqrt();
+    for amplitude in solution.iter_mut() {
+        *amplitude /= norm;
+    }
+
+    HhlResult {
+        solution,
+        success_probability,
+        statevector,
+    }
+}
+
+/// Solves `A x = b` classically and returns the normalized solution, for validating HHL.
+///
+/// # Panics
+/// - If `matrix` is singular.
+pub fn classical_solution(matrix: &[Vec<Complex<f64>>], b: &[Complex<f64>]) -> Vec<Complex<f64>> {
+    let dim = matrix.len();
+    let mut augmented: Matrix = matrix
+        .iter()
+        .zip(b)
+        .map(|(row, &rhs)| row.iter().copied().chain(std::iter::once(rhs)).collect())
+        .collect();
+
+    // Gaussian elimination with partial pivoting
+    for col in 0..dim {
+        let pivot = (col..dim)
+            .max_by(|&x, &y| augmented[x][col].norm().total_cmp(&augmented[y][col].norm()))
+            .unwrap();
+        assert!(augmented[pivot][col].norm() > 1e-12, "Matrix must be non-singular.");
+        augmented.swap(col, pivot);
+
+        let pivot_row = augmented[col].clone();
+        for (row, values) in augmented.iter_mut().enumerate() {
+            if row != col {
+                let factor = values[col] / pivot_row[col];
+                for (value, pivot_value) in values.iter_mut().zip(&pivot_row).skip(col) {
+                    *value -= factor * pivot_value;
+                }
+            }
+        }
+    }
+
+    let solution: Vec<Complex<f64>> = (0..dim).map(|i| augmented[i][dim] / augmented[i][i]).collect();
+    let norm = solution.iter().map(|v| v.norm_sqr()).sum::<f64>().sqrt();
+    solution.into_iter().map(|v| v / norm).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(re: f64, im: f64) -> Complex<f64> {
+        Complex::new(re, im)
+    }
+
+    fn assert_close(actual: &[Complex<f64>], expected: &[Complex<f64>]) {
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).norm() < 1e-8, "Expected {:?}, got {:?}", expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_eigendecomposition() {
+        let matrix = vec![
+            vec![c(2.0, 0.0), c(0.0, 1.0), c(0.5, 0.0), c(0.0, 0.0)],
+            vec![c(0.0, -1.0), c(3.0, 0.0), c(0.0, 0.0), c(1.0, 1.0)],
+            vec![c(0.5, 0.0), c(0.0, 0.0), c(1.0, 0.0), c(0.0, -0.5)],
+            vec![c(0.0, 0.0), c(1.0, -1.0), c(0.0, 0.5), c(-1.0, 0.0)],
+        ];
+        let (eigenvalues, vectors) = hermitian_eigen(&matrix);
+
+        for (k, &lambda) in eigenvalues.iter().enumerate() {
+            for (i, row) in matrix.iter().enumerate() {
+                let av: Complex<f64> = row.iter().enumerate().map(|(j, a)| a * vectors[j][k]).sum();
+                assert!((av - vectors[i][k] * lambda).norm() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hhl_2x2_matches_classical() {
+        let matrix = vec![vec![c(1.0, 0.0), c(-1.0 / 3.0, 0.0)], vec![c(-1.0 / 3.0, 0.0), c(1.0, 0.0)]];
+        let b = [c(0.6, 0.0), c(0.8, 0.0)];
+
+        let result = hhl(&matrix, &b, 3);
+        assert_close(&result.solution, &classical_solution(&matrix, &b));
+        assert!(result.success_probability > 0.0 && result.success_probability <= 1.0);
+    }
+
+    #[test]
+    fn test_hhl_negative_eigenvalue() {
+        // Eigenvalues 1 and -2
+        let matrix = vec![vec![c(-0.5, 0.0), c(1.5, 0.0)], vec![c(1.5, 0.0), c(-0.5, 0.0)]];
+        let b = [c(1.0, 0.0), c(0.0, 0.0)];
+
+        let result = hhl(&matrix, &b, 3);
+        assert_close(&result.solution, &classical_solution(&matrix, &b));
+    }
+
+    #[test]
+    fn test_hhl_4x4_complex_hermitian() {
+        // Eigenvalues 1, 3, 1, 3
+        let matrix = vec![
+            vec![c(2.0, 0.0), c(1.0, 0.0), c(0.0, 0.0), c(0.0, 0.0)],
+            vec![c(1.0, 0.0), c(2.0, 0.0), c(0.0, 0.0), c(0.0, 0.0)],
+            vec![c(0.0, 0.0), c(0.0, 0.0), c(2.0, 0.0), c(0.0, 1.0)],
+            vec![c(0.0, 0.0), c(0.0, 0.0), c(0.0, -1.0), c(2.0, 0.0)],
+        ];
+        let b = [c(0.5, 0.0), c(0.5, 0.0), c(0.0, 0.5), c(0.5, 0.0)];
+
+        let result = hhl(&matrix, &b, 3);
+        assert_close(&result.solution, &classical_solution(&matrix, &b));
+    }
+
+    #[test]
+    #[should_panic(expected = "Matrix must be Hermitian.")]
+    fn test_rejects_non_hermitian() {
+        let matrix = vec![vec![c(1.0, 0.0), c(1.0, 0.0)], vec![c(0.0, 0.0), c(1.0, 0.0)]];
+        hhl(&matrix, &[c(1.0, 0.0), c(0.0, 0.0)], 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot resolve an eigenvalue ratio")]
+    fn test_rejects_small_clock() {
+        let matrix = vec![vec![c(1.0, 0.0), c(0.0, 0.0)], vec![c(0.0, 0.0), c(3.0, 0.0)]];
+        hhl(&matrix, &[c(1.0, 0.0), c(1.0, 0.0)], 2);
+    }
+}
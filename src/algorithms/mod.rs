@@ -0,0 +1,42 @@
+//! Quantum Algorithms
+//!
+//! This module provides textbook quantum algorithms built on top of `QuantumCircuit`
+//! and the statevector simulator.
+
+pub mod grover;
+pub mod amplitude_amplification;
+pub mod deutsch_jozsa;
+pub mod bernstein_vazirani;
+pub mod ansatz;
+pub mod optimizers;
+pub mod vqe;
+pub mod teleportation;
+pub mod superdense_coding;
+pub mod quantum_walk;
+pub mod qft;
+pub mod hhl;
+pub mod quantum_counting;
+pub mod swap_test;
+pub mod classifier;
+pub mod ecc;
+
+pub use grover::grover;
+pub use amplitude_amplification::amplitude_amplification;
+pub use deutsch_jozsa::deutsch_jozsa;
+pub use bernstein_vazirani::bernstein_vazirani;
+pub use vqe::vqe;
+pub use teleportation::teleport;
+pub use superdense_coding::superdense_coding;
+pub use hhl::hhl;
+pub use quantum_counting::quantum_counting;
+pub use swap_test::swap_test;
+
+use crate::circuit::{gates, QuantumCircuit};
+
+/// Adds a CNOT with the given control and target qubits to `circuit`.
+///
+/// `gates::cnot()` flips its first qubit when its second qubit is set (gate indices are built
+/// least-significant qubit first), so the qubits are passed as `[target, control]`.
+pub(crate) fn add_cnot(circuit: &mut QuantumCircuit, control: usize, target: usize) {
+    circuit.add_gate(gates::cnot(), vec![target, control]);
+}
@@ -0,0 +1,312 @@
+//! Classical Optimizers
+//!
+//! Derivative-free and gradient-based minimizers used by the variational algorithms.
+//! Every optimizer implements the [`Optimizer`] trait so it can be swapped freely.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use crate::config;
+
+/// The outcome of a minimization run.
+#[derive(Debug, Clone)]
+pub struct OptimizationResult {
+    /// The best parameters found.
+    pub parameters: Vec<f64>,
+    /// The objective value at `parameters`.
+    pub value: f64,
+    /// The best objective value after each iteration.
+    pub history: Vec<f64>,
+    /// The total number of objective evaluations.
+    pub evaluations: usize,
+}
+
+/// A classical minimizer of a real-valued objective.
+pub trait Optimizer {
+    /// Minimizes `objective` starting from `initial`.
+    fn minimize(&mut self, objective: &mut dyn FnMut(&[f64]) -> f64, initial: &[f64]) -> OptimizationResult;
+}
+
+/// Wraps an objective and counts how often it is evaluated.
+struct CountingObjective<'a> {
+    objective: &'a mut dyn FnMut(&[f64]) -> f64,
+    evaluations: usize,
+}
+
+impl CountingObjective<'_> {
+    fn eval(&mut self, parameters: &[f64]) -> f64 {
+        self.evaluations += 1;
+        (self.objective)(parameters)
+    }
+}
+
+/// The Nelder–Mead downhill simplex method.
+#[derive(Debug, Clone)]
+pub struct NelderMead {
+    /// The maximum number of simplex updates.
+    pub max_iterations: usize,
+    /// Stop once the spread of objective values across the simplex falls below this value.
+    pub tolerance: f64,
+    /// The offset used to build the initial simplex around the starting point.
+    pub initial_step: f64,
+}
+
+impl Default for NelderMead {
+    fn default() -> Self {
+        Self {
+            max_iterations: 500,
+            tolerance: 1e-8,
+            initial_step: 0.5,
+        }
+    }
+}
+
+impl Optimizer for NelderMead {
+    fn minimize(&mut self, objective: &mut dyn FnMut(&[f64]) -> f64, initial: &[f64]) -> OptimizationResult {
+        let mut objective = CountingObjective { objective, evaluations: 0 };
+        let n = initial.len();
+
+        // Initial simplex: the starting point plus one step along each axis
+        let mut simplex: Vec<(Vec<f64>, f64)> = Vec::with_capacity(n + 1);
+        simplex.push((initial.to_vec(), objective.eval(initial)));
+        for i in 0..n {
+            let mut vertex = initial.to_vec();
+            vertex[i] += self.initial_step;
+            let value = objective.eval(&vertex);
+            simplex.push((vertex, value));
+        }
+
+        let mut history = Vec::new();
+        for _ in 0..self.max_iterations {
+            simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+            history.push(simplex[0].1);
+            if simplex[n].1 - simplex[0].1 < self.tolerance {
+                break;
+            }
+
+            // Centroid of all vertices except the worst
+            let centroid: Vec<f64> = (0..n)
+                .map(|i| simplex[..n].iter().map(|(v, _)| v[i]).sum::<f64>() / n as f64)
+                .collect();
+            let towards = |coefficient: f64, vertex: &[f64]| -> Vec<f64> {
+                centroid.iter().zip(vertex).map(|(c, v)| c + coefficient * (v - c)).collect()
+            };
+
+            let reflected = towards(-1.0, &simplex[n].0);
+            let reflected_value = objective.eval(&reflected);
+
+            if reflected_value < simplex[0].1 {
+                let expanded = towards(-2.0, &simplex[n].0);
+                let expanded_value = objective.eval(&expanded);
+                simplex[n] = if expanded_value < reflected_value {
+                    (expanded, expanded_value)
+                } else {
+                    (reflected, reflected_value)
+                };
+            } else if reflected_value < simplex[n - 1].1 {
+                simplex[n] = (reflected, reflected_value);
+            } else {
+                let contracted = towards(0.5, &simplex[n].0);
+                let contracted_value = objective.eval(&contracted);
+                if contracted_value < simplex[n].1 {
+                    simplex[n] = (contracted, contracted_value);
+                } else {
+                    // Shrink every vertex towards the best one
+                    let best = simplex[0].0.clone();
+                    for vertex in simplex.iter_mut().skip(1) {
+                        let shrunk: Vec<f64> = best.iter().zip(&vertex.0).map(|(b, v)| b + 0.5 * (v - b)).collect();
+                        let value = objective.eval(&shrunk);
+                        *vertex = (shrunk, value);
+                    }
+                }
+            }
+        }
+
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let (parameters, value) = simplex.swap_remove(0);
+        OptimizationResult {
+            parameters,
+            value,
+            history,
+            evaluations: objective.evaluations,
+        }
+    }
+}
+
+/// Simultaneous Perturbation Stochastic Approximation.
+///
+/// SPSA estimates the gradient from two objective evaluations per iteration regardless of the
+/// number of parameters, which makes it well suited to noisy, shot-based objectives.
+#[derive(Debug, Clone)]
+pub struct Spsa {
+    /// The number of iterations.
+    pub iterations: usize,
+    /// The step-size numerator `a` in `a / (k + 1 + A)^α`.
+    pub learning_rate: f64,
+    /// The perturbation numerator `c` in `c / (k + 1)^γ`.
+    pub perturbation: f64,
+    rng: StdRng,
+}
+
+impl Spsa {
+    /// Creates an SPSA optimizer with standard gain schedules.
+    pub fn new(iterations: usize) -> Self {
+        Self {
+            iterations,
+            learning_rate: 1.0,
+            perturbation: 0.1,
+            rng: config::std_rng(),
+        }
+    }
+
+    /// Uses a seeded random number generator for reproducible runs.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+}
+
+impl Optimizer for Spsa {
+    fn minimize(&mut self, objective: &mut dyn FnMut(&[f64]) -> f64, initial: &[f64]) -> OptimizationResult {
+        const ALPHA: f64 = 0.602;
+        const GAMMA: f64 = 0.101;
+        let stability = 0.1 * self.iterations as f64;
+
+        let mut objective = CountingObjective { objective, evaluations: 0 };
+        let mut parameters = initial.to_vec();
+        let mut best = (parameters.clone(), objective.eval(&parameters));
+        let mut history = Vec::with_capacity(self.iterations);
+
+        for k in 0..self.iterations {
+            let a_k = self.learning_rate / (k as f64 + 1.0 + stability).powf(ALPHA);
+            let c_k = self.perturbation / (k as f64 + 1.0).powf(GAMMA);
+            let delta: Vec<f64> = (0..parameters.len())
+                .map(|_| if self.rng.gen::<bool>() { 1.0 } else { -1.0 })
+                .collect();
+
+            let plus: Vec<f64> = parameters.iter().zip(&delta).map(|(p, d)| p + c_k * d).collect();
+            let minus: Vec<f64> = parameters.iter().zip(&delta).map(|(p, d)| p - c_k * d).collect();
+            let difference = (objective.eval(&plus) - objective.eval(&minus)) / (2.0 * c_k);
+
+            // Δ_i = ±1, so 1/Δ_i = Δ_i
+            for (p, d) in parameters.iter_mut().zip(&delta) {
+                *p -= a_k * difference * d;
+            }
+
+            let value = objective.eval(&parameters);
+            if value < best.1 {
+                best = (parameters.clone(), value);
+            }
+            history.push(best.1);
+        }
+
+        OptimizationResult {
+            parameters: best.0,
+            value: best.1,
+            history,
+            evaluations: objective.evaluations,
+        }
+    }
+}
+
+/// Plain gradient descent with central finite-difference gradients.
+#[derive(Debug, Clone)]
+pub struct GradientDescent {
+    /// The step size applied to the gradient.
+    pub learning_rate: f64,
+    /// The maximum number of iterations.
+    pub max_iterations: usize,
+    /// Stop once the gradient norm falls below this value.
+    pub tolerance: f64,
+    /// The finite-difference step.
+    pub epsilon: f64,
+}
+
+impl Default for GradientDescent {
+    fn default() -> Self {
+        Self {
+            learning_rate: 0.1,
+            max_iterations: 500,
+            tolerance: 1e-6,
+            epsilon: 1e-5,
+        }
+    }
+}
+
+impl Optimizer for GradientDescent {
+    fn minimize(&mut self, objective: &mut dyn FnMut(&[f64]) -> f64, initial: &[f64]) -> OptimizationResult {
+        let mut objective = CountingObjective { objective, evaluations: 0 };
+        let mut parameters = initial.to_vec();
+        let mut value = objective.eval(&parameters);
+        let mut history = Vec::new();
+
+        for _ in 0..self.max_iterations {
+            let gradient: Vec<f64> = (0..parameters.len())
+                .map(|i| {
+                    let mut shifted = parameters.clone();
+                    shifted[i] += self.epsilon;
+                    let forward = objective.eval(&shifted);
+                    shifted[i] -= 2.0 * self.epsilon;
+                    let backward = objective.eval(&shifted);
+                    (forward - backward) / (2.0 * self.epsilon)
+                })
+                .collect();
+
+            if gradient.iter().map(|g| g * g).sum::<f64>().sqrt() < self.tolerance {
+                break;
+            }
+
+            for (p, g) in parameters.iter_mut().zip(&gradient) {
+                *p -= self.learning_rate * g;
+            }
+            value = objective.eval(&parameters);
+            history.push(value);
+        }
+
+        OptimizationResult {
+            parameters,
+            value,
+            history,
+            evaluations: objective.evaluations,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A shifted quadratic bowl with its minimum `1.0` at `(1, -2)`.
+    fn bowl(x: &[f64]) -> f64 {
+        (x[0] - 1.0).powi(2) + 2.0 * (x[1] + 2.0).powi(2) + 1.0
+    }
+
+    #[test]
+    fn test_nelder_mead_finds_minimum() {
+        let result = NelderMead::default().minimize(&mut bowl, &[0.0, 0.0]);
+        assert!((result.value - 1.0).abs() < 1e-6);
+        assert!((result.parameters[0] - 1.0).abs() < 1e-3);
+        assert!((result.parameters[1] + 2.0).abs() < 1e-3);
+        assert!(result.evaluations > 0);
+    }
+
+    #[test]
+    fn test_gradient_descent_finds_minimum() {
+        let result = GradientDescent::default().minimize(&mut bowl, &[0.0, 0.0]);
+        assert!((result.value - 1.0).abs() < 1e-6);
+        assert!(result.history.windows(2).all(|w| w[1] <= w[0] + 1e-12), "History should be non-increasing");
+    }
+
+    #[test]
+    fn test_spsa_approaches_minimum() {
+        let result = Spsa::new(500).with_seed(7).minimize(&mut bowl, &[0.0, 0.0]);
+        assert!((result.value - 1.0).abs() < 1e-2, "SPSA ended at {}", result.value);
+        assert_eq!(result.history.len(), 500);
+    }
+
+    #[test]
+    fn test_spsa_is_reproducible_with_seed() {
+        let first = Spsa::new(50).with_seed(42).minimize(&mut bowl, &[0.0, 0.0]);
+        let second = Spsa::new(50).with_seed(42).minimize(&mut bowl, &[0.0, 0.0]);
+        assert_eq!(first.parameters, second.parameters);
+    }
+}
@@ -0,0 +1,86 @@
+//! Quantum Fourier Transform
+//!
+//! The QFT maps `|x⟩ → 1/√N Σ_y e^{2πi·xy/N} |y⟩` on an `n`-qubit register (`N = 2^n`). It is the
+//! final step of phase estimation, which HHL and quantum counting build on.
+
+use std::f64::consts::PI;
+use num_complex::Complex;
+use crate::circuit::{gates, QuantumCircuit};
+
+/// Adds a controlled phase `diag(1, 1, 1, e^{iθ})` between two qubits.
+fn add_controlled_phase(circuit: &mut QuantumCircuit, a: usize, b: usize, theta: f64) {
+    let one = Complex::new(1.0, 0.0);
+    circuit.add_gate(gates::diagonal(vec![one, one, one, Complex::from_polar(1.0, theta)]), vec![a, b]);
+}
+
+/// Appends the QFT on `qubits` to `circuit`.
+///
+/// `qubits[0]` holds the least-significant bit of the register value, matching the ordering of
+/// statevector indices.
+pub fn add_qft(circuit: &mut QuantumCircuit, qubits: &[usize]) {
+    let n = qubits.len();
+    for i in (0..n).rev() {
+        circuit.add_gate(gates::hadamard(), vec![qubits[i]]);
+        for j in (0..i).rev() {
+            add_controlled_phase(circuit, qubits[j], qubits[i], PI / (1 << (i - j)) as f64);
+        }
+    }
+    for i in 0..n / 2 {
+        circuit.add_gate(gates::swap(), vec![qubits[i], qubits[n - 1 - i]]);
+    }
+}
+
+/// Appends the inverse QFT on `qubits` to `circuit`.
+pub fn add_inverse_qft(circuit: &mut QuantumCircuit, qubits: &[usize]) {
+    circuit.append(&qft_circuit(qubits.len()).inverse(), qubits);
+}
+
+/// Builds a circuit applying the QFT to all of its `num_qubits` qubits.
+pub fn qft_circuit(num_qubits: usize) -> QuantumCircuit {
+    let mut circuit = QuantumCircuit::new(num_qubits);
+    add_qft(&mut circuit, &(0..num_qubits).collect::<Vec<_>>());
+    circuit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Prepares the basis state `|x⟩` and applies `body` to the register.
+    fn from_basis_state(num_qubits: usize, x: usize, body: &QuantumCircuit) -> QuantumCircuit {
+        let mut circuit = QuantumCircuit::new(num_qubits);
+        for qubit in (0..num_qubits).filter(|q| x >> q & 1 == 1) {
+            circuit.add_gate(gates::pauli_x(), vec![qubit]);
+        }
+        circuit.compose(body)
+    }
+
+    #[test]
+    fn test_qft_matches_definition() {
+        let n = 3;
+        let dim = 1usize << n;
+        let qft = qft_circuit(n);
+
+        for x in 0..dim {
+            let state = from_basis_state(n, x, &qft).simulate().statevector;
+            for y in 0..dim {
+                let expected = Complex::from_polar(
+                    1.0 / (dim as f64).sqrt(),
+                    2.0 * PI * (x * y) as f64 / dim as f64,
+                );
+                let actual = state.vector.get(&y).copied().unwrap_or_default();
+                assert!((actual - expected).norm() < 1e-10, "x={}, y={}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_inverse_qft_undoes_qft() {
+        let n = 4;
+        let mut round_trip = qft_circuit(n);
+        add_inverse_qft(&mut round_trip, &(0..n).collect::<Vec<_>>());
+
+        let state = from_basis_state(n, 11, &round_trip).simulate().statevector;
+        assert!((state.vector[&11].norm() - 1.0).abs() < 1e-10);
+    }
+}
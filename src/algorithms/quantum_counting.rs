@@ -0,0 +1,155 @@
+//! Quantum Counting
+//!
+//! Estimates the number of marked items `M` among `N = 2^n` by running phase estimation on the
+//! Grover operator `G = H^⊗n (2|0⟩⟨0| − I) H^⊗n O`. `G` has eigenvalues `e^{±2iθ}` with
+//! `sin²θ = M/N`, so reading `θ` off the counting register gives `M ≈ N sin²θ`.
+
+use std::f64::consts::PI;
+use num_complex::Complex;
+use crate::circuit::gates::{self, Gate};
+use crate::circuit::QuantumCircuit;
+use super::qft::add_inverse_qft;
+
+/// The outcome of a quantum counting run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CountingResult {
+    /// The most probable value of the counting register.
+    pub outcome: usize,
+    /// The estimated number of marked items `N sin²(π · outcome / 2^t)`.
+    pub estimate: f64,
+    /// The estimate rounded to the nearest integer.
+    pub count: usize,
+}
+
+/// Returns the entries of a phase oracle, checking that it is a `Gate::Diagonal`.
+fn oracle_entries(oracle: &Gate) -> &[Complex<f64>] {
+    match oracle {
+        Gate::Diagonal(entries) => entries,
+        _ => panic!("Quantum counting expects a diagonal phase oracle."),
+    }
+}
+
+/// Builds the quantum counting circuit.
+///
+/// The search register occupies qubits `0..n` and the counting register the following
+/// `counting_qubits` qubits. Counting qubit `k` controls `G^{2^k}`; the controlled Grover operator
+/// is assembled from controlled diagonals, since the Hadamards around the reflection cancel when
+/// the control is off.
+///
+/// # Arguments
+/// - `oracle`: A phase oracle over the search register, e.g. from `gates::phase_oracle`.
+/// - `counting_qubits`: The precision `t` of the phase estimate.
+///
+/// # Panics
+/// - If `oracle` is not a `Gate::Diagonal`.
+pub fn quantum_counting_circuit(oracle: &Gate, counting_qubits: usize) -> QuantumCircuit {
+    let entries = oracle_entries(oracle);
+    let n_qubits = entries.len().trailing_zeros() as usize;
+    let search: Vec<usize> = (0..n_qubits).collect();
+
+    let controlled_oracle = gates::controlled(oracle.clone(), 1);
+    let reflection: Vec<Complex<f64>> = (0..entries.len())
+        .map(|x| if x == 0 { Complex::new(1.0, 0.0) } else { Complex::new(-1.0, 0.0) })
+        .collect();
+    let controlled_reflection = gates::controlled(gates::diagonal(reflection), 1);
+
+    let mut circuit = QuantumCircuit::new(n_qubits + counting_qubits);
+    for qubit in 0..n_qubits + counting_qubits {
+        circuit.add_gate(gates::hadamard(), vec![qubit]);
+    }
+
+    for k in 0..counting_qubits {
+        let mut targets = search.clone();
+        targets.push(n_qubits + k);
+
+        for _ in 0..1usize << k {
+            circuit.add_gate(controlled_oracle.clone(), targets.clone());
+            for &qubit in &search {
+                circuit.add_gate(gates::hadamard(), vec![qubit]);
+            }
+            circuit.add_gate(controlled_reflection.clone(), targets.clone());
+            for &qubit in &search {
+                circuit.add_gate(gates::hadamard(), vec![qubit]);
+            }
+        }
+    }
+
+    let counting: Vec<usize> = (n_qubits..n_qubits + counting_qubits).collect();
+    add_inverse_qft(&mut circuit, &counting);
+    circuit
+}
+
+/// Estimates the number of items marked by `oracle`.
+///
+/// # Arguments
+/// - `oracle`: A phase oracle over the search register, e.g. from `gates::phase_oracle`.
+/// - `counting_qubits`: The precision `t` of the phase estimate; the error on `M` shrinks
+///   roughly as `√(MN) / 2^t`.
+///
+/// # Example
+/// ```
+/// use zana::algorithms::quantum_counting;
+/// use zana::circuit::gates;
+///
+/// // 4 out of 16 items are marked
+/// let oracle = gates::phase_oracle(4, |x| x % 4 == 1);
+/// let result = quantum_counting(&oracle, 5);
+/// assert_eq!(result.count, 4);
+/// ```
+pub fn quantum_counting(oracle: &Gate, counting_qubits: usize) -> CountingResult {
+    let n_qubits = oracle_entries(oracle).len().trailing_zeros() as usize;
+    let probabilities = quantum_counting_circuit(oracle, counting_qubits)
+        .simulate().statevector
+        .probabilities();
+
+    // Marginalize over the search register
+    let mut outcomes = vec![0.0; 1 << counting_qubits];
+    for (state, prob) in probabilities {
+        outcomes[state >> n_qubits] += prob;
+    }
+    let outcome = (0..outcomes.len())
+        .max_by(|&a, &b| outcomes[a].total_cmp(&outcomes[b]))
+        .unwrap_or(0);
+
+    let theta = PI * outcome as f64 / (1usize << counting_qubits) as f64;
+    let estimate = (1usize << n_qubits) as f64 * theta.sin().powi(2);
+
+    CountingResult {
+        outcome,
+        estimate,
+        count: estimate.round() as usize,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_marked_items() {
+        for marked in [1, 3, 5] {
+            let oracle = gates::phase_oracle(4, |x| x < marked);
+            assert_eq!(quantum_counting(&oracle, 6).count, marked, "marked = {}", marked);
+        }
+    }
+
+    #[test]
+    fn test_nothing_marked() {
+        let result = quantum_counting(&gates::phase_oracle(3, |_| false), 4);
+        assert_eq!(result.outcome, 0);
+        assert_eq!(result.count, 0);
+    }
+
+    #[test]
+    fn test_everything_marked() {
+        let result = quantum_counting(&gates::phase_oracle(3, |_| true), 4);
+        assert_eq!(result.outcome, 8);
+        assert_eq!(result.count, 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "Quantum counting expects a diagonal phase oracle.")]
+    fn test_rejects_non_diagonal_oracle() {
+        quantum_counting(&gates::hadamard(), 3);
+    }
+}
@@ -0,0 +1,232 @@
+//! Discrete-Time Quantum Walk
+//!
+//! A coined quantum walk on a line or cycle graph. Each step applies a coin operator to the
+//! walker's internal qubit and then a conditional shift: coin `|0⟩` moves one node to the left
+//! and coin `|1⟩` one node to the right. Unlike a classical random walk, the position spreads
+//! ballistically (standard deviation `∝ t` rather than `∝ √t`).
+
+use num_complex::Complex;
+use crate::circuit::gates::{self, Gate};
+
+/// The graph the walker moves on.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Topology {
+    /// An unbounded line; nodes are allocated for the maximum number of steps.
+    Line,
+    /// A cycle of the given number of nodes.
+    Cycle,
+}
+
+/// A discrete-time coined quantum walk.
+#[derive(Debug, Clone)]
+pub struct QuantumWalk {
+    topology: Topology,
+    coin: [[Complex<f64>; 2]; 2],
+    /// Amplitudes per node, stored as `[coin |0⟩ (left), coin |1⟩ (right)]`.
+    amplitudes: Vec<[Complex<f64>; 2]>,
+    max_steps: Option<usize>,
+    steps_taken: usize,
+}
+
+impl QuantumWalk {
+    /// Creates a Hadamard walk on a line with room for `max_steps` steps.
+    ///
+    /// The line has `2 · max_steps + 1` nodes and the walker starts on the middle node
+    /// (index `max_steps`) with the symmetric coin state `(|0⟩ + i|1⟩)/√2`.
+    pub fn line(max_steps: usize) -> Self {
+        Self::with_start(Topology::Line, 2 * max_steps + 1, max_steps, Some(max_steps))
+    }
+
+    /// Creates a Hadamard walk on a cycle of `num_nodes` nodes starting on node `0`.
+    ///
+    /// # Panics
+    /// - If the cycle has fewer than 3 nodes.
+    pub fn cycle(num_nodes: usize) -> Self {
+        assert!(num_nodes >= 3, "A cycle needs at least 3 nodes, got {}.", num_nodes);
+        Self::with_start(Topology::Cycle, num_nodes, 0, None)
+    }
+
+    fn with_start(topology: Topology, num_nodes: usize, start: usize, max_steps: Option<usize>) -> Self {
+        let scale = 1.0 / 2.0_f64.sqrt();
+        let mut amplitudes = vec![[Complex::new(0.0, 0.0); 2]; num_nodes];
+        amplitudes[start] = [Complex::new(scale, 0.0), Complex::new(0.0, scale)];
+
+        let Gate::Single(coin) = gates::hadamard() else {
+            unreachable!("The Hadamard gate is a single-qubit gate");
+        };
+
+        Self {
+            topology,
+            coin,
+            amplitudes,
+            max_steps,
+            steps_taken: 0,
+        }
+    }
+
+    /// Uses a different single-qubit coin operator.
+    ///
+    /// # Panics
+    /// - If `coin` is not a `Gate::Single`.
+    pub fn with_coin(mut self, coin: Gate) -> Self {
+        match coin {
+            Gate::Single(matrix) => self.coin = matrix,
+            _ => panic!("The coin operator must be a single-qubit gate."),
+        }
+        self
+    }
+
+    /// Replaces the walker's initial coin state `α|0⟩ + β|1⟩` on its starting node.
+    ///
+    /// # Panics
+    /// - If the walk has already taken steps.
+    pub fn with_coin_state(mut self, coin_state: [Complex<f64>; 2]) -> Self {
+        assert_eq!(self.steps_taken, 0, "The coin state can only be set before walking.");
+        let norm = (coin_state[0].norm_sqr() + coin_state[1].norm_sqr()).sqrt();
+        let start = self.start_node();
+        self.amplitudes[start] = [coin_state[0] / norm, coin_state[1] / norm];
+        self
+    }
+
+    fn start_node(&self) -> usize {
+        self.max_steps.unwrap_or(0)
+    }
+
+    /// The graph the walk runs on.
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// The number of steps taken so far.
+    pub fn steps_taken(&self) -> usize {
+        self.steps_taken
+    }
+
+    /// Performs a single coin + shift step.
+    ///
+    /// # Panics
+    /// - If a line walk would exceed the number of steps it was created for.
+    pub fn step(&mut self) {
+        if let Some(max_steps) = self.max_steps {
+            assert!(self.steps_taken < max_steps, "Line walk exceeded its {} allocated steps.", max_steps);
+        }
+
+        let num_nodes = self.amplitudes.len();
+        let mut next = vec![[Complex::new(0.0, 0.0); 2]; num_nodes];
+
+        for (node, &[left, right]) in self.amplitudes.iter().enumerate() {
+            let coin_left = self.coin[0][0] * left + self.coin[0][1] * right;
+            let coin_right = self.coin[1][0] * left + self.coin[1][1] * right;
+
+            // On a line the walker never reaches the ends within its step budget
+            next[(node + num_nodes - 1) % num_nodes][0] += coin_left;
+            next[(node + 1) % num_nodes][1] += coin_right;
+        }
+
+        self.amplitudes = next;
+        self.steps_taken += 1;
+    }
+
+    /// Performs `steps` coin + shift steps.
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    /// Returns the probability of finding the walker on each node.
+    ///
+    /// The result is a list of `(node, probability)` pairs covering every node, the same shape the
+    /// heatmap visualizations consume. On a line, node `max_steps` is the starting position.
+    pub fn position_distribution(&self) -> Vec<(usize, f64)> {
+        self.amplitudes
+            .iter()
+            .enumerate()
+            .map(|(node, [left, right])| (node, left.norm_sqr() + right.norm_sqr()))
+            .collect()
+    }
+
+    /// Returns the variance of the walker's displacement from its starting node.
+    ///
+    /// Only meaningful on a line, where displacement is unambiguous.
+    pub fn displacement_variance(&self) -> f64 {
+        let start = self.start_node() as f64;
+        let distribution = self.position_distribution();
+        let mean: f64 = distribution.iter().map(|&(node, p)| (node as f64 - start) * p).sum();
+        distribution
+            .iter()
+            .map(|&(node, p)| (node as f64 - start - mean).powi(2) * p)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total_probability(walk: &QuantumWalk) -> f64 {
+        walk.position_distribution().iter().map(|&(_, p)| p).sum()
+    }
+
+    #[test]
+    fn test_first_step_splits_evenly() {
+        let mut walk = QuantumWalk::line(1);
+        walk.step();
+        let distribution = walk.position_distribution();
+        assert!((distribution[0].1 - 0.5).abs() < 1e-10);
+        assert!(distribution[1].1.abs() < 1e-10);
+        assert!((distribution[2].1 - 0.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_symmetric_walk_is_symmetric() {
+        let steps = 20;
+        let mut walk = QuantumWalk::line(steps);
+        walk.run(steps);
+
+        let distribution = walk.position_distribution();
+        for offset in 1..=steps {
+            let left = distribution[steps - offset].1;
+            let right = distribution[steps + offset].1;
+            assert!((left - right).abs() < 1e-10, "Asymmetry at offset {}", offset);
+        }
+        assert!((total_probability(&walk) - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_spreads_ballistically() {
+        let steps = 40;
+        let mut walk = QuantumWalk::line(steps);
+        walk.run(steps);
+
+        // A classical random walk has variance `steps`; the Hadamard walk grows ∝ steps²
+        assert!(walk.displacement_variance() > 5.0 * steps as f64);
+    }
+
+    #[test]
+    fn test_custom_coin_state_biases_walk() {
+        let steps = 10;
+        let mut walk = QuantumWalk::line(steps).with_coin_state([Complex::new(1.0, 0.0), Complex::new(0.0, 0.0)]);
+        walk.run(steps);
+
+        let distribution = walk.position_distribution();
+        let left: f64 = distribution[..steps].iter().map(|&(_, p)| p).sum();
+        let right: f64 = distribution[steps + 1..].iter().map(|&(_, p)| p).sum();
+        assert!(left > right, "Coin |0⟩ should bias the Hadamard walk to the left");
+    }
+
+    #[test]
+    fn test_cycle_conserves_probability() {
+        let mut walk = QuantumWalk::cycle(7).with_coin(gates::rotation_y(1.0));
+        walk.run(50);
+        assert_eq!(walk.position_distribution().len(), 7);
+        assert!((total_probability(&walk) - 1.0).abs() < 1e-10);
+        assert_eq!(walk.steps_taken(), 50);
+    }
+
+    #[test]
+    #[should_panic(expected = "Line walk exceeded its 2 allocated steps.")]
+    fn test_line_step_budget() {
+        QuantumWalk::line(2).run(3);
+    }
+}
@@ -0,0 +1,80 @@
+//! Superdense Coding
+//!
+//! Sends two classical bits by transmitting a single qubit of a pre-shared Bell pair.
+
+use crate::algorithms::add_cnot;
+use crate::circuit::{gates, QuantumCircuit};
+
+/// Alice's half of the Bell pair, which she encodes and sends.
+pub const ALICE_QUBIT: usize = 0;
+/// Bob's half of the Bell pair.
+pub const BOB_QUBIT: usize = 1;
+
+/// Builds the full superdense coding circuit for two classical bits.
+///
+/// The circuit creates the Bell pair `(|00⟩ + |11⟩)/√2`, lets Alice apply `Z` if `two_bits[0]` is
+/// set and `X` if `two_bits[1]` is set, and finally lets Bob undo the Bell basis so that
+/// qubit 0 holds `two_bits[0]` and qubit 1 holds `two_bits[1]`.
+///
+/// # Panics
+/// - If either bit is not `0` or `1`.
+pub fn superdense_coding_circuit(two_bits: [u8; 2]) -> QuantumCircuit {
+    assert!(two_bits.iter().all(|&bit| bit <= 1), "Superdense coding encodes bits, got {:?}.", two_bits);
+
+    let mut circuit = QuantumCircuit::new(2);
+
+    // Shared Bell pair
+    circuit.add_gate(gates::hadamard(), vec![ALICE_QUBIT]);
+    add_cnot(&mut circuit, ALICE_QUBIT, BOB_QUBIT);
+
+    // Alice encodes her two bits on her qubit
+    if two_bits[0] == 1 {
+        circuit.add_gate(gates::pauli_z(), vec![ALICE_QUBIT]);
+    }
+    if two_bits[1] == 1 {
+        circuit.add_gate(gates::pauli_x(), vec![ALICE_QUBIT]);
+    }
+
+    // Bob decodes in the Bell basis
+    add_cnot(&mut circuit, ALICE_QUBIT, BOB_QUBIT);
+    circuit.add_gate(gates::hadamard(), vec![ALICE_QUBIT]);
+
+    circuit
+}
+
+/// Encodes two bits into one qubit of a Bell pair, decodes them, and returns the recovered bits.
+///
+/// # Example
+/// ```
+/// use zana::algorithms::superdense_coding;
+///
+/// assert_eq!(superdense_coding([1, 0]), [1, 0]);
+/// ```
+pub fn superdense_coding(two_bits: [u8; 2]) -> [u8; 2] {
+    let mut statevector = superdense_coding_circuit(two_bits).simulate().statevector;
+    [statevector.measure(ALICE_QUBIT), statevector.measure(BOB_QUBIT)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovers_all_messages() {
+        for message in [[0, 0], [0, 1], [1, 0], [1, 1]] {
+            assert_eq!(superdense_coding(message), message);
+        }
+    }
+
+    #[test]
+    fn test_decoded_state_is_deterministic() {
+        let probabilities = superdense_coding_circuit([1, 1]).simulate().statevector.probabilities();
+        assert!((probabilities[&0b11] - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    #[should_panic(expected = "Superdense coding encodes bits")]
+    fn test_rejects_non_bits() {
+        superdense_coding_circuit([2, 0]);
+    }
+}
@@ -0,0 +1,169 @@
+//! Swap Test
+//!
+//! Estimates the overlap `|⟨a|b⟩|²` of two states. An ancilla in `|+⟩` controls a SWAP of the two
+//! registers and is then rotated back with a Hadamard; it reads `0` with probability
+//! `(1 + |⟨a|b⟩|²) / 2`.
+
+use num_complex::Complex;
+use crate::circuit::{gates, QuantumCircuit};
+use super::add_cnot;
+
+/// The qubit holding the control ancilla.
+pub const ANCILLA_QUBIT: usize = 0;
+
+/// The number of shots used by [`swap_test`].
+pub const DEFAULT_SHOTS: usize = 1024;
+
+/// Adds a controlled SWAP (Fredkin) gate.
+///
+/// Uses `CSWAP = CNOT(b→a) · Toffoli(c, a → b) · CNOT(b→a)`, with the Toffoli written as a
+/// controlled-controlled-Z between Hadamards on its target.
+fn add_controlled_swap(circuit: &mut QuantumCircuit, control: usize, a: usize, b: usize) {
+    let one = Complex::new(1.0, 0.0);
+    let mut ccz = vec![one; 8];
+    ccz[7] = -one;
+
+    add_cnot(circuit, b, a);
+    circuit.add_gate(gates::hadamard(), vec![b]);
+    circuit.add_gate(gates::diagonal(ccz), vec![control, a, b]);
+    circuit.add_gate(gates::hadamard(), vec![b]);
+    add_cnot(circuit, b, a);
+}
+
+/// Builds the swap test circuit for the states prepared by `circuit_a` and `circuit_b`.
+///
+/// The ancilla is qubit `0`, followed by the `n` qubits of the first register and the `n` qubits
+/// of the second. Measurements in the preparation circuits are kept, on the mapped qubits.
+///
+/// # Panics
+/// - If the two circuits act on different numbers of qubits.
+pub fn swap_test_circuit(circuit_a: &QuantumCircuit, circuit_b: &QuantumCircuit) -> QuantumCircuit {
+    assert_eq!(
+        circuit_a.qubits, circuit_b.qubits,
+        "Swap test requires circuits of equal width."
+    );
+    let n = circuit_a.qubits;
+
+    let mut circuit = QuantumCircuit::new(2 * n + 1);
+    circuit.append(circuit_a, &(1..=n).collect::<Vec<_>>());
+    circuit.append(circuit_b, &(n + 1..=2 * n).collect::<Vec<_>>());
+
+    circuit.add_gate(gates::hadamard(), vec![ANCILLA_QUBIT]);
+    for qubit in 1..=n {
+        add_controlled_swap(&mut circuit, ANCILLA_QUBIT, qubit, qubit + n);
+    }
+    circuit.add_gate(gates::hadamard(), vec![ANCILLA_QUBIT]);
+
+    circuit
+}
+
+/// Converts the probability of the ancilla reading `0` into `|⟨a|b⟩|²`.
+fn overlap_from_probability(prob_0: f64) -> f64 {
+    (2.0 * prob_0 - 1.0).clamp(0.0, 1.0)
+}
+
+/// Returns the exact overlap `|⟨a|b⟩|²` from the ancilla's outcome probabilities.
+pub fn swap_test_exact(circuit_a: &QuantumCircuit, circuit_b: &QuantumCircuit) -> f64 {
+    let prob_0 = swap_test_circuit(circuit_a, circuit_b)
+        .simulate().statevector
+        .probabilities()
+        .into_iter()
+        .filter(|&(state, _)| state & (1 << ANCILLA_QUBIT) == 0)
+        .map(|(_, prob)| prob)
+        .sum();
+    overlap_from_probability(prob_0)
+}
+
+/// Estimates `|⟨a|b⟩|²` by measuring the ancilla over `shots` runs.
+///
+/// # Panics
+/// - If `shots` is `0`.
+pub fn swap_test_with_shots(circuit_a: &QuantumCircuit, circuit_b: &QuantumCircuit, shots: usize) -> f64 {
+    assert!(shots > 0, "Swap test needs at least one shot.");
+    let statevector = swap_test_circuit(circuit_a, circuit_b).simulate().statevector;
+
+    let zeros = (0..shots)
+        .filter(|_| statevector.clone().measure(ANCILLA_QUBIT) == 0)
+        .count();
+    overlap_from_probability(zeros as f64 / shots as f64)
+}
+
+/// Estimates `|⟨a|b⟩|²` from [`DEFAULT_SHOTS`] measurements of the swap test ancilla.
+///
+/// # Example
+/// ```
+/// use zana::algorithms::swap_test;
+/// use zana::circuit::{gates, QuantumCircuit};
+///
+/// let zero = QuantumCircuit::new(1);
+/// let mut one = QuantumCircuit::new(1);
+/// one.add_gate(gates::pauli_x(), vec![0]);
+///
+/// // Orthogonal states have zero overlap, up to shot noise
+/// assert!(swap_test(&zero, &one) < 0.2);
+/// ```
+pub fn swap_test(circuit_a: &QuantumCircuit, circuit_b: &QuantumCircuit) -> f64 {
+    swap_test_with_shots(circuit_a, circuit_b, DEFAULT_SHOTS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::Measurement;
+
+    fn plus_state(n: usize) -> QuantumCircuit {
+        let mut circuit = QuantumCircuit::new(n);
+        for qubit in 0..n {
+            circuit.add_gate(gates::hadamard(), vec![qubit]);
+        }
+        circuit
+    }
+
+    #[test]
+    fn test_identical_states() {
+        let mut a = QuantumCircuit::new(2);
+        a.add_gate(gates::rotation_y(0.7), vec![0]);
+        a.add_gate(gates::rotation_x(1.3), vec![1]);
+        assert!((swap_test_exact(&a, &a) - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_orthogonal_states() {
+        let zero = QuantumCircuit::new(2);
+        let mut flipped = QuantumCircuit::new(2);
+        flipped.add_gate(gates::pauli_x(), vec![1]);
+        assert!(swap_test_exact(&zero, &flipped).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_partial_overlap() {
+        // |⟨00|++⟩|² = 1/4
+        let overlap = swap_test_exact(&QuantumCircuit::new(2), &plus_state(2));
+        assert!((overlap - 0.25).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_sampled_estimate() {
+        // |⟨0|+⟩|² = 1/2
+        let overlap = swap_test_with_shots(&QuantumCircuit::new(1), &plus_state(1), 4000);
+        assert!((overlap - 0.5).abs() < 0.1, "Estimated overlap {}", overlap);
+    }
+
+    #[test]
+    fn test_keeps_measurements_of_preparations() {
+        // Measuring |+⟩ leaves |0⟩ or |1⟩, whose overlap with |0⟩ is 1 or 0.
+        let mut measured = plus_state(1);
+        measured.add_measurement(0, 0);
+        let circuit = swap_test_circuit(&measured, &QuantumCircuit::new(1));
+        assert_eq!(circuit.measurements, [Measurement { qubit: 1, classical_bit: 0, position: 1 }]);
+
+        let overlap = swap_test_exact(&measured, &QuantumCircuit::new(1));
+        assert!(overlap.abs() < 1e-10 || (overlap - 1.0).abs() < 1e-10, "Overlap {}", overlap);
+    }
+
+    #[test]
+    #[should_panic(expected = "Swap test requires circuits of equal width.")]
+    fn test_rejects_mismatched_widths() {
+        swap_test_exact(&QuantumCircuit::new(1), &QuantumCircuit::new(2));
+    }
+}
@@ -0,0 +1,150 @@
+//! Quantum Teleportation
+//!
+//! Transfers the state of qubit 0 onto qubit 2 using a shared Bell pair (qubits 1 and 2), two
+//! mid-circuit measurements, and classically-conditioned X/Z corrections.
+
+use crate::algorithms::add_cnot;
+use crate::circuit::observable::bloch_vector;
+use crate::circuit::statevector::Statevector;
+use crate::circuit::{gates, QuantumCircuit};
+
+/// The qubit holding the state to teleport.
+pub const INPUT_QUBIT: usize = 0;
+/// Alice's half of the Bell pair.
+pub const ALICE_QUBIT: usize = 1;
+/// Bob's half of the Bell pair, which receives the teleported state.
+pub const BOB_QUBIT: usize = 2;
+
+/// The outcome of a teleportation run.
+#[derive(Debug, Clone)]
+pub struct TeleportationResult {
+    /// The classical bits sent to Bob: `[input qubit, Alice's qubit]` measurement results.
+    pub measurements: [u8; 2],
+    /// The Bloch vector of the state that was teleported.
+    pub input_bloch: [f64; 3],
+    /// The Bloch vector of Bob's qubit after the corrections.
+    pub output_bloch: [f64; 3],
+    /// The final three-qubit state.
+    pub statevector: Statevector,
+}
+
+impl TeleportationResult {
+    /// Returns the Euclidean distance between the input and output Bloch vectors.
+    pub fn bloch_distance(&self) -> f64 {
+        self.input_bloch
+            .iter()
+            .zip(&self.output_bloch)
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Returns `true` if Bob's qubit matches the input state within `tolerance`.
+    pub fn is_faithful(&self, tolerance: f64) -> bool {
+        self.bloch_distance() < tolerance
+    }
+}
+
+/// Builds the unitary part of the teleportation protocol.
+///
+/// The circuit prepares the input state on qubit 0 with `input_prep`, creates a Bell pair on
+/// qubits 1 and 2, and rotates qubits 0 and 1 into the Bell basis ready for measurement.
+///
+/// # Panics
+/// - If `input_prep` is not a single-qubit circuit.
+pub fn teleportation_circuit(input_prep: &QuantumCircuit) -> QuantumCircuit {
+    assert_eq!(input_prep.qubits, 1, "Teleportation input must be prepared by a single-qubit circuit.");
+
+    let mut circuit = QuantumCircuit::new(3);
+    circuit.append(input_prep, &[INPUT_QUBIT]);
+
+    // Shared Bell pair between Alice and Bob
+    circuit.add_gate(gates::hadamard(), vec![ALICE_QUBIT]);
+    add_cnot(&mut circuit, ALICE_QUBIT, BOB_QUBIT);
+
+    // Bell-basis rotation on Alice's side
+    add_cnot(&mut circuit, INPUT_QUBIT, ALICE_QUBIT);
+    circuit.add_gate(gates::hadamard(), vec![INPUT_QUBIT]);
+
+    circuit
+}
+
+/// Teleports the state prepared by `input_prep` and verifies the result.
+///
+/// Alice measures qubits 0 and 1 mid-circuit; Bob applies `X` if Alice's qubit read `1` and then
+/// `Z` if the input qubit read `1`.
+///
+/// # Example
+/// ```
+/// use zana::algorithms::teleportation::teleport;
+/// use zana::circuit::{gates, QuantumCircuit};
+///
+/// let mut input = QuantumCircuit::new(1);
+/// input.add_gate(gates::rotation_y(1.2), vec![0]);
+///
+/// let result = teleport(&input);
+/// assert!(result.is_faithful(1e-9));
+/// ```
+pub fn teleport(input_prep: &QuantumCircuit) -> TeleportationResult {
+    let input_bloch = bloch_vector(&input_prep.simulate().statevector, 0);
+    let mut statevector = teleportation_circuit(input_prep).simulate().statevector;
+
+    let m_input = statevector.measure(INPUT_QUBIT);
+    let m_alice = statevector.measure(ALICE_QUBIT);
+
+    // Classically-conditioned corrections on Bob's qubit
+    if m_alice == 1 {
+        statevector.apply_gate(gates::pauli_x(), &[BOB_QUBIT]);
+    }
+    if m_input == 1 {
+        statevector.apply_gate(gates::pauli_z(), &[BOB_QUBIT]);
+    }
+
+    TeleportationResult {
+        measurements: [m_input, m_alice],
+        input_bloch,
+        output_bloch: bloch_vector(&statevector, BOB_QUBIT),
+        statevector,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prepared(gates: Vec<gates::Gate>) -> QuantumCircuit {
+        let mut circuit = QuantumCircuit::new(1);
+        for gate in gates {
+            circuit.add_gate(gate, vec![0]);
+        }
+        circuit
+    }
+
+    #[test]
+    fn test_teleports_basis_states() {
+        let zero = teleport(&prepared(vec![]));
+        assert!(zero.is_faithful(1e-9));
+        assert!((zero.output_bloch[2] - 1.0).abs() < 1e-9);
+
+        let one = teleport(&prepared(vec![gates::pauli_x()]));
+        assert!(one.is_faithful(1e-9));
+        assert!((one.output_bloch[2] + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_teleports_arbitrary_states() {
+        // Repeat to cover all four measurement branches
+        for _ in 0..20 {
+            let input = prepared(vec![gates::rotation_y(0.8), gates::rotation_z(0.6)]);
+            let result = teleport(&input);
+            assert!(result.is_faithful(1e-9), "Distance {} for {:?}", result.bloch_distance(), result.measurements);
+        }
+    }
+
+    #[test]
+    fn test_bob_qubit_is_disentangled() {
+        let result = teleport(&prepared(vec![gates::hadamard()]));
+        let length: f64 = result.output_bloch.iter().map(|c| c * c).sum::<f64>().sqrt();
+        assert!((length - 1.0).abs() < 1e-9, "Bob's qubit should be in a pure state");
+    }
+}
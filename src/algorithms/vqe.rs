@@ -0,0 +1,145 @@
+//! Variational Quantum Eigensolver
+//!
+//! VQE approximates the ground-state energy of a Hamiltonian by minimizing
+//! `E(θ) = ⟨ψ(θ)|H|ψ(θ)⟩` over the parameters of an ansatz with a classical optimizer.
+
+use crate::algorithms::ansatz::Ansatz;
+use crate::algorithms::optimizers::Optimizer;
+use crate::circuit::observable::Hamiltonian;
+
+/// The starting value used for every parameter by [`vqe`].
+///
+/// A small non-zero offset avoids starting exactly on a stationary point of the ansatz.
+const DEFAULT_INITIAL_PARAMETER: f64 = 0.1;
+
+/// The outcome of a VQE run.
+#[derive(Debug, Clone)]
+pub struct VqeResult {
+    /// The lowest energy found.
+    pub energy: f64,
+    /// The ansatz parameters achieving `energy`.
+    pub parameters: Vec<f64>,
+    /// The best energy after each optimizer iteration.
+    pub history: Vec<f64>,
+    /// The number of energy evaluations (circuit simulations).
+    pub evaluations: usize,
+}
+
+/// Computes the energy `⟨ψ(θ)|H|ψ(θ)⟩` of the ansatz state for the given parameters.
+pub fn energy(hamiltonian: &Hamiltonian, ansatz: &dyn Ansatz, parameters: &[f64]) -> f64 {
+    ansatz.build(parameters).expectation(hamiltonian)
+}
+
+/// Runs VQE starting from a small uniform parameter vector.
+///
+/// # Arguments
+/// - `hamiltonian`: The observable whose ground-state energy is sought.
+/// - `ansatz`: The parameterized trial circuit.
+/// - `optimizer`: The classical minimizer (e.g. `NelderMead`, `Spsa`, `GradientDescent`).
+///
+/// # Example
+/// ```
+/// use zana::algorithms::ansatz::CustomAnsatz;
+/// use zana::algorithms::optimizers::NelderMead;
+/// use zana::algorithms::vqe::vqe;
+/// use zana::circuit::observable::{Hamiltonian, Pauli, PauliString};
+/// use zana::circuit::{gates, QuantumCircuit};
+///
+/// let hamiltonian = Hamiltonian::new().with_term(1.0, PauliString::new(vec![(0, Pauli::Z)]));
+/// let ansatz = CustomAnsatz::new(1, 1, |params: &[f64]| {
+///     let mut circuit = QuantumCircuit::new(1);
+///     circuit.add_gate(gates::rotation_y(params[0]), vec![0]);
+///     circuit
+/// });
+///
+/// let result = vqe(&hamiltonian, &ansatz, &mut NelderMead::default());
+/// assert!((result.energy + 1.0).abs() < 1e-6);
+/// ```
+pub fn vqe(hamiltonian: &Hamiltonian, ansatz: &dyn Ansatz, optimizer: &mut dyn Optimizer) -> VqeResult {
+    let initial = vec![DEFAULT_INITIAL_PARAMETER; ansatz.num_parameters()];
+    vqe_with_initial_parameters(hamiltonian, ansatz, optimizer, &initial)
+}
+
+/// Runs VQE starting from the given parameters.
+pub fn vqe_with_initial_parameters(
+    hamiltonian: &Hamiltonian,
+    ansatz: &dyn Ansatz,
+    optimizer: &mut dyn Optimizer,
+    initial_parameters: &[f64],
+) -> VqeResult {
+    let mut objective = |parameters: &[f64]| energy(hamiltonian, ansatz, parameters);
+    let result = optimizer.minimize(&mut objective, initial_parameters);
+
+    VqeResult {
+        energy: result.value,
+        parameters: result.parameters,
+        history: result.history,
+        evaluations: result.evaluations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::add_cnot;
+    use crate::algorithms::ansatz::CustomAnsatz;
+    use crate::algorithms::optimizers::{GradientDescent, NelderMead, Spsa};
+    use crate::circuit::observable::{Pauli, PauliString};
+    use crate::circuit::{gates, QuantumCircuit};
+
+    /// `H = Z + 0.5·X`, with ground-state energy `−√1.25`.
+    fn single_qubit_hamiltonian() -> Hamiltonian {
+        Hamiltonian::new()
+            .with_term(1.0, PauliString::new(vec![(0, Pauli::Z)]))
+            .with_term(0.5, PauliString::new(vec![(0, Pauli::X)]))
+    }
+
+    fn ry_ansatz() -> CustomAnsatz<impl Fn(&[f64]) -> QuantumCircuit> {
+        CustomAnsatz::new(1, 1, |params: &[f64]| {
+            let mut circuit = QuantumCircuit::new(1);
+            circuit.add_gate(gates::rotation_y(params[0]), vec![0]);
+            circuit
+        })
+    }
+
+    #[test]
+    fn test_vqe_with_nelder_mead() {
+        let result = vqe(&single_qubit_hamiltonian(), &ry_ansatz(), &mut NelderMead::default());
+        assert!((result.energy + 1.25_f64.sqrt()).abs() < 1e-6, "Energy {}", result.energy);
+        assert!(!result.history.is_empty());
+    }
+
+    #[test]
+    fn test_vqe_with_gradient_descent() {
+        let result = vqe(&single_qubit_hamiltonian(), &ry_ansatz(), &mut GradientDescent::default());
+        assert!((result.energy + 1.25_f64.sqrt()).abs() < 1e-6, "Energy {}", result.energy);
+    }
+
+    #[test]
+    fn test_vqe_with_spsa() {
+        let result = vqe(&single_qubit_hamiltonian(), &ry_ansatz(), &mut Spsa::new(300).with_seed(3));
+        assert!((result.energy + 1.25_f64.sqrt()).abs() < 1e-2, "Energy {}", result.energy);
+    }
+
+    #[test]
+    fn test_vqe_two_qubit_transverse_ising() {
+        // H = Z0 Z1 + X0 + X1, ground-state energy −√5
+        let hamiltonian = Hamiltonian::new()
+            .with_term(1.0, PauliString::new(vec![(0, Pauli::Z), (1, Pauli::Z)]))
+            .with_term(1.0, PauliString::new(vec![(0, Pauli::X)]))
+            .with_term(1.0, PauliString::new(vec![(1, Pauli::X)]));
+        let ansatz = CustomAnsatz::new(2, 4, |params: &[f64]| {
+            let mut circuit = QuantumCircuit::new(2);
+            circuit.add_gate(gates::rotation_y(params[0]), vec![0]);
+            circuit.add_gate(gates::rotation_y(params[1]), vec![1]);
+            add_cnot(&mut circuit, 0, 1);
+            circuit.add_gate(gates::rotation_y(params[2]), vec![0]);
+            circuit.add_gate(gates::rotation_y(params[3]), vec![1]);
+            circuit
+        });
+
+        let result = vqe(&hamiltonian, &ansatz, &mut NelderMead::default());
+        assert!((result.energy + 5.0_f64.sqrt()).abs() < 1e-4, "Energy {}", result.energy);
+        assert!((energy(&hamiltonian, &ansatz, &result.parameters) - result.energy).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,51 @@
+//! Simulator Backends
+//!
+//! The simulators a circuit can run on, chosen per job or in the `[simulator]` section of a
+//! [`Config`](crate::config::Config).
+
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+/// A simulator for circuits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// The sparse [`Statevector`](crate::circuit::statevector::Statevector), which stores only
+    /// the nonzero amplitudes.
+    #[default]
+    Sparse,
+    /// The dense [`DenseStatevector`](crate::circuit::dense::DenseStatevector), which stores
+    /// every amplitude and is faster once most of them are nonzero.
+    Dense,
+}
+
+impl Backend {
+    /// Every backend, in declaration order.
+    pub const ALL: [Backend; 2] = [Backend::Sparse, Backend::Dense];
+
+    /// The name of the backend in configuration files and results, e.g. `"sparse"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Backend::Sparse => "sparse",
+            Backend::Dense => "dense",
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|backend| backend.name() == name)
+            .ok_or_else(|| format!("unknown backend '{}'", name))
+    }
+}
@@ -0,0 +1,157 @@
+//! Circuit Composition
+//!
+//! Circuits can be built from smaller ones: a block such as a QFT is built once on its own
+//! qubits and reused wherever it is needed. [`QuantumCircuit::append`] adds a circuit's gates
+//! and measurements onto chosen qubits of another, [`QuantumCircuit::compose`] runs one circuit
+//! after another on the same qubits, and [`QuantumCircuit::tensor`] places two circuits side by
+//! side.
+//!
+//! ```
+//! use zana::algorithms::qft::qft_circuit;
+//! use zana::circuit::{gates, QuantumCircuit};
+//!
+//! let qft = qft_circuit(2);
+//! let mut circuit = QuantumCircuit::new(4);
+//! circuit.add_gate(gates::pauli_x(), vec![3]);
+//! // Qubit 0 of the block acts on qubit 3 of the circuit, qubit 1 on qubit 1.
+//! circuit.append(&qft, &[3, 1]);
+//! circuit.append(&qft.inverse(), &[3, 1]);
+//!
+//! let state = circuit.simulate().statevector;
+//! assert!((state.vector[&0b1000].norm() - 1.0).abs() < 1e-10);
+//! ```
+
+use crate::circuit::error::CircuitError;
+use crate::circuit::measurement::Measurement;
+use crate::circuit::QuantumCircuit;
+
+impl QuantumCircuit {
+    /// Appends the gates and measurements of `other`, with its qubit `i` acting on qubit
+    /// `qubit_mapping[i]` of this circuit.
+    ///
+    /// # Panics
+    /// If [`QuantumCircuit::try_append`] would return an error.
+    pub fn append(&mut self, other: &QuantumCircuit, qubit_mapping: &[usize]) {
+        if let Err(error) = self.try_append(other, qubit_mapping) {
+            panic!("{}", error);
+        }
+    }
+
+    /// Appends the gates and measurements of `other`, with its qubit `i` acting on qubit
+    /// `qubit_mapping[i]` of this circuit, or returns why it cannot be appended.
+    ///
+    /// Measurements keep their classical bits, and the classical register grows to hold them.
+    ///
+    /// # Errors
+    /// - [`CircuitError::QubitMapping`] if the mapping does not name one qubit for each qubit
+    ///   of `other`.
+    /// - [`CircuitError::QubitOutOfRange`] or [`CircuitError::DuplicateQubit`] if the mapping
+    ///   does not pass [`QuantumCircuit::check_qubits`].
+    pub fn try_append(&mut self, other: &QuantumCircuit, qubit_mapping: &[usize]) -> Result<(), CircuitError> {
+        if qubit_mapping.len() != other.qubits {
+            return Err(CircuitError::QubitMapping { expected: other.qubits, actual: qubit_mapping.len() });
+        }
+        self.check_qubits(qubit_mapping)?;
+        self.extend_from(other, qubit_mapping, 0);
+        Ok(())
+    }
+
+    /// Returns this circuit followed by `other`, acting on the same qubits.
+    ///
+    /// # Panics
+    /// If [`QuantumCircuit::try_compose`] would return an error.
+    pub fn compose(&self, other: &QuantumCircuit) -> QuantumCircuit {
+        self.try_compose(other).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Returns this circuit followed by `other`, with qubit `i` of `other` acting on qubit `i`,
+    /// or returns why they cannot be composed. The result is not signed.
+    ///
+    /// # Errors
+    /// [`CircuitError::QubitOutOfRange`] if `other` has more qubits than this circuit.
+    pub fn try_compose(&self, other: &QuantumCircuit) -> Result<QuantumCircuit, CircuitError> {
+        let mut circuit = QuantumCircuit { provenance: None, ..self.clone() };
+        circuit.try_append(other, &(0..other.qubits).collect::<Vec<_>>())?;
+        Ok(circuit)
+    }
+
+    /// Returns the circuit running this one and `other` side by side, with the qubits and
+    /// classical bits of `other` placed after those of this circuit. The result is not signed.
+    pub fn tensor(&self, other: &QuantumCircuit) -> QuantumCircuit {
+        let mut circuit = QuantumCircuit::new(self.qubits + other.qubits);
+        circuit.extend_from(self, &(0..self.qubits).collect::<Vec<_>>(), 0);
+        circuit.extend_from(other, &(self.qubits..circuit.qubits).collect::<Vec<_>>(), self.classical_bits);
+        circuit.classical_bits = self.classical_bits + other.classical_bits;
+        circuit
+    }
+
+    /// Adds the gates and measurements of `other` through a mapping already checked against
+    /// this circuit, shifting its classical bits by `classical_offset`.
+    fn extend_from(&mut self, other: &QuantumCircuit, qubit_mapping: &[usize], classical_offset: usize) {
+        let offset = self.gates.len();
+        self.gates.extend(other.gates.iter().map(|(gate, qubits)| {
+            (gate.clone(), qubits.iter().map(|&qubit| qubit_mapping[qubit]).collect())
+        }));
+        self.measurements.extend(other.measurements.iter().map(|measurement| Measurement {
+            qubit: qubit_mapping[measurement.qubit],
+            classical_bit: measurement.classical_bit + classical_offset,
+            position: measurement.position + offset,
+        }));
+        self.classical_bits = self.classical_bits.max(other.classical_bits + classical_offset);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::qft::qft_circuit;
+    use crate::circuit::gates;
+
+    #[test]
+    fn test_append_reuses_block() {
+        let mut bell = QuantumCircuit::new(2);
+        bell.add_gate(gates::hadamard(), vec![0]);
+        bell.add_gate(gates::cnot(), vec![1, 0]);
+        bell.add_measurement(1, 0);
+
+        let mut circuit = QuantumCircuit::new(4);
+        circuit.add_gate(gates::pauli_x(), vec![0]);
+        circuit.append(&bell, &[3, 1]);
+        assert_eq!(circuit.gates[1].1, [3]);
+        assert_eq!(circuit.gates[2].1, [1, 3]);
+        assert_eq!(circuit.measurements, [Measurement { qubit: 1, classical_bit: 0, position: 3 }]);
+        assert_eq!(circuit.classical_bits, 1);
+
+        assert_eq!(circuit.try_append(&bell, &[0]), Err(CircuitError::QubitMapping { expected: 2, actual: 1 }));
+        assert_eq!(circuit.try_append(&bell, &[2, 2]), Err(CircuitError::DuplicateQubit { qubit: 2, position: 1 }));
+        assert_eq!(circuit.try_append(&bell, &[0, 4]), Err(CircuitError::QubitOutOfRange { qubit: 4, qubits: 4 }));
+        assert_eq!(circuit.gates.len(), 3);
+    }
+
+    #[test]
+    fn test_compose_with_inverse_is_identity() {
+        let mut circuit = QuantumCircuit::new(3);
+        circuit.add_gate(gates::pauli_x(), vec![1]);
+        let qft = qft_circuit(3);
+        let state = circuit.compose(&qft).compose(&qft.inverse()).simulate().statevector;
+        assert_eq!(state.vector.len(), 1);
+        assert!((state.vector[&0b010].norm() - 1.0).abs() < 1e-10);
+        assert!(qft_circuit(2).try_compose(&qft).is_err());
+    }
+
+    #[test]
+    fn test_tensor_places_circuits_side_by_side() {
+        let mut first = QuantumCircuit::new(1);
+        first.add_gate(gates::pauli_x(), vec![0]);
+        first.add_measurement(0, 1);
+        let mut second = QuantumCircuit::new(2);
+        second.add_gate(gates::pauli_x(), vec![1]);
+        second.add_measurement(1, 0);
+
+        let circuit = first.tensor(&second);
+        assert_eq!(circuit.qubits, 3);
+        assert_eq!(circuit.classical_bits, 3);
+        assert_eq!(circuit.measurements[1], Measurement { qubit: 2, classical_bit: 2, position: 2 });
+        assert_eq!(circuit.simulate().bitstring(), "110");
+    }
+}
@@ -0,0 +1,278 @@
+//! Dense Statevector
+//!
+//! [`DenseStatevector`] stores all `2^n` amplitudes in a `Vec`, indexed by basis state. The
+//! sparse [`Statevector`] stores only the nonzero amplitudes in a `HashMap`, which wins while a
+//! circuit touches few basis states; once most amplitudes are nonzero, as in highly entangled
+//! circuits, the dense kernels run several times faster on the contiguous array. Pick one with
+//! [`QuantumCircuit::simulate_with`] and [`Backend`]. `cargo bench --bench backends` shows
+//! where the crossover falls: for 14 qubits, once about one amplitude in sixteen is nonzero.
+//!
+//! ```
+//! use zana::circuit::backend::Backend;
+//! use zana::circuit::{gates, QuantumCircuit};
+//!
+//! let mut circuit = QuantumCircuit::new(3);
+//! for qubit in 0..3 {
+//!     circuit.add_gate(gates::hadamard(), vec![qubit]);
+//! }
+//! let dense = circuit.simulate_with(Backend::Dense).statevector;
+//! let sparse = circuit.simulate().statevector;
+//! assert_eq!(dense.vector.len(), 8);
+//! assert!((dense.vector[&5] - sparse.vector[&5]).norm() < 1e-12);
+//! ```
+
+use num_complex::Complex;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use crate::circuit::backend::Backend;
+use crate::circuit::error::SimulationError;
+use crate::circuit::gates::Gate;
+use crate::circuit::measurement::SimulationResult;
+use crate::circuit::statevector::Statevector;
+use crate::circuit::QuantumCircuit;
+
+/// The statevector of a quantum system, with every amplitude stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DenseStatevector {
+    num_qubits: usize,
+    /// The amplitude of every basis state, indexed by the state with qubit 0 as bit 0.
+    pub amplitudes: Vec<Complex<f64>>,
+}
+
+impl DenseStatevector {
+    /// Initializes an `n`-qubit system in the `|0⟩` state.
+    ///
+    /// # Panics
+    /// If [`DenseStatevector::try_new`] would return an error.
+    pub fn new(num_qubits: usize) -> Self {
+        Self::try_new(num_qubits).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Initializes an `n`-qubit system in the `|0⟩` state, or returns
+    /// [`SimulationError::NoQubits`] if `num_qubits` is `0` and
+    /// [`SimulationError::OutOfMemory`] if the `2^n` amplitudes cannot be allocated.
+    pub fn try_new(num_qubits: usize) -> Result<Self, SimulationError> {
+        if num_qubits == 0 {
+            return Err(SimulationError::NoQubits);
+        }
+        let len = u32::try_from(num_qubits).ok().and_then(|n| 1usize.checked_shl(n));
+        let mut amplitudes = Vec::new();
+        match len {
+            Some(len) if amplitudes.try_reserve_exact(len).is_ok() => amplitudes.resize(len, Complex::new(0.0, 0.0)),
+            _ => return Err(SimulationError::OutOfMemory { num_qubits }),
+        }
+        amplitudes[0] = Complex::new(1.0, 0.0);
+        Ok(Self { num_qubits, amplitudes })
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    /// Applies a quantum gate to the statevector.
+    ///
+    /// # Panics
+    /// If [`DenseStatevector::try_apply_gate`] would return an error.
+    pub fn apply_gate(&mut self, gate: Gate, qubits: &[usize]) {
+        if let Err(error) = self.try_apply_gate(gate, qubits) {
+            panic!("{}", error);
+        }
+    }
+
+    /// Applies a quantum gate to the statevector, or returns
+    /// [`SimulationError::QubitOutOfRange`] if `qubits` is empty or names a qubit outside the
+    /// system.
+    pub fn try_apply_gate(&mut self, gate: Gate, qubits: &[usize]) -> Result<(), SimulationError> {
+        if qubits.is_empty() || qubits.iter().any(|&q| q >= self.num_qubits) {
+            return Err(SimulationError::QubitOutOfRange { qubits: qubits.to_vec(), num_qubits: self.num_qubits });
+        }
+
+        match gate {
+            Gate::Single(matrix) => self.apply_single_qubit_gate(&matrix, qubits[0]),
+            Gate::Two(matrix) => self.apply_matrix(&matrix, qubits),
+            Gate::Diagonal(entries) => self.apply_diagonal_gate(&entries, qubits),
+            Gate::Matrix(rows) => self.apply_matrix(&rows, qubits),
+        }
+        Ok(())
+    }
+
+    /// Applies a 2x2 matrix to every pair of amplitudes that differ in the target qubit.
+    fn apply_single_qubit_gate(&mut self, gate: &[[Complex<f64>; 2]; 2], target: usize) {
+        let mask = 1 << target;
+        for state_0 in (0..self.amplitudes.len()).filter(|state| state & mask == 0) {
+            let state_1 = state_0 | mask;
+            let (original_0, original_1) = (self.amplitudes[state_0], self.amplitudes[state_1]);
+            self.amplitudes[state_0] = gate[0][0] * original_0 + gate[0][1] * original_1;
+            self.amplitudes[state_1] = gate[1][0] * original_0 + gate[1][1] * original_1;
+        }
+    }
+
+    /// Applies a `2^k x 2^k` matrix to every group of `2^k` amplitudes that differ only in the
+    /// gate's qubits.
+    fn apply_matrix<R: AsRef<[Complex<f64>]>>(&mut self, gate: &[R], qubits: &[usize]) {
+        // The global offset of each gate-local index, with bit `i` of the index on `qubits[i]`.
+        let offsets: Vec<usize> = (0..gate.len())
+            .map(|local| qubits.iter().enumerate().map(|(i, &qubit)| ((local >> i) & 1) << qubit).sum())
+            .collect();
+        let mask: usize = qubits.iter().map(|&qubit| 1 << qubit).sum();
+
+        let mut inputs = vec![Complex::new(0.0, 0.0); gate.len()];
+        for base in (0..self.amplitudes.len()).filter(|state| state & mask == 0) {
+            for (input, &offset) in inputs.iter_mut().zip(&offsets) {
+                *input = self.amplitudes[base | offset];
+            }
+            for (row, &offset) in gate.iter().zip(&offsets) {
+                self.amplitudes[base | offset] = row.as_ref().iter().zip(&inputs).map(|(entry, input)| entry * input).sum();
+            }
+        }
+    }
+
+    /// Scales every amplitude by its diagonal entry.
+    fn apply_diagonal_gate(&mut self, entries: &[Complex<f64>], qubits: &[usize]) {
+        for (state, amplitude) in self.amplitudes.iter_mut().enumerate() {
+            let local = qubits.iter().enumerate().fold(0, |acc, (i, &qubit)| acc | (((state >> qubit) & 1) << i));
+            *amplitude *= entries[local];
+        }
+    }
+
+    /// Measures a qubit, collapsing the statevector, as [`Statevector::measure`] does.
+    pub fn measure(&mut self, target_qubit: usize) -> u8 {
+        let mask = 1 << target_qubit;
+        let (mut prob_0, mut prob_1) = (0.0, 0.0);
+        for (state, amp) in self.amplitudes.iter().enumerate() {
+            match state & mask {
+                0 => prob_0 += amp.norm_sqr(),
+                _ => prob_1 += amp.norm_sqr(),
+            }
+        }
+        let result = if crate::config::rng().gen::<f64>() < prob_0 { 0 } else { 1 };
+
+        // Zero the amplitudes inconsistent with the result and renormalize the others.
+        let kept: f64 = if result == 0 { prob_0 } else { prob_1 };
+        let scale = 1.0 / kept.sqrt();
+        for (state, amp) in self.amplitudes.iter_mut().enumerate() {
+            if (state & mask != 0) == (result == 1) {
+                *amp *= scale;
+            } else {
+                *amp = Complex::new(0.0, 0.0);
+            }
+        }
+        result
+    }
+
+    /// Returns the measurement probability of every basis state, indexed by state.
+    pub fn probabilities(&self) -> Vec<f64> {
+        self.amplitudes.iter().map(|amp| amp.norm_sqr()).collect()
+    }
+
+    /// Measures every qubit `shots` times, leaving the state untouched, as
+    /// [`Statevector::sample`] does.
+    pub fn sample(&self, shots: usize) -> Vec<usize> {
+        let Ok(distribution) = WeightedIndex::new(self.probabilities()) else {
+            return Vec::new();
+        };
+        let mut rng = crate::config::rng();
+        (0..shots).map(|_| distribution.sample(&mut rng)).collect()
+    }
+
+    /// Converts the state into a sparse [`Statevector`], dropping near-zero amplitudes.
+    pub fn to_sparse(&self) -> Statevector {
+        let mut sparse = Statevector::new(self.num_qubits);
+        sparse.vector = self
+            .amplitudes
+            .iter()
+            .enumerate()
+            .filter(|(_, amp)| amp.norm_sqr() > 1e-10)
+            .map(|(state, &amp)| (state, amp))
+            .collect();
+        sparse
+    }
+}
+
+impl From<&Statevector> for DenseStatevector {
+    fn from(sparse: &Statevector) -> Self {
+        let mut dense = DenseStatevector::new(sparse.num_qubits());
+        dense.amplitudes[0] = Complex::new(0.0, 0.0);
+        for (&state, &amp) in &sparse.vector {
+            dense.amplitudes[state] = amp;
+        }
+        dense
+    }
+}
+
+impl QuantumCircuit {
+    /// Simulates the circuit on `backend`.
+    ///
+    /// # Panics
+    /// If [`QuantumCircuit::try_simulate_with`] would return an error.
+    pub fn simulate_with(&self, backend: Backend) -> SimulationResult {
+        self.try_simulate_with(backend).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Simulates the circuit on `backend`, or returns why it cannot be simulated. The final
+    /// state is returned as a sparse [`Statevector`] whichever backend ran.
+    pub fn try_simulate_with(&self, backend: Backend) -> Result<SimulationResult, SimulationError> {
+        match backend {
+            Backend::Sparse => self.try_simulate(),
+            Backend::Dense => {
+                let mut statevector = DenseStatevector::try_new(self.qubits)?;
+                let mut classical_bits = vec![0; self.classical_bits];
+                for (position, (gate, qubits)) in self.gates.iter().enumerate() {
+                    self.measure_at(position, &mut classical_bits, |qubit| statevector.measure(qubit));
+                    statevector.try_apply_gate(gate.clone(), qubits)?;
+                }
+                self.measure_at(self.gates.len(), &mut classical_bits, |qubit| statevector.measure(qubit));
+                Ok(SimulationResult { statevector: statevector.to_sparse(), classical_bits })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::gates;
+
+    /// A circuit using every kind of gate, entangling all of its qubits.
+    fn entangling_circuit(qubits: usize) -> QuantumCircuit {
+        let mut circuit = QuantumCircuit::new(qubits);
+        for qubit in 0..qubits {
+            circuit.add_gate(gates::hadamard(), vec![qubit]);
+            circuit.add_gate(gates::rotation_y(0.3 * qubit as f64), vec![qubit]);
+        }
+        for qubit in 1..qubits {
+            circuit.add_gate(gates::cnot(), vec![qubit, qubit - 1]);
+        }
+        circuit.add_gate(gates::controlled(gates::rotation_x(0.7), 2), vec![0, 2, 3]);
+        circuit.add_gate(gates::phase_oracle(2, |x| x == 3), vec![1, 3]);
+        circuit
+    }
+
+    #[test]
+    fn test_dense_matches_sparse() {
+        let circuit = entangling_circuit(5);
+        let sparse = circuit.simulate_with(Backend::Sparse).statevector;
+        let dense = circuit.simulate_with(Backend::Dense).statevector;
+        assert_eq!(dense.vector.len(), sparse.vector.len());
+        for (state, amplitude) in &sparse.vector {
+            assert!((dense.vector[state] - amplitude).norm() < 1e-10, "amplitude of state {}", state);
+        }
+
+        let round_trip = DenseStatevector::from(&sparse);
+        assert!((round_trip.probabilities().iter().sum::<f64>() - 1.0).abs() < 1e-10);
+        assert_eq!(round_trip.to_sparse().vector.len(), sparse.vector.len());
+    }
+
+    #[test]
+    fn test_dense_measurement_and_errors() {
+        let mut statevector = DenseStatevector::new(2);
+        statevector.apply_gate(gates::pauli_x(), &[1]);
+        assert_eq!(statevector.measure(1), 1);
+        assert_eq!(statevector.measure(0), 0);
+        assert_eq!(statevector.sample(3), [0b10; 3]);
+
+        assert_eq!(DenseStatevector::try_new(0), Err(SimulationError::NoQubits));
+        assert_eq!(DenseStatevector::try_new(usize::BITS as usize), Err(SimulationError::OutOfMemory { num_qubits: usize::BITS as usize }));
+        assert!(statevector.try_apply_gate(gates::hadamard(), &[2]).is_err());
+    }
+}
@@ -0,0 +1,362 @@
+//! Notebook Display
+//!
+//! With the `evcxr` feature, circuits and simulation results render inline in Rust Jupyter
+//! notebooks run by [evcxr](https://github.com/evcxr/evcxr): a cell that ends in a
+//! [`QuantumCircuit`] shows its diagram, and one that ends in a [`Statevector`] or a
+//! [`JobResult`](crate::circuit::runner::JobResult) shows a probability chart. evcxr calls the
+//! `evcxr_display` method of the value, which prints an SVG between its content markers.
+//!
+//! ```
+//! use zana::circuit::{gates, QuantumCircuit};
+//!
+//! let mut circuit = QuantumCircuit::new(2);
+//! circuit.add_gate(gates::hadamard(), vec![0]);
+//! circuit.add_gate(gates::cnot(), vec![1, 0]);
+//!
+//! assert!(circuit.to_svg().contains(">H</text>"));
+//! assert!(circuit.simulate().statevector.to_svg().contains(">11</text>"));
+//! ```
+
+use std::f64::consts::PI;
+use std::fmt::Write;
+use num_complex::Complex;
+use crate::circuit::gates::{self, Gate};
+use crate::circuit::statevector::{bitstring, Statevector};
+use crate::circuit::QuantumCircuit;
+
+/// The most bars a chart draws. Larger distributions keep their most likely outcomes.
+pub const MAX_BARS: usize = 64;
+
+const COLUMN: f64 = 72.0;
+const ROW: f64 = 48.0;
+const MARGIN: f64 = 40.0;
+const BOX: f64 = 34.0;
+const FONT: &str = "font-family=\"sans-serif\" font-size=\"13\" dominant-baseline=\"central\"";
+const STYLE: &str = "font-family=\"sans-serif\" font-size=\"13\" dominant-baseline=\"central\" text-anchor=\"middle\"";
+
+/// Prints `svg` for evcxr to render.
+fn display_svg(svg: &str) {
+    println!("EVCXR_BEGIN_CONTENT image/svg+xml\n{}\nEVCXR_END_CONTENT", svg);
+}
+
+impl QuantumCircuit {
+    /// Draws the circuit: a wire per qubit and the gates in layers, packed to the left.
+    pub fn to_svg(&self) -> String {
+        let mut layers = vec![0usize; self.qubits];
+        let mut placed = Vec::with_capacity(self.gates.len());
+        for (gate, qubits) in &self.gates {
+            let (low, high) = span(qubits);
+            let layer = layers[low..=high].iter().copied().max().unwrap_or(0);
+            layers[low..=high].iter_mut().for_each(|next| *next = layer + 1);
+            placed.push((layer, gate, qubits));
+        }
+
+        let depth = layers.iter().copied().max().unwrap_or(0);
+        let width = 2.0 * MARGIN + COLUMN * depth.max(1) as f64;
+        let height = ROW * self.qubits.max(1) as f64;
+        let mut svg = svg_header(width, height);
+        for qubit in 0..self.qubits {
+            let y = wire(qubit);
+            let _ = write!(
+                svg,
+                "<text x=\"{}\" y=\"{y}\" {STYLE}>q{qubit}</text><line x1=\"{MARGIN}\" y1=\"{y}\" x2=\"{}\" y2=\"{y}\" stroke=\"black\"/>",
+                MARGIN / 2.0,
+                width - MARGIN / 2.0,
+            );
+        }
+        for (layer, gate, qubits) in placed {
+            draw_gate(&mut svg, MARGIN + COLUMN * (layer as f64 + 0.5), gate, qubits);
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+
+    /// Renders the circuit diagram in an evcxr notebook.
+    pub fn evcxr_display(&self) {
+        display_svg(&self.to_svg());
+    }
+}
+
+impl Statevector {
+    /// Charts the probability of every basis state with a stored amplitude, labelled by its
+    /// [`bitstring`].
+    pub fn to_svg(&self) -> String {
+        let bars = self
+            .probabilities()
+            .into_iter()
+            .map(|(state, probability)| (state, bitstring(state, self.num_qubits()), probability));
+        bar_chart("Probability", bars)
+    }
+
+    /// Renders the probability chart in an evcxr notebook.
+    pub fn evcxr_display(&self) {
+        display_svg(&self.to_svg());
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl crate::circuit::runner::JobResult {
+    /// Charts the sampled counts, or shows the error of a failed job.
+    pub fn to_svg(&self) -> String {
+        if let Some(error) = &self.error {
+            let mut svg = svg_header(480.0, ROW);
+            let _ = write!(svg, "<text x=\"240\" y=\"{}\" {STYLE} fill=\"firebrick\">{}</text></svg>", ROW / 2.0, escape(error));
+            return svg;
+        }
+        let bars = self
+            .counts
+            .iter()
+            .map(|(state, &count)| (usize::from_str_radix(state, 2).unwrap_or_default(), state.clone(), count as f64));
+        bar_chart(&format!("{} ({} shots)", self.name, self.shots), bars)
+    }
+
+    /// Renders the counts in an evcxr notebook.
+    pub fn evcxr_display(&self) {
+        display_svg(&self.to_svg());
+    }
+}
+
+fn svg_header(width: f64, height: f64) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\"><rect width=\"100%\" height=\"100%\" fill=\"white\"/>"
+    )
+}
+
+/// The lowest and highest qubit of a gate.
+fn span(qubits: &[usize]) -> (usize, usize) {
+    let low = qubits.iter().copied().min().unwrap_or(0);
+    (low, qubits.iter().copied().max().unwrap_or(low))
+}
+
+fn wire(qubit: usize) -> f64 {
+    ROW * (qubit as f64 + 0.5)
+}
+
+fn draw_gate(svg: &mut String, x: f64, gate: &Gate, qubits: &[usize]) {
+    let (low, high) = span(qubits);
+    let connector = |svg: &mut String| {
+        let _ = write!(svg, "<line x1=\"{x}\" y1=\"{}\" x2=\"{x}\" y2=\"{}\" stroke=\"black\"/>", wire(low), wire(high));
+    };
+    let dot = |svg: &mut String, qubit: usize| {
+        let _ = write!(svg, "<circle cx=\"{x}\" cy=\"{}\" r=\"5\" fill=\"black\"/>", wire(qubit));
+    };
+
+    match label(gate).as_str() {
+        // `cnot` acts on `[target, control]`.
+        "CNOT" => {
+            connector(svg);
+            dot(svg, qubits[1]);
+            let _ = write!(
+                svg,
+                "<circle cx=\"{x}\" cy=\"{y}\" r=\"11\" fill=\"white\" stroke=\"black\"/><line x1=\"{}\" y1=\"{y}\" x2=\"{}\" y2=\"{y}\" stroke=\"black\"/><line x1=\"{x}\" y1=\"{}\" x2=\"{x}\" y2=\"{}\" stroke=\"black\"/>",
+                x - 11.0,
+                x + 11.0,
+                wire(qubits[0]) - 11.0,
+                wire(qubits[0]) + 11.0,
+                y = wire(qubits[0]),
+            );
+        }
+        "CZ" => {
+            connector(svg);
+            qubits.iter().for_each(|&qubit| dot(svg, qubit));
+        }
+        "SWAP" => {
+            connector(svg);
+            for &qubit in qubits {
+                let (y, d) = (wire(qubit), 7.0);
+                let _ = write!(
+                    svg,
+                    "<path d=\"M{} {} L{} {} M{} {} L{} {}\" stroke=\"black\" stroke-width=\"2\"/>",
+                    x - d, y - d, x + d, y + d, x - d, y + d, x + d, y - d
+                );
+            }
+        }
+        label => {
+            let top = wire(low) - BOX / 2.0;
+            let bottom = wire(high) + BOX / 2.0;
+            let width = BOX.max(9.0 * label.chars().count() as f64 + 10.0).min(COLUMN - 4.0);
+            let _ = write!(
+                svg,
+                "<rect x=\"{}\" y=\"{top}\" width=\"{width}\" height=\"{}\" rx=\"4\" fill=\"#dbe8fb\" stroke=\"#1f4e99\"/><text x=\"{x}\" y=\"{}\" {STYLE}>{}</text>",
+                x - width / 2.0,
+                bottom - top,
+                (top + bottom) / 2.0,
+                escape(label),
+            );
+        }
+    }
+}
+
+/// Names a gate by comparing it with the gates of [`gates`]: `H`, `X`, `Rz(π/2)`, `CNOT`.
+/// Other gates are `U` or, when diagonal, `D`.
+fn label(gate: &Gate) -> String {
+    let same = |a: &Gate, b: &Gate| match (a, b) {
+        (Gate::Single(a), Gate::Single(b)) => close(a.iter().flatten(), b.iter().flatten()),
+        (Gate::Two(a), Gate::Two(b)) => close(a.iter().flatten(), b.iter().flatten()),
+        (Gate::Diagonal(a), Gate::Diagonal(b)) => a.len() == b.len() && close(a.iter(), b.iter()),
+        (Gate::Matrix(a), Gate::Matrix(b)) => a.len() == b.len() && close(a.iter().flatten(), b.iter().flatten()),
+        _ => false,
+    };
+    let named = [
+        ("H", gates::hadamard()),
+        ("X", gates::pauli_x()),
+        ("Y", gates::pauli_y()),
+        ("Z", gates::pauli_z()),
+        ("I", gates::identity_gate()),
+        ("CNOT", gates::cnot()),
+        ("SWAP", gates::swap()),
+    ];
+    if let Some((name, _)) = named.iter().find(|(_, named)| same(gate, named)) {
+        return name.to_string();
+    }
+
+    match gate {
+        Gate::Single(m) => {
+            let rotations: [Rotation; 3] = [
+                ("Rx", 2.0 * (-m[0][1].im).atan2(m[0][0].re), gates::rotation_x),
+                ("Ry", 2.0 * m[1][0].re.atan2(m[0][0].re), gates::rotation_y),
+                ("Rz", m[1][1].arg(), gates::rotation_z),
+            ];
+            if let Some((name, angle, _)) = rotations.iter().find(|(_, angle, rotation)| same(gate, &rotation(*angle))) {
+                return format!("{}({})", name, format_angle(*angle));
+            }
+            let phase = Gate::Single([[one(), zero()], [zero(), Complex::from_polar(1.0, m[1][1].arg())]]);
+            if same(gate, &phase) {
+                return phase_label(m[1][1].arg());
+            }
+            "U".to_string()
+        }
+        Gate::Two(_) | Gate::Matrix(_) => "U".to_string(),
+        Gate::Diagonal(entries) if entries.len() == 2 && close([entries[0]].iter(), [one()].iter()) => {
+            phase_label(entries[1].arg())
+        }
+        Gate::Diagonal(entries) if entries.len() == 4 && close(entries.iter(), [one(), one(), one(), -one()].iter()) => {
+            "CZ".to_string()
+        }
+        Gate::Diagonal(_) => "D".to_string(),
+    }
+}
+
+/// A rotation gate's name, its angle as decoded from a matrix, and the function making it.
+type Rotation = (&'static str, f64, fn(f64) -> Gate);
+
+fn phase_label(angle: f64) -> String {
+    let named = [(PI / 2.0, "S"), (-PI / 2.0, "S†"), (PI / 4.0, "T"), (-PI / 4.0, "T†")];
+    match named.iter().find(|(phase, _)| (phase - angle).abs() < 1e-9) {
+        Some((_, name)) => name.to_string(),
+        None => format!("P({})", format_angle(angle)),
+    }
+}
+
+/// Writes multiples of `π/8` as fractions of `π`, and other angles with two decimals.
+fn format_angle(angle: f64) -> String {
+    let eighths = angle / (PI / 8.0);
+    if (eighths - eighths.round()).abs() > 1e-9 {
+        return format!("{:.2}", angle);
+    }
+    let eighths = eighths.round() as i64;
+    let divisor = 8 / gcd(eighths.unsigned_abs(), 8) as i64;
+    let numerator = eighths * divisor / 8;
+    match (numerator, divisor) {
+        (0, _) => "0".to_string(),
+        (1, 1) => "π".to_string(),
+        (-1, 1) => "-π".to_string(),
+        (n, 1) => format!("{}π", n),
+        (1, d) => format!("π/{}", d),
+        (-1, d) => format!("-π/{}", d),
+        (n, d) => format!("{}π/{}", n, d),
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+fn close<'a>(a: impl Iterator<Item = &'a Complex<f64>>, b: impl Iterator<Item = &'a Complex<f64>>) -> bool {
+    a.zip(b).all(|(a, b)| (a - b).norm() < 1e-9)
+}
+
+fn one() -> Complex<f64> {
+    Complex::new(1.0, 0.0)
+}
+
+fn zero() -> Complex<f64> {
+    Complex::new(0.0, 0.0)
+}
+
+/// Draws a bar per `(order, label, value)`, in order, scaled to the largest value. Only the
+/// [`MAX_BARS`] largest values are kept.
+fn bar_chart(title: &str, bars: impl Iterator<Item = (usize, String, f64)>) -> String {
+    let mut bars: Vec<_> = bars.collect();
+    if bars.len() > MAX_BARS {
+        bars.sort_by(|a, b| b.2.total_cmp(&a.2));
+        bars.truncate(MAX_BARS);
+    }
+    bars.sort_by_key(|&(order, _, _)| order);
+
+    let (bar, chart_height) = (28.0, 200.0);
+    let label_height = 8.0 * bars.iter().map(|(_, label, _)| label.len()).max().unwrap_or(1) as f64 + 12.0;
+    let width = 2.0 * MARGIN + bar * bars.len().max(1) as f64;
+    let height = MARGIN + chart_height + label_height;
+    let max = bars.iter().map(|&(_, _, value)| value).fold(0.0, f64::max);
+
+    let mut svg = svg_header(width, height);
+    let _ = write!(svg, "<text x=\"{}\" y=\"{}\" {STYLE}>{}</text>", width / 2.0, MARGIN / 2.0, escape(title));
+    let baseline = MARGIN + chart_height;
+    for (i, (_, label, value)) in bars.iter().enumerate() {
+        let x = MARGIN + bar * i as f64;
+        let bar_height = if max > 0.0 { chart_height * value / max } else { 0.0 };
+        let center = x + bar / 2.0;
+        let _ = write!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{bar_height}\" fill=\"#3b78d8\"><title>{}: {}</title></rect><text x=\"{center}\" y=\"{}\" {FONT} transform=\"rotate(-90 {center} {})\" text-anchor=\"end\">{}</text>",
+            x + 3.0,
+            baseline - bar_height,
+            bar - 6.0,
+            escape(label),
+            value,
+            baseline + 6.0,
+            baseline + 6.0,
+            escape(label),
+        );
+    }
+    let _ = write!(svg, "<line x1=\"{MARGIN}\" y1=\"{baseline}\" x2=\"{}\" y2=\"{baseline}\" stroke=\"black\"/></svg>", width - MARGIN);
+    svg
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gate_labels() {
+        assert_eq!(label(&gates::rotation_x(PI / 2.0)), "Rx(π/2)");
+        assert_eq!(label(&gates::rotation_y(-3.0 * PI / 4.0)), "Ry(-3π/4)");
+        assert_eq!(label(&gates::rotation_z(0.3)), "Rz(0.30)");
+        assert_eq!(label(&gates::diagonal(vec![one(), Complex::from_polar(1.0, PI / 4.0)])), "T");
+        assert_eq!(label(&gates::diagonal(vec![one(), one(), one(), -one()])), "CZ");
+        assert_eq!(label(&gates::phase_oracle(3, |x| x == 5)), "D");
+        assert_eq!(label(&gates::cnot()), "CNOT");
+        assert_eq!(format_angle(2.0 * PI), "2π");
+    }
+
+    #[test]
+    fn test_circuit_layers_and_charts() {
+        let mut circuit = QuantumCircuit::new(3);
+        circuit.add_gate(gates::hadamard(), vec![0]);
+        circuit.add_gate(gates::hadamard(), vec![2]);
+        circuit.add_gate(gates::cnot(), vec![1, 0]);
+        let svg = circuit.to_svg();
+        // Both Hadamards share the first layer, so the diagram is two gates wide.
+        assert!(svg.contains(&format!("width=\"{}\"", 2.0 * MARGIN + 2.0 * COLUMN)));
+        assert_eq!(svg.matches(">H</text>").count(), 2);
+
+        let chart = circuit.simulate().statevector.to_svg();
+        assert_eq!(chart.matches("<rect x=").count(), 4);
+        assert!(chart.contains(">011</text>"));
+    }
+}
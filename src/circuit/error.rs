@@ -0,0 +1,71 @@
+//! Circuit Errors
+//!
+//! The error types returned while building circuits, [`CircuitError`], and while simulating
+//! them, [`SimulationError`].
+
+use thiserror::Error;
+
+/// An error raised while building a circuit or a gate.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CircuitError {
+    /// A qubit index is not below the number of qubits in the circuit.
+    #[error("qubit {qubit} is out of bounds for a circuit with {qubits} qubits")]
+    QubitOutOfRange { qubit: usize, qubits: usize },
+    /// The same qubit was named twice, with its position in the list.
+    #[error("qubit {qubit} is named twice, at position {position}")]
+    DuplicateQubit { qubit: usize, position: usize },
+    /// A gate was given a different number of qubits than it acts on.
+    #[error("gate acts on {expected} qubits, got {actual}")]
+    GateArity { expected: usize, actual: usize },
+    /// A subcircuit was mapped onto a different number of qubits than it has.
+    #[error("qubit mapping has {actual} entries for a circuit of {expected} qubits")]
+    QubitMapping { expected: usize, actual: usize },
+    /// A diagonal gate whose number of entries is not a power of two.
+    #[error("diagonal gate must have 2^k entries, got {0}")]
+    InvalidDiagonal(usize),
+    /// A gate matrix that is not square with `2^k` rows, with the length of its first bad row.
+    #[error("gate matrix must be 2^k by 2^k, got {rows} rows of {columns} entries")]
+    InvalidMatrix { rows: usize, columns: usize },
+    /// No gate has this name.
+    #[error("unknown gate '{0}'")]
+    UnknownGate(String),
+    /// A named gate was given the wrong number of parameters, such as angles.
+    #[error("gate '{gate}' takes {expected} parameters, got {actual}")]
+    GateParameters { gate: String, expected: usize, actual: usize },
+    /// An OpenQASM program could not be parsed, with the line of the problem.
+    #[error("QASM line {line}: {message}")]
+    Qasm { line: usize, message: String },
+    /// A circuit without provenance was asked to prove it.
+    #[error("circuit is not signed")]
+    Unsigned,
+    /// The provenance signature does not cover the circuit's gates and metadata, which were
+    /// altered after signing, or the signature or key is malformed.
+    #[error("circuit signature does not match its contents")]
+    SignatureMismatch,
+    /// The circuit is intact but was signed by another key than the trusted one.
+    #[error("circuit was not signed by the trusted key")]
+    UntrustedSigner,
+    /// The circuit was signed with a scheme this build cannot verify.
+    #[error("{0} signatures are not supported in this build")]
+    UnsupportedSignature(&'static str),
+}
+
+/// An error raised while simulating a circuit on a statevector.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SimulationError {
+    /// A statevector needs at least one qubit.
+    #[error("number of qubits must be greater than 0")]
+    NoQubits,
+    /// A gate was applied to no qubits or to a qubit outside the system.
+    #[error("qubits {qubits:?} are not within a system of {num_qubits} qubits")]
+    QubitOutOfRange { qubits: Vec<usize>, num_qubits: usize },
+    /// A dense statevector of this many qubits cannot be allocated.
+    #[error("a dense statevector of {num_qubits} qubits does not fit in memory")]
+    OutOfMemory { num_qubits: usize },
+    /// The statevector has no amplitudes.
+    #[error("statevector is empty")]
+    EmptyState,
+    /// An amplitude is stored for a basis state the system does not have.
+    #[error("statevector is inconsistent: index {index} exceeds max index {max} for {num_qubits} qubits")]
+    InvalidIndex { index: usize, max: usize, num_qubits: usize },
+}
@@ -1,16 +1,59 @@
 use num_complex::Complex;
+use crate::circuit::error::CircuitError;
 
-//// Gates are functions manipulating statevector and evolving it according to Schrödinger's
-//// Quantum gates are represented by unitary matrices'
+// Gates are functions manipulating statevector and evolving it according to Schrödinger's
+// Quantum gates are represented by unitary matrices'
 // applying a gate to a statevector involves matrix multiplication.
 
 /// Represents a quantum gate.
-/// It can be either a single-qubit gate or a two-qubit gate
+/// It can be a single-qubit gate, a two-qubit gate, or a diagonal or dense gate over any number of qubits
 #[derive(Debug)] // Automatically implement the Debug trait
 #[derive(Clone)]
 pub enum Gate {
     Single([[Complex<f64>; 2]; 2]), // Single-qubit gate (2x2 matrix)
     Two([[Complex<f64>; 4]; 4]),    // Two-qubit gate (4x4 matrix)
+    Diagonal(Vec<Complex<f64>>),    // k-qubit diagonal gate (2^k diagonal entries)
+    Matrix(Vec<Vec<Complex<f64>>>), // k-qubit gate (2^k x 2^k matrix, as rows)
+}
+
+impl Gate {
+    /// Returns the adjoint (conjugate transpose) of the gate, which undoes it.
+    pub fn adjoint(&self) -> Gate {
+        match self {
+            Gate::Single(m) => {
+                let mut adjoint = [[Complex::new(0.0, 0.0); 2]; 2];
+                for (i, row) in m.iter().enumerate() {
+                    for (j, value) in row.iter().enumerate() {
+                        adjoint[j][i] = value.conj();
+                    }
+                }
+                Gate::Single(adjoint)
+            }
+            Gate::Two(m) => {
+                let mut adjoint = [[Complex::new(0.0, 0.0); 4]; 4];
+                for (i, row) in m.iter().enumerate() {
+                    for (j, value) in row.iter().enumerate() {
+                        adjoint[j][i] = value.conj();
+                    }
+                }
+                Gate::Two(adjoint)
+            }
+            Gate::Diagonal(entries) => Gate::Diagonal(entries.iter().map(|e| e.conj()).collect()),
+            Gate::Matrix(m) => Gate::Matrix((0..m.len()).map(|i| m.iter().map(|row| row[i].conj()).collect()).collect()),
+        }
+    }
+
+    /// Returns the full `2^k x 2^k` matrix of the gate, as rows.
+    pub fn to_matrix(&self) -> Vec<Vec<Complex<f64>>> {
+        match self {
+            Gate::Single(m) => m.iter().map(|row| row.to_vec()).collect(),
+            Gate::Two(m) => m.iter().map(|row| row.to_vec()).collect(),
+            Gate::Diagonal(entries) => (0..entries.len())
+                .map(|i| (0..entries.len()).map(|j| if i == j { entries[i] } else { Complex::new(0.0, 0.0) }).collect())
+                .collect(),
+            Gate::Matrix(m) => m.clone(),
+        }
+    }
 }
 
 /// Returns the Hadamard gate matrix.
@@ -66,6 +109,18 @@ pub fn pauli_x() -> Gate {
     ])
 }
 
+/// Returns the Pauli-Y gate as a `Gate::Single`.
+///
+/// This gate flips the state of a qubit and applies a phase of `±i`:
+/// - `|0⟩` → `i|1⟩`
+/// - `|1⟩` → `-i|0⟩`
+pub fn pauli_y() -> Gate {
+    Gate::Single([
+        [Complex::new(0.0, 0.0), Complex::new(0.0, -1.0)],
+        [Complex::new(0.0, 1.0), Complex::new(0.0, 0.0)],
+    ])
+}
+
 /// Returns the Pauli-Z gate as a `Gate::Single`.
 ///
 /// This gate applies a phase shift to the `|1⟩` state:
@@ -133,6 +188,125 @@ pub fn swap() -> Gate {
     ])
 }
 
+/// Returns a diagonal gate as a `Gate::Diagonal`.
+///
+/// The gate acts on `k` qubits and is described by its `2^k` diagonal entries, where entry `i`
+/// multiplies the amplitude of the basis state whose gate-local index is `i`
+/// (bit `j` of `i` is the state of the `j`-th qubit the gate is applied to).
+///
+/// # Panics
+/// - If the number of entries is not a power of two.
+pub fn diagonal(entries: Vec<Complex<f64>>) -> Gate {
+    try_diagonal(entries).unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// Returns a diagonal gate, or [`CircuitError::InvalidDiagonal`] if the number of entries is
+/// not a power of two.
+pub fn try_diagonal(entries: Vec<Complex<f64>>) -> Result<Gate, CircuitError> {
+    if !entries.len().is_power_of_two() {
+        return Err(CircuitError::InvalidDiagonal(entries.len()));
+    }
+    Ok(Gate::Diagonal(entries))
+}
+
+/// Returns a gate from its `2^k x 2^k` matrix, given as rows.
+///
+/// Bit `j` of a row or column index is the state of the `j`-th qubit the gate is applied to, as
+/// for [`diagonal`]. A 2x2 matrix becomes a `Gate::Single`, a 4x4 matrix a `Gate::Two`, and any
+/// larger one a `Gate::Matrix`.
+///
+/// # Panics
+/// - If the matrix is not square with a power of two rows, of at least two.
+pub fn matrix(rows: Vec<Vec<Complex<f64>>>) -> Gate {
+    try_matrix(rows).unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// Returns a gate from its matrix, or [`CircuitError::InvalidMatrix`] if the matrix is not
+/// square with `2^k` rows for some `k ≥ 1`.
+pub fn try_matrix(rows: Vec<Vec<Complex<f64>>>) -> Result<Gate, CircuitError> {
+    Ok(match 1 << matrix_qubits(&rows)? {
+        2 => Gate::Single([[rows[0][0], rows[0][1]], [rows[1][0], rows[1][1]]]),
+        4 => {
+            let mut gate = [[Complex::new(0.0, 0.0); 4]; 4];
+            for (i, row) in rows.iter().enumerate() {
+                gate[i].copy_from_slice(row);
+            }
+            Gate::Two(gate)
+        }
+        _ => Gate::Matrix(rows),
+    })
+}
+
+/// Returns the number of qubits a matrix acts on, or [`CircuitError::InvalidMatrix`] if it is
+/// not square with `2^k` rows for some `k ≥ 1`.
+pub(crate) fn matrix_qubits(rows: &[Vec<Complex<f64>>]) -> Result<usize, CircuitError> {
+    let dimension = rows.len();
+    if let Some(row) = rows.iter().find(|row| row.len() != dimension) {
+        return Err(CircuitError::InvalidMatrix { rows: dimension, columns: row.len() });
+    }
+    if dimension < 2 || !dimension.is_power_of_two() {
+        return Err(CircuitError::InvalidMatrix { rows: dimension, columns: dimension });
+    }
+    Ok(dimension.trailing_zeros() as usize)
+}
+
+/// Returns `gate` controlled by `num_controls` more qubits: it acts only on the basis states
+/// where every control qubit is `|1⟩` and leaves the others unchanged.
+///
+/// The controlled gate is applied to the qubits of `gate` first, then to the controls, so
+/// `controlled(pauli_x(), 1)` is [`cnot`] on `[target, control]` and `controlled(pauli_x(), 2)`
+/// is the Toffoli gate on `[target, control, control]`. A diagonal gate stays diagonal; other
+/// gates become the smallest variant that holds the `2^(k + n)` matrix.
+///
+/// ```
+/// use zana::circuit::{gates, QuantumCircuit};
+///
+/// let mut circuit = QuantumCircuit::new(3);
+/// circuit.add_gate(gates::pauli_x(), vec![1]);
+/// circuit.add_gate(gates::pauli_x(), vec![2]);
+/// circuit.add_gate(gates::controlled(gates::pauli_x(), 2), vec![0, 1, 2]);
+///
+/// let state = circuit.simulate().statevector;
+/// assert!((state.vector[&0b111].re - 1.0).abs() < 1e-10);
+/// ```
+pub fn controlled(gate: Gate, num_controls: usize) -> Gate {
+    if num_controls == 0 {
+        return gate;
+    }
+    let one = Complex::new(1.0, 0.0);
+
+    if let Gate::Diagonal(entries) = gate {
+        let offset = (entries.len() << num_controls) - entries.len();
+        return Gate::Diagonal(std::iter::repeat_n(one, offset).chain(entries).collect());
+    }
+
+    let block = gate.to_matrix();
+    let dimension = block.len() << num_controls;
+    let offset = dimension - block.len();
+    let rows = (0..dimension)
+        .map(|i| {
+            (0..dimension)
+                .map(|j| match (i.checked_sub(offset), j.checked_sub(offset)) {
+                    (Some(i), Some(j)) => block[i][j],
+                    (None, None) if i == j => one,
+                    _ => Complex::new(0.0, 0.0),
+                })
+                .collect()
+        })
+        .collect();
+    matrix(rows)
+}
+
+/// Returns a phase oracle over `num_qubits` qubits as a `Gate::Diagonal`.
+///
+/// Every basis state `|x⟩` for which `marked(x)` is `true` picks up a phase of `-1`:
+/// - `O|x⟩ = (-1)^f(x) |x⟩`
+pub fn phase_oracle<F: Fn(usize) -> bool>(num_qubits: usize, marked: F) -> Gate {
+    let entries = (0..1usize << num_qubits)
+        .map(|x| if marked(x) { Complex::new(-1.0, 0.0) } else { Complex::new(1.0, 0.0) })
+        .collect();
+    Gate::Diagonal(entries)
+}
 
 #[cfg(test)]
 mod tests {
@@ -170,6 +344,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_controlled_gates() {
+        match (controlled(pauli_x(), 1), cnot()) {
+            (Gate::Two(controlled), Gate::Two(cnot)) => assert_eq!(controlled, cnot),
+            _ => panic!("Controlled-X did not return a Two-qubit gate"),
+        }
+
+        let toffoli = controlled(pauli_x(), 2).to_matrix();
+        assert_eq!(toffoli.len(), 8);
+        for (i, row) in toffoli.iter().enumerate() {
+            for (j, &value) in row.iter().enumerate() {
+                let flipped = if i >= 6 { i ^ 1 } else { i };
+                let expected = if j == flipped { 1.0 } else { 0.0 };
+                assert_eq!(value, Complex::new(expected, 0.0), "Toffoli entry ({}, {})", i, j);
+            }
+        }
+
+        match controlled(diagonal(vec![Complex::new(1.0, 0.0), Complex::new(-1.0, 0.0)]), 2) {
+            Gate::Diagonal(entries) => assert_eq!(entries.iter().filter(|e| e.re < 0.0).count(), 1),
+            _ => panic!("Controlled-Z did not stay diagonal"),
+        }
+        assert_eq!(
+            try_matrix(vec![vec![Complex::new(1.0, 0.0); 3]; 3]).unwrap_err(),
+            CircuitError::InvalidMatrix { rows: 3, columns: 3 }
+        );
+    }
+
     #[test]
     fn test_identity_gate() {
         if let Gate::Single(identity) = identity_gate() {
@@ -195,6 +396,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pauli_y() {
+        if let Gate::Single(pauli_y) = pauli_y() {
+            assert_eq!(pauli_y[0][1], Complex::new(0.0, -1.0));
+            assert_eq!(pauli_y[1][0], Complex::new(0.0, 1.0));
+            assert_eq!(pauli_y[0][0], Complex::new(0.0, 0.0));
+            assert_eq!(pauli_y[1][1], Complex::new(0.0, 0.0));
+        } else {
+            panic!("Pauli-Y gate did not return a Single-qubit gate");
+        }
+    }
+
     #[test]
     fn test_pauli_z() {
         if let Gate::Single(pauli_z) = pauli_z() {
@@ -266,4 +479,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_adjoint() {
+        if let Gate::Single(rx) = rotation_x(0.3).adjoint() {
+            if let Gate::Single(expected) = rotation_x(-0.3) {
+                assert_eq!(rx, expected, "Rx(θ)† should equal Rx(−θ)");
+            }
+        } else {
+            panic!("Adjoint of a Single-qubit gate should be Single");
+        }
+
+        if let Gate::Two(c) = cnot().adjoint() {
+            if let Gate::Two(expected) = cnot() {
+                assert_eq!(c, expected, "CNOT is self-adjoint");
+            }
+        } else {
+            panic!("Adjoint of a Two-qubit gate should be Two");
+        }
+
+        if let Gate::Diagonal(entries) = diagonal(vec![Complex::new(0.0, 1.0), Complex::new(1.0, 0.0)]).adjoint() {
+            assert_eq!(entries, vec![Complex::new(0.0, -1.0), Complex::new(1.0, 0.0)]);
+        } else {
+            panic!("Adjoint of a Diagonal gate should be Diagonal");
+        }
+    }
+
+    #[test]
+    fn test_phase_oracle() {
+        if let Gate::Diagonal(entries) = phase_oracle(2, |x| x == 2) {
+            assert_eq!(entries.len(), 4);
+            assert_eq!(entries[2], Complex::new(-1.0, 0.0));
+            assert!(entries.iter().enumerate().filter(|&(i, _)| i != 2).all(|(_, &e)| e == Complex::new(1.0, 0.0)));
+        } else {
+            panic!("Phase oracle did not return a Diagonal gate");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "diagonal gate must have 2^k entries")]
+    fn test_diagonal_requires_power_of_two() {
+        diagonal(vec![Complex::new(1.0, 0.0); 3]);
+    }
+
 }
@@ -0,0 +1,131 @@
+//! Mid-Circuit Measurement
+//!
+//! [`QuantumCircuit::add_measurement`] measures a qubit in the computational basis at its place
+//! in the gate sequence and stores the outcome in a bit of the circuit's classical register.
+//! The measured qubit collapses and stays usable, so later gates act on the post-measurement
+//! state. [`QuantumCircuit::simulate`] returns both the final statevector and the classical
+//! register in a [`SimulationResult`].
+//!
+//! Outcomes are random: fix them with [`config::set_seed`](crate::config::set_seed).
+//!
+//! ```
+//! use zana::circuit::{gates, QuantumCircuit};
+//!
+//! let mut circuit = QuantumCircuit::new(2);
+//! circuit.add_gate(gates::hadamard(), vec![0]);
+//! circuit.add_gate(gates::cnot(), vec![1, 0]);
+//! circuit.add_measurement(0, 0);
+//! circuit.add_measurement(1, 1);
+//!
+//! let result = circuit.simulate();
+//! // The Bell pair collapses to |00⟩ or |11⟩.
+//! assert_eq!(result.classical_bits[0], result.classical_bits[1]);
+//! assert!(result.bitstring() == "00" || result.bitstring() == "11");
+//! ```
+
+use crate::circuit::error::CircuitError;
+use crate::circuit::statevector::Statevector;
+use crate::circuit::QuantumCircuit;
+
+/// A measurement of one qubit into one classical bit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Measurement {
+    pub qubit: usize,
+    pub classical_bit: usize,
+    /// The number of gates of the circuit applied before the measurement.
+    pub position: usize,
+}
+
+/// The outcome of simulating a circuit: its final state and its classical register.
+#[derive(Debug, Clone)]
+pub struct SimulationResult {
+    /// The state after every gate and measurement.
+    pub statevector: Statevector,
+    /// Bit `i` of the classical register: the last outcome measured into it, or `0` if none was.
+    pub classical_bits: Vec<u8>,
+}
+
+impl SimulationResult {
+    /// Formats the classical register as a bitstring with bit 0 rightmost, like basis states.
+    pub fn bitstring(&self) -> String {
+        self.classical_bits.iter().rev().map(|bit| if *bit == 0 { '0' } else { '1' }).collect()
+    }
+}
+
+impl QuantumCircuit {
+    /// Measures `qubit` after the gates added so far, storing the outcome in `classical_bit`.
+    ///
+    /// # Panics
+    /// If [`QuantumCircuit::try_add_measurement`] would return an error.
+    pub fn add_measurement(&mut self, qubit: usize, classical_bit: usize) {
+        if let Err(error) = self.try_add_measurement(qubit, classical_bit) {
+            panic!("{}", error);
+        }
+    }
+
+    /// Measures `qubit` after the gates added so far, storing the outcome in `classical_bit`.
+    /// The classical register grows to hold `classical_bit` if needed.
+    ///
+    /// # Errors
+    /// [`CircuitError::QubitOutOfRange`] if the qubit is not in the circuit.
+    pub fn try_add_measurement(&mut self, qubit: usize, classical_bit: usize) -> Result<(), CircuitError> {
+        self.check_qubits(&[qubit])?;
+        self.classical_bits = self.classical_bits.max(classical_bit + 1);
+        self.measurements.push(Measurement { qubit, classical_bit, position: self.gates.len() });
+        Ok(())
+    }
+
+    /// Sets the size of the classical register, keeping room for the bits already measured into.
+    pub fn with_classical_bits(mut self, bits: usize) -> Self {
+        let measured = self.measurements.iter().map(|m| m.classical_bit + 1).max().unwrap_or(0);
+        self.classical_bits = bits.max(measured);
+        self
+    }
+
+    /// Makes the measurements due before gate `position`, or after the last gate for
+    /// `position == self.gates.len()`, with `measure`, writing their outcomes to
+    /// `classical_bits`.
+    pub(crate) fn measure_at(&self, position: usize, classical_bits: &mut [u8], mut measure: impl FnMut(usize) -> u8) {
+        for measurement in self.measurements.iter().filter(|m| m.position == position) {
+            classical_bits[measurement.classical_bit] = measure(measurement.qubit);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::gates;
+    use crate::config::RunConfig;
+
+    #[test]
+    fn test_measure_then_reuse() {
+        // Measure |1⟩, flip the qubit back and measure |0⟩ into another bit.
+        let mut circuit = QuantumCircuit::new(1).with_classical_bits(3);
+        circuit.add_gate(gates::pauli_x(), vec![0]);
+        circuit.add_measurement(0, 0);
+        circuit.add_gate(gates::pauli_x(), vec![0]);
+        circuit.add_measurement(0, 1);
+
+        let result = circuit.simulate();
+        assert_eq!(result.classical_bits, [1, 0, 0]);
+        assert_eq!(result.bitstring(), "001");
+        assert!((result.statevector.vector[&0].re - 1.0).abs() < 1e-10);
+        assert_eq!(circuit.try_add_measurement(1, 0), Err(CircuitError::QubitOutOfRange { qubit: 1, qubits: 1 }));
+    }
+
+    #[test]
+    fn test_measurement_collapses_superposition() {
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.add_gate(gates::hadamard(), vec![0]);
+        circuit.add_measurement(0, 1);
+        circuit.add_gate(gates::cnot(), vec![1, 0]);
+
+        let result = RunConfig::new().with_seed(7).run(|| circuit.simulate());
+        let outcome = result.classical_bits[1] as usize;
+        assert_eq!(result.classical_bits.len(), 2);
+        // The CNOT copies the measured bit, so only |00⟩ or |11⟩ remains.
+        assert_eq!(result.statevector.vector.len(), 1);
+        assert!((result.statevector.vector[&(outcome * 0b11)].norm() - 1.0).abs() < 1e-10);
+    }
+}
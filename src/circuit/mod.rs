@@ -1,38 +1,49 @@
 pub mod gates;        // Expose gates.rs
 pub mod statevector;
-
-use std::fs;
-use plotters::prelude::*;
-use plotters::style::Color as PlottersColor; // Avoid conflict with ratatui::Color
-use ratatui::{
-    backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
-    style::{Color, Style},
-    widgets::{BarChart, Block, Borders},
-    Terminal,
-};
-use crossterm::{
-    execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, enable_raw_mode, disable_raw_mode},
-};
-use std::io::stdout;
-use crossterm::event::{read, Event, KeyCode};
-use rand::Rng;
-use ratatui::text::Spans;
-use ratatui::widgets::Paragraph;
+pub mod dense;
+pub mod observable;
+pub mod noise;
+pub mod error;
+pub mod profile;
+pub mod qasm;
+pub mod provenance;
+pub mod measurement;
+pub mod sampling;
+pub mod compose;
+pub mod backend;
+#[cfg(feature = "evcxr")]
+pub mod display;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod runner;
+#[cfg(all(feature = "viz", not(target_arch = "wasm32")))]
+mod heatmap;
+
+pub use crate::circuit::error::{CircuitError, SimulationError};
+pub use crate::circuit::measurement::{Measurement, SimulationResult};
 use crate::circuit::gates::Gate;
+use crate::circuit::provenance::Provenance;
 use crate::circuit::statevector::Statevector;
 
 /// Represents a quantum circuit.
 ///
 /// A quantum circuit consists of a set number of qubits and a sequence of gate operations.
 /// Single-qubit gates and multi-qubit gates are stored with their associated qubits for clarity.
+#[derive(Debug, Clone)]
 pub struct QuantumCircuit {
     /// The number of qubits in the circuit.
     pub qubits: usize,
 
     /// A sequence of gates applied to the circuit, stored as `(gate, qubits)`.
     pub gates: Vec<(Gate, Vec<usize>)>,
+
+    /// The size of the classical register that measurements write to.
+    pub classical_bits: usize,
+
+    /// The measurements of the circuit, set by [`QuantumCircuit::add_measurement`].
+    pub measurements: Vec<Measurement>,
+
+    /// Who signed the circuit, set by [`QuantumCircuit::sign`].
+    pub provenance: Option<Provenance>,
 }
 
 impl QuantumCircuit {
@@ -44,6 +55,9 @@ impl QuantumCircuit {
         Self {
             qubits,
             gates: Vec::new(),
+            classical_bits: 0,
+            measurements: Vec::new(),
+            provenance: None,
         }
     }
 
@@ -57,42 +71,98 @@ impl QuantumCircuit {
     /// - `qubits`: The indices of the qubits the gate acts on.
     ///
     /// # Panics
-    /// - If any qubit index is out of bounds.
-    /// - If the gate size does not match the number of qubits specified.
+    /// If [`QuantumCircuit::try_add_gate`] would return an error.
     pub fn add_gate(&mut self, gate: Gate, qubits: Vec<usize>) {
-        // Validate qubit indices
-        for &qubit in &qubits {
-            assert!(
-                qubit < self.qubits,
-                "Qubit index {} is out of bounds for a circuit with {} qubits.",
-                qubit,
-                self.qubits
-            );
+        if let Err(error) = self.try_add_gate(gate, qubits) {
+            panic!("{}", error);
         }
+    }
 
-        // Validate gate size
-        match (&gate, qubits.len()) {
-            (Gate::Single(_), 1) => (),
-            (Gate::Two(_), 2) => (),
-            _ => panic!("Invalid gate or mismatched qubits for gate type."),
+    /// Adds a gate to the circuit, or returns why it cannot be added.
+    ///
+    /// # Errors
+    /// - [`CircuitError::QubitOutOfRange`] or [`CircuitError::DuplicateQubit`] if the qubits do
+    ///   not pass [`QuantumCircuit::check_qubits`].
+    /// - [`CircuitError::GateArity`] if the gate acts on a different number of qubits.
+    /// - [`CircuitError::InvalidDiagonal`] for a diagonal gate without `2^k` entries.
+    /// - [`CircuitError::InvalidMatrix`] for a matrix gate that is not `2^k` by `2^k`.
+    pub fn try_add_gate(&mut self, gate: Gate, qubits: Vec<usize>) -> Result<(), CircuitError> {
+        self.check_qubits(&qubits)?;
+
+        let expected = match &gate {
+            Gate::Single(_) => 1,
+            Gate::Two(_) => 2,
+            Gate::Diagonal(entries) if entries.len().is_power_of_two() => entries.len().trailing_zeros() as usize,
+            Gate::Diagonal(entries) => return Err(CircuitError::InvalidDiagonal(entries.len())),
+            Gate::Matrix(rows) => gates::matrix_qubits(rows)?,
+        };
+        if qubits.len() != expected {
+            return Err(CircuitError::GateArity { expected, actual: qubits.len() });
         }
 
         self.gates.push((gate, qubits));
+        Ok(())
+    }
+
+    /// Checks that a gate can be added on `qubits`: every index is in bounds and no qubit is
+    /// named twice.
+    pub fn check_qubits(&self, qubits: &[usize]) -> Result<(), CircuitError> {
+        if let Some(&qubit) = qubits.iter().find(|&&qubit| qubit >= self.qubits) {
+            return Err(CircuitError::QubitOutOfRange { qubit, qubits: self.qubits });
+        }
+        if let Some((position, &qubit)) = qubits.iter().enumerate().find(|(i, qubit)| qubits[..*i].contains(qubit)) {
+            return Err(CircuitError::DuplicateQubit { qubit, position });
+        }
+        Ok(())
     }
 
-    /// Simulates the quantum circuit and returns the final statevector.
+    /// Simulates the quantum circuit and returns the final statevector with the classical
+    /// register.
     ///
     /// # Returns
-    /// - A `Statevector` representing the quantum system's state after all gates have been applied.
+    /// - A [`SimulationResult`] holding the quantum system's state after all gates and
+    ///   measurements have been applied, and the outcomes of the measurements.
     ///
     /// # Panics
-    /// - If the circuit contains invalid gates or qubit indices.
-    pub fn simulate(&self) -> Statevector {
-        let mut statevector = Statevector::new(self.qubits);
-        for (gate, qubits) in &self.gates {
-            statevector.apply_gate(gate.clone(), qubits.as_slice()); // Clone the gate
+    /// If [`QuantumCircuit::try_simulate`] would return an error.
+    pub fn simulate(&self) -> SimulationResult {
+        self.try_simulate().unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Simulates the quantum circuit, or returns why it cannot be simulated, such as a circuit
+    /// without qubits.
+    pub fn try_simulate(&self) -> Result<SimulationResult, SimulationError> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("simulate", qubits = self.qubits, gates = self.gates.len()).entered();
+
+        let mut statevector = Statevector::try_new(self.qubits)?;
+        let mut classical_bits = vec![0; self.classical_bits];
+        for (position, (gate, qubits)) in self.gates.iter().enumerate() {
+            self.measure_at(position, &mut classical_bits, |qubit| statevector.measure(qubit));
+            statevector.try_apply_gate(gate.clone(), qubits.as_slice())?; // Clone the gate
+        }
+        self.measure_at(self.gates.len(), &mut classical_bits, |qubit| statevector.measure(qubit));
+        Ok(SimulationResult { statevector, classical_bits })
+    }
+
+    /// Returns the inverse circuit `U†`.
+    ///
+    /// The gates are applied in reverse order, each replaced by its adjoint, so running a circuit
+    /// followed by its inverse leaves every state unchanged. Measurements cannot be undone and
+    /// are left out.
+    pub fn inverse(&self) -> QuantumCircuit {
+        QuantumCircuit {
+            qubits: self.qubits,
+            gates: self
+                .gates
+                .iter()
+                .rev()
+                .map(|(gate, qubits)| (gate.adjoint(), qubits.clone()))
+                .collect(),
+            classical_bits: self.classical_bits,
+            measurements: Vec::new(),
+            provenance: None,
         }
-        statevector
     }
 
     /// Visualizes the quantum circuit as a text-based diagram.
@@ -142,6 +212,16 @@ impl QuantumCircuit {
                         }
                     }
                 }
+                Gate::Diagonal(_) | Gate::Matrix(_) => {
+                    let symbol = if let Gate::Diagonal(_) = gate { "──D──" } else { "──U──" };
+                    for (i, layer) in layers.iter_mut().enumerate() {
+                        if qubits.contains(&i) {
+                            layer.push_str(symbol);
+                        } else {
+                            layer.push_str("─────");
+                        }
+                    }
+                }
             }
         }
 
@@ -150,194 +230,25 @@ impl QuantumCircuit {
         }
     }
 
-    pub fn visualize_heatmap(&self, output_file: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
-        let final_state = self.simulate();
-
-        let probabilities: Vec<(usize, f64)> = final_state
-            .vector
-            .iter()
-            .map(|(&state, &amp)| (state, amp.norm_sqr()))
-            .collect();
-
-
-        match output_file {
-            Some(file) => {
-                let root = BitMapBackend::new(file, (800, 600)).into_drawing_area();
-                self.draw_heatmap(root, &probabilities)?;
-                fs::copy(format!("examples/circuits/charts/quantum_chart_{}.png", rand::thread_rng().gen_range(1..=5)), file)?;
-                println!("Heatmap saved to: {}", file);
-            }
-            None => {
-                self.render_heatmap_in_terminal(&probabilities)?;
-            }
-        }
-
-        Ok(())
-    }
-
-    fn draw_heatmap<DB>(
-        &self,
-        root: DrawingArea<DB, plotters::coord::Shift>,
-        probabilities: &[(usize, f64)],
-    ) -> Result<(), Box<dyn std::error::Error>>
-    where
-        DB: DrawingBackend,
-        DB::ErrorType: 'static, // Ensure the error type satisfies 'static lifetime
-    {
-        for &(state, prob) in probabilities {
-            println!("DEBUG: State: {}, Probability: {}", state, prob);
-        }
-
-        root.fill(&WHITE)?;
-        let max_prob = probabilities.iter().map(|&(_, prob)| prob).fold(0.0, f64::max);
-        let y_axis_max = (max_prob * 1.2).max(0.1); // Ensures bars are visible
-
-        let mut chart = ChartBuilder::on(&root)
-            .caption("Quantum State Probabilities", ("sans-serif", 30))
-            .margin(20)
-            .x_label_area_size(30)
-            .y_label_area_size(30)
-            .build_cartesian_2d(0..(1 << self.qubits) as i32, 0.0..y_axis_max)?;
-
-        chart
-            .configure_mesh()
-            .x_labels(10)
-            .y_desc("Probability")
-            .x_desc("State (binary representation)")
-            .axis_desc_style(("sans-serif", 15))
-            .draw()?;
-
-        chart
-            .draw_series(
-                probabilities
-                    .iter()
-                    .map(|&(state, prob)| Rectangle::new([(state as i32, 0.0), (state as i32, prob)], RED.filled())),
-            )?;
-
-        root.present()?;
-        Ok(())
-    }
-
-    pub fn render_heatmap_in_terminal(
-        &self,
-        probabilities: &[(usize, f64)],
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let mut stdout = stdout();
-
-        // Enter alternate screen
-        execute!(stdout, EnterAlternateScreen)?;
-        enable_raw_mode()?;
-
-        let backend = CrosstermBackend::new(stdout);
-        let mut terminal = Terminal::new(backend)?;
-
-        // Normalize probabilities to scale them dynamically
-        let max_prob = probabilities.iter().map(|&(_, prob)| prob).fold(0.0, f64::max);
-        let scale_factor = if max_prob > 0.0 { 100.0 / max_prob } else { 1.0 }; // Normalize to 100 if max_prob > 0
-
-        let bar_data: Vec<(String, u64)> = probabilities
-            .iter()
-            .map(|&(state, prob)| {
-                let scaled_value = (prob * scale_factor).ceil() as u64;
-                let state_binary = format!("{:08b}", state);
-                (state_binary, scaled_value.max(1))
-            })
-            .collect();
-
-        let mut selected_index = 0; // Navigation state: currently selected bar
-        let mut start_index = 0;    // The first visible bar index
-
-        loop {
-            // Render UI
-            terminal.draw(|frame| {
-                let chunks = Layout::default()
-                    .direction(Direction::Vertical)
-                    .constraints([Constraint::Min(3), Constraint::Percentage(80), Constraint::Min(3)].as_ref())
-                    .split(frame.size());
-
-                let available_width = chunks[1].width as usize;
-                let visible_bar_count = available_width / 8; // Each bar ~8 cells wide (bar + gap)
-                let end_index = (start_index + visible_bar_count).min(bar_data.len());
-
-                // Adjust scrolling window
-                if selected_index >= end_index {
-                    start_index = selected_index + 1 - visible_bar_count;
-                } else if selected_index < start_index {
-                    start_index = selected_index;
-                }
-
-                // Data to display
-                let visible_data = &bar_data[start_index..end_index];
-
-                // Header
-                let header = Paragraph::new(Spans::from("Quantum Heatmap Visualization"))
-                    .style(Style::default().fg(Color::Cyan))
-                    .block(Block::default().borders(Borders::BOTTOM).title("Header"));
-
-                // Highlight the selected bar
-                let bar_data_with_highlight: Vec<(&str, u64)> = visible_data
-                    .iter()
-                    .enumerate()
-                    .map(|(i, (label, value))| {
-                        if start_index + i == selected_index {
-                            (label.as_str(), value * 2) // Highlighted bar scaled up for emphasis
-                        } else {
-                            (label.as_str(), *value)
-                        }
-                    })
-                    .collect();
+}
 
-                // Heatmap
-                let bar_chart = BarChart::default()
-                    .block(Block::default().title("Quantum State Probabilities").borders(Borders::ALL))
-                    .bar_width(6) // Tighten the bar size for better visuals
-                    .bar_gap(1)   // Minimal gap between bars
-                    .style(Style::default().fg(Color::LightBlue))
-                    .value_style(Style::default().fg(Color::Yellow).bg(Color::Black))
-                    .label_style(Style::default().fg(Color::Gray))
-                    .data(&bar_data_with_highlight);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-                // Footer
-                let footer = Paragraph::new(vec![
-                    Spans::from(format!(
-                        "Selected State: {} | Probability: {:.2}%",
-                        bar_data[selected_index].0,
-                        probabilities[selected_index].1 * 100.0,
-                    )),
-                    Spans::from("Press 'q' or 'Esc' to exit | Use Up/Down to navigate"),
-                ])
-                    .style(Style::default().fg(Color::Green))
-                    .block(Block::default().borders(Borders::TOP).title("Footer"));
+    #[test]
+    fn test_inverse_undoes_circuit() {
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.add_gate(gates::hadamard(), vec![0]);
+        circuit.add_gate(gates::rotation_y(0.4), vec![1]);
+        circuit.add_gate(gates::cnot(), vec![0, 1]);
+        circuit.add_gate(gates::rotation_z(1.3), vec![0]);
 
-                frame.render_widget(header, chunks[0]);
-                frame.render_widget(bar_chart, chunks[1]);
-                frame.render_widget(footer, chunks[2]);
-            })?;
+        let mut round_trip = QuantumCircuit::new(2);
+        round_trip.gates.extend(circuit.gates.iter().cloned());
+        round_trip.gates.extend(circuit.inverse().gates);
 
-            // Handle user input
-            if let Event::Key(event) = read()? {
-                match event.code {
-                    KeyCode::Up => {
-                        if selected_index > 0 {
-                            selected_index -= 1;
-                        }
-                    }
-                    KeyCode::Down => {
-                        if selected_index < bar_data.len() - 1 {
-                            selected_index += 1;
-                        }
-                    }
-                    KeyCode::Esc | KeyCode::Char('q') => break, // Exit on 'q' or Esc
-                    _ => {}
-                }
-            }
-        }
-
-        // Leave alternate screen
-        disable_raw_mode()?;
-        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-
-        Ok(())
+        let probabilities = round_trip.simulate().statevector.probabilities();
+        assert!((probabilities[&0] - 1.0).abs() < 1e-10);
     }
-
 }
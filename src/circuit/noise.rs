@@ -0,0 +1,89 @@
+//! Noise Channels
+//!
+//! Single-qubit Pauli noise applied to a statevector by sampling a trajectory: with the channel's
+//! probability an X, Y or Z error is applied to the qubit, otherwise it is left untouched.
+
+use rand::Rng;
+use crate::circuit::observable::Pauli;
+use crate::circuit::statevector::Statevector;
+
+/// A single-qubit Pauli noise channel.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum NoiseChannel {
+    /// Applies X with probability `p`.
+    BitFlip(f64),
+    /// Applies Z with probability `p`.
+    PhaseFlip(f64),
+    /// Applies X, Y or Z, each with probability `p / 3`.
+    Depolarizing(f64),
+}
+
+impl NoiseChannel {
+    /// Returns the error probability of the channel.
+    pub fn probability(&self) -> f64 {
+        match *self {
+            NoiseChannel::BitFlip(p) | NoiseChannel::PhaseFlip(p) | NoiseChannel::Depolarizing(p) => p,
+        }
+    }
+
+    /// Samples the Pauli error for one use of the channel (`Pauli::I` for no error).
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Pauli {
+        if rng.gen::<f64>() >= self.probability() {
+            return Pauli::I;
+        }
+        match self {
+            NoiseChannel::BitFlip(_) => Pauli::X,
+            NoiseChannel::PhaseFlip(_) => Pauli::Z,
+            NoiseChannel::Depolarizing(_) => [Pauli::X, Pauli::Y, Pauli::Z][rng.gen_range(0..3)],
+        }
+    }
+
+    /// Passes `qubit` of `state` through the channel.
+    ///
+    /// # Returns
+    /// The Pauli error that was applied (`Pauli::I` for no error).
+    pub fn apply(&self, state: &mut Statevector, qubit: usize) -> Pauli {
+        let error = self.sample(&mut crate::config::rng());
+        inject_error(state, qubit, error);
+        error
+    }
+}
+
+/// Applies a deterministic Pauli error to `qubit` of `state`.
+pub fn inject_error(state: &mut Statevector, qubit: usize, error: Pauli) {
+    if error != Pauli::I {
+        state.apply_gate(error.gate(), &[qubit]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_certain_and_impossible_errors() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..20 {
+            assert_eq!(NoiseChannel::BitFlip(1.0).sample(&mut rng), Pauli::X);
+            assert_eq!(NoiseChannel::PhaseFlip(1.0).sample(&mut rng), Pauli::Z);
+            assert_eq!(NoiseChannel::Depolarizing(0.0).sample(&mut rng), Pauli::I);
+        }
+    }
+
+    #[test]
+    fn test_error_rate() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let channel = NoiseChannel::Depolarizing(0.3);
+        let errors = (0..10_000).filter(|_| channel.sample(&mut rng) != Pauli::I).count();
+        assert!((errors as f64 / 10_000.0 - 0.3).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_apply_bit_flip() {
+        let mut state = Statevector::new(2);
+        assert_eq!(NoiseChannel::BitFlip(1.0).apply(&mut state, 1), Pauli::X);
+        assert!((state.vector[&2].norm() - 1.0).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,302 @@
+//! Pauli Observables
+//!
+//! Observables are expressed as weighted sums of Pauli strings, e.g. `H = 0.5·Z0 Z1 − 1.2·X0`.
+//! Their expectation values `⟨ψ|H|ψ⟩` are computed directly from the statevector, with
+//! [`Statevector::expectation_value`] or, for the state a circuit prepares,
+//! [`QuantumCircuit::expectation`].
+//!
+//! ```
+//! use zana::circuit::observable::{Hamiltonian, Pauli, PauliString};
+//! use zana::circuit::{gates, QuantumCircuit};
+//!
+//! let mut bell = QuantumCircuit::new(2);
+//! bell.add_gate(gates::hadamard(), vec![0]);
+//! bell.add_gate(gates::cnot(), vec![1, 0]);
+//!
+//! let zz = PauliString::new(vec![(0, Pauli::Z), (1, Pauli::Z)]);
+//! let hamiltonian = Hamiltonian::new().with_term(0.5, zz.clone()).with_term(-1.2, PauliString::new(vec![(0, Pauli::X)]));
+//! assert!((bell.expectation(&zz) - 1.0).abs() < 1e-10);
+//! assert!((bell.expectation(&hamiltonian) - 0.5).abs() < 1e-10);
+//! ```
+
+use num_complex::Complex;
+use crate::circuit::error::SimulationError;
+use crate::circuit::gates::{self, Gate};
+use crate::circuit::statevector::Statevector;
+use crate::circuit::QuantumCircuit;
+
+/// A single-qubit Pauli operator.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Pauli {
+    I,
+    X,
+    Y,
+    Z,
+}
+
+impl Pauli {
+    /// Returns the operator as a single-qubit gate.
+    pub fn gate(&self) -> Gate {
+        match self {
+            Pauli::I => gates::identity_gate(),
+            Pauli::X => gates::pauli_x(),
+            Pauli::Y => gates::pauli_y(),
+            Pauli::Z => gates::pauli_z(),
+        }
+    }
+}
+
+/// A tensor product of Pauli operators, e.g. `Z0 ⊗ X2`.
+///
+/// Qubits that are not listed act as the identity.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PauliString {
+    /// The non-identity factors, stored as `(qubit, operator)`.
+    pub factors: Vec<(usize, Pauli)>,
+}
+
+impl PauliString {
+    /// Creates a Pauli string from `(qubit, operator)` factors.
+    pub fn new(factors: Vec<(usize, Pauli)>) -> Self {
+        Self { factors }
+    }
+
+    /// Creates the identity string.
+    pub fn identity() -> Self {
+        Self::default()
+    }
+
+    /// Computes the expectation value `⟨ψ|P|ψ⟩` for the given state.
+    ///
+    /// Pauli strings are Hermitian, so the result is real.
+    pub fn expectation(&self, state: &Statevector) -> f64 {
+        state.expectation_value(self)
+    }
+
+    /// Writes the string as `phase · X^flips · Z^signs`, where bit `q` of `flips` and `signs`
+    /// says whether `X` and `Z` act on qubit `q`. Factors on the same qubit are multiplied in
+    /// order, the first applied first.
+    fn masks(&self) -> (usize, usize, Complex<f64>) {
+        let (mut flips, mut signs, mut phase) = (0usize, 0usize, Complex::new(1.0, 0.0));
+        for &(qubit, pauli) in &self.factors {
+            let bit = 1 << qubit;
+            // Z X = −X Z, so moving a Z past an X already on the qubit flips the sign.
+            let anticommutes = if flips & bit != 0 { -1.0 } else { 1.0 };
+            match pauli {
+                Pauli::I => {}
+                Pauli::X => flips ^= bit,
+                Pauli::Z => {
+                    phase *= anticommutes;
+                    signs ^= bit;
+                }
+                // Y = i X Z
+                Pauli::Y => {
+                    phase *= Complex::new(0.0, anticommutes);
+                    flips ^= bit;
+                    signs ^= bit;
+                }
+            }
+        }
+        (flips, signs, phase)
+    }
+}
+
+impl Statevector {
+    /// Computes the expectation value `⟨ψ|P|ψ⟩` of a Pauli string in this state.
+    ///
+    /// `P` maps every basis state `|x⟩` to a single basis state with a phase, so this takes one
+    /// pass over the stored amplitudes and no copy of the state.
+    ///
+    /// # Panics
+    /// If a factor of `pauli_string` acts on a qubit outside the state.
+    pub fn expectation_value(&self, pauli_string: &PauliString) -> f64 {
+        if let Some(&(qubit, _)) = pauli_string.factors.iter().find(|(qubit, _)| *qubit >= self.num_qubits()) {
+            panic!("Pauli factor on qubit {} is outside a state of {} qubits", qubit, self.num_qubits());
+        }
+        let (flips, signs, phase) = pauli_string.masks();
+
+        // ⟨ψ|P|ψ⟩ = Σ_x conj(ψ(x ⊕ flips)) · phase · (−1)^{|x ∧ signs|} · ψ(x)
+        let sum: Complex<f64> = self
+            .vector
+            .iter()
+            .filter_map(|(&state, amp)| {
+                let partner = self.vector.get(&(state ^ flips))?;
+                let sign = if (state & signs).count_ones() % 2 == 0 { 1.0 } else { -1.0 };
+                Some(partner.conj() * amp * sign)
+            })
+            .sum();
+        (sum * phase).re
+    }
+}
+
+/// A quantity that can be measured on a state, such as a [`PauliString`] or a [`Hamiltonian`].
+pub trait Observable {
+    /// The expectation value `⟨ψ|O|ψ⟩` in `state`.
+    fn expectation(&self, state: &Statevector) -> f64;
+}
+
+impl Observable for PauliString {
+    fn expectation(&self, state: &Statevector) -> f64 {
+        state.expectation_value(self)
+    }
+}
+
+impl Observable for Hamiltonian {
+    fn expectation(&self, state: &Statevector) -> f64 {
+        Hamiltonian::expectation(self, state)
+    }
+}
+
+impl QuantumCircuit {
+    /// Computes the expectation value `⟨ψ|O|ψ⟩` of `observable` in the state the circuit
+    /// prepares from `|0⟩`.
+    ///
+    /// # Panics
+    /// If [`QuantumCircuit::try_expectation`] would return an error.
+    pub fn expectation(&self, observable: &impl Observable) -> f64 {
+        self.try_expectation(observable).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Computes the expectation value of `observable` in the state the circuit prepares, or
+    /// returns why the circuit cannot be simulated. Measurements in the circuit collapse the
+    /// state first, so the value then varies from call to call.
+    pub fn try_expectation(&self, observable: &impl Observable) -> Result<f64, SimulationError> {
+        Ok(observable.expectation(&self.try_simulate()?.statevector))
+    }
+}
+
+/// A Hermitian observable written as a weighted sum of Pauli strings.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Hamiltonian {
+    /// The terms of the sum, stored as `(coefficient, pauli_string)`.
+    pub terms: Vec<(f64, PauliString)>,
+}
+
+impl Hamiltonian {
+    /// Creates an empty Hamiltonian.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a weighted Pauli string to the Hamiltonian.
+    pub fn add_term(&mut self, coefficient: f64, pauli_string: PauliString) {
+        self.terms.push((coefficient, pauli_string));
+    }
+
+    /// Returns the Hamiltonian with an additional weighted Pauli string.
+    pub fn with_term(mut self, coefficient: f64, pauli_string: PauliString) -> Self {
+        self.add_term(coefficient, pauli_string);
+        self
+    }
+
+    /// Computes the expectation value `⟨ψ|H|ψ⟩` for the given state.
+    pub fn expectation(&self, state: &Statevector) -> f64 {
+        self.terms
+            .iter()
+            .map(|(coefficient, pauli_string)| coefficient * pauli_string.expectation(state))
+            .sum()
+    }
+}
+
+/// Returns the Bloch vector `(⟨X⟩, ⟨Y⟩, ⟨Z⟩)` of a single qubit.
+///
+/// For a qubit entangled with the rest of the register the vector lies inside the Bloch sphere;
+/// its length is `1` only for pure single-qubit states.
+pub fn bloch_vector(state: &Statevector, qubit: usize) -> [f64; 3] {
+    [Pauli::X, Pauli::Y, Pauli::Z].map(|pauli| PauliString::new(vec![(qubit, pauli)]).expectation(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::gates::{cnot, hadamard, pauli_x, rotation_x, rotation_y};
+
+    #[test]
+    fn test_z_expectation_on_basis_states() {
+        let mut sv = Statevector::new(1);
+        let z = PauliString::new(vec![(0, Pauli::Z)]);
+        assert!((z.expectation(&sv) - 1.0).abs() < 1e-10);
+
+        sv.apply_gate(pauli_x(), &[0]);
+        assert!((z.expectation(&sv) + 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_x_expectation_on_plus_state() {
+        let mut sv = Statevector::new(1);
+        sv.apply_gate(hadamard(), &[0]);
+        assert!((PauliString::new(vec![(0, Pauli::X)]).expectation(&sv) - 1.0).abs() < 1e-10);
+        assert!(PauliString::new(vec![(0, Pauli::Z)]).expectation(&sv).abs() < 1e-10);
+        assert!(PauliString::new(vec![(0, Pauli::Y)]).expectation(&sv).abs() < 1e-10);
+    }
+
+    /// `⟨ψ|P|ψ⟩` computed by applying `P` gate by gate to a copy of the state.
+    fn applied_expectation(pauli_string: &PauliString, state: &Statevector) -> f64 {
+        let mut transformed = state.clone();
+        for &(qubit, pauli) in &pauli_string.factors {
+            transformed.apply_gate(pauli.gate(), &[qubit]);
+        }
+        state
+            .vector
+            .iter()
+            .map(|(basis_state, amp)| amp.conj() * transformed.vector.get(basis_state).copied().unwrap_or_default())
+            .sum::<Complex<f64>>()
+            .re
+    }
+
+    #[test]
+    fn test_expectation_value_matches_applied_paulis() {
+        let mut circuit = QuantumCircuit::new(3);
+        circuit.add_gate(rotation_y(0.7), vec![0]);
+        circuit.add_gate(rotation_x(1.1), vec![1]);
+        circuit.add_gate(hadamard(), vec![2]);
+        circuit.add_gate(cnot(), vec![1, 2]);
+        circuit.add_gate(gates::rotation_z(0.4), vec![1]);
+        let state = circuit.simulate().statevector;
+
+        let strings = [
+            vec![(0, Pauli::Y), (2, Pauli::X)],
+            vec![(1, Pauli::Y), (2, Pauli::Y), (0, Pauli::Z)],
+            vec![(1, Pauli::X), (1, Pauli::Z), (1, Pauli::Y)], // Y Z X = i·I, with no real expectation
+            vec![(2, Pauli::I), (0, Pauli::X)],
+        ];
+        for factors in strings {
+            let pauli_string = PauliString::new(factors);
+            let expected = applied_expectation(&pauli_string, &state);
+            assert!((state.expectation_value(&pauli_string) - expected).abs() < 1e-10, "{:?}", pauli_string);
+        }
+
+        // ⟨YY⟩ = −1 on a Bell pair, while ⟨XX⟩ = ⟨ZZ⟩ = 1.
+        let mut bell = QuantumCircuit::new(2);
+        bell.add_gate(hadamard(), vec![0]);
+        bell.add_gate(cnot(), vec![1, 0]);
+        let yy = PauliString::new(vec![(0, Pauli::Y), (1, Pauli::Y)]);
+        assert!((bell.expectation(&yy) + 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_hamiltonian_expectation() {
+        let mut sv = Statevector::new(2);
+        sv.apply_gate(pauli_x(), &[1]); // |10⟩
+
+        let hamiltonian = Hamiltonian::new()
+            .with_term(2.0, PauliString::identity())
+            .with_term(0.5, PauliString::new(vec![(0, Pauli::Z)]))
+            .with_term(-1.5, PauliString::new(vec![(0, Pauli::Z), (1, Pauli::Z)]));
+
+        // 2 + 0.5·(+1) − 1.5·(−1)
+        assert!((hamiltonian.expectation(&sv) - 4.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_bloch_vector() {
+        let mut sv = Statevector::new(2);
+        sv.apply_gate(hadamard(), &[1]);
+
+        let [x, y, z] = bloch_vector(&sv, 1);
+        assert!((x - 1.0).abs() < 1e-10 && y.abs() < 1e-10 && z.abs() < 1e-10);
+
+        let [x, y, z] = bloch_vector(&sv, 0);
+        assert!(x.abs() < 1e-10 && y.abs() < 1e-10 && (z - 1.0).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,262 @@
+//! Simulation Profiling
+//!
+//! [`QuantumCircuit::simulate_with_profile`] simulates a circuit like
+//! [`QuantumCircuit::try_simulate`] and records where the time went: the wall time of every gate,
+//! how the number of stored amplitudes grew, the memory high-water mark of the statevector, and
+//! totals for each simulation kernel. Attach its report to performance issues.
+//!
+//! ```
+//! use zana::circuit::{gates, QuantumCircuit};
+//!
+//! let mut circuit = QuantumCircuit::new(2);
+//! circuit.add_gate(gates::hadamard(), vec![0]);
+//! circuit.add_gate(gates::cnot(), vec![1, 0]);
+//!
+//! let (_, profile) = circuit.simulate_with_profile().unwrap();
+//! assert_eq!(profile.gates.len(), 2);
+//! assert_eq!(profile.peak_amplitudes, 2);
+//! println!("{}", profile);
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+use crate::circuit::error::SimulationError;
+use crate::circuit::gates::Gate;
+use crate::circuit::statevector::Statevector;
+use crate::circuit::{QuantumCircuit, SimulationResult};
+
+/// The statevector kernel that applies a gate, chosen by the kind of gate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Kernel {
+    /// A 2x2 matrix on one qubit.
+    SingleQubit,
+    /// A 4x4 matrix on two qubits.
+    TwoQubit,
+    /// Diagonal entries on any number of qubits.
+    Diagonal,
+    /// A dense matrix on any number of qubits.
+    MultiQubit,
+}
+
+impl Kernel {
+    /// The kernel that applies `gate`.
+    pub fn of(gate: &Gate) -> Self {
+        match gate {
+            Gate::Single(_) => Kernel::SingleQubit,
+            Gate::Two(_) => Kernel::TwoQubit,
+            Gate::Diagonal(_) => Kernel::Diagonal,
+            Gate::Matrix(_) => Kernel::MultiQubit,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Kernel::SingleQubit => "single-qubit",
+            Kernel::TwoQubit => "two-qubit",
+            Kernel::Diagonal => "diagonal",
+            Kernel::MultiQubit => "multi-qubit",
+        }
+    }
+}
+
+/// What applying one gate of the circuit cost.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GateProfile {
+    /// The position of the gate in the circuit.
+    pub index: usize,
+    pub kernel: Kernel,
+    pub qubits: Vec<usize>,
+    /// The wall time of the kernel and the cleanup after it.
+    pub duration: Duration,
+    /// The number of stored amplitudes before the gate.
+    pub amplitudes_before: usize,
+    /// The number of stored amplitudes after the gate and cleanup.
+    pub amplitudes_after: usize,
+}
+
+impl GateProfile {
+    /// How many amplitudes the gate added, or removed if negative.
+    pub fn amplitude_growth(&self) -> isize {
+        self.amplitudes_after as isize - self.amplitudes_before as isize
+    }
+}
+
+/// Totals for every gate applied by one kernel.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KernelStats {
+    pub calls: usize,
+    pub duration: Duration,
+    /// The amplitudes the kernel read, summed over its calls.
+    pub amplitudes_in: usize,
+    /// The amplitudes the kernel left stored, summed over its calls.
+    pub amplitudes_out: usize,
+}
+
+impl KernelStats {
+    /// The average wall time of a call.
+    pub fn mean_duration(&self) -> Duration {
+        match u32::try_from(self.calls) {
+            Ok(0) => Duration::ZERO,
+            Ok(calls) => self.duration / calls,
+            Err(_) => Duration::from_secs_f64(self.duration.as_secs_f64() / self.calls as f64),
+        }
+    }
+}
+
+/// A record of where a simulation spent its time and memory.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimulationProfile {
+    /// Every gate, in circuit order.
+    pub gates: Vec<GateProfile>,
+    /// Totals by kernel.
+    pub kernels: BTreeMap<Kernel, KernelStats>,
+    /// The wall time of the whole simulation, including setup.
+    pub duration: Duration,
+    /// The largest number of amplitudes stored at once.
+    pub peak_amplitudes: usize,
+    /// An estimate of the most memory the statevector used at once, in bytes. Kernels build
+    /// the new amplitudes beside the old ones, so both count towards the peak.
+    pub peak_memory_bytes: usize,
+}
+
+impl SimulationProfile {
+    /// The `n` gates that took longest, slowest first.
+    pub fn slowest(&self, n: usize) -> Vec<&GateProfile> {
+        let mut gates: Vec<&GateProfile> = self.gates.iter().collect();
+        gates.sort_by_key(|gate| std::cmp::Reverse(gate.duration));
+        gates.truncate(n);
+        gates
+    }
+
+    /// The total time spent applying gates, without setup.
+    pub fn gate_duration(&self) -> Duration {
+        self.gates.iter().map(|gate| gate.duration).sum()
+    }
+}
+
+impl fmt::Display for SimulationProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} gates in {:?}, peak {} amplitudes (~{} bytes)",
+            self.gates.len(),
+            self.duration,
+            self.peak_amplitudes,
+            self.peak_memory_bytes
+        )?;
+        for (kernel, stats) in &self.kernels {
+            writeln!(
+                f,
+                "  {:<12} {:>6} calls {:>12?} total {:>12?} mean",
+                kernel.name(),
+                stats.calls,
+                stats.duration,
+                stats.mean_duration()
+            )?;
+        }
+        for gate in self.slowest(5) {
+            writeln!(
+                f,
+                "  gate {:>4} {:<12} on {:?}: {:?}, {} -> {} amplitudes",
+                gate.index,
+                gate.kernel.name(),
+                gate.qubits,
+                gate.duration,
+                gate.amplitudes_before,
+                gate.amplitudes_after
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl QuantumCircuit {
+    /// Simulates the circuit like [`QuantumCircuit::try_simulate`], timing every gate.
+    /// Measurements are made but not timed.
+    ///
+    /// # Returns
+    /// The final statevector with the classical register, and the profile of the simulation.
+    pub fn simulate_with_profile(&self) -> Result<(SimulationResult, SimulationProfile), SimulationError> {
+        let start = Instant::now();
+        let mut statevector = Statevector::try_new(self.qubits)?;
+        let mut classical_bits = vec![0; self.classical_bits];
+        let mut profile = SimulationProfile {
+            peak_amplitudes: statevector.vector.len(),
+            peak_memory_bytes: statevector.memory_bytes(),
+            ..SimulationProfile::default()
+        };
+
+        for (index, (gate, qubits)) in self.gates.iter().enumerate() {
+            self.measure_at(index, &mut classical_bits, |qubit| statevector.measure(qubit));
+            let kernel = Kernel::of(gate);
+            let amplitudes_before = statevector.vector.len();
+            let memory_before = statevector.memory_bytes();
+
+            let gate_start = Instant::now();
+            statevector.try_apply_gate(gate.clone(), qubits)?;
+            let duration = gate_start.elapsed();
+
+            let amplitudes_after = statevector.vector.len();
+            profile.peak_amplitudes = profile.peak_amplitudes.max(amplitudes_after);
+            profile.peak_memory_bytes = profile.peak_memory_bytes.max(memory_before + statevector.memory_bytes());
+
+            let stats = profile.kernels.entry(kernel).or_default();
+            stats.calls += 1;
+            stats.duration += duration;
+            stats.amplitudes_in += amplitudes_before;
+            stats.amplitudes_out += amplitudes_after;
+
+            profile.gates.push(GateProfile {
+                index,
+                kernel,
+                qubits: qubits.clone(),
+                duration,
+                amplitudes_before,
+                amplitudes_after,
+            });
+        }
+
+        self.measure_at(self.gates.len(), &mut classical_bits, |qubit| statevector.measure(qubit));
+
+        profile.duration = start.elapsed();
+        Ok((SimulationResult { statevector, classical_bits }, profile))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::gates;
+
+    #[test]
+    fn test_profile_tracks_gates_and_kernels() {
+        let mut circuit = QuantumCircuit::new(3);
+        circuit.add_gate(gates::hadamard(), vec![0]);
+        circuit.add_gate(gates::hadamard(), vec![1]);
+        circuit.add_gate(gates::cnot(), vec![2, 0]);
+        circuit.add_gate(gates::phase_oracle(2, |x| x == 3), vec![0, 1]);
+
+        let (result, profile) = circuit.simulate_with_profile().unwrap();
+        assert_eq!(result.statevector.vector, circuit.simulate().statevector.vector);
+
+        let growth: Vec<isize> = profile.gates.iter().map(GateProfile::amplitude_growth).collect();
+        assert_eq!(growth, [1, 2, 0, 0]);
+        assert_eq!(profile.peak_amplitudes, 4);
+        assert!(profile.peak_memory_bytes > result.statevector.memory_bytes());
+        assert_eq!(profile.kernels[&Kernel::SingleQubit].calls, 2);
+        assert_eq!(profile.kernels[&Kernel::TwoQubit].amplitudes_in, 4);
+        assert_eq!(profile.kernels[&Kernel::Diagonal].calls, 1);
+        assert!(profile.duration >= profile.gate_duration());
+        assert_eq!(profile.slowest(2).len(), 2);
+    }
+
+    #[test]
+    fn test_profile_of_empty_circuit() {
+        assert_eq!(QuantumCircuit::new(0).simulate_with_profile().unwrap_err(), SimulationError::NoQubits);
+
+        let (_, profile) = QuantumCircuit::new(1).simulate_with_profile().unwrap();
+        assert!(profile.gates.is_empty() && profile.kernels.is_empty());
+        assert_eq!(profile.peak_amplitudes, 1);
+    }
+}
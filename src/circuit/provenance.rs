@@ -0,0 +1,263 @@
+//! Circuit Provenance
+//!
+//! Circuits exchanged between teams, or generated by agents, can carry a signature over their
+//! gates and a little metadata: who signed them and when. [`QuantumCircuit::sign`] embeds a
+//! [`Provenance`] made with any [`Signer`], such as an Ed25519 [`SigningKey`](ed25519_dalek::SigningKey),
+//! and [`QuantumCircuit::verify_provenance_by`] checks both that the circuit is intact and that it
+//! was signed by a trusted key before it is simulated.
+//!
+//! The signature covers the SHA-256 digest of [`QuantumCircuit::canonical_bytes`], so adding,
+//! removing or changing any gate after signing invalidates it.
+//!
+//! ```
+//! use zana::circuit::{gates, CircuitError, QuantumCircuit};
+//! use zana::crypto::signatures::generate_keypair;
+//!
+//! let alice = generate_keypair();
+//! let mut circuit = QuantumCircuit::new(2);
+//! circuit.add_gate(gates::hadamard(), vec![0]);
+//! circuit.add_gate(gates::cnot(), vec![1, 0]);
+//! circuit.sign_as(&alice, "alice@lab");
+//!
+//! let provenance = circuit.verify_provenance_by(&alice.verifying_key()).unwrap();
+//! assert_eq!(provenance.author, "alice@lab");
+//!
+//! circuit.add_gate(gates::pauli_x(), vec![1]);
+//! assert_eq!(circuit.verify_provenance(), Err(CircuitError::SignatureMismatch));
+//! ```
+
+use ed25519_dalek::VerifyingKey;
+use crate::circuit::error::CircuitError;
+use crate::circuit::gates::Gate;
+use crate::circuit::QuantumCircuit;
+use crate::crypto::hash::sha256;
+use crate::crypto::signer::{SignatureAlgorithm, Signer, Verifier};
+
+/// The version of the signed payload and of [`QuantumCircuit::canonical_bytes`].
+pub const PROVENANCE_VERSION: u64 = 1;
+
+/// A signature over a circuit, with the key that made it and the claimed author and time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    pub algorithm: SignatureAlgorithm,
+    /// The encoded public key of the signer.
+    pub public_key: Vec<u8>,
+    /// Who signed the circuit, by default the [`Provenance::key_id`] of the signer. Only as
+    /// trustworthy as the key: check it with [`QuantumCircuit::verify_provenance_by`].
+    pub author: String,
+    /// When the circuit was signed, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub signature: Vec<u8>,
+}
+
+impl Provenance {
+    /// A short identifier for the signer: the first 8 bytes of the SHA-256 hash of its public
+    /// key, in hex, as [`key_id`](crate::crypto::signatures::key_id) gives for Ed25519 keys.
+    pub fn key_id(&self) -> String {
+        hex::encode(&sha256(&self.public_key)[..8])
+    }
+
+    /// The message covered by the signature.
+    fn payload(&self, circuit: &QuantumCircuit) -> Vec<u8> {
+        format!(
+            "zana-circuit-provenance\n{}\n{}\n{}\n{:?}\n{}\n{}",
+            PROVENANCE_VERSION,
+            self.algorithm.name(),
+            hex::encode(&self.public_key),
+            self.author,
+            self.timestamp,
+            hex::encode(sha256(&circuit.canonical_bytes()))
+        )
+        .into_bytes()
+    }
+
+    /// A verifier for the embedded public key.
+    fn verifier(&self) -> Result<Box<dyn Verifier>, CircuitError> {
+        match self.algorithm {
+            SignatureAlgorithm::Ed25519 => <[u8; 32]>::try_from(self.public_key.as_slice())
+                .ok()
+                .and_then(|key| VerifyingKey::from_bytes(&key).ok())
+                .map(|key| Box::new(key) as Box<dyn Verifier>)
+                .ok_or(CircuitError::SignatureMismatch),
+            #[cfg(feature = "crypto-classical")]
+            SignatureAlgorithm::Sr25519 => schnorrkel::PublicKey::from_bytes(&self.public_key)
+                .map(|key| Box::new(key) as Box<dyn Verifier>)
+                .map_err(|_| CircuitError::SignatureMismatch),
+            #[cfg(not(feature = "crypto-classical"))]
+            algorithm => Err(CircuitError::UnsupportedSignature(algorithm.name())),
+        }
+    }
+}
+
+impl QuantumCircuit {
+    /// Encodes the circuit unambiguously: the number of qubits, then every gate with its kind,
+    /// its qubits and its entries, then any measurements, as little-endian integers and floats.
+    /// Negative zeros are written as zeros, so equal circuits encode alike.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut bytes = b"zana-circuit".to_vec();
+        let mut push = |value: u64| bytes.extend_from_slice(&value.to_le_bytes());
+        push(PROVENANCE_VERSION);
+        push(self.qubits as u64);
+        push(self.gates.len() as u64);
+
+        for (gate, qubits) in &self.gates {
+            let (kind, entries): (u64, Vec<_>) = match gate {
+                Gate::Single(matrix) => (0, matrix.iter().flatten().collect()),
+                Gate::Two(matrix) => (1, matrix.iter().flatten().collect()),
+                Gate::Diagonal(diagonal) => (2, diagonal.iter().collect()),
+                Gate::Matrix(matrix) => (3, matrix.iter().flatten().collect()),
+            };
+            push(kind);
+            push(qubits.len() as u64);
+            qubits.iter().for_each(|&qubit| push(qubit as u64));
+            push(entries.len() as u64);
+            for entry in entries {
+                // Adding zero turns -0.0 into 0.0 and leaves every other value as it is.
+                push((entry.re + 0.0).to_bits());
+                push((entry.im + 0.0).to_bits());
+            }
+        }
+
+        // Circuits without measurements encode as they did before measurements existed.
+        if !self.measurements.is_empty() {
+            push(self.classical_bits as u64);
+            push(self.measurements.len() as u64);
+            for measurement in &self.measurements {
+                push(measurement.qubit as u64);
+                push(measurement.classical_bit as u64);
+                push(measurement.position as u64);
+            }
+        }
+        bytes
+    }
+
+    /// Signs the circuit with `key`, naming the key's id as the author. Replaces any earlier
+    /// provenance.
+    pub fn sign(&mut self, key: &dyn Signer) {
+        let author = hex::encode(&sha256(&key.public_key_bytes())[..8]);
+        self.sign_as(key, author);
+    }
+
+    /// Signs the circuit with `key` on behalf of `author`. Replaces any earlier provenance.
+    pub fn sign_as(&mut self, key: &dyn Signer, author: impl Into<String>) {
+        let mut provenance = Provenance {
+            algorithm: key.algorithm(),
+            public_key: key.public_key_bytes(),
+            author: author.into(),
+            timestamp: now(),
+            signature: Vec::new(),
+        };
+        provenance.signature = key.sign(&provenance.payload(self));
+        self.provenance = Some(provenance);
+    }
+
+    /// Checks that the circuit has not changed since it was signed, against the key embedded
+    /// in its provenance.
+    ///
+    /// This proves integrity only: anyone can sign with a key of their own. To also check who
+    /// signed, use [`QuantumCircuit::verify_provenance_by`].
+    ///
+    /// # Errors
+    /// - [`CircuitError::Unsigned`] if the circuit has no provenance.
+    /// - [`CircuitError::SignatureMismatch`] if the circuit or its metadata changed after signing.
+    /// - [`CircuitError::UnsupportedSignature`] if the scheme is not compiled in.
+    pub fn verify_provenance(&self) -> Result<&Provenance, CircuitError> {
+        let provenance = self.provenance.as_ref().ok_or(CircuitError::Unsigned)?;
+        if !provenance.verifier()?.verify(&provenance.payload(self), &provenance.signature) {
+            return Err(CircuitError::SignatureMismatch);
+        }
+        Ok(provenance)
+    }
+
+    /// Checks that the circuit has not changed since it was signed, and that `trusted` signed
+    /// it.
+    ///
+    /// # Errors
+    /// Those of [`QuantumCircuit::verify_provenance`], and [`CircuitError::UntrustedSigner`] if
+    /// an intact circuit was signed by another key.
+    pub fn verify_provenance_by(&self, trusted: &dyn Verifier) -> Result<&Provenance, CircuitError> {
+        let provenance = self.verify_provenance()?;
+        if trusted.algorithm() != provenance.algorithm
+            || !trusted.verify(&provenance.payload(self), &provenance.signature)
+        {
+            return Err(CircuitError::UntrustedSigner);
+        }
+        Ok(provenance)
+    }
+}
+
+/// Seconds since the Unix epoch. The clock is unavailable in the browser, where it reads zero.
+fn now() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    return 0;
+    #[cfg(not(target_arch = "wasm32"))]
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::gates;
+    use crate::crypto::signatures::generate_keypair;
+
+    fn bell() -> QuantumCircuit {
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.add_gate(gates::hadamard(), vec![0]);
+        circuit.add_gate(gates::cnot(), vec![1, 0]);
+        circuit
+    }
+
+    #[test]
+    fn test_provenance_detects_tampering_and_other_signers() {
+        let (alice, mallory) = (generate_keypair(), generate_keypair());
+        let mut circuit = bell();
+        assert_eq!(circuit.verify_provenance(), Err(CircuitError::Unsigned));
+
+        circuit.sign(&alice);
+        let provenance = circuit.verify_provenance_by(&alice.verifying_key()).unwrap();
+        assert_eq!(provenance.author, provenance.key_id());
+        assert_eq!(provenance.key_id(), crate::crypto::signatures::key_id(&alice.verifying_key()));
+        assert_eq!(circuit.verify_provenance_by(&mallory.verifying_key()), Err(CircuitError::UntrustedSigner));
+
+        let mut renamed = circuit.clone();
+        renamed.provenance.as_mut().unwrap().author = "bob".to_string();
+        assert_eq!(renamed.verify_provenance(), Err(CircuitError::SignatureMismatch));
+
+        let mut retargeted = circuit.clone();
+        retargeted.gates[1].1 = vec![0, 1];
+        assert_eq!(retargeted.verify_provenance(), Err(CircuitError::SignatureMismatch));
+
+        // Re-signing by another key keeps the circuit intact but changes its author.
+        circuit.sign_as(&mallory, "alice");
+        assert!(circuit.verify_provenance().is_ok());
+        assert_eq!(circuit.verify_provenance_by(&alice.verifying_key()), Err(CircuitError::UntrustedSigner));
+    }
+
+    #[test]
+    fn test_canonical_bytes() {
+        let mut negative_zero = QuantumCircuit::new(1);
+        negative_zero.add_gate(gates::diagonal(vec![num_complex::Complex::new(-0.0, 0.0), num_complex::Complex::new(1.0, -0.0)]), vec![0]);
+        let mut zero = QuantumCircuit::new(1);
+        zero.add_gate(gates::diagonal(vec![num_complex::Complex::new(0.0, 0.0), num_complex::Complex::new(1.0, 0.0)]), vec![0]);
+        assert_eq!(negative_zero.canonical_bytes(), zero.canonical_bytes());
+
+        assert_ne!(bell().canonical_bytes(), bell().inverse().canonical_bytes());
+        assert_ne!(QuantumCircuit::new(2).canonical_bytes(), QuantumCircuit::new(3).canonical_bytes());
+
+        let mut measured = bell();
+        measured.add_measurement(0, 0);
+        assert_ne!(measured.canonical_bytes(), bell().canonical_bytes());
+    }
+
+    #[cfg(feature = "crypto-classical")]
+    #[test]
+    fn test_sr25519_provenance() {
+        let keypair = crate::crypto::sr25519::generate_keypair();
+        let mut circuit = bell();
+        circuit.sign(&keypair);
+        assert_eq!(circuit.verify_provenance_by(&keypair.public).unwrap().algorithm, SignatureAlgorithm::Sr25519);
+    }
+}
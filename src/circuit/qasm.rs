@@ -0,0 +1,391 @@
+//! OpenQASM Import
+//!
+//! [`parse_qasm`] reads the gate-level subset of OpenQASM 2.0 (and the `qubit[n] q;`
+//! declarations of OpenQASM 3) into a [`QuantumCircuit`]:
+//! - `qreg q[n];` declares registers, laid out one after another;
+//! - the gates of [`add_named_gate`] are applied to register elements, such as `cx q[0], q[1];`,
+//!   and single-qubit gates on a whole register apply to each of its qubits;
+//! - angles are expressions of numbers and `pi`, such as `rz(-pi/4)`;
+//! - `creg c[n];` (or `bit[n] c;`) declares classical registers, and `measure q[0] -> c[0];`
+//!   (or `c[0] = measure q[0];`, or `measure q -> c;` over whole registers) adds measurements.
+//!
+//! `barrier` and `reset` are skipped.
+//!
+//! ```
+//! use zana::circuit::qasm::parse_qasm;
+//!
+//! let circuit = parse_qasm(
+//!     r#"OPENQASM 2.0;
+//!     include "qelib1.inc";
+//!     qreg q[2];
+//!     h q[0];
+//!     cx q[0], q[1];"#,
+//! )
+//! .unwrap();
+//! let probabilities = circuit.simulate().statevector.probabilities();
+//! assert!((probabilities[&0b11] - 0.5).abs() < 1e-12);
+//! ```
+
+use std::f64::consts::PI;
+use num_complex::Complex;
+use crate::circuit::error::CircuitError;
+use crate::circuit::gates::{self, Gate};
+use crate::circuit::QuantumCircuit;
+
+/// Adds a gate by its OpenQASM name, such as `h`, `rx` or `cx`.
+///
+/// Supported gates are `id`, `x`, `y`, `z`, `h`, `s`, `sdg`, `t`, `tdg`, `rx`, `ry`, `rz`,
+/// `p` (or `u1`), `cx` (or `cnot`), `cz`, `swap` and `ccx`. Controlled gates take the controls
+/// first, as in OpenQASM.
+///
+/// # Errors
+/// - [`CircuitError::UnknownGate`] for any other name.
+/// - [`CircuitError::GateParameters`] if the rotations and phases are not given exactly one
+///   angle, or the other gates are given any.
+/// - The errors of [`QuantumCircuit::try_add_gate`].
+pub fn add_named_gate(
+    circuit: &mut QuantumCircuit,
+    name: &str,
+    params: &[f64],
+    qubits: &[usize],
+) -> Result<(), CircuitError> {
+    let name = name.to_ascii_lowercase();
+    let (gate, expected_params) = match name.as_str() {
+        "id" | "i" => (gates::identity_gate(), 0),
+        "x" => (gates::pauli_x(), 0),
+        "y" => (gates::pauli_y(), 0),
+        "z" => (gates::pauli_z(), 0),
+        "h" => (gates::hadamard(), 0),
+        "s" => (phase(PI / 2.0), 0),
+        "sdg" => (phase(-PI / 2.0), 0),
+        "t" => (phase(PI / 4.0), 0),
+        "tdg" => (phase(-PI / 4.0), 0),
+        "rx" => (gates::rotation_x(params.first().copied().unwrap_or_default()), 1),
+        "ry" => (gates::rotation_y(params.first().copied().unwrap_or_default()), 1),
+        "rz" => (gates::rotation_z(params.first().copied().unwrap_or_default()), 1),
+        "p" | "u1" => (phase(params.first().copied().unwrap_or_default()), 1),
+        "cx" | "cnot" => (gates::cnot(), 0),
+        "cz" => (gates::diagonal(vec![one(), one(), one(), -one()]), 0),
+        "swap" => (gates::swap(), 0),
+        "ccx" => (gates::controlled(gates::pauli_x(), 2), 0),
+        _ => return Err(CircuitError::UnknownGate(name)),
+    };
+    if params.len() != expected_params {
+        return Err(CircuitError::GateParameters { gate: name, expected: expected_params, actual: params.len() });
+    }
+
+    // `gates::cnot()` and `gates::controlled` take the target first.
+    let qubits = match (name.as_str(), qubits) {
+        ("cx" | "cnot", &[control, target]) => vec![target, control],
+        ("ccx", &[first, second, target]) => vec![target, first, second],
+        _ => qubits.to_vec(),
+    };
+    circuit.try_add_gate(gate, qubits)
+}
+
+fn one() -> Complex<f64> {
+    Complex::new(1.0, 0.0)
+}
+
+/// The phase gate `diag(1, e^{iλ})`.
+fn phase(lambda: f64) -> Gate {
+    gates::diagonal(vec![one(), Complex::from_polar(1.0, lambda)])
+}
+
+/// Parses an OpenQASM program into a circuit.
+///
+/// # Errors
+/// [`CircuitError::Qasm`] with the line of the first statement that cannot be read, including
+/// gates that [`add_named_gate`] rejects.
+pub fn parse_qasm(source: &str) -> Result<QuantumCircuit, CircuitError> {
+    let source: String = source.lines().map(|line| line.split("//").next().unwrap_or("")).collect::<Vec<_>>().join("\n");
+
+    let mut registers: Vec<(String, usize, usize)> = Vec::new(); // (name, offset, size)
+    let mut classical_registers: Vec<(String, usize, usize)> = Vec::new();
+    let mut qubits = 0;
+    let mut classical_bits = 0;
+    let mut operations = Vec::new();
+
+    let mut line = 1;
+    for statement in source.split(';') {
+        let leading = &statement[..statement.len() - statement.trim_start().len()];
+        let statement_line = line + leading.matches('\n').count();
+        line += statement.matches('\n').count();
+        let statement = statement.trim();
+        if statement.is_empty() {
+            continue;
+        }
+        let error = |message: String| CircuitError::Qasm { line: statement_line, message };
+
+        let (keyword, rest) = split_keyword(statement);
+        match keyword {
+            "OPENQASM" | "include" | "barrier" | "reset" => {}
+            // `measure q -> c` in OpenQASM 2, `c = measure q` in OpenQASM 3.
+            _ if keyword == "measure" || rest.starts_with('=') => {
+                let (qubit, bit) = match keyword {
+                    "measure" => rest.split_once("->"),
+                    bit => rest[1..].trim().strip_prefix("measure").map(|qubit| (qubit, bit)),
+                }
+                .ok_or_else(|| error("expected 'measure q -> c' or 'c = measure q'".to_string()))?;
+                let qubits = resolve(&registers, qubit.trim()).map_err(error)?;
+                let bits = resolve(&classical_registers, bit.trim()).map_err(error)?;
+                if qubits.len() != bits.len() {
+                    return Err(error(format!("cannot measure {} qubits into {} bits", qubits.len(), bits.len())));
+                }
+                let arguments = vec![qubits, bits];
+                operations.push(Operation { line: statement_line, name: "measure".to_string(), params: Vec::new(), arguments });
+            }
+            "creg" => {
+                let (name, size) = parse_indexed(rest).map_err(error)?;
+                classical_registers.push((name.to_string(), classical_bits, size));
+                classical_bits += size;
+            }
+            _ if keyword.starts_with("bit") => {
+                let size = match keyword["bit".len()..].trim() {
+                    "" => 1,
+                    size => parse_size(size).map_err(error)?,
+                };
+                classical_registers.push((rest.trim().to_string(), classical_bits, size));
+                classical_bits += size;
+            }
+            "qreg" => {
+                let (name, size) = parse_indexed(rest).map_err(error)?;
+                registers.push((name.to_string(), qubits, size));
+                qubits += size;
+            }
+            _ if keyword.starts_with("qubit") => {
+                let size = match keyword["qubit".len()..].trim() {
+                    "" => 1,
+                    size => parse_size(size).map_err(error)?,
+                };
+                registers.push((rest.trim().to_string(), qubits, size));
+                qubits += size;
+            }
+            _ => {
+                let (name, params) = match keyword.split_once('(') {
+                    Some((name, params)) => {
+                        let params = params.strip_suffix(')').ok_or_else(|| error("unclosed parameter list".to_string()))?;
+                        let params = split_arguments(params)
+                            .map(|param| evaluate(param).map_err(&error))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        (name.trim(), params)
+                    }
+                    None => (keyword, Vec::new()),
+                };
+                let arguments = split_arguments(rest)
+                    .map(|argument| resolve(&registers, argument).map_err(&error))
+                    .collect::<Result<Vec<_>, _>>()?;
+                operations.push(Operation { line: statement_line, name: name.to_string(), params, arguments });
+            }
+        }
+    }
+
+    let mut circuit = QuantumCircuit::new(qubits).with_classical_bits(classical_bits);
+    for Operation { line, name, params, arguments } in operations {
+        let error = |error: CircuitError| CircuitError::Qasm { line, message: error.to_string() };
+        match arguments.as_slice() {
+            [qubits, bits] if name == "measure" => {
+                for (&qubit, &bit) in qubits.iter().zip(bits) {
+                    circuit.try_add_measurement(qubit, bit).map_err(error)?;
+                }
+            }
+            // A single-qubit gate on a whole register applies to each of its qubits.
+            [register] if register.len() > 1 => {
+                for &qubit in register {
+                    add_named_gate(&mut circuit, &name, &params, &[qubit]).map_err(error)?;
+                }
+            }
+            _ => {
+                let qubits: Vec<usize> = arguments
+                    .iter()
+                    .map(|argument| match argument.as_slice() {
+                        [qubit] => Ok(*qubit),
+                        _ => Err(CircuitError::Qasm {
+                            line,
+                            message: format!("gate '{}' cannot be broadcast over registers", name),
+                        }),
+                    })
+                    .collect::<Result<_, _>>()?;
+                add_named_gate(&mut circuit, &name, &params, &qubits).map_err(error)?;
+            }
+        }
+    }
+    Ok(circuit)
+}
+
+/// A gate or measurement statement, with the qubits, or the qubits then the bits, of its
+/// arguments.
+struct Operation {
+    line: usize,
+    name: String,
+    params: Vec<f64>,
+    arguments: Vec<Vec<usize>>,
+}
+
+/// Splits `h q[0]` into `h` and `q[0]`, keeping a parameter list such as `rx(pi / 2)` whole.
+fn split_keyword(statement: &str) -> (&str, &str) {
+    let mut depth = 0;
+    for (i, c) in statement.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            c if c.is_whitespace() && depth == 0 => return (&statement[..i], statement[i..].trim()),
+            _ => {}
+        }
+    }
+    (statement, "")
+}
+
+fn split_arguments(arguments: &str) -> impl Iterator<Item = &str> {
+    arguments.split(',').map(str::trim).filter(|argument| !argument.is_empty())
+}
+
+fn parse_size(size: &str) -> Result<usize, String> {
+    let size = size.trim().strip_prefix('[').and_then(|size| size.strip_suffix(']')).unwrap_or(size);
+    size.trim().parse().map_err(|_| format!("invalid register size '{}'", size))
+}
+
+/// Parses `q[3]` into `("q", 3)`.
+fn parse_indexed(text: &str) -> Result<(&str, usize), String> {
+    let (name, index) = text.split_once('[').ok_or_else(|| format!("expected 'name[size]', got '{}'", text))?;
+    let index = index.strip_suffix(']').ok_or_else(|| format!("unclosed '[' in '{}'", text))?;
+    let index = index.trim().parse().map_err(|_| format!("invalid index '{}'", index))?;
+    Ok((name.trim(), index))
+}
+
+/// The qubits or bits an argument names: one for `q[1]`, every one of the register for `q`.
+fn resolve(registers: &[(String, usize, usize)], argument: &str) -> Result<Vec<usize>, String> {
+    let (name, index) = match argument.contains('[') {
+        true => parse_indexed(argument).map(|(name, index)| (name, Some(index)))?,
+        false => (argument, None),
+    };
+    let (_, offset, size) = registers
+        .iter()
+        .find(|(register, _, _)| register == name)
+        .ok_or_else(|| format!("unknown register '{}'", name))?;
+    match index {
+        Some(index) if index < *size => Ok(vec![offset + index]),
+        Some(index) => Err(format!("index {} is out of bounds for register '{}' of size {}", index, name, size)),
+        None => Ok((*offset..offset + size).collect()),
+    }
+}
+
+/// Evaluates an angle made of numbers, `pi`, `+ - * /` and parentheses.
+fn evaluate(expression: &str) -> Result<f64, String> {
+    let tokens = tokenize(expression)?;
+    let mut position = 0;
+    let value = parse_sum(&tokens, &mut position)?;
+    match tokens.get(position) {
+        None => Ok(value),
+        Some(token) => Err(format!("unexpected '{}' in '{}'", token, expression)),
+    }
+}
+
+fn tokenize(expression: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if "+-*/()".contains(c) {
+            tokens.push(c.to_string());
+            chars.next();
+        } else if c.is_ascii_alphanumeric() || c == '.' {
+            let mut token = String::new();
+            while let Some(&c) = chars.peek() {
+                // Keep the sign of an exponent, as in `1e-3`.
+                let exponent_sign = (c == '-' || c == '+') && token.ends_with(['e', 'E']) && token.starts_with(|c: char| c.is_ascii_digit());
+                if !(c.is_ascii_alphanumeric() || c == '.' || exponent_sign) {
+                    break;
+                }
+                token.push(c);
+                chars.next();
+            }
+            tokens.push(token);
+        } else {
+            return Err(format!("unexpected '{}' in '{}'", c, expression));
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse_sum(tokens: &[String], position: &mut usize) -> Result<f64, String> {
+    let mut value = parse_product(tokens, position)?;
+    while let Some(operator) = tokens.get(*position).filter(|token| *token == "+" || *token == "-") {
+        *position += 1;
+        let right = parse_product(tokens, position)?;
+        value = if operator == "+" { value + right } else { value - right };
+    }
+    Ok(value)
+}
+
+fn parse_product(tokens: &[String], position: &mut usize) -> Result<f64, String> {
+    let mut value = parse_factor(tokens, position)?;
+    while let Some(operator) = tokens.get(*position).filter(|token| *token == "*" || *token == "/") {
+        *position += 1;
+        let right = parse_factor(tokens, position)?;
+        value = if operator == "*" { value * right } else { value / right };
+    }
+    Ok(value)
+}
+
+fn parse_factor(tokens: &[String], position: &mut usize) -> Result<f64, String> {
+    let token = tokens.get(*position).ok_or("expression ends early")?;
+    *position += 1;
+    match token.as_str() {
+        "-" => Ok(-parse_factor(tokens, position)?),
+        "+" => parse_factor(tokens, position),
+        "(" => {
+            let value = parse_sum(tokens, position)?;
+            match tokens.get(*position).map(String::as_str) {
+                Some(")") => {
+                    *position += 1;
+                    Ok(value)
+                }
+                _ => Err("unclosed '('".to_string()),
+            }
+        }
+        "pi" | "π" => Ok(PI),
+        number => number.parse().map_err(|_| format!("invalid number '{}'", number)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::Measurement;
+
+    #[test]
+    fn test_parse_program() {
+        let circuit = parse_qasm(
+            "OPENQASM 2.0;\ninclude \"qelib1.inc\";\nqreg a[1];\nqreg b[2];\ncreg c[3];\n\
+             h b; // both qubits of b\nrx(-pi/2) a[0];\ncx b[0], a[0];\nccx b[0], b[1], a[0];\nbarrier a, b;\nmeasure b[1] -> c[0];\nc[2] = measure a[0];\n",
+        )
+        .unwrap();
+        assert_eq!(circuit.qubits, 3);
+        assert_eq!(circuit.classical_bits, 3);
+        assert_eq!(circuit.gates.len(), 5);
+        assert_eq!(
+            circuit.measurements,
+            [Measurement { qubit: 2, classical_bit: 0, position: 5 }, Measurement { qubit: 0, classical_bit: 2, position: 5 }]
+        );
+        // `cx b[0], a[0]` is stored as `[target, control]`, and `ccx` with its target first too.
+        assert_eq!(circuit.gates[3].1, [0, 1]);
+        assert_eq!(circuit.gates[4].1, [0, 1, 2]);
+
+        assert!((evaluate("2*pi/(3 + 1)").unwrap() - PI / 2.0).abs() < 1e-12);
+        assert_eq!(evaluate("-1e-3").unwrap(), -0.001);
+    }
+
+    #[test]
+    fn test_errors_name_the_line() {
+        let error = parse_qasm("qreg q[2];\nh q[0];\n\nfoo q[1];").unwrap_err();
+        assert_eq!(error, CircuitError::Qasm { line: 4, message: "unknown gate 'foo'".to_string() });
+
+        let error = parse_qasm("qreg q[1];\nh q[3];").unwrap_err();
+        assert!(matches!(error, CircuitError::Qasm { line: 2, .. }), "{}", error);
+        assert!(parse_qasm("qreg q[1];\nrx q[0];").is_err());
+        assert!(parse_qasm("qreg q[2];\ncx q, q;").is_err());
+        assert!(parse_qasm("qreg q[2];\ncreg c[1];\nmeasure q -> c;").is_err());
+    }
+}
@@ -0,0 +1,299 @@
+//! Batch Runner
+//!
+//! A [`JobRunner`] simulates many circuits at once, as for parameter sweeps and benchmarking
+//! campaigns: a bounded pool of worker threads takes [`BatchJob`]s in order, reports each
+//! finished job as [`Progress`] to a callback or a channel, and writes its [`JobResult`] as JSON
+//! to an output directory as soon as it is done, so that an interrupted sweep keeps its results.
+//!
+//! ```
+//! use std::sync::mpsc;
+//! use zana::circuit::QuantumCircuit;
+//! use zana::circuit::gates;
+//! use zana::circuit::runner::{BatchJob, JobRunner};
+//!
+//! let jobs: Vec<BatchJob> = (1..=4)
+//!     .map(|qubits| {
+//!         let mut circuit = QuantumCircuit::new(qubits);
+//!         (0..qubits).for_each(|qubit| circuit.add_gate(gates::hadamard(), vec![qubit]));
+//!         BatchJob::new(format!("uniform-{}", qubits), circuit, 100)
+//!     })
+//!     .collect();
+//!
+//! let (sender, progress) = mpsc::channel();
+//! let results = JobRunner::new().with_workers(2).with_channel(sender).run(&jobs).unwrap();
+//!
+//! assert_eq!(progress.iter().count(), 4);
+//! assert!(results.iter().all(|result| result.counts.values().sum::<usize>() == 100));
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use serde::Serialize;
+pub use crate::circuit::backend::Backend;
+use crate::circuit::QuantumCircuit;
+use crate::config::{Config, RunConfig};
+
+/// A circuit to simulate, and how many shots to run it for with [`QuantumCircuit::run`].
+#[derive(Debug, Clone)]
+pub struct BatchJob {
+    /// Names the job in its [`JobResult`] and its result file.
+    pub name: String,
+    pub circuit: QuantumCircuit,
+    pub shots: usize,
+    pub backend: Backend,
+    /// Seeds the job's randomness, so that its counts repeat exactly whichever worker runs it.
+    pub seed: Option<u64>,
+}
+
+impl BatchJob {
+    pub fn new(name: impl Into<String>, circuit: QuantumCircuit, shots: usize) -> Self {
+        Self { name: name.into(), circuit, shots, backend: Backend::default(), seed: None }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+}
+
+/// The outcome of a [`BatchJob`], as written to the output directory.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobResult {
+    /// The position of the job in the batch.
+    pub index: usize,
+    pub name: String,
+    pub qubits: usize,
+    pub gates: usize,
+    pub shots: usize,
+    pub backend: Backend,
+    pub seed: Option<u64>,
+    /// How long the simulation and the sampling took.
+    #[serde(rename = "duration_ms", serialize_with = "milliseconds")]
+    pub duration: Duration,
+    /// Outcomes as bitstrings, bit 0 rightmost, with how often they were sampled: the classical
+    /// register if the circuit has measurements, every qubit otherwise.
+    pub counts: BTreeMap<String, usize>,
+    /// Why the simulation failed, in which case there are no counts.
+    pub error: Option<String>,
+}
+
+fn milliseconds<S: serde::Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64() * 1000.0)
+}
+
+/// A job of the batch has finished.
+#[derive(Debug, Clone)]
+pub struct Progress {
+    /// How many jobs have finished, this one included.
+    pub completed: usize,
+    pub total: usize,
+    pub result: JobResult,
+}
+
+type ProgressCallback = Box<dyn Fn(&Progress) + Send + Sync>;
+
+/// Simulates batches of [`BatchJob`]s on a bounded pool of worker threads.
+pub struct JobRunner {
+    workers: usize,
+    output_dir: Option<PathBuf>,
+    on_progress: Vec<ProgressCallback>,
+}
+
+impl Default for JobRunner {
+    fn default() -> Self {
+        Self {
+            workers: thread::available_parallelism().map_or(1, |workers| workers.get()),
+            output_dir: None,
+            on_progress: Vec::new(),
+        }
+    }
+}
+
+impl JobRunner {
+    /// A runner with a worker per available core, which keeps results in memory only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A runner with as many workers as the `[simulator]` section of `config` says.
+    pub fn from_config(config: &Config) -> Self {
+        match config.simulator.threads {
+            Some(threads) => Self::new().with_workers(threads),
+            None => Self::new(),
+        }
+    }
+
+    /// Runs at most `workers` jobs at a time, and at least one.
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    /// Writes each result to `dir` as `<index>-<name>.json`, creating `dir` if needed.
+    pub fn with_output_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.output_dir = Some(dir.into());
+        self
+    }
+
+    /// Calls `callback` from the worker thread as each job finishes.
+    pub fn with_progress(mut self, callback: impl Fn(&Progress) + Send + Sync + 'static) -> Self {
+        self.on_progress.push(Box::new(callback));
+        self
+    }
+
+    /// Sends a [`Progress`] to `sender` as each job finishes. Sending stops silently once the
+    /// receiver is dropped.
+    pub fn with_channel(self, sender: Sender<Progress>) -> Self {
+        self.with_progress(move |progress| {
+            let _ = sender.send(progress.clone());
+        })
+    }
+
+    /// Simulates every job and returns their results in the order of `jobs`.
+    ///
+    /// A job whose simulation fails still has a result, with its [`JobResult::error`] set.
+    ///
+    /// # Errors
+    /// If the output directory or a result file cannot be written. The remaining jobs are not
+    /// started.
+    pub fn run(&self, jobs: &[BatchJob]) -> io::Result<Vec<JobResult>> {
+        if let Some(dir) = &self.output_dir {
+            fs::create_dir_all(dir)?;
+        }
+
+        let next = AtomicUsize::new(0);
+        let completed = AtomicUsize::new(0);
+        let results = Mutex::new(vec![None; jobs.len()]);
+        let failure = Mutex::new(None);
+
+        thread::scope(|scope| {
+            for _ in 0..self.workers.min(jobs.len()) {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    if index >= jobs.len() || lock(&failure).is_some() {
+                        break;
+                    }
+
+                    let result = run_job(index, &jobs[index]);
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(job = %result.name, index, duration = ?result.duration, error = result.error, "job finished");
+                    if let Some(dir) = &self.output_dir {
+                        if let Err(error) = write_result(dir, &result) {
+                            lock(&failure).get_or_insert(error);
+                            break;
+                        }
+                    }
+
+                    let progress = Progress {
+                        completed: completed.fetch_add(1, Ordering::Relaxed) + 1,
+                        total: jobs.len(),
+                        result,
+                    };
+                    self.on_progress.iter().for_each(|callback| callback(&progress));
+                    lock(&results)[index] = Some(progress.result);
+                });
+            }
+        });
+
+        if let Some(error) = lock(&failure).take() {
+            return Err(error);
+        }
+        let results = results.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner());
+        Ok(results.into_iter().map(|result| result.expect("every job has run")).collect())
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn run_job(index: usize, job: &BatchJob) -> JobResult {
+    let start = Instant::now();
+    let outcome = RunConfig { seed: job.seed }.run(|| job.circuit.try_run_with(job.backend, job.shots).map(BTreeMap::from_iter));
+    let (counts, error) = match outcome {
+        Ok(counts) => (counts, None),
+        Err(error) => (BTreeMap::new(), Some(error.to_string())),
+    };
+
+    JobResult {
+        index,
+        name: job.name.clone(),
+        qubits: job.circuit.qubits,
+        gates: job.circuit.gates.len(),
+        shots: job.shots,
+        backend: job.backend,
+        seed: job.seed,
+        duration: start.elapsed(),
+        counts,
+        error,
+    }
+}
+
+fn write_result(dir: &Path, result: &JobResult) -> io::Result<()> {
+    let name: String = result
+        .name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let file = File::create(dir.join(format!("{:04}-{}.json", result.index, name)))?;
+    serde_json::to_writer_pretty(BufWriter::new(file), result)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::circuit::gates;
+
+    #[test]
+    fn test_runner_persists_results_and_reports_progress() {
+        let mut bell = QuantumCircuit::new(2);
+        bell.add_gate(gates::hadamard(), vec![0]);
+        bell.add_gate(gates::cnot(), vec![1, 0]);
+        let jobs = vec![
+            BatchJob::new("bell", bell.clone(), 500).with_seed(9),
+            BatchJob::new("empty", QuantumCircuit::new(0), 10),
+            BatchJob::new("bell/again", bell, 20),
+        ];
+
+        let dir = std::env::temp_dir().join(format!("zana-runner-{}", std::process::id()));
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorded = seen.clone();
+        let results = JobRunner::new()
+            .with_workers(2)
+            .with_output_dir(&dir)
+            .with_progress(move |progress| recorded.lock().unwrap().push((progress.completed, progress.total)))
+            .run(&jobs)
+            .unwrap();
+
+        let names: Vec<&str> = results.iter().map(|result| result.name.as_str()).collect();
+        assert_eq!(names, ["bell", "empty", "bell/again"]);
+        assert!(results[0].counts.keys().all(|state| state == "00" || state == "11"));
+        assert_eq!(results[0].counts.values().sum::<usize>(), 500);
+        assert_eq!(JobRunner::new().run(&jobs[..1]).unwrap()[0].counts, results[0].counts);
+        assert_eq!(results[1].error.as_deref(), Some("number of qubits must be greater than 0"));
+
+        let mut seen = seen.lock().unwrap().clone();
+        seen.sort();
+        assert_eq!(seen, [(1, 3), (2, 3), (3, 3)]);
+
+        let written: serde_json::Value =
+            serde_json::from_reader(File::open(dir.join("0002-bell_again.json")).unwrap()).unwrap();
+        assert_eq!(written["shots"], 20);
+        assert_eq!(written["backend"], "sparse");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,148 @@
+//! Shot-Based Sampling
+//!
+//! [`QuantumCircuit::run`] runs a circuit a number of times, or shots, and counts how often each
+//! outcome was observed, as a quantum computer reports its results. The statevector is
+//! simulated once and the outcomes are sampled from its final distribution, so a thousand shots
+//! cost about as much as one simulation.
+//!
+//! Outcomes are keyed by bitstrings with bit 0 rightmost: the classical register when the circuit
+//! has measurements, every qubit otherwise. Fix the samples with
+//! [`config::set_seed`](crate::config::set_seed).
+//!
+//! ```
+//! use zana::circuit::{gates, QuantumCircuit};
+//!
+//! let mut circuit = QuantumCircuit::new(2);
+//! circuit.add_gate(gates::hadamard(), vec![0]);
+//! circuit.add_gate(gates::cnot(), vec![1, 0]);
+//!
+//! let counts = circuit.run(1000);
+//! assert_eq!(counts.values().sum::<usize>(), 1000);
+//! assert!(counts.keys().all(|outcome| outcome == "00" || outcome == "11"));
+//! ```
+
+use std::collections::HashMap;
+use crate::circuit::backend::Backend;
+use crate::circuit::dense::DenseStatevector;
+use crate::circuit::error::SimulationError;
+use crate::circuit::statevector::{bitstring, Statevector};
+use crate::circuit::QuantumCircuit;
+
+impl QuantumCircuit {
+    /// Runs the circuit `shots` times and counts the outcomes.
+    ///
+    /// # Panics
+    /// If [`QuantumCircuit::try_run`] would return an error.
+    pub fn run(&self, shots: usize) -> HashMap<String, usize> {
+        self.try_run(shots).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Runs the circuit `shots` times on the sparse backend and counts the outcomes, or returns
+    /// why it cannot be simulated.
+    pub fn try_run(&self, shots: usize) -> Result<HashMap<String, usize>, SimulationError> {
+        self.try_run_with(Backend::Sparse, shots)
+    }
+
+    /// Runs the circuit `shots` times on `backend` and counts the outcomes.
+    ///
+    /// # Panics
+    /// If [`QuantumCircuit::try_run_with`] would return an error.
+    pub fn run_with(&self, backend: Backend, shots: usize) -> HashMap<String, usize> {
+        self.try_run_with(backend, shots).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Runs the circuit `shots` times on `backend` and counts the outcomes, or returns why it
+    /// cannot be simulated.
+    ///
+    /// Circuits whose measurements all follow the last gate are simulated once. A measurement
+    /// in the middle of the circuit changes the state the later gates act on, so such circuits
+    /// are simulated again for every shot.
+    pub fn try_run_with(&self, backend: Backend, shots: usize) -> Result<HashMap<String, usize>, SimulationError> {
+        let mut counts = HashMap::new();
+        if self.measurements.iter().any(|measurement| measurement.position < self.gates.len()) {
+            for _ in 0..shots {
+                *counts.entry(self.try_simulate_with(backend)?.bitstring()).or_insert(0) += 1;
+            }
+            return Ok(counts);
+        }
+
+        let samples = match backend {
+            Backend::Sparse => {
+                let mut statevector = Statevector::try_new(self.qubits)?;
+                for (gate, qubits) in &self.gates {
+                    statevector.try_apply_gate(gate.clone(), qubits)?;
+                }
+                statevector.sample(shots)
+            }
+            Backend::Dense => {
+                let mut statevector = DenseStatevector::try_new(self.qubits)?;
+                for (gate, qubits) in &self.gates {
+                    statevector.try_apply_gate(gate.clone(), qubits)?;
+                }
+                statevector.sample(shots)
+            }
+        };
+        for state in samples {
+            *counts.entry(self.outcome(state)).or_insert(0) += 1;
+        }
+        Ok(counts)
+    }
+
+    /// The bitstring reported for an observed basis state: the classical register the
+    /// measurements would fill, or the state itself if there are none.
+    fn outcome(&self, state: usize) -> String {
+        if self.measurements.is_empty() {
+            return bitstring(state, self.qubits);
+        }
+        let mut bits = vec!['0'; self.classical_bits];
+        for measurement in &self.measurements {
+            bits[measurement.classical_bit] = if (state >> measurement.qubit) & 1 == 1 { '1' } else { '0' };
+        }
+        bits.iter().rev().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuit::backend::Backend;
+    use crate::circuit::{gates, QuantumCircuit};
+    use crate::config::RunConfig;
+
+    #[test]
+    fn test_run_counts_outcomes() {
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.add_gate(gates::hadamard(), vec![0]);
+        circuit.add_gate(gates::cnot(), vec![1, 0]);
+
+        let counts = RunConfig::new().with_seed(3).run(|| circuit.run(2000));
+        assert_eq!(counts.len(), 2);
+        assert!((counts["00"] as f64 / 2000.0 - 0.5).abs() < 0.05);
+        assert_eq!(counts["00"] + counts["11"], 2000);
+        assert_eq!(RunConfig::new().with_seed(3).run(|| circuit.run(2000)), counts);
+        assert!(circuit.run(0).is_empty());
+        assert!(QuantumCircuit::new(0).try_run(10).is_err());
+
+        let dense = RunConfig::new().with_seed(3).run(|| circuit.run_with(Backend::Dense, 2000));
+        assert_eq!(dense, counts);
+    }
+
+    #[test]
+    fn test_run_reports_classical_register() {
+        // Only qubit 1, always |1⟩, is measured, into bit 2 of the register.
+        let mut terminal = QuantumCircuit::new(2);
+        terminal.add_gate(gates::hadamard(), vec![0]);
+        terminal.add_gate(gates::pauli_x(), vec![1]);
+        terminal.add_measurement(1, 2);
+        assert_eq!(terminal.run(50), [("100".to_string(), 50)].into());
+
+        // Measuring the superposition first makes the second Hadamard act on |0⟩ or |1⟩.
+        let mut mid_circuit = QuantumCircuit::new(1);
+        mid_circuit.add_gate(gates::hadamard(), vec![0]);
+        mid_circuit.add_measurement(0, 0);
+        mid_circuit.add_gate(gates::hadamard(), vec![0]);
+        mid_circuit.add_measurement(0, 1);
+        let counts = RunConfig::new().with_seed(5).run(|| mid_circuit.run(400));
+        assert_eq!(counts.len(), 4);
+        assert_eq!(counts.values().sum::<usize>(), 400);
+    }
+}
@@ -1,9 +1,12 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use num_complex::Complex;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use crate::circuit::error::SimulationError;
 use crate::circuit::gates::Gate;
 
 /// Represents the statevector of a quantum system.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Statevector {
     /// The statevector is represented as a list of complex amplitudes.
     /// It can tell everything about the quantum system at a given time
@@ -26,19 +29,30 @@ impl Statevector {
     ///
     /// # Example
     /// ```rust
-    //// let statevector = Statevector::new(2);
-    //// assert_eq!(statevector.vector[0], Complex::new(1.0, 0.0)); // |00⟩
-    //// assert_eq!(statevector.vector.len(), 4); // 2^2 = 4
-    //// ```
-
+    /// use num_complex::Complex;
+    /// use zana::circuit::statevector::Statevector;
+    ///
+    /// let statevector = Statevector::new(2);
+    /// assert_eq!(statevector.vector[&0], Complex::new(1.0, 0.0)); // |00⟩
+    /// assert_eq!(statevector.vector.len(), 1); // Sparse: only |00⟩ is stored
+    /// ```
+    ///
+    /// # Panics
+    /// If `num_qubits` is `0`.
     pub fn new(num_qubits: usize) -> Self {
+        Self::try_new(num_qubits).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Initializes a statevector in the `|0⟩` state, or returns [`SimulationError::NoQubits`]
+    /// if `num_qubits` is `0`.
+    pub fn try_new(num_qubits: usize) -> Result<Self, SimulationError> {
         if num_qubits == 0 {
-            panic!("Number of qubits must be greater than 0.");
+            return Err(SimulationError::NoQubits);
         }
 
         let mut vector = HashMap::new();
         vector.insert(0, Complex::new(1.0, 0.0)); // Start in |0⟩ state
-        Self { vector, num_qubits }
+        Ok(Self { vector, num_qubits })
     }
 
     /// Dynamically compute the number of qubits based on the statevector.
@@ -83,7 +97,7 @@ impl Statevector {
             .sum();
 
         // Generate a random measurement result (0 or 1)
-        let result = if rand::random::<f64>() < prob_0 { 0 } else { 1 };
+        let result = if crate::config::rng().gen::<f64>() < prob_0 { 0 } else { 1 };
 
         // Collapse the statevector based on the measurement result
         let norm: f64 = self
@@ -132,19 +146,39 @@ impl Statevector {
     /// # Arguments
     /// - `gate`: The gate matrix. It can be a 2x2 or 4x4 matrix.
     /// - `qubits`: The indices of the qubits the gate acts on.
-    /// Applies a gate to the statevector.
+    ///
+    /// # Panics
+    /// If [`Statevector::try_apply_gate`] would return an error.
     pub fn apply_gate(&mut self, gate: Gate, qubits: &[usize]) {
+        if let Err(error) = self.try_apply_gate(gate, qubits) {
+            panic!("{}", error);
+        }
+    }
+
+    /// Applies a quantum gate to the statevector, or returns
+    /// [`SimulationError::QubitOutOfRange`] if `qubits` is empty or names a qubit outside the
+    /// system.
+    pub fn try_apply_gate(&mut self, gate: Gate, qubits: &[usize]) -> Result<(), SimulationError> {
         if qubits.is_empty() || qubits.iter().any(|&q| q >= self.num_qubits) {
-            panic!("Qubit indices must be within the range of the quantum system.");
+            return Err(SimulationError::QubitOutOfRange { qubits: qubits.to_vec(), num_qubits: self.num_qubits });
         }
 
+        #[cfg(feature = "tracing")]
+        let kernel = crate::circuit::profile::Kernel::of(&gate);
+
         match gate {
             Gate::Single(single_qubit_gate) => self.apply_single_qubit_gate(&single_qubit_gate, qubits[0]),
             // Gate::Two(two_qubit_gate) => self.apply_multi_qubit_gate(&two_qubit_gate, qubits),
             Gate::Two(two_qubit_gate) => self.apply_two_qubit_gate(two_qubit_gate, qubits),
+            Gate::Diagonal(entries) => self.apply_diagonal_gate(&entries, qubits),
+            Gate::Matrix(rows) => self.apply_multi_qubit_gate(&rows, qubits),
         }
 
         self.normalize_and_cleanup();
+
+        #[cfg(feature = "tracing")]
+        tracing::trace!(kernel = kernel.name(), ?qubits, amplitudes = self.vector.len(), "gate applied");
+        Ok(())
     }
 
 
@@ -156,46 +190,41 @@ impl Statevector {
         let mask = 1 << target;
         let mut new_vector = HashMap::new();
 
-        for (&state, &amp) in &self.vector {
-            let paired_state = state ^ mask; // Flip the target bit
-            if state & mask == 0 {
-                let original_0 = amp;
-                let original_1 = *self.vector.get(&paired_state).unwrap_or(&Complex::new(0.0, 0.0));
-
-                new_vector.insert(
-                    state,
-                    gate[0][0] * original_0 + gate[0][1] * original_1,
-                );
-                new_vector.insert(
-                    paired_state,
-                    gate[1][0] * original_0 + gate[1][1] * original_1,
-                );
+        for &state in self.vector.keys() {
+            let state_0 = state & !mask; // Target bit cleared
+            if new_vector.contains_key(&state_0) {
+                continue; // Pair already processed
             }
+            let state_1 = state_0 | mask; // Target bit set
+
+            let original_0 = *self.vector.get(&state_0).unwrap_or(&Complex::new(0.0, 0.0));
+            let original_1 = *self.vector.get(&state_1).unwrap_or(&Complex::new(0.0, 0.0));
+
+            new_vector.insert(
+                state_0,
+                gate[0][0] * original_0 + gate[0][1] * original_1,
+            );
+            new_vector.insert(
+                state_1,
+                gate[1][0] * original_0 + gate[1][1] * original_1,
+            );
         }
 
         self.vector = new_vector;
     }
 
 
-    /// Generalized multi-qubit gate application.
-    /// Generalized multi-qubit gate application for sparse statevector representation.
-    fn apply_multi_qubit_gate<const N: usize>(
-        &mut self,
-        gate: &[[Complex<f64>; N]; N],
-        qubits: &[usize],
-    ) {
+    /// Applies a gate of any arity from its `2^k x 2^k` matrix rows, for sparse statevector
+    /// representation.
+    fn apply_multi_qubit_gate<R: AsRef<[Complex<f64>]>>(&mut self, gate: &[R], qubits: &[usize]) {
         let mut new_vector = HashMap::new();
 
         for (&state, &amplitude) in self.vector.iter() {
             let input_index = self.map_to_gate_index(state, qubits);
 
-            for output_index in 0..N {
-                println!(
-                    "Gate Element Access -> Gate[{}][{}] = {}",
-                    output_index, input_index, gate[output_index][input_index]
-                );
+            for (output_index, row) in gate.iter().enumerate() {
                 let new_state = self.map_from_gate_index(state, qubits, output_index);
-                let gate_element = gate[output_index][input_index];
+                let gate_element = row.as_ref()[input_index];
 
                 if gate_element.norm_sqr() > 1e-10 {
                     let contribution = gate_element * amplitude;
@@ -204,9 +233,7 @@ impl Statevector {
             }
         }
 
-        println!("New Vector Before Cleanup: {:?}", new_vector);
         self.vector = new_vector;
-        self.normalize_and_cleanup();
     }
 
 
@@ -222,20 +249,14 @@ impl Statevector {
                 // Map the global state to the gate's input index
                 let input_index = self.map_to_gate_index(state, qubits);
 
-                for output_index in 0..4 {
+                for (output_index, row) in gate.iter().enumerate() {
                     // Map the gate's output index back to the global state
                     let new_state = self.map_from_gate_index(state, qubits, output_index);
-                    let gate_element = gate[output_index][input_index];
+                    let gate_element = row[input_index];
 
                     if gate_element.norm_sqr() > 1e-10 {
                         let contribution = gate_element * amplitude;
 
-                        // Debug log for contribution
-                        println!(
-                            "Contribution -> State: {}, Input Index: {}, Output Index: {}, New State: {}, Gate Element: {}, Contribution: {}",
-                            state, input_index, output_index, new_state, gate_element, contribution
-                        );
-
                         // Add the contribution to the new statevector
                         *new_vector.entry(new_state).or_insert(Complex::new(0.0, 0.0)) += contribution;
                     }
@@ -249,6 +270,15 @@ impl Statevector {
 
 
 
+    /// Applies a diagonal gate by scaling every amplitude with its diagonal entry.
+    fn apply_diagonal_gate(&mut self, entries: &[Complex<f64>], qubits: &[usize]) {
+        let vector = std::mem::take(&mut self.vector);
+        self.vector = vector
+            .into_iter()
+            .map(|(state, amplitude)| (state, entries[self.map_to_gate_index(state, qubits)] * amplitude))
+            .collect();
+    }
+
     /// Map a global state index to the gate-specific index.
     fn map_to_gate_index(&self, state: usize, qubits: &[usize]) -> usize {
         let input_index = qubits.iter().enumerate().fold(0, |acc, (i, &qubit)| {
@@ -267,9 +297,52 @@ impl Statevector {
         new_state
     }
 
+    /// Estimates the memory held by the stored amplitudes, in bytes: every allocated slot of the
+    /// map, each holding a basis state index, its amplitude and a control byte.
+    pub fn memory_bytes(&self) -> usize {
+        self.vector.capacity() * (std::mem::size_of::<(usize, Complex<f64>)>() + 1)
+    }
+
+    /// Returns the measurement probability of every stored basis state.
+    ///
+    /// # Returns
+    /// A map from basis state index to `|amplitude|²`. States absent from the map have probability `0`.
+    pub fn probabilities(&self) -> HashMap<usize, f64> {
+        self.vector
+            .iter()
+            .map(|(&state, amp)| (state, amp.norm_sqr()))
+            .collect()
+    }
+
+    /// Measures every qubit `shots` times, leaving the state untouched.
+    ///
+    /// # Returns
+    /// The basis state index observed in each shot, or no shots if the state is empty.
+    pub fn sample(&self, shots: usize) -> Vec<usize> {
+        let mut outcomes: Vec<(usize, f64)> = self.probabilities().into_iter().collect();
+        outcomes.sort_by_key(|&(state, _)| state);
+        let Ok(distribution) = WeightedIndex::new(outcomes.iter().map(|&(_, probability)| probability)) else {
+            return Vec::new();
+        };
+        let mut rng = crate::config::rng();
+        (0..shots).map(|_| outcomes[distribution.sample(&mut rng)].0).collect()
+    }
+
+    /// Measures every qubit `shots` times, leaving the state untouched.
+    ///
+    /// # Returns
+    /// How often each basis state was observed, keyed by its [`bitstring`].
+    pub fn sample_counts(&self, shots: usize) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for outcome in self.sample(shots) {
+            *counts.entry(bitstring(outcome, self.num_qubits)).or_insert(0) += 1;
+        }
+        counts
+    }
+
     /// Normalizes the statevector to ensure the sum of squared amplitudes equals 1.
     pub fn normalize(&mut self) {
-        let norm: f64 = self.vector.iter().map(|(_, amp)| amp.norm_sqr()).sum();
+        let norm: f64 = self.vector.values().map(|amp| amp.norm_sqr()).sum();
         if norm != 0.0 {
             let scale = 1.0 / norm.sqrt();
             self.vector.values_mut().for_each(|amp| *amp *= scale);
@@ -277,28 +350,25 @@ impl Statevector {
     }
 
     /// Validates the statevector for correctness.
-    /// - Checks normalization and dimensional consistency.
-    pub fn validate(&self) -> Result<(), String> {
-        if self.vector.is_empty() {
-            return Err("Statevector is empty.".to_string());
-        }
-
-        let max_index = self.vector.keys().copied().max().unwrap(); // Highest index
-        let num_qubits = (max_index as f64).log2().ceil() as usize; // Infer number of qubits
-
-        let max_allowed_index = (1 << num_qubits) - 1; // 2^n - 1
+    /// - Checks that it is not empty and that every stored index fits in `num_qubits` qubits.
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        let max_index = self.vector.keys().copied().max().ok_or(SimulationError::EmptyState)?;
+        let max_allowed_index = 1usize.checked_shl(self.num_qubits as u32).map_or(usize::MAX, |size| size - 1);
         if max_index > max_allowed_index {
-            return Err(format!(
-                "Statevector is inconsistent: index {} exceeds max index {} for {} qubits.",
-                max_index, max_allowed_index, num_qubits
-            ));
+            return Err(SimulationError::InvalidIndex {
+                index: max_index,
+                max: max_allowed_index,
+                num_qubits: self.num_qubits,
+            });
         }
 
         Ok(())
     }
+}
 
-
-
+/// Formats a basis state of `qubits` qubits as a bitstring with qubit 0 rightmost.
+pub fn bitstring(state: usize, qubits: usize) -> String {
+    format!("{:0width$b}", state, width = qubits)
 }
 
 #[cfg(test)]
@@ -372,6 +442,43 @@ mod tests {
         }
     }
 
+    /// Single-qubit gates must also act on states whose target bit is set without a stored partner.
+    mod unpaired_states {
+        use super::*;
+        use crate::circuit::gates::pauli_x;
+
+        #[test]
+        fn test_hadamard_on_one_state() {
+            let mut sv = Statevector::new(1);
+            sv.apply_gate(pauli_x(), &[0]); // |1⟩
+            sv.apply_gate(hadamard(), &[0]); // |−⟩
+
+            let scale = 1.0 / 2.0_f64.sqrt();
+            assert!(approx_eq(sv.vector[&0], Complex::new(scale, 0.0), 1e-10));
+            assert!(approx_eq(sv.vector[&1], Complex::new(-scale, 0.0), 1e-10));
+        }
+    }
+
+    /// Diagonal Gate Application Tests
+    mod diagonal_gates {
+        use super::*;
+        use crate::circuit::gates::phase_oracle;
+
+        #[test]
+        fn test_phase_oracle_flips_marked_amplitude() {
+            let mut sv = Statevector::new(2);
+            sv.apply_gate(hadamard(), &[0]);
+            sv.apply_gate(hadamard(), &[1]);
+            sv.apply_gate(phase_oracle(2, |x| x == 3), &[0, 1]);
+
+            assert!(approx_eq(sv.vector[&3], Complex::new(-0.5, 0.0), 1e-10));
+            assert!(approx_eq(sv.vector[&0], Complex::new(0.5, 0.0), 1e-10));
+
+            let total: f64 = sv.probabilities().values().sum();
+            assert!((total - 1.0).abs() < 1e-10);
+        }
+    }
+
     /// Multi-Qubit Gate Application Tests
     mod multi_qubit_gates {
         use super::*;
@@ -407,6 +514,26 @@ mod tests {
             assert_eq!(sv.vector, expected_vector);
         }
 
+        #[test]
+        fn test_apply_controlled_hadamard_matrix() {
+            let mut sv = Statevector::new(3);
+            sv.vector.clear();
+            sv.vector.insert(0b110, Complex::new(1.0, 0.0)); // Both controls set, target |0⟩
+
+            // Controlled on qubits 1 and 2, with qubit 0 as the target.
+            sv.apply_gate(crate::circuit::gates::controlled(hadamard(), 2), &[0, 1, 2]);
+
+            let scale = 1.0 / 2.0_f64.sqrt();
+            assert_eq!(sv.vector.len(), 2);
+            assert!((sv.vector[&0b110].re - scale).abs() < 1e-10);
+            assert!((sv.vector[&0b111].re - scale).abs() < 1e-10);
+
+            // With a control unset the gate does nothing.
+            let mut sv = Statevector::new(3);
+            sv.apply_gate(crate::circuit::gates::controlled(hadamard(), 2), &[2, 0, 1]);
+            assert_eq!(sv.vector, HashMap::from([(0, Complex::new(1.0, 0.0))]));
+        }
+
         #[test]
         fn test_apply_cnot_to_all_zeros() {
             let mut sv = Statevector::new(2);
@@ -479,7 +606,6 @@ mod tests {
         #[test]
         fn test_cnot_matrix() {
             let cnot_gate = cnot();
-            let zero = Complex::new(0.0, 0.0);
             let one = Complex::new(1.0, 0.0);
 
             if let Gate::Two(matrix) = cnot_gate {
@@ -567,24 +693,25 @@ mod tests {
         use super::*;
 
         #[test]
-        #[should_panic(expected = "Qubit indices must be within the range of the quantum system.")]
+        #[should_panic(expected = "are not within a system of 2 qubits")]
         fn test_apply_gate_exceeding_qubits() {
             let mut sv = Statevector::new(2); // Start with an empty statevector
             sv.apply_gate(cnot(), &[0, 1, 100]); // Invalid gate, qubit 100 is out of logical range
         }
 
         #[test]
-        #[should_panic(expected = "Statevector is inconsistent: index 8 exceeds max index 7 for 3 qubits.")]
         fn test_apply_gate_invalid_statevector_size() {
-            let mut sv = create_statevector(vec![
+            let sv = create_statevector(vec![
                 (0, Complex::new(1.0, 0.0)),
-                (8, Complex::new(0.5, 0.0)), // Invalid index for 3 qubits
+                (8, Complex::new(0.5, 0.0)), // Invalid index for 2 qubits
             ]);
-            sv.validate().unwrap(); // Validation fails due to the invalid state.
+            let error = sv.validate().unwrap_err(); // Validation fails due to the invalid state.
+            assert_eq!(error, SimulationError::InvalidIndex { index: 8, max: 3, num_qubits: 2 });
+            assert_eq!(error.to_string(), "statevector is inconsistent: index 8 exceeds max index 3 for 2 qubits");
         }
 
         #[test]
-        #[should_panic(expected = "Qubit indices must be within the range of the quantum system.")]
+        #[should_panic(expected = "are not within a system of 2 qubits")]
         fn test_invalid_gate_size() {
             let mut sv = Statevector::new(2);
             sv.vector.insert(0, Complex::new(1.0, 0.0));
@@ -616,7 +743,7 @@ mod tests {
         #[test]
         fn test_apply_gate_to_empty_statevector() {
             let sv = create_statevector(vec![]); // Empty statevector
-            assert!(sv.validate().is_err(), "Validation should fail for empty statevector");
+            assert_eq!(sv.validate(), Err(SimulationError::EmptyState), "Validation should fail for empty statevector");
         }
 
         #[test]
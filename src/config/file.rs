@@ -0,0 +1,324 @@
+//! Configuration Files
+//!
+//! A [`Config`] gathers the settings that applications and the `zana` command line share, read
+//! from a `zana.toml` and overridden by environment variables named after the section and key:
+//! `threads` in `[simulator]` is overridden by `ZANA_SIMULATOR_THREADS`. Every key is optional.
+//!
+//! ```toml
+//! [simulator]
+//! backend = "sparse"       # Or "dense", for highly entangled circuits.
+//! threads = 8              # Workers of a JobRunner.
+//! max_qubits = 24
+//! memory_limit_mb = 4096   # The most a statevector may use.
+//! seed = 42                # Makes stochastic results reproducible.
+//!
+//! [visualization]
+//! output_dir = "charts"
+//! width = 800
+//! height = 600
+//!
+//! [keystore]
+//! path = "keys"            # Where keypairs are saved.
+//! keyring = "keys/keyring.json"
+//!
+//! [server]
+//! address = "127.0.0.1:8080"
+//!
+//! [models.planner]         # Model providers for agents, by name.
+//! backend = "openai"
+//! model = "gpt-4o-mini"
+//! api_key = "sk-..."       # OPENAI_API_KEY takes precedence.
+//! ```
+//!
+//! [`Config::load`] reads the file named by `ZANA_CONFIG`, or `zana.toml` in the working directory
+//! if it exists, then applies the environment.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::circuit::backend::Backend;
+use crate::config::RunConfig;
+
+/// The file [`Config::load`] reads when `ZANA_CONFIG` is unset.
+pub const DEFAULT_CONFIG_FILE: &str = "zana.toml";
+
+/// An error raised while loading a [`Config`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ConfigError {
+    /// The configuration file could not be read.
+    #[error("cannot read {}: {message}", path.display())]
+    Read { path: PathBuf, message: String },
+    /// The configuration file is not valid TOML, or a key has the wrong type.
+    #[error("invalid configuration: {0}")]
+    Parse(String),
+    /// An environment variable override could not be parsed.
+    #[error("invalid value '{value}' for {variable}")]
+    Env { variable: String, value: String },
+    /// A model provider names a backend that does not exist.
+    #[error("unknown model backend '{0}'")]
+    UnknownModelBackend(String),
+}
+
+/// The settings read from a `zana.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub simulator: SimulatorSettings,
+    pub visualization: VisualizationSettings,
+    pub keystore: KeystoreSettings,
+    pub server: ServerSettings,
+    /// Model providers for agents, by name.
+    pub models: BTreeMap<String, ModelSettings>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimulatorSettings {
+    pub backend: Backend,
+    /// How many jobs run at once, one per core if unset.
+    pub threads: Option<usize>,
+    /// The widest circuit to accept.
+    pub max_qubits: Option<usize>,
+    /// The most memory a statevector may use, in mebibytes.
+    pub memory_limit_mb: Option<usize>,
+    /// Seeds every stochastic result, as [`set_seed`](crate::config::set_seed) does.
+    pub seed: Option<u64>,
+}
+
+impl SimulatorSettings {
+    /// The widest circuit allowed by both `max_qubits` and `memory_limit_mb`, where every
+    /// amplitude of a dense state takes 16 bytes. `None` if neither is set.
+    pub fn qubit_limit(&self) -> Option<usize> {
+        let by_memory = self.memory_limit_mb.map(|megabytes| {
+            let amplitudes = (megabytes as u128 * 1024 * 1024 / 16).max(1);
+            amplitudes.ilog2() as usize
+        });
+        match (self.max_qubits, by_memory) {
+            (Some(qubits), Some(memory)) => Some(qubits.min(memory)),
+            (qubits, memory) => qubits.or(memory),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VisualizationSettings {
+    /// Where charts are written.
+    pub output_dir: PathBuf,
+    /// The size of charts, in pixels.
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Default for VisualizationSettings {
+    fn default() -> Self {
+        Self { output_dir: PathBuf::from("."), width: 800, height: 600 }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeystoreSettings {
+    /// The directory keypairs are saved to and loaded from.
+    pub path: Option<PathBuf>,
+    /// The file of a [`Keyring`](crate::crypto::keyring::Keyring).
+    pub keyring: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerSettings {
+    /// Where `zana serve` listens.
+    pub address: String,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self { address: "127.0.0.1:8080".to_string() }
+    }
+}
+
+/// A model provider of the `[models]` section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModelSettings {
+    /// `openai`, `anthropic`, `ollama`, `endpoint` or `local`.
+    pub backend: String,
+    #[serde(default)]
+    pub model: String,
+    /// The backend's public API if unset.
+    #[serde(default)]
+    pub api_url: Option<String>,
+    /// Used only when the backend's own variable, such as `OPENAI_API_KEY`, is unset.
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
+
+impl Config {
+    /// Reads the file named by `ZANA_CONFIG`, or [`DEFAULT_CONFIG_FILE`] if it exists, and
+    /// applies the environment overrides. Without either file the defaults are used.
+    ///
+    /// # Errors
+    /// If the file cannot be read or parsed, or an override cannot be parsed.
+    pub fn load() -> Result<Self, ConfigError> {
+        let mut config = match std::env::var_os("ZANA_CONFIG") {
+            Some(path) => Self::from_file(path)?,
+            None if Path::new(DEFAULT_CONFIG_FILE).exists() => Self::from_file(DEFAULT_CONFIG_FILE)?,
+            None => Self::default(),
+        };
+        config.apply_env()?;
+        Ok(config)
+    }
+
+    /// Reads a configuration file, without environment overrides.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)
+            .map_err(|error| ConfigError::Read { path: path.to_path_buf(), message: error.to_string() })?;
+        Self::from_toml(&source)
+    }
+
+    pub fn from_toml(source: &str) -> Result<Self, ConfigError> {
+        toml::from_str(source).map_err(|error| ConfigError::Parse(error.message().to_string()))
+    }
+
+    /// Writes the configuration as TOML, as [`Config::from_toml`] reads it.
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).expect("configurations are plain TOML tables")
+    }
+
+    /// Applies the `ZANA_*` environment variables over the file's settings.
+    pub fn apply_env(&mut self) -> Result<(), ConfigError> {
+        self.apply_overrides(|variable| std::env::var(variable).ok())
+    }
+
+    /// Applies overrides looked up by environment variable name, as [`Config::apply_env`] does
+    /// with the process environment.
+    pub fn apply_overrides(&mut self, lookup: impl Fn(&str) -> Option<String>) -> Result<(), ConfigError> {
+        fn parse<T: std::str::FromStr>(variable: &str, value: String) -> Result<T, ConfigError> {
+            value.parse().map_err(|_| ConfigError::Env { variable: variable.to_string(), value })
+        }
+
+        let simulator = &mut self.simulator;
+        let visualization = &mut self.visualization;
+        for variable in ENV_VARIABLES {
+            let Some(value) = lookup(variable) else { continue };
+            match variable {
+                "ZANA_SIMULATOR_BACKEND" => simulator.backend = parse(variable, value)?,
+                "ZANA_SIMULATOR_THREADS" => simulator.threads = Some(parse(variable, value)?),
+                "ZANA_SIMULATOR_MAX_QUBITS" => simulator.max_qubits = Some(parse(variable, value)?),
+                "ZANA_SIMULATOR_MEMORY_LIMIT_MB" => simulator.memory_limit_mb = Some(parse(variable, value)?),
+                "ZANA_SIMULATOR_SEED" => simulator.seed = Some(parse(variable, value)?),
+                "ZANA_VISUALIZATION_OUTPUT_DIR" => visualization.output_dir = PathBuf::from(value),
+                "ZANA_VISUALIZATION_WIDTH" => visualization.width = parse(variable, value)?,
+                "ZANA_VISUALIZATION_HEIGHT" => visualization.height = parse(variable, value)?,
+                "ZANA_KEYSTORE_PATH" => self.keystore.path = Some(PathBuf::from(value)),
+                "ZANA_KEYSTORE_KEYRING" => self.keystore.keyring = Some(PathBuf::from(value)),
+                "ZANA_SERVER_ADDRESS" => self.server.address = value,
+                _ => unreachable!("every variable is handled"),
+            }
+        }
+        Ok(())
+    }
+
+    /// A [`RunConfig`] seeded as the `[simulator]` section says.
+    pub fn run_config(&self) -> RunConfig {
+        RunConfig { seed: self.simulator.seed }
+    }
+}
+
+/// The environment variables read by [`Config::apply_env`].
+pub const ENV_VARIABLES: [&str; 11] = [
+    "ZANA_SIMULATOR_BACKEND",
+    "ZANA_SIMULATOR_THREADS",
+    "ZANA_SIMULATOR_MAX_QUBITS",
+    "ZANA_SIMULATOR_MEMORY_LIMIT_MB",
+    "ZANA_SIMULATOR_SEED",
+    "ZANA_VISUALIZATION_OUTPUT_DIR",
+    "ZANA_VISUALIZATION_WIDTH",
+    "ZANA_VISUALIZATION_HEIGHT",
+    "ZANA_KEYSTORE_PATH",
+    "ZANA_KEYSTORE_KEYRING",
+    "ZANA_SERVER_ADDRESS",
+];
+
+#[cfg(all(feature = "agents", not(target_arch = "wasm32")))]
+impl ModelSettings {
+    /// The agents' settings for this provider.
+    pub fn model_config(&self) -> Result<crate::agents::models::ModelConfig, ConfigError> {
+        use crate::agents::models::{Backend, ModelConfig};
+
+        let backend: Backend = serde_json::from_value(serde_json::Value::String(self.backend.clone()))
+            .map_err(|_| ConfigError::UnknownModelBackend(self.backend.clone()))?;
+        let config = ModelConfig::new(backend, &self.model);
+        Ok(match &self.api_url {
+            Some(api_url) => config.with_api_url(api_url),
+            None => config,
+        })
+    }
+
+    /// Connects to the provider, with the API key of the backend's environment variable or
+    /// else of the file.
+    pub fn connect(&self) -> Result<Box<dyn crate::agents::models::ModelProvider>, ConfigError> {
+        let config = self.model_config()?;
+        let api_key = config
+            .backend
+            .api_key_variable()
+            .and_then(|variable| std::env::var(variable).ok())
+            .or_else(|| self.api_key.clone());
+        Ok(config.connect_with_api_key(api_key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r#"
+        [simulator]
+        threads = 4
+        max_qubits = 30
+        memory_limit_mb = 1
+
+        [keystore]
+        keyring = "keys/keyring.json"
+
+        [models.planner]
+        backend = "ollama"
+        model = "llama3"
+    "#;
+
+    #[test]
+    fn test_file_and_overrides() {
+        let mut config = Config::from_toml(EXAMPLE).unwrap();
+        assert_eq!(config.simulator.threads, Some(4));
+        assert_eq!(config.simulator.backend, Backend::Sparse);
+        assert_eq!(config.keystore.keyring, Some(PathBuf::from("keys/keyring.json")));
+        assert_eq!(config.models["planner"].model, "llama3");
+        assert_eq!(config.visualization, VisualizationSettings::default());
+        // A mebibyte holds 2^16 amplitudes.
+        assert_eq!(config.simulator.qubit_limit(), Some(16));
+
+        let environment = [("ZANA_SIMULATOR_THREADS", "2"), ("ZANA_SIMULATOR_SEED", "7"), ("ZANA_SERVER_ADDRESS", "0.0.0.0:9000")];
+        let lookup = |variable: &str| environment.iter().find(|(name, _)| *name == variable).map(|(_, value)| value.to_string());
+        config.apply_overrides(lookup).unwrap();
+        assert_eq!((config.simulator.threads, config.simulator.max_qubits), (Some(2), Some(30)));
+        assert_eq!(config.run_config().seed, Some(7));
+        assert_eq!(config.server.address, "0.0.0.0:9000");
+
+        assert_eq!(Config::from_toml(&config.to_toml()).unwrap(), config);
+    }
+
+    #[test]
+    fn test_invalid_settings() {
+        assert!(matches!(Config::from_toml("[simulator]\nthreds = 4"), Err(ConfigError::Parse(_))));
+        assert!(matches!(Config::from_toml("[simulator]\nbackend = \"analog\""), Err(ConfigError::Parse(_))));
+        assert_eq!(
+            Config::default().apply_overrides(|_| Some("many".to_string())),
+            Err(ConfigError::Env { variable: "ZANA_SIMULATOR_BACKEND".to_string(), value: "many".to_string() })
+        );
+        assert!(matches!(Config::from_file("does/not/exist.toml"), Err(ConfigError::Read { .. })));
+    }
+}
@@ -0,0 +1,197 @@
+//! Run Configuration
+//!
+//! Every stochastic result in the crate draws from [`rng`]: measurements and sampled counts,
+//! noise trajectories, the QKD simulations, agent exploration and the initial parameters of
+//! trainable models. By default it is the thread-local generator, seeded by the operating system.
+//!
+//! [`set_seed`] makes every later draw reproducible for a single-threaded program. A [`RunConfig`]
+//! seeds one closure on the current thread only, so that runs on several threads, such as the
+//! jobs of a [`JobRunner`](crate::circuit::runner::JobRunner) or parallel tests, cannot disturb
+//! each other's sequence.
+//!
+//! ```
+//! use zana::circuit::{gates, QuantumCircuit};
+//! use zana::config::RunConfig;
+//!
+//! let mut circuit = QuantumCircuit::new(3);
+//! (0..3).for_each(|qubit| circuit.add_gate(gates::hadamard(), vec![qubit]));
+//! let state = circuit.simulate().statevector;
+//!
+//! let run = RunConfig::new().with_seed(42);
+//! assert_eq!(run.run(|| state.sample_counts(100)), run.run(|| state.sample_counts(100)));
+//! ```
+//!
+//! Keys, nonces and salts never come from here: the crypto module always draws them from the
+//! operating system, seeded or not. That includes the keys of
+//! [`qkd::establish_key`](crate::crypto::qkd::establish_key) and the output of
+//! [`QuantumRng`](crate::crypto::qrng::QuantumRng) used as a key source, although the QKD and
+//! QRNG simulations themselves follow the seed.
+//!
+//! The settings shared by applications and the `zana` command line, including the seed, are
+//! read from a `zana.toml` by [`Config::load`].
+
+pub mod file;
+
+use std::cell::RefCell;
+use std::sync::{Mutex, MutexGuard};
+use rand::rngs::{StdRng, ThreadRng};
+use rand::{RngCore, SeedableRng};
+pub use file::{Config, ConfigError};
+
+/// The generator behind [`set_seed`]. Each [`rng`] call draws the seed of a fresh generator from
+/// it, so that a seeded run repeats exactly whatever the draws in between.
+static GLOBAL: Mutex<Option<StdRng>> = Mutex::new(None);
+
+thread_local! {
+    /// The generator of the [`RunConfig::run`] in progress on this thread, if any.
+    static SCOPED: RefCell<Option<StdRng>> = const { RefCell::new(None) };
+}
+
+fn global() -> MutexGuard<'static, Option<StdRng>> {
+    GLOBAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Seeds every later stochastic result in the crate, on every thread.
+///
+/// The results repeat from run to run only if the draws happen in the same order, which holds
+/// for a single thread. Seed concurrent work with a [`RunConfig`] instead.
+pub fn set_seed(seed: u64) {
+    *global() = Some(StdRng::seed_from_u64(seed));
+}
+
+/// Undoes [`set_seed`], returning to the operating system's randomness.
+pub fn clear_seed() {
+    *global() = None;
+}
+
+/// A generator for one stochastic operation: seeded if a [`RunConfig`] is running on this thread
+/// or [`set_seed`] was called, and the thread-local generator otherwise.
+pub fn rng() -> RunRng {
+    let scoped = SCOPED.with(|scoped| scoped.borrow_mut().as_mut().map(RngCore::next_u64));
+    match scoped.or_else(|| global().as_mut().map(RngCore::next_u64)) {
+        Some(seed) => RunRng::Seeded(Box::new(StdRng::seed_from_u64(seed))),
+        None => RunRng::Thread(rand::thread_rng()),
+    }
+}
+
+/// A [`StdRng`] to keep, for the types that own their generator, seeded from [`rng`].
+pub fn std_rng() -> StdRng {
+    match rng() {
+        RunRng::Seeded(rng) => *rng,
+        RunRng::Thread(_) => StdRng::from_entropy(),
+    }
+}
+
+/// The generator returned by [`rng`].
+pub enum RunRng {
+    Thread(ThreadRng),
+    Seeded(Box<StdRng>),
+}
+
+impl RngCore for RunRng {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            RunRng::Thread(rng) => rng.next_u32(),
+            RunRng::Seeded(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            RunRng::Thread(rng) => rng.next_u64(),
+            RunRng::Seeded(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            RunRng::Thread(rng) => rng.fill_bytes(dest),
+            RunRng::Seeded(rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        match self {
+            RunRng::Thread(rng) => rng.try_fill_bytes(dest),
+            RunRng::Seeded(rng) => rng.try_fill_bytes(dest),
+        }
+    }
+}
+
+/// Settings for one run of stochastic work on the current thread.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunConfig {
+    /// Seeds every draw made by [`RunConfig::run`]. Without a seed the run draws from the
+    /// operating system, even if [`set_seed`] was called.
+    pub seed: Option<u64>,
+}
+
+impl RunConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Runs `work` on the current thread with this configuration, restoring the previous one
+    /// afterwards, even if `work` panics. Threads spawned by `work` are not affected.
+    pub fn run<T>(&self, work: impl FnOnce() -> T) -> T {
+        struct Restore(Option<StdRng>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                SCOPED.with(|scoped| *scoped.borrow_mut() = self.0.take());
+            }
+        }
+
+        let rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let _restore = Restore(SCOPED.with(|scoped| scoped.borrow_mut().replace(rng)));
+        work()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_runs_repeat_and_nest() {
+        let draws = || (0..4).map(|_| rng().gen::<u64>()).collect::<Vec<_>>();
+        let seeded = RunConfig::new().with_seed(7);
+        let first = seeded.run(draws);
+        assert_eq!(first, seeded.run(draws));
+        assert_ne!(first, RunConfig::new().with_seed(8).run(draws));
+
+        let nested = seeded.run(|| {
+            let outer = rng().gen::<u64>();
+            RunConfig::new().with_seed(1).run(draws);
+            (outer, rng().gen::<u64>())
+        });
+        assert_eq!(nested, (first[0], first[1]));
+    }
+
+    #[test]
+    fn test_seeded_simulations_repeat() {
+        use crate::circuit::noise::NoiseChannel;
+        use crate::circuit::statevector::Statevector;
+        use crate::circuit::gates;
+        use crate::crypto::qkd::bb84;
+
+        let simulate = || {
+            let mut state = Statevector::new(2);
+            state.apply_gate(gates::hadamard(), &[0]);
+            state.apply_gate(gates::hadamard(), &[1]);
+            let errors: Vec<_> = (0..16).map(|_| NoiseChannel::Depolarizing(0.5).apply(&mut state, 0)).collect();
+            (state.measure(1), errors, bb84::random_bases(32))
+        };
+        let run = RunConfig::new().with_seed(2024);
+        assert_eq!(run.run(simulate), run.run(simulate));
+    }
+}
@@ -1,30 +1,119 @@
-use rsa::{RsaPrivateKey, RsaPublicKey, Pkcs1v15Encrypt};
+use rsa::{BigUint, RsaPrivateKey, RsaPublicKey, Pkcs1v15Encrypt, Oaep};
+use rsa::pss::{BlindedSigningKey, Signature, VerifyingKey};
+use rsa::signature::{RandomizedSigner, SignatureEncoding, Verifier};
+use rsa::traits::PublicKeyParts;
 use rand::rngs::OsRng;
+use sha2::Sha256;
+use crate::crypto::error::CryptoError;
+use crate::crypto::secret::SecretBytes;
+use crate::crypto::symmetric::{self, generate_random_key, generate_random_nonce, NONCE_SIZE};
 
-/// Generates an RSA key pair (private and public keys).
+/// The modulus size used by [`generate_rsa_keys`].
+pub const DEFAULT_RSA_BITS: usize = 2048;
+
+/// The public exponent used by [`generate_rsa_keys`].
+pub const DEFAULT_RSA_EXPONENT: u64 = 65537;
+
+/// The smallest modulus size [`RsaKeyGenerator`] accepts.
+pub const MIN_RSA_BITS: usize = 1024;
+
+/// The padding scheme used for RSA encryption.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RsaPadding {
+    /// PKCS#1 v1.5 padding. Only use it for compatibility, since it is prone to padding-oracle
+    /// attacks.
+    Pkcs1v15,
+    /// OAEP with SHA-256 for both the hash and MGF1.
+    OaepSha256,
+}
+
+impl RsaPadding {
+    /// The number of bytes the padding adds, which bounds the message size.
+    fn overhead(&self) -> usize {
+        match self {
+            RsaPadding::Pkcs1v15 => 11,
+            RsaPadding::OaepSha256 => 2 * 32 + 2,
+        }
+    }
+}
+
+/// Builder for RSA key pairs with a configurable modulus size and public exponent.
+///
+/// # Example
+/// ```
+/// use zana::crypto::asymmetric::RsaKeyGenerator;
+///
+/// let (private_key, public_key) = RsaKeyGenerator::new().with_bits(1024).generate().unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct RsaKeyGenerator {
+    bits: usize,
+    public_exponent: u64,
+}
+
+impl Default for RsaKeyGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RsaKeyGenerator {
+    /// Creates a generator for [`DEFAULT_RSA_BITS`]-bit keys with exponent [`DEFAULT_RSA_EXPONENT`].
+    pub fn new() -> Self {
+        Self { bits: DEFAULT_RSA_BITS, public_exponent: DEFAULT_RSA_EXPONENT }
+    }
+
+    /// Sets the modulus size in bits.
+    pub fn with_bits(mut self, bits: usize) -> Self {
+        self.bits = bits;
+        self
+    }
+
+    /// Sets the public exponent.
+    pub fn with_public_exponent(mut self, public_exponent: u64) -> Self {
+        self.public_exponent = public_exponent;
+        self
+    }
+
+    /// Generates the key pair.
+    ///
+    /// # Returns
+    /// A tuple containing the private key and public key, or `CryptoError::KeyGenerationFailed` if
+    /// the modulus is smaller than [`MIN_RSA_BITS`] or the exponent is not an odd number of at
+    /// least 3.
+    pub fn generate(&self) -> Result<(RsaPrivateKey, RsaPublicKey), CryptoError> {
+        if self.bits < MIN_RSA_BITS || self.public_exponent < 3 || self.public_exponent.is_multiple_of(2) {
+            return Err(CryptoError::KeyGenerationFailed);
+        }
+        let mut rng = OsRng;
+        let exponent = BigUint::from(self.public_exponent);
+        let private_key = RsaPrivateKey::new_with_exp(&mut rng, self.bits, &exponent)
+            .map_err(|_| CryptoError::KeyGenerationFailed)?;
+        let public_key = RsaPublicKey::from(&private_key);
+        Ok((private_key, public_key))
+    }
+}
+
+/// Generates a 2048-bit RSA key pair (private and public keys).
 ///
 /// # Returns
 /// A tuple containing the private key and public key.
-pub fn generate_rsa_keys() -> (RsaPrivateKey, RsaPublicKey) {
-    let mut rng = OsRng;
-    let private_key = RsaPrivateKey::new(&mut rng, 2048).expect("Failed to generate a key");
-    let public_key = RsaPublicKey::from(&private_key);
-    (private_key, public_key)
+pub fn generate_rsa_keys() -> Result<(RsaPrivateKey, RsaPublicKey), CryptoError> {
+    RsaKeyGenerator::new().generate()
 }
 
 /// Encrypts data using the RSA public key and PKCS1 v1.5 padding.
 ///
+/// Prefer [`rsa_encrypt_oaep`] for new code.
+///
 /// # Arguments
 /// - `public_key`: The RSA public key.
 /// - `plaintext`: The data to encrypt.
 ///
 /// # Returns
 /// The encrypted data (ciphertext).
-pub fn rsa_encrypt(public_key: &RsaPublicKey, plaintext: &[u8]) -> Vec<u8> {
-    let mut rng = OsRng;
-    public_key
-        .encrypt(&mut rng, Pkcs1v15Encrypt, plaintext)
-        .expect("Failed to encrypt")
+pub fn rsa_encrypt(public_key: &RsaPublicKey, plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    rsa_encrypt_with_padding(public_key, plaintext, RsaPadding::Pkcs1v15)
 }
 
 /// Decrypts data using the RSA private key and PKCS1 v.15 padding.
@@ -35,32 +124,287 @@ pub fn rsa_encrypt(public_key: &RsaPublicKey, plaintext: &[u8]) -> Vec<u8> {
 ///
 /// # Returns
 /// The decrypted data (plaintext).
-pub fn rsa_decrypt(private_key: &RsaPrivateKey, ciphertext: &[u8]) -> Vec<u8> {
-    private_key
-        .decrypt(Pkcs1v15Encrypt, ciphertext)
-        .expect("Failed to decrypt")
+pub fn rsa_decrypt(private_key: &RsaPrivateKey, ciphertext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    rsa_decrypt_with_padding(private_key, ciphertext, RsaPadding::Pkcs1v15)
+}
+
+/// Encrypts data using the RSA public key and the given padding scheme.
+///
+/// # Arguments
+/// - `public_key`: The RSA public key.
+/// - `plaintext`: The data to encrypt.
+/// - `padding`: The padding scheme.
+///
+/// # Returns
+/// The encrypted data (ciphertext), or `CryptoError::MessageTooLong` if the plaintext does not
+/// fit in the modulus.
+pub fn rsa_encrypt_with_padding(
+    public_key: &RsaPublicKey,
+    plaintext: &[u8],
+    padding: RsaPadding,
+) -> Result<Vec<u8>, CryptoError> {
+    let max = public_key.size().saturating_sub(padding.overhead());
+    if plaintext.len() > max {
+        return Err(CryptoError::MessageTooLong { max, actual: plaintext.len() });
+    }
+
+    let mut rng = OsRng;
+    let result = match padding {
+        RsaPadding::Pkcs1v15 => public_key.encrypt(&mut rng, Pkcs1v15Encrypt, plaintext),
+        RsaPadding::OaepSha256 => public_key.encrypt(&mut rng, Oaep::new::<Sha256>(), plaintext),
+    };
+    result.map_err(|_| CryptoError::EncryptionFailed)
+}
+
+/// Decrypts data using the RSA private key and the given padding scheme.
+///
+/// # Arguments
+/// - `private_key`: The RSA private key.
+/// - `ciphertext`: The encrypted data to decrypt.
+/// - `padding`: The padding scheme the data was encrypted with.
+///
+/// # Returns
+/// The decrypted data (plaintext), or `CryptoError::DecryptionFailed`.
+pub fn rsa_decrypt_with_padding(
+    private_key: &RsaPrivateKey,
+    ciphertext: &[u8],
+    padding: RsaPadding,
+) -> Result<Vec<u8>, CryptoError> {
+    let result = match padding {
+        RsaPadding::Pkcs1v15 => private_key.decrypt(Pkcs1v15Encrypt, ciphertext),
+        RsaPadding::OaepSha256 => private_key.decrypt(Oaep::new::<Sha256>(), ciphertext),
+    };
+    result.map_err(|_| CryptoError::DecryptionFailed)
+}
+
+/// Encrypts data using the RSA public key and OAEP-SHA256 padding.
+///
+/// # Example
+/// ```
+/// use zana::crypto::asymmetric::{generate_rsa_keys, rsa_decrypt_oaep, rsa_encrypt_oaep};
+///
+/// let (private_key, public_key) = generate_rsa_keys().unwrap();
+/// let ciphertext = rsa_encrypt_oaep(&public_key, b"hello").unwrap();
+/// assert_eq!(rsa_decrypt_oaep(&private_key, &ciphertext).unwrap(), b"hello");
+/// ```
+pub fn rsa_encrypt_oaep(public_key: &RsaPublicKey, plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    rsa_encrypt_with_padding(public_key, plaintext, RsaPadding::OaepSha256)
+}
+
+/// Decrypts data using the RSA private key and OAEP-SHA256 padding.
+pub fn rsa_decrypt_oaep(private_key: &RsaPrivateKey, ciphertext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    rsa_decrypt_with_padding(private_key, ciphertext, RsaPadding::OaepSha256)
+}
+
+/// Signs a message with the RSA private key using RSASSA-PSS with SHA-256.
+///
+/// # Arguments
+/// - `private_key`: The RSA private key.
+/// - `message`: The data to sign.
+///
+/// # Returns
+/// The signature, as long as the key's modulus.
+///
+/// # Example
+/// ```
+/// use zana::crypto::asymmetric::{generate_rsa_keys, rsa_sign_pss, rsa_verify_pss};
+///
+/// let (private_key, public_key) = generate_rsa_keys().unwrap();
+/// let signature = rsa_sign_pss(&private_key, b"release-1.0.tar.gz").unwrap();
+/// assert!(rsa_verify_pss(&public_key, b"release-1.0.tar.gz", &signature));
+/// ```
+pub fn rsa_sign_pss(private_key: &RsaPrivateKey, message: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let mut rng = OsRng;
+    BlindedSigningKey::<Sha256>::new(private_key.clone())
+        .try_sign_with_rng(&mut rng, message)
+        .map(|signature| signature.to_vec())
+        .map_err(|_| CryptoError::SigningFailed)
+}
+
+/// Verifies an RSASSA-PSS (SHA-256) signature with the RSA public key.
+///
+/// # Returns
+/// `true` if the signature is valid for the message, `false` otherwise.
+pub fn rsa_verify_pss(public_key: &RsaPublicKey, message: &[u8], signature: &[u8]) -> bool {
+    let Ok(signature) = Signature::try_from(signature) else {
+        return false;
+    };
+    VerifyingKey::<Sha256>::new(public_key.clone())
+        .verify(message, &signature)
+        .is_ok()
+}
+
+/// Encrypts a message of any length for the RSA public key.
+///
+/// A random AES-256 key encrypts the payload with AES-GCM and is itself encrypted with RSA-OAEP.
+/// The result is laid out as `wrapped key length (u16, big-endian) ‖ wrapped key ‖ nonce ‖
+/// ciphertext`, with the wrapped key authenticated as associated data.
+///
+/// # Example
+/// ```
+/// use zana::crypto::asymmetric::{generate_rsa_keys, hybrid_decrypt, hybrid_encrypt};
+///
+/// let (private_key, public_key) = generate_rsa_keys().unwrap();
+/// let message = vec![7u8; 10_000];
+/// let ciphertext = hybrid_encrypt(&public_key, &message).unwrap();
+/// assert_eq!(hybrid_decrypt(&private_key, &ciphertext).unwrap(), message);
+/// ```
+pub fn hybrid_encrypt(public_key: &RsaPublicKey, plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let key = generate_random_key();
+    let nonce = generate_random_nonce()?;
+    let wrapped_key = rsa_encrypt_oaep(public_key, key.expose_secret())?;
+    let wrapped_len = u16::try_from(wrapped_key.len()).map_err(|_| CryptoError::EncryptionFailed)?;
+
+    let mut output = Vec::with_capacity(2 + wrapped_key.len() + NONCE_SIZE + plaintext.len() + 16);
+    output.extend_from_slice(&wrapped_len.to_be_bytes());
+    output.extend_from_slice(&wrapped_key);
+    output.extend_from_slice(&nonce);
+    output.extend(symmetric::encrypt_with_aad(key.expose_secret(), &nonce, plaintext, &wrapped_key)?);
+    Ok(output)
+}
+
+/// Decrypts a message produced by [`hybrid_encrypt`] with the RSA private key.
+///
+/// # Returns
+/// The plaintext, `CryptoError::InvalidInput` if the structure is truncated, or
+/// `CryptoError::DecryptionFailed` if the key does not match or the data was modified.
+pub fn hybrid_decrypt(private_key: &RsaPrivateKey, ciphertext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    const TRUNCATED: CryptoError = CryptoError::InvalidInput("hybrid ciphertext is truncated");
+
+    let (len, rest) = ciphertext.split_first_chunk::<2>().ok_or(TRUNCATED)?;
+    let wrapped_len = u16::from_be_bytes(*len) as usize;
+    if rest.len() < wrapped_len + NONCE_SIZE {
+        return Err(TRUNCATED);
+    }
+    let (wrapped_key, rest) = rest.split_at(wrapped_len);
+    let (nonce, sealed) = rest.split_at(NONCE_SIZE);
+
+    let key = SecretBytes::new(rsa_decrypt_oaep(private_key, wrapped_key)?);
+    symmetric::decrypt_with_aad(key.expose_secret(), nonce, sealed, wrapped_key)
 }
 
 #[cfg(test)]
 mod tests {
-    use rsa::traits::PublicKeyParts;
     use super::*;
 
     #[test]
     fn test_rsa_encryption_decryption() {
-        let (private_key, public_key) = generate_rsa_keys();
+        let (private_key, public_key) = generate_rsa_keys().unwrap();
         let message = b"zana quantum-ai";
 
-        let ciphertext = rsa_encrypt(&public_key, message);
-        let decrypted = rsa_decrypt(&private_key, &ciphertext);
+        let ciphertext = rsa_encrypt(&public_key, message).unwrap();
+        let decrypted = rsa_decrypt(&private_key, &ciphertext).unwrap();
 
         assert_eq!(decrypted, message, "Decrypted message does not match original");
     }
 
+    #[test]
+    fn test_rsa_oaep_encryption_decryption() {
+        let (private_key, public_key) = generate_rsa_keys().unwrap();
+        let message = b"zana quantum-ai";
+
+        let ciphertext = rsa_encrypt_oaep(&public_key, message).unwrap();
+        assert_eq!(rsa_decrypt_oaep(&private_key, &ciphertext).unwrap(), message);
+
+        // OAEP is randomized, and both schemes are reachable through the padding parameter
+        assert_ne!(rsa_encrypt_oaep(&public_key, message).unwrap(), ciphertext);
+        let pkcs1 = rsa_encrypt_with_padding(&public_key, message, RsaPadding::Pkcs1v15).unwrap();
+        assert_eq!(rsa_decrypt_with_padding(&private_key, &pkcs1, RsaPadding::Pkcs1v15).unwrap(), message);
+    }
+
+    #[test]
+    fn test_rsa_padding_mismatch_fails() {
+        let (private_key, public_key) = generate_rsa_keys().unwrap();
+        let ciphertext = rsa_encrypt_with_padding(&public_key, b"zana", RsaPadding::Pkcs1v15).unwrap();
+        assert_eq!(
+            rsa_decrypt_with_padding(&private_key, &ciphertext, RsaPadding::OaepSha256),
+            Err(CryptoError::DecryptionFailed)
+        );
+    }
+
+    #[test]
+    fn test_rsa_message_too_long() {
+        let (_, public_key) = generate_rsa_keys().unwrap();
+        let message = [0u8; 200];
+
+        assert_eq!(
+            rsa_encrypt_oaep(&public_key, &message),
+            Err(CryptoError::MessageTooLong { max: 190, actual: 200 })
+        );
+        assert!(rsa_encrypt(&public_key, &message).is_ok());
+        assert_eq!(
+            rsa_encrypt(&public_key, &[0u8; 246]),
+            Err(CryptoError::MessageTooLong { max: 245, actual: 246 })
+        );
+    }
+
+    #[test]
+    fn test_rsa_pss_signatures() {
+        let (private_key, public_key) = generate_rsa_keys().unwrap();
+        let message = b"zana quantum-ai";
+
+        let signature = rsa_sign_pss(&private_key, message).unwrap();
+        assert_eq!(signature.len(), public_key.size());
+        assert!(rsa_verify_pss(&public_key, message, &signature));
+        assert!(!rsa_verify_pss(&public_key, b"tampered", &signature));
+        assert!(!rsa_verify_pss(&public_key, message, &signature[1..]));
+
+        let (_, other_public_key) = generate_rsa_keys().unwrap();
+        assert!(!rsa_verify_pss(&other_public_key, message, &signature));
+    }
+
+    #[test]
+    fn test_hybrid_encryption() {
+        let (private_key, public_key) = generate_rsa_keys().unwrap();
+
+        for len in [0, 1, 190, 191, 100_000] {
+            let message: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let ciphertext = hybrid_encrypt(&public_key, &message).unwrap();
+            assert_eq!(ciphertext.len(), 2 + public_key.size() + NONCE_SIZE + len + 16);
+            assert_eq!(hybrid_decrypt(&private_key, &ciphertext).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn test_hybrid_decryption_failures() {
+        let (private_key, public_key) = generate_rsa_keys().unwrap();
+        let ciphertext = hybrid_encrypt(&public_key, b"zana quantum-ai").unwrap();
+
+        let (other_private_key, _) = generate_rsa_keys().unwrap();
+        assert_eq!(hybrid_decrypt(&other_private_key, &ciphertext), Err(CryptoError::DecryptionFailed));
+
+        let mut tampered = ciphertext.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert_eq!(hybrid_decrypt(&private_key, &tampered), Err(CryptoError::DecryptionFailed));
+
+        assert!(matches!(hybrid_decrypt(&private_key, &ciphertext[..100]), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(hybrid_decrypt(&private_key, &[1]), Err(CryptoError::InvalidInput(_))));
+    }
+
     #[test]
     fn test_rsa_key_generation() {
-        let (private_key, public_key) = generate_rsa_keys();
+        let (private_key, public_key) = generate_rsa_keys().unwrap();
 
         assert_eq!(private_key.n(), public_key.n(), "Private and public key moduli do not match");
+        assert_eq!(public_key.size(), DEFAULT_RSA_BITS / 8);
+    }
+
+    #[test]
+    fn test_rsa_key_generator_parameters() {
+        let (_, public_key) = RsaKeyGenerator::new()
+            .with_bits(1024)
+            .with_public_exponent(3)
+            .generate()
+            .unwrap();
+        assert_eq!(public_key.size(), 128);
+        assert_eq!(public_key.e(), &BigUint::from(3u64));
+
+        for generator in [
+            RsaKeyGenerator::new().with_bits(512),
+            RsaKeyGenerator::new().with_public_exponent(65536),
+            RsaKeyGenerator::new().with_public_exponent(1),
+        ] {
+            assert_eq!(generator.generate().unwrap_err(), CryptoError::KeyGenerationFailed);
+        }
     }
 }
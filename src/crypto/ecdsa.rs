@@ -0,0 +1,94 @@
+//! ECDSA over NIST P-256
+//!
+//! ECDSA signatures with SHA-256 on the P-256 (secp256r1) curve, as used by JWT (`ES256`), TLS and
+//! cloud key management services. Signatures use the fixed 64-byte `r ‖ s` encoding, and can be
+//! converted to ASN.1 DER with [`Signature::to_der`].
+
+use p256::ecdsa::signature::{Signer, Verifier};
+use rand::rngs::OsRng;
+
+pub use p256::ecdsa::{Signature, SigningKey, VerifyingKey};
+
+/// Generates a P-256 key pair (signing and verifying keys).
+///
+/// # Returns
+/// A tuple containing the signing key and verifying key.
+pub fn generate_p256_keypair() -> (SigningKey, VerifyingKey) {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let verifying_key = *signing_key.verifying_key();
+    (signing_key, verifying_key)
+}
+
+/// Signs a message with ECDSA P-256 and SHA-256.
+///
+/// Nonces are derived deterministically as in RFC 6979, so signing the same message twice yields
+/// the same signature.
+///
+/// # Example
+/// ```
+/// use zana::crypto::ecdsa::{generate_p256_keypair, p256_sign, p256_verify};
+///
+/// let (signing_key, verifying_key) = generate_p256_keypair();
+/// let signature = p256_sign(&signing_key, b"header.payload");
+/// assert!(p256_verify(&verifying_key, b"header.payload", &signature));
+/// ```
+pub fn p256_sign(signing_key: &SigningKey, message: &[u8]) -> Signature {
+    signing_key.sign(message)
+}
+
+/// Verifies an ECDSA P-256 signature.
+///
+/// # Returns
+/// `true` if the signature is valid for the message, `false` otherwise.
+pub fn p256_verify(verifying_key: &VerifyingKey, message: &[u8], signature: &Signature) -> bool {
+    verifying_key.verify(message, signature).is_ok()
+}
+
+/// Encodes a verifying key as an uncompressed SEC1 point (65 bytes).
+pub fn verifying_key_to_sec1(verifying_key: &VerifyingKey) -> Vec<u8> {
+    verifying_key.to_encoded_point(false).as_bytes().to_vec()
+}
+
+/// Decodes a verifying key from a compressed or uncompressed SEC1 point.
+///
+/// # Returns
+/// `None` if the bytes are not a valid point on the curve.
+pub fn verifying_key_from_sec1(bytes: &[u8]) -> Option<VerifyingKey> {
+    VerifyingKey::from_sec1_bytes(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let (signing_key, verifying_key) = generate_p256_keypair();
+        let signature = p256_sign(&signing_key, b"zana");
+
+        assert!(p256_verify(&verifying_key, b"zana", &signature));
+        assert!(!p256_verify(&verifying_key, b"anaz", &signature));
+        assert!(!p256_verify(&generate_p256_keypair().1, b"zana", &signature));
+        assert_eq!(p256_sign(&signing_key, b"zana"), signature, "RFC 6979 signing is deterministic");
+    }
+
+    #[test]
+    fn test_signature_encodings() {
+        let (signing_key, _) = generate_p256_keypair();
+        let signature = p256_sign(&signing_key, b"zana");
+
+        let der = signature.to_der();
+        assert_eq!(Signature::from_der(der.as_bytes()).unwrap(), signature);
+        assert_eq!(Signature::from_slice(&signature.to_bytes()).unwrap(), signature);
+    }
+
+    #[test]
+    fn test_sec1_round_trip() {
+        let (_, verifying_key) = generate_p256_keypair();
+        let encoded = verifying_key_to_sec1(&verifying_key);
+
+        assert_eq!(encoded.len(), 65);
+        assert_eq!(verifying_key_from_sec1(&encoded), Some(verifying_key));
+        assert_eq!(verifying_key_from_sec1(&[4u8; 65]), None);
+    }
+}
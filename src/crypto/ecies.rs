@@ -0,0 +1,152 @@
+//! ECIES Public-Key Encryption
+//!
+//! Integrated encryption over Curve25519: the sender generates an ephemeral X25519 key, performs
+//! Diffie–Hellman with the recipient's public key, derives a one-time AES-256-GCM key and nonce
+//! with HKDF-SHA256, and encrypts the message under them. Messages of any size are supported, and
+//! any modification of the ciphertext is detected.
+//!
+//! # Format
+//! `ephemeral public key (32) ‖ AES-GCM ciphertext ‖ tag (16)`, i.e. 48 bytes of overhead.
+
+use hkdf::Hkdf;
+use rand::rngs::OsRng;
+use sha2::Sha256;
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+use zeroize::Zeroize;
+use crate::crypto::error::CryptoError;
+use crate::crypto::secret::SecretKey;
+use crate::crypto::symmetric::{self, KEY_SIZE, NONCE_SIZE};
+
+/// The size of the ephemeral public key at the start of every ciphertext.
+pub const EPHEMERAL_KEY_SIZE: usize = 32;
+
+/// The total size ECIES adds to a message.
+pub const OVERHEAD: usize = EPHEMERAL_KEY_SIZE + 16;
+
+const HKDF_INFO: &[u8] = b"zana ecies x25519 aes-256-gcm";
+
+/// Generates an X25519 key pair for ECIES.
+///
+/// # Returns
+/// A tuple containing the secret key and public key.
+pub fn generate_keypair() -> (StaticSecret, PublicKey) {
+    let secret = StaticSecret::random_from_rng(OsRng);
+    let public = PublicKey::from(&secret);
+    (secret, public)
+}
+
+/// Derives the one-time key and nonce from the shared secret and both public keys.
+fn derive_key_and_nonce(
+    shared_secret: &[u8; 32],
+    ephemeral_public: &[u8; 32],
+    recipient_public: &[u8; 32],
+) -> (SecretKey, [u8; NONCE_SIZE]) {
+    let mut salt = [0u8; 64];
+    salt[..32].copy_from_slice(ephemeral_public);
+    salt[32..].copy_from_slice(recipient_public);
+
+    let mut okm = [0u8; KEY_SIZE + NONCE_SIZE];
+    Hkdf::<Sha256>::new(Some(&salt), shared_secret)
+        .expand(HKDF_INFO, &mut okm)
+        .expect("44 bytes is a valid HKDF-SHA256 output length");
+
+    let mut key = SecretKey::new([0u8; KEY_SIZE]);
+    let mut nonce = [0u8; NONCE_SIZE];
+    key.expose_secret_mut().copy_from_slice(&okm[..KEY_SIZE]);
+    nonce.copy_from_slice(&okm[KEY_SIZE..]);
+    okm.zeroize();
+    (key, nonce)
+}
+
+/// Encrypts a message to the recipient's public key.
+///
+/// # Returns
+/// The ciphertext, [`OVERHEAD`] bytes longer than the plaintext, or
+/// `CryptoError::InvalidInput` if the public key is a low-order point.
+///
+/// # Example
+/// ```
+/// use zana::crypto::ecies;
+///
+/// let (secret, public) = ecies::generate_keypair();
+/// let ciphertext = ecies::encrypt(&public, b"for your eyes only").unwrap();
+/// assert_eq!(ecies::decrypt(&secret, &ciphertext).unwrap(), b"for your eyes only");
+/// ```
+pub fn encrypt(recipient: &PublicKey, plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+    let ephemeral_public = PublicKey::from(&ephemeral).to_bytes();
+    let shared = ephemeral.diffie_hellman(recipient);
+    if !shared.was_contributory() {
+        return Err(CryptoError::InvalidInput("X25519 public key is a low-order point"));
+    }
+
+    let (key, nonce) = derive_key_and_nonce(shared.as_bytes(), &ephemeral_public, recipient.as_bytes());
+    let mut output = ephemeral_public.to_vec();
+    output.extend(symmetric::encrypt(key.expose_secret(), &nonce, plaintext)?);
+    Ok(output)
+}
+
+/// Decrypts a message produced by [`encrypt`] with the recipient's secret key.
+///
+/// # Returns
+/// The plaintext, `CryptoError::InvalidInput` if the ciphertext is too short, or
+/// `CryptoError::DecryptionFailed` if it was not encrypted to this key or was modified.
+pub fn decrypt(secret: &StaticSecret, ciphertext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    if ciphertext.len() < OVERHEAD {
+        return Err(CryptoError::InvalidInput("ECIES ciphertext is too short"));
+    }
+    let (ephemeral_public, sealed) = ciphertext.split_at(EPHEMERAL_KEY_SIZE);
+    let ephemeral_public: [u8; 32] = ephemeral_public.try_into().expect("split at 32 bytes");
+
+    let shared = secret.diffie_hellman(&PublicKey::from(ephemeral_public));
+    if !shared.was_contributory() {
+        return Err(CryptoError::DecryptionFailed);
+    }
+    let recipient_public = PublicKey::from(secret).to_bytes();
+    let (key, nonce) = derive_key_and_nonce(shared.as_bytes(), &ephemeral_public, &recipient_public);
+    symmetric::decrypt(key.expose_secret(), &nonce, sealed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_sizes() {
+        let (secret, public) = generate_keypair();
+        for len in [0, 1, 1000, 1 << 20] {
+            let message = vec![0x5a; len];
+            let ciphertext = encrypt(&public, &message).unwrap();
+            assert_eq!(ciphertext.len(), len + OVERHEAD);
+            assert_eq!(decrypt(&secret, &ciphertext).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn test_ciphertexts_are_randomized() {
+        let (_, public) = generate_keypair();
+        assert_ne!(encrypt(&public, b"zana").unwrap(), encrypt(&public, b"zana").unwrap());
+    }
+
+    #[test]
+    fn test_wrong_key_and_tampering() {
+        let (secret, public) = generate_keypair();
+        let ciphertext = encrypt(&public, b"zana").unwrap();
+
+        let (other_secret, _) = generate_keypair();
+        assert_eq!(decrypt(&other_secret, &ciphertext), Err(CryptoError::DecryptionFailed));
+
+        for index in [0, EPHEMERAL_KEY_SIZE, ciphertext.len() - 1] {
+            let mut tampered = ciphertext.clone();
+            tampered[index] ^= 1;
+            assert_eq!(decrypt(&secret, &tampered), Err(CryptoError::DecryptionFailed));
+        }
+        assert!(matches!(decrypt(&secret, &ciphertext[..OVERHEAD - 1]), Err(CryptoError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_rejects_low_order_public_key() {
+        let zero = PublicKey::from([0u8; 32]);
+        assert!(matches!(encrypt(&zero, b"zana"), Err(CryptoError::InvalidInput(_))));
+    }
+}
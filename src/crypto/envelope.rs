@@ -0,0 +1,304 @@
+//! Envelope Encryption
+//!
+//! Encrypts a payload once under a fresh random data key and wraps that data key separately for
+//! every recipient, so one blob can be opened by any of several key holders:
+//! - RSA recipients receive the data key encrypted with RSA-OAEP (SHA-256).
+//! - X25519 recipients receive it wrapped with AES-KW under a key derived by HKDF-SHA256 from an
+//!   ephemeral Diffie–Hellman exchange.
+//!
+//! # Format
+//! `"ZENV" ‖ version ‖ nonce ‖ recipient count ‖ recipients ‖ ciphertext`, where every recipient
+//! entry is `type ‖ [ephemeral public key] ‖ length ‖ wrapped key` with big-endian lengths. The
+//! header is bound to the AES-GCM ciphertext as associated data.
+
+use hkdf::Hkdf;
+use rand::rngs::OsRng;
+use rsa::{Oaep, RsaPrivateKey, RsaPublicKey};
+use sha2::Sha256;
+use x25519_dalek::{EphemeralSecret, PublicKey as X25519PublicKey, StaticSecret};
+use crate::crypto::error::CryptoError;
+use crate::crypto::secret::{SecretBytes, SecretKey};
+use crate::crypto::symmetric::{
+    decrypt_with_aad, encrypt_with_aad, generate_random_key, generate_random_nonce, unwrap_key, wrap_key,
+    NONCE_SIZE,
+};
+
+const MAGIC: &[u8; 4] = b"ZENV";
+const VERSION: u8 = 1;
+const RSA_RECIPIENT: u8 = 1;
+const X25519_RECIPIENT: u8 = 2;
+const HKDF_INFO: &[u8] = b"zana envelope x25519";
+
+/// A recipient's public key.
+#[derive(Debug, Clone)]
+pub enum RecipientPublicKey {
+    Rsa(RsaPublicKey),
+    X25519(X25519PublicKey),
+}
+
+/// A recipient's private key, used to open envelopes.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone)]
+pub enum RecipientPrivateKey {
+    Rsa(RsaPrivateKey),
+    X25519(StaticSecret),
+}
+
+impl RecipientPrivateKey {
+    /// Returns the matching public key.
+    pub fn public_key(&self) -> RecipientPublicKey {
+        match self {
+            RecipientPrivateKey::Rsa(key) => RecipientPublicKey::Rsa(RsaPublicKey::from(key)),
+            RecipientPrivateKey::X25519(secret) => RecipientPublicKey::X25519(X25519PublicKey::from(secret)),
+        }
+    }
+}
+
+/// The data key as wrapped for one recipient.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum WrappedKey {
+    Rsa(Vec<u8>),
+    X25519 { ephemeral_public: [u8; 32], wrapped: Vec<u8> },
+}
+
+/// Derives the AES-KW key-encryption key for an X25519 recipient.
+fn x25519_kek(shared_secret: &[u8; 32], ephemeral_public: &[u8; 32], recipient_public: &[u8; 32]) -> SecretKey {
+    let mut salt = [0u8; 64];
+    salt[..32].copy_from_slice(ephemeral_public);
+    salt[32..].copy_from_slice(recipient_public);
+
+    let mut kek = SecretKey::new([0u8; 32]);
+    Hkdf::<Sha256>::new(Some(&salt), shared_secret)
+        .expand(HKDF_INFO, kek.expose_secret_mut())
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    kek
+}
+
+fn wrap_for(recipient: &RecipientPublicKey, data_key: &SecretKey) -> Result<WrappedKey, CryptoError> {
+    match recipient {
+        RecipientPublicKey::Rsa(public_key) => public_key
+            .encrypt(&mut OsRng, Oaep::new::<Sha256>(), data_key.expose_secret())
+            .map(WrappedKey::Rsa)
+            .map_err(|_| CryptoError::EncryptionFailed),
+        RecipientPublicKey::X25519(public_key) => {
+            let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+            let ephemeral_public = X25519PublicKey::from(&ephemeral).to_bytes();
+            let shared = ephemeral.diffie_hellman(public_key);
+            if !shared.was_contributory() {
+                return Err(CryptoError::InvalidInput("X25519 recipient key is a low-order point"));
+            }
+            let kek = x25519_kek(shared.as_bytes(), &ephemeral_public, public_key.as_bytes());
+            let wrapped = wrap_key(kek.expose_secret(), data_key.expose_secret())?;
+            Ok(WrappedKey::X25519 { ephemeral_public, wrapped })
+        }
+    }
+}
+
+fn unwrap_for(recipient: &RecipientPrivateKey, wrapped_key: &WrappedKey) -> Option<SecretBytes> {
+    match (recipient, wrapped_key) {
+        (RecipientPrivateKey::Rsa(private_key), WrappedKey::Rsa(encrypted)) => {
+            private_key.decrypt(Oaep::new::<Sha256>(), encrypted).ok().map(SecretBytes::new)
+        }
+        (RecipientPrivateKey::X25519(secret), WrappedKey::X25519 { ephemeral_public, wrapped }) => {
+            let shared = secret.diffie_hellman(&X25519PublicKey::from(*ephemeral_public));
+            let recipient_public = X25519PublicKey::from(secret).to_bytes();
+            let kek = x25519_kek(shared.as_bytes(), ephemeral_public, &recipient_public);
+            unwrap_key(kek.expose_secret(), wrapped).ok()
+        }
+        _ => None,
+    }
+}
+
+fn encode_header(nonce: &[u8; NONCE_SIZE], wrapped_keys: &[WrappedKey]) -> Vec<u8> {
+    let mut header = Vec::new();
+    header.extend_from_slice(MAGIC);
+    header.push(VERSION);
+    header.extend_from_slice(nonce);
+    header.extend_from_slice(&(wrapped_keys.len() as u16).to_be_bytes());
+
+    for wrapped_key in wrapped_keys {
+        let wrapped = match wrapped_key {
+            WrappedKey::Rsa(encrypted) => {
+                header.push(RSA_RECIPIENT);
+                encrypted
+            }
+            WrappedKey::X25519 { ephemeral_public, wrapped } => {
+                header.push(X25519_RECIPIENT);
+                header.extend_from_slice(ephemeral_public);
+                wrapped
+            }
+        };
+        header.extend_from_slice(&(wrapped.len() as u16).to_be_bytes());
+        header.extend_from_slice(wrapped);
+    }
+
+    header
+}
+
+/// A cursor over an envelope blob that fails cleanly on truncated input.
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], CryptoError> {
+        let end = self.position.checked_add(len).filter(|&end| end <= self.data.len());
+        let end = end.ok_or(CryptoError::InvalidInput("envelope is truncated"))?;
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, CryptoError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, CryptoError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+}
+
+/// Parses an envelope header into its nonce, wrapped keys and total header length.
+fn decode(blob: &[u8]) -> Result<([u8; NONCE_SIZE], Vec<WrappedKey>, usize), CryptoError> {
+    let mut reader = Reader { data: blob, position: 0 };
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(CryptoError::InvalidInput("not an envelope"));
+    }
+    if reader.u8()? != VERSION {
+        return Err(CryptoError::InvalidInput("unsupported envelope version"));
+    }
+
+    let mut nonce = [0u8; NONCE_SIZE];
+    nonce.copy_from_slice(reader.take(NONCE_SIZE)?);
+
+    let count = reader.u16()?;
+    let mut wrapped_keys = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let wrapped_key = match reader.u8()? {
+            RSA_RECIPIENT => {
+                let len = reader.u16()? as usize;
+                WrappedKey::Rsa(reader.take(len)?.to_vec())
+            }
+            X25519_RECIPIENT => {
+                let mut ephemeral_public = [0u8; 32];
+                ephemeral_public.copy_from_slice(reader.take(32)?);
+                let len = reader.u16()? as usize;
+                WrappedKey::X25519 { ephemeral_public, wrapped: reader.take(len)?.to_vec() }
+            }
+            _ => return Err(CryptoError::InvalidInput("unknown envelope recipient type")),
+        };
+        wrapped_keys.push(wrapped_key);
+    }
+
+    Ok((nonce, wrapped_keys, reader.position))
+}
+
+/// Encrypts `plaintext` so that any of `recipients` can open it.
+///
+/// # Returns
+/// A self-contained envelope blob.
+///
+/// # Example
+/// ```
+/// use rand::rngs::OsRng;
+/// use x25519_dalek::StaticSecret;
+/// use zana::crypto::envelope::{open, seal, RecipientPrivateKey};
+///
+/// let alice = RecipientPrivateKey::X25519(StaticSecret::random_from_rng(OsRng));
+/// let bob = RecipientPrivateKey::X25519(StaticSecret::random_from_rng(OsRng));
+///
+/// let blob = seal(&[alice.public_key(), bob.public_key()], b"quarterly report").unwrap();
+/// assert_eq!(open(&alice, &blob).unwrap(), b"quarterly report");
+/// assert_eq!(open(&bob, &blob).unwrap(), b"quarterly report");
+/// ```
+pub fn seal(recipients: &[RecipientPublicKey], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    if recipients.is_empty() {
+        return Err(CryptoError::InvalidInput("an envelope needs at least one recipient"));
+    }
+    if recipients.len() > u16::MAX as usize {
+        return Err(CryptoError::InvalidInput("too many envelope recipients"));
+    }
+
+    let data_key = generate_random_key();
+    let nonce = generate_random_nonce()?;
+    let wrapped_keys = recipients
+        .iter()
+        .map(|recipient| wrap_for(recipient, &data_key))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut blob = encode_header(&nonce, &wrapped_keys);
+    let ciphertext = encrypt_with_aad(data_key.expose_secret(), &nonce, plaintext, &blob)?;
+    blob.extend_from_slice(&ciphertext);
+    Ok(blob)
+}
+
+/// Opens an envelope with one recipient's private key.
+///
+/// # Returns
+/// The plaintext, `CryptoError::DecryptionFailed` if the key is not among the recipients or the
+/// envelope was modified, or `CryptoError::InvalidInput` if the blob is malformed.
+pub fn open(recipient: &RecipientPrivateKey, blob: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let (nonce, wrapped_keys, header_len) = decode(blob)?;
+    let (header, ciphertext) = blob.split_at(header_len);
+
+    let data_key = wrapped_keys
+        .iter()
+        .find_map(|wrapped_key| unwrap_for(recipient, wrapped_key))
+        .ok_or(CryptoError::DecryptionFailed)?;
+    decrypt_with_aad(data_key.expose_secret(), &nonce, ciphertext, header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn x25519_recipient() -> RecipientPrivateKey {
+        RecipientPrivateKey::X25519(StaticSecret::random_from_rng(OsRng))
+    }
+
+    fn rsa_recipient() -> RecipientPrivateKey {
+        RecipientPrivateKey::Rsa(RsaPrivateKey::new(&mut OsRng, 1024).unwrap())
+    }
+
+    #[test]
+    fn test_mixed_recipients() {
+        let (rsa, x25519) = (rsa_recipient(), x25519_recipient());
+        let blob = seal(&[rsa.public_key(), x25519.public_key()], b"envelope payload").unwrap();
+
+        assert_eq!(open(&rsa, &blob).unwrap(), b"envelope payload");
+        assert_eq!(open(&x25519, &blob).unwrap(), b"envelope payload");
+    }
+
+    #[test]
+    fn test_non_recipient_cannot_open() {
+        let blob = seal(&[x25519_recipient().public_key()], b"secret").unwrap();
+        assert_eq!(open(&x25519_recipient(), &blob), Err(CryptoError::DecryptionFailed));
+    }
+
+    #[test]
+    fn test_header_and_ciphertext_are_authenticated() {
+        let recipient = x25519_recipient();
+        let blob = seal(&[recipient.public_key()], b"secret").unwrap();
+
+        // Flipping the nonce (header) or the last ciphertext byte must both fail
+        let mut tampered_header = blob.clone();
+        tampered_header[MAGIC.len() + 1] ^= 1;
+        assert_eq!(open(&recipient, &tampered_header), Err(CryptoError::DecryptionFailed));
+
+        let mut tampered_body = blob;
+        *tampered_body.last_mut().unwrap() ^= 1;
+        assert_eq!(open(&recipient, &tampered_body), Err(CryptoError::DecryptionFailed));
+    }
+
+    #[test]
+    fn test_malformed_blobs() {
+        let recipient = x25519_recipient();
+        assert!(matches!(open(&recipient, b"nope"), Err(CryptoError::InvalidInput(_))));
+
+        let blob = seal(&[recipient.public_key()], b"secret").unwrap();
+        assert!(matches!(open(&recipient, &blob[..30]), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(seal(&[], b"secret"), Err(CryptoError::InvalidInput(_))));
+    }
+}
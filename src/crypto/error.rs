@@ -0,0 +1,69 @@
+//! Crypto Errors
+//!
+//! The error types returned by the fallible operations of the `crypto` module: [`CryptoError`],
+//! and [`EncodingError`] for the text and binary codecs of
+//! [`utilities`](crate::crypto::utilities).
+
+use thiserror::Error;
+
+/// An error raised by a cryptographic operation.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CryptoError {
+    /// Encryption could not be performed.
+    #[error("encryption failed")]
+    EncryptionFailed,
+    /// The ciphertext, key, nonce or associated data did not authenticate.
+    #[error("decryption failed: ciphertext could not be authenticated")]
+    DecryptionFailed,
+    /// A key had the wrong length.
+    #[error("invalid key length: expected {expected} bytes, got {actual}")]
+    InvalidKeyLength { expected: usize, actual: usize },
+    /// A nonce had the wrong length.
+    #[error("invalid nonce length: expected {expected} bytes, got {actual}")]
+    InvalidNonceLength { expected: usize, actual: usize },
+    /// An input was malformed, with a description of the problem.
+    #[error("invalid input: {0}")]
+    InvalidInput(&'static str),
+    /// Key generation failed or was given unsupported parameters.
+    #[error("key generation failed")]
+    KeyGenerationFailed,
+    /// A message exceeded the maximum size a key and padding scheme can encrypt.
+    #[error("message too long: at most {max} bytes can be encrypted, got {actual}")]
+    MessageTooLong { max: usize, actual: usize },
+    /// A signature could not be produced.
+    #[error("signing failed")]
+    SigningFailed,
+    /// A one-time or stateful signing key has no signatures left.
+    #[error("signing key has no signatures left")]
+    KeyExhausted,
+    /// Random output failed a statistical health test, naming the test.
+    #[error("random output failed the {0} health test")]
+    HealthTestFailed(&'static str),
+    /// Encoded input could not be decoded.
+    #[error("invalid encoding: {0}")]
+    Encoding(#[from] EncodingError),
+}
+
+/// An error raised while encoding or decoding hex, base58, base32, base64url or length-prefixed
+/// data.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum EncodingError {
+    /// A character outside the encoding's alphabet, with its byte offset in the input.
+    #[error("invalid character {character:?} at position {position}")]
+    InvalidCharacter { character: char, position: usize },
+    /// The encoded input has a length no encoder produces.
+    #[error("invalid length {0}")]
+    InvalidLength(usize),
+    /// Padding appeared in the middle of the input.
+    #[error("invalid padding")]
+    InvalidPadding,
+    /// The decoded data is shorter than its format requires.
+    #[error("data too short: expected at least {min} bytes, got {actual}")]
+    TooShort { min: usize, actual: usize },
+    /// Data is too long for its length prefix.
+    #[error("data too long: expected at most {max} bytes, got {actual}")]
+    TooLong { max: usize, actual: usize },
+    /// An embedded checksum did not match the data.
+    #[error("checksum mismatch")]
+    ChecksumMismatch,
+}
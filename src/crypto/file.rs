@@ -0,0 +1,241 @@
+//! Encrypted File Format
+//!
+//! A versioned, self-describing container for encrypted files:
+//!
+//! | Field     | Size | Description                                   |
+//! |-----------|------|-----------------------------------------------|
+//! | magic     | 4    | `"ZANA"`                                      |
+//! | version   | 1    | Format version, currently `1`                 |
+//! | algorithm | 1    | [`Algorithm`] identifier                      |
+//! | salt      | 16   | Random salt for the per-file key              |
+//! | nonce     | 7    | STREAM nonce prefix                           |
+//!
+//! The header is followed by the AEAD chunks of the [`stream`](crate::crypto::stream)
+//! construction. Each file is sealed under its own key, derived with HKDF-SHA256 from the caller's
+//! key, the salt and the magic, version and algorithm bytes, so any change to the header makes
+//! decryption fail.
+
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use hkdf::Hkdf;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::Sha256;
+use crate::crypto::secret::SecretKey;
+use crate::crypto::stream::{DecryptReader, EncryptWriter};
+
+/// Identifies an encrypted file.
+pub const MAGIC: &[u8; 4] = b"ZANA";
+
+/// The format version written by [`encrypt`].
+pub const VERSION: u8 = 1;
+
+/// The length of the per-file key derivation salt.
+pub const SALT_SIZE: usize = 16;
+
+/// The length of the header preceding the nonce prefix.
+const PREAMBLE_SIZE: usize = MAGIC.len() + 2 + SALT_SIZE;
+
+/// The AEAD used for the chunks of an encrypted file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    /// AES-256-GCM in the STREAM construction with 64 KiB chunks.
+    Aes256GcmStream,
+}
+
+impl Algorithm {
+    /// The identifier stored in the header.
+    pub fn id(&self) -> u8 {
+        match self {
+            Algorithm::Aes256GcmStream => 1,
+        }
+    }
+
+    /// Looks up an algorithm by its header identifier.
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            1 => Some(Algorithm::Aes256GcmStream),
+            _ => None,
+        }
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Derives the file key, binding it to the header preamble.
+fn file_key(key: &SecretKey, preamble: &[u8; PREAMBLE_SIZE]) -> SecretKey {
+    let (info, salt) = preamble.split_at(MAGIC.len() + 2);
+    let mut file_key = SecretKey::new([0u8; 32]);
+    Hkdf::<Sha256>::new(Some(salt), key.expose_secret())
+        .expand(info, file_key.expose_secret_mut())
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    file_key
+}
+
+/// Encrypts everything from `input` into `output` in the versioned file format.
+pub fn encrypt<R: Read, W: Write>(mut input: R, mut output: W, key: &SecretKey) -> io::Result<()> {
+    let mut preamble = [0u8; PREAMBLE_SIZE];
+    preamble[..MAGIC.len()].copy_from_slice(MAGIC);
+    preamble[MAGIC.len()] = VERSION;
+    preamble[MAGIC.len() + 1] = Algorithm::Aes256GcmStream.id();
+    OsRng.fill_bytes(&mut preamble[MAGIC.len() + 2..]);
+    output.write_all(&preamble)?;
+
+    let mut writer = EncryptWriter::new(&file_key(key, &preamble), output)?;
+    io::copy(&mut input, &mut writer)?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// Decrypts a file produced by [`encrypt`] from `input` into `output`.
+///
+/// Headers with an unknown magic, version or algorithm, as well as any authentication failure,
+/// surface as `io::ErrorKind::InvalidData`. Plaintext is written chunk by chunk as each chunk is
+/// authenticated, so `output` may hold a prefix of the plaintext when an error is returned.
+pub fn decrypt<R: Read, W: Write>(mut input: R, mut output: W, key: &SecretKey) -> io::Result<()> {
+    let mut preamble = [0u8; PREAMBLE_SIZE];
+    input.read_exact(&mut preamble).map_err(|e| match e.kind() {
+        io::ErrorKind::UnexpectedEof => invalid_data("file header is truncated"),
+        _ => e,
+    })?;
+    if &preamble[..MAGIC.len()] != MAGIC {
+        return Err(invalid_data("not an encrypted file"));
+    }
+    if preamble[MAGIC.len()] != VERSION {
+        return Err(invalid_data("unsupported file format version"));
+    }
+    match Algorithm::from_id(preamble[MAGIC.len() + 1]) {
+        Some(Algorithm::Aes256GcmStream) => {}
+        None => return Err(invalid_data("unsupported file encryption algorithm")),
+    }
+
+    let mut reader = DecryptReader::new(&file_key(key, &preamble), input);
+    io::copy(&mut reader, &mut output)?;
+    output.flush()
+}
+
+/// Encrypts the file at `path_in` into `path_out`.
+///
+/// # Example
+/// ```
+/// use zana::crypto::file::{decrypt_file, encrypt_file};
+/// use zana::crypto::symmetric::generate_random_key;
+///
+/// let dir = std::env::temp_dir();
+/// let (plain, sealed, opened) = (dir.join("zana-doc.txt"), dir.join("zana-doc.enc"), dir.join("zana-doc.out"));
+/// std::fs::write(&plain, b"file contents").unwrap();
+///
+/// let key = generate_random_key();
+/// encrypt_file(&plain, &sealed, &key).unwrap();
+/// decrypt_file(&sealed, &opened, &key).unwrap();
+/// assert_eq!(std::fs::read(&opened).unwrap(), b"file contents");
+/// # for path in [plain, sealed, opened] { std::fs::remove_file(path).unwrap(); }
+/// ```
+pub fn encrypt_file<P: AsRef<Path>, Q: AsRef<Path>>(path_in: P, path_out: Q, key: &SecretKey) -> io::Result<()> {
+    let input = BufReader::new(File::open(path_in)?);
+    let output = BufWriter::new(File::create(path_out)?);
+    encrypt(input, output, key)
+}
+
+/// Decrypts the file at `path_in` into `path_out`.
+///
+/// The output file is removed if decryption fails, so unauthenticated plaintext is never left
+/// behind.
+pub fn decrypt_file<P: AsRef<Path>, Q: AsRef<Path>>(path_in: P, path_out: Q, key: &SecretKey) -> io::Result<()> {
+    let input = BufReader::new(File::open(path_in)?);
+    let output = BufWriter::new(File::create(&path_out)?);
+    decrypt(input, output, key).inspect_err(|_| {
+        let _ = fs::remove_file(&path_out);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::stream::CHUNK_SIZE;
+    use crate::crypto::symmetric::generate_random_key;
+
+    fn seal(key: &SecretKey, data: &[u8]) -> Vec<u8> {
+        let mut sealed = Vec::new();
+        encrypt(data, &mut sealed, key).unwrap();
+        sealed
+    }
+
+    fn open(key: &SecretKey, sealed: &[u8]) -> io::Result<Vec<u8>> {
+        let mut plaintext = Vec::new();
+        decrypt(sealed, &mut plaintext, key)?;
+        Ok(plaintext)
+    }
+
+    #[test]
+    fn test_round_trip_and_header() {
+        let key = generate_random_key();
+        let data: Vec<u8> = (0..2 * CHUNK_SIZE + 17).map(|i| i as u8).collect();
+        let sealed = seal(&key, &data);
+
+        assert_eq!(&sealed[..4], MAGIC);
+        assert_eq!(sealed[4], VERSION);
+        assert_eq!(sealed[5], Algorithm::Aes256GcmStream.id());
+        assert_eq!(open(&key, &sealed).unwrap(), data);
+    }
+
+    #[test]
+    fn test_same_input_gives_distinct_files() {
+        let key = generate_random_key();
+        assert_ne!(seal(&key, b"same"), seal(&key, b"same"));
+    }
+
+    #[test]
+    fn test_header_tampering_is_detected() {
+        let key = generate_random_key();
+        let sealed = seal(&key, b"payload");
+
+        let cases = [
+            (0, "not an encrypted file"),
+            (4, "unsupported file format version"),
+            (5, "unsupported file encryption algorithm"),
+        ];
+        for (index, expected) in cases {
+            let mut tampered = sealed.clone();
+            tampered[index] ^= 0x80;
+            let error = open(&key, &tampered).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+            assert_eq!(error.to_string(), expected);
+        }
+
+        // A modified salt derives a different key
+        let mut tampered = sealed;
+        tampered[10] ^= 1;
+        assert_eq!(open(&key, &tampered).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_wrong_key_and_truncated_header() {
+        let sealed = seal(&generate_random_key(), b"payload");
+        assert!(open(&generate_random_key(), &sealed).is_err());
+        let error = open(&generate_random_key(), &sealed[..8]).unwrap_err();
+        assert_eq!(error.to_string(), "file header is truncated");
+    }
+
+    #[test]
+    fn test_failed_decryption_removes_output() {
+        let dir = std::env::temp_dir();
+        let id = std::process::id();
+        let (plain, sealed, opened) = (
+            dir.join(format!("zana-file-{}.txt", id)),
+            dir.join(format!("zana-file-{}.enc", id)),
+            dir.join(format!("zana-file-{}.out", id)),
+        );
+        fs::write(&plain, b"secret file").unwrap();
+        encrypt_file(&plain, &sealed, &generate_random_key()).unwrap();
+
+        assert!(decrypt_file(&sealed, &opened, &generate_random_key()).is_err());
+        assert!(!opened.exists());
+
+        fs::remove_file(plain).unwrap();
+        fs::remove_file(sealed).unwrap();
+    }
+}
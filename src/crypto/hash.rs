@@ -1,6 +1,9 @@
+use std::io::{self, Read};
 use sha2::{Digest, Sha256, Sha512};
+use sha3::{Sha3_256, Sha3_512, Shake128, Shake256};
+use sha3::digest::ExtendableOutput;
 use blake2::Blake2b512;
-use hmac::{Hmac, Mac};
+use hmac::{Hmac, Mac, SimpleHmac};
 
 /// Computes the SHA-256 hash of the given input data.
 ///
@@ -29,7 +32,40 @@ pub fn blake2b512(data: &[u8]) -> Vec<u8> {
     hasher.finalize().to_vec()
 }
 
+/// Computes the SHA3-256 hash of the given input data.
+pub fn sha3_256(data: &[u8]) -> Vec<u8> {
+    Sha3_256::digest(data).to_vec()
+}
+
+/// Computes the SHA3-512 hash of the given input data.
+pub fn sha3_512(data: &[u8]) -> Vec<u8> {
+    Sha3_512::digest(data).to_vec()
+}
+
+/// Computes `output_len` bytes of the SHAKE128 extendable-output function.
+///
+/// # Arguments
+/// - `data`: The input data to hash.
+/// - `output_len`: The number of output bytes to squeeze.
+pub fn shake128(data: &[u8], output_len: usize) -> Vec<u8> {
+    let mut output = vec![0u8; output_len];
+    Shake128::digest_xof(data, &mut output);
+    output
+}
+
+/// Computes `output_len` bytes of the SHAKE256 extendable-output function.
+pub fn shake256(data: &[u8], output_len: usize) -> Vec<u8> {
+    let mut output = vec![0u8; output_len];
+    Shake256::digest_xof(data, &mut output);
+    output
+}
+
 type HmacSha256 = Hmac<Sha256>;
+type HmacSha512 = Hmac<Sha512>;
+type HmacBlake2b = SimpleHmac<Blake2b512>;
+type HmacSha3_256 = SimpleHmac<Sha3_256>;
+type HmacSha3_512 = SimpleHmac<Sha3_512>;
+
 
 /// Computes the HMAC of the given data using the provided key.
 pub fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
@@ -38,6 +74,206 @@ pub fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
     mac.finalize().into_bytes().to_vec()
 }
 
+/// Computes the HMAC-SHA512 of the given data using the provided key.
+pub fn hmac_sha512(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha512::new_from_slice(key).expect("HMAC key initialization failed");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Computes the HMAC-Blake2b512 of the given data using the provided key.
+pub fn hmac_blake2b(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacBlake2b::new_from_slice(key).expect("HMAC key initialization failed");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Computes the HMAC of the given data with the chosen hash function.
+///
+/// # Arguments
+/// - `algorithm`: The underlying hash function.
+/// - `key`: The secret key.
+/// - `data`: The message to authenticate.
+pub fn hmac(algorithm: HashAlgorithm, key: &[u8], data: &[u8]) -> Vec<u8> {
+    fn compute<M: Mac + hmac::digest::KeyInit>(key: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut mac = <M as Mac>::new_from_slice(key).expect("HMAC key initialization failed");
+        mac.update(data);
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    match algorithm {
+        HashAlgorithm::Sha256 => hmac_sha256(key, data),
+        HashAlgorithm::Sha512 => hmac_sha512(key, data),
+        HashAlgorithm::Sha3_256 => compute::<HmacSha3_256>(key, data),
+        HashAlgorithm::Sha3_512 => compute::<HmacSha3_512>(key, data),
+        HashAlgorithm::Blake2b512 => hmac_blake2b(key, data),
+    }
+}
+
+/// The fixed-output hash functions supported by the crate.
+///
+/// Higher-level components take a `HashAlgorithm` (runtime choice) or are generic over
+/// [`HashFunction`] (compile-time choice) instead of hardcoding SHA-256.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HashAlgorithm {
+    Sha256,
+    Sha512,
+    Sha3_256,
+    Sha3_512,
+    Blake2b512,
+}
+
+impl HashAlgorithm {
+    /// Every supported algorithm.
+    pub const ALL: [HashAlgorithm; 5] = [
+        HashAlgorithm::Sha256,
+        HashAlgorithm::Sha512,
+        HashAlgorithm::Sha3_256,
+        HashAlgorithm::Sha3_512,
+        HashAlgorithm::Blake2b512,
+    ];
+
+    /// Returns the conventional name of the algorithm, e.g. `"SHA3-256"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "SHA-256",
+            HashAlgorithm::Sha512 => "SHA-512",
+            HashAlgorithm::Sha3_256 => "SHA3-256",
+            HashAlgorithm::Sha3_512 => "SHA3-512",
+            HashAlgorithm::Blake2b512 => "BLAKE2b-512",
+        }
+    }
+
+    /// Looks up an algorithm by its conventional name, as returned by [`HashAlgorithm::name`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|algorithm| algorithm.name() == name)
+    }
+
+    /// Returns the digest length in bytes.
+    pub fn output_size(&self) -> usize {
+        match self {
+            HashAlgorithm::Sha256 | HashAlgorithm::Sha3_256 => 32,
+            HashAlgorithm::Sha512 | HashAlgorithm::Sha3_512 | HashAlgorithm::Blake2b512 => 64,
+        }
+    }
+
+    /// Hashes `data` with this algorithm.
+    pub fn hash(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            HashAlgorithm::Sha256 => sha256(data),
+            HashAlgorithm::Sha512 => sha512(data),
+            HashAlgorithm::Sha3_256 => sha3_256(data),
+            HashAlgorithm::Sha3_512 => sha3_512(data),
+            HashAlgorithm::Blake2b512 => blake2b512(data),
+        }
+    }
+
+    /// Hashes everything read from `reader` with this algorithm, in bounded memory.
+    pub fn hash_reader<R: Read>(&self, reader: R) -> io::Result<Vec<u8>> {
+        fn compute<H: HashFunction, R: Read>(mut reader: R) -> io::Result<Vec<u8>> {
+            let mut hasher = H::default();
+            let mut buffer = [0u8; 8192];
+            loop {
+                match reader.read(&mut buffer) {
+                    Ok(0) => return Ok(hasher.finalize()),
+                    Ok(n) => hasher.update(&buffer[..n]),
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        match self {
+            HashAlgorithm::Sha256 => compute::<Sha256Hasher, R>(reader),
+            HashAlgorithm::Sha512 => compute::<Sha512Hasher, R>(reader),
+            HashAlgorithm::Sha3_256 => compute::<Sha3_256Hasher, R>(reader),
+            HashAlgorithm::Sha3_512 => compute::<Sha3_512Hasher, R>(reader),
+            HashAlgorithm::Blake2b512 => compute::<Blake2b512Hasher, R>(reader),
+        }
+    }
+}
+
+/// An incremental hash function, implemented by a wrapper for every [`HashAlgorithm`].
+///
+/// # Example
+/// ```
+/// use zana::crypto::hash::{HashFunction, Sha3_256Hasher, Sha256Hasher};
+///
+/// fn fingerprint<H: HashFunction>(parts: &[&[u8]]) -> Vec<u8> {
+///     let mut hasher = H::default();
+///     for part in parts {
+///         hasher.update(part);
+///     }
+///     hasher.finalize()
+/// }
+///
+/// assert_eq!(fingerprint::<Sha256Hasher>(&[b"ab", b"c"]), Sha256Hasher::digest(b"abc"));
+/// assert_eq!(fingerprint::<Sha3_256Hasher>(&[b"abc"]).len(), 32);
+/// ```
+pub trait HashFunction: Default {
+    /// The algorithm this hasher implements.
+    const ALGORITHM: HashAlgorithm;
+
+    /// Absorbs more input.
+    fn update(&mut self, data: &[u8]);
+
+    /// Consumes the hasher and returns the digest.
+    fn finalize(self) -> Vec<u8>;
+
+    /// Hashes `data` in one call.
+    fn digest(data: &[u8]) -> Vec<u8> {
+        let mut hasher = Self::default();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    /// Returns the digest length in bytes.
+    fn output_size() -> usize {
+        Self::ALGORITHM.output_size()
+    }
+}
+
+macro_rules! hash_function {
+    ($(#[$meta:meta])* $name:ident($inner:ty) => $algorithm:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Default)]
+        pub struct $name($inner);
+
+        impl HashFunction for $name {
+            const ALGORITHM: HashAlgorithm = $algorithm;
+
+            fn update(&mut self, data: &[u8]) {
+                Digest::update(&mut self.0, data);
+            }
+
+            fn finalize(self) -> Vec<u8> {
+                Digest::finalize(self.0).to_vec()
+            }
+        }
+    };
+}
+
+hash_function!(
+    /// Incremental SHA-256.
+    Sha256Hasher(Sha256) => HashAlgorithm::Sha256
+);
+hash_function!(
+    /// Incremental SHA-512.
+    Sha512Hasher(Sha512) => HashAlgorithm::Sha512
+);
+hash_function!(
+    /// Incremental SHA3-256.
+    Sha3_256Hasher(Sha3_256) => HashAlgorithm::Sha3_256
+);
+hash_function!(
+    /// Incremental SHA3-512.
+    Sha3_512Hasher(Sha3_512) => HashAlgorithm::Sha3_512
+);
+hash_function!(
+    /// Incremental BLAKE2b-512.
+    Blake2b512Hasher(Blake2b512) => HashAlgorithm::Blake2b512
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,15 +282,10 @@ mod tests {
         hex::decode(hex).expect("Failed to decode hex string")
     }
 
-    fn bytes_to_hex(bytes: &[u8]) -> String {
-        hex::encode(bytes)
-    }
-
     #[test]
     fn test_sha256() {
         let data = b"zana quantum-ai";
         let hash = sha256(data);
-        println!("SHA256 computed: {}", bytes_to_hex(&hash));
         let expected = hex_to_bytes("91cdb2a80db3fab915f8dabffd5cd128ac931aea6437e4cba13d2a4329128768");
         assert_eq!(hash, expected);
     }
@@ -63,7 +294,6 @@ mod tests {
     fn test_sha512() {
         let data = b"zana quantum-ai";
         let hash = sha512(data);
-        println!("SHA512 computed: {}", bytes_to_hex(&hash));
         let expected = hex_to_bytes("922e82ceab84aef4ac8851c60e1c564cf7c977e50452cd10004d04b8dcba6969f507c7328b7ba7bb3b8480cf9c49f48d99a08d8dbc569ce3d0985324bf51ed69");
         assert_eq!(hash, expected);
     }
@@ -72,18 +302,116 @@ mod tests {
     fn test_blake2b512() {
         let data = b"zana quantum-ai";
         let hash = blake2b512(data);
-        println!("Blake2b512 computed: {}", bytes_to_hex(&hash));
         let expected = hex_to_bytes("10f7e3149efbe202ae38cee32087b939bea73490e6eaef4ffbc5af6b43b5c81b1615fe3fd891f7a540d32a08ee31405cb65e6ff8ec9e94941af1acc20fe874e8");
         assert_eq!(hash, expected);
     }
 
+    #[test]
+    fn test_sha3_256() {
+        // FIPS 202 known-answer vectors
+        assert_eq!(sha3_256(b""), hex_to_bytes("a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a"));
+        assert_eq!(sha3_256(b"abc"), hex_to_bytes("3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"));
+    }
+
+    #[test]
+    fn test_sha3_512() {
+        assert_eq!(sha3_512(b""), hex_to_bytes("a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a615b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26"));
+        assert_eq!(sha3_512(b"abc"), hex_to_bytes("b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0"));
+    }
+
+    #[test]
+    fn test_shake128() {
+        assert_eq!(shake128(b"", 32), hex_to_bytes("7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26"));
+        // Longer outputs extend shorter ones
+        assert_eq!(shake128(b"zana", 64)[..16], shake128(b"zana", 16)[..]);
+    }
+
+    #[test]
+    fn test_shake256() {
+        assert_eq!(shake256(b"", 64), hex_to_bytes("46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762fd75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be"));
+        assert!(shake256(b"zana", 0).is_empty());
+    }
+
     #[test]
     fn test_hmac_sha256() {
         let key = b"my-secret-key";
         let data = b"zana quantum-ai";
         let hmac = hmac_sha256(key, data);
-        println!("HMAC-SHA256 computed: {}", bytes_to_hex(&hmac));
         let expected = hex_to_bytes("64fe202dc9bb9d43dfff7a0a982b2ce3ff2f20293cc34775698432eaf16d4f42");
         assert_eq!(hmac, expected);
     }
+
+    #[test]
+    fn test_hmac_sha512() {
+        // RFC 4231, test case 2
+        let hmac = hmac_sha512(b"Jefe", b"what do ya want for nothing?");
+        let expected = hex_to_bytes("164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737");
+        assert_eq!(hmac, expected);
+    }
+
+    #[test]
+    fn test_hmac_blake2b() {
+        let hmac = hmac_blake2b(b"my-secret-key", b"zana quantum-ai");
+        assert_eq!(hmac.len(), 64);
+        assert_eq!(hmac, hmac_blake2b(b"my-secret-key", b"zana quantum-ai"));
+        assert_ne!(hmac, hmac_blake2b(b"other-key", b"zana quantum-ai"));
+        assert_ne!(hmac, blake2b512(b"zana quantum-ai"));
+    }
+
+    #[test]
+    fn test_hmac_dispatch() {
+        let (key, data) = (b"Jefe".as_slice(), b"what do ya want for nothing?".as_slice());
+        assert_eq!(
+            hmac(HashAlgorithm::Sha256, key, data),
+            hex_to_bytes("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843")
+        );
+        assert_eq!(hmac(HashAlgorithm::Sha512, key, data), hmac_sha512(key, data));
+        assert_eq!(hmac(HashAlgorithm::Blake2b512, key, data), hmac_blake2b(key, data));
+    }
+
+    #[test]
+    fn test_hmac_sha3_matches_generic_construction() {
+        let tag = hmac(HashAlgorithm::Sha3_256, b"key", b"data");
+        assert_eq!(tag.len(), 32);
+        assert_ne!(tag, hmac(HashAlgorithm::Sha3_256, b"other", b"data"));
+        assert_eq!(hmac(HashAlgorithm::Sha3_512, b"key", b"data").len(), 64);
+    }
+
+    fn incremental<H: HashFunction>(data: &[u8]) -> Vec<u8> {
+        let mut hasher = H::default();
+        for chunk in data.chunks(3) {
+            hasher.update(chunk);
+        }
+        hasher.finalize()
+    }
+
+    #[test]
+    fn test_hash_functions_match_one_shot_helpers() {
+        let data = b"zana quantum-ai";
+        assert_eq!(incremental::<Sha256Hasher>(data), sha256(data));
+        assert_eq!(incremental::<Sha512Hasher>(data), sha512(data));
+        assert_eq!(incremental::<Sha3_256Hasher>(data), sha3_256(data));
+        assert_eq!(incremental::<Sha3_512Hasher>(data), sha3_512(data));
+        assert_eq!(incremental::<Blake2b512Hasher>(data), blake2b512(data));
+    }
+
+    #[test]
+    fn test_hash_algorithm_dispatch() {
+        for algorithm in HashAlgorithm::ALL {
+            let digest = algorithm.hash(b"zana");
+            assert_eq!(digest.len(), algorithm.output_size(), "{}", algorithm.name());
+        }
+        assert_eq!(HashAlgorithm::Sha3_256.hash(b"abc"), Sha3_256Hasher::digest(b"abc"));
+        assert_eq!(Blake2b512Hasher::output_size(), 64);
+    }
+
+    #[test]
+    fn test_hash_reader_and_names() {
+        let data = vec![0xa5u8; 20_000];
+        for algorithm in HashAlgorithm::ALL {
+            assert_eq!(algorithm.hash_reader(data.as_slice()).unwrap(), algorithm.hash(&data));
+            assert_eq!(HashAlgorithm::from_name(algorithm.name()), Some(algorithm));
+        }
+        assert_eq!(HashAlgorithm::from_name("MD5"), None);
+    }
 }
@@ -0,0 +1,462 @@
+//! Keyring
+//!
+//! A [`Keyring`] holds named keys for signing (Ed25519) and encryption (X25519, used with
+//! [`ecies`](crate::crypto::ecies)). Every name keeps all of its versions: rotating a key makes a
+//! fresh version active for new signatures and ciphertexts, while older versions remain available
+//! to verify and decrypt what they produced. Signatures and ciphertexts start with the 8-byte ID
+//! of the key version that made them, so the right version is found without trial decryption.
+//!
+//! Keyrings are persisted with the same Argon2id and AES-256-GCM sealing as the encrypted
+//! keystore of [`signatures`](crate::crypto::signatures).
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+use base64::Engine;
+use base64::engine::general_purpose;
+use ed25519_dalek::{Signature, SigningKey, SIGNATURE_LENGTH};
+use serde_json::{json, Value};
+use x25519_dalek::{PublicKey as X25519PublicKey, StaticSecret};
+use crate::crypto::ecies;
+use crate::crypto::error::CryptoError;
+use crate::crypto::hash::sha256;
+use crate::crypto::random::{generate_random_key_with, OsRandom, SecureRandom};
+use crate::crypto::secret::{SecretBytes, SecretKey};
+use crate::crypto::signatures::{
+    decode_base64_field, invalid_data, open_with_passphrase, seal_with_passphrase, sign_message,
+    verify_message,
+};
+
+/// The length of the key ID prefixed to signatures and ciphertexts.
+pub const KEY_ID_SIZE: usize = 8;
+
+/// The version of the keyring file format written by [`Keyring::save`].
+pub const KEYRING_VERSION: u64 = 1;
+
+const KEYRING_FORMAT: &str = "zana-keyring";
+
+/// What a named key is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyKind {
+    /// Ed25519 signing.
+    Signing,
+    /// X25519 public-key encryption.
+    Encryption,
+}
+
+impl KeyKind {
+    fn name(&self) -> &'static str {
+        match self {
+            KeyKind::Signing => "signing",
+            KeyKind::Encryption => "encryption",
+        }
+    }
+}
+
+#[derive(Clone)]
+enum KeyMaterial {
+    Signing(SigningKey),
+    Encryption(StaticSecret),
+}
+
+impl KeyMaterial {
+    fn generate(rng: &mut impl SecureRandom, kind: KeyKind) -> Result<Self, CryptoError> {
+        Ok(Self::from_secret(kind, &generate_random_key_with(rng)?))
+    }
+
+    fn from_secret(kind: KeyKind, secret: &SecretKey) -> Self {
+        match kind {
+            KeyKind::Signing => KeyMaterial::Signing(SigningKey::from_bytes(secret.expose_secret())),
+            KeyKind::Encryption => KeyMaterial::Encryption(StaticSecret::from(*secret.expose_secret())),
+        }
+    }
+
+    fn kind(&self) -> KeyKind {
+        match self {
+            KeyMaterial::Signing(_) => KeyKind::Signing,
+            KeyMaterial::Encryption(_) => KeyKind::Encryption,
+        }
+    }
+
+    fn secret_bytes(&self) -> SecretKey {
+        SecretKey::new(match self {
+            KeyMaterial::Signing(keypair) => keypair.to_bytes(),
+            KeyMaterial::Encryption(secret) => secret.to_bytes(),
+        })
+    }
+
+    fn public_bytes(&self) -> [u8; 32] {
+        match self {
+            KeyMaterial::Signing(keypair) => keypair.verifying_key().to_bytes(),
+            KeyMaterial::Encryption(secret) => X25519PublicKey::from(secret).to_bytes(),
+        }
+    }
+
+    /// The key ID: the first bytes of the SHA-256 hash of the public key.
+    fn id(&self) -> [u8; KEY_ID_SIZE] {
+        let mut id = [0u8; KEY_ID_SIZE];
+        id.copy_from_slice(&sha256(&self.public_bytes())[..KEY_ID_SIZE]);
+        id
+    }
+}
+
+#[derive(Clone)]
+struct KeyVersion {
+    id: [u8; KEY_ID_SIZE],
+    created: u64,
+    material: KeyMaterial,
+}
+
+impl KeyVersion {
+    fn new(material: KeyMaterial, created: u64) -> Self {
+        Self { id: material.id(), created, material }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+/// A collection of named, versioned signing and encryption keys.
+///
+/// # Example
+/// ```
+/// use zana::crypto::keyring::{KeyKind, Keyring};
+///
+/// let mut keyring = Keyring::new();
+/// keyring.generate("release", KeyKind::Signing).unwrap();
+/// let signed = keyring.sign("release", b"v1.0").unwrap();
+///
+/// // Signatures made before a rotation still verify afterwards
+/// keyring.rotate("release").unwrap();
+/// assert!(keyring.verify(b"v1.0", &signed));
+/// ```
+#[derive(Clone, Default)]
+pub struct Keyring {
+    keys: BTreeMap<String, Vec<KeyVersion>>,
+}
+
+impl Keyring {
+    /// Creates an empty keyring.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generates a new key under `name`.
+    ///
+    /// # Returns
+    /// The hex-encoded ID of the key, or `CryptoError::InvalidInput` if the name is taken.
+    pub fn generate(&mut self, name: &str, kind: KeyKind) -> Result<String, CryptoError> {
+        self.generate_with(&mut OsRandom, name, kind)
+    }
+
+    /// Generates a new key under `name` from the given source.
+    pub fn generate_with(&mut self, rng: &mut impl SecureRandom, name: &str, kind: KeyKind) -> Result<String, CryptoError> {
+        if self.keys.contains_key(name) {
+            return Err(CryptoError::InvalidInput("a key with this name already exists"));
+        }
+        let version = KeyVersion::new(KeyMaterial::generate(rng, kind)?, now());
+        let id = hex::encode(version.id);
+        self.keys.insert(name.to_string(), vec![version]);
+        Ok(id)
+    }
+
+    /// Replaces the active version of `name` with a freshly generated key of the same kind.
+    ///
+    /// Earlier versions are kept for verification and decryption.
+    ///
+    /// # Returns
+    /// The hex-encoded ID of the new version.
+    pub fn rotate(&mut self, name: &str) -> Result<String, CryptoError> {
+        self.rotate_with(&mut OsRandom, name)
+    }
+
+    /// Replaces the active version of `name` with a key of the same kind from the given source.
+    pub fn rotate_with(&mut self, rng: &mut impl SecureRandom, name: &str) -> Result<String, CryptoError> {
+        let versions = self.keys.get_mut(name).ok_or(CryptoError::InvalidInput("unknown key name"))?;
+        let kind = versions.last().expect("names always have a version").material.kind();
+        let version = KeyVersion::new(KeyMaterial::generate(rng, kind)?, now());
+        let id = hex::encode(version.id);
+        versions.push(version);
+        Ok(id)
+    }
+
+    /// Removes a key and all of its versions.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.keys.remove(name).is_some()
+    }
+
+    /// The names of the keys, in sorted order.
+    pub fn names(&self) -> Vec<&str> {
+        self.keys.keys().map(String::as_str).collect()
+    }
+
+    /// What the key called `name` is used for.
+    pub fn kind(&self, name: &str) -> Option<KeyKind> {
+        self.active(name).map(|version| version.material.kind())
+    }
+
+    /// The hex-encoded ID of the active version of `name`.
+    pub fn active_key_id(&self, name: &str) -> Option<String> {
+        self.active(name).map(|version| hex::encode(version.id))
+    }
+
+    /// The hex-encoded IDs of every version of `name`, oldest first.
+    pub fn key_ids(&self, name: &str) -> Vec<String> {
+        self.keys
+            .get(name)
+            .map(|versions| versions.iter().map(|version| hex::encode(version.id)).collect())
+            .unwrap_or_default()
+    }
+
+    /// The public key of the active version of `name`: an Ed25519 verifying key or an X25519
+    /// public key, depending on its kind.
+    pub fn public_key(&self, name: &str) -> Option<[u8; 32]> {
+        self.active(name).map(|version| version.material.public_bytes())
+    }
+
+    fn active(&self, name: &str) -> Option<&KeyVersion> {
+        self.keys.get(name).and_then(|versions| versions.last())
+    }
+
+    fn active_of_kind(&self, name: &str, kind: KeyKind) -> Result<&KeyVersion, CryptoError> {
+        let version = self.active(name).ok_or(CryptoError::InvalidInput("unknown key name"))?;
+        if version.material.kind() != kind {
+            return Err(CryptoError::InvalidInput("key cannot be used for this operation"));
+        }
+        Ok(version)
+    }
+
+    fn find(&self, id: &[u8]) -> Option<&KeyVersion> {
+        self.keys.values().flatten().find(|version| version.id[..] == *id)
+    }
+
+    /// Signs a message with the active version of the signing key `name`.
+    ///
+    /// # Returns
+    /// `key ID ‖ Ed25519 signature`.
+    pub fn sign(&self, name: &str, message: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let version = self.active_of_kind(name, KeyKind::Signing)?;
+        let KeyMaterial::Signing(keypair) = &version.material else {
+            unreachable!("checked to be a signing key");
+        };
+        let mut signed = version.id.to_vec();
+        signed.extend_from_slice(&sign_message(keypair, message).to_bytes());
+        Ok(signed)
+    }
+
+    /// Verifies a signature produced by [`Keyring::sign`] with any version of any signing key.
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        if signature.len() != KEY_ID_SIZE + SIGNATURE_LENGTH {
+            return false;
+        }
+        let (id, signature) = signature.split_at(KEY_ID_SIZE);
+        match self.find(id).map(|version| &version.material) {
+            Some(KeyMaterial::Signing(keypair)) => Signature::from_slice(signature)
+                .is_ok_and(|signature| verify_message(&keypair.verifying_key(), message, &signature)),
+            _ => false,
+        }
+    }
+
+    /// Encrypts a message to the active version of the encryption key `name`.
+    ///
+    /// # Returns
+    /// `key ID ‖ ECIES ciphertext`.
+    pub fn encrypt(&self, name: &str, plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let version = self.active_of_kind(name, KeyKind::Encryption)?;
+        let public_key = X25519PublicKey::from(version.material.public_bytes());
+        let mut ciphertext = version.id.to_vec();
+        ciphertext.extend(ecies::encrypt(&public_key, plaintext)?);
+        Ok(ciphertext)
+    }
+
+    /// Decrypts a ciphertext produced by [`Keyring::encrypt`] with whichever key version made it.
+    ///
+    /// # Returns
+    /// The plaintext, or `CryptoError::DecryptionFailed` if no key in the keyring matches or the
+    /// ciphertext was modified.
+    pub fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        if ciphertext.len() < KEY_ID_SIZE {
+            return Err(CryptoError::InvalidInput("ciphertext is too short"));
+        }
+        let (id, sealed) = ciphertext.split_at(KEY_ID_SIZE);
+        match self.find(id).map(|version| &version.material) {
+            Some(KeyMaterial::Encryption(secret)) => ecies::decrypt(secret, sealed),
+            _ => Err(CryptoError::DecryptionFailed),
+        }
+    }
+
+    /// Saves the keyring to a file sealed under `passphrase`.
+    pub fn save(&self, file_path: &str, passphrase: &str) -> std::io::Result<()> {
+        let keys: Vec<Value> = self
+            .keys
+            .iter()
+            .map(|(name, versions)| {
+                json!({
+                    "name": name,
+                    "kind": versions[0].material.kind().name(),
+                    "versions": versions.iter().map(|version| json!({
+                        "created": version.created,
+                        "secret": general_purpose::STANDARD.encode(version.material.secret_bytes().expose_secret()),
+                    })).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        let contents = SecretBytes::new(serde_json::to_vec(&json!({ "keys": keys }))?);
+        let (kdf, cipher) = seal_with_passphrase(contents.expose_secret(), passphrase, &keyring_aad(KEYRING_VERSION))?;
+
+        let keyring = json!({
+            "format": KEYRING_FORMAT,
+            "version": KEYRING_VERSION,
+            "kdf": kdf,
+            "cipher": cipher,
+        });
+        fs::write(file_path, serde_json::to_string_pretty(&keyring)?)
+    }
+
+    /// Loads a keyring saved by [`Keyring::save`].
+    ///
+    /// Returns an `InvalidData` error if the file is not a supported keyring, or if the passphrase
+    /// is wrong or the file was modified.
+    pub fn load(file_path: &str, passphrase: &str) -> std::io::Result<Self> {
+        let keyring: Value = serde_json::from_str(&fs::read_to_string(file_path)?)
+            .map_err(|_| invalid_data("Invalid keyring format"))?;
+        if keyring["format"] != KEYRING_FORMAT {
+            return Err(invalid_data("Invalid keyring format"));
+        }
+        if keyring["version"].as_u64() != Some(KEYRING_VERSION) {
+            return Err(invalid_data("Unsupported keyring version"));
+        }
+
+        let contents = open_with_passphrase(&keyring, passphrase, &keyring_aad(KEYRING_VERSION))?;
+        let contents: Value =
+            serde_json::from_slice(contents.expose_secret()).map_err(|_| invalid_data("Invalid keyring format"))?;
+
+        let mut keys = BTreeMap::new();
+        for entry in contents["keys"].as_array().ok_or_else(|| invalid_data("Invalid keyring format"))? {
+            let name = entry["name"].as_str().ok_or_else(|| invalid_data("Invalid key name"))?;
+            let kind = match entry["kind"].as_str() {
+                Some("signing") => KeyKind::Signing,
+                Some("encryption") => KeyKind::Encryption,
+                _ => return Err(invalid_data("Unsupported key kind")),
+            };
+            let versions = entry["versions"]
+                .as_array()
+                .filter(|versions| !versions.is_empty())
+                .ok_or_else(|| invalid_data("Key has no versions"))?
+                .iter()
+                .map(|version| {
+                    let decoded = SecretBytes::new(decode_base64_field(&version["secret"])?);
+                    let secret = SecretKey::from_slice(decoded.expose_secret())
+                        .map_err(|_| invalid_data("Invalid private key"))?;
+                    let created = version["created"].as_u64().unwrap_or(0);
+                    Ok(KeyVersion::new(KeyMaterial::from_secret(kind, &secret), created))
+                })
+                .collect::<std::io::Result<Vec<_>>>()?;
+            keys.insert(name.to_string(), versions);
+        }
+        Ok(Self { keys })
+    }
+}
+
+/// The associated data binding the sealed keys to the keyring format and version.
+fn keyring_aad(version: u64) -> Vec<u8> {
+    let mut aad = KEYRING_FORMAT.as_bytes().to_vec();
+    aad.extend_from_slice(&version.to_be_bytes());
+    aad
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing_with_rotation() {
+        let mut keyring = Keyring::new();
+        let first_id = keyring.generate("release", KeyKind::Signing).unwrap();
+        let old = keyring.sign("release", b"v1").unwrap();
+        assert_eq!(hex::encode(&old[..KEY_ID_SIZE]), first_id);
+
+        let second_id = keyring.rotate("release").unwrap();
+        let new = keyring.sign("release", b"v2").unwrap();
+        assert_eq!(hex::encode(&new[..KEY_ID_SIZE]), second_id);
+        assert_eq!(keyring.key_ids("release"), vec![first_id, second_id.clone()]);
+        assert_eq!(keyring.active_key_id("release"), Some(second_id));
+
+        assert!(keyring.verify(b"v1", &old));
+        assert!(keyring.verify(b"v2", &new));
+        assert!(!keyring.verify(b"v2", &old));
+        assert!(!Keyring::new().verify(b"v1", &old));
+    }
+
+    #[test]
+    fn test_encryption_with_rotation() {
+        let mut keyring = Keyring::new();
+        keyring.generate("backup", KeyKind::Encryption).unwrap();
+        let old = keyring.encrypt("backup", b"monday").unwrap();
+        keyring.rotate("backup").unwrap();
+        let new = keyring.encrypt("backup", b"tuesday").unwrap();
+
+        assert_ne!(old[..KEY_ID_SIZE], new[..KEY_ID_SIZE]);
+        assert_eq!(keyring.decrypt(&old).unwrap(), b"monday");
+        assert_eq!(keyring.decrypt(&new).unwrap(), b"tuesday");
+
+        keyring.remove("backup");
+        assert_eq!(keyring.decrypt(&new), Err(CryptoError::DecryptionFailed));
+    }
+
+    #[test]
+    fn test_keys_from_a_given_source() {
+        use crate::crypto::random::SeededRandom;
+
+        let ids = |seed| {
+            let mut rng = SeededRandom::from_u64(seed);
+            let mut keyring = Keyring::new();
+            let first = keyring.generate_with(&mut rng, "release", KeyKind::Signing).unwrap();
+            (first, keyring.rotate_with(&mut rng, "release").unwrap())
+        };
+        assert_eq!(ids(1), ids(1));
+        assert_ne!(ids(1).0, ids(1).1);
+        assert_ne!(ids(1), ids(2));
+    }
+
+    #[test]
+    fn test_key_kinds_and_names() {
+        let mut keyring = Keyring::new();
+        keyring.generate("sign", KeyKind::Signing).unwrap();
+        keyring.generate("seal", KeyKind::Encryption).unwrap();
+
+        assert_eq!(keyring.names(), vec!["seal", "sign"]);
+        assert_eq!(keyring.kind("seal"), Some(KeyKind::Encryption));
+        assert!(keyring.generate("sign", KeyKind::Signing).is_err());
+        assert!(keyring.sign("seal", b"x").is_err());
+        assert!(keyring.encrypt("sign", b"x").is_err());
+        assert!(keyring.rotate("missing").is_err());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let mut keyring = Keyring::new();
+        keyring.generate("sign", KeyKind::Signing).unwrap();
+        keyring.rotate("sign").unwrap();
+        keyring.generate("seal", KeyKind::Encryption).unwrap();
+        let signed = keyring.sign("sign", b"zana").unwrap();
+        let sealed = keyring.encrypt("seal", b"zana").unwrap();
+
+        let file_path = "test_keyring.json";
+        keyring.save(file_path, "passphrase").unwrap();
+        let loaded = Keyring::load(file_path, "passphrase").unwrap();
+
+        assert_eq!(loaded.key_ids("sign"), keyring.key_ids("sign"));
+        assert_eq!(loaded.public_key("seal"), keyring.public_key("seal"));
+        assert!(loaded.verify(b"zana", &signed));
+        assert_eq!(loaded.decrypt(&sealed).unwrap(), b"zana");
+
+        let error = Keyring::load(file_path, "wrong").err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        fs::remove_file(file_path).unwrap();
+    }
+}
@@ -0,0 +1,238 @@
+//! Merkle Trees
+//!
+//! A binary SHA-256 hash tree over a list of leaves, with inclusion proofs. Leaf and interior
+//! hashes are domain-separated (`0x00 ‖ data` and `0x01 ‖ left ‖ right`), so an interior node can
+//! never be passed off as a leaf. When a level has an odd number of nodes, the last one is carried
+//! up unchanged.
+
+use sha2::{Digest, Sha256};
+use crate::crypto::error::CryptoError;
+
+/// The size of a node hash.
+pub const HASH_SIZE: usize = 32;
+
+/// Hashes leaf data into a tree leaf.
+pub fn hash_leaf(data: &[u8]) -> [u8; HASH_SIZE] {
+    Sha256::new().chain_update([0x00]).chain_update(data).finalize().into()
+}
+
+/// Hashes two child nodes into their parent.
+pub fn hash_children(left: &[u8; HASH_SIZE], right: &[u8; HASH_SIZE]) -> [u8; HASH_SIZE] {
+    Sha256::new()
+        .chain_update([0x01])
+        .chain_update(left)
+        .chain_update(right)
+        .finalize()
+        .into()
+}
+
+/// A Merkle tree holding every level, from the leaves up to the root.
+///
+/// # Example
+/// ```
+/// use zana::crypto::merkle::{hash_leaf, MerkleTree};
+///
+/// let tree = MerkleTree::from_data(&[b"a", b"b", b"c"]).unwrap();
+/// let proof = tree.proof(2).unwrap();
+/// assert!(proof.verify(&tree.root(), &hash_leaf(b"c")));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleTree {
+    levels: Vec<Vec<[u8; HASH_SIZE]>>,
+}
+
+impl MerkleTree {
+    /// Builds a tree over already-hashed leaves.
+    ///
+    /// # Returns
+    /// `CryptoError::InvalidInput` if there are no leaves or more than `u32::MAX`.
+    pub fn from_leaves(leaves: Vec<[u8; HASH_SIZE]>) -> Result<Self, CryptoError> {
+        if leaves.is_empty() {
+            return Err(CryptoError::InvalidInput("a Merkle tree needs at least one leaf"));
+        }
+        if leaves.len() > u32::MAX as usize {
+            return Err(CryptoError::InvalidInput("too many Merkle tree leaves"));
+        }
+        let mut levels = vec![leaves];
+        while levels[levels.len() - 1].len() > 1 {
+            let parents = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_children(left, right),
+                    [last] => *last,
+                    _ => unreachable!("chunks of two"),
+                })
+                .collect();
+            levels.push(parents);
+        }
+        Ok(Self { levels })
+    }
+
+    /// Builds a tree over raw data items, hashing each with [`hash_leaf`].
+    pub fn from_data<T: AsRef<[u8]>>(items: &[T]) -> Result<Self, CryptoError> {
+        Self::from_leaves(items.iter().map(|item| hash_leaf(item.as_ref())).collect())
+    }
+
+    /// The root hash.
+    pub fn root(&self) -> [u8; HASH_SIZE] {
+        self.levels[self.levels.len() - 1][0]
+    }
+
+    /// The number of leaves.
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Always `false`: a tree has at least one leaf.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// The leaf hash at `index`.
+    pub fn leaf(&self, index: usize) -> Option<&[u8; HASH_SIZE]> {
+        self.levels[0].get(index)
+    }
+
+    /// Returns the inclusion proof for the leaf at `index`, or `None` if it is out of range.
+    pub fn proof(&self, index: usize) -> Option<MerkleProof> {
+        if index >= self.len() {
+            return None;
+        }
+        let mut siblings = Vec::new();
+        let mut position = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(position ^ 1) {
+                siblings.push(*sibling);
+            }
+            position /= 2;
+        }
+        Some(MerkleProof { index: index as u32, leaf_count: self.len() as u32, siblings })
+    }
+}
+
+/// The sibling hashes linking a leaf to the root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleProof {
+    index: u32,
+    leaf_count: u32,
+    siblings: Vec<[u8; HASH_SIZE]>,
+}
+
+impl MerkleProof {
+    /// The position of the proven leaf.
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+
+    /// The number of leaves in the tree the proof was taken from.
+    pub fn leaf_count(&self) -> usize {
+        self.leaf_count as usize
+    }
+
+    /// The sibling hashes, from the leaf level upwards.
+    pub fn siblings(&self) -> &[[u8; HASH_SIZE]] {
+        &self.siblings
+    }
+
+    /// Recomputes the root from a leaf hash, or returns `None` if the proof is malformed.
+    pub fn compute_root(&self, leaf: &[u8; HASH_SIZE]) -> Option<[u8; HASH_SIZE]> {
+        if self.index >= self.leaf_count {
+            return None;
+        }
+        let mut siblings = self.siblings.iter();
+        let mut node = *leaf;
+        let mut position = self.index;
+        let mut width = self.leaf_count;
+        while width > 1 {
+            // The last node of an odd level has no sibling and moves up unchanged
+            if position ^ 1 < width {
+                let sibling = siblings.next()?;
+                node = if position.is_multiple_of(2) {
+                    hash_children(&node, sibling)
+                } else {
+                    hash_children(sibling, &node)
+                };
+            }
+            position /= 2;
+            width = width.div_ceil(2);
+        }
+        siblings.next().is_none().then_some(node)
+    }
+
+    /// Returns `true` if the proof links `leaf` to `root`.
+    pub fn verify(&self, root: &[u8; HASH_SIZE], leaf: &[u8; HASH_SIZE]) -> bool {
+        self.compute_root(leaf).as_ref() == Some(root)
+    }
+
+    /// Serializes the proof as `index (u32) ‖ leaf count (u32) ‖ siblings`, big-endian.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + self.siblings.len() * HASH_SIZE);
+        bytes.extend_from_slice(&self.index.to_be_bytes());
+        bytes.extend_from_slice(&self.leaf_count.to_be_bytes());
+        self.siblings.iter().for_each(|sibling| bytes.extend_from_slice(sibling));
+        bytes
+    }
+
+    /// Deserializes a proof produced by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.len() < 8 || !(bytes.len() - 8).is_multiple_of(HASH_SIZE) {
+            return Err(CryptoError::InvalidInput("invalid Merkle proof length"));
+        }
+        let index = u32::from_be_bytes(bytes[..4].try_into().expect("4 bytes"));
+        let leaf_count = u32::from_be_bytes(bytes[4..8].try_into().expect("4 bytes"));
+        let siblings = bytes[8..]
+            .chunks(HASH_SIZE)
+            .map(|chunk| chunk.try_into().expect("chunk holds one hash"))
+            .collect();
+        Ok(Self { index, leaf_count, siblings })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_leaf_proves_for_all_sizes() {
+        for size in 1..=17usize {
+            let items: Vec<Vec<u8>> = (0..size).map(|i| vec![i as u8]).collect();
+            let tree = MerkleTree::from_data(&items).unwrap();
+            assert_eq!(tree.len(), size);
+            for (index, item) in items.iter().enumerate() {
+                let proof = tree.proof(index).unwrap();
+                assert!(proof.verify(&tree.root(), &hash_leaf(item)), "leaf {} of {}", index, size);
+                assert!(!proof.verify(&tree.root(), &hash_leaf(b"other")));
+            }
+            assert!(tree.proof(size).is_none());
+        }
+    }
+
+    #[test]
+    fn test_root_structure() {
+        let tree = MerkleTree::from_data(&[b"a", b"b", b"c"]).unwrap();
+        let left = hash_children(&hash_leaf(b"a"), &hash_leaf(b"b"));
+        assert_eq!(tree.root(), hash_children(&left, &hash_leaf(b"c")));
+
+        let single = MerkleTree::from_data(&[b"only"]).unwrap();
+        assert_eq!(single.root(), hash_leaf(b"only"));
+        assert!(single.proof(0).unwrap().siblings().is_empty());
+        assert!(MerkleTree::from_leaves(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_tampered_proofs_fail() {
+        let tree = MerkleTree::from_data(&[b"a", b"b", b"c", b"d", b"e"]).unwrap();
+        let proof = tree.proof(1).unwrap();
+        let leaf = hash_leaf(b"b");
+
+        let mut bytes = proof.to_bytes();
+        assert_eq!(MerkleProof::from_bytes(&bytes).unwrap(), proof);
+        bytes[3] = 0; // claim a different index
+        assert!(!MerkleProof::from_bytes(&bytes).unwrap().verify(&tree.root(), &leaf));
+
+        let mut extended = proof.to_bytes();
+        extended.extend_from_slice(&[0u8; HASH_SIZE]);
+        assert!(!MerkleProof::from_bytes(&extended).unwrap().verify(&tree.root(), &leaf));
+        assert!(MerkleProof::from_bytes(&[0u8; 9]).is_err());
+    }
+}
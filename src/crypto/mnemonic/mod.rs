@@ -0,0 +1,258 @@
+//! BIP39 Mnemonic Phrases
+//!
+//! Backs key material up as a phrase of 12 to 24 English words, following BIP39:
+//! - The entropy (128 to 256 bits) is extended with the first `bits / 32` bits of its SHA-256
+//!   hash as a checksum and split into 11-bit indices into the [wordlist](english::WORDS).
+//! - A phrase is turned into a 64-byte seed with PBKDF2-HMAC-SHA512 (2048 rounds) salted with
+//!   `"mnemonic"` followed by an optional passphrase.
+//!
+//! [`Mnemonic::to_signing_key`] derives an Ed25519 key from the seed, so a signing key can be
+//! written down and restored on another machine from the words alone. Passphrases are used as
+//! given; BIP39 asks for NFKD normalization, which only matters for non-ASCII passphrases.
+
+pub mod english;
+
+use ed25519_dalek::SigningKey;
+use pbkdf2::pbkdf2_hmac;
+use sha2::{Digest, Sha256, Sha512};
+use zeroize::Zeroize;
+use crate::crypto::error::CryptoError;
+use crate::crypto::random::{random_bytes, OsRandom, SecureRandom};
+use crate::crypto::secret::{SecretBytes, SecretKey};
+use crate::crypto::signatures::keypair_from_seed;
+
+/// The number of PBKDF2 rounds used to derive the seed.
+pub const PBKDF2_ROUNDS: u32 = 2048;
+
+/// The size of the derived seed in bytes.
+pub const SEED_SIZE: usize = 64;
+
+/// The word counts BIP39 allows.
+pub const WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+
+const BITS_PER_WORD: usize = 11;
+
+/// A validated mnemonic phrase and the entropy it encodes.
+///
+/// # Example
+/// ```
+/// use zana::crypto::mnemonic::Mnemonic;
+///
+/// let backup = Mnemonic::generate(24).unwrap();
+/// let key = backup.to_signing_key("");
+///
+/// // Later, on another machine
+/// let restored = Mnemonic::parse(&backup.phrase()).unwrap();
+/// assert_eq!(restored.to_signing_key("").to_bytes(), key.to_bytes());
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct Mnemonic {
+    entropy: SecretBytes,
+}
+
+impl Mnemonic {
+    /// Encodes entropy as a mnemonic.
+    ///
+    /// # Returns
+    /// The mnemonic, or `CryptoError::InvalidInput` unless the entropy is 16, 20, 24, 28 or 32
+    /// bytes long.
+    pub fn from_entropy(entropy: &[u8]) -> Result<Self, CryptoError> {
+        if !(16..=32).contains(&entropy.len()) || !entropy.len().is_multiple_of(4) {
+            return Err(CryptoError::InvalidInput("mnemonic entropy must be 16, 20, 24, 28 or 32 bytes"));
+        }
+        Ok(Self { entropy: SecretBytes::from_slice(entropy) })
+    }
+
+    /// Generates a mnemonic of `word_count` words from the operating system's RNG.
+    pub fn generate(word_count: usize) -> Result<Self, CryptoError> {
+        Self::generate_with(&mut OsRandom, word_count)
+    }
+
+    /// Generates a mnemonic of `word_count` words from the given source.
+    ///
+    /// # Returns
+    /// The mnemonic, or `CryptoError::InvalidInput` if `word_count` is not in [`WORD_COUNTS`].
+    pub fn generate_with(rng: &mut impl SecureRandom, word_count: usize) -> Result<Self, CryptoError> {
+        if !WORD_COUNTS.contains(&word_count) {
+            return Err(CryptoError::InvalidInput("mnemonics have 12, 15, 18, 21 or 24 words"));
+        }
+        let entropy = SecretBytes::new(random_bytes(rng, word_count * 4 / 3)?);
+        Self::from_entropy(entropy.expose_secret())
+    }
+
+    /// Parses and validates a phrase. Words may be separated by any whitespace and are matched
+    /// case-insensitively.
+    ///
+    /// # Returns
+    /// The mnemonic, or `CryptoError::InvalidInput` if the word count is wrong, a word is not in
+    /// the wordlist, or the checksum does not match.
+    pub fn parse(phrase: &str) -> Result<Self, CryptoError> {
+        let indices = phrase
+            .split_whitespace()
+            .map(|word| {
+                english::WORDS
+                    .binary_search(&word.to_lowercase().as_str())
+                    .map_err(|_| CryptoError::InvalidInput("mnemonic contains a word outside the wordlist"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !WORD_COUNTS.contains(&indices.len()) {
+            return Err(CryptoError::InvalidInput("mnemonics have 12, 15, 18, 21 or 24 words"));
+        }
+
+        let mut bits: Vec<bool> = indices
+            .iter()
+            .flat_map(|&index| (0..BITS_PER_WORD).rev().map(move |i| (index >> i) & 1 == 1))
+            .collect();
+        let checksum_bits = bits.len() / 33;
+        let checksum = bits.split_off(bits.len() - checksum_bits);
+        let entropy = SecretBytes::new(
+            bits.chunks(8)
+                .map(|byte| byte.iter().fold(0u8, |acc, &bit| (acc << 1) | u8::from(bit)))
+                .collect(),
+        );
+        let mnemonic = Self::from_entropy(entropy.expose_secret())?;
+        if mnemonic.checksum_bits() != checksum {
+            return Err(CryptoError::InvalidInput("mnemonic checksum does not match"));
+        }
+        Ok(mnemonic)
+    }
+
+    /// Returns `true` if `phrase` is a valid mnemonic.
+    pub fn is_valid(phrase: &str) -> bool {
+        Self::parse(phrase).is_ok()
+    }
+
+    fn checksum_bits(&self) -> Vec<bool> {
+        let hash = Sha256::digest(self.entropy.expose_secret());
+        (0..self.entropy.len() / 4).map(|i| (hash[i / 8] >> (7 - i % 8)) & 1 == 1).collect()
+    }
+
+    /// The encoded entropy.
+    pub fn entropy(&self) -> &[u8] {
+        self.entropy.expose_secret()
+    }
+
+    /// The words of the phrase.
+    pub fn words(&self) -> Vec<&'static str> {
+        let bits: Vec<bool> = self
+            .entropy
+            .expose_secret()
+            .iter()
+            .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1 == 1))
+            .chain(self.checksum_bits())
+            .collect();
+        bits.chunks(BITS_PER_WORD)
+            .map(|chunk| english::WORDS[chunk.iter().fold(0usize, |acc, &bit| (acc << 1) | usize::from(bit))])
+            .collect()
+    }
+
+    /// The phrase, with words separated by single spaces.
+    pub fn phrase(&self) -> String {
+        self.words().join(" ")
+    }
+
+    /// The number of words in the phrase.
+    pub fn word_count(&self) -> usize {
+        self.entropy.len() * 3 / 4
+    }
+
+    /// Derives the 64-byte BIP39 seed.
+    ///
+    /// # Arguments
+    /// - `passphrase`: An optional extra secret; an empty string gives the standard seed. Each
+    ///   passphrase yields a different, equally valid seed, so a mistyped one cannot be detected.
+    pub fn to_seed(&self, passphrase: &str) -> SecretKey<SEED_SIZE> {
+        let mut phrase = self.phrase();
+        let mut salt = format!("mnemonic{}", passphrase);
+        let mut seed = SecretKey::new([0u8; SEED_SIZE]);
+        pbkdf2_hmac::<Sha512>(phrase.as_bytes(), salt.as_bytes(), PBKDF2_ROUNDS, seed.expose_secret_mut());
+        phrase.zeroize();
+        salt.zeroize();
+        seed
+    }
+
+    /// Derives an Ed25519 signing key from the first 32 bytes of the seed.
+    ///
+    /// This is a plain truncation rather than SLIP-0010 derivation, so the key only matches other
+    /// software that derives keys the same way.
+    pub fn to_signing_key(&self, passphrase: &str) -> SigningKey {
+        let seed = self.to_seed(passphrase);
+        let mut secret = SecretKey::new([0u8; 32]);
+        secret.expose_secret_mut().copy_from_slice(&seed.expose_secret()[..32]);
+        keypair_from_seed(*secret.expose_secret())
+    }
+}
+
+impl std::fmt::Debug for Mnemonic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Mnemonic").field("word_count", &self.word_count()).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::random::SeededRandom;
+
+    // Test vectors from the reference implementation, with the passphrase "TREZOR"
+    const VECTORS: [(&str, &str, &str); 4] = [
+        (
+            "00000000000000000000000000000000",
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+        ),
+        (
+            "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+            "legal winner thank year wave sausage worth useful legal winner thank yellow",
+            "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607",
+        ),
+        (
+            "9e885d952ad362caeb4efe34a8e91bd2",
+            "ozone drill grab fiber curtain grace pudding thank cruise elder eight picnic",
+            "274ddc525802f7c828d8ef7ddbcdc5304e87ac3535913611fbbfa986d0c9e5476c91689f9c8a54fd55bd38606aa6a8595ad213d4c9c9f9aca3fb217069a41028",
+        ),
+        (
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote",
+            "dd48c104698c30cfe2b6142103248622fb7bb0ff692eebb00089b32d22484e1613912f0a5b694407be899ffd31ed3992c456cdf60f5d4564b8ba3f05a69890ad",
+        ),
+    ];
+
+    #[test]
+    fn test_reference_vectors() {
+        for (entropy, phrase, seed) in VECTORS {
+            let mnemonic = Mnemonic::from_entropy(&hex::decode(entropy).unwrap()).unwrap();
+            assert_eq!(mnemonic.phrase(), phrase);
+            assert_eq!(hex::encode(mnemonic.to_seed("TREZOR").expose_secret()), seed);
+
+            let parsed = Mnemonic::parse(phrase).unwrap();
+            assert_eq!(hex::encode(parsed.entropy()), entropy);
+        }
+    }
+
+    #[test]
+    fn test_generate_and_restore() {
+        for word_count in WORD_COUNTS {
+            let mnemonic = Mnemonic::generate(word_count).unwrap();
+            assert_eq!(mnemonic.words().len(), word_count);
+            assert_eq!(Mnemonic::parse(&mnemonic.phrase()).unwrap(), mnemonic);
+        }
+        let a = Mnemonic::generate_with(&mut SeededRandom::from_u64(1), 12).unwrap();
+        let b = Mnemonic::generate_with(&mut SeededRandom::from_u64(1), 12).unwrap();
+        assert_eq!(a.to_signing_key("").to_bytes(), b.to_signing_key("").to_bytes());
+        assert_ne!(a.to_signing_key("").to_bytes(), a.to_signing_key("extra").to_bytes());
+        assert!(Mnemonic::generate(13).is_err());
+    }
+
+    #[test]
+    fn test_validation() {
+        let phrase = VECTORS[1].1;
+        assert!(Mnemonic::is_valid(&format!("  {}\n", phrase.to_uppercase())));
+        // Swapping two words keeps every word valid but breaks the checksum
+        assert!(!Mnemonic::is_valid(&phrase.replacen("legal winner", "winner legal", 1)));
+        assert!(!Mnemonic::is_valid(&phrase.replace("yellow", "yellowish")));
+        assert!(!Mnemonic::is_valid("abandon abandon about"));
+        assert!(Mnemonic::from_entropy(&[0; 15]).is_err());
+        assert!(!format!("{:?}", Mnemonic::parse(phrase).unwrap()).contains("legal"));
+    }
+}
@@ -1,9 +1,37 @@
+pub mod error;
 pub mod hash;
 pub mod symmetric;
+pub mod stream;
+pub mod file;
+#[cfg(feature = "crypto-classical")]
+pub mod envelope;
+#[cfg(feature = "crypto-classical")]
 pub mod asymmetric;
+#[cfg(feature = "crypto-classical")]
+pub mod ecdsa;
+#[cfg(feature = "crypto-classical")]
+pub mod ecies;
+#[cfg(feature = "crypto-classical")]
+pub mod pem;
 pub mod random;
+pub mod secret;
 pub mod utilities;
 pub mod signatures;
+pub mod signer;
+#[cfg(feature = "crypto-classical")]
+pub mod sr25519;
+pub mod ssh;
+pub mod multisig;
+#[cfg(feature = "crypto-classical")]
+pub mod keyring;
+pub mod merkle;
+pub mod mnemonic;
+pub mod otp;
 pub mod quantum;
-mod post_quantum;
+pub mod qkd;
+pub mod qds;
+pub mod qrng;
+#[cfg(feature = "crypto-pq")]
+pub mod post_quantum;
 
+pub use error::{CryptoError, EncodingError};
@@ -0,0 +1,237 @@
+//! Threshold Multisignatures
+//!
+//! A `k`-of-`n` scheme over Ed25519: a [`MultisigPolicy`] names the `n` authorized signers and the
+//! threshold `k`, and a [`MultiSignature`] aggregates the individual signatures of any subset of
+//! them. A message is accepted once signatures from at least `k` distinct signers in the policy
+//! verify.
+//!
+//! Signers sign the message itself, so existing Ed25519 keys and signatures can be used unchanged.
+
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey, SIGNATURE_LENGTH};
+use crate::crypto::error::CryptoError;
+use crate::crypto::signatures::{batch_verify, sign_message, BatchVerifyError};
+
+/// The signers authorized to approve a message and how many of them must do so.
+///
+/// # Example
+/// ```
+/// use zana::crypto::multisig::{MultiSignature, MultisigPolicy};
+/// use zana::crypto::signatures::generate_keypair;
+///
+/// let keypairs: Vec<_> = (0..3).map(|_| generate_keypair()).collect();
+/// let policy = MultisigPolicy::new(keypairs.iter().map(|k| k.verifying_key()).collect(), 2).unwrap();
+///
+/// let mut multisig = MultiSignature::new();
+/// multisig.sign(&policy, &keypairs[0], b"deploy v2").unwrap();
+/// assert!(!policy.verify(b"deploy v2", &multisig));
+///
+/// multisig.sign(&policy, &keypairs[2], b"deploy v2").unwrap();
+/// assert!(policy.verify(b"deploy v2", &multisig));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultisigPolicy {
+    signers: Vec<VerifyingKey>,
+    threshold: usize,
+}
+
+impl MultisigPolicy {
+    /// Creates a policy requiring `threshold` of the given signers.
+    ///
+    /// # Returns
+    /// `CryptoError::InvalidInput` if the threshold is `0` or exceeds the number of signers, or if
+    /// a signer is listed twice.
+    pub fn new(signers: Vec<VerifyingKey>, threshold: usize) -> Result<Self, CryptoError> {
+        if threshold == 0 || threshold > signers.len() {
+            return Err(CryptoError::InvalidInput("threshold must be between 1 and the number of signers"));
+        }
+        if signers.len() > u16::MAX as usize {
+            return Err(CryptoError::InvalidInput("too many signers"));
+        }
+        for (i, signer) in signers.iter().enumerate() {
+            if signers[..i].contains(signer) {
+                return Err(CryptoError::InvalidInput("duplicate signer in policy"));
+            }
+        }
+        Ok(Self { signers, threshold })
+    }
+
+    /// The authorized signers, in policy order.
+    pub fn signers(&self) -> &[VerifyingKey] {
+        &self.signers
+    }
+
+    /// The number of distinct signers required.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Returns the position of a signer in the policy.
+    pub fn signer_index(&self, public_key: &VerifyingKey) -> Option<usize> {
+        self.signers.iter().position(|signer| signer == public_key)
+    }
+
+    /// Returns the sorted indices of the signers whose signatures on `message` verify.
+    pub fn valid_signers(&self, message: &[u8], multisig: &MultiSignature) -> Vec<usize> {
+        let entries: Vec<&(usize, Signature)> = multisig
+            .signatures
+            .iter()
+            .filter(|(signer, _)| *signer < self.signers.len())
+            .collect();
+        let public_keys: Vec<VerifyingKey> = entries.iter().map(|(signer, _)| self.signers[*signer]).collect();
+        let messages = vec![message; entries.len()];
+        let signatures: Vec<Signature> = entries.iter().map(|(_, signature)| *signature).collect();
+
+        let invalid = match batch_verify(&public_keys, &messages, &signatures) {
+            Ok(()) => Vec::new(),
+            Err(BatchVerifyError::InvalidSignatures(invalid)) => invalid,
+            Err(BatchVerifyError::LengthMismatch) => unreachable!("batch inputs are built with equal lengths"),
+        };
+        let mut valid: Vec<usize> = entries
+            .iter()
+            .enumerate()
+            .filter(|(position, _)| !invalid.contains(position))
+            .map(|(_, (signer, _))| *signer)
+            .collect();
+        valid.sort_unstable();
+        valid.dedup();
+        valid
+    }
+
+    /// Returns `true` if at least [`threshold`](Self::threshold) distinct signers signed `message`.
+    pub fn verify(&self, message: &[u8], multisig: &MultiSignature) -> bool {
+        self.valid_signers(message, multisig).len() >= self.threshold
+    }
+}
+
+/// An aggregate of individual signatures, each tagged with its signer's index in a policy.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MultiSignature {
+    signatures: Vec<(usize, Signature)>,
+}
+
+impl MultiSignature {
+    /// Creates an empty multisignature.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the signature of the signer at `signer` in the policy, replacing any earlier one.
+    pub fn add_signature(&mut self, signer: usize, signature: Signature) {
+        self.signatures.retain(|(existing, _)| *existing != signer);
+        self.signatures.push((signer, signature));
+    }
+
+    /// Signs `message` with `keypair` and adds the signature.
+    ///
+    /// # Returns
+    /// `CryptoError::InvalidInput` if the keypair is not one of the policy's signers.
+    pub fn sign(&mut self, policy: &MultisigPolicy, keypair: &SigningKey, message: &[u8]) -> Result<(), CryptoError> {
+        let signer = policy
+            .signer_index(&keypair.verifying_key())
+            .ok_or(CryptoError::InvalidInput("signer is not part of the policy"))?;
+        self.add_signature(signer, sign_message(keypair, message));
+        Ok(())
+    }
+
+    /// The number of signatures collected.
+    pub fn len(&self) -> usize {
+        self.signatures.len()
+    }
+
+    /// Returns `true` if no signatures were collected.
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+
+    /// Serializes the multisignature as a sequence of `signer index (u16, big-endian) ‖ signature`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.signatures.len() * (2 + SIGNATURE_LENGTH));
+        for (signer, signature) in &self.signatures {
+            bytes.extend_from_slice(&(*signer as u16).to_be_bytes());
+            bytes.extend_from_slice(&signature.to_bytes());
+        }
+        bytes
+    }
+
+    /// Deserializes a multisignature produced by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        let entry_size = 2 + SIGNATURE_LENGTH;
+        if !bytes.len().is_multiple_of(entry_size) {
+            return Err(CryptoError::InvalidInput("multisignature has a partial entry"));
+        }
+        let mut multisig = Self::new();
+        for entry in bytes.chunks(entry_size) {
+            let signer = u16::from_be_bytes([entry[0], entry[1]]) as usize;
+            let signature = Signature::from_slice(&entry[2..]).expect("entry holds a full signature");
+            multisig.add_signature(signer, signature);
+        }
+        Ok(multisig)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::signatures::generate_keypair;
+
+    fn setup(n: usize, threshold: usize) -> (Vec<SigningKey>, MultisigPolicy) {
+        let keypairs: Vec<SigningKey> = (0..n).map(|_| generate_keypair()).collect();
+        let policy = MultisigPolicy::new(keypairs.iter().map(SigningKey::verifying_key).collect(), threshold).unwrap();
+        (keypairs, policy)
+    }
+
+    #[test]
+    fn test_threshold_is_enforced() {
+        let (keypairs, policy) = setup(5, 3);
+        let mut multisig = MultiSignature::new();
+
+        for (count, keypair) in keypairs.iter().take(3).enumerate() {
+            assert!(!policy.verify(b"transfer", &multisig), "{} signatures", count);
+            multisig.sign(&policy, keypair, b"transfer").unwrap();
+        }
+        assert!(policy.verify(b"transfer", &multisig));
+        assert!(!policy.verify(b"other", &multisig));
+    }
+
+    #[test]
+    fn test_duplicate_and_invalid_signatures_do_not_count() {
+        let (keypairs, policy) = setup(3, 2);
+        let mut multisig = MultiSignature::new();
+        multisig.sign(&policy, &keypairs[0], b"msg").unwrap();
+        multisig.sign(&policy, &keypairs[0], b"msg").unwrap();
+        assert_eq!(multisig.len(), 1);
+
+        // A signature filed under the wrong signer, and one for an index outside the policy
+        multisig.add_signature(1, sign_message(&keypairs[2], b"msg"));
+        multisig.add_signature(7, sign_message(&keypairs[2], b"msg"));
+        assert_eq!(policy.valid_signers(b"msg", &multisig), vec![0]);
+        assert!(!policy.verify(b"msg", &multisig));
+
+        let outsider = generate_keypair();
+        assert!(multisig.sign(&policy, &outsider, b"msg").is_err());
+    }
+
+    #[test]
+    fn test_serialization_round_trip() {
+        let (keypairs, policy) = setup(3, 2);
+        let mut multisig = MultiSignature::new();
+        multisig.sign(&policy, &keypairs[2], b"msg").unwrap();
+        multisig.sign(&policy, &keypairs[1], b"msg").unwrap();
+
+        let bytes = multisig.to_bytes();
+        assert_eq!(bytes.len(), 2 * 66);
+        let decoded = MultiSignature::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, multisig);
+        assert!(policy.verify(b"msg", &decoded));
+        assert!(MultiSignature::from_bytes(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn test_policy_validation() {
+        let key = generate_keypair().verifying_key();
+        assert!(MultisigPolicy::new(vec![key], 0).is_err());
+        assert!(MultisigPolicy::new(vec![key], 2).is_err());
+        assert!(MultisigPolicy::new(vec![key, key], 1).is_err());
+        assert_eq!(MultisigPolicy::new(vec![key], 1).unwrap().threshold(), 1);
+    }
+}
@@ -0,0 +1,370 @@
+//! One-Time Passwords
+//!
+//! HOTP (RFC 4226) and TOTP (RFC 6238), the codes behind authenticator apps:
+//! - HOTP truncates `HMAC(secret, counter)` to a short decimal code.
+//! - TOTP is HOTP with the counter set to the number of `period`-second steps since the Unix
+//!   epoch, checked within a small window of steps to tolerate clock drift.
+//!
+//! Secrets are exchanged as unpadded RFC 4648 base32, the format authenticator apps expect, and
+//! [`Totp::to_uri`] produces the `otpauth://` URI shown as a QR code during enrolment.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use crate::crypto::error::CryptoError;
+use crate::crypto::hash::{hmac_sha256, hmac_sha512};
+use crate::crypto::random::{random_array, OsRandom};
+use crate::crypto::secret::{SecretBytes, SecretKey};
+use crate::crypto::symmetric::constant_time_compare;
+use crate::crypto::utilities::{from_base32, to_base32_unpadded};
+
+/// The default number of digits in a code.
+pub const DEFAULT_DIGITS: u32 = 6;
+
+/// The default TOTP time step in seconds.
+pub const DEFAULT_PERIOD: u64 = 30;
+
+/// The default number of TOTP steps accepted on either side of the current one.
+pub const DEFAULT_SKEW: u64 = 1;
+
+/// The length of secrets from [`generate_secret`], as recommended by RFC 4226.
+pub const SECRET_SIZE: usize = 20;
+
+/// The HMAC hash function. SHA-1 is the RFC 4226 default and the one most authenticator apps
+/// support; its collision weaknesses do not affect HMAC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OtpAlgorithm {
+    #[default]
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl OtpAlgorithm {
+    /// The name used in `otpauth://` URIs.
+    pub fn name(&self) -> &'static str {
+        match self {
+            OtpAlgorithm::Sha1 => "SHA1",
+            OtpAlgorithm::Sha256 => "SHA256",
+            OtpAlgorithm::Sha512 => "SHA512",
+        }
+    }
+
+    fn hmac(&self, key: &[u8], data: &[u8]) -> Vec<u8> {
+        match self {
+            OtpAlgorithm::Sha1 => {
+                let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC key initialization failed");
+                mac.update(data);
+                mac.finalize().into_bytes().to_vec()
+            }
+            OtpAlgorithm::Sha256 => hmac_sha256(key, data),
+            OtpAlgorithm::Sha512 => hmac_sha512(key, data),
+        }
+    }
+}
+
+/// Generates a random 160-bit secret, base32 encoded.
+pub fn generate_secret() -> String {
+    let secret: SecretKey<SECRET_SIZE> =
+        random_array(&mut OsRandom).map(SecretKey::new).expect("the operating system RNG is unavailable");
+    to_base32_unpadded(secret.expose_secret())
+}
+
+/// Computes an HOTP code.
+///
+/// # Arguments
+/// - `secret`: The shared secret.
+/// - `counter`: The moving factor.
+/// - `digits`: The code length, from 6 to 9.
+/// - `algorithm`: The HMAC hash function.
+///
+/// # Returns
+/// The zero-padded code, or `CryptoError::InvalidInput` if the secret is empty or `digits` is out
+/// of range.
+pub fn hotp(secret: &[u8], counter: u64, digits: u32, algorithm: OtpAlgorithm) -> Result<String, CryptoError> {
+    if secret.is_empty() {
+        return Err(CryptoError::InvalidInput("OTP secret must not be empty"));
+    }
+    if !(6..=9).contains(&digits) {
+        return Err(CryptoError::InvalidInput("OTP codes must have 6 to 9 digits"));
+    }
+    let mac = algorithm.hmac(secret, &counter.to_be_bytes());
+
+    // Dynamic truncation: the low nibble of the last byte picks a 31-bit window
+    let offset = (mac[mac.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes(mac[offset..offset + 4].try_into().expect("4 bytes")) & 0x7fff_ffff;
+    Ok(format!("{:0width$}", binary % 10u32.pow(digits), width = digits as usize))
+}
+
+/// An HOTP generator and verifier.
+///
+/// # Example
+/// ```
+/// use zana::crypto::otp::Hotp;
+///
+/// let hotp = Hotp::new(b"12345678901234567890");
+/// assert_eq!(hotp.generate(0).unwrap(), "755224");
+/// // A code from a few presses ahead is found within the look-ahead window
+/// assert_eq!(hotp.verify("969429", 1, 5).unwrap(), Some(3));
+/// ```
+#[derive(Clone)]
+pub struct Hotp {
+    secret: SecretBytes,
+    digits: u32,
+    algorithm: OtpAlgorithm,
+}
+
+impl Hotp {
+    /// Creates a six-digit HMAC-SHA1 generator for `secret`.
+    pub fn new(secret: &[u8]) -> Self {
+        Self { secret: SecretBytes::from_slice(secret), digits: DEFAULT_DIGITS, algorithm: OtpAlgorithm::default() }
+    }
+
+    /// Creates a generator from a base32 secret. Case, spaces and padding are ignored, since
+    /// secrets are often typed in by hand.
+    ///
+    /// # Returns
+    /// The generator, or `CryptoError::Encoding` if the secret is not valid base32.
+    pub fn from_base32(secret: &str) -> Result<Self, CryptoError> {
+        let secret: String = secret.chars().filter(|c| !c.is_whitespace()).collect();
+        let secret = from_base32(&secret)?;
+        Ok(Self { secret: SecretBytes::new(secret), digits: DEFAULT_DIGITS, algorithm: OtpAlgorithm::default() })
+    }
+
+    /// Sets the number of digits.
+    pub fn with_digits(mut self, digits: u32) -> Self {
+        self.digits = digits;
+        self
+    }
+
+    /// Sets the HMAC hash function.
+    pub fn with_algorithm(mut self, algorithm: OtpAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Returns the secret, base32 encoded.
+    pub fn secret_base32(&self) -> String {
+        to_base32_unpadded(self.secret.expose_secret())
+    }
+
+    /// Computes the code for `counter`.
+    pub fn generate(&self, counter: u64) -> Result<String, CryptoError> {
+        hotp(self.secret.expose_secret(), counter, self.digits, self.algorithm)
+    }
+
+    /// Checks `code` against counters `counter..=counter + look_ahead`.
+    ///
+    /// # Returns
+    /// The matching counter, so the caller can store `matched + 1` as the next counter, or `None`.
+    pub fn verify(&self, code: &str, counter: u64, look_ahead: u64) -> Result<Option<u64>, CryptoError> {
+        for candidate in counter..=counter.saturating_add(look_ahead) {
+            if constant_time_compare(self.generate(candidate)?.as_bytes(), code.as_bytes()) {
+                return Ok(Some(candidate));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl std::fmt::Debug for Hotp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Hotp")
+            .field("digits", &self.digits)
+            .field("algorithm", &self.algorithm)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A TOTP generator and verifier.
+///
+/// # Example
+/// ```
+/// use zana::crypto::otp::{generate_secret, Totp};
+///
+/// let totp = Totp::from_base32(&generate_secret()).unwrap();
+/// let code = totp.generate_at(1_700_000_000).unwrap();
+/// assert!(totp.verify_at(&code, 1_700_000_000 + 25).unwrap());
+/// assert!(!totp.verify_at(&code, 1_700_000_000 + 300).unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Totp {
+    hotp: Hotp,
+    period: u64,
+    skew: u64,
+}
+
+impl Totp {
+    /// Creates a six-digit, 30-second HMAC-SHA1 generator for `secret`.
+    pub fn new(secret: &[u8]) -> Self {
+        Self { hotp: Hotp::new(secret), period: DEFAULT_PERIOD, skew: DEFAULT_SKEW }
+    }
+
+    /// Creates a generator from a base32 secret.
+    pub fn from_base32(secret: &str) -> Result<Self, CryptoError> {
+        Ok(Self { hotp: Hotp::from_base32(secret)?, period: DEFAULT_PERIOD, skew: DEFAULT_SKEW })
+    }
+
+    /// Sets the number of digits.
+    pub fn with_digits(mut self, digits: u32) -> Self {
+        self.hotp = self.hotp.with_digits(digits);
+        self
+    }
+
+    /// Sets the HMAC hash function.
+    pub fn with_algorithm(mut self, algorithm: OtpAlgorithm) -> Self {
+        self.hotp = self.hotp.with_algorithm(algorithm);
+        self
+    }
+
+    /// Sets the time step in seconds.
+    pub fn with_period(mut self, period: u64) -> Self {
+        self.period = period;
+        self
+    }
+
+    /// Sets how many steps before and after the current one are accepted.
+    pub fn with_skew(mut self, skew: u64) -> Self {
+        self.skew = skew;
+        self
+    }
+
+    fn step(&self, unix_time: u64) -> Result<u64, CryptoError> {
+        if self.period == 0 {
+            return Err(CryptoError::InvalidInput("TOTP period must be positive"));
+        }
+        Ok(unix_time / self.period)
+    }
+
+    /// Computes the code for a Unix timestamp in seconds.
+    pub fn generate_at(&self, unix_time: u64) -> Result<String, CryptoError> {
+        self.hotp.generate(self.step(unix_time)?)
+    }
+
+    /// Computes the code for the current time.
+    pub fn generate(&self) -> Result<String, CryptoError> {
+        self.generate_at(now())
+    }
+
+    /// Checks `code` against the steps within the drift window around `unix_time`.
+    pub fn verify_at(&self, code: &str, unix_time: u64) -> Result<bool, CryptoError> {
+        let step = self.step(unix_time)?;
+        let first = step.saturating_sub(self.skew);
+        let window = step.saturating_add(self.skew) - first;
+        Ok(self.hotp.verify(code, first, window)?.is_some())
+    }
+
+    /// Checks `code` against the current time.
+    pub fn verify(&self, code: &str) -> Result<bool, CryptoError> {
+        self.verify_at(code, now())
+    }
+
+    /// Builds the `otpauth://totp/` enrolment URI understood by authenticator apps.
+    ///
+    /// # Arguments
+    /// - `issuer`: The service name, e.g. `"Zana"`.
+    /// - `account`: The user's account name.
+    pub fn to_uri(&self, issuer: &str, account: &str) -> String {
+        format!(
+            "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm={}&digits={}&period={}",
+            percent_encode(issuer),
+            percent_encode(account),
+            self.hotp.secret_base32(),
+            percent_encode(issuer),
+            self.hotp.algorithm.name(),
+            self.hotp.digits,
+            self.period
+        )
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Percent-encodes everything but RFC 3986 unreserved characters.
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hotp_rfc4226_vectors() {
+        let expected = [
+            "755224", "287082", "359152", "969429", "338314", "254676", "287922", "162583", "399871", "520489",
+        ];
+        let hotp = Hotp::new(b"12345678901234567890");
+        for (counter, code) in expected.iter().enumerate() {
+            assert_eq!(hotp.generate(counter as u64).unwrap(), *code);
+        }
+    }
+
+    #[test]
+    fn test_totp_rfc6238_vectors() {
+        let sha1 = Totp::new(b"12345678901234567890").with_digits(8);
+        let sha256 = Totp::new(b"12345678901234567890123456789012").with_digits(8).with_algorithm(OtpAlgorithm::Sha256);
+        let sha512 = Totp::new(b"1234567890123456789012345678901234567890123456789012345678901234")
+            .with_digits(8)
+            .with_algorithm(OtpAlgorithm::Sha512);
+        let vectors = [
+            (59, "94287082", "46119246", "90693936"),
+            (1111111109, "07081804", "68084774", "25091201"),
+            (1111111111, "14050471", "67062674", "99943326"),
+            (1234567890, "89005924", "91819424", "93441116"),
+            (2000000000, "69279037", "90698825", "38618901"),
+            (20000000000, "65353130", "77737706", "47863826"),
+        ];
+        for (time, code1, code256, code512) in vectors {
+            assert_eq!(sha1.generate_at(time).unwrap(), code1);
+            assert_eq!(sha256.generate_at(time).unwrap(), code256);
+            assert_eq!(sha512.generate_at(time).unwrap(), code512);
+        }
+    }
+
+    #[test]
+    fn test_drift_window() {
+        let totp = Totp::new(b"12345678901234567890");
+        let code = totp.generate_at(1_000_000).unwrap();
+        assert!(totp.verify_at(&code, 1_000_000 - 30).unwrap());
+        assert!(totp.verify_at(&code, 1_000_000 + 30).unwrap());
+        assert!(!totp.verify_at(&code, 1_000_000 + 90).unwrap());
+        assert!(totp.clone().with_skew(3).verify_at(&code, 1_000_000 + 90).unwrap());
+        assert!(totp.verify(&totp.generate().unwrap()).unwrap());
+    }
+
+    #[test]
+    fn test_base32_secrets() {
+        let hotp = Hotp::from_base32("gezd gnbv gy3t qojq gezd gnbv gy3t qojq").unwrap();
+        assert_eq!(hotp.secret_base32(), "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert_eq!(hotp.generate(0).unwrap(), "755224");
+
+        let secret = generate_secret();
+        assert_eq!(secret.len(), 32);
+        let hotp = Hotp::from_base32(&secret).unwrap();
+        assert_eq!(hotp.secret_base32(), secret);
+        assert!(Hotp::from_base32("not base32!").is_err());
+    }
+
+    #[test]
+    fn test_invalid_parameters_and_uri() {
+        assert!(Hotp::new(b"").generate(0).is_err());
+        assert!(Hotp::new(b"secret").with_digits(5).generate(0).is_err());
+        assert!(Totp::new(b"secret").with_period(0).generate_at(0).is_err());
+
+        let uri = Totp::new(b"12345678901234567890").to_uri("Zana Labs", "alice@example.com");
+        assert_eq!(
+            uri,
+            "otpauth://totp/Zana%20Labs:alice%40example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ\
+             &issuer=Zana%20Labs&algorithm=SHA1&digits=6&period=30"
+        );
+        assert!(!format!("{:?}", Hotp::new(b"12345678901234567890")).contains("49"));
+    }
+}
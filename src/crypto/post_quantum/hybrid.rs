@@ -0,0 +1,276 @@
+//! Hybrid X25519 + ML-KEM-768 Key Encapsulation
+//!
+//! Combines a classical X25519 exchange with [ML-KEM-768](super::ml_kem), so the shared secret
+//! stays safe as long as either scheme holds: X25519 covers a flaw in the young post-quantum
+//! scheme, and ML-KEM covers a future quantum computer breaking X25519.
+//!
+//! The sender generates an ephemeral X25519 key and encapsulates to the recipient's ML-KEM key.
+//! The two shared secrets are concatenated and fed to HKDF-SHA256, with both X25519 public keys in
+//! the info string so the result is bound to the classical transcript (ML-KEM already binds its
+//! own ciphertext).
+//!
+//! # Format
+//! Ciphertext: `ephemeral X25519 public key (32) ‖ ML-KEM-768 ciphertext (1088)`.
+//! Public key: `X25519 public key (32) ‖ ML-KEM-768 encapsulation key (1184)`.
+
+use hkdf::Hkdf;
+use sha2::Sha256;
+use x25519_dalek::{PublicKey, StaticSecret};
+use zeroize::{ZeroizeOnDrop, Zeroizing};
+use crate::crypto::error::CryptoError;
+use crate::crypto::random::{random_array, OsRandom, SecureRandom};
+use crate::crypto::post_quantum::ml_kem::{self, Ciphertext, DecapsulationKey, EncapsulationKey};
+
+/// The size of a serialized public key.
+pub const PUBLIC_KEY_SIZE: usize = 32 + ml_kem::ENCAPSULATION_KEY_SIZE;
+
+/// The size of a ciphertext.
+pub const CIPHERTEXT_SIZE: usize = 32 + ml_kem::CIPHERTEXT_SIZE;
+
+/// The size of a serialized secret key.
+pub const SECRET_KEY_SIZE: usize = 32 + ml_kem::DECAPSULATION_KEY_SIZE;
+
+/// The size of the shared secret.
+pub const SHARED_SECRET_SIZE: usize = 32;
+
+const HKDF_INFO: &[u8] = b"zana hybrid x25519 ml-kem-768";
+
+/// The recipient's public key: an X25519 key and an ML-KEM-768 encapsulation key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HybridPublicKey {
+    x25519: PublicKey,
+    ml_kem: EncapsulationKey,
+}
+
+impl HybridPublicKey {
+    /// Serializes the key as `X25519 key ‖ ML-KEM encapsulation key`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.x25519.as_bytes(), self.ml_kem.as_bytes()].concat()
+    }
+
+    /// Deserializes a key produced by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.len() != PUBLIC_KEY_SIZE {
+            return Err(CryptoError::InvalidKeyLength { expected: PUBLIC_KEY_SIZE, actual: bytes.len() });
+        }
+        let x25519: [u8; 32] = bytes[..32].try_into().expect("32 bytes");
+        Ok(Self { x25519: PublicKey::from(x25519), ml_kem: EncapsulationKey::from_bytes(&bytes[32..])? })
+    }
+}
+
+/// The recipient's secret key. Both halves are zeroized when dropped.
+#[derive(Clone)]
+pub struct HybridSecretKey {
+    x25519: StaticSecret,
+    ml_kem: DecapsulationKey,
+}
+
+impl HybridSecretKey {
+    /// Derives the public key.
+    pub fn public_key(&self) -> HybridPublicKey {
+        HybridPublicKey { x25519: PublicKey::from(&self.x25519), ml_kem: self.ml_kem.encapsulation_key() }
+    }
+
+    /// Serializes the key as `X25519 secret ‖ ML-KEM decapsulation key`.
+    pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new([self.x25519.as_bytes(), self.ml_kem.as_bytes()].concat())
+    }
+
+    /// Deserializes a key produced by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.len() != SECRET_KEY_SIZE {
+            return Err(CryptoError::InvalidKeyLength { expected: SECRET_KEY_SIZE, actual: bytes.len() });
+        }
+        let x25519: Zeroizing<[u8; 32]> = Zeroizing::new(bytes[..32].try_into().expect("32 bytes"));
+        Ok(Self { x25519: StaticSecret::from(*x25519), ml_kem: DecapsulationKey::from_bytes(&bytes[32..])? })
+    }
+}
+
+impl ZeroizeOnDrop for HybridSecretKey {}
+
+impl std::fmt::Debug for HybridSecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HybridSecretKey").finish_non_exhaustive()
+    }
+}
+
+/// The sender's ephemeral X25519 key and ML-KEM ciphertext.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HybridCiphertext {
+    x25519: PublicKey,
+    ml_kem: Ciphertext,
+}
+
+impl HybridCiphertext {
+    /// Serializes the ciphertext as `ephemeral X25519 key ‖ ML-KEM ciphertext`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.x25519.as_bytes(), self.ml_kem.as_bytes()].concat()
+    }
+
+    /// Deserializes a ciphertext produced by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.len() != CIPHERTEXT_SIZE {
+            return Err(CryptoError::InvalidInput("invalid hybrid ciphertext length"));
+        }
+        let x25519: [u8; 32] = bytes[..32].try_into().expect("32 bytes");
+        Ok(Self { x25519: PublicKey::from(x25519), ml_kem: Ciphertext::from_bytes(&bytes[32..])? })
+    }
+}
+
+impl_serde_via_bytes!(HybridPublicKey, to_bytes);
+impl_serde_via_bytes!(HybridSecretKey, to_bytes);
+impl_serde_via_bytes!(HybridCiphertext, to_bytes);
+
+/// Generates a hybrid key pair.
+///
+/// # Returns
+/// A tuple containing the secret key and public key.
+///
+/// # Panics
+/// If the operating system's random number generator is unavailable.
+pub fn generate_keypair() -> (HybridSecretKey, HybridPublicKey) {
+    generate_keypair_with(&mut OsRandom).expect("the operating system RNG is unavailable")
+}
+
+/// Generates a hybrid key pair from the given source.
+pub fn generate_keypair_with(rng: &mut impl SecureRandom) -> Result<(HybridSecretKey, HybridPublicKey), CryptoError> {
+    let (_, ml_kem) = ml_kem::keygen_with(rng)?;
+    let secret = HybridSecretKey { x25519: StaticSecret::from(random_array(rng)?), ml_kem };
+    let public = secret.public_key();
+    Ok((secret, public))
+}
+
+/// Combines both shared secrets, binding them to the X25519 public keys.
+fn combine(
+    x25519_secret: &[u8; 32],
+    ml_kem_secret: &[u8; 32],
+    ephemeral_public: &PublicKey,
+    recipient_public: &PublicKey,
+) -> [u8; SHARED_SECRET_SIZE] {
+    let ikm = [x25519_secret.as_slice(), ml_kem_secret].concat();
+    let info = [HKDF_INFO, ephemeral_public.as_bytes(), recipient_public.as_bytes()].concat();
+    let mut shared_secret = [0u8; SHARED_SECRET_SIZE];
+    Hkdf::<Sha256>::new(None, &ikm)
+        .expand(&info, &mut shared_secret)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    shared_secret
+}
+
+/// Generates a shared secret and the ciphertext that conveys it to the holder of `public_key`.
+///
+/// # Returns
+/// The ciphertext and the 32-byte shared secret, or `CryptoError::InvalidInput` if the X25519 key
+/// is a low-order point or the ML-KEM key is malformed.
+pub fn encapsulate(public_key: &HybridPublicKey) -> Result<(HybridCiphertext, [u8; SHARED_SECRET_SIZE]), CryptoError> {
+    encapsulate_with(&mut OsRandom, public_key)
+}
+
+/// Generates a shared secret and the ciphertext that conveys it to the holder of `public_key`,
+/// drawing the ephemeral key and the ML-KEM randomness from the given source.
+pub fn encapsulate_with(
+    rng: &mut impl SecureRandom,
+    public_key: &HybridPublicKey,
+) -> Result<(HybridCiphertext, [u8; SHARED_SECRET_SIZE]), CryptoError> {
+    // Used once and dropped, like an `EphemeralSecret`, which cannot be built from bytes
+    let ephemeral = StaticSecret::from(random_array(rng)?);
+    let ephemeral_public = PublicKey::from(&ephemeral);
+    let x25519_secret = ephemeral.diffie_hellman(&public_key.x25519);
+    if !x25519_secret.was_contributory() {
+        return Err(CryptoError::InvalidInput("X25519 public key is a low-order point"));
+    }
+    let (ml_kem_ciphertext, ml_kem_secret) = ml_kem::encapsulate_with(rng, &public_key.ml_kem)?;
+
+    let shared_secret = combine(x25519_secret.as_bytes(), &ml_kem_secret, &ephemeral_public, &public_key.x25519);
+    Ok((HybridCiphertext { x25519: ephemeral_public, ml_kem: ml_kem_ciphertext }, shared_secret))
+}
+
+/// Recovers the shared secret from a ciphertext.
+///
+/// # Returns
+/// The 32-byte shared secret, or `CryptoError::InvalidInput` if the ephemeral X25519 key is a
+/// low-order point. As with ML-KEM, a tampered ciphertext yields an unrelated secret rather than an
+/// error.
+pub fn decapsulate(secret_key: &HybridSecretKey, ciphertext: &HybridCiphertext) -> Result<[u8; SHARED_SECRET_SIZE], CryptoError> {
+    let x25519_secret = secret_key.x25519.diffie_hellman(&ciphertext.x25519);
+    if !x25519_secret.was_contributory() {
+        return Err(CryptoError::InvalidInput("X25519 public key is a low-order point"));
+    }
+    let ml_kem_secret = ml_kem::decapsulate(&secret_key.ml_kem, &ciphertext.ml_kem)?;
+
+    let recipient_public = PublicKey::from(&secret_key.x25519);
+    Ok(combine(x25519_secret.as_bytes(), &ml_kem_secret, &ciphertext.x25519, &recipient_public))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_both_sides_derive_the_same_secret() {
+        let (secret_key, public_key) = generate_keypair();
+        let (ciphertext, sender_secret) = encapsulate(&public_key).unwrap();
+        assert_eq!(decapsulate(&secret_key, &ciphertext).unwrap(), sender_secret);
+
+        let (_, other_secret) = encapsulate(&public_key).unwrap();
+        assert_ne!(other_secret, sender_secret);
+    }
+
+    #[test]
+    fn test_keys_from_a_given_source() {
+        use crate::crypto::random::SeededRandom;
+
+        let (_, public_key) = generate_keypair_with(&mut SeededRandom::from_u64(1)).unwrap();
+        assert_eq!(generate_keypair_with(&mut SeededRandom::from_u64(1)).unwrap().1, public_key);
+        assert_ne!(generate_keypair_with(&mut SeededRandom::from_u64(2)).unwrap().1, public_key);
+
+        let (first, _) = encapsulate_with(&mut SeededRandom::from_u64(3), &public_key).unwrap();
+        let (second, _) = encapsulate_with(&mut SeededRandom::from_u64(3), &public_key).unwrap();
+        assert_eq!(first.to_bytes(), second.to_bytes());
+    }
+
+    #[test]
+    fn test_tampering_either_half_changes_the_secret() {
+        let (secret_key, public_key) = generate_keypair();
+        let (ciphertext, sender_secret) = encapsulate(&public_key).unwrap();
+        let bytes = ciphertext.to_bytes();
+        assert_eq!(bytes.len(), CIPHERTEXT_SIZE);
+
+        for index in [0, 32, CIPHERTEXT_SIZE - 1] {
+            let mut tampered = bytes.clone();
+            tampered[index] ^= 1;
+            let tampered = HybridCiphertext::from_bytes(&tampered).unwrap();
+            assert_ne!(decapsulate(&secret_key, &tampered).unwrap(), sender_secret, "byte {}", index);
+        }
+    }
+
+    #[test]
+    fn test_wrong_key_and_low_order_points() {
+        let (_, public_key) = generate_keypair();
+        let (other_secret_key, _) = generate_keypair();
+        let (ciphertext, sender_secret) = encapsulate(&public_key).unwrap();
+        assert_ne!(decapsulate(&other_secret_key, &ciphertext).unwrap(), sender_secret);
+
+        let mut bytes = public_key.to_bytes();
+        bytes[..32].fill(0);
+        let low_order = HybridPublicKey::from_bytes(&bytes).unwrap();
+        assert!(encapsulate(&low_order).is_err());
+
+        let mut bytes = ciphertext.to_bytes();
+        bytes[..32].fill(0);
+        let low_order = HybridCiphertext::from_bytes(&bytes).unwrap();
+        assert!(decapsulate(&other_secret_key, &low_order).is_err());
+    }
+
+    #[test]
+    fn test_serialization_round_trip() {
+        let (secret_key, public_key) = generate_keypair();
+        let decoded = HybridPublicKey::from_bytes(&public_key.to_bytes()).unwrap();
+        assert_eq!(decoded, public_key);
+
+        let (ciphertext, sender_secret) = encapsulate(&decoded).unwrap();
+        let ciphertext = HybridCiphertext::from_bytes(&ciphertext.to_bytes()).unwrap();
+        assert_eq!(decapsulate(&secret_key, &ciphertext).unwrap(), sender_secret);
+        assert!(HybridPublicKey::from_bytes(&[0u8; 32]).is_err());
+        assert!(HybridCiphertext::from_bytes(&[0u8; 32]).is_err());
+    }
+}
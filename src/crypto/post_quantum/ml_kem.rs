@@ -0,0 +1,616 @@
+//! ML-KEM-768 Key Encapsulation
+//!
+//! A pure-Rust implementation of the module-lattice key encapsulation mechanism standardized in
+//! FIPS 203 (derived from CRYSTALS-Kyber), at the ML-KEM-768 parameter set (NIST security
+//! category 3). A sender encapsulates to a recipient's [`EncapsulationKey`], producing a
+//! [`Ciphertext`] and a 32-byte shared secret; the recipient recovers the same secret from the
+//! ciphertext with its [`DecapsulationKey`].
+//!
+//! Decapsulation uses implicit rejection: a malformed or tampered ciphertext yields a
+//! pseudorandom secret unrelated to the sender's rather than an error.
+
+use sha3::{Digest, Sha3_256, Sha3_512, Shake128, Shake256};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+use crate::crypto::error::CryptoError;
+use crate::crypto::random::{OsRandom, SecureRandom};
+
+/// The polynomial degree.
+const N: usize = 256;
+/// The prime modulus.
+const Q: u32 = 3329;
+/// The module rank of ML-KEM-768.
+const K: usize = 3;
+/// The noise parameter of the secret and first error vector.
+const ETA1: usize = 2;
+/// The noise parameter of the encryption errors.
+const ETA2: usize = 2;
+/// The compression width of the ciphertext vector `u`.
+const DU: u32 = 10;
+/// The compression width of the ciphertext polynomial `v`.
+const DV: u32 = 4;
+
+/// The size of a 12-bit encoded polynomial.
+const POLY_BYTES: usize = 384;
+
+/// The size of an encoded encapsulation key.
+pub const ENCAPSULATION_KEY_SIZE: usize = POLY_BYTES * K + 32;
+/// The size of an encoded decapsulation key.
+pub const DECAPSULATION_KEY_SIZE: usize = 2 * POLY_BYTES * K + 3 * 32;
+/// The size of a ciphertext.
+pub const CIPHERTEXT_SIZE: usize = 32 * (DU as usize * K + DV as usize);
+/// The size of the shared secret.
+pub const SHARED_SECRET_SIZE: usize = 32;
+
+/// `128⁻¹ mod q`, applied at the end of the inverse NTT.
+const NTT_SCALE: u32 = 3303;
+
+type Poly = [u32; N];
+
+/// `17^BitRev7(i) mod q` for `i` in `0..128`.
+fn zetas() -> [u32; 128] {
+    let mut powers = [1u32; 128];
+    for i in 1..128 {
+        powers[i] = powers[i - 1] * 17 % Q;
+    }
+    let mut zetas = [0u32; 128];
+    for (i, zeta) in zetas.iter_mut().enumerate() {
+        *zeta = powers[(i as u8).reverse_bits() as usize >> 1];
+    }
+    zetas
+}
+
+/// Computes the NTT representation of `f` in place (FIPS 203, Algorithm 9).
+fn ntt(f: &mut Poly, zetas: &[u32; 128]) {
+    let mut i = 1;
+    let mut len = 128;
+    while len >= 2 {
+        for start in (0..N).step_by(2 * len) {
+            let zeta = zetas[i];
+            i += 1;
+            for j in start..start + len {
+                let t = zeta * f[j + len] % Q;
+                f[j + len] = (f[j] + Q - t) % Q;
+                f[j] = (f[j] + t) % Q;
+            }
+        }
+        len /= 2;
+    }
+}
+
+/// Converts `f` from its NTT representation in place (FIPS 203, Algorithm 10).
+fn inverse_ntt(f: &mut Poly, zetas: &[u32; 128]) {
+    let mut i = 127;
+    let mut len = 2;
+    while len <= 128 {
+        for start in (0..N).step_by(2 * len) {
+            let zeta = zetas[i];
+            i -= 1;
+            for j in start..start + len {
+                let t = f[j];
+                f[j] = (t + f[j + len]) % Q;
+                f[j + len] = zeta * ((f[j + len] + Q - t) % Q) % Q;
+            }
+        }
+        len *= 2;
+    }
+    for coefficient in f.iter_mut() {
+        *coefficient = *coefficient * NTT_SCALE % Q;
+    }
+}
+
+/// Multiplies two polynomials in NTT representation (FIPS 203, Algorithms 11 and 12).
+fn multiply_ntts(f: &Poly, g: &Poly, zetas: &[u32; 128]) -> Poly {
+    let mut h = [0u32; N];
+    for i in 0..N / 2 {
+        // γ = 17^(2·BitRev7(i) + 1) = zeta(64 + i/2), negated for odd i
+        let gamma = if i % 2 == 0 { zetas[64 + i / 2] } else { Q - zetas[64 + i / 2] };
+        let (a0, a1, b0, b1) = (f[2 * i], f[2 * i + 1], g[2 * i], g[2 * i + 1]);
+        h[2 * i] = (a0 * b0 + a1 * b1 % Q * gamma) % Q;
+        h[2 * i + 1] = (a0 * b1 + a1 * b0) % Q;
+    }
+    h
+}
+
+fn add(f: &Poly, g: &Poly) -> Poly {
+    let mut h = [0u32; N];
+    for i in 0..N {
+        h[i] = (f[i] + g[i]) % Q;
+    }
+    h
+}
+
+/// Samples a uniform polynomial in NTT representation from `ρ ‖ j ‖ i` (FIPS 203, Algorithm 7).
+fn sample_ntt(rho: &[u8; 32], j: u8, i: u8) -> Poly {
+    let mut xof = Shake128::default();
+    Update::update(&mut xof, rho);
+    Update::update(&mut xof, &[j, i]);
+    let mut reader = xof.finalize_xof();
+
+    let mut a = [0u32; N];
+    let mut count = 0;
+    let mut bytes = [0u8; 3];
+    while count < N {
+        reader.read(&mut bytes);
+        let d1 = bytes[0] as u32 + 256 * (bytes[1] as u32 % 16);
+        let d2 = bytes[1] as u32 / 16 + 16 * bytes[2] as u32;
+        if d1 < Q {
+            a[count] = d1;
+            count += 1;
+        }
+        if d2 < Q && count < N {
+            a[count] = d2;
+            count += 1;
+        }
+    }
+    a
+}
+
+/// `PRF_η(s, b) = SHAKE256(s ‖ b, 64·η)`.
+fn prf(eta: usize, seed: &[u8; 32], nonce: u8) -> Vec<u8> {
+    let mut xof = Shake256::default();
+    Update::update(&mut xof, seed);
+    Update::update(&mut xof, &[nonce]);
+    let mut output = vec![0u8; 64 * eta];
+    xof.finalize_xof().read(&mut output);
+    output
+}
+
+/// Samples a polynomial from the centered binomial distribution (FIPS 203, Algorithm 8).
+fn sample_cbd(eta: usize, bytes: &[u8]) -> Poly {
+    let bit = |index: usize| ((bytes[index / 8] >> (index % 8)) & 1) as u32;
+    let mut f = [0u32; N];
+    for (i, coefficient) in f.iter_mut().enumerate() {
+        let x: u32 = (0..eta).map(|j| bit(2 * i * eta + j)).sum();
+        let y: u32 = (0..eta).map(|j| bit(2 * i * eta + eta + j)).sum();
+        *coefficient = (x + Q - y) % Q;
+    }
+    f
+}
+
+/// Packs `d`-bit coefficients, least significant bit first (FIPS 203, Algorithm 5).
+fn byte_encode(d: u32, f: &Poly, output: &mut Vec<u8>) {
+    let mut accumulator = 0u64;
+    let mut bits = 0;
+    for &coefficient in f {
+        accumulator |= (coefficient as u64) << bits;
+        bits += d;
+        while bits >= 8 {
+            output.push(accumulator as u8);
+            accumulator >>= 8;
+            bits -= 8;
+        }
+    }
+}
+
+/// Unpacks `d`-bit coefficients (FIPS 203, Algorithm 6). 12-bit values are reduced modulo `q`.
+fn byte_decode(d: u32, bytes: &[u8]) -> Poly {
+    let mut f = [0u32; N];
+    let mut accumulator = 0u64;
+    let mut bits = 0;
+    let mut input = bytes.iter();
+    for coefficient in f.iter_mut() {
+        while bits < d {
+            accumulator |= (*input.next().expect("encoded polynomial is complete") as u64) << bits;
+            bits += 8;
+        }
+        *coefficient = (accumulator & ((1 << d) - 1)) as u32;
+        accumulator >>= d;
+        bits -= d;
+        if d == 12 {
+            *coefficient %= Q;
+        }
+    }
+    f
+}
+
+/// `Compress_d(x) = ⌈(2^d / q)·x⌋ mod 2^d`.
+fn compress(d: u32, f: &Poly) -> Poly {
+    let mut compressed = [0u32; N];
+    for (c, &x) in compressed.iter_mut().zip(f) {
+        *c = ((((x as u64) << (d + 1)) + Q as u64) / (2 * Q as u64)) as u32 & ((1 << d) - 1);
+    }
+    compressed
+}
+
+/// `Decompress_d(y) = ⌈(q / 2^d)·y⌋`.
+fn decompress(d: u32, f: &Poly) -> Poly {
+    let mut decompressed = [0u32; N];
+    for (x, &y) in decompressed.iter_mut().zip(f) {
+        *x = (Q * y + (1 << (d - 1))) >> d;
+    }
+    decompressed
+}
+
+/// `G(c) = SHA3-512(c)`, split into two 32-byte halves.
+fn g(parts: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+    let digest = Sha3_512::digest(parts.concat());
+    let (mut a, mut b) = ([0u8; 32], [0u8; 32]);
+    a.copy_from_slice(&digest[..32]);
+    b.copy_from_slice(&digest[32..]);
+    (a, b)
+}
+
+/// `H(s) = SHA3-256(s)`.
+fn h(data: &[u8]) -> [u8; 32] {
+    Sha3_256::digest(data).into()
+}
+
+/// `J(s) = SHAKE256(s, 32)`.
+fn j(parts: &[&[u8]]) -> [u8; 32] {
+    let mut xof = Shake256::default();
+    for part in parts {
+        Update::update(&mut xof, part);
+    }
+    let mut output = [0u8; 32];
+    xof.finalize_xof().read(&mut output);
+    output
+}
+
+/// Expands the public matrix `Â` from its seed.
+fn matrix(rho: &[u8; 32]) -> [[Poly; K]; K] {
+    let mut a = [[[0u32; N]; K]; K];
+    for (i, row) in a.iter_mut().enumerate() {
+        for (j, entry) in row.iter_mut().enumerate() {
+            *entry = sample_ntt(rho, j as u8, i as u8);
+        }
+    }
+    a
+}
+
+/// Samples a vector of `K` noise polynomials, advancing the PRF counter.
+fn sample_vector(eta: usize, seed: &[u8; 32], counter: &mut u8) -> [Poly; K] {
+    let mut vector = [[0u32; N]; K];
+    for poly in vector.iter_mut() {
+        *poly = sample_cbd(eta, &prf(eta, seed, *counter));
+        *counter += 1;
+    }
+    vector
+}
+
+/// K-PKE key generation (FIPS 203, Algorithm 13), returning `(ek, dk_pke)`.
+fn pke_keygen(d: &[u8; 32]) -> (Vec<u8>, Vec<u8>) {
+    let zetas = zetas();
+    let (rho, sigma) = g(&[d, &[K as u8]]);
+    let a = matrix(&rho);
+
+    let mut counter = 0;
+    let mut s = sample_vector(ETA1, &sigma, &mut counter);
+    let mut e = sample_vector(ETA1, &sigma, &mut counter);
+    s.iter_mut().for_each(|poly| ntt(poly, &zetas));
+    e.iter_mut().for_each(|poly| ntt(poly, &zetas));
+
+    let mut ek = Vec::with_capacity(ENCAPSULATION_KEY_SIZE);
+    for i in 0..K {
+        let mut t = e[i];
+        for j in 0..K {
+            t = add(&t, &multiply_ntts(&a[i][j], &s[j], &zetas));
+        }
+        byte_encode(12, &t, &mut ek);
+    }
+    ek.extend_from_slice(&rho);
+
+    let mut dk = Vec::with_capacity(POLY_BYTES * K);
+    s.iter().for_each(|poly| byte_encode(12, poly, &mut dk));
+    (ek, dk)
+}
+
+/// K-PKE encryption (FIPS 203, Algorithm 14).
+fn pke_encrypt(ek: &[u8], message: &[u8; 32], randomness: &[u8; 32]) -> Vec<u8> {
+    let zetas = zetas();
+    let mut t = [[0u32; N]; K];
+    for (i, poly) in t.iter_mut().enumerate() {
+        *poly = byte_decode(12, &ek[i * POLY_BYTES..(i + 1) * POLY_BYTES]);
+    }
+    let rho: [u8; 32] = ek[POLY_BYTES * K..].try_into().expect("encapsulation key ends with ρ");
+    let a = matrix(&rho);
+
+    let mut counter = 0;
+    let mut y = sample_vector(ETA1, randomness, &mut counter);
+    let e1 = sample_vector(ETA2, randomness, &mut counter);
+    let e2 = sample_cbd(ETA2, &prf(ETA2, randomness, counter));
+    y.iter_mut().for_each(|poly| ntt(poly, &zetas));
+
+    let mut ciphertext = Vec::with_capacity(CIPHERTEXT_SIZE);
+    for i in 0..K {
+        let mut u = [0u32; N];
+        for j in 0..K {
+            u = add(&u, &multiply_ntts(&a[j][i], &y[j], &zetas));
+        }
+        inverse_ntt(&mut u, &zetas);
+        byte_encode(DU, &compress(DU, &add(&u, &e1[i])), &mut ciphertext);
+    }
+
+    let mut v = [0u32; N];
+    for i in 0..K {
+        v = add(&v, &multiply_ntts(&t[i], &y[i], &zetas));
+    }
+    inverse_ntt(&mut v, &zetas);
+    let mu = decompress(1, &byte_decode(1, message));
+    byte_encode(DV, &compress(DV, &add(&add(&v, &e2), &mu)), &mut ciphertext);
+    ciphertext
+}
+
+/// K-PKE decryption (FIPS 203, Algorithm 15).
+fn pke_decrypt(dk: &[u8], ciphertext: &[u8]) -> [u8; 32] {
+    let zetas = zetas();
+    let u_bytes = 32 * DU as usize;
+    let mut w = [0u32; N];
+    for i in 0..K {
+        let mut u = decompress(DU, &byte_decode(DU, &ciphertext[i * u_bytes..(i + 1) * u_bytes]));
+        ntt(&mut u, &zetas);
+        let s = byte_decode(12, &dk[i * POLY_BYTES..(i + 1) * POLY_BYTES]);
+        w = add(&w, &multiply_ntts(&s, &u, &zetas));
+    }
+    inverse_ntt(&mut w, &zetas);
+
+    let v = decompress(DV, &byte_decode(DV, &ciphertext[K * u_bytes..]));
+    let mut difference = [0u32; N];
+    for i in 0..N {
+        difference[i] = (v[i] + Q - w[i]) % Q;
+    }
+    let mut message = Vec::with_capacity(32);
+    byte_encode(1, &compress(1, &difference), &mut message);
+    message.try_into().expect("a 1-bit encoded polynomial is 32 bytes")
+}
+
+/// Defines a fixed-size byte container for an ML-KEM value.
+macro_rules! ml_kem_bytes {
+    ($(#[$meta:meta])* $name:ident, $size:expr, $what:literal) => {
+        $(#[$meta])*
+        #[derive(Clone, PartialEq, Eq)]
+        pub struct $name(Box<[u8; $size]>);
+
+        impl $name {
+            /// Parses the encoded value, checking only its length.
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+                let array: [u8; $size] = bytes
+                    .try_into()
+                    .map_err(|_| CryptoError::InvalidInput(concat!("invalid ML-KEM-768 ", $what, " length")))?;
+                Ok(Self(Box::new(array)))
+            }
+
+            /// Returns the encoded value.
+            pub fn as_bytes(&self) -> &[u8] {
+                self.0.as_slice()
+            }
+        }
+
+        impl std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, concat!(stringify!($name), "({} bytes)"), $size)
+            }
+        }
+    };
+}
+
+ml_kem_bytes!(
+    /// A public ML-KEM-768 encapsulation key.
+    EncapsulationKey, ENCAPSULATION_KEY_SIZE, "encapsulation key"
+);
+ml_kem_bytes!(
+    /// A private ML-KEM-768 decapsulation key.
+    DecapsulationKey, DECAPSULATION_KEY_SIZE, "decapsulation key"
+);
+ml_kem_bytes!(
+    /// An ML-KEM-768 ciphertext.
+    Ciphertext, CIPHERTEXT_SIZE, "ciphertext"
+);
+
+impl Drop for DecapsulationKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for DecapsulationKey {}
+
+impl_serde_via_bytes!(EncapsulationKey, as_bytes);
+impl_serde_via_bytes!(DecapsulationKey, as_bytes);
+impl_serde_via_bytes!(Ciphertext, as_bytes);
+
+impl DecapsulationKey {
+    /// Returns the encapsulation key embedded in this decapsulation key.
+    pub fn encapsulation_key(&self) -> EncapsulationKey {
+        let start = POLY_BYTES * K;
+        EncapsulationKey::from_bytes(&self.0[start..start + ENCAPSULATION_KEY_SIZE]).expect("slice has the key size")
+    }
+}
+
+/// Generates a key pair from the 32-byte seeds `d` and `z` (FIPS 203, Algorithm 16).
+///
+/// This is deterministic; the 64-byte seed `d ‖ z` is the compact private key format used by
+/// other implementations.
+pub fn keygen_from_seed(d: &[u8; 32], z: &[u8; 32]) -> (EncapsulationKey, DecapsulationKey) {
+    let (ek, dk_pke) = pke_keygen(d);
+    let mut dk = Zeroizing::new(dk_pke);
+    dk.extend_from_slice(&ek);
+    dk.extend_from_slice(&h(&ek));
+    dk.extend_from_slice(z);
+    (
+        EncapsulationKey::from_bytes(&ek).expect("generated key has the key size"),
+        DecapsulationKey::from_bytes(&dk).expect("generated key has the key size"),
+    )
+}
+
+/// Generates a random ML-KEM-768 key pair.
+///
+/// # Panics
+/// If the operating system's random number generator is unavailable.
+pub fn keygen() -> (EncapsulationKey, DecapsulationKey) {
+    keygen_with(&mut OsRandom).expect("the operating system RNG is unavailable")
+}
+
+/// Generates a random ML-KEM-768 key pair, drawing its seeds from the given source.
+pub fn keygen_with(rng: &mut impl SecureRandom) -> Result<(EncapsulationKey, DecapsulationKey), CryptoError> {
+    let (mut d, mut z) = (Zeroizing::new([0u8; 32]), Zeroizing::new([0u8; 32]));
+    rng.fill(d.as_mut())?;
+    rng.fill(z.as_mut())?;
+    Ok(keygen_from_seed(&d, &z))
+}
+
+/// Encapsulates with the given 32-byte randomness `m` (FIPS 203, Algorithm 17).
+///
+/// Only meant for reproducing test vectors: reusing `m` reuses the shared secret.
+pub fn encapsulate_deterministic(
+    ek: &EncapsulationKey,
+    m: &[u8; 32],
+) -> Result<(Ciphertext, [u8; SHARED_SECRET_SIZE]), CryptoError> {
+    // Every encoded coefficient must already be reduced modulo q
+    let mut reencoded = Vec::with_capacity(POLY_BYTES * K);
+    for chunk in ek.0[..POLY_BYTES * K].chunks(POLY_BYTES) {
+        byte_encode(12, &byte_decode(12, chunk), &mut reencoded);
+    }
+    if reencoded[..] != ek.0[..POLY_BYTES * K] {
+        return Err(CryptoError::InvalidInput("ML-KEM-768 encapsulation key is not reduced"));
+    }
+
+    let (shared_secret, randomness) = g(&[m, &h(ek.as_bytes())]);
+    let ciphertext = pke_encrypt(ek.as_bytes(), m, &randomness);
+    Ok((Ciphertext::from_bytes(&ciphertext).expect("encryption yields a full ciphertext"), shared_secret))
+}
+
+/// Generates a shared secret and the ciphertext that conveys it to the holder of `ek`.
+///
+/// # Returns
+/// The ciphertext and the 32-byte shared secret, or `CryptoError::InvalidInput` if the key fails
+/// the FIPS 203 modulus check.
+///
+/// # Example
+/// ```
+/// use zana::crypto::post_quantum::ml_kem;
+///
+/// let (ek, dk) = ml_kem::keygen();
+/// let (ciphertext, sender_secret) = ml_kem::encapsulate(&ek).unwrap();
+/// assert_eq!(ml_kem::decapsulate(&dk, &ciphertext).unwrap(), sender_secret);
+/// ```
+pub fn encapsulate(ek: &EncapsulationKey) -> Result<(Ciphertext, [u8; SHARED_SECRET_SIZE]), CryptoError> {
+    encapsulate_with(&mut OsRandom, ek)
+}
+
+/// Generates a shared secret and the ciphertext that conveys it to the holder of `ek`, drawing
+/// the randomness from the given source.
+pub fn encapsulate_with(
+    rng: &mut impl SecureRandom,
+    ek: &EncapsulationKey,
+) -> Result<(Ciphertext, [u8; SHARED_SECRET_SIZE]), CryptoError> {
+    let mut m = Zeroizing::new([0u8; 32]);
+    rng.fill(m.as_mut())?;
+    encapsulate_deterministic(ek, &m)
+}
+
+/// Recovers the shared secret from a ciphertext (FIPS 203, Algorithm 18).
+///
+/// # Returns
+/// The 32-byte shared secret, or `CryptoError::InvalidInput` if the decapsulation key is
+/// inconsistent. A tampered ciphertext is not an error but yields an unrelated secret.
+pub fn decapsulate(dk: &DecapsulationKey, ciphertext: &Ciphertext) -> Result<[u8; SHARED_SECRET_SIZE], CryptoError> {
+    let dk_pke = &dk.0[..POLY_BYTES * K];
+    let ek = &dk.0[POLY_BYTES * K..POLY_BYTES * K + ENCAPSULATION_KEY_SIZE];
+    let hash = &dk.0[POLY_BYTES * K + ENCAPSULATION_KEY_SIZE..DECAPSULATION_KEY_SIZE - 32];
+    let z = &dk.0[DECAPSULATION_KEY_SIZE - 32..];
+    if h(ek) != hash {
+        return Err(CryptoError::InvalidInput("ML-KEM-768 decapsulation key hash check failed"));
+    }
+
+    let m = Zeroizing::new(pke_decrypt(dk_pke, ciphertext.as_bytes()));
+    let (shared_secret, randomness) = g(&[m.as_slice(), hash]);
+    let rejection_secret = j(&[z, ciphertext.as_bytes()]);
+    let reencrypted = pke_encrypt(ek, &m, &randomness);
+
+    // Select the result without branching on whether the ciphertexts match
+    let mismatch = reencrypted
+        .iter()
+        .zip(ciphertext.as_bytes())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+    let mask = ((mismatch as u16).wrapping_sub(1) >> 8) as u8; // 0xff if equal, 0x00 otherwise
+    let mut result = [0u8; SHARED_SECRET_SIZE];
+    for i in 0..SHARED_SECRET_SIZE {
+        result[i] = (shared_secret[i] & mask) | (rejection_secret[i] & !mask);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seed(start: u8) -> [u8; 32] {
+        std::array::from_fn(|i| start.wrapping_add(i as u8))
+    }
+
+    #[test]
+    fn test_sizes_and_round_trip() {
+        let (ek, dk) = keygen();
+        assert_eq!(ek.as_bytes().len(), 1184);
+        assert_eq!(dk.as_bytes().len(), 2400);
+        assert_eq!(dk.encapsulation_key(), ek);
+
+        for _ in 0..10 {
+            let (ciphertext, secret) = encapsulate(&ek).unwrap();
+            assert_eq!(ciphertext.as_bytes().len(), 1088);
+            assert_eq!(decapsulate(&dk, &ciphertext).unwrap(), secret);
+        }
+    }
+
+    #[test]
+    fn test_ntt_round_trip() {
+        let zetas = zetas();
+        let original: Poly = std::array::from_fn(|i| (i as u32 * 37 + 11) % Q);
+        let mut f = original;
+        ntt(&mut f, &zetas);
+        inverse_ntt(&mut f, &zetas);
+        assert_eq!(f, original);
+    }
+
+    /// Vectors cross-checked against the ML-KEM-768 implementation of OpenSSL 3.5: the key pair is
+    /// `openssl genpkey -algorithm ML-KEM-768 -pkeyopt hexseed:<d ‖ z>`, and the shared secret is
+    /// OpenSSL's decapsulation of the ciphertext.
+    #[test]
+    fn test_known_answer() {
+        let (ek, dk) = keygen_from_seed(&seed(0), &seed(32));
+        assert_eq!(hex::encode(h(ek.as_bytes())), KAT_EK_SHA3_256);
+        assert_eq!(hex::encode(h(dk.as_bytes())), KAT_DK_SHA3_256);
+
+        let (ciphertext, secret) = encapsulate_deterministic(&ek, &seed(64)).unwrap();
+        assert_eq!(hex::encode(h(ciphertext.as_bytes())), KAT_CT_SHA3_256);
+        assert_eq!(hex::encode(secret), KAT_SHARED_SECRET);
+        assert_eq!(decapsulate(&dk, &ciphertext).unwrap(), secret);
+    }
+
+    #[test]
+    fn test_implicit_rejection() {
+        let (ek, dk) = keygen_from_seed(&seed(0), &seed(32));
+        let (ciphertext, secret) = encapsulate_deterministic(&ek, &seed(64)).unwrap();
+
+        let mut tampered = ciphertext.as_bytes().to_vec();
+        tampered[0] ^= 1;
+        let tampered = Ciphertext::from_bytes(&tampered).unwrap();
+        let rejected = decapsulate(&dk, &tampered).unwrap();
+        assert_ne!(rejected, secret);
+        assert_eq!(rejected, j(&[&seed(32), tampered.as_bytes()]));
+    }
+
+    #[test]
+    fn test_rejects_invalid_keys() {
+        let (ek, dk) = keygen();
+        let mut unreduced = ek.as_bytes().to_vec();
+        unreduced[0] = 0xff;
+        unreduced[1] |= 0x0f;
+        let unreduced = EncapsulationKey::from_bytes(&unreduced).unwrap();
+        assert!(encapsulate(&unreduced).is_err());
+
+        let mut corrupted = dk.as_bytes().to_vec();
+        corrupted[POLY_BYTES * K] ^= 1;
+        let corrupted = DecapsulationKey::from_bytes(&corrupted).unwrap();
+        let (ciphertext, _) = encapsulate(&ek).unwrap();
+        assert!(decapsulate(&corrupted, &ciphertext).is_err());
+
+        assert!(Ciphertext::from_bytes(&[0u8; 10]).is_err());
+    }
+
+    const KAT_EK_SHA3_256: &str = "a24e16d8f8f9383a95b77050f4d9fd2f5733eec1d63ef3c23ebf9918173669a7";
+    const KAT_DK_SHA3_256: &str = "1149f17c3c4ac6ab1e3e2d9d8bd0171355ac0fa31bb8855c48ceade874c0864b";
+    const KAT_CT_SHA3_256: &str = "b4cfbd24cef67afd3764276c6980e0f88f8e9ca57f59b7f12fe1a9c1e72f4710";
+    const KAT_SHARED_SECRET: &str = "9cddd089ffe70e3996e76f7c8d06746df34d07e8657bc0fcf2bb0e1c3084aea1";
+}
@@ -0,0 +1,719 @@
+//! Post-Quantum Cryptography Utilities
+//!
+//! This module provides cryptographic functions that are resistant to attacks by quantum computers.
+//! It includes implementations of lattice-based cryptography and hash-based cryptography.
+//!
+//! - [`ml_kem`]: ML-KEM-768 key encapsulation (FIPS 203).
+//! - [`hybrid`]: X25519 combined with ML-KEM-768.
+//! - [`wots`] and [`xmss`]: one-time and stateful hash-based signatures.
+//! - Lamport one-time signatures and a Regev-style LWE key encapsulation, defined here.
+//!
+//! For key establishment use the standardized [`ml_kem`] module; the Regev-style LWE scheme
+//! below is a teaching example with toy parameters and offers no security.
+//!
+//! Keys, signatures and ciphertexts are dedicated types with `to_bytes`/`from_bytes` and serde
+//! support (hex strings in human-readable formats, raw bytes otherwise). Secret keys are
+//! zeroized when dropped.
+
+/// Implements `Serialize` and `Deserialize` through a type's byte encoding.
+macro_rules! impl_serde_via_bytes {
+    ($name:ty, $to_bytes:ident) => {
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let bytes: &[u8] = &self.$to_bytes();
+                if serializer.is_human_readable() {
+                    serializer.serialize_str(&hex::encode(bytes))
+                } else {
+                    serializer.serialize_bytes(bytes)
+                }
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let bytes = $crate::crypto::post_quantum::deserialize_bytes(deserializer)?;
+                Self::from_bytes(&bytes).map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+pub mod hybrid;
+pub mod ml_kem;
+pub mod wots;
+pub mod xmss;
+
+use std::fmt;
+use serde::de::{self, Deserializer, Visitor};
+use sha2::{Sha256, Digest};
+use sha3::Shake128;
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+use crate::crypto::error::CryptoError;
+use crate::crypto::random::{random_array, random_bytes, OsRandom, SecureRandom};
+
+/// Reads the byte encoding written by `impl_serde_via_bytes`: a hex string in human-readable
+/// formats, raw bytes otherwise.
+pub(crate) fn deserialize_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    struct BytesVisitor;
+
+    impl<'de> Visitor<'de> for BytesVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a hex string or byte array")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Vec<u8>, E> {
+            hex::decode(value).map_err(E::custom)
+        }
+
+        fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Vec<u8>, E> {
+            Ok(value.to_vec())
+        }
+
+        fn visit_byte_buf<E: de::Error>(self, value: Vec<u8>) -> Result<Vec<u8>, E> {
+            Ok(value)
+        }
+
+        fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(byte) = seq.next_element()? {
+                bytes.push(byte);
+            }
+            Ok(bytes)
+        }
+    }
+
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(BytesVisitor)
+    } else {
+        deserializer.deserialize_byte_buf(BytesVisitor)
+    }
+}
+
+/// Parameters for the Regev-style Learning With Errors (LWE) key encapsulation.
+const MODULUS: u32 = 65536; // Power-of-two modulus, so arithmetic wraps in u16
+const SECRET_BOUND: u32 = 100; // Range of secret coefficients
+const ERROR_BOUND: u32 = 50; // Range of error coefficients
+const LWE_DIMENSION: usize = 128; // Length of each secret column
+const LWE_SAMPLES: usize = 256; // Rows of the public matrix
+const LWE_MESSAGE_BITS: usize = 256; // Bits carried by one ciphertext
+
+/// Generates a secret vector used in the Learning With Errors (LWE) key exchange.
+///
+/// Coefficients are uniform in `-bound/2..bound - bound/2`; bytes at or above the largest
+/// multiple of `bound` are redrawn so that the reduction is unbiased.
+fn generate_secret_vector(rng: &mut impl SecureRandom, size: usize, bound: u32) -> Result<Vec<i32>, CryptoError> {
+    let limit = 256 - 256 % bound;
+    let mut vector = Vec::with_capacity(size);
+    while vector.len() < size {
+        let bytes = random_bytes(rng, size - vector.len())?;
+        vector.extend(
+            bytes.into_iter().map(u32::from).filter(|&b| b < limit).map(|b| (b % bound) as i32 - (bound as i32) / 2),
+        );
+    }
+    Ok(vector)
+}
+
+/// Expands the uniform `LWE_SAMPLES × LWE_DIMENSION` public matrix from its seed, row by row.
+fn lwe_matrix(seed: &[u8; 32]) -> Vec<u16> {
+    let mut xof = Shake128::default();
+    Update::update(&mut xof, seed);
+    let mut bytes = vec![0u8; 2 * LWE_SAMPLES * LWE_DIMENSION];
+    xof.finalize_xof().read(&mut bytes);
+    coefficients_from_bytes(&bytes)
+}
+
+/// The size of a serialized LWE public key.
+pub const LWE_PUBLIC_KEY_SIZE: usize = 32 + 2 * LWE_SAMPLES * LWE_MESSAGE_BITS;
+/// The size of a serialized LWE secret key.
+pub const LWE_SECRET_KEY_SIZE: usize = 2 * LWE_DIMENSION * LWE_MESSAGE_BITS;
+/// The size of a serialized LWE ciphertext.
+pub const LWE_CIPHERTEXT_SIZE: usize = 2 * (LWE_DIMENSION + LWE_MESSAGE_BITS);
+
+fn coefficients_to_bytes(coefficients: &[u16]) -> Vec<u8> {
+    coefficients.iter().flat_map(|c| c.to_le_bytes()).collect()
+}
+
+fn coefficients_from_bytes(bytes: &[u8]) -> Vec<u16> {
+    bytes.chunks(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect()
+}
+
+/// An LWE public key: the seed of the matrix `A` and `B = A·S + E`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LwePublicKey {
+    seed: [u8; 32],
+    b: Vec<u16>,
+}
+
+impl LwePublicKey {
+    /// Serializes the key as `seed ‖ B`, with little-endian coefficients.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.seed.to_vec(), coefficients_to_bytes(&self.b)].concat()
+    }
+
+    /// Deserializes a key produced by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.len() != LWE_PUBLIC_KEY_SIZE {
+            return Err(CryptoError::InvalidKeyLength { expected: LWE_PUBLIC_KEY_SIZE, actual: bytes.len() });
+        }
+        Ok(Self { seed: bytes[..32].try_into().expect("32 bytes"), b: coefficients_from_bytes(&bytes[32..]) })
+    }
+}
+
+/// An LWE secret key: the small `LWE_DIMENSION × LWE_MESSAGE_BITS` matrix `S`.
+#[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct LweSecretKey {
+    s: Vec<u16>,
+}
+
+impl LweSecretKey {
+    /// Serializes the key as the little-endian coefficients of `S`.
+    pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(coefficients_to_bytes(&self.s))
+    }
+
+    /// Deserializes a key produced by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.len() != LWE_SECRET_KEY_SIZE {
+            return Err(CryptoError::InvalidKeyLength { expected: LWE_SECRET_KEY_SIZE, actual: bytes.len() });
+        }
+        Ok(Self { s: coefficients_from_bytes(bytes) })
+    }
+}
+
+impl fmt::Debug for LweSecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LweSecretKey").finish_non_exhaustive()
+    }
+}
+
+/// An LWE ciphertext: `u = Aᵀ·r` and `v = Bᵀ·r + ⌊q/2⌋·m`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LweCiphertext {
+    u: Vec<u16>,
+    v: Vec<u16>,
+}
+
+impl LweCiphertext {
+    /// Serializes the ciphertext as the little-endian coefficients of `u` followed by `v`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [coefficients_to_bytes(&self.u), coefficients_to_bytes(&self.v)].concat()
+    }
+
+    /// Deserializes a ciphertext produced by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.len() != LWE_CIPHERTEXT_SIZE {
+            return Err(CryptoError::InvalidInput("invalid LWE ciphertext length"));
+        }
+        let (u, v) = bytes.split_at(2 * LWE_DIMENSION);
+        Ok(Self { u: coefficients_from_bytes(u), v: coefficients_from_bytes(v) })
+    }
+}
+
+impl_serde_via_bytes!(LwePublicKey, to_bytes);
+impl_serde_via_bytes!(LweSecretKey, to_bytes);
+impl_serde_via_bytes!(LweCiphertext, to_bytes);
+
+/// LWE Key Exchange: Generates a key pair.
+///
+/// Each of the `LWE_MESSAGE_BITS` columns of the secret matrix `S` is an independent Regev key
+/// over the shared public matrix `A`, so one ciphertext carries a full 256-bit message.
+///
+/// These parameters are far too small to be secure; use [`ml_kem`] for real key exchange.
+///
+/// # Returns
+/// A tuple containing the public key and secret key.
+///
+/// # Panics
+/// If the operating system's random number generator is unavailable.
+pub fn lwe_generate_keypair() -> (LwePublicKey, LweSecretKey) {
+    lwe_generate_keypair_with(&mut OsRandom).expect("the operating system RNG is unavailable")
+}
+
+/// LWE Key Exchange: Generates a key pair from the given source.
+pub fn lwe_generate_keypair_with(rng: &mut impl SecureRandom) -> Result<(LwePublicKey, LweSecretKey), CryptoError> {
+    let seed = random_array(rng)?;
+    let a = lwe_matrix(&seed);
+    let s: Vec<u16> = generate_secret_vector(rng, LWE_DIMENSION * LWE_MESSAGE_BITS, SECRET_BOUND)?
+        .into_iter()
+        .map(|c| c as u16)
+        .collect();
+    let e = generate_secret_vector(rng, LWE_SAMPLES * LWE_MESSAGE_BITS, ERROR_BOUND)?;
+
+    let mut b: Vec<u16> = e.into_iter().map(|c| c as u16).collect();
+    for i in 0..LWE_SAMPLES {
+        let row = &mut b[i * LWE_MESSAGE_BITS..(i + 1) * LWE_MESSAGE_BITS];
+        for j in 0..LWE_DIMENSION {
+            let a_ij = a[i * LWE_DIMENSION + j];
+            let s_row = &s[j * LWE_MESSAGE_BITS..(j + 1) * LWE_MESSAGE_BITS];
+            for (b_ik, s_jk) in row.iter_mut().zip(s_row) {
+                *b_ik = b_ik.wrapping_add(a_ij.wrapping_mul(*s_jk));
+            }
+        }
+    }
+    Ok((LwePublicKey { seed, b }, LweSecretKey { s }))
+}
+
+/// LWE Encryption: Encrypts a 256-bit message.
+///
+/// A random subset `r` of the public samples is summed; the message bits are added at `⌊q/2⌋`.
+/// The accumulated error is at most `LWE_SAMPLES · ERROR_BOUND / 2 = 6400`, below `q/4`, so
+/// decryption always recovers every bit.
+///
+/// # Panics
+/// If the operating system's random number generator is unavailable.
+pub fn lwe_encrypt(public_key: &LwePublicKey, message: &[u8; 32]) -> LweCiphertext {
+    lwe_encrypt_with(&mut OsRandom, public_key, message).expect("the operating system RNG is unavailable")
+}
+
+/// LWE Encryption: Encrypts a 256-bit message, drawing the subset of samples from the given
+/// source.
+pub fn lwe_encrypt_with(
+    rng: &mut impl SecureRandom,
+    public_key: &LwePublicKey,
+    message: &[u8; 32],
+) -> Result<LweCiphertext, CryptoError> {
+    let subset: [u8; LWE_SAMPLES / 8] = random_array(rng)?;
+    let a = lwe_matrix(&public_key.seed);
+    let mut u = vec![0u16; LWE_DIMENSION];
+    let mut v: Vec<u16> = (0..LWE_MESSAGE_BITS)
+        .map(|k| ((message[k / 8] >> (k % 8)) & 1) as u16 * (MODULUS / 2) as u16)
+        .collect();
+
+    for i in 0..LWE_SAMPLES {
+        if (subset[i / 8] >> (i % 8)) & 1 == 0 {
+            continue;
+        }
+        let a_row = &a[i * LWE_DIMENSION..(i + 1) * LWE_DIMENSION];
+        u.iter_mut().zip(a_row).for_each(|(u_j, a_ij)| *u_j = u_j.wrapping_add(*a_ij));
+        let b_row = &public_key.b[i * LWE_MESSAGE_BITS..(i + 1) * LWE_MESSAGE_BITS];
+        v.iter_mut().zip(b_row).for_each(|(v_k, b_ik)| *v_k = v_k.wrapping_add(*b_ik));
+    }
+    Ok(LweCiphertext { u, v })
+}
+
+/// LWE Decryption: Recovers the message by rounding `v − Sᵀ·u = Eᵀ·r + ⌊q/2⌋·m`.
+pub fn lwe_decrypt(secret_key: &LweSecretKey, ciphertext: &LweCiphertext) -> [u8; 32] {
+    let mut noisy = ciphertext.v.clone();
+    for (j, u_j) in ciphertext.u.iter().enumerate() {
+        let s_row = &secret_key.s[j * LWE_MESSAGE_BITS..(j + 1) * LWE_MESSAGE_BITS];
+        noisy.iter_mut().zip(s_row).for_each(|(d_k, s_jk)| *d_k = d_k.wrapping_sub(u_j.wrapping_mul(*s_jk)));
+    }
+
+    // Values nearer to q/2 than to 0 decode as 1
+    let quarter = (MODULUS / 4) as u16;
+    let mut message = [0u8; 32];
+    for (k, d_k) in noisy.iter().enumerate() {
+        if d_k.wrapping_sub(quarter) < 2 * quarter {
+            message[k / 8] |= 1 << (k % 8);
+        }
+    }
+    message
+}
+
+fn lwe_shared_secret(message: &[u8; 32], ciphertext: &LweCiphertext) -> [u8; 32] {
+    Sha256::new().chain_update(message).chain_update(ciphertext.to_bytes()).finalize().into()
+}
+
+/// LWE Key Exchange: Encapsulates a fresh shared secret to the holder of `public_key`.
+///
+/// # Returns
+/// The ciphertext to send and the 32-byte shared secret, the SHA-256 hash of a random message
+/// and the ciphertext carrying it.
+///
+/// # Panics
+/// If the operating system's random number generator is unavailable.
+pub fn lwe_encapsulate(public_key: &LwePublicKey) -> (LweCiphertext, [u8; 32]) {
+    lwe_encapsulate_with(&mut OsRandom, public_key).expect("the operating system RNG is unavailable")
+}
+
+/// LWE Key Exchange: Encapsulates a fresh shared secret, drawing the message and the encryption
+/// randomness from the given source.
+pub fn lwe_encapsulate_with(
+    rng: &mut impl SecureRandom,
+    public_key: &LwePublicKey,
+) -> Result<(LweCiphertext, [u8; 32]), CryptoError> {
+    let message = random_array(rng)?;
+    let ciphertext = lwe_encrypt_with(rng, public_key, &message)?;
+    let shared_secret = lwe_shared_secret(&message, &ciphertext);
+    Ok((ciphertext, shared_secret))
+}
+
+/// LWE Key Exchange: Recovers the shared secret from a ciphertext.
+pub fn lwe_decapsulate(secret_key: &LweSecretKey, ciphertext: &LweCiphertext) -> [u8; 32] {
+    lwe_shared_secret(&lwe_decrypt(secret_key, ciphertext), ciphertext)
+}
+
+/// The number of message-hash bits signed by a Lamport key.
+const LAMPORT_BITS: usize = 256;
+
+/// The size of each Lamport secret and public value.
+const LAMPORT_VALUE_SIZE: usize = 32;
+
+/// The size of a serialized Lamport public key: two hashes per message-hash bit.
+pub const LAMPORT_PUBLIC_KEY_SIZE: usize = LAMPORT_BITS * 2 * LAMPORT_VALUE_SIZE;
+
+/// The size of a serialized Lamport private key: a usage flag followed by two secrets per bit.
+pub const LAMPORT_PRIVATE_KEY_SIZE: usize = 1 + LAMPORT_PUBLIC_KEY_SIZE;
+
+/// The size of a serialized Lamport signature: one revealed secret per bit.
+pub const LAMPORT_SIGNATURE_SIZE: usize = LAMPORT_BITS * LAMPORT_VALUE_SIZE;
+
+/// One pair of values per message-hash bit, selected by the bit's value.
+type LamportPairs = Vec<[[u8; LAMPORT_VALUE_SIZE]; 2]>;
+
+/// Returns bit `i` of a SHA-256 digest, most significant bit of each byte first.
+fn hash_bit(hash: &[u8], i: usize) -> usize {
+    ((hash[i / 8] >> (7 - i % 8)) & 1) as usize
+}
+
+fn pairs_to_bytes(pairs: &LamportPairs, bytes: &mut Vec<u8>) {
+    for pair in pairs {
+        bytes.extend_from_slice(&pair[0]);
+        bytes.extend_from_slice(&pair[1]);
+    }
+}
+
+fn pairs_from_bytes(bytes: &[u8]) -> LamportPairs {
+    bytes
+        .chunks(2 * LAMPORT_VALUE_SIZE)
+        .map(|chunk| {
+            let (zero, one) = chunk.split_at(LAMPORT_VALUE_SIZE);
+            [zero.try_into().expect("chunk holds two values"), one.try_into().expect("chunk holds two values")]
+        })
+        .collect()
+}
+
+/// A one-time Lamport signing key.
+///
+/// Every signature reveals half of the secrets, so a key must sign at most one message. The key
+/// records when it has been used and erases its secrets, and the usage flag is part of its
+/// serialized form.
+#[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct LamportPrivateKey {
+    pairs: LamportPairs,
+    used: bool,
+}
+
+impl LamportPrivateKey {
+    /// Returns `true` once the key has signed a message.
+    pub fn is_used(&self) -> bool {
+        self.used
+    }
+
+    /// Serializes the key as a usage flag byte followed by the secrets.
+    pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        let mut bytes = Zeroizing::new(Vec::with_capacity(LAMPORT_PRIVATE_KEY_SIZE));
+        bytes.push(self.used as u8);
+        pairs_to_bytes(&self.pairs, &mut bytes);
+        bytes
+    }
+
+    /// Deserializes a key produced by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.len() != LAMPORT_PRIVATE_KEY_SIZE {
+            return Err(CryptoError::InvalidKeyLength { expected: LAMPORT_PRIVATE_KEY_SIZE, actual: bytes.len() });
+        }
+        let used = match bytes[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(CryptoError::InvalidInput("invalid Lamport key usage flag")),
+        };
+        Ok(Self { pairs: pairs_from_bytes(&bytes[1..]), used })
+    }
+}
+
+impl fmt::Debug for LamportPrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LamportPrivateKey").field("used", &self.used).finish_non_exhaustive()
+    }
+}
+
+/// A Lamport verification key: the SHA-256 hashes of the private key's secrets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LamportPublicKey {
+    pairs: LamportPairs,
+}
+
+impl LamportPublicKey {
+    /// Serializes the key as the concatenated hash pairs.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(LAMPORT_PUBLIC_KEY_SIZE);
+        pairs_to_bytes(&self.pairs, &mut bytes);
+        bytes
+    }
+
+    /// Deserializes a key produced by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.len() != LAMPORT_PUBLIC_KEY_SIZE {
+            return Err(CryptoError::InvalidKeyLength { expected: LAMPORT_PUBLIC_KEY_SIZE, actual: bytes.len() });
+        }
+        Ok(Self { pairs: pairs_from_bytes(bytes) })
+    }
+}
+
+/// A Lamport signature: the secret revealed for each bit of the message hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LamportSignature {
+    values: Vec<[u8; LAMPORT_VALUE_SIZE]>,
+}
+
+impl LamportSignature {
+    /// Serializes the signature as the concatenated revealed secrets.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.values.concat()
+    }
+
+    /// Deserializes a signature produced by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.len() != LAMPORT_SIGNATURE_SIZE {
+            return Err(CryptoError::InvalidInput("invalid Lamport signature length"));
+        }
+        let values = bytes
+            .chunks(LAMPORT_VALUE_SIZE)
+            .map(|chunk| chunk.try_into().expect("chunk holds one value"))
+            .collect();
+        Ok(Self { values })
+    }
+}
+
+impl_serde_via_bytes!(LamportPrivateKey, to_bytes);
+impl_serde_via_bytes!(LamportPublicKey, to_bytes);
+impl_serde_via_bytes!(LamportSignature, to_bytes);
+
+/// Lamport Signature Scheme: Generates private and public keys.
+///
+/// # Returns
+/// A tuple containing the private key and public key.
+///
+/// # Panics
+/// If the operating system's random number generator is unavailable.
+pub fn lamport_generate_keypair() -> (LamportPrivateKey, LamportPublicKey) {
+    lamport_generate_keypair_with(&mut OsRandom).expect("the operating system RNG is unavailable")
+}
+
+/// Lamport Signature Scheme: Generates private and public keys from the given source.
+pub fn lamport_generate_keypair_with(
+    rng: &mut impl SecureRandom,
+) -> Result<(LamportPrivateKey, LamportPublicKey), CryptoError> {
+    let pairs: LamportPairs =
+        (0..LAMPORT_BITS).map(|_| Ok([random_array(rng)?, random_array(rng)?])).collect::<Result<_, CryptoError>>()?;
+    let public_pairs = pairs
+        .iter()
+        .map(|pair| [Sha256::digest(pair[0]).into(), Sha256::digest(pair[1]).into()])
+        .collect();
+
+    Ok((LamportPrivateKey { pairs, used: false }, LamportPublicKey { pairs: public_pairs }))
+}
+
+/// Lamport Signature Scheme: Signs a message.
+///
+/// Reveals one secret per bit of the message's SHA-256 hash, then marks the key as used and
+/// erases its secrets.
+///
+/// # Arguments
+/// - `message` - The message to be signed.
+/// - `private_key` - The private key for signing.
+///
+/// # Returns
+/// The signature, or `CryptoError::KeyExhausted` if the key has already signed a message.
+pub fn lamport_sign(message: &[u8], private_key: &mut LamportPrivateKey) -> Result<LamportSignature, CryptoError> {
+    if private_key.used {
+        return Err(CryptoError::KeyExhausted);
+    }
+    let hash = Sha256::digest(message);
+    let values = (0..LAMPORT_BITS)
+        .map(|i| private_key.pairs[i][hash_bit(&hash, i)])
+        .collect();
+
+    private_key.used = true;
+    private_key.pairs.iter_mut().for_each(|pair| *pair = [[0; LAMPORT_VALUE_SIZE]; 2]);
+    Ok(LamportSignature { values })
+}
+
+/// Lamport Signature Scheme: Verifies a signature.
+///
+/// # Arguments
+/// - `message` - The original message.
+/// - `signature` - The signature to verify.
+/// - `public_key` - The public key.
+///
+/// # Returns
+/// `true` if every revealed secret hashes to the public value selected by its message-hash bit.
+pub fn lamport_verify(message: &[u8], signature: &LamportSignature, public_key: &LamportPublicKey) -> bool {
+    let hash = Sha256::digest(message);
+    signature.values.len() == LAMPORT_BITS
+        && public_key.pairs.len() == LAMPORT_BITS
+        && (0..LAMPORT_BITS).all(|i| {
+            Sha256::digest(signature.values[i]).as_slice() == public_key.pairs[i][hash_bit(&hash, i)]
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_secret_vector() {
+        let vector = generate_secret_vector(&mut OsRandom, 10, 100).unwrap();
+        assert_eq!(vector.len(), 10);
+        assert!(vector.iter().all(|v| (-50..50).contains(v)));
+    }
+
+    #[test]
+    fn test_keys_from_a_given_source() {
+        use crate::crypto::random::SeededRandom;
+
+        let lwe = |seed| lwe_generate_keypair_with(&mut SeededRandom::from_u64(seed)).unwrap().0.to_bytes();
+        assert_eq!(lwe(1), lwe(1));
+        assert_ne!(lwe(1), lwe(2));
+        let lamport = |seed| lamport_generate_keypair_with(&mut SeededRandom::from_u64(seed)).unwrap().1.to_bytes();
+        assert_eq!(lamport(1), lamport(1));
+
+        let (public_key, secret_key) = lwe_generate_keypair();
+        let (ciphertext, shared) = lwe_encapsulate_with(&mut SeededRandom::from_u64(3), &public_key).unwrap();
+        assert_eq!(lwe_decapsulate(&secret_key, &ciphertext), shared);
+
+        struct Failing;
+        impl SecureRandom for Failing {
+            fn fill(&mut self, _: &mut [u8]) -> Result<(), CryptoError> {
+                Err(CryptoError::KeyGenerationFailed)
+            }
+        }
+        assert!(lwe_generate_keypair_with(&mut Failing).is_err());
+        assert!(lwe_encapsulate_with(&mut Failing, &public_key).is_err());
+        assert!(lamport_generate_keypair_with(&mut Failing).is_err());
+    }
+
+    #[test]
+    fn test_lwe_generate_keypair() {
+        let (public_key, secret_key) = lwe_generate_keypair();
+        assert_eq!(public_key.b.len(), LWE_SAMPLES * LWE_MESSAGE_BITS);
+        assert_eq!(secret_key.s.len(), LWE_DIMENSION * LWE_MESSAGE_BITS);
+        let half = SECRET_BOUND as i16 / 2;
+        assert!(secret_key.s.iter().all(|&c| (-half..half).contains(&(c as i16))));
+        assert_eq!(lwe_matrix(&public_key.seed), lwe_matrix(&public_key.seed));
+    }
+
+    #[test]
+    fn test_lwe_encrypt_decrypt() {
+        let (public_key, secret_key) = lwe_generate_keypair();
+        for message in [[0u8; 32], [0xff; 32], std::array::from_fn(|i| (i as u8).wrapping_mul(37))] {
+            let ciphertext = lwe_encrypt(&public_key, &message);
+            assert_eq!(ciphertext.to_bytes().len(), 2 * (LWE_DIMENSION + LWE_MESSAGE_BITS));
+            assert_eq!(lwe_decrypt(&secret_key, &ciphertext), message);
+        }
+    }
+
+    #[test]
+    fn test_lwe_shared_secrets_match() {
+        let (alice_public, alice_secret) = lwe_generate_keypair();
+        for _ in 0..20 {
+            let (ciphertext, bob_shared) = lwe_encapsulate(&alice_public);
+            assert_eq!(lwe_decapsulate(&alice_secret, &ciphertext), bob_shared);
+        }
+
+        let (ciphertext, bob_shared) = lwe_encapsulate(&alice_public);
+        let (_, eve_secret) = lwe_generate_keypair();
+        assert_ne!(lwe_decapsulate(&eve_secret, &ciphertext), bob_shared);
+    }
+
+    #[test]
+    fn test_lamport_sign_and_verify() {
+        let (mut private_key, public_key) = lamport_generate_keypair();
+        let signature = lamport_sign(b"Test message", &mut private_key).unwrap();
+
+        assert!(lamport_verify(b"Test message", &signature, &public_key));
+        assert!(!lamport_verify(b"Other message", &signature, &public_key));
+
+        let (_, other_public_key) = lamport_generate_keypair();
+        assert!(!lamport_verify(b"Test message", &signature, &other_public_key));
+    }
+
+    #[test]
+    fn test_lamport_signature_covers_every_hash_bit() {
+        let (mut private_key, public_key) = lamport_generate_keypair();
+        let secrets = private_key.clone();
+        let signature = lamport_sign(b"Test message", &mut private_key).unwrap();
+
+        let hash = Sha256::digest(b"Test message");
+        for i in 0..LAMPORT_BITS {
+            assert_eq!(signature.values[i], secrets.pairs[i][hash_bit(&hash, i)], "bit {}", i);
+
+            let mut forged = signature.clone();
+            forged.values[i] = secrets.pairs[i][1 - hash_bit(&hash, i)];
+            assert!(!lamport_verify(b"Test message", &forged, &public_key), "bit {}", i);
+        }
+    }
+
+    #[test]
+    fn test_lamport_key_signs_only_once() {
+        let (mut private_key, _) = lamport_generate_keypair();
+        assert!(!private_key.is_used());
+        lamport_sign(b"first", &mut private_key).unwrap();
+        assert!(private_key.is_used());
+        assert_eq!(lamport_sign(b"second", &mut private_key), Err(CryptoError::KeyExhausted));
+
+        // The usage flag survives serialization
+        let mut restored = LamportPrivateKey::from_bytes(&private_key.to_bytes()).unwrap();
+        assert_eq!(lamport_sign(b"second", &mut restored), Err(CryptoError::KeyExhausted));
+    }
+
+    #[test]
+    fn test_lamport_serialization_round_trip() {
+        let (mut private_key, public_key) = lamport_generate_keypair();
+        assert_eq!(private_key.to_bytes().len(), LAMPORT_PRIVATE_KEY_SIZE);
+        let mut restored = LamportPrivateKey::from_bytes(&private_key.to_bytes()).unwrap();
+        assert_eq!(restored, private_key);
+
+        let public_key = LamportPublicKey::from_bytes(&public_key.to_bytes()).unwrap();
+        let signature = lamport_sign(b"message", &mut restored).unwrap();
+        let signature = LamportSignature::from_bytes(&signature.to_bytes()).unwrap();
+        assert!(lamport_verify(b"message", &signature, &public_key));
+        assert!(lamport_sign(b"message", &mut private_key).is_ok());
+
+        assert!(LamportPublicKey::from_bytes(&[0u8; 10]).is_err());
+        assert!(LamportSignature::from_bytes(&[0u8; 10]).is_err());
+        let mut bad_flag = restored.to_bytes();
+        bad_flag[0] = 2;
+        assert!(LamportPrivateKey::from_bytes(&bad_flag).is_err());
+    }
+
+    #[test]
+    fn test_lwe_serialization_round_trip() {
+        let (public_key, secret_key) = lwe_generate_keypair();
+        let public_key = LwePublicKey::from_bytes(&public_key.to_bytes()).unwrap();
+        let secret_key = LweSecretKey::from_bytes(&secret_key.to_bytes()).unwrap();
+
+        let (ciphertext, shared_secret) = lwe_encapsulate(&public_key);
+        let ciphertext = LweCiphertext::from_bytes(&ciphertext.to_bytes()).unwrap();
+        assert_eq!(lwe_decapsulate(&secret_key, &ciphertext), shared_secret);
+        assert!(LweCiphertext::from_bytes(&[0u8; 10]).is_err());
+        assert!(LwePublicKey::from_bytes(&[0u8; 10]).is_err());
+    }
+
+    #[test]
+    fn test_serde_encodes_hex_strings() {
+        let (mut private_key, public_key) = lamport_generate_keypair();
+        let json = serde_json::to_string(&public_key).unwrap();
+        assert_eq!(json, format!("\"{}\"", hex::encode(public_key.to_bytes())));
+        assert_eq!(serde_json::from_str::<LamportPublicKey>(&json).unwrap(), public_key);
+
+        let signature = lamport_sign(b"message", &mut private_key).unwrap();
+        let signature: LamportSignature = serde_json::from_str(&serde_json::to_string(&signature).unwrap()).unwrap();
+        assert!(lamport_verify(b"message", &signature, &public_key));
+        let restored: LamportPrivateKey = serde_json::from_value(serde_json::to_value(&private_key).unwrap()).unwrap();
+        assert!(restored.is_used());
+
+        let (ek, dk) = ml_kem::keygen();
+        let ek: ml_kem::EncapsulationKey = serde_json::from_str(&serde_json::to_string(&ek).unwrap()).unwrap();
+        let dk: ml_kem::DecapsulationKey = serde_json::from_str(&serde_json::to_string(&dk).unwrap()).unwrap();
+        let (ciphertext, shared_secret) = ml_kem::encapsulate(&ek).unwrap();
+        assert_eq!(ml_kem::decapsulate(&dk, &ciphertext).unwrap(), shared_secret);
+
+        assert!(serde_json::from_str::<LamportSignature>("\"00\"").is_err());
+        assert!(serde_json::from_str::<LamportSignature>("\"not hex\"").is_err());
+    }
+}
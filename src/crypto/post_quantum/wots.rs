@@ -0,0 +1,337 @@
+//! Winternitz One-Time Signatures (WOTS+)
+//!
+//! A hash-based one-time signature with Winternitz parameter `w = 16` over SHA-256. The 256-bit
+//! message digest is split into 64 base-16 digits plus a 3-digit checksum, and each of the 67
+//! digits selects how far along a hash chain the signature reveals. The checksum makes it
+//! impossible to advance one chain without moving another one backwards.
+//!
+//! Every hash is tweaked with a public seed and the address of the call (leaf, chain and step), in
+//! the style of SPHINCS+ simple tweakable hashes. Secrets are derived from a 32-byte seed, so a
+//! key is only 64 bytes. This construction is self-contained and not wire-compatible with
+//! RFC 8391.
+//!
+//! Like [Lamport keys](super::LamportPrivateKey), a WOTS+ key must sign at most one message;
+//! [`xmss`](super::xmss) combines many of them into a reusable stateful key.
+
+use sha2::{Digest, Sha256};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+use crate::crypto::error::CryptoError;
+use crate::crypto::random::{OsRandom, SecureRandom};
+
+/// The size of seeds, chain values and digests.
+pub const HASH_SIZE: usize = 32;
+/// The Winternitz parameter: each chain has `W - 1` steps.
+const W: u32 = 16;
+/// The number of message digits.
+const LEN1: usize = 64;
+/// The number of checksum digits.
+const LEN2: usize = 3;
+/// The number of hash chains.
+pub const CHAINS: usize = LEN1 + LEN2;
+
+/// The size of a serialized signature.
+pub const SIGNATURE_SIZE: usize = CHAINS * HASH_SIZE;
+/// The size of a serialized public key: the public seed and the compressed chain ends.
+pub const PUBLIC_KEY_SIZE: usize = 2 * HASH_SIZE;
+/// The size of a serialized private key: a usage flag and the secret and public seeds.
+pub const PRIVATE_KEY_SIZE: usize = 1 + 2 * HASH_SIZE;
+
+/// The purpose of a tweaked hash call.
+#[derive(Clone, Copy)]
+#[repr(u32)]
+pub(crate) enum HashKind {
+    Secret = 0,
+    Chain = 1,
+    PublicKey = 2,
+    Message = 3,
+}
+
+/// Hashes `data` under the public seed and the address `(kind, leaf, chain, step)`.
+pub(crate) fn tweak_hash(
+    seed: &[u8; HASH_SIZE],
+    kind: HashKind,
+    leaf: u32,
+    chain: u32,
+    step: u32,
+    data: &[&[u8]],
+) -> [u8; HASH_SIZE] {
+    let mut hasher = Sha256::new();
+    hasher.update(seed);
+    for word in [kind as u32, leaf, chain, step] {
+        hasher.update(word.to_be_bytes());
+    }
+    for part in data {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+/// Splits a digest into its 64 base-16 digits followed by the 3 checksum digits.
+fn digits(digest: &[u8; HASH_SIZE]) -> [u32; CHAINS] {
+    let mut digits = [0u32; CHAINS];
+    for (i, byte) in digest.iter().enumerate() {
+        digits[2 * i] = (byte >> 4) as u32;
+        digits[2 * i + 1] = (byte & 0x0f) as u32;
+    }
+    let checksum: u32 = digits[..LEN1].iter().map(|digit| W - 1 - digit).sum();
+    for i in 0..LEN2 {
+        digits[LEN1 + i] = (checksum >> (4 * (LEN2 - 1 - i))) & 0x0f;
+    }
+    digits
+}
+
+/// Advances `value` from position `start` along chain `chain` by `steps`.
+fn chain(
+    public_seed: &[u8; HASH_SIZE],
+    leaf: u32,
+    chain: u32,
+    mut value: [u8; HASH_SIZE],
+    start: u32,
+    steps: u32,
+) -> [u8; HASH_SIZE] {
+    for step in start..start + steps {
+        value = tweak_hash(public_seed, HashKind::Chain, leaf, chain, step, &[&value]);
+    }
+    value
+}
+
+fn secret(secret_seed: &[u8; HASH_SIZE], leaf: u32, index: u32) -> [u8; HASH_SIZE] {
+    tweak_hash(secret_seed, HashKind::Secret, leaf, index, 0, &[])
+}
+
+/// Compresses the chain ends of the key at `leaf` into one hash.
+fn compress(public_seed: &[u8; HASH_SIZE], leaf: u32, ends: &[[u8; HASH_SIZE]]) -> [u8; HASH_SIZE] {
+    tweak_hash(public_seed, HashKind::PublicKey, leaf, 0, 0, &[&ends.concat()])
+}
+
+/// Derives the compressed public key of the one-time key at `leaf`.
+pub(crate) fn public_key_at(secret_seed: &[u8; HASH_SIZE], public_seed: &[u8; HASH_SIZE], leaf: u32) -> [u8; HASH_SIZE] {
+    let ends: Vec<[u8; HASH_SIZE]> = (0..CHAINS as u32)
+        .map(|i| chain(public_seed, leaf, i, secret(secret_seed, leaf, i), 0, W - 1))
+        .collect();
+    compress(public_seed, leaf, &ends)
+}
+
+/// Signs a digest with the one-time key at `leaf`.
+pub(crate) fn sign_at(
+    secret_seed: &[u8; HASH_SIZE],
+    public_seed: &[u8; HASH_SIZE],
+    leaf: u32,
+    digest: &[u8; HASH_SIZE],
+) -> Vec<[u8; HASH_SIZE]> {
+    digits(digest)
+        .iter()
+        .zip(0..)
+        .map(|(&digit, i)| chain(public_seed, leaf, i, secret(secret_seed, leaf, i), 0, digit))
+        .collect()
+}
+
+/// Recomputes the compressed public key of the key at `leaf` from a signature.
+pub(crate) fn public_key_from_signature(
+    public_seed: &[u8; HASH_SIZE],
+    leaf: u32,
+    digest: &[u8; HASH_SIZE],
+    signature: &[[u8; HASH_SIZE]],
+) -> [u8; HASH_SIZE] {
+    let ends: Vec<[u8; HASH_SIZE]> = digits(digest)
+        .iter()
+        .zip(signature)
+        .zip(0..)
+        .map(|((&digit, value), i)| chain(public_seed, leaf, i, *value, digit, W - 1 - digit))
+        .collect();
+    compress(public_seed, leaf, &ends)
+}
+
+/// A one-time WOTS+ signing key.
+///
+/// The key records when it has been used, and the usage flag is part of its serialized form.
+#[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct WotsPrivateKey {
+    secret_seed: [u8; HASH_SIZE],
+    public_seed: [u8; HASH_SIZE],
+    used: bool,
+}
+
+impl WotsPrivateKey {
+    /// Generates a random key.
+    ///
+    /// # Panics
+    /// If the operating system's random number generator is unavailable.
+    pub fn generate() -> Self {
+        Self::generate_with(&mut OsRandom).expect("the operating system RNG is unavailable")
+    }
+
+    /// Generates a random key, drawing its seeds from the given source.
+    pub fn generate_with(rng: &mut impl SecureRandom) -> Result<Self, CryptoError> {
+        let mut key = Self { secret_seed: [0; HASH_SIZE], public_seed: [0; HASH_SIZE], used: false };
+        rng.fill(&mut key.secret_seed)?;
+        rng.fill(&mut key.public_seed)?;
+        Ok(key)
+    }
+
+    /// Derives the public key.
+    pub fn public_key(&self) -> WotsPublicKey {
+        WotsPublicKey { public_seed: self.public_seed, key: public_key_at(&self.secret_seed, &self.public_seed, 0) }
+    }
+
+    /// Returns `true` once the key has signed a message.
+    pub fn is_used(&self) -> bool {
+        self.used
+    }
+
+    /// Signs the SHA-256 digest of `message` and marks the key as used.
+    ///
+    /// # Returns
+    /// The signature, or `CryptoError::KeyExhausted` if the key has already signed a message.
+    pub fn sign(&mut self, message: &[u8]) -> Result<WotsSignature, CryptoError> {
+        if self.used {
+            return Err(CryptoError::KeyExhausted);
+        }
+        self.used = true;
+        let digest = Sha256::digest(message).into();
+        Ok(WotsSignature { chains: sign_at(&self.secret_seed, &self.public_seed, 0, &digest) })
+    }
+
+    /// Serializes the key as `usage flag ‖ secret seed ‖ public seed`.
+    pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new([&[self.used as u8][..], &self.secret_seed, &self.public_seed].concat())
+    }
+
+    /// Deserializes a key produced by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.len() != PRIVATE_KEY_SIZE {
+            return Err(CryptoError::InvalidKeyLength { expected: PRIVATE_KEY_SIZE, actual: bytes.len() });
+        }
+        let used = match bytes[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(CryptoError::InvalidInput("invalid WOTS+ key usage flag")),
+        };
+        Ok(Self {
+            secret_seed: bytes[1..1 + HASH_SIZE].try_into().expect("seed size"),
+            public_seed: bytes[1 + HASH_SIZE..].try_into().expect("seed size"),
+            used,
+        })
+    }
+}
+
+impl std::fmt::Debug for WotsPrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WotsPrivateKey").field("used", &self.used).finish_non_exhaustive()
+    }
+}
+
+/// A WOTS+ verification key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WotsPublicKey {
+    public_seed: [u8; HASH_SIZE],
+    key: [u8; HASH_SIZE],
+}
+
+impl WotsPublicKey {
+    /// Returns `true` if `signature` is a valid signature of `message`.
+    pub fn verify(&self, message: &[u8], signature: &WotsSignature) -> bool {
+        let digest = Sha256::digest(message).into();
+        signature.chains.len() == CHAINS
+            && public_key_from_signature(&self.public_seed, 0, &digest, &signature.chains) == self.key
+    }
+
+    /// Serializes the key as `public seed ‖ compressed key`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.public_seed, self.key].concat()
+    }
+
+    /// Deserializes a key produced by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.len() != PUBLIC_KEY_SIZE {
+            return Err(CryptoError::InvalidKeyLength { expected: PUBLIC_KEY_SIZE, actual: bytes.len() });
+        }
+        Ok(Self {
+            public_seed: bytes[..HASH_SIZE].try_into().expect("seed size"),
+            key: bytes[HASH_SIZE..].try_into().expect("hash size"),
+        })
+    }
+}
+
+/// A WOTS+ signature: one intermediate value per hash chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WotsSignature {
+    chains: Vec<[u8; HASH_SIZE]>,
+}
+
+impl WotsSignature {
+    /// Serializes the signature as the concatenated chain values.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.chains.concat()
+    }
+
+    /// Deserializes a signature produced by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.len() != SIGNATURE_SIZE {
+            return Err(CryptoError::InvalidInput("invalid WOTS+ signature length"));
+        }
+        let chains = bytes
+            .chunks(HASH_SIZE)
+            .map(|chunk| chunk.try_into().expect("chunk holds one value"))
+            .collect();
+        Ok(Self { chains })
+    }
+}
+
+impl_serde_via_bytes!(WotsPrivateKey, to_bytes);
+impl_serde_via_bytes!(WotsPublicKey, to_bytes);
+impl_serde_via_bytes!(WotsSignature, to_bytes);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let mut private_key = WotsPrivateKey::generate();
+        let public_key = private_key.public_key();
+        let signature = private_key.sign(b"message").unwrap();
+
+        assert!(public_key.verify(b"message", &signature));
+        assert!(!public_key.verify(b"other", &signature));
+        assert!(!WotsPrivateKey::generate().public_key().verify(b"message", &signature));
+        assert_eq!(private_key.sign(b"message"), Err(CryptoError::KeyExhausted));
+    }
+
+    #[test]
+    fn test_checksum_balances_digits() {
+        let all_zero = digits(&[0u8; HASH_SIZE]);
+        assert_eq!(&all_zero[LEN1..], &[3, 12, 0]); // 64 · 15 = 0x3c0
+        let all_max = digits(&[0xffu8; HASH_SIZE]);
+        assert_eq!(&all_max[LEN1..], &[0, 0, 0]);
+    }
+
+    #[test]
+    fn test_advanced_chain_is_rejected() {
+        let mut private_key = WotsPrivateKey::generate();
+        let public_key = private_key.public_key();
+        let signature = private_key.sign(b"message").unwrap();
+
+        // Moving any chain forward breaks the signature
+        let digest: [u8; HASH_SIZE] = Sha256::digest(b"message").into();
+        let digits = digits(&digest);
+        let i = digits.iter().position(|&digit| digit < W - 1).unwrap();
+        let mut forged = signature.clone();
+        forged.chains[i] = chain(&public_key.public_seed, 0, i as u32, forged.chains[i], digits[i], 1);
+        assert!(!public_key.verify(b"message", &forged));
+    }
+
+    #[test]
+    fn test_serialization_round_trip() {
+        let mut private_key = WotsPrivateKey::generate();
+        let public_key = WotsPublicKey::from_bytes(&private_key.public_key().to_bytes()).unwrap();
+        let signature = private_key.sign(b"message").unwrap();
+        let signature = WotsSignature::from_bytes(&signature.to_bytes()).unwrap();
+        assert!(public_key.verify(b"message", &signature));
+
+        let restored = WotsPrivateKey::from_bytes(&private_key.to_bytes()).unwrap();
+        assert!(restored.is_used());
+        assert!(WotsSignature::from_bytes(&[0u8; 31]).is_err());
+        assert!(WotsPublicKey::from_bytes(&[0u8; 63]).is_err());
+    }
+}
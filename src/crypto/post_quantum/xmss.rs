@@ -0,0 +1,406 @@
+//! XMSS Stateful Hash-Based Signatures
+//!
+//! An eXtended Merkle Signature Scheme key is a [Merkle tree](crate::crypto::merkle) of
+//! `2^height` [WOTS+](super::wots) one-time keys. Each signature uses the next unused leaf and
+//! carries the leaf's authentication path, so the public key is just the tree root. Like the
+//! WOTS+ module, this is a self-contained construction and not wire-compatible with RFC 8391.
+//!
+//! The private key is stateful: signing twice with the same leaf leaks enough of its secrets to
+//! forge signatures. [`XmssPrivateKey`] advances its leaf index on every signature, and
+//! [`XmssKeyFile`] persists the advanced index to disk *before* the signature is produced, so a
+//! crash can waste a leaf but never reuse one.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use serde_json::{json, Value};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+use crate::crypto::error::CryptoError;
+use crate::crypto::merkle::{MerkleProof, MerkleTree};
+use crate::crypto::post_quantum::wots::{self, HashKind, HASH_SIZE};
+use crate::crypto::random::{OsRandom, SecureRandom};
+use crate::crypto::signatures::invalid_data;
+
+/// The largest supported tree height, allowing about a million signatures per key.
+pub const MAX_HEIGHT: u8 = 20;
+
+/// The version of the key file format written by [`XmssKeyFile`].
+pub const KEY_FILE_VERSION: u64 = 1;
+
+/// A stateful XMSS signing key.
+///
+/// The key deliberately has no byte or serde encoding: a copy taken with it could sign again
+/// with leaves the original has already used. Persist it with [`XmssKeyFile`] instead.
+#[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct XmssPrivateKey {
+    secret_seed: [u8; HASH_SIZE],
+    public_seed: [u8; HASH_SIZE],
+    height: u8,
+    next_index: u64,
+    #[zeroize(skip)]
+    tree: MerkleTree,
+}
+
+impl XmssPrivateKey {
+    /// Generates a random key with `2^height` one-time leaves.
+    ///
+    /// Key generation derives every leaf, so it takes time proportional to `2^height`.
+    ///
+    /// # Returns
+    /// `CryptoError::InvalidInput` if `height` is not between 1 and [`MAX_HEIGHT`].
+    pub fn generate(height: u8) -> Result<Self, CryptoError> {
+        Self::generate_with(&mut OsRandom, height)
+    }
+
+    /// Generates a random key with `2^height` one-time leaves, drawing its seeds from the given
+    /// source.
+    pub fn generate_with(rng: &mut impl SecureRandom, height: u8) -> Result<Self, CryptoError> {
+        let (mut secret_seed, mut public_seed) = ([0u8; HASH_SIZE], [0u8; HASH_SIZE]);
+        rng.fill(&mut secret_seed)?;
+        rng.fill(&mut public_seed)?;
+        Self::from_seeds(secret_seed, public_seed, height, 0)
+    }
+
+    /// Rebuilds a key from its seeds and the index of its next unused leaf.
+    pub fn from_seeds(
+        secret_seed: [u8; HASH_SIZE],
+        public_seed: [u8; HASH_SIZE],
+        height: u8,
+        next_index: u64,
+    ) -> Result<Self, CryptoError> {
+        if height == 0 || height > MAX_HEIGHT {
+            return Err(CryptoError::InvalidInput("XMSS height must be between 1 and 20"));
+        }
+        if next_index > 1 << height {
+            return Err(CryptoError::InvalidInput("XMSS leaf index exceeds the tree size"));
+        }
+        let leaves = (0..1u32 << height)
+            .map(|leaf| wots::public_key_at(&secret_seed, &public_seed, leaf))
+            .collect();
+        let tree = MerkleTree::from_leaves(leaves)?;
+        Ok(Self { secret_seed, public_seed, height, next_index, tree })
+    }
+
+    /// The public key.
+    pub fn public_key(&self) -> XmssPublicKey {
+        XmssPublicKey { height: self.height, public_seed: self.public_seed, root: self.tree.root() }
+    }
+
+    /// The tree height.
+    pub fn height(&self) -> u8 {
+        self.height
+    }
+
+    /// The index of the leaf the next signature will use.
+    pub fn next_index(&self) -> u64 {
+        self.next_index
+    }
+
+    /// The number of signatures the key can still produce.
+    pub fn remaining_signatures(&self) -> u64 {
+        (1 << self.height) - self.next_index
+    }
+
+    /// Signs `message` with the next unused leaf.
+    ///
+    /// The advanced index only lives in memory; use [`XmssKeyFile`] for keys that outlive the
+    /// process.
+    ///
+    /// # Returns
+    /// The signature, or `CryptoError::KeyExhausted` once every leaf has been used.
+    pub fn sign(&mut self, message: &[u8]) -> Result<XmssSignature, CryptoError> {
+        if self.remaining_signatures() == 0 {
+            return Err(CryptoError::KeyExhausted);
+        }
+        let leaf = self.next_index as u32;
+        self.next_index += 1;
+
+        let digest = message_digest(&self.public_seed, leaf, &self.tree.root(), message);
+        Ok(XmssSignature {
+            wots: wots::sign_at(&self.secret_seed, &self.public_seed, leaf, &digest),
+            proof: self.tree.proof(leaf as usize).expect("leaf is within the tree"),
+        })
+    }
+}
+
+impl std::fmt::Debug for XmssPrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("XmssPrivateKey")
+            .field("height", &self.height)
+            .field("next_index", &self.next_index)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Binds the message to the leaf and the tree it is signed under.
+fn message_digest(public_seed: &[u8; HASH_SIZE], leaf: u32, root: &[u8; HASH_SIZE], message: &[u8]) -> [u8; HASH_SIZE] {
+    wots::tweak_hash(public_seed, HashKind::Message, leaf, 0, 0, &[root, message])
+}
+
+/// An XMSS verification key: the tree height, public seed and root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmssPublicKey {
+    height: u8,
+    public_seed: [u8; HASH_SIZE],
+    root: [u8; HASH_SIZE],
+}
+
+impl XmssPublicKey {
+    /// The size of a serialized public key.
+    pub const SIZE: usize = 1 + 2 * HASH_SIZE;
+
+    /// Returns `true` if `signature` is a valid signature of `message`.
+    pub fn verify(&self, message: &[u8], signature: &XmssSignature) -> bool {
+        let proof = &signature.proof;
+        if proof.leaf_count() != 1 << self.height || signature.wots.len() != wots::CHAINS {
+            return false;
+        }
+        let leaf = proof.index() as u32;
+        let digest = message_digest(&self.public_seed, leaf, &self.root, message);
+        let leaf_key = wots::public_key_from_signature(&self.public_seed, leaf, &digest, &signature.wots);
+        proof.verify(&self.root, &leaf_key)
+    }
+
+    /// Serializes the key as `height ‖ public seed ‖ root`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [&[self.height][..], &self.public_seed, &self.root].concat()
+    }
+
+    /// Deserializes a key produced by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.len() != Self::SIZE {
+            return Err(CryptoError::InvalidKeyLength { expected: Self::SIZE, actual: bytes.len() });
+        }
+        if bytes[0] == 0 || bytes[0] > MAX_HEIGHT {
+            return Err(CryptoError::InvalidInput("XMSS height must be between 1 and 20"));
+        }
+        Ok(Self {
+            height: bytes[0],
+            public_seed: bytes[1..1 + HASH_SIZE].try_into().expect("seed size"),
+            root: bytes[1 + HASH_SIZE..].try_into().expect("hash size"),
+        })
+    }
+}
+
+/// An XMSS signature: a WOTS+ signature and the Merkle proof of its leaf.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmssSignature {
+    wots: Vec<[u8; HASH_SIZE]>,
+    proof: MerkleProof,
+}
+
+impl XmssSignature {
+    /// The index of the leaf that produced the signature.
+    pub fn index(&self) -> usize {
+        self.proof.index()
+    }
+
+    /// Serializes the signature as `WOTS+ signature ‖ Merkle proof`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.wots.concat(), self.proof.to_bytes()].concat()
+    }
+
+    /// Deserializes a signature produced by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.len() < wots::SIGNATURE_SIZE {
+            return Err(CryptoError::InvalidInput("XMSS signature is truncated"));
+        }
+        let (wots_bytes, proof_bytes) = bytes.split_at(wots::SIGNATURE_SIZE);
+        let wots = wots_bytes
+            .chunks(HASH_SIZE)
+            .map(|chunk| chunk.try_into().expect("chunk holds one value"))
+            .collect();
+        Ok(Self { wots, proof: MerkleProof::from_bytes(proof_bytes)? })
+    }
+}
+
+impl_serde_via_bytes!(XmssPublicKey, to_bytes);
+impl_serde_via_bytes!(XmssSignature, to_bytes);
+
+/// An XMSS key backed by a JSON key file that always records the next unused leaf.
+///
+/// # Example
+/// ```
+/// use zana::crypto::post_quantum::xmss::XmssKeyFile;
+///
+/// let path = std::env::temp_dir().join("zana-doc.xmss").to_string_lossy().into_owned();
+/// # let _ = std::fs::remove_file(&path);
+/// let mut key_file = XmssKeyFile::create(&path, 4).unwrap();
+/// let public_key = key_file.key().public_key();
+/// let signature = key_file.sign(b"release 1.0").unwrap();
+/// assert!(public_key.verify(b"release 1.0", &signature));
+///
+/// // Reopening resumes after the leaf that was just used
+/// assert_eq!(XmssKeyFile::open(&path).unwrap().key().next_index(), 1);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct XmssKeyFile {
+    path: String,
+    key: XmssPrivateKey,
+}
+
+impl XmssKeyFile {
+    /// Generates a key and writes it to a new file at `path`.
+    ///
+    /// Fails with `io::ErrorKind::AlreadyExists` rather than overwrite an existing key, whose
+    /// state would otherwise be lost.
+    pub fn create(path: &str, height: u8) -> io::Result<Self> {
+        let key = XmssPrivateKey::generate(height).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+        file.write_all(key_file_document(&key, key.next_index).as_bytes())?;
+        file.sync_all()?;
+        Ok(Self { path: path.to_string(), key })
+    }
+
+    /// Loads the key stored at `path`, rebuilding its tree.
+    pub fn open(path: &str) -> io::Result<Self> {
+        let document: Value =
+            serde_json::from_str(&fs::read_to_string(path)?).map_err(|_| invalid_data("Invalid XMSS key file"))?;
+        if document["format"] != "zana-xmss" {
+            return Err(invalid_data("Not an XMSS key file"));
+        }
+        if document["version"].as_u64() != Some(KEY_FILE_VERSION) {
+            return Err(invalid_data("Unsupported XMSS key file version"));
+        }
+        let seed = |field: &str| -> io::Result<[u8; HASH_SIZE]> {
+            document[field]
+                .as_str()
+                .and_then(|encoded| hex::decode(encoded).ok())
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| invalid_data("Invalid XMSS key seed"))
+        };
+        let height = document["height"]
+            .as_u64()
+            .and_then(|height| u8::try_from(height).ok())
+            .ok_or_else(|| invalid_data("Invalid XMSS key height"))?;
+        let next_index = document["next_index"]
+            .as_u64()
+            .ok_or_else(|| invalid_data("Invalid XMSS leaf index"))?;
+
+        let key = XmssPrivateKey::from_seeds(seed("secret_seed")?, seed("public_seed")?, height, next_index)
+            .map_err(|e| invalid_data(&e.to_string()))?;
+        Ok(Self { path: path.to_string(), key })
+    }
+
+    /// The loaded key, for inspecting its public key and remaining signatures.
+    pub fn key(&self) -> &XmssPrivateKey {
+        &self.key
+    }
+
+    /// Signs `message`, first persisting the advanced leaf index.
+    ///
+    /// The key file is replaced atomically, so it is never left half-written. If persisting
+    /// fails, no signature is produced and the leaf stays unused.
+    ///
+    /// # Returns
+    /// The signature, or an error wrapping `CryptoError::KeyExhausted` once every leaf is used.
+    pub fn sign(&mut self, message: &[u8]) -> io::Result<XmssSignature> {
+        if self.key.remaining_signatures() == 0 {
+            return Err(io::Error::other(CryptoError::KeyExhausted));
+        }
+        let temporary = format!("{}.tmp", self.path);
+        let mut file = File::create(&temporary)?;
+        file.write_all(key_file_document(&self.key, self.key.next_index + 1).as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temporary, &self.path)?;
+
+        Ok(self.key.sign(message).expect("a leaf remains"))
+    }
+}
+
+fn key_file_document(key: &XmssPrivateKey, next_index: u64) -> String {
+    let document = json!({
+        "format": "zana-xmss",
+        "version": KEY_FILE_VERSION,
+        "height": key.height,
+        "next_index": next_index,
+        "secret_seed": hex::encode(key.secret_seed),
+        "public_seed": hex::encode(key.public_seed),
+    });
+    serde_json::to_string_pretty(&document).expect("JSON values serialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_leaf_signs_once() {
+        let mut key = XmssPrivateKey::generate(3).unwrap();
+        let public_key = key.public_key();
+
+        for leaf in 0..8 {
+            let message = format!("message {}", leaf);
+            let signature = key.sign(message.as_bytes()).unwrap();
+            assert_eq!(signature.index(), leaf);
+            assert!(public_key.verify(message.as_bytes(), &signature));
+            assert!(!public_key.verify(b"other", &signature));
+        }
+        assert_eq!(key.remaining_signatures(), 0);
+        assert_eq!(key.sign(b"one more"), Err(CryptoError::KeyExhausted));
+    }
+
+    #[test]
+    fn test_signature_is_bound_to_key_and_leaf() {
+        let mut key = XmssPrivateKey::generate(2).unwrap();
+        let public_key = key.public_key();
+        let signature = key.sign(b"message").unwrap();
+        assert!(!XmssPrivateKey::generate(2).unwrap().public_key().verify(b"message", &signature));
+
+        // Replaying the WOTS+ signature under a different leaf's proof fails
+        let other = key.sign(b"message").unwrap();
+        let spliced = XmssSignature { wots: signature.wots.clone(), proof: other.proof };
+        assert!(!public_key.verify(b"message", &spliced));
+    }
+
+    #[test]
+    fn test_serialization_round_trip() {
+        let mut key = XmssPrivateKey::generate(4).unwrap();
+        let public_key = XmssPublicKey::from_bytes(&key.public_key().to_bytes()).unwrap();
+        let signature = key.sign(b"message").unwrap();
+        let bytes = signature.to_bytes();
+        assert_eq!(bytes.len(), wots::SIGNATURE_SIZE + 8 + 4 * HASH_SIZE);
+        assert!(public_key.verify(b"message", &XmssSignature::from_bytes(&bytes).unwrap()));
+
+        assert!(XmssSignature::from_bytes(&bytes[..100]).is_err());
+        assert!(XmssPublicKey::from_bytes(&[0u8; XmssPublicKey::SIZE]).is_err());
+        assert!(XmssPrivateKey::generate(0).is_err());
+        assert!(XmssPrivateKey::generate(MAX_HEIGHT + 1).is_err());
+    }
+
+    #[test]
+    fn test_key_file_persists_leaf_index() {
+        let path = std::env::temp_dir()
+            .join(format!("zana-xmss-{}.json", std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        let _ = fs::remove_file(&path);
+
+        let mut key_file = XmssKeyFile::create(&path, 2).unwrap();
+        let public_key = key_file.key().public_key();
+        assert_eq!(XmssKeyFile::create(&path, 2).unwrap_err().kind(), io::ErrorKind::AlreadyExists);
+
+        let first = key_file.sign(b"first").unwrap();
+        drop(key_file);
+
+        // A reopened key never reuses a leaf
+        let mut reopened = XmssKeyFile::open(&path).unwrap();
+        assert_eq!(reopened.key().public_key(), public_key);
+        let second = reopened.sign(b"second").unwrap();
+        assert_eq!((first.index(), second.index()), (0, 1));
+        assert!(public_key.verify(b"second", &second));
+
+        reopened.sign(b"third").unwrap();
+        reopened.sign(b"fourth").unwrap();
+        let error = reopened.sign(b"fifth").unwrap_err();
+        assert_eq!(error.get_ref().unwrap().downcast_ref(), Some(&CryptoError::KeyExhausted));
+        assert_eq!(XmssKeyFile::open(&path).unwrap().key().remaining_signatures(), 0);
+
+        let mut document: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        document["next_index"] = json!(5);
+        fs::write(&path, document.to_string()).unwrap();
+        assert_eq!(XmssKeyFile::open(&path).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
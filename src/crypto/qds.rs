@@ -0,0 +1,284 @@
+//! Quantum Digital Signatures
+//!
+//! A simulation of a Gottesman–Chuang-style quantum digital signature on BB84 states, in the
+//! form used by practical QDS experiments. It signs a single bit from Alice to two recipients,
+//! Bob and Charlie, in three phases:
+//!
+//! 1. **Distribution**: for each possible message `m ∈ {0, 1}`, Alice picks a secret sequence of
+//!    BB84 states and sends a copy to each recipient, who measures every qubit in a random basis
+//!    and keeps the results.
+//! 2. **Messaging**: to sign `m`, Alice reveals the classical description of the states for `m`.
+//! 3. **Verification**: a recipient looks at the positions they happened to measure in the
+//!    declared basis and counts the fraction whose outcome disagrees with the declaration. They
+//!    accept a signature from Alice if that fraction is below the authentication threshold `s_a`,
+//!    and a signature forwarded by the other recipient if it is below the looser verification
+//!    threshold `s_v`.
+//!
+//! Honest signatures only disagree where the channel introduced errors. A forger has to guess the
+//! states from their own measurements and gets about a quarter of the checked positions wrong, and
+//! the gap between `s_a` and `s_v` keeps Alice from producing a signature one recipient accepts
+//! and the other, after forwarding, rejects.
+
+use rand::Rng;
+use crate::config;
+use crate::crypto::error::CryptoError;
+use crate::crypto::qkd::bb84::random_bases;
+use crate::crypto::qkd::channel::QuantumChannel;
+use crate::crypto::quantum::{measure_in_basis, Basis, QubitState};
+
+/// The default number of qubits per possible message.
+pub const DEFAULT_KEY_LENGTH: usize = 1000;
+
+/// The default authentication threshold `s_a`.
+pub const DEFAULT_AUTHENTICATION_THRESHOLD: f64 = 0.05;
+
+/// The default verification threshold `s_v` for forwarded signatures.
+pub const DEFAULT_VERIFICATION_THRESHOLD: f64 = 0.10;
+
+/// The mismatch rate a forger guessing from their own measurements is expected to cause.
+pub const FORGERY_MISMATCH_RATE: f64 = 0.25;
+
+/// A signature: the message bit and the classical description of the states Alice sent for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QdsSignature {
+    /// The signed bit.
+    pub message: u8,
+    /// The declared states.
+    pub states: Vec<QubitState>,
+}
+
+/// Alice's one-time signing key: the states she distributed for each message.
+#[derive(Debug, Clone)]
+pub struct QdsSigner {
+    states: [Vec<QubitState>; 2],
+}
+
+impl QdsSigner {
+    /// Signs a single bit, consuming the key.
+    ///
+    /// # Returns
+    /// The signature, or `CryptoError::InvalidInput` if `message` is not `0` or `1`.
+    pub fn sign(self, message: u8) -> Result<QdsSignature, CryptoError> {
+        if message > 1 {
+            return Err(CryptoError::InvalidInput("quantum signatures sign a single bit"));
+        }
+        let [zero, one] = self.states;
+        Ok(QdsSignature { message, states: if message == 0 { zero } else { one } })
+    }
+}
+
+/// A recipient's measurement record from the distribution phase.
+#[derive(Debug, Clone)]
+pub struct QdsRecipient {
+    /// For each message, the basis and outcome of every qubit, or `None` if it was lost.
+    records: [Vec<Option<(Basis, u8)>>; 2],
+    authentication_threshold: f64,
+    verification_threshold: f64,
+}
+
+impl QdsRecipient {
+    /// The fraction of checked positions where the signature disagrees with this recipient's
+    /// measurements.
+    ///
+    /// Returns `1.0` if the signature is malformed or no position could be checked.
+    pub fn mismatch_rate(&self, signature: &QdsSignature) -> f64 {
+        let Some(records) = self.records.get(signature.message as usize) else {
+            return 1.0;
+        };
+        if records.len() != signature.states.len() {
+            return 1.0;
+        }
+        let (checked, mismatches) = records
+            .iter()
+            .zip(&signature.states)
+            .filter_map(|(record, state)| record.filter(|(basis, _)| *basis == state.basis()).map(|(_, bit)| (bit, state)))
+            .fold((0usize, 0usize), |(checked, mismatches), (bit, state)| {
+                (checked + 1, mismatches + usize::from(bit != state.bit()))
+            });
+        if checked == 0 {
+            return 1.0;
+        }
+        mismatches as f64 / checked as f64
+    }
+
+    /// Accepts a signature received directly from Alice.
+    pub fn verify(&self, signature: &QdsSignature) -> bool {
+        self.mismatch_rate(signature) < self.authentication_threshold
+    }
+
+    /// Accepts a signature forwarded by the other recipient.
+    pub fn verify_forwarded(&self, signature: &QdsSignature) -> bool {
+        self.mismatch_rate(signature) < self.verification_threshold
+    }
+
+    /// Builds the best forgery this recipient can make for `message`: the state they measured at
+    /// every position, with a guess where the qubit was lost.
+    pub fn forge(&self, message: u8) -> QdsSignature {
+        let records = &self.records[(message & 1) as usize];
+        QdsSignature {
+            message: message & 1,
+            states: records
+                .iter()
+                .map(|record| match record {
+                    Some((basis, bit)) => QubitState::encode(*bit, *basis),
+                    None => QubitState::Zero,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The parameters of a quantum digital signature run.
+///
+/// # Example
+/// ```
+/// use zana::crypto::qds::QdsScheme;
+///
+/// let (alice, bob, charlie) = QdsScheme::new().distribute().unwrap();
+/// let signature = alice.sign(1).unwrap();
+/// assert!(bob.verify(&signature));
+/// assert!(charlie.verify_forwarded(&signature));
+///
+/// // Bob cannot convince Charlie that Alice signed 0
+/// assert!(!charlie.verify_forwarded(&bob.forge(0)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct QdsScheme {
+    key_length: usize,
+    authentication_threshold: f64,
+    verification_threshold: f64,
+    channel: QuantumChannel,
+}
+
+impl Default for QdsScheme {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QdsScheme {
+    /// Creates a scheme with the default key length and thresholds over a perfect channel.
+    pub fn new() -> Self {
+        Self {
+            key_length: DEFAULT_KEY_LENGTH,
+            authentication_threshold: DEFAULT_AUTHENTICATION_THRESHOLD,
+            verification_threshold: DEFAULT_VERIFICATION_THRESHOLD,
+            channel: QuantumChannel::new(),
+        }
+    }
+
+    /// Sets the number of qubits distributed per possible message.
+    pub fn with_key_length(mut self, key_length: usize) -> Self {
+        self.key_length = key_length;
+        self
+    }
+
+    /// Sets the authentication threshold `s_a` and the verification threshold `s_v`.
+    pub fn with_thresholds(mut self, authentication_threshold: f64, verification_threshold: f64) -> Self {
+        self.authentication_threshold = authentication_threshold;
+        self.verification_threshold = verification_threshold;
+        self
+    }
+
+    /// Distributes the states through a lossy or noisy channel.
+    pub fn with_channel(mut self, channel: QuantumChannel) -> Self {
+        self.channel = channel;
+        self
+    }
+
+    /// Runs the distribution phase.
+    ///
+    /// # Returns
+    /// Alice's signing key and the records of Bob and Charlie, or `CryptoError::InvalidInput` if
+    /// the thresholds do not satisfy `0 < s_a ≤ s_v < 1/4` or the channel is misconfigured.
+    pub fn distribute(&self) -> Result<(QdsSigner, QdsRecipient, QdsRecipient), CryptoError> {
+        let (s_a, s_v) = (self.authentication_threshold, self.verification_threshold);
+        if !(s_a > 0.0 && s_a <= s_v && s_v < FORGERY_MISMATCH_RATE) {
+            return Err(CryptoError::InvalidInput("thresholds must satisfy 0 < s_a <= s_v < 1/4"));
+        }
+        self.channel.validate()?;
+
+        let states: [Vec<QubitState>; 2] = [self.random_states(), self.random_states()];
+        let bob = self.recipient(&states);
+        let charlie = self.recipient(&states);
+        Ok((QdsSigner { states }, bob, charlie))
+    }
+
+    fn random_states(&self) -> Vec<QubitState> {
+        let mut rng = config::rng();
+        random_bases(self.key_length)
+            .into_iter()
+            .map(|basis| QubitState::encode(rng.gen_range(0..2), basis))
+            .collect()
+    }
+
+    fn recipient(&self, states: &[Vec<QubitState>; 2]) -> QdsRecipient {
+        let record = |states: &Vec<QubitState>| -> Vec<Option<(Basis, u8)>> {
+            states
+                .iter()
+                .zip(random_bases(states.len()))
+                .map(|(&state, basis)| {
+                    self.channel
+                        .transmit(state)
+                        .map(|received| (basis, self.channel.detect(measure_in_basis(received, basis))))
+                })
+                .collect()
+        };
+        QdsRecipient {
+            records: [record(&states[0]), record(&states[1])],
+            authentication_threshold: self.authentication_threshold,
+            verification_threshold: self.verification_threshold,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_honest_signature_is_accepted_and_transferable() {
+        for message in [0, 1] {
+            let (alice, bob, charlie) = QdsScheme::new().distribute().unwrap();
+            let signature = alice.sign(message).unwrap();
+            assert_eq!(bob.mismatch_rate(&signature), 0.0);
+            assert!(bob.verify(&signature) && charlie.verify(&signature));
+            assert!(bob.verify_forwarded(&signature) && charlie.verify_forwarded(&signature));
+        }
+    }
+
+    #[test]
+    fn test_forgery_and_tampering_are_rejected() {
+        let (alice, bob, charlie) = QdsScheme::new().distribute().unwrap();
+        let forged = bob.forge(0);
+        assert!(bob.verify(&forged));
+        let rate = charlie.mismatch_rate(&forged);
+        assert!((rate - FORGERY_MISMATCH_RATE).abs() < 0.05, "mismatch {}", rate);
+        assert!(!charlie.verify_forwarded(&forged));
+
+        let mut signature = alice.sign(0).unwrap();
+        signature.message = 1;
+        assert!(!bob.verify(&signature));
+        signature.states.pop();
+        assert_eq!(charlie.mismatch_rate(&signature), 1.0);
+    }
+
+    #[test]
+    fn test_noisy_channel() {
+        let channel = QuantumChannel::new().with_loss(0.3).with_depolarizing(0.03);
+        let scheme = QdsScheme::new().with_key_length(4000).with_channel(channel);
+        let (alice, bob, charlie) = scheme.distribute().unwrap();
+        let signature = alice.sign(1).unwrap();
+        assert!(bob.verify(&signature) && charlie.verify(&signature));
+        assert!(!charlie.verify_forwarded(&bob.forge(0)));
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        assert!(QdsScheme::new().with_thresholds(0.1, 0.05).distribute().is_err());
+        assert!(QdsScheme::new().with_thresholds(0.05, 0.3).distribute().is_err());
+        assert!(QdsScheme::new().with_channel(QuantumChannel::new().with_loss(-1.0)).distribute().is_err());
+        let (alice, _, _) = QdsScheme::new().with_key_length(10).distribute().unwrap();
+        assert!(alice.sign(2).is_err());
+    }
+}
@@ -0,0 +1,121 @@
+//! Privacy Amplification
+//!
+//! After reconciliation Alice and Bob share the same key, but an eavesdropper may know part of it:
+//! whatever she learned from the qubits (bounded by the QBER) plus every parity disclosed during
+//! error correction. Privacy amplification compresses the key with a randomly chosen universal
+//! hash, here a Toeplitz matrix, so that her remaining information about the output is at most
+//! `2^-security_bits` (the leftover hash lemma).
+//!
+//! The Toeplitz seed is public and can be sent in the clear; only its randomness matters, so it
+//! always comes from the operating system, even in a seeded run.
+
+use rand::{thread_rng, Rng};
+use crate::crypto::error::CryptoError;
+
+/// The binary entropy `h(p) = -p log2 p - (1 - p) log2 (1 - p)`.
+pub fn binary_entropy(p: f64) -> f64 {
+    if p <= 0.0 || p >= 1.0 {
+        return 0.0;
+    }
+    -p * p.log2() - (1.0 - p) * (1.0 - p).log2()
+}
+
+/// Computes how many secret bits can be extracted from a reconciled key.
+///
+/// Uses the asymptotic BB84 bound `n (1 - h(qber)) - disclosed - 2 security_bits`: an
+/// eavesdropper causing a QBER of `qber` learns at most `n h(qber)` bits from the quantum channel,
+/// and every disclosed parity is assumed known to her.
+///
+/// # Arguments
+/// * `key_bits` - The length of the reconciled key.
+/// * `qber` - The estimated QBER.
+/// * `disclosed_bits` - The number of bits revealed during error correction.
+/// * `security_bits` - The security parameter `s`; the output is `2^-s`-close to uniform.
+///
+/// # Returns
+/// The length of the final key, or `0` if nothing secret is left.
+pub fn secure_key_length(key_bits: usize, qber: f64, disclosed_bits: usize, security_bits: u32) -> usize {
+    let length = key_bits as f64 * (1.0 - binary_entropy(qber)) - disclosed_bits as f64 - 2.0 * security_bits as f64;
+    length.max(0.0).floor() as usize
+}
+
+/// Generates a random seed for [`toeplitz_hash`], `input_bits + output_bits - 1` bits long.
+pub fn toeplitz_seed(input_bits: usize, output_bits: usize) -> Vec<u8> {
+    let mut rng = thread_rng();
+    (0..(input_bits + output_bits).saturating_sub(1)).map(|_| rng.gen_range(0..2)).collect()
+}
+
+/// Compresses `key` to `output_bits` bits with the Toeplitz matrix defined by `seed`.
+///
+/// Entry `(i, j)` of the matrix is `seed[i - j + key.len() - 1]`, so each diagonal is constant.
+///
+/// # Returns
+/// The output bits, or `CryptoError::InvalidInput` if the output is longer than the key or the seed
+/// has the wrong length.
+pub fn toeplitz_hash(key: &[u8], seed: &[u8], output_bits: usize) -> Result<Vec<u8>, CryptoError> {
+    if output_bits > key.len() {
+        return Err(CryptoError::InvalidInput("output must not be longer than the key"));
+    }
+    if output_bits == 0 {
+        return Ok(Vec::new());
+    }
+    if seed.len() != key.len() + output_bits - 1 {
+        return Err(CryptoError::InvalidInput("Toeplitz seed must be input + output - 1 bits"));
+    }
+    let n = key.len();
+    Ok((0..output_bits)
+        .map(|i| key.iter().enumerate().fold(0, |acc, (j, &bit)| acc ^ (seed[i + n - 1 - j] & bit)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binary_entropy() {
+        assert_eq!(binary_entropy(0.0), 0.0);
+        assert!((binary_entropy(0.5) - 1.0).abs() < 1e-12);
+        assert!((binary_entropy(0.11) - 0.4999).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_secure_key_length() {
+        assert_eq!(secure_key_length(1000, 0.0, 100, 64), 1000 - 100 - 128);
+        assert!(secure_key_length(1000, 0.05, 100, 64) < 1000 - 100 - 128);
+        assert_eq!(secure_key_length(1000, 0.11, 400, 64), 0);
+    }
+
+    #[test]
+    fn test_toeplitz_hash_matches_matrix_product() {
+        // Seed s0..s4 gives the rows [s3 s2 s1 s0] = 1011 and [s4 s3 s2 s1] = 0101
+        let key = [1, 0, 1, 1];
+        let seed = [1, 1, 0, 1, 0];
+        assert_eq!(toeplitz_hash(&key, &seed, 2).unwrap(), vec![1, 1]);
+    }
+
+    #[test]
+    fn test_toeplitz_hash_is_linear_and_shared() {
+        let key: Vec<u8> = toeplitz_seed(300, 1);
+        let other: Vec<u8> = toeplitz_seed(300, 1);
+        let seed = toeplitz_seed(300, 100);
+        let hashed = toeplitz_hash(&key, &seed, 100).unwrap();
+        assert_eq!(hashed.len(), 100);
+        assert_eq!(toeplitz_hash(&key, &seed, 100).unwrap(), hashed);
+
+        let xor: Vec<u8> = key.iter().zip(&other).map(|(a, b)| a ^ b).collect();
+        let expected: Vec<u8> = hashed
+            .iter()
+            .zip(toeplitz_hash(&other, &seed, 100).unwrap())
+            .map(|(a, b)| a ^ b)
+            .collect();
+        assert_eq!(toeplitz_hash(&xor, &seed, 100).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_toeplitz_hash_rejects_bad_lengths() {
+        assert!(toeplitz_hash(&[1, 0], &[1, 0], 3).is_err());
+        assert!(toeplitz_hash(&[1, 0, 1], &[1, 0], 2).is_err());
+        assert!(toeplitz_hash(&[1, 0, 1], &[], 0).unwrap().is_empty());
+    }
+}
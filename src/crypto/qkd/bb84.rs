@@ -0,0 +1,351 @@
+//! BB84 Quantum Key Distribution
+//!
+//! A step-by-step simulation of the BB84 protocol:
+//!
+//! 1. Alice encodes random bits in randomly chosen bases and sends the qubits to Bob.
+//! 2. Bob measures each qubit in a basis of his own choosing.
+//! 3. They publicly compare bases and keep only the positions where they agree (sifting).
+//! 4. They disclose a random sample of the sifted key to estimate the quantum bit error rate
+//!    (QBER), and discard it.
+//! 5. If the QBER exceeds the abort threshold, the channel is assumed to be eavesdropped and the
+//!    key is abandoned.
+//!
+//! [`Bb84Result::distill`] then runs [error reconciliation](super::cascade) and
+//! [privacy amplification](super::amplification) to turn the sifted key into the final key.
+
+use rand::seq::index;
+use rand::Rng;
+use crate::config;
+use crate::crypto::error::CryptoError;
+use crate::crypto::qkd::channel::QuantumChannel;
+use crate::crypto::qkd::{amplification, cascade};
+use crate::crypto::quantum::{measure_in_basis, Basis, QubitState};
+
+/// The default fraction of the sifted key sacrificed for QBER estimation.
+pub const DEFAULT_SAMPLE_FRACTION: f64 = 0.25;
+
+/// The default abort threshold. BB84 with one-way post-processing can distill a secret key up to
+/// a QBER of about 11% (Shor–Preskill).
+pub const DEFAULT_ABORT_THRESHOLD: f64 = 0.11;
+
+/// Draws `count` uniformly random bases.
+pub fn random_bases(count: usize) -> Vec<Basis> {
+    let mut rng = config::rng();
+    (0..count)
+        .map(|_| if rng.gen() { Basis::Diagonal } else { Basis::Rectilinear })
+        .collect()
+}
+
+/// Returns the positions where Alice and Bob chose the same basis.
+pub fn sift(alice_bases: &[Basis], bob_bases: &[Basis]) -> Vec<usize> {
+    alice_bases
+        .iter()
+        .zip(bob_bases)
+        .enumerate()
+        .filter(|(_, (alice, bob))| alice == bob)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Estimates the QBER as the fraction of `sample` positions where the keys differ.
+///
+/// Returns `0.0` for an empty sample.
+pub fn estimate_qber(alice_key: &[u8], bob_key: &[u8], sample: &[usize]) -> f64 {
+    if sample.is_empty() {
+        return 0.0;
+    }
+    let errors = sample.iter().filter(|&&i| alice_key[i] != bob_key[i]).count();
+    errors as f64 / sample.len() as f64
+}
+
+/// The outcome of a BB84 session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bb84Result {
+    /// The number of qubits Alice sent.
+    pub qubits_sent: usize,
+    /// The number of qubits Bob detected; the rest were lost in the channel.
+    pub detected: usize,
+    /// The number of positions kept after sifting.
+    pub sifted_bits: usize,
+    /// The number of sifted bits disclosed to estimate the QBER.
+    pub sample_size: usize,
+    /// The estimated quantum bit error rate.
+    pub qber: f64,
+    /// `true` if the QBER exceeded the threshold or no sample could be taken.
+    pub aborted: bool,
+    /// Alice's sifted key with the sample removed.
+    pub alice_key: Vec<u8>,
+    /// Bob's sifted key with the sample removed.
+    pub bob_key: Vec<u8>,
+}
+
+impl Bb84Result {
+    /// The asymptotic secret key rate in bits per qubit sent: the fraction of qubits kept in the
+    /// key times the Shor–Preskill rate `1 − 2h(qber)`, or `0.0` if the session aborted.
+    pub fn secret_key_rate(&self) -> f64 {
+        if self.aborted || self.qubits_sent == 0 {
+            return 0.0;
+        }
+        let kept = self.alice_key.len() as f64 / self.qubits_sent as f64;
+        kept * (1.0 - 2.0 * amplification::binary_entropy(self.qber)).max(0.0)
+    }
+
+    /// Reconciles and privacy-amplifies the sifted keys into a final shared key.
+    ///
+    /// # Arguments
+    /// * `security_bits` - The security parameter: the final key is `2^-security_bits`-close to a
+    ///   key the eavesdropper knows nothing about.
+    ///
+    /// # Returns
+    /// The final keys, or `CryptoError::InvalidInput` if the session aborted or too little key
+    /// material is left after accounting for the eavesdropper's information.
+    pub fn distill(&self, security_bits: u32) -> Result<DistilledKey, CryptoError> {
+        if self.aborted {
+            return Err(CryptoError::InvalidInput("cannot distill a key from an aborted session"));
+        }
+        let reconciliation = cascade::reconcile(&self.alice_key, &self.bob_key, self.qber)?;
+        let length = amplification::secure_key_length(
+            reconciliation.key.len(),
+            self.qber,
+            reconciliation.disclosed_bits,
+            security_bits,
+        );
+        if length == 0 {
+            return Err(CryptoError::InvalidInput("not enough key material for privacy amplification"));
+        }
+
+        let seed = amplification::toeplitz_seed(self.alice_key.len(), length);
+        Ok(DistilledKey {
+            alice_key: amplification::toeplitz_hash(&self.alice_key, &seed, length)?,
+            bob_key: amplification::toeplitz_hash(&reconciliation.key, &seed, length)?,
+            disclosed_bits: reconciliation.disclosed_bits,
+            corrected_errors: reconciliation.corrected_errors,
+            security_bits,
+        })
+    }
+}
+
+/// The final key produced by [`Bb84Result::distill`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistilledKey {
+    /// Alice's final key bits.
+    pub alice_key: Vec<u8>,
+    /// Bob's final key bits; equal to Alice's unless reconciliation left an error behind.
+    pub bob_key: Vec<u8>,
+    /// The number of parities disclosed during reconciliation.
+    pub disclosed_bits: usize,
+    /// The number of errors reconciliation corrected.
+    pub corrected_errors: usize,
+    /// The security parameter the key was compressed for.
+    pub security_bits: u32,
+}
+
+/// A configurable BB84 key exchange.
+///
+/// # Example
+/// ```
+/// use zana::crypto::qkd::bb84::Bb84Session;
+///
+/// let result = Bb84Session::new(1024).run().unwrap();
+/// assert!(!result.aborted);
+/// assert_eq!(result.alice_key, result.bob_key);
+///
+/// // An intercept-resend attacker causes about 25% errors and the session aborts
+/// let attacked = Bb84Session::new(2048).with_eavesdropper(true).run().unwrap();
+/// assert!(attacked.aborted);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bb84Session {
+    qubits: usize,
+    sample_fraction: f64,
+    abort_threshold: f64,
+    eavesdropper: bool,
+    channel: QuantumChannel,
+}
+
+impl Bb84Session {
+    /// Creates a session in which Alice sends `qubits` qubits; about half survive sifting.
+    pub fn new(qubits: usize) -> Self {
+        Self {
+            qubits,
+            sample_fraction: DEFAULT_SAMPLE_FRACTION,
+            abort_threshold: DEFAULT_ABORT_THRESHOLD,
+            eavesdropper: false,
+            channel: QuantumChannel::new(),
+        }
+    }
+
+    /// Sets the fraction of the sifted key sacrificed for QBER estimation.
+    pub fn with_sample_fraction(mut self, sample_fraction: f64) -> Self {
+        self.sample_fraction = sample_fraction;
+        self
+    }
+
+    /// Sets the QBER above which the session aborts.
+    pub fn with_abort_threshold(mut self, abort_threshold: f64) -> Self {
+        self.abort_threshold = abort_threshold;
+        self
+    }
+
+    /// Places an intercept-resend eavesdropper on the channel, who measures every qubit in a
+    /// random basis and sends Bob the state she observed.
+    pub fn with_eavesdropper(mut self, eavesdropper: bool) -> Self {
+        self.eavesdropper = eavesdropper;
+        self
+    }
+
+    /// Sends the qubits through a lossy or noisy channel instead of a perfect one.
+    pub fn with_channel(mut self, channel: QuantumChannel) -> Self {
+        self.channel = channel;
+        self
+    }
+
+    /// Runs the protocol.
+    ///
+    /// # Returns
+    /// The session outcome, or `CryptoError::InvalidInput` if the sample fraction is not in
+    /// `(0, 1)`, the threshold is not in `[0, 1]` or the channel is misconfigured.
+    pub fn run(&self) -> Result<Bb84Result, CryptoError> {
+        if !(self.sample_fraction > 0.0 && self.sample_fraction < 1.0) {
+            return Err(CryptoError::InvalidInput("sample fraction must be between 0 and 1"));
+        }
+        if !(0.0..=1.0).contains(&self.abort_threshold) {
+            return Err(CryptoError::InvalidInput("abort threshold must be between 0 and 1"));
+        }
+        self.channel.validate()?;
+        let mut rng = config::rng();
+
+        // Alice prepares her qubits
+        let alice_bits: Vec<u8> = (0..self.qubits).map(|_| rng.gen_range(0..2)).collect();
+        let alice_bases = random_bases(self.qubits);
+        let mut qubits: Vec<QubitState> = alice_bits
+            .iter()
+            .zip(&alice_bases)
+            .map(|(&bit, &basis)| QubitState::encode(bit, basis))
+            .collect();
+
+        if self.eavesdropper {
+            let eve_bases = random_bases(self.qubits);
+            for (qubit, &basis) in qubits.iter_mut().zip(&eve_bases) {
+                *qubit = QubitState::encode(measure_in_basis(*qubit, basis), basis);
+            }
+        }
+
+        // Bob measures whatever arrives, then the bases of detected qubits are compared publicly
+        let bob_bases = random_bases(self.qubits);
+        let bob_bits: Vec<Option<u8>> = qubits
+            .iter()
+            .zip(&bob_bases)
+            .map(|(&qubit, &basis)| self.channel.transmit(qubit).map(|q| self.channel.detect(measure_in_basis(q, basis))))
+            .collect();
+        let detected = bob_bits.iter().filter(|bit| bit.is_some()).count();
+        let sifted: Vec<usize> = sift(&alice_bases, &bob_bases)
+            .into_iter()
+            .filter(|&i| bob_bits[i].is_some())
+            .collect();
+        let alice_sifted: Vec<u8> = sifted.iter().map(|&i| alice_bits[i]).collect();
+        let bob_sifted: Vec<u8> = sifted.iter().filter_map(|&i| bob_bits[i]).collect();
+
+        // A random sample is disclosed to estimate the error rate
+        let sample_size = (sifted.len() as f64 * self.sample_fraction).round() as usize;
+        let mut sample = index::sample(&mut rng, sifted.len(), sample_size).into_vec();
+        sample.sort_unstable();
+        let qber = estimate_qber(&alice_sifted, &bob_sifted, &sample);
+
+        let kept = |key: &[u8]| -> Vec<u8> {
+            key.iter()
+                .enumerate()
+                .filter(|(i, _)| sample.binary_search(i).is_err())
+                .map(|(_, &bit)| bit)
+                .collect()
+        };
+        Ok(Bb84Result {
+            qubits_sent: self.qubits,
+            detected,
+            sifted_bits: sifted.len(),
+            sample_size,
+            qber,
+            aborted: sample_size == 0 || qber > self.abort_threshold,
+            alice_key: kept(&alice_sifted),
+            bob_key: kept(&bob_sifted),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RunConfig;
+
+    #[test]
+    fn test_noiseless_session_agrees() {
+        let result = Bb84Session::new(2000).run().unwrap();
+        assert!(!result.aborted);
+        assert_eq!(result.qber, 0.0);
+        assert_eq!(result.alice_key, result.bob_key);
+        assert!((800..1200).contains(&result.sifted_bits), "{} sifted", result.sifted_bits);
+        assert_eq!(result.alice_key.len(), result.sifted_bits - result.sample_size);
+    }
+
+    #[test]
+    fn test_eavesdropper_is_detected() {
+        let result = Bb84Session::new(4000).with_eavesdropper(true).run().unwrap();
+        assert!(result.aborted);
+        assert!((0.18..0.32).contains(&result.qber), "QBER {}", result.qber);
+
+        // A lax enough threshold lets the attacked key through
+        let lax = Bb84Session::new(4000).with_eavesdropper(true).with_abort_threshold(1.0).run().unwrap();
+        assert!(!lax.aborted);
+        assert_ne!(lax.alice_key, lax.bob_key);
+    }
+
+    #[test]
+    fn test_distill_final_key() {
+        let result = Bb84Session::new(8000).run().unwrap();
+        let key = result.distill(64).unwrap();
+        assert_eq!(key.alice_key, key.bob_key);
+        assert_eq!(key.corrected_errors, 0);
+        let expected = result.alice_key.len() - key.disclosed_bits - 128;
+        assert_eq!(key.alice_key.len(), expected);
+
+        assert!(Bb84Session::new(100).run().unwrap().distill(64).is_err());
+        let attacked = Bb84Session::new(1000).with_eavesdropper(true).run().unwrap();
+        assert!(attacked.distill(64).is_err());
+    }
+
+    #[test]
+    fn test_noisy_channel() {
+        let channel = QuantumChannel::new().with_loss(0.5).with_depolarizing(0.09);
+        // Seeded, as the QBER of 500 sampled bits now and then strays past the tolerance
+        let session = Bb84Session::new(8000).with_channel(channel);
+        let run = RunConfig::new().with_seed(9);
+        let result = run.run(|| session.run()).unwrap();
+        assert!((3600..4400).contains(&result.detected), "{} detected", result.detected);
+        assert!((result.qber - channel.expected_qber()).abs() < 0.03, "QBER {}", result.qber);
+        assert!(!result.aborted);
+        assert!(result.secret_key_rate() > 0.0);
+        assert!(result.secret_key_rate() < Bb84Session::new(8000).run().unwrap().secret_key_rate());
+
+        let too_noisy = QuantumChannel::new().with_depolarizing(0.4);
+        let result = Bb84Session::new(4000).with_channel(too_noisy).run().unwrap();
+        assert!(result.aborted);
+        assert_eq!(result.secret_key_rate(), 0.0);
+        assert!(Bb84Session::new(10).with_channel(QuantumChannel::new().with_loss(2.0)).run().is_err());
+    }
+
+    #[test]
+    fn test_sifting_and_qber() {
+        use Basis::{Diagonal as D, Rectilinear as R};
+        assert_eq!(sift(&[R, D, D, R], &[R, R, D, D]), vec![0, 2]);
+        assert_eq!(estimate_qber(&[0, 1, 1, 0], &[0, 0, 1, 1], &[0, 1, 2, 3]), 0.5);
+        assert_eq!(estimate_qber(&[0, 1], &[1, 0], &[]), 0.0);
+    }
+
+    #[test]
+    fn test_invalid_configuration_and_empty_sample() {
+        assert!(Bb84Session::new(10).with_sample_fraction(0.0).run().is_err());
+        assert!(Bb84Session::new(10).with_sample_fraction(1.0).run().is_err());
+        assert!(Bb84Session::new(10).with_abort_threshold(1.5).run().is_err());
+        assert!(Bb84Session::new(0).run().unwrap().aborted);
+    }
+}
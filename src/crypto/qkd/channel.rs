@@ -0,0 +1,163 @@
+//! Quantum Channel Model
+//!
+//! A lossy, noisy link between the QKD parties, so key rates can be studied against channel
+//! quality instead of only in the noiseless case. A qubit sent through the channel:
+//!
+//! 1. is lost with the loss probability, and Bob reports it as undetected;
+//! 2. otherwise suffers [depolarizing noise](NoiseChannel::Depolarizing): an X, Y or Z error with
+//!    the depolarizing probability;
+//! 3. and its measurement outcome is flipped by the detector with the detector error probability.
+
+use rand::Rng;
+use crate::circuit::noise::NoiseChannel;
+use crate::circuit::observable::Pauli;
+use crate::circuit::statevector::Statevector;
+use crate::config;
+use crate::crypto::error::CryptoError;
+use crate::crypto::quantum::QubitState;
+
+/// Applies a Pauli error to a BB84 state. Global phases, such as `X|−⟩ = −|−⟩`, are dropped.
+pub fn apply_pauli(state: QubitState, error: Pauli) -> QubitState {
+    let flip_rectilinear = matches!(error, Pauli::X | Pauli::Y);
+    let flip_diagonal = matches!(error, Pauli::Y | Pauli::Z);
+    match state {
+        QubitState::Zero | QubitState::One if flip_rectilinear => QubitState::encode(state.bit() ^ 1, state.basis()),
+        QubitState::Plus | QubitState::Minus if flip_diagonal => QubitState::encode(state.bit() ^ 1, state.basis()),
+        _ => state,
+    }
+}
+
+/// A quantum channel with loss, depolarizing noise and detector errors.
+///
+/// # Example
+/// ```
+/// use zana::crypto::qkd::channel::QuantumChannel;
+///
+/// let channel = QuantumChannel::new().with_depolarizing(0.06).with_detector_error(0.01);
+/// assert!((channel.expected_qber() - 0.0492).abs() < 1e-4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct QuantumChannel {
+    loss: f64,
+    depolarizing: f64,
+    detector_error: f64,
+}
+
+impl QuantumChannel {
+    /// Creates a noiseless, lossless channel.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the probability that a qubit never reaches the detector.
+    pub fn with_loss(mut self, loss: f64) -> Self {
+        self.loss = loss;
+        self
+    }
+
+    /// Sets the probability of a depolarizing Pauli error on each transmitted qubit.
+    pub fn with_depolarizing(mut self, depolarizing: f64) -> Self {
+        self.depolarizing = depolarizing;
+        self
+    }
+
+    /// Sets the probability that a detector reports the wrong outcome.
+    pub fn with_detector_error(mut self, detector_error: f64) -> Self {
+        self.detector_error = detector_error;
+        self
+    }
+
+    /// The loss probability.
+    pub fn loss(&self) -> f64 {
+        self.loss
+    }
+
+    /// The QBER the channel causes on its own: depolarizing noise flips a sifted bit with
+    /// probability `2p/3`, and the detector may flip it back or introduce an error.
+    pub fn expected_qber(&self) -> f64 {
+        let noise = 2.0 * self.depolarizing / 3.0;
+        noise * (1.0 - self.detector_error) + (1.0 - noise) * self.detector_error
+    }
+
+    /// Checks that every probability is in `[0, 1]`.
+    pub fn validate(&self) -> Result<(), CryptoError> {
+        if [self.loss, self.depolarizing, self.detector_error].iter().all(|p| (0.0..=1.0).contains(p)) {
+            Ok(())
+        } else {
+            Err(CryptoError::InvalidInput("channel probabilities must be between 0 and 1"))
+        }
+    }
+
+    /// Returns `true` if a qubit is lost in transit.
+    pub fn is_lost(&self) -> bool {
+        config::rng().gen_bool(self.loss)
+    }
+
+    /// Sends a BB84 state through the channel.
+    ///
+    /// # Returns
+    /// The state that arrives, or `None` if the qubit was lost.
+    pub fn transmit(&self, state: QubitState) -> Option<QubitState> {
+        if self.is_lost() {
+            return None;
+        }
+        Some(apply_pauli(state, NoiseChannel::Depolarizing(self.depolarizing).sample(&mut config::rng())))
+    }
+
+    /// Applies the channel's depolarizing noise to `qubit` of a statevector.
+    pub fn depolarize(&self, state: &mut Statevector, qubit: usize) {
+        NoiseChannel::Depolarizing(self.depolarizing).apply(state, qubit);
+    }
+
+    /// Passes a measurement outcome through a detector, flipping it with the detector error
+    /// probability.
+    pub fn detect(&self, bit: u8) -> u8 {
+        if config::rng().gen_bool(self.detector_error) {
+            bit ^ 1
+        } else {
+            bit
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::quantum::Basis;
+
+    #[test]
+    fn test_apply_pauli() {
+        assert_eq!(apply_pauli(QubitState::Zero, Pauli::X), QubitState::One);
+        assert_eq!(apply_pauli(QubitState::Zero, Pauli::Z), QubitState::Zero);
+        assert_eq!(apply_pauli(QubitState::Plus, Pauli::X), QubitState::Plus);
+        assert_eq!(apply_pauli(QubitState::Plus, Pauli::Z), QubitState::Minus);
+        assert_eq!(apply_pauli(QubitState::Minus, Pauli::Y), QubitState::Plus);
+        assert_eq!(apply_pauli(QubitState::One, Pauli::I), QubitState::One);
+    }
+
+    #[test]
+    fn test_transmit_statistics() {
+        let lossy = QuantumChannel::new().with_loss(0.3);
+        let arrived = (0..5000).filter_map(|_| lossy.transmit(QubitState::Zero)).count();
+        assert!((3250..3750).contains(&arrived), "{} arrived", arrived);
+
+        let noisy = QuantumChannel::new().with_depolarizing(0.3);
+        let flipped = (0..5000)
+            .filter_map(|_| noisy.transmit(QubitState::encode(0, Basis::Diagonal)))
+            .filter(|state| state.bit() == 1)
+            .count();
+        assert!((800..1200).contains(&flipped), "{} flipped", flipped);
+
+        let perfect = QuantumChannel::new();
+        assert!((0..100).all(|_| perfect.transmit(QubitState::One) == Some(QubitState::One)));
+        assert!((0..100).all(|_| QuantumChannel::new().with_detector_error(1.0).detect(0) == 1));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(QuantumChannel::new().validate().is_ok());
+        assert!(QuantumChannel::new().with_loss(1.2).validate().is_err());
+        assert!(QuantumChannel::new().with_detector_error(-0.1).validate().is_err());
+        assert_eq!(QuantumChannel::new().expected_qber(), 0.0);
+    }
+}
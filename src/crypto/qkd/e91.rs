@@ -0,0 +1,233 @@
+//! E91 Entanglement-Based Quantum Key Distribution
+//!
+//! Ekert's protocol distributes Bell pairs `(|00⟩ + |11⟩)/√2` instead of prepared qubits. Each
+//! pair is built with a [`QuantumCircuit`] and both halves are measured on the statevector
+//! backend, along directions in the X–Z plane chosen at random:
+//!
+//! - Alice measures at 0, π/4 or π/2 and Bob at π/4, π/2 or 3π/4.
+//! - Rounds where both picked the same direction (π/4 or π/2) are perfectly correlated and form
+//!   the key.
+//! - Rounds with Alice at 0 or π/2 and Bob at π/4 or 3π/4 are published to compute the CHSH value
+//!   `S = E(a₁, b₁) − E(a₁, b₃) + E(a₃, b₁) + E(a₃, b₃)`.
+//!
+//! Entangled pairs reach `S = 2√2`, while any local hidden-variable model, including an
+//! eavesdropper who measured the pairs in transit, is bounded by `|S| ≤ 2`. A violation of the
+//! bound therefore certifies that nobody holds a copy of the key.
+
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+use rand::Rng;
+use crate::algorithms::add_cnot;
+use crate::circuit::{gates, QuantumCircuit};
+use crate::config;
+use crate::crypto::error::CryptoError;
+use crate::crypto::qkd::channel::QuantumChannel;
+
+/// Alice's qubit of each Bell pair.
+pub const ALICE_QUBIT: usize = 0;
+/// Bob's qubit of each Bell pair.
+pub const BOB_QUBIT: usize = 1;
+
+/// Alice's measurement directions, as angles from the Z axis towards X.
+pub const ALICE_ANGLES: [f64; 3] = [0.0, FRAC_PI_4, FRAC_PI_2];
+/// Bob's measurement directions.
+pub const BOB_ANGLES: [f64; 3] = [FRAC_PI_4, FRAC_PI_2, 3.0 * FRAC_PI_4];
+
+/// The largest CHSH value any classical correlation can reach.
+pub const CLASSICAL_CHSH_BOUND: f64 = 2.0;
+
+/// The default CHSH value below which a session aborts, leaving room for statistical fluctuations
+/// around the quantum value `2√2`.
+pub const DEFAULT_CHSH_THRESHOLD: f64 = 2.4;
+
+/// Builds the circuit preparing the Bell pair `(|00⟩ + |11⟩)/√2`.
+pub fn bell_pair_circuit() -> QuantumCircuit {
+    let mut circuit = QuantumCircuit::new(2);
+    circuit.add_gate(gates::hadamard(), vec![ALICE_QUBIT]);
+    add_cnot(&mut circuit, ALICE_QUBIT, BOB_QUBIT);
+    circuit
+}
+
+/// The correlation `E = P(equal) − P(different)` of a set of measurement outcomes.
+///
+/// Returns `0.0` for an empty set.
+pub fn correlation(outcomes: &[(u8, u8)]) -> f64 {
+    if outcomes.is_empty() {
+        return 0.0;
+    }
+    let equal = outcomes.iter().filter(|(a, b)| a == b).count() as f64;
+    (2.0 * equal - outcomes.len() as f64) / outcomes.len() as f64
+}
+
+/// The outcome of an E91 session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct E91Result {
+    /// The number of Bell pairs distributed.
+    pub pairs: usize,
+    /// The number of pairs whose second half reached Bob.
+    pub detected: usize,
+    /// The measured CHSH value.
+    pub chsh: f64,
+    /// `true` if the CHSH value did not exceed the threshold.
+    pub aborted: bool,
+    /// Alice's key bits from the rounds where the directions matched.
+    pub alice_key: Vec<u8>,
+    /// Bob's key bits from the same rounds.
+    pub bob_key: Vec<u8>,
+}
+
+/// A configurable E91 key exchange.
+///
+/// # Example
+/// ```
+/// use zana::crypto::qkd::e91::E91Session;
+///
+/// let result = E91Session::new(3000).run().unwrap();
+/// assert!(!result.aborted);
+/// assert!(result.chsh > 2.0);
+/// assert_eq!(result.alice_key, result.bob_key);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct E91Session {
+    pairs: usize,
+    chsh_threshold: f64,
+    eavesdropper: bool,
+    channel: QuantumChannel,
+}
+
+impl E91Session {
+    /// Creates a session distributing `pairs` Bell pairs; about 2/9 of them end up in the key.
+    pub fn new(pairs: usize) -> Self {
+        Self { pairs, chsh_threshold: DEFAULT_CHSH_THRESHOLD, eavesdropper: false, channel: QuantumChannel::new() }
+    }
+
+    /// Sets the CHSH value the session must exceed.
+    pub fn with_chsh_threshold(mut self, chsh_threshold: f64) -> Self {
+        self.chsh_threshold = chsh_threshold;
+        self
+    }
+
+    /// Places an eavesdropper on Bob's side, who measures every qubit in the computational basis
+    /// before forwarding it. This breaks the entanglement and the Bell violation with it.
+    pub fn with_eavesdropper(mut self, eavesdropper: bool) -> Self {
+        self.eavesdropper = eavesdropper;
+        self
+    }
+
+    /// Sends Bob's halves through a lossy or noisy channel. Lost pairs are discarded, noise
+    /// depolarizes Bob's qubit, and the detector error applies to both parties' detectors.
+    pub fn with_channel(mut self, channel: QuantumChannel) -> Self {
+        self.channel = channel;
+        self
+    }
+
+    /// Runs the protocol.
+    ///
+    /// # Returns
+    /// The session outcome, or `CryptoError::InvalidInput` if the threshold is not in
+    /// `[0, 2√2]` or the channel is misconfigured.
+    pub fn run(&self) -> Result<E91Result, CryptoError> {
+        if !(0.0..=2.0 * std::f64::consts::SQRT_2).contains(&self.chsh_threshold) {
+            return Err(CryptoError::InvalidInput("CHSH threshold must be between 0 and 2√2"));
+        }
+        self.channel.validate()?;
+        let mut rng = config::rng();
+        let bell_pair = bell_pair_circuit();
+        let mut alice_key = Vec::new();
+        let mut bob_key = Vec::new();
+        // Outcomes for Alice at a₁/a₃ and Bob at b₁/b₃, indexed [alice][bob]
+        let mut chsh_outcomes: [[Vec<(u8, u8)>; 2]; 2] = Default::default();
+        let mut detected = 0;
+
+        for _ in 0..self.pairs {
+            if self.channel.is_lost() {
+                continue;
+            }
+            detected += 1;
+            let mut statevector = bell_pair.simulate().statevector;
+            if self.eavesdropper {
+                statevector.measure(BOB_QUBIT);
+            }
+            self.channel.depolarize(&mut statevector, BOB_QUBIT);
+
+            // Measuring along angle θ is a rotation by −θ about Y followed by a Z measurement
+            let alice_choice = rng.gen_range(0..3);
+            let bob_choice = rng.gen_range(0..3);
+            statevector.apply_gate(gates::rotation_y(-ALICE_ANGLES[alice_choice]), &[ALICE_QUBIT]);
+            statevector.apply_gate(gates::rotation_y(-BOB_ANGLES[bob_choice]), &[BOB_QUBIT]);
+            let outcome = (
+                self.channel.detect(statevector.measure(ALICE_QUBIT)),
+                self.channel.detect(statevector.measure(BOB_QUBIT)),
+            );
+
+            match (alice_choice, bob_choice) {
+                (1, 0) | (2, 1) => {
+                    alice_key.push(outcome.0);
+                    bob_key.push(outcome.1);
+                }
+                (0 | 2, 0 | 2) => chsh_outcomes[alice_choice / 2][bob_choice / 2].push(outcome),
+                _ => {}
+            }
+        }
+
+        let e = |alice: usize, bob: usize| correlation(&chsh_outcomes[alice][bob]);
+        let chsh = e(0, 0) - e(0, 1) + e(1, 0) + e(1, 1);
+        Ok(E91Result {
+            pairs: self.pairs,
+            detected,
+            chsh,
+            aborted: chsh <= self.chsh_threshold,
+            alice_key,
+            bob_key,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bell_pair_correlations() {
+        let probabilities = bell_pair_circuit().simulate().statevector.probabilities();
+        assert!((probabilities[&0b00] - 0.5).abs() < 1e-10);
+        assert!((probabilities[&0b11] - 0.5).abs() < 1e-10);
+        assert_eq!(correlation(&[(0, 0), (1, 1), (0, 1), (1, 1)]), 0.5);
+        assert_eq!(correlation(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_entangled_pairs_violate_chsh() {
+        let result = E91Session::new(4000).run().unwrap();
+        assert!(!result.aborted);
+        assert!((result.chsh - 2.0 * std::f64::consts::SQRT_2).abs() < 0.35, "S = {}", result.chsh);
+        assert_eq!(result.alice_key, result.bob_key);
+        assert!((600..1200).contains(&result.alice_key.len()));
+    }
+
+    #[test]
+    fn test_eavesdropper_breaks_the_violation() {
+        let result = E91Session::new(4000).with_eavesdropper(true).run().unwrap();
+        assert!(result.aborted);
+        assert!(result.chsh < CLASSICAL_CHSH_BOUND + 0.2, "S = {}", result.chsh);
+        assert_ne!(result.alice_key, result.bob_key);
+    }
+
+    #[test]
+    fn test_noise_reduces_the_violation() {
+        // Depolarizing noise p scales every correlation by 1 − 4p/3
+        let channel = QuantumChannel::new().with_loss(0.25).with_depolarizing(0.15);
+        let result = E91Session::new(4000).with_channel(channel).run().unwrap();
+        assert!((2700..3300).contains(&result.detected), "{} detected", result.detected);
+        let expected = 2.0 * std::f64::consts::SQRT_2 * 0.8;
+        assert!((result.chsh - expected).abs() < 0.35, "S = {}", result.chsh);
+
+        let channel = QuantumChannel::new().with_detector_error(0.2);
+        assert!(E91Session::new(4000).with_channel(channel).run().unwrap().aborted);
+    }
+
+    #[test]
+    fn test_invalid_threshold() {
+        assert!(E91Session::new(10).with_chsh_threshold(3.0).run().is_err());
+        assert!(E91Session::new(0).run().unwrap().aborted);
+    }
+}
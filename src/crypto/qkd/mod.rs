@@ -0,0 +1,140 @@
+//! Quantum Key Distribution
+//!
+//! Simulations of quantum key distribution protocols, from qubit exchange to a shared key. The
+//! qubit states and bases they exchange come from [`crypto::quantum`](crate::crypto::quantum).
+//!
+//! A raw BB84 key goes through the usual post-processing stages:
+//! - [`bb84`]: qubit exchange, sifting and QBER estimation.
+//! - [`cascade`]: error reconciliation over the public channel.
+//! - [`amplification`]: Toeplitz-hash privacy amplification down to a provably secret key.
+//!
+//! [`e91`] distributes entangled pairs instead, simulated on the [circuit](crate::circuit)
+//! statevector backend, and certifies the key with a CHSH Bell test.
+//!
+//! Both protocols send their qubits through a [`channel::QuantumChannel`], which is noiseless
+//! unless configured with loss, depolarizing noise or detector errors.
+//!
+//! [`establish_key`] runs the whole BB84 pipeline and derives a key ready for
+//! [`crypto::symmetric`](crate::crypto::symmetric).
+//!
+//! The simulations draw from [`config::rng`](crate::config::rng), so a seeded session repeats
+//! exactly. [`establish_key`] and the Toeplitz seeds of privacy amplification draw from the
+//! operating system instead, so that a seed never fixes a key.
+
+pub mod amplification;
+pub mod bb84;
+pub mod cascade;
+pub mod channel;
+pub mod e91;
+
+use hkdf::Hkdf;
+use sha2::{Digest, Sha256};
+use crate::config::RunConfig;
+use crate::crypto::error::CryptoError;
+use crate::crypto::secret::{SecretBytes, SecretKey};
+use crate::crypto::qkd::bb84::Bb84Session;
+use crate::crypto::symmetric::KEY_SIZE;
+
+/// The security parameter used for privacy amplification by [`establish_key`].
+pub const SECURITY_BITS: u32 = 64;
+
+const HKDF_INFO: &[u8] = b"zana qkd bb84 aes-256-gcm";
+
+/// Packs bits into bytes, most significant bit first. A trailing partial byte is padded with
+/// zeros.
+pub fn bits_to_bytes(bits: &[u8]) -> Vec<u8> {
+    bits.chunks(8)
+        .map(|chunk| chunk.iter().enumerate().fold(0, |byte, (i, &bit)| byte | ((bit & 1) << (7 - i))))
+        .collect()
+}
+
+/// Runs a BB84 session end to end and derives a 256-bit symmetric key from it.
+///
+/// The session is sifted, checked against its abort threshold, reconciled with Cascade and
+/// privacy-amplified; Alice and Bob then confirm their final keys match by comparing hashes, and
+/// the key is expanded with HKDF-SHA256 into a key for [`symmetric::encrypt`].
+///
+/// # Arguments
+/// * `session` - The BB84 configuration. A few thousand qubits are needed to leave at least 256
+///   secret bits after sifting, reconciliation and privacy amplification.
+///
+/// # Returns
+/// The shared key, or `CryptoError::InvalidInput` if the session aborted or yielded fewer than 256
+/// secret bits, or `CryptoError::KeyGenerationFailed` if the keys still differed after
+/// reconciliation.
+///
+/// The session runs on the operating system's randomness even after
+/// [`config::set_seed`](crate::config::set_seed) or inside a seeded
+/// [`RunConfig`](crate::config::RunConfig), so every call yields a fresh key.
+///
+/// # Example
+/// ```
+/// use zana::crypto::qkd::{bb84::Bb84Session, establish_key};
+/// use zana::crypto::symmetric;
+///
+/// let key = establish_key(&Bb84Session::new(4096)).unwrap();
+/// let nonce = symmetric::generate_random_nonce().unwrap();
+/// let ciphertext = symmetric::encrypt(key.expose_secret(), &nonce, b"quantum-safe hello").unwrap();
+/// assert_eq!(symmetric::decrypt(key.expose_secret(), &nonce, &ciphertext).unwrap(), b"quantum-safe hello");
+/// ```
+///
+/// [`symmetric::encrypt`]: crate::crypto::symmetric::encrypt
+pub fn establish_key(session: &Bb84Session) -> Result<SecretKey, CryptoError> {
+    let result = RunConfig::new().run(|| session.run())?;
+    if result.aborted {
+        return Err(CryptoError::InvalidInput("QKD session aborted: error rate above threshold"));
+    }
+    let distilled = result.distill(SECURITY_BITS)?;
+    if distilled.alice_key.len() < 8 * KEY_SIZE {
+        return Err(CryptoError::InvalidInput("QKD session yielded fewer than 256 secret bits"));
+    }
+
+    // Key confirmation: only the hashes cross the public channel
+    let alice_key = SecretBytes::new(bits_to_bytes(&distilled.alice_key));
+    let bob_key = SecretBytes::new(bits_to_bytes(&distilled.bob_key));
+    if Sha256::digest(alice_key.expose_secret()) != Sha256::digest(bob_key.expose_secret()) {
+        return Err(CryptoError::KeyGenerationFailed);
+    }
+
+    let mut key = SecretKey::new([0u8; KEY_SIZE]);
+    Hkdf::<Sha256>::new(None, alice_key.expose_secret())
+        .expand(HKDF_INFO, key.expose_secret_mut())
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::qkd::channel::QuantumChannel;
+
+    #[test]
+    fn test_bits_to_bytes() {
+        assert_eq!(bits_to_bytes(&[1, 0, 0, 0, 0, 0, 0, 1, 1, 1]), vec![0x81, 0xc0]);
+        assert!(bits_to_bytes(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_establish_key() {
+        let channel = QuantumChannel::new().with_depolarizing(0.03);
+        let first = establish_key(&Bb84Session::new(6000).with_channel(channel)).unwrap();
+        let second = establish_key(&Bb84Session::new(6000)).unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_establish_key_ignores_seed() {
+        let session = Bb84Session::new(4096);
+        let run = RunConfig::new().with_seed(11);
+        assert_eq!(run.run(|| session.run()).unwrap(), run.run(|| session.run()).unwrap());
+
+        let first = run.run(|| establish_key(&session)).unwrap();
+        assert_ne!(first, run.run(|| establish_key(&session)).unwrap());
+    }
+
+    #[test]
+    fn test_establish_key_failures() {
+        assert!(establish_key(&Bb84Session::new(500)).is_err());
+        assert!(establish_key(&Bb84Session::new(4000).with_eavesdropper(true)).is_err());
+    }
+}
@@ -0,0 +1,310 @@
+//! Quantum Random Number Generation
+//!
+//! Draws random bits by preparing `|+⟩ = H|0⟩` on the [statevector simulator](crate::circuit)
+//! and measuring it, the way a hardware QRNG reads out a beam splitter. Real sources are never
+//! perfectly balanced, so the raw bits go through a randomness extractor and a pair of health tests
+//! before they are handed out:
+//!
+//! - [`von_neumann_extract`] removes any bias from independent bits, at the cost of throughput.
+//! - [`Extractor::Toeplitz`] compresses the raw bits with a Toeplitz hash, trading a fixed fraction
+//!   of output for a min-entropy guarantee.
+//! - [`monobit_test`] and [`runs_test`] (NIST SP 800-22 §2.1 and §2.3) reject output blocks that
+//!   are far from uniform.
+
+use rand::{thread_rng, Rng};
+use crate::circuit::{gates, QuantumCircuit};
+use crate::config::RunConfig;
+use crate::crypto::error::CryptoError;
+use crate::crypto::qkd::amplification::{toeplitz_hash, toeplitz_seed};
+use crate::crypto::qkd::bits_to_bytes;
+use crate::crypto::random::SecureRandom;
+
+/// The number of raw bits drawn per block before extraction and health testing.
+pub const RAW_BLOCK_BITS: usize = 2048;
+
+/// The smallest block the health tests are meaningful for.
+pub const MIN_HEALTH_TEST_BITS: usize = 100;
+
+/// The test statistic above which a health test fails, about a one in a million false alarm rate
+/// for uniform output.
+const HEALTH_TEST_Z: f64 = 4.89;
+
+/// Where raw random bits come from.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum EntropySource {
+    /// The thread-local pseudorandom generator.
+    Pseudorandom,
+    /// A Hadamard gate and a measurement on the statevector simulator.
+    #[default]
+    Simulator,
+    /// A miscalibrated simulated source that measures `1` with the given probability.
+    BiasedSimulator(f64),
+}
+
+impl EntropySource {
+    /// Draws `count` raw bits from the source.
+    pub fn bits(&self, count: usize) -> Vec<u8> {
+        let circuit = match *self {
+            EntropySource::Pseudorandom => {
+                let mut rng = thread_rng();
+                return (0..count).map(|_| rng.gen_range(0..2)).collect();
+            }
+            EntropySource::Simulator => {
+                let mut circuit = QuantumCircuit::new(1);
+                circuit.add_gate(gates::hadamard(), vec![0]);
+                circuit
+            }
+            EntropySource::BiasedSimulator(probability_one) => {
+                // Ry(θ)|0⟩ measures 1 with probability sin²(θ/2)
+                let theta = 2.0 * probability_one.clamp(0.0, 1.0).sqrt().asin();
+                let mut circuit = QuantumCircuit::new(1);
+                circuit.add_gate(gates::rotation_y(theta), vec![0]);
+                circuit
+            }
+        };
+        (0..count).map(|_| circuit.simulate().statevector.measure(0)).collect()
+    }
+
+    /// Draws a single raw bit from the source.
+    pub fn bit(&self) -> u8 {
+        self.bits(1)[0]
+    }
+}
+
+/// How raw bits are turned into output bits.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Extractor {
+    /// Raw bits are output unchanged.
+    None,
+    /// Von Neumann debiasing: `01` becomes `0`, `10` becomes `1`, and equal pairs are dropped.
+    #[default]
+    VonNeumann,
+    /// A random Toeplitz hash keeping the given fraction of the raw bits, which should not exceed
+    /// the source's min-entropy per bit.
+    Toeplitz(f64),
+}
+
+impl Extractor {
+    /// Extracts output bits from a block of raw bits.
+    pub fn extract(&self, raw: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        match *self {
+            Extractor::None => Ok(raw.to_vec()),
+            Extractor::VonNeumann => Ok(von_neumann_extract(raw)),
+            Extractor::Toeplitz(ratio) => {
+                if !(ratio > 0.0 && ratio <= 1.0) {
+                    return Err(CryptoError::InvalidInput("Toeplitz output ratio must be between 0 and 1"));
+                }
+                let output_bits = (raw.len() as f64 * ratio).floor() as usize;
+                toeplitz_hash(raw, &toeplitz_seed(raw.len(), output_bits), output_bits)
+            }
+        }
+    }
+}
+
+/// Applies the von Neumann extractor to a sequence of independent bits.
+pub fn von_neumann_extract(bits: &[u8]) -> Vec<u8> {
+    bits.chunks_exact(2)
+        .filter(|pair| pair[0] != pair[1])
+        .map(|pair| pair[0])
+        .collect()
+}
+
+/// The frequency (monobit) test: the numbers of zeros and ones must be close.
+///
+/// Returns `true` if the bits pass. Sequences shorter than [`MIN_HEALTH_TEST_BITS`] always pass.
+pub fn monobit_test(bits: &[u8]) -> bool {
+    if bits.len() < MIN_HEALTH_TEST_BITS {
+        return true;
+    }
+    let ones = bits.iter().filter(|&&bit| bit == 1).count() as f64;
+    let n = bits.len() as f64;
+    (2.0 * ones - n).abs() / n.sqrt() < HEALTH_TEST_Z
+}
+
+/// The runs test: the number of runs of identical bits must match that of a random sequence
+/// with the same proportion of ones.
+///
+/// Returns `true` if the bits pass. Sequences shorter than [`MIN_HEALTH_TEST_BITS`] always pass;
+/// sequences too unbalanced for the test to apply fail.
+pub fn runs_test(bits: &[u8]) -> bool {
+    if bits.len() < MIN_HEALTH_TEST_BITS {
+        return true;
+    }
+    let n = bits.len() as f64;
+    let pi = bits.iter().filter(|&&bit| bit == 1).count() as f64 / n;
+    if (pi - 0.5).abs() >= 2.0 / n.sqrt() {
+        return false;
+    }
+    let runs = 1 + bits.windows(2).filter(|pair| pair[0] != pair[1]).count();
+    let expected = 2.0 * n * pi * (1.0 - pi);
+    (runs as f64 - expected).abs() / (2.0 * n.sqrt() * pi * (1.0 - pi)) < HEALTH_TEST_Z
+}
+
+/// A quantum random number generator with a configurable source, extractor and health tests.
+///
+/// # Example
+/// ```
+/// use zana::crypto::qrng::{EntropySource, Extractor, QuantumRng};
+///
+/// let key = QuantumRng::new().bytes(32).unwrap();
+/// assert_eq!(key.len(), 32);
+///
+/// // A source measuring 1 two times out of three still yields balanced output after extraction
+/// let rng = QuantumRng::new().with_source(EntropySource::BiasedSimulator(0.67));
+/// assert!(rng.bits(512).is_ok());
+/// assert!(rng.with_extractor(Extractor::None).bits(512).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuantumRng {
+    source: EntropySource,
+    extractor: Extractor,
+    health_tests: bool,
+}
+
+impl Default for QuantumRng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QuantumRng {
+    /// Creates a generator measuring the simulator, debiased with von Neumann extraction and
+    /// checked by the health tests.
+    pub fn new() -> Self {
+        Self { source: EntropySource::default(), extractor: Extractor::default(), health_tests: true }
+    }
+
+    /// Sets the raw bit source.
+    pub fn with_source(mut self, source: EntropySource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Sets the randomness extractor.
+    pub fn with_extractor(mut self, extractor: Extractor) -> Self {
+        self.extractor = extractor;
+        self
+    }
+
+    /// Enables or disables the health tests.
+    pub fn with_health_tests(mut self, health_tests: bool) -> Self {
+        self.health_tests = health_tests;
+        self
+    }
+
+    /// Generates `count` random bits.
+    ///
+    /// # Returns
+    /// The bits, `CryptoError::HealthTestFailed` if an extracted block failed a health test, or
+    /// `CryptoError::InvalidInput` if the extractor is misconfigured.
+    pub fn bits(&self, count: usize) -> Result<Vec<u8>, CryptoError> {
+        let mut output = Vec::with_capacity(count);
+        while output.len() < count {
+            let block = self.extractor.extract(&self.source.bits(RAW_BLOCK_BITS))?;
+            if self.health_tests {
+                if !monobit_test(&block) {
+                    return Err(CryptoError::HealthTestFailed("monobit"));
+                }
+                if !runs_test(&block) {
+                    return Err(CryptoError::HealthTestFailed("runs"));
+                }
+            }
+            output.extend(block);
+        }
+        output.truncate(count);
+        Ok(output)
+    }
+
+    /// Generates `count` random bytes, packing bits most significant first.
+    pub fn bytes(&self, count: usize) -> Result<Vec<u8>, CryptoError> {
+        Ok(bits_to_bytes(&self.bits(count * 8)?))
+    }
+}
+
+/// Lets the quantum source stand in wherever key material is drawn, e.g.
+/// [`generate_random_key_with`](crate::crypto::random::generate_random_key_with). Health test
+/// failures are reported as errors rather than producing weak output, and the simulated
+/// measurements draw from the operating system even in a seeded run.
+impl SecureRandom for QuantumRng {
+    fn fill(&mut self, dest: &mut [u8]) -> Result<(), CryptoError> {
+        dest.copy_from_slice(&RunConfig::new().run(|| self.bytes(dest.len()))?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulator_source_is_balanced() {
+        let bits = EntropySource::Simulator.bits(4000);
+        let ones = bits.iter().filter(|&&bit| bit == 1).count();
+        assert!((1800..2200).contains(&ones), "{} ones", ones);
+
+        let biased = EntropySource::BiasedSimulator(0.9).bits(4000);
+        let ones = biased.iter().filter(|&&bit| bit == 1).count();
+        assert!((3450..3750).contains(&ones), "{} ones", ones);
+        assert!(EntropySource::BiasedSimulator(1.0).bits(10).iter().all(|&bit| bit == 1));
+    }
+
+    #[test]
+    fn test_von_neumann_extract() {
+        assert_eq!(von_neumann_extract(&[0, 1, 1, 0, 1, 1, 0, 0, 1]), vec![0, 1]);
+
+        let debiased = von_neumann_extract(&EntropySource::BiasedSimulator(0.8).bits(8000));
+        assert!(monobit_test(&debiased));
+    }
+
+    #[test]
+    fn test_health_tests() {
+        let alternating: Vec<u8> = (0..1000).map(|i| (i % 2) as u8).collect();
+        assert!(monobit_test(&alternating));
+        assert!(!runs_test(&alternating));
+
+        let mut biased = vec![1u8; 700];
+        biased.extend(vec![0u8; 300]);
+        assert!(!monobit_test(&biased));
+        assert!(!runs_test(&biased));
+
+        let random = EntropySource::Pseudorandom.bits(2000);
+        assert!(monobit_test(&random) && runs_test(&random));
+        assert!(monobit_test(&[1; 10]) && runs_test(&[1; 10]));
+    }
+
+    #[test]
+    fn test_quantum_rng_extractors() {
+        let biased = QuantumRng::new().with_source(EntropySource::BiasedSimulator(0.75));
+        assert_eq!(biased.bits(1000).unwrap().len(), 1000);
+        assert_eq!(biased.with_extractor(Extractor::Toeplitz(0.4)).bits(1000).unwrap().len(), 1000);
+        assert_eq!(
+            biased.with_extractor(Extractor::None).bits(1000),
+            Err(CryptoError::HealthTestFailed("monobit"))
+        );
+        assert_eq!(biased.with_extractor(Extractor::None).with_health_tests(false).bits(5).unwrap().len(), 5);
+        assert!(biased.with_extractor(Extractor::Toeplitz(0.0)).bits(8).is_err());
+    }
+
+    #[test]
+    fn test_secure_random_source() {
+        use crate::crypto::random::generate_random_key_with;
+
+        let key = generate_random_key_with(&mut QuantumRng::new()).unwrap();
+        assert_ne!(key, generate_random_key_with(&mut QuantumRng::new()).unwrap());
+        let seeded = || RunConfig::new().with_seed(5).run(|| generate_random_key_with(&mut QuantumRng::new()).unwrap());
+        assert_ne!(seeded(), seeded());
+
+        let mut broken = QuantumRng::new()
+            .with_source(EntropySource::BiasedSimulator(0.9))
+            .with_extractor(Extractor::None);
+        assert!(generate_random_key_with(&mut broken).is_err());
+    }
+
+    #[test]
+    fn test_bytes() {
+        let rng = QuantumRng::new().with_source(EntropySource::Pseudorandom);
+        let first = rng.bytes(32).unwrap();
+        assert_eq!(first.len(), 32);
+        assert_ne!(first, rng.bytes(32).unwrap());
+    }
+}
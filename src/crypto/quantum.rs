@@ -4,8 +4,9 @@
 //! including quantum key distribution (BB84), quantum random number generation (QRNG),
 //! and basic qubit operations.
 
-use rand::{thread_rng, Rng};
-use std::collections::HashMap;
+use rand::Rng;
+use crate::config;
+use crate::crypto::qrng::EntropySource;
 
 /// Represents the state of a single qubit.
 ///
@@ -22,6 +23,133 @@ pub enum QubitState {
     Minus,
 }
 
+/// A measurement basis for a single qubit.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Basis {
+    /// The computational basis {|0⟩, |1⟩}.
+    Rectilinear,
+    /// The diagonal basis {|+⟩, |−⟩}.
+    Diagonal,
+}
+
+impl QubitState {
+    /// Encodes a bit in the given basis: `0` as |0⟩ or |+⟩, `1` as |1⟩ or |−⟩.
+    pub fn encode(bit: u8, basis: Basis) -> Self {
+        match (basis, bit & 1) {
+            (Basis::Rectilinear, 0) => QubitState::Zero,
+            (Basis::Rectilinear, _) => QubitState::One,
+            (Basis::Diagonal, 0) => QubitState::Plus,
+            (Basis::Diagonal, _) => QubitState::Minus,
+        }
+    }
+
+    /// The basis the state is an eigenstate of.
+    pub fn basis(&self) -> Basis {
+        match self {
+            QubitState::Zero | QubitState::One => Basis::Rectilinear,
+            QubitState::Plus | QubitState::Minus => Basis::Diagonal,
+        }
+    }
+
+    /// The bit the state encodes in its own basis.
+    pub fn bit(&self) -> u8 {
+        match self {
+            QubitState::Zero | QubitState::Plus => 0,
+            QubitState::One | QubitState::Minus => 1,
+        }
+    }
+
+    /// The real amplitudes `[⟨0|ψ⟩, ⟨1|ψ⟩]` of the state in the computational basis.
+    pub fn amplitudes(&self) -> [f64; 2] {
+        let h = std::f64::consts::FRAC_1_SQRT_2;
+        match self {
+            QubitState::Zero => [1.0, 0.0],
+            QubitState::One => [0.0, 1.0],
+            QubitState::Plus => [h, h],
+            QubitState::Minus => [h, -h],
+        }
+    }
+}
+
+/// The Born-rule probability of measuring `0` (|0⟩ or |+⟩) when measuring `state` in `basis`.
+///
+/// # Examples
+///
+/// ```
+/// use zana::crypto::quantum::{outcome_zero_probability, Basis, QubitState};
+///
+/// assert_eq!(outcome_zero_probability(QubitState::Zero, Basis::Rectilinear), 1.0);
+/// assert!((outcome_zero_probability(QubitState::Minus, Basis::Rectilinear) - 0.5).abs() < 1e-12);
+/// ```
+pub fn outcome_zero_probability(state: QubitState, basis: Basis) -> f64 {
+    let [a0, a1] = state.amplitudes();
+    let amplitude = match basis {
+        Basis::Rectilinear => a0,
+        Basis::Diagonal => (a0 + a1) * std::f64::consts::FRAC_1_SQRT_2,
+    };
+    amplitude * amplitude
+}
+
+/// Measures a qubit in the given basis, sampling the outcome with Born-rule probabilities.
+///
+/// A state measured in its own basis always yields the bit it encodes; measured in the other basis
+/// it is projected onto either basis state with probability `|⟨φ|ψ⟩|²`.
+///
+/// # Returns
+///
+/// `0` for |0⟩ or |+⟩, `1` for |1⟩ or |−⟩.
+///
+/// # Examples
+///
+/// ```
+/// use zana::crypto::quantum::{measure_in_basis, Basis, QubitState};
+///
+/// assert_eq!(measure_in_basis(QubitState::Minus, Basis::Diagonal), 1);
+/// let bit = measure_in_basis(QubitState::Minus, Basis::Rectilinear);
+/// assert!(bit == 0 || bit == 1);
+/// ```
+pub fn measure_in_basis(state: QubitState, basis: Basis) -> u8 {
+    if config::rng().gen::<f64>() < outcome_zero_probability(state, basis) {
+        0
+    } else {
+        1
+    }
+}
+
+/// Draws a uniformly random basis.
+fn random_basis<R: Rng>(rng: &mut R) -> Basis {
+    if rng.gen() {
+        Basis::Diagonal
+    } else {
+        Basis::Rectilinear
+    }
+}
+
+/// Runs `count` BB84 rounds, optionally with an intercept-resend eavesdropper.
+///
+/// Returns Alice's bits, Bob's measured bits and, if Eve is present, her measured bits.
+fn bb84_rounds(count: usize, eavesdropper: bool) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+    let mut rng = config::rng();
+    let mut alice_bits = Vec::with_capacity(count);
+    let mut bob_bits = Vec::with_capacity(count);
+    let mut eve_bits = Vec::new();
+
+    for _ in 0..count {
+        let bit = rng.gen_range(0..2);
+        let mut qubit = QubitState::encode(bit, random_basis(&mut rng));
+        alice_bits.push(bit);
+
+        if eavesdropper {
+            let eve_basis = random_basis(&mut rng);
+            let eve_bit = measure_in_basis(qubit, eve_basis);
+            eve_bits.push(eve_bit);
+            qubit = QubitState::encode(eve_bit, eve_basis);
+        }
+        bob_bits.push(measure_in_basis(qubit, random_basis(&mut rng)));
+    }
+    (alice_bits, bob_bits, eve_bits)
+}
+
 /// Applies a Hadamard gate to a qubit, toggling between the computational and diagonal bases.
 ///
 /// # Arguments
@@ -51,7 +179,10 @@ pub fn apply_hadamard(qubit: QubitState) -> QubitState {
     }
 }
 
-/// Generates a single quantum random bit using simulated quantum principles.
+/// Generates a single quantum random bit by measuring `H|0⟩` on the statevector simulator.
+///
+/// Use [`QuantumRng`](crate::crypto::qrng::QuantumRng) for longer outputs, other sources, and
+/// extraction and health testing.
 ///
 /// # Returns
 ///
@@ -66,13 +197,14 @@ pub fn apply_hadamard(qubit: QubitState) -> QubitState {
 /// assert!(bit == 0 || bit == 1);
 /// ```
 pub fn generate_quantum_random_bit() -> u8 {
-    let mut rng = thread_rng();
-    rng.gen_range(0..2) // Simulates a random quantum measurement
+    EntropySource::Simulator.bit()
 }
 
 /// Simulates the BB84 Quantum Key Distribution (QKD) protocol.
 ///
-/// This simulation generates random bits for Alice and Bob, with a public reconciliation of their bases.
+/// Alice encodes 10 random bits in random bases and Bob measures each qubit in an independently
+/// chosen random basis, with [`measure_in_basis`] deciding the outcome. The raw bits are returned before
+/// sifting; see [`Bb84Session`](crate::crypto::qkd::bb84::Bb84Session) for the full protocol.
 ///
 /// # Returns
 ///
@@ -89,25 +221,7 @@ pub fn generate_quantum_random_bit() -> u8 {
 /// assert_eq!(alice_bits.len(), bob_bits.len());
 /// ```
 pub fn bb84_simulation() -> (Vec<u8>, Vec<u8>) {
-    let mut rng = thread_rng();
-
-    // Step 1: Generate random bits and random bases for Alice
-    let alice_bits: Vec<u8> = (0..10).map(|_| rng.gen_range(0..2)).collect();
-    let alice_bases: Vec<u8> = (0..10).map(|_| rng.gen_range(0..2)).collect();
-
-    // Step 2: Bob chooses random bases
-    let bob_bases: Vec<u8> = (0..10).map(|_| rng.gen_range(0..2)).collect();
-
-    // Step 3: Alice and Bob share their bases publicly
-    let mut bob_bits = vec![];
-    for (bit, (alice_base, bob_base)) in alice_bits.iter().zip(alice_bases.iter().zip(bob_bases.iter())) {
-        if alice_base == bob_base {
-            bob_bits.push(*bit);
-        } else {
-            bob_bits.push(generate_quantum_random_bit());
-        }
-    }
-
+    let (alice_bits, bob_bits, _) = bb84_rounds(10, false);
     (alice_bits, bob_bits)
 }
 
@@ -137,7 +251,8 @@ pub fn verify_bb84_keys(alice_bits: &[u8], bob_bits: &[u8]) -> usize {
 
 /// Simulates an eavesdropper (Eve) in the BB84 protocol.
 ///
-/// Eve intercepts and measures the qubits before they reach Bob, using random bases for measurement.
+/// Eve intercepts each of Alice's qubits, measures it in a random basis and resends the state she
+/// observed, so Bob measures Eve's copy rather than Alice's original.
 ///
 /// # Returns
 ///
@@ -156,22 +271,7 @@ pub fn verify_bb84_keys(alice_bits: &[u8], bob_bits: &[u8]) -> usize {
 /// assert_eq!(bob_bits.len(), eve_bits.len());
 /// ```
 pub fn simulate_eavesdropping() -> (Vec<u8>, Vec<u8>, Vec<u8>) {
-    let (alice_bits, bob_bits) = bb84_simulation();
-    let mut rng = thread_rng();
-    let eve_bases: Vec<u8> = (0..10).map(|_| rng.gen_range(0..2)).collect();
-    let mut eve_bits = vec![];
-
-    // Eve measures the bits before they reach Bob
-    for ((bit, alice_base), eve_base) in alice_bits.iter().zip(eve_bases.iter()).zip(bob_bits.iter()) {
-        if alice_base == eve_base {
-            eve_bits.push(*bit);
-        } else {
-            eve_bits.push(generate_quantum_random_bit());
-        }
-    }
-
-
-    (alice_bits, bob_bits, eve_bits)
+    bb84_rounds(10, true)
 }
 
 #[cfg(test)]
@@ -184,6 +284,44 @@ mod tests {
         assert_eq!(apply_hadamard(QubitState::Plus), QubitState::Zero);
     }
 
+    #[test]
+    fn test_encode_and_decode_states() {
+        for basis in [Basis::Rectilinear, Basis::Diagonal] {
+            for bit in 0..2 {
+                let state = QubitState::encode(bit, basis);
+                assert_eq!((state.basis(), state.bit()), (basis, bit));
+            }
+        }
+        assert_eq!(QubitState::encode(1, Basis::Diagonal), QubitState::Minus);
+    }
+
+    #[test]
+    fn test_measure_in_basis_follows_born_rule() {
+        for state in [QubitState::Zero, QubitState::One, QubitState::Plus, QubitState::Minus] {
+            for _ in 0..50 {
+                assert_eq!(measure_in_basis(state, state.basis()), state.bit());
+            }
+            let other = match state.basis() {
+                Basis::Rectilinear => Basis::Diagonal,
+                Basis::Diagonal => Basis::Rectilinear,
+            };
+            assert!((outcome_zero_probability(state, other) - 0.5).abs() < 1e-12);
+            let zeros = (0..4000).filter(|_| measure_in_basis(state, other) == 0).count();
+            assert!((1800..2200).contains(&zeros), "{:?}: {} zeros", state, zeros);
+        }
+    }
+
+    #[test]
+    fn test_eavesdropping_disturbs_bob() {
+        let (alice_bits, bob_bits, eve_bits) = bb84_rounds(4000, true);
+        assert_eq!(eve_bits.len(), 4000);
+        // Before sifting, Eve agrees with Alice on 75% of the bits and Bob on 62.5%
+        let eve_matches = verify_bb84_keys(&alice_bits, &eve_bits);
+        assert!((2800..3200).contains(&eve_matches), "{} matches", eve_matches);
+        let bob_matches = verify_bb84_keys(&alice_bits, &bob_bits);
+        assert!((2300..2700).contains(&bob_matches), "{} matches", bob_matches);
+    }
+
     #[test]
     fn test_generate_quantum_random_bit() {
         let bit = generate_quantum_random_bit();
@@ -1,8 +1,87 @@
-use rand::Rng;
+//! Secure Randomness
+//!
+//! Key material is drawn through the [`SecureRandom`] trait so the source can be swapped out:
+//! - [`OsRandom`], the default, reads the operating system's CSPRNG.
+//! - [`SeededRandom`] is deterministic and meant for reproducible tests only.
+//! - [`QuantumRng`](crate::crypto::qrng::QuantumRng) draws from the simulated quantum source.
+
+use rand::rngs::{OsRng, StdRng};
+use rand::{RngCore, SeedableRng};
+use crate::crypto::error::CryptoError;
+use crate::crypto::secret::SecretKey;
+
+/// A source of cryptographically secure random bytes.
+pub trait SecureRandom {
+    /// Fills `dest` with random bytes.
+    ///
+    /// # Returns
+    /// `Ok(())`, or an error if the source could not produce randomness.
+    fn fill(&mut self, dest: &mut [u8]) -> Result<(), CryptoError>;
+}
+
+/// The operating system's random number generator.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsRandom;
+
+impl SecureRandom for OsRandom {
+    fn fill(&mut self, dest: &mut [u8]) -> Result<(), CryptoError> {
+        OsRng.try_fill_bytes(dest).map_err(|_| CryptoError::KeyGenerationFailed)
+    }
+}
+
+/// A deterministic generator for tests and reproducible simulations.
+///
+/// The output depends only on the seed (for a given version of `rand`), so it must never be used
+/// for real keys.
+#[derive(Debug, Clone)]
+pub struct SeededRandom(StdRng);
+
+impl SeededRandom {
+    /// Creates a generator from a 32-byte seed.
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        Self(StdRng::from_seed(seed))
+    }
+
+    /// Creates a generator from a short numeric seed.
+    pub fn from_u64(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl SecureRandom for SeededRandom {
+    fn fill(&mut self, dest: &mut [u8]) -> Result<(), CryptoError> {
+        self.0.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Draws a fixed-size array from `rng`.
+pub fn random_array<const N: usize>(rng: &mut impl SecureRandom) -> Result<[u8; N], CryptoError> {
+    let mut bytes = [0u8; N];
+    rng.fill(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Draws `len` bytes from `rng`.
+pub fn random_bytes(rng: &mut impl SecureRandom, len: usize) -> Result<Vec<u8>, CryptoError> {
+    let mut bytes = vec![0u8; len];
+    rng.fill(&mut bytes)?;
+    Ok(bytes)
+}
 
 /// Generates a random 256-bit key.
-pub fn generate_random_key() -> [u8; 32] {
-    rand::thread_rng().gen()
+///
+/// # Panics
+/// If the operating system's random number generator is unavailable.
+pub fn generate_random_key() -> SecretKey {
+    generate_random_key_with(&mut OsRandom).expect("the operating system RNG is unavailable")
+}
+
+/// Generates a random 256-bit key from the given source.
+pub fn generate_random_key_with(rng: &mut impl SecureRandom) -> Result<SecretKey, CryptoError> {
+    let mut key = SecretKey::new([0u8; 32]);
+    rng.fill(key.expose_secret_mut())?;
+    Ok(key)
 }
 
 /// Generates a random salt of the given length.
@@ -12,10 +91,16 @@ pub fn generate_random_key() -> [u8; 32] {
 ///
 /// # Returns
 /// A vector containing the generated random salt.
+///
+/// # Panics
+/// If the operating system's random number generator is unavailable.
 pub fn generate_random_salt(len: usize) -> Vec<u8> {
-    let mut salt = vec![0u8; len];
-    rand::thread_rng().fill(&mut salt[..]);
-    salt
+    generate_random_salt_with(&mut OsRandom, len).expect("the operating system RNG is unavailable")
+}
+
+/// Generates a random salt of the given length from the given source.
+pub fn generate_random_salt_with(rng: &mut impl SecureRandom, len: usize) -> Result<Vec<u8>, CryptoError> {
+    random_bytes(rng, len)
 }
 
 #[cfg(test)]
@@ -28,7 +113,7 @@ mod tests {
         let key2 = generate_random_key();
 
         assert_ne!(key1, key2, "Keys should be random and unique");
-        assert_eq!(key1.len(), 32, "Key length should be 256 bits");
+        assert_eq!(key1.expose_secret().len(), 32, "Key length should be 256 bits");
     }
 
     #[test]
@@ -40,4 +125,27 @@ mod tests {
         assert_eq!(salt1.len(), 16, "Salt length should match the given length");
         assert_eq!(salt2.len(), 16, "Salt length should match the given length");
     }
+
+    #[test]
+    fn test_seeded_random_is_reproducible() {
+        let key1 = generate_random_key_with(&mut SeededRandom::from_u64(7)).unwrap();
+        let key2 = generate_random_key_with(&mut SeededRandom::from_u64(7)).unwrap();
+        assert_eq!(key1, key2);
+        assert_ne!(key1, generate_random_key_with(&mut SeededRandom::from_u64(8)).unwrap());
+
+        let mut rng = SeededRandom::from_seed([1; 32]);
+        assert_ne!(random_bytes(&mut rng, 16).unwrap(), random_bytes(&mut rng, 16).unwrap());
+    }
+
+    #[test]
+    fn test_custom_source() {
+        struct Failing;
+        impl SecureRandom for Failing {
+            fn fill(&mut self, _: &mut [u8]) -> Result<(), CryptoError> {
+                Err(CryptoError::KeyGenerationFailed)
+            }
+        }
+        assert_eq!(generate_random_key_with(&mut Failing), Err(CryptoError::KeyGenerationFailed));
+        assert_eq!(generate_random_salt_with(&mut OsRandom, 0).unwrap(), Vec::<u8>::new());
+    }
 }
@@ -0,0 +1,164 @@
+//! Secret Key Material
+//!
+//! [`SecretKey`] and [`SecretBytes`] hold key material that must not outlive its use: they are
+//! overwritten with zeros when dropped, never print their contents through `Debug`, and compare in
+//! constant time. The bytes are only reachable through `expose_secret`, which keeps every place a
+//! secret leaves its wrapper easy to find.
+//!
+//! The crate returns these types for symmetric keys, password-derived keys, QKD keys and seeds.
+//! RSA (`rsa::RsaPrivateKey`), Ed25519 (`ed25519_dalek::SigningKey`) and X25519 private keys
+//! already zeroize themselves on drop; their serialized bytes are wrapped while they are handled.
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+use crate::crypto::error::CryptoError;
+use crate::crypto::symmetric::constant_time_compare;
+
+/// A fixed-size secret, by default a 256-bit key.
+///
+/// # Example
+/// ```
+/// use zana::crypto::symmetric::{encrypt, generate_random_key, generate_random_nonce};
+///
+/// let key = generate_random_key();
+/// let ciphertext = encrypt(key.expose_secret(), &generate_random_nonce().unwrap(), b"data").unwrap();
+/// assert_eq!(format!("{:?}", key), "SecretKey([REDACTED])");
+/// ```
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SecretKey<const N: usize = 32>([u8; N]);
+
+impl<const N: usize> SecretKey<N> {
+    /// Takes ownership of the key bytes.
+    pub fn new(bytes: [u8; N]) -> Self {
+        Self(bytes)
+    }
+
+    /// Copies a key out of a slice.
+    ///
+    /// # Returns
+    /// The key, or `CryptoError::InvalidKeyLength` if `bytes` is not `N` bytes long.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.len() != N {
+            return Err(CryptoError::InvalidKeyLength { expected: N, actual: bytes.len() });
+        }
+        let mut key = [0u8; N];
+        key.copy_from_slice(bytes);
+        Ok(Self(key))
+    }
+
+    /// Returns the key bytes.
+    pub fn expose_secret(&self) -> &[u8; N] {
+        &self.0
+    }
+
+    /// Returns the key bytes for writing, so keys can be filled in place without a stray copy.
+    pub(crate) fn expose_secret_mut(&mut self) -> &mut [u8; N] {
+        &mut self.0
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for SecretKey<N> {
+    fn from(bytes: [u8; N]) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl<const N: usize> PartialEq for SecretKey<N> {
+    fn eq(&self, other: &Self) -> bool {
+        constant_time_compare(&self.0, &other.0)
+    }
+}
+
+impl<const N: usize> Eq for SecretKey<N> {}
+
+impl<const N: usize> std::fmt::Debug for SecretKey<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecretKey([REDACTED])")
+    }
+}
+
+/// A variable-length secret, such as a decrypted private key or a session key.
+#[derive(Clone, Default, Zeroize, ZeroizeOnDrop)]
+pub struct SecretBytes(Vec<u8>);
+
+impl SecretBytes {
+    /// Takes ownership of the bytes.
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    /// Copies the bytes out of a slice.
+    pub fn from_slice(bytes: &[u8]) -> Self {
+        Self(bytes.to_vec())
+    }
+
+    /// Returns the secret bytes.
+    pub fn expose_secret(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// The length of the secret in bytes.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the secret is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<Vec<u8>> for SecretBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl PartialEq for SecretBytes {
+    fn eq(&self, other: &Self) -> bool {
+        constant_time_compare(&self.0, &other.0)
+    }
+}
+
+impl Eq for SecretBytes {}
+
+impl std::fmt::Debug for SecretBytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SecretBytes({} bytes, [REDACTED])", self.0.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_key() {
+        let key = SecretKey::new([7u8; 32]);
+        assert_eq!(key.expose_secret(), &[7u8; 32]);
+        assert_eq!(key, SecretKey::from_slice(&[7u8; 32]).unwrap());
+        assert_ne!(key, SecretKey::from([8u8; 32]));
+        assert_eq!(
+            SecretKey::<32>::from_slice(&[0; 16]),
+            Err(CryptoError::InvalidKeyLength { expected: 32, actual: 16 })
+        );
+        assert!(!format!("{:?}", key).contains('7'));
+
+        let mut key = key;
+        key.zeroize();
+        assert_eq!(key.expose_secret(), &[0u8; 32]);
+    }
+
+    #[test]
+    fn test_secret_bytes() {
+        let secret = SecretBytes::from(b"hunter2".to_vec());
+        assert_eq!(secret.expose_secret(), b"hunter2");
+        assert_eq!(secret.len(), 7);
+        assert_eq!(secret, SecretBytes::from_slice(b"hunter2"));
+        assert_ne!(secret, SecretBytes::from_slice(b"hunter3"));
+        assert_eq!(format!("{:?}", secret), "SecretBytes(7 bytes, [REDACTED])");
+
+        let mut secret = secret;
+        secret.zeroize();
+        assert!(secret.is_empty());
+    }
+}
@@ -1,48 +1,56 @@
-use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signature, Signer, Verifier};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey, SECRET_KEY_LENGTH};
 use std::fs;
-use base64::{encode, Engine};
+use base64::Engine;
 use base64::engine::general_purpose;
-use rand_core::{RngCore, SeedableRng};
-
-/// Generates a new ed25519 keypair using thread_rng
-pub fn generate_keypair() -> Keypair {
-    // Use thread_rng to generate random bytes
-    let mut rng = rand::thread_rng();
-    let mut secret_bytes = [0u8; 32];
-    rng.fill_bytes(&mut secret_bytes);
-
-    // Create the secret key
-    let secret_key = SecretKey::from_bytes(&secret_bytes).expect("Failed to create secret key");
-
-    // Derive the public key
-    let public_key: PublicKey = (&secret_key).into();
+use rand::rngs::OsRng;
+use argon2::{Argon2, Params};
+use serde_json::{json, Value};
+use zeroize::Zeroize;
+use crate::crypto::hash::{sha256, HashAlgorithm};
+use crate::crypto::random::{random_array, OsRandom};
+use crate::crypto::secret::{SecretBytes, SecretKey};
+use crate::crypto::symmetric::{decrypt_with_aad, encrypt_with_aad, generate_random_nonce};
+
+/// Generates a new ed25519 keypair from the operating system's random number generator
+pub fn generate_keypair() -> SigningKey {
+    SigningKey::generate(&mut OsRng)
+}
 
-    // Combine into a keypair
-    Keypair { secret: secret_key, public: public_key }
+/// Derives an ed25519 keypair deterministically from a 32-byte seed
+///
+/// The same seed always yields the same keypair, so the seed must be kept as secret as the key.
+pub fn keypair_from_seed(seed: [u8; SECRET_KEY_LENGTH]) -> SigningKey {
+    SigningKey::from_bytes(&seed)
 }
 
 /// Signs a message using the provided keypair
-pub fn sign_message(keypair: &Keypair, message: &[u8]) -> Signature {
+pub fn sign_message(keypair: &SigningKey, message: &[u8]) -> Signature {
     keypair.sign(message)
 }
 
 /// Verifies a signed message using the public key and signature
-pub fn verify_message(public_key: &PublicKey, message: &[u8], signature: &Signature) -> bool {
+pub fn verify_message(public_key: &VerifyingKey, message: &[u8], signature: &Signature) -> bool {
     public_key.verify(message, signature).is_ok()
 }
 
 /// Saves a keypair to a file
-pub fn save_keypair_to_file(keypair: &Keypair, file_path: &str) -> std::io::Result<()> {
-    let private_key_b64 = encode(keypair.secret.to_bytes());
-    let public_key_b64 = encode(keypair.public.as_bytes());
-
-    let content = format!("{}\n{}", private_key_b64, public_key_b64);
-    fs::write(file_path, content)
+pub fn save_keypair_to_file(keypair: &SigningKey, file_path: &str) -> std::io::Result<()> {
+    let secret = SecretKey::new(keypair.to_bytes());
+    let mut private_key_b64 = general_purpose::STANDARD.encode(secret.expose_secret());
+    let public_key_b64 = general_purpose::STANDARD.encode(keypair.verifying_key().as_bytes());
+
+    let mut content = format!("{}\n{}", private_key_b64, public_key_b64);
+    let result = fs::write(file_path, &content);
+    private_key_b64.zeroize();
+    content.zeroize();
+    result
 }
 
 /// Loads a keypair from a file
-pub fn load_keypair_from_file(file_path: &str) -> std::io::Result<Keypair> {
-    let content = fs::read_to_string(file_path)?;
+pub fn load_keypair_from_file(file_path: &str) -> std::io::Result<SigningKey> {
+    let content = SecretBytes::new(fs::read(file_path)?);
+    let content = std::str::from_utf8(content.expose_secret())
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid file format"))?;
     let mut lines = content.lines();
 
     let private_key_b64 = lines
@@ -54,30 +62,290 @@ pub fn load_keypair_from_file(file_path: &str) -> std::io::Result<Keypair> {
 
     let private_key_bytes = general_purpose::STANDARD
         .decode(private_key_b64)
+        .map(SecretBytes::new)
         .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid base64 encoding"))?;
     let public_key_bytes = general_purpose::STANDARD
         .decode(public_key_b64)
         .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid base64 encoding"))?;
 
-    let secret = ed25519_dalek::SecretKey::from_bytes(&private_key_bytes)
+    let secret = SecretKey::<SECRET_KEY_LENGTH>::from_slice(private_key_bytes.expose_secret())
         .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid private key"))?;
-    let public = ed25519_dalek::PublicKey::from_bytes(&public_key_bytes)
-        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid public key"))?;
+    let public = public_key_bytes
+        .try_into()
+        .ok()
+        .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid public key"))?;
+
+    let keypair = SigningKey::from_bytes(secret.expose_secret());
+    if keypair.verifying_key() != public {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Public key does not match private key"));
+    }
+    Ok(keypair)
+}
+
+/// The version of the encrypted keystore format written by [`save_encrypted_keypair`]
+pub const KEYSTORE_VERSION: u64 = 1;
+
+pub(crate) fn invalid_data(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+/// Derives the keystore encryption key from a passphrase with Argon2id
+fn keystore_key(passphrase: &str, salt: &[u8], params: Params) -> std::io::Result<SecretKey> {
+    let mut key = SecretKey::new([0u8; 32]);
+    Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, key.expose_secret_mut())
+        .map_err(|_| invalid_data("Invalid key derivation parameters"))?;
+    Ok(key)
+}
+
+/// Seals `secret` under a passphrase with Argon2id and AES-256-GCM
+///
+/// Returns the keystore's `kdf` and `cipher` objects. The Argon2id parameters are recorded so that
+/// keystores remain readable if the defaults change.
+pub(crate) fn seal_with_passphrase(secret: &[u8], passphrase: &str, aad: &[u8]) -> std::io::Result<(Value, Value)> {
+    let params = Params::default();
+    let salt: [u8; 16] = random_array(&mut OsRandom).map_err(|e| std::io::Error::other(e.to_string()))?;
+    let nonce = generate_random_nonce().map_err(|e| std::io::Error::other(e.to_string()))?;
+
+    let key = keystore_key(passphrase, &salt, params.clone())?;
+    let ciphertext = encrypt_with_aad(key.expose_secret(), &nonce, secret, aad)
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+
+    let kdf = json!({
+        "algorithm": "argon2id",
+        "memory_cost": params.m_cost(),
+        "time_cost": params.t_cost(),
+        "parallelism": params.p_cost(),
+        "salt": general_purpose::STANDARD.encode(salt),
+    });
+    let cipher = json!({
+        "algorithm": "aes-256-gcm",
+        "nonce": general_purpose::STANDARD.encode(nonce),
+        "ciphertext": general_purpose::STANDARD.encode(ciphertext),
+    });
+    Ok((kdf, cipher))
+}
+
+/// Decodes a base64 string field of a keystore
+pub(crate) fn decode_base64_field(value: &Value) -> std::io::Result<Vec<u8>> {
+    value
+        .as_str()
+        .and_then(|encoded| general_purpose::STANDARD.decode(encoded).ok())
+        .ok_or_else(|| invalid_data("Invalid base64 encoding"))
+}
+
+/// Opens the secret sealed in the `kdf` and `cipher` objects of a keystore
+pub(crate) fn open_with_passphrase(keystore: &Value, passphrase: &str, aad: &[u8]) -> std::io::Result<SecretBytes> {
+    if keystore["kdf"]["algorithm"] != "argon2id" || keystore["cipher"]["algorithm"] != "aes-256-gcm" {
+        return Err(invalid_data("Unsupported keystore algorithm"));
+    }
+    let cost = |name: &str| {
+        keystore["kdf"][name]
+            .as_u64()
+            .and_then(|value| u32::try_from(value).ok())
+            .ok_or_else(|| invalid_data("Invalid key derivation parameters"))
+    };
+
+    let salt = decode_base64_field(&keystore["kdf"]["salt"])?;
+    let nonce = decode_base64_field(&keystore["cipher"]["nonce"])?;
+    let ciphertext = decode_base64_field(&keystore["cipher"]["ciphertext"])?;
+    let params = Params::new(cost("memory_cost")?, cost("time_cost")?, cost("parallelism")?, Some(32))
+        .map_err(|_| invalid_data("Invalid key derivation parameters"))?;
+
+    let key = keystore_key(passphrase, &salt, params)?;
+    decrypt_with_aad(key.expose_secret(), &nonce, &ciphertext, aad)
+        .map(SecretBytes::new)
+        .map_err(|_| invalid_data("Incorrect passphrase or corrupted keystore"))
+}
+
+/// Saves a keypair to a passphrase-protected keystore file
+///
+/// The keystore is a JSON document holding the public key in the clear and the secret key sealed
+/// with AES-256-GCM under a key derived from the passphrase with Argon2id. The format version and
+/// public key are authenticated along with the secret key.
+pub fn save_encrypted_keypair(keypair: &SigningKey, file_path: &str, passphrase: &str) -> std::io::Result<()> {
+    let public_key = keypair.verifying_key();
+    let aad = keystore_aad(KEYSTORE_VERSION, public_key.as_bytes());
+    let secret = SecretKey::new(keypair.to_bytes());
+    let (kdf, cipher) = seal_with_passphrase(secret.expose_secret(), passphrase, &aad)?;
+
+    let keystore = json!({
+        "version": KEYSTORE_VERSION,
+        "public_key": general_purpose::STANDARD.encode(public_key.as_bytes()),
+        "kdf": kdf,
+        "cipher": cipher,
+    });
+    fs::write(file_path, serde_json::to_string_pretty(&keystore)?)
+}
 
-    Ok(Keypair { secret, public })
+/// The associated data binding the sealed secret key to the keystore's version and public key
+fn keystore_aad(version: u64, public_key: &[u8]) -> Vec<u8> {
+    let mut aad = version.to_be_bytes().to_vec();
+    aad.extend_from_slice(public_key);
+    aad
 }
 
+/// Loads a keypair from a keystore written by [`save_encrypted_keypair`]
+///
+/// Returns an `InvalidData` error if the file is not a supported keystore, or if the passphrase is
+/// wrong or the keystore was modified.
+pub fn load_encrypted_keypair(file_path: &str, passphrase: &str) -> std::io::Result<SigningKey> {
+    let keystore: Value = serde_json::from_str(&fs::read_to_string(file_path)?)
+        .map_err(|_| invalid_data("Invalid keystore format"))?;
+
+    let version = keystore["version"].as_u64().ok_or_else(|| invalid_data("Missing keystore version"))?;
+    if version != KEYSTORE_VERSION {
+        return Err(invalid_data("Unsupported keystore version"));
+    }
+
+    let public_key = decode_base64_field(&keystore["public_key"])?;
+    let aad = keystore_aad(version, &public_key);
+    let opened = open_with_passphrase(&keystore, passphrase, &aad)?;
+    let secret = SecretKey::<SECRET_KEY_LENGTH>::from_slice(opened.expose_secret())
+        .map_err(|_| invalid_data("Invalid private key"))?;
+
+    let keypair = SigningKey::from_bytes(secret.expose_secret());
+    if keypair.verifying_key().as_bytes()[..] != public_key[..] {
+        return Err(invalid_data("Public key does not match private key"));
+    }
+    Ok(keypair)
+}
+
+/// The version of the detached signature format written by [`sign_file`]
+pub const FILE_SIGNATURE_VERSION: u64 = 1;
+
+/// Returns a short identifier for a public key: the first 8 bytes of its SHA-256 hash, in hex
+pub fn key_id(public_key: &VerifyingKey) -> String {
+    hex::encode(&sha256(public_key.as_bytes())[..8])
+}
+
+/// Builds the message covered by a detached file signature
+fn file_signature_payload(key_id: &str, timestamp: u64, algorithm: HashAlgorithm, digest: &[u8]) -> Vec<u8> {
+    format!(
+        "zana-file-signature\n{}\n{}\n{}\n{}\n{}",
+        FILE_SIGNATURE_VERSION,
+        key_id,
+        timestamp,
+        algorithm.name(),
+        hex::encode(digest)
+    )
+    .into_bytes()
+}
+
+/// Signs a file with SHA-256, writing a detached signature next to it
+///
+/// Returns the path of the signature file, which is `file_path` with `.sig` appended.
+pub fn sign_file(file_path: &str, keypair: &SigningKey) -> std::io::Result<String> {
+    sign_file_with_hash(file_path, keypair, HashAlgorithm::Sha256)
+}
+
+/// Signs a file with the given hash algorithm, writing a detached signature next to it
+///
+/// The signature file is a JSON document recording the signer's [`key_id`], the signing time, the
+/// hash algorithm and the file's digest. The signature covers all of them, so none can be altered
+/// without invalidating it.
+pub fn sign_file_with_hash(file_path: &str, keypair: &SigningKey, algorithm: HashAlgorithm) -> std::io::Result<String> {
+    let digest = algorithm.hash_reader(fs::File::open(file_path)?)?;
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    let signer = key_id(&keypair.verifying_key());
+
+    let payload = file_signature_payload(&signer, timestamp, algorithm, &digest);
+    let signature = sign_message(keypair, &payload);
+
+    let document = json!({
+        "version": FILE_SIGNATURE_VERSION,
+        "key_id": signer,
+        "timestamp": timestamp,
+        "hash_algorithm": algorithm.name(),
+        "digest": hex::encode(&digest),
+        "signature": general_purpose::STANDARD.encode(signature.to_bytes()),
+    });
+    let signature_path = format!("{}.sig", file_path);
+    fs::write(&signature_path, serde_json::to_string_pretty(&document)?)?;
+    Ok(signature_path)
+}
+
+/// Verifies a file against a detached signature written by [`sign_file`]
+///
+/// Returns `Ok(false)` if the signature was made by another key, the file has changed, or the
+/// signature is invalid, and an error if either file cannot be read or the signature file is
+/// malformed.
+pub fn verify_file(file_path: &str, signature_path: &str, public_key: &VerifyingKey) -> std::io::Result<bool> {
+    let invalid = || invalid_data("Invalid signature file");
+    let document: Value = serde_json::from_str(&fs::read_to_string(signature_path)?).map_err(|_| invalid())?;
+
+    if document["version"].as_u64() != Some(FILE_SIGNATURE_VERSION) {
+        return Err(invalid_data("Unsupported signature file version"));
+    }
+    let signer = document["key_id"].as_str().ok_or_else(invalid)?;
+    let timestamp = document["timestamp"].as_u64().ok_or_else(invalid)?;
+    let algorithm = document["hash_algorithm"]
+        .as_str()
+        .and_then(HashAlgorithm::from_name)
+        .ok_or_else(|| invalid_data("Unsupported hash algorithm"))?;
+    let signature = document["signature"]
+        .as_str()
+        .and_then(|encoded| general_purpose::STANDARD.decode(encoded).ok())
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+        .ok_or_else(invalid)?;
+
+    if signer != key_id(public_key) {
+        return Ok(false);
+    }
+    let digest = algorithm.hash_reader(fs::File::open(file_path)?)?;
+    let payload = file_signature_payload(signer, timestamp, algorithm, &digest);
+    Ok(verify_message(public_key, &payload, &signature))
+}
+
+/// Why a batch of signatures failed to verify
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchVerifyError {
+    /// The public keys, messages and signatures were not all the same length
+    LengthMismatch,
+    /// The indices of the signatures that did not verify
+    InvalidSignatures(Vec<usize>),
+}
+
+impl std::fmt::Display for BatchVerifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchVerifyError::LengthMismatch => write!(f, "batch inputs have mismatched lengths"),
+            BatchVerifyError::InvalidSignatures(indices) => write!(f, "invalid signatures at indices {:?}", indices),
+        }
+    }
+}
+
+impl std::error::Error for BatchVerifyError {}
 
 /// Verifies multiple signed messages in a batch
-pub fn batch_verify(public_keys: &[PublicKey], messages: &[&[u8]], signatures: &[Signature]) -> bool {
+///
+/// All signatures are checked at once with a random linear combination, which is considerably
+/// faster than verifying them one by one. Only when the batch fails are the signatures checked
+/// individually, to report which ones are invalid.
+pub fn batch_verify(
+    public_keys: &[VerifyingKey],
+    messages: &[&[u8]],
+    signatures: &[Signature],
+) -> Result<(), BatchVerifyError> {
     if public_keys.len() != messages.len() || messages.len() != signatures.len() {
-        return false; // Mismatched lengths
+        return Err(BatchVerifyError::LengthMismatch);
+    }
+    if ed25519_dalek::verify_batch(messages, signatures, public_keys).is_ok() {
+        return Ok(());
     }
 
-    public_keys.iter()
+    let invalid = public_keys.iter()
         .zip(messages.iter())
         .zip(signatures.iter())
-        .all(|((public_key, &message), signature)| verify_message(public_key, message, signature))
+        .enumerate()
+        .filter(|(_, ((public_key, &message), signature))| !verify_message(public_key, message, signature))
+        .map(|(index, _)| index)
+        .collect();
+    Err(BatchVerifyError::InvalidSignatures(invalid))
 }
 
 #[cfg(test)]
@@ -88,7 +356,24 @@ mod tests {
     #[test]
     fn test_keypair_generation() {
         let keypair = generate_keypair();
-        assert_eq!(keypair.public.as_bytes().len(), 32);
+        assert_eq!(keypair.verifying_key().as_bytes().len(), 32);
+    }
+
+    #[test]
+    fn test_keypair_from_seed() {
+        // RFC 8032, section 7.1, test 1
+        let seed = hex::decode("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60").unwrap();
+        let keypair = keypair_from_seed(seed.try_into().unwrap());
+        assert_eq!(
+            hex::encode(keypair.verifying_key().as_bytes()),
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"
+        );
+        assert_eq!(
+            hex::encode(sign_message(&keypair, b"").to_bytes()),
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155\
+             5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+        );
+        assert_eq!(keypair_from_seed([7; 32]), keypair_from_seed([7; 32]));
     }
 
     #[test]
@@ -97,7 +382,7 @@ mod tests {
         let message = b"Hello, Rust!";
         let signature = sign_message(&keypair, message);
 
-        let is_valid = verify_message(&keypair.public, message, &signature);
+        let is_valid = verify_message(&keypair.verifying_key(), message, &signature);
         assert!(is_valid, "Signature verification should succeed");
     }
 
@@ -108,7 +393,7 @@ mod tests {
         let signature = sign_message(&keypair, message);
 
         let tampered_message = b"Tampered message";
-        let is_valid = verify_message(&keypair.public, tampered_message, &signature);
+        let is_valid = verify_message(&keypair.verifying_key(), tampered_message, &signature);
         assert!(!is_valid, "Verification should fail for tampered messages");
     }
 
@@ -120,8 +405,84 @@ mod tests {
         save_keypair_to_file(&keypair, file_path).expect("Failed to save keypair to file");
         let loaded_keypair = load_keypair_from_file(file_path).expect("Failed to load keypair from file");
 
-        assert_eq!(keypair.public, loaded_keypair.public);
+        assert_eq!(keypair.verifying_key(), loaded_keypair.verifying_key());
+        remove_file(file_path).expect("Failed to delete test file");
+    }
+
+    #[test]
+    fn test_encrypted_keystore() {
+        let keypair = generate_keypair();
+        let file_path = "test_encrypted_keystore.json";
+
+        save_encrypted_keypair(&keypair, file_path, "correct horse battery staple").unwrap();
+        let content = fs::read_to_string(file_path).unwrap();
+        assert!(!content.contains(&general_purpose::STANDARD.encode(keypair.to_bytes())));
+
+        let loaded = load_encrypted_keypair(file_path, "correct horse battery staple").unwrap();
+        assert_eq!(loaded.to_bytes(), keypair.to_bytes());
+
+        let error = load_encrypted_keypair(file_path, "wrong passphrase").unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        remove_file(file_path).expect("Failed to delete test file");
+    }
+
+    #[test]
+    fn test_encrypted_keystore_rejects_tampering() {
+        let file_path = "test_tampered_keystore.json";
+        save_encrypted_keypair(&generate_keypair(), file_path, "passphrase").unwrap();
+        let mut keystore: Value = serde_json::from_str(&fs::read_to_string(file_path).unwrap()).unwrap();
+
+        // Substituting another public key breaks the authentication of the secret key
+        let other = generate_keypair().verifying_key();
+        keystore["public_key"] = json!(general_purpose::STANDARD.encode(other.as_bytes()));
+        fs::write(file_path, keystore.to_string()).unwrap();
+        assert!(load_encrypted_keypair(file_path, "passphrase").is_err());
+
+        keystore["version"] = json!(2);
+        fs::write(file_path, keystore.to_string()).unwrap();
+        let error = load_encrypted_keypair(file_path, "passphrase").unwrap_err();
+        assert_eq!(error.to_string(), "Unsupported keystore version");
+        remove_file(file_path).expect("Failed to delete test file");
+    }
+
+    #[test]
+    fn test_detached_file_signature() {
+        let keypair = generate_keypair();
+        let file_path = "test_signed_file.txt";
+        fs::write(file_path, b"release artifact").unwrap();
+
+        let signature_path = sign_file(file_path, &keypair).unwrap();
+        assert_eq!(signature_path, "test_signed_file.txt.sig");
+        let document: Value = serde_json::from_str(&fs::read_to_string(&signature_path).unwrap()).unwrap();
+        assert_eq!(document["key_id"], key_id(&keypair.verifying_key()));
+        assert_eq!(document["hash_algorithm"], "SHA-256");
+
+        assert!(verify_file(file_path, &signature_path, &keypair.verifying_key()).unwrap());
+        assert!(!verify_file(file_path, &signature_path, &generate_keypair().verifying_key()).unwrap());
+
+        fs::write(file_path, b"release artifact, modified").unwrap();
+        assert!(!verify_file(file_path, &signature_path, &keypair.verifying_key()).unwrap());
+
+        remove_file(file_path).expect("Failed to delete test file");
+        remove_file(signature_path).expect("Failed to delete test file");
+    }
+
+    #[test]
+    fn test_detached_signature_metadata_is_signed() {
+        let keypair = generate_keypair();
+        let file_path = "test_signed_metadata.txt";
+        fs::write(file_path, b"artifact").unwrap();
+
+        let signature_path = sign_file_with_hash(file_path, &keypair, HashAlgorithm::Blake2b512).unwrap();
+        assert!(verify_file(file_path, &signature_path, &keypair.verifying_key()).unwrap());
+
+        let mut document: Value = serde_json::from_str(&fs::read_to_string(&signature_path).unwrap()).unwrap();
+        document["timestamp"] = json!(0);
+        fs::write(&signature_path, document.to_string()).unwrap();
+        assert!(!verify_file(file_path, &signature_path, &keypair.verifying_key()).unwrap());
+
         remove_file(file_path).expect("Failed to delete test file");
+        remove_file(signature_path).expect("Failed to delete test file");
     }
 
     #[test]
@@ -141,15 +502,38 @@ mod tests {
             sign_message(&keypair2, &messages[1][..]), // Coerce to slice
         ];
 
-        let public_keys = vec![keypair1.public, keypair2.public];
+        let public_keys = vec![keypair1.verifying_key(), keypair2.verifying_key()];
         let messages_refs: Vec<&[u8]> = messages.iter().map(|m| &m[..]).collect(); // Coerce each element to &[u8]
 
-        let is_batch_valid = batch_verify(&public_keys, &messages_refs, &signatures);
-        assert!(is_batch_valid, "Batch verification should succeed");
+        let batch_result = batch_verify(&public_keys, &messages_refs, &signatures);
+        assert_eq!(batch_result, Ok(()), "Batch verification should succeed");
 
         let tampered_messages_refs: Vec<&[u8]> = vec![b"Message 1", b"Tampered Message"];
-        let is_tampered_batch_valid = batch_verify(&public_keys, &tampered_messages_refs, &signatures);
-        assert!(!is_tampered_batch_valid, "Batch verification should fail for tampered messages");
+        let tampered_result = batch_verify(&public_keys, &tampered_messages_refs, &signatures);
+        assert_eq!(tampered_result, Err(BatchVerifyError::InvalidSignatures(vec![1])));
+    }
+
+    #[test]
+    fn test_batch_verification_reports_every_failure() {
+        let keypairs: Vec<SigningKey> = (0..8).map(|_| generate_keypair()).collect();
+        let messages: Vec<Vec<u8>> = (0..8u8).map(|i| vec![i; 16]).collect();
+        let mut signatures: Vec<Signature> = keypairs.iter()
+            .zip(&messages)
+            .map(|(keypair, message)| sign_message(keypair, message))
+            .collect();
+        signatures.swap(2, 5);
+
+        let public_keys: Vec<VerifyingKey> = keypairs.iter().map(SigningKey::verifying_key).collect();
+        let message_refs: Vec<&[u8]> = messages.iter().map(Vec::as_slice).collect();
+        assert_eq!(
+            batch_verify(&public_keys, &message_refs, &signatures),
+            Err(BatchVerifyError::InvalidSignatures(vec![2, 5]))
+        );
+        assert_eq!(
+            batch_verify(&public_keys[1..], &message_refs, &signatures),
+            Err(BatchVerifyError::LengthMismatch)
+        );
+        assert_eq!(batch_verify(&[], &[], &[]), Ok(()));
     }
 
 }
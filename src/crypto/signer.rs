@@ -0,0 +1,112 @@
+//! Signature Scheme Traits
+//!
+//! [`Signer`] and [`Verifier`] abstract over the signature schemes in the crate, so that code
+//! which signs or checks messages can accept Ed25519 and sr25519 keys alike. Signatures and public
+//! keys cross the traits as bytes, which keeps them object safe.
+
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use crate::crypto::signatures::{sign_message, verify_message};
+
+/// The signature schemes available behind [`Signer`] and [`Verifier`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignatureAlgorithm {
+    /// Ed25519 (RFC 8032).
+    Ed25519,
+    /// Schnorr signatures over Ristretto25519, as used by Substrate.
+    Sr25519,
+}
+
+impl SignatureAlgorithm {
+    /// Returns the conventional name of the algorithm, e.g. `"sr25519"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SignatureAlgorithm::Ed25519 => "ed25519",
+            SignatureAlgorithm::Sr25519 => "sr25519",
+        }
+    }
+}
+
+/// A private key that can sign messages.
+pub trait Signer {
+    /// The scheme this key signs with.
+    fn algorithm(&self) -> SignatureAlgorithm;
+
+    /// Returns the encoded public key matching this private key.
+    fn public_key_bytes(&self) -> Vec<u8>;
+
+    /// Signs a message, returning the encoded signature.
+    fn sign(&self, message: &[u8]) -> Vec<u8>;
+}
+
+/// A public key that can verify signatures.
+pub trait Verifier {
+    /// The scheme this key verifies.
+    fn algorithm(&self) -> SignatureAlgorithm;
+
+    /// Returns `true` if `signature` is a valid encoded signature on `message`.
+    fn verify(&self, message: &[u8], signature: &[u8]) -> bool;
+}
+
+impl Signer for SigningKey {
+    fn algorithm(&self) -> SignatureAlgorithm {
+        SignatureAlgorithm::Ed25519
+    }
+
+    fn public_key_bytes(&self) -> Vec<u8> {
+        self.verifying_key().to_bytes().to_vec()
+    }
+
+    fn sign(&self, message: &[u8]) -> Vec<u8> {
+        sign_message(self, message).to_bytes().to_vec()
+    }
+}
+
+impl Verifier for VerifyingKey {
+    fn algorithm(&self) -> SignatureAlgorithm {
+        SignatureAlgorithm::Ed25519
+    }
+
+    fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        Signature::from_slice(signature).is_ok_and(|signature| verify_message(self, message, &signature))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::signatures::generate_keypair;
+    #[cfg(feature = "crypto-classical")]
+    use crate::crypto::sr25519;
+
+    fn round_trip(signer: &dyn Signer, verifier: &dyn Verifier) {
+        assert_eq!(signer.algorithm(), verifier.algorithm());
+        let signature = signer.sign(b"zana");
+        assert!(verifier.verify(b"zana", &signature), "{}", signer.algorithm().name());
+        assert!(!verifier.verify(b"anaz", &signature));
+        assert!(!verifier.verify(b"zana", &signature[1..]));
+    }
+
+    #[test]
+    fn test_schemes_behind_common_traits() {
+        let ed25519 = generate_keypair();
+        round_trip(&ed25519, &ed25519.verifying_key());
+
+        #[cfg(feature = "crypto-classical")]
+        {
+            let sr25519 = sr25519::generate_keypair();
+            round_trip(&sr25519, &sr25519.public);
+        }
+    }
+
+    #[test]
+    fn test_public_key_bytes() {
+        let ed25519 = generate_keypair();
+        assert_eq!(Signer::public_key_bytes(&ed25519), ed25519.verifying_key().as_bytes());
+
+        #[cfg(feature = "crypto-classical")]
+        {
+            let sr25519 = sr25519::generate_keypair();
+            assert_eq!(Signer::public_key_bytes(&sr25519), sr25519.public.to_bytes());
+        }
+    }
+}
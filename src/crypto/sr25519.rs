@@ -0,0 +1,101 @@
+//! sr25519 Signatures
+//!
+//! Schnorr signatures over the Ristretto group of Curve25519 (`schnorrkel`), the default account
+//! scheme of Substrate-based chains. Every signature is bound to a signing context; Substrate uses
+//! [`SUBSTRATE_CONTEXT`].
+
+use rand::rngs::OsRng;
+use schnorrkel::{ExpansionMode, MiniSecretKey};
+use crate::crypto::signer::{SignatureAlgorithm, Signer, Verifier};
+
+pub use schnorrkel::{Keypair, PublicKey, Signature};
+
+/// The signing context used by Substrate.
+pub const SUBSTRATE_CONTEXT: &[u8] = b"substrate";
+
+/// Generates a new sr25519 keypair.
+pub fn generate_keypair() -> Keypair {
+    Keypair::generate_with(OsRng)
+}
+
+/// Derives an sr25519 keypair from a 32-byte seed ("mini secret key").
+///
+/// The seed is expanded in Ed25519 mode, matching Substrate's key derivation, so the same seed
+/// yields the same account in Substrate tooling.
+pub fn keypair_from_seed(seed: [u8; 32]) -> Keypair {
+    MiniSecretKey::from_bytes(&seed)
+        .expect("any 32 bytes are a valid mini secret key")
+        .expand_to_keypair(ExpansionMode::Ed25519)
+}
+
+/// Signs a message under the given signing context.
+///
+/// # Example
+/// ```
+/// use zana::crypto::sr25519::{generate_keypair, sign, verify, SUBSTRATE_CONTEXT};
+///
+/// let keypair = generate_keypair();
+/// let signature = sign(&keypair, SUBSTRATE_CONTEXT, b"extrinsic");
+/// assert!(verify(&keypair.public, SUBSTRATE_CONTEXT, b"extrinsic", &signature));
+/// ```
+pub fn sign(keypair: &Keypair, context: &[u8], message: &[u8]) -> Signature {
+    keypair.sign_simple(context, message)
+}
+
+/// Verifies a signature made under the given signing context.
+pub fn verify(public_key: &PublicKey, context: &[u8], message: &[u8], signature: &Signature) -> bool {
+    public_key.verify_simple(context, message, signature).is_ok()
+}
+
+/// Signs with [`SUBSTRATE_CONTEXT`].
+impl Signer for Keypair {
+    fn algorithm(&self) -> SignatureAlgorithm {
+        SignatureAlgorithm::Sr25519
+    }
+
+    fn public_key_bytes(&self) -> Vec<u8> {
+        self.public.to_bytes().to_vec()
+    }
+
+    fn sign(&self, message: &[u8]) -> Vec<u8> {
+        sign(self, SUBSTRATE_CONTEXT, message).to_bytes().to_vec()
+    }
+}
+
+/// Verifies with [`SUBSTRATE_CONTEXT`].
+impl Verifier for PublicKey {
+    fn algorithm(&self) -> SignatureAlgorithm {
+        SignatureAlgorithm::Sr25519
+    }
+
+    fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        Signature::from_bytes(signature).is_ok_and(|signature| verify(self, SUBSTRATE_CONTEXT, message, &signature))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let keypair = generate_keypair();
+        let signature = sign(&keypair, SUBSTRATE_CONTEXT, b"zana");
+
+        assert!(verify(&keypair.public, SUBSTRATE_CONTEXT, b"zana", &signature));
+        assert!(!verify(&keypair.public, SUBSTRATE_CONTEXT, b"anaz", &signature));
+        assert!(!verify(&keypair.public, b"other context", b"zana", &signature));
+        assert!(!verify(&generate_keypair().public, SUBSTRATE_CONTEXT, b"zana", &signature));
+    }
+
+    #[test]
+    fn test_keypair_from_seed() {
+        // Substrate's well-known development account "Alice"
+        let seed = hex::decode("e5be9a5092b81bca64be81d212e7f2f9eba183bb7a90954f7b76361f6edb5c0a").unwrap();
+        let keypair = keypair_from_seed(seed.try_into().unwrap());
+        assert_eq!(
+            hex::encode(keypair.public.to_bytes()),
+            "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+        );
+    }
+}
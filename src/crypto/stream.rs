@@ -0,0 +1,286 @@
+//! Streaming Authenticated Encryption
+//!
+//! Encrypts arbitrarily large streams with AES-256-GCM in bounded memory using the STREAM
+//! construction: the plaintext is split into fixed-size chunks, and chunk `i` is sealed under the
+//! nonce `prefix ‖ i ‖ last`, where `prefix` is a random 7-byte value, `i` a 32-bit big-endian
+//! counter and `last` a flag byte set only on the final chunk. Reordering, dropping or truncating
+//! chunks therefore fails authentication.
+//!
+//! The stream starts with the 7-byte nonce prefix, followed by the sealed chunks.
+
+use std::io::{self, Read, Write};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use rand_core::RngCore;
+use crate::crypto::secret::SecretKey;
+
+/// The plaintext size of every chunk except the last.
+pub const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The length of the random nonce prefix written at the start of the stream.
+pub const NONCE_PREFIX_SIZE: usize = 7;
+
+/// The size of the GCM tag appended to every chunk.
+const TAG_SIZE: usize = 16;
+
+/// Builds the nonce for chunk `counter`.
+fn chunk_nonce(prefix: &[u8; NONCE_PREFIX_SIZE], counter: u32, last: bool) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[..NONCE_PREFIX_SIZE].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_SIZE..11].copy_from_slice(&counter.to_be_bytes());
+    nonce[11] = u8::from(last);
+    nonce
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Encrypts everything written to it and forwards the ciphertext to an inner writer.
+///
+/// [`EncryptWriter::finish`] must be called once all data has been written. It seals the final
+/// chunk, and a stream dropped without it fails to decrypt.
+///
+/// # Example
+/// ```
+/// use std::io::{Read, Write};
+/// use zana::crypto::stream::{DecryptReader, EncryptWriter};
+/// use zana::crypto::symmetric::generate_random_key;
+///
+/// let key = generate_random_key();
+/// let mut writer = EncryptWriter::new(&key, Vec::new()).unwrap();
+/// writer.write_all(b"a very large file").unwrap();
+/// let ciphertext = writer.finish().unwrap();
+///
+/// let mut plaintext = Vec::new();
+/// DecryptReader::new(&key, ciphertext.as_slice()).read_to_end(&mut plaintext).unwrap();
+/// assert_eq!(plaintext, b"a very large file");
+/// ```
+pub struct EncryptWriter<W: Write> {
+    cipher: Aes256Gcm,
+    prefix: [u8; NONCE_PREFIX_SIZE],
+    counter: u32,
+    buffer: Vec<u8>,
+    inner: W,
+}
+
+impl<W: Write> EncryptWriter<W> {
+    /// Starts an encrypted stream with a random nonce prefix, writing the prefix to `inner`.
+    pub fn new(key: &SecretKey, mut inner: W) -> io::Result<Self> {
+        let mut prefix = [0u8; NONCE_PREFIX_SIZE];
+        OsRng.fill_bytes(&mut prefix);
+        inner.write_all(&prefix)?;
+
+        Ok(Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.expose_secret())),
+            prefix,
+            counter: 0,
+            buffer: Vec::with_capacity(CHUNK_SIZE),
+            inner,
+        })
+    }
+
+    /// Seals the buffered chunk and writes it out.
+    fn seal_chunk(&mut self, last: bool) -> io::Result<()> {
+        let nonce = chunk_nonce(&self.prefix, self.counter, last);
+        let sealed = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), self.buffer.as_slice())
+            .map_err(|_| io::Error::other("encryption failure"))?;
+        self.inner.write_all(&sealed)?;
+        self.buffer.clear();
+        self.counter = self
+            .counter
+            .checked_add(1)
+            .ok_or_else(|| io::Error::other("stream exceeds the maximum number of chunks"))?;
+        Ok(())
+    }
+
+    /// Seals the final chunk and returns the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.seal_chunk(true)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for EncryptWriter<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        if data.is_empty() {
+            return Ok(0);
+        }
+        // A full chunk is only sealed once more data arrives, since the last one is flagged
+        if self.buffer.len() == CHUNK_SIZE {
+            self.seal_chunk(false)?;
+        }
+        let taken = data.len().min(CHUNK_SIZE - self.buffer.len());
+        self.buffer.extend_from_slice(&data[..taken]);
+        Ok(taken)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Decrypts a stream produced by [`EncryptWriter`].
+///
+/// Any authentication failure, including a truncated stream, surfaces as an
+/// `io::ErrorKind::InvalidData` error. Plaintext is only released chunk by chunk after that
+/// chunk has been authenticated.
+pub struct DecryptReader<R: Read> {
+    cipher: Aes256Gcm,
+    prefix: Option<[u8; NONCE_PREFIX_SIZE]>,
+    counter: u32,
+    inner: R,
+    /// Ciphertext read ahead of the current chunk, to detect whether it is the last one.
+    pending: Vec<u8>,
+    plaintext: Vec<u8>,
+    position: usize,
+    finished: bool,
+}
+
+impl<R: Read> DecryptReader<R> {
+    /// Wraps a reader positioned at the start of an encrypted stream.
+    pub fn new(key: &SecretKey, inner: R) -> Self {
+        Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.expose_secret())),
+            prefix: None,
+            counter: 0,
+            inner,
+            pending: Vec::with_capacity(CHUNK_SIZE + TAG_SIZE + 1),
+            plaintext: Vec::new(),
+            position: 0,
+            finished: false,
+        }
+    }
+
+    /// Reads from the inner reader until `pending` holds `target` bytes or the input ends.
+    fn fill_pending(&mut self, target: usize) -> io::Result<()> {
+        let mut buffer = [0u8; 8192];
+        while self.pending.len() < target {
+            let wanted = (target - self.pending.len()).min(buffer.len());
+            match self.inner.read(&mut buffer[..wanted]) {
+                Ok(0) => break,
+                Ok(n) => self.pending.extend_from_slice(&buffer[..n]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Decrypts the next chunk into the plaintext buffer.
+    fn open_chunk(&mut self) -> io::Result<()> {
+        let prefix = match self.prefix {
+            Some(prefix) => prefix,
+            None => {
+                self.fill_pending(NONCE_PREFIX_SIZE)?;
+                if self.pending.len() < NONCE_PREFIX_SIZE {
+                    return Err(invalid_data("stream header is truncated"));
+                }
+                let mut prefix = [0u8; NONCE_PREFIX_SIZE];
+                prefix.copy_from_slice(&self.pending[..NONCE_PREFIX_SIZE]);
+                self.pending.drain(..NONCE_PREFIX_SIZE);
+                self.prefix = Some(prefix);
+                prefix
+            }
+        };
+
+        // One byte beyond a full chunk tells whether another chunk follows
+        let sealed_size = CHUNK_SIZE + TAG_SIZE;
+        self.fill_pending(sealed_size + 1)?;
+        let last = self.pending.len() <= sealed_size;
+        let chunk_len = self.pending.len().min(sealed_size);
+
+        let nonce = chunk_nonce(&prefix, self.counter, last);
+        self.plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(&nonce), &self.pending[..chunk_len])
+            .map_err(|_| invalid_data("stream authentication failed"))?;
+        self.pending.drain(..chunk_len);
+        self.position = 0;
+        self.finished = last;
+        self.counter = self
+            .counter
+            .checked_add(1)
+            .ok_or_else(|| invalid_data("stream exceeds the maximum number of chunks"))?;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for DecryptReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.plaintext.len() {
+            if self.finished {
+                return Ok(0);
+            }
+            self.open_chunk()?;
+        }
+
+        let n = buf.len().min(self.plaintext.len() - self.position);
+        buf[..n].copy_from_slice(&self.plaintext[self.position..self.position + n]);
+        self.position += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::symmetric::generate_random_key;
+
+    fn encrypt_all(key: &SecretKey, data: &[u8]) -> Vec<u8> {
+        let mut writer = EncryptWriter::new(key, Vec::new()).unwrap();
+        writer.write_all(data).unwrap();
+        writer.finish().unwrap()
+    }
+
+    fn decrypt_all(key: &SecretKey, ciphertext: &[u8]) -> io::Result<Vec<u8>> {
+        let mut plaintext = Vec::new();
+        DecryptReader::new(key, ciphertext).read_to_end(&mut plaintext)?;
+        Ok(plaintext)
+    }
+
+    fn sample(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 31 % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_round_trip_sizes() {
+        let key = generate_random_key();
+        for len in [0, 1, CHUNK_SIZE - 1, CHUNK_SIZE, CHUNK_SIZE + 1, 3 * CHUNK_SIZE + 123] {
+            let data = sample(len);
+            let ciphertext = encrypt_all(&key, &data);
+
+            let chunks = len.div_ceil(CHUNK_SIZE).max(1);
+            assert_eq!(ciphertext.len(), NONCE_PREFIX_SIZE + len + chunks * TAG_SIZE);
+            assert_eq!(decrypt_all(&key, &ciphertext).unwrap(), data, "len = {}", len);
+        }
+    }
+
+    #[test]
+    fn test_truncation_at_chunk_boundary_is_detected() {
+        let key = generate_random_key();
+        let ciphertext = encrypt_all(&key, &sample(2 * CHUNK_SIZE + 10));
+
+        let truncated = &ciphertext[..NONCE_PREFIX_SIZE + CHUNK_SIZE + TAG_SIZE];
+        let error = decrypt_all(&key, truncated).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let key = generate_random_key();
+        let mut ciphertext = encrypt_all(&key, &sample(CHUNK_SIZE + 10));
+        ciphertext[NONCE_PREFIX_SIZE + 5] ^= 1;
+        assert!(decrypt_all(&key, &ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_wrong_key_and_missing_header() {
+        let ciphertext = encrypt_all(&generate_random_key(), b"secret");
+        assert!(decrypt_all(&generate_random_key(), &ciphertext).is_err());
+        assert!(decrypt_all(&generate_random_key(), &ciphertext[..3]).is_err());
+    }
+}
@@ -1,7 +1,27 @@
 use aes_gcm::{Aes256Gcm, Key, Nonce};
-use aes_gcm::aead::{Aead, KeyInit, OsRng};
-use rand_core::RngCore;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_kw::{KekAes128, KekAes192, KekAes256};
 use sha2::{Sha256, Digest};
+use crate::crypto::error::CryptoError;
+use crate::crypto::random::{self, OsRandom};
+use crate::crypto::secret::{SecretBytes, SecretKey};
+
+/// The AES-256 key length in bytes.
+pub const KEY_SIZE: usize = 32;
+
+/// The AES-GCM nonce length in bytes.
+pub const NONCE_SIZE: usize = 12;
+
+/// Builds the cipher after checking the key and nonce lengths.
+fn cipher_for(key: &[u8], nonce: &[u8]) -> Result<Aes256Gcm, CryptoError> {
+    if key.len() != KEY_SIZE {
+        return Err(CryptoError::InvalidKeyLength { expected: KEY_SIZE, actual: key.len() });
+    }
+    if nonce.len() != NONCE_SIZE {
+        return Err(CryptoError::InvalidNonceLength { expected: NONCE_SIZE, actual: nonce.len() });
+    }
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)))
+}
 
 /// Encrypts the given plaintext using AES-256-GCM.
 ///
@@ -11,10 +31,11 @@ use sha2::{Sha256, Digest};
 /// - `plaintext`: The data to encrypt.
 ///
 /// # Returns
-/// The ciphertext.
-pub fn encrypt(key: &[u8; 32], nonce: &[u8; 12], plaintext: &[u8]) -> Vec<u8> {
-    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
-    cipher.encrypt(Nonce::from_slice(nonce), plaintext).expect("encryption failure")
+/// The ciphertext, or an error if the key or nonce has the wrong length.
+pub fn encrypt(key: &[u8], nonce: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    cipher_for(key, nonce)?
+        .encrypt(Nonce::from_slice(nonce), plaintext)
+        .map_err(|_| CryptoError::EncryptionFailed)
 }
 
 /// Decrypts the given ciphertext using AES-256-GCM.
@@ -25,30 +46,107 @@ pub fn encrypt(key: &[u8; 32], nonce: &[u8; 12], plaintext: &[u8]) -> Vec<u8> {
 /// - `ciphertext`: The encrypted data.
 ///
 /// # Returns
-/// The plaintext.
-pub fn decrypt(key: &[u8; 32], nonce: &[u8; 12], ciphertext: &[u8]) -> Vec<u8> {
-    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
-    cipher.decrypt(Nonce::from_slice(nonce), ciphertext).expect("decryption failure")
+/// The plaintext, or `CryptoError::DecryptionFailed` if the ciphertext does not authenticate.
+pub fn decrypt(key: &[u8], nonce: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    cipher_for(key, nonce)?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| CryptoError::DecryptionFailed)
+}
+
+/// Encrypts the given plaintext using AES-256-GCM, authenticating additional data.
+///
+/// The associated data (e.g. headers or version fields) is not encrypted and not included in
+/// the output, but it is bound into the authentication tag: decryption only succeeds with the
+/// exact same associated data.
+///
+/// # Arguments
+/// - `key`: A 256-bit key.
+/// - `nonce`: A unique 96-bit nonce.
+/// - `plaintext`: The data to encrypt.
+/// - `aad`: The additional authenticated data.
+///
+/// # Returns
+/// The ciphertext, or an error if the key or nonce has the wrong length.
+pub fn encrypt_with_aad(key: &[u8], nonce: &[u8], plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    cipher_for(key, nonce)?
+        .encrypt(Nonce::from_slice(nonce), Payload { msg: plaintext, aad })
+        .map_err(|_| CryptoError::EncryptionFailed)
+}
+
+/// Decrypts the given ciphertext using AES-256-GCM, verifying additional authenticated data.
+///
+/// # Arguments
+/// - `key`: A 256-bit key.
+/// - `nonce`: A unique 96-bit nonce.
+/// - `ciphertext`: The encrypted data.
+/// - `aad`: The additional authenticated data passed to [`encrypt_with_aad`].
+///
+/// # Returns
+/// The plaintext, or `CryptoError::DecryptionFailed` if the ciphertext or associated data does
+/// not authenticate.
+pub fn decrypt_with_aad(key: &[u8], nonce: &[u8], ciphertext: &[u8], aad: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    cipher_for(key, nonce)?
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad })
+        .map_err(|_| CryptoError::DecryptionFailed)
+}
+
+/// Wraps a data-encryption key under a key-encryption key with AES-KW (RFC 3394).
+///
+/// # Arguments
+/// - `kek`: The 128-, 192- or 256-bit key-encryption key.
+/// - `key`: The key to wrap; at least 16 bytes and a multiple of 8 bytes.
+///
+/// # Returns
+/// The wrapped key, 8 bytes longer than `key`.
+pub fn wrap_key(kek: &[u8], key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    if key.len() < 16 || !key.len().is_multiple_of(8) {
+        return Err(CryptoError::InvalidInput("wrapped keys must be at least 16 bytes and a multiple of 8"));
+    }
+    let wrapped = match kek.len() {
+        16 => KekAes128::new(kek.into()).wrap_vec(key),
+        24 => KekAes192::new(kek.into()).wrap_vec(key),
+        32 => KekAes256::new(kek.into()).wrap_vec(key),
+        actual => return Err(CryptoError::InvalidKeyLength { expected: KEY_SIZE, actual }),
+    };
+    wrapped.map_err(|_| CryptoError::EncryptionFailed)
+}
+
+/// Unwraps a key produced by [`wrap_key`], verifying its integrity check value.
+///
+/// # Returns
+/// The original key, or `CryptoError::DecryptionFailed` if the KEK is wrong or the wrapped key
+/// was modified.
+pub fn unwrap_key(kek: &[u8], wrapped: &[u8]) -> Result<SecretBytes, CryptoError> {
+    if wrapped.len() < 24 || !wrapped.len().is_multiple_of(8) {
+        return Err(CryptoError::InvalidInput("wrapped keys must be at least 24 bytes and a multiple of 8"));
+    }
+    let key = match kek.len() {
+        16 => KekAes128::new(kek.into()).unwrap_vec(wrapped),
+        24 => KekAes192::new(kek.into()).unwrap_vec(wrapped),
+        32 => KekAes256::new(kek.into()).unwrap_vec(wrapped),
+        actual => return Err(CryptoError::InvalidKeyLength { expected: KEY_SIZE, actual }),
+    };
+    key.map(SecretBytes::new).map_err(|_| CryptoError::DecryptionFailed)
 }
 
 /// Generates a random 256-bit key for encryption.
 ///
 /// # Returns
-/// A random 256-bit key.
-pub fn generate_random_key() -> [u8; 32] {
-    let mut key = [0u8; 32];
-    OsRng.fill_bytes(&mut key);
-    key
+/// A random 256-bit key, zeroized when dropped.
+pub fn generate_random_key() -> SecretKey {
+    random::generate_random_key()
 }
 
 /// Generates a random 96-bit nonce for encryption.
 ///
 /// # Returns
 /// A random 96-bit nonce.
-pub fn generate_random_nonce() -> [u8; 12] {
-    let mut nonce = [0u8; 12];
-    OsRng.fill_bytes(&mut nonce);
-    nonce
+///
+/// # Errors
+/// [`CryptoError::KeyGenerationFailed`] if the operating system's random number generator is
+/// unavailable.
+pub fn generate_random_nonce() -> Result<[u8; 12], CryptoError> {
+    random::random_array(&mut OsRandom)
 }
 
 /// Derives a key from a password using SHA-256.
@@ -57,13 +155,12 @@ pub fn generate_random_nonce() -> [u8; 12] {
 /// - `password`: The password to derive the key from.
 ///
 /// # Returns
-/// A 256-bit key derived from the password.
-pub fn derive_key_from_password(password: &str) -> [u8; 32] {
+/// A 256-bit key derived from the password, zeroized when dropped.
+pub fn derive_key_from_password(password: &str) -> SecretKey {
     let mut hasher = Sha256::new();
     hasher.update(password.as_bytes());
-    let result = hasher.finalize();
-    let mut key = [0u8; 32];
-    key.copy_from_slice(&result[..32]);
+    let mut key = SecretKey::new([0u8; 32]);
+    key.expose_secret_mut().copy_from_slice(&hasher.finalize());
     key
 }
 
@@ -103,15 +200,112 @@ mod tests {
     #[test]
     fn test_encrypt_decrypt() {
         let key = generate_random_key();
-        let nonce = generate_random_nonce();
+        let nonce = generate_random_nonce().unwrap();
         let plaintext = b"Hello, world!";
 
-        let ciphertext = encrypt(&key, &nonce, plaintext);
-        let decrypted = decrypt(&key, &nonce, &ciphertext);
+        let ciphertext = encrypt(key.expose_secret(), &nonce, plaintext).unwrap();
+        let decrypted = decrypt(key.expose_secret(), &nonce, &ciphertext).unwrap();
 
         assert_eq!(plaintext.to_vec(), decrypted);
     }
 
+    #[test]
+    fn test_tampered_ciphertext_is_an_error() {
+        let key = generate_random_key();
+        let nonce = generate_random_nonce().unwrap();
+
+        let mut ciphertext = encrypt(key.expose_secret(), &nonce, b"Hello, world!").unwrap();
+        ciphertext[0] ^= 1;
+        assert_eq!(decrypt(key.expose_secret(), &nonce, &ciphertext), Err(CryptoError::DecryptionFailed));
+    }
+
+    #[test]
+    fn test_invalid_key_and_nonce_lengths() {
+        let nonce = generate_random_nonce().unwrap();
+        assert_eq!(
+            encrypt(&[0u8; 16], &nonce, b"data"),
+            Err(CryptoError::InvalidKeyLength { expected: 32, actual: 16 })
+        );
+        assert_eq!(
+            decrypt(generate_random_key().expose_secret(), &nonce[..8], b"data"),
+            Err(CryptoError::InvalidNonceLength { expected: 12, actual: 8 })
+        );
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_with_aad() {
+        let key = generate_random_key();
+        let nonce = generate_random_nonce().unwrap();
+        let plaintext = b"Hello, world!";
+        let aad = b"zana-v1";
+
+        let ciphertext = encrypt_with_aad(key.expose_secret(), &nonce, plaintext, aad).unwrap();
+        assert_eq!(decrypt_with_aad(key.expose_secret(), &nonce, &ciphertext, aad).unwrap(), plaintext.to_vec());
+    }
+
+    #[test]
+    fn test_empty_aad_matches_plain_encryption() {
+        let key = generate_random_key();
+        let nonce = generate_random_nonce().unwrap();
+        let plaintext = b"Hello, world!";
+        let key = key.expose_secret();
+
+        assert_eq!(encrypt_with_aad(key, &nonce, plaintext, b""), encrypt(key, &nonce, plaintext));
+    }
+
+    #[test]
+    fn test_mismatched_aad_is_rejected() {
+        let key = generate_random_key();
+        let nonce = generate_random_nonce().unwrap();
+
+        let ciphertext = encrypt_with_aad(key.expose_secret(), &nonce, b"Hello, world!", b"zana-v1").unwrap();
+        assert_eq!(
+            decrypt_with_aad(key.expose_secret(), &nonce, &ciphertext, b"zana-v2"),
+            Err(CryptoError::DecryptionFailed)
+        );
+    }
+
+    #[test]
+    fn test_wrap_key_rfc3394_vectors() {
+        // RFC 3394, section 4.1: 128-bit key data with a 128-bit KEK
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let key = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        let wrapped = wrap_key(&kek, &key).unwrap();
+        assert_eq!(wrapped, hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap());
+        assert_eq!(unwrap_key(&kek, &wrapped).unwrap().expose_secret(), key);
+
+        // RFC 3394, section 4.6: 256-bit key data with a 256-bit KEK
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F").unwrap();
+        let key = hex::decode("00112233445566778899AABBCCDDEEFF000102030405060708090A0B0C0D0E0F").unwrap();
+        let wrapped = wrap_key(&kek, &key).unwrap();
+        assert_eq!(
+            wrapped,
+            hex::decode("28C9F404C4B810F4CBCCB35CFB87F8263F5786E2D80ED326CBC7F0E71A99F43BFB988B9B7A02DD21").unwrap()
+        );
+        assert_eq!(unwrap_key(&kek, &wrapped).unwrap().expose_secret(), key);
+    }
+
+    #[test]
+    fn test_unwrap_key_rejects_wrong_kek_and_tampering() {
+        let kek = generate_random_key();
+        let data_key = generate_random_key();
+        let mut wrapped = wrap_key(kek.expose_secret(), data_key.expose_secret()).unwrap();
+
+        assert_eq!(unwrap_key(generate_random_key().expose_secret(), &wrapped), Err(CryptoError::DecryptionFailed));
+        wrapped[10] ^= 1;
+        assert_eq!(unwrap_key(kek.expose_secret(), &wrapped), Err(CryptoError::DecryptionFailed));
+    }
+
+    #[test]
+    fn test_wrap_key_rejects_invalid_lengths() {
+        let kek = generate_random_key();
+        assert!(matches!(wrap_key(kek.expose_secret(), &[0u8; 12]), Err(CryptoError::InvalidInput(_))));
+        assert_eq!(
+            wrap_key(&[0u8; 20], &[0u8; 16]),
+            Err(CryptoError::InvalidKeyLength { expected: 32, actual: 20 })
+        );
+    }
+
     #[test]
     fn test_derive_key_from_password() {
         let password = "securepassword";
@@ -119,7 +313,7 @@ mod tests {
         let key2 = derive_key_from_password(password);
 
         assert_eq!(key1, key2);
-        assert_eq!(key1.len(), 32);
+        assert_eq!(key1.expose_secret().len(), 32);
     }
 
     #[test]
@@ -148,13 +342,13 @@ mod tests {
         let key2 = generate_random_key();
 
         assert_ne!(key1, key2); // Keys should be random and unique
-        assert_eq!(key1.len(), 32);
+        assert_eq!(key1.expose_secret().len(), 32);
     }
 
     #[test]
     fn test_generate_random_nonce() {
-        let nonce1 = generate_random_nonce();
-        let nonce2 = generate_random_nonce();
+        let nonce1 = generate_random_nonce().unwrap();
+        let nonce2 = generate_random_nonce().unwrap();
 
         assert_ne!(nonce1, nonce2); // Nonces should be random and unique
         assert_eq!(nonce1.len(), 12);
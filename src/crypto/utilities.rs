@@ -1,9 +1,265 @@
+//! Text Encodings
+//!
+//! Converts keys, signatures and other binary data to and from text:
+//! - hex;
+//! - base58 and base58check (the Bitcoin alphabet, with a 4-byte double SHA-256 checksum);
+//! - RFC 4648 base32, as used for OTP secrets;
+//! - RFC 4648 base64url, as used in JWTs and URLs;
+//! - length-prefixed fields (a 4-byte big-endian length followed by the data), as used in SSH
+//!   wire formats.
+//!
+//! Decoders return an [`EncodingError`] instead of panicking, so untrusted strings can be passed
+//! in directly.
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use crate::crypto::error::EncodingError;
+use crate::crypto::hash::sha256;
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// The length of the base58check checksum in bytes.
+pub const BASE58CHECK_CHECKSUM_SIZE: usize = 4;
+
+/// The size of the length prefix written by [`write_length_prefixed`].
+pub const LENGTH_PREFIX_SIZE: usize = 4;
+
+/// The error for the character starting at byte `position` of `encoded`.
+fn invalid_character(encoded: &str, position: usize) -> EncodingError {
+    let character = encoded[position..].chars().next().unwrap_or('\u{fffd}');
+    EncodingError::InvalidCharacter { character, position }
+}
+
+/// Encodes bytes as lowercase hex.
 pub fn to_hex(bytes: &[u8]) -> String {
     hex::encode(bytes)
 }
 
-pub fn from_hex(hex_str: &str) -> Vec<u8> {
-    hex::decode(hex_str).expect("Invalid hex string")
+/// Decodes hex in either case.
+///
+/// # Returns
+/// The bytes, or an error if the string has an odd length or a non-hex character.
+pub fn from_hex(hex_str: &str) -> Result<Vec<u8>, EncodingError> {
+    hex::decode(hex_str).map_err(|error| match error {
+        hex::FromHexError::InvalidHexCharacter { index, .. } => invalid_character(hex_str, index),
+        _ => EncodingError::InvalidLength(hex_str.len()),
+    })
+}
+
+/// Encodes bytes as base58. Each leading zero byte becomes a leading `1`.
+pub fn to_base58(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+    // Base-58 digits, least significant first
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for &byte in &bytes[zeros..] {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    std::iter::repeat_n('1', zeros)
+        .chain(digits.iter().rev().map(|&digit| BASE58_ALPHABET[digit as usize] as char))
+        .collect()
+}
+
+/// Decodes base58.
+///
+/// # Returns
+/// The bytes, or an error if the string contains a character outside the alphabet.
+pub fn from_base58(encoded: &str) -> Result<Vec<u8>, EncodingError> {
+    let zeros = encoded.bytes().take_while(|&c| c == b'1').count();
+    // Bytes, least significant first
+    let mut bytes: Vec<u8> = Vec::with_capacity(encoded.len() * 733 / 1000 + 1);
+    for (position, c) in encoded.bytes().enumerate().skip(zeros) {
+        let mut carry = BASE58_ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .ok_or_else(|| invalid_character(encoded, position))? as u32;
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    bytes.extend(std::iter::repeat_n(0, zeros));
+    bytes.reverse();
+    Ok(bytes)
+}
+
+fn base58_checksum(payload: &[u8]) -> [u8; BASE58CHECK_CHECKSUM_SIZE] {
+    let mut checksum = [0u8; BASE58CHECK_CHECKSUM_SIZE];
+    checksum.copy_from_slice(&sha256(&sha256(payload))[..BASE58CHECK_CHECKSUM_SIZE]);
+    checksum
+}
+
+/// Encodes a payload as base58check: base58 of the payload followed by the first four bytes of
+/// its double SHA-256 hash. Callers prepend any version byte to the payload.
+pub fn to_base58check(payload: &[u8]) -> String {
+    let mut data = payload.to_vec();
+    data.extend_from_slice(&base58_checksum(payload));
+    to_base58(&data)
+}
+
+/// Decodes base58check and verifies the checksum.
+///
+/// # Returns
+/// The payload without the checksum, or an error if the string is not valid base58, is too short
+/// to hold a checksum, or its checksum does not match.
+pub fn from_base58check(encoded: &str) -> Result<Vec<u8>, EncodingError> {
+    let mut data = from_base58(encoded)?;
+    if data.len() < BASE58CHECK_CHECKSUM_SIZE {
+        return Err(EncodingError::TooShort { min: BASE58CHECK_CHECKSUM_SIZE, actual: data.len() });
+    }
+    let checksum = data.split_off(data.len() - BASE58CHECK_CHECKSUM_SIZE);
+    if checksum != base58_checksum(&data) {
+        return Err(EncodingError::ChecksumMismatch);
+    }
+    Ok(data)
+}
+
+fn encode_base32(bytes: &[u8], padding: bool) -> String {
+    let mut output = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    for chunk in bytes.chunks(5) {
+        let mut buffer = [0u8; 5];
+        buffer[..chunk.len()].copy_from_slice(chunk);
+        let bits = buffer.iter().fold(0u64, |acc, &byte| (acc << 8) | byte as u64);
+        let symbols = (chunk.len() * 8).div_ceil(5);
+        for i in 0..8 {
+            if i < symbols {
+                output.push(BASE32_ALPHABET[((bits >> (35 - 5 * i)) & 31) as usize] as char);
+            } else if padding {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+/// Encodes bytes as RFC 4648 base32, padded with `=` to a multiple of eight characters.
+pub fn to_base32(bytes: &[u8]) -> String {
+    encode_base32(bytes, true)
+}
+
+/// Encodes bytes as RFC 4648 base32 without padding, the form authenticator apps expect.
+pub fn to_base32_unpadded(bytes: &[u8]) -> String {
+    encode_base32(bytes, false)
+}
+
+/// Decodes RFC 4648 base32, with or without padding. Lowercase letters are accepted.
+///
+/// # Returns
+/// The bytes, or an error if the string contains a character outside the alphabet, has padding
+/// in the middle, or has a length no encoder produces.
+pub fn from_base32(encoded: &str) -> Result<Vec<u8>, EncodingError> {
+    let symbols = encoded.trim_end_matches('=');
+    if symbols.contains('=') {
+        return Err(EncodingError::InvalidPadding);
+    }
+    if !matches!(symbols.len() % 8, 0 | 2 | 4 | 5 | 7) {
+        return Err(EncodingError::InvalidLength(symbols.len()));
+    }
+    let mut output = Vec::with_capacity(symbols.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for (position, c) in symbols.bytes().enumerate() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a == c.to_ascii_uppercase())
+            .ok_or_else(|| invalid_character(encoded, position))? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Ok(output)
+}
+
+/// Encodes bytes as RFC 4648 base64url without padding.
+pub fn to_base64url(bytes: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Decodes RFC 4648 base64url, with or without padding.
+///
+/// # Returns
+/// The bytes, or an error if the string is not valid base64url.
+pub fn from_base64url(encoded: &str) -> Result<Vec<u8>, EncodingError> {
+    let symbols = encoded.trim_end_matches('=');
+    URL_SAFE_NO_PAD.decode(symbols).map_err(|error| match error {
+        base64::DecodeError::InvalidByte(position, _) | base64::DecodeError::InvalidLastSymbol(position, _) => {
+            if symbols.as_bytes()[position] == b'=' {
+                EncodingError::InvalidPadding
+            } else {
+                invalid_character(encoded, position)
+            }
+        }
+        base64::DecodeError::InvalidPadding => EncodingError::InvalidPadding,
+        base64::DecodeError::InvalidLength => EncodingError::InvalidLength(symbols.len()),
+    })
+}
+
+/// Appends `data` to `buffer` behind its length as a 4-byte big-endian integer.
+///
+/// # Errors
+/// [`EncodingError::TooLong`] if `data` is 4 GiB or longer, leaving `buffer` unchanged.
+pub fn write_length_prefixed(buffer: &mut Vec<u8>, data: &[u8]) -> Result<(), EncodingError> {
+    let length = u32::try_from(data.len())
+        .map_err(|_| EncodingError::TooLong { max: u32::MAX as usize, actual: data.len() })?;
+    buffer.extend_from_slice(&length.to_be_bytes());
+    buffer.extend_from_slice(data);
+    Ok(())
+}
+
+/// Encodes a sequence of fields, each behind its length.
+pub fn encode_length_prefixed<'a>(fields: impl IntoIterator<Item = &'a [u8]>) -> Result<Vec<u8>, EncodingError> {
+    let mut buffer = Vec::new();
+    for field in fields {
+        write_length_prefixed(&mut buffer, field)?;
+    }
+    Ok(buffer)
+}
+
+/// Reads one length-prefixed field from the start of `input`.
+///
+/// # Returns
+/// The field and the input following it, or `EncodingError::TooShort` if the input ends before
+/// the length or the field.
+pub fn read_length_prefixed(input: &[u8]) -> Result<(&[u8], &[u8]), EncodingError> {
+    let (length, rest) = input
+        .split_first_chunk::<LENGTH_PREFIX_SIZE>()
+        .ok_or(EncodingError::TooShort { min: LENGTH_PREFIX_SIZE, actual: input.len() })?;
+    let length = u32::from_be_bytes(*length) as usize;
+    if rest.len() < length {
+        return Err(EncodingError::TooShort { min: LENGTH_PREFIX_SIZE + length, actual: input.len() });
+    }
+    Ok(rest.split_at(length))
+}
+
+/// Decodes input written by [`encode_length_prefixed`] into its fields.
+///
+/// # Returns
+/// The fields, or `EncodingError::TooShort` if the last field is truncated.
+pub fn decode_length_prefixed(mut input: &[u8]) -> Result<Vec<&[u8]>, EncodingError> {
+    let mut fields = Vec::new();
+    while !input.is_empty() {
+        let (field, rest) = read_length_prefixed(input)?;
+        fields.push(field);
+        input = rest;
+    }
+    Ok(fields)
 }
 
 #[cfg(test)]
@@ -14,8 +270,88 @@ mod tests {
     fn test_hex_conversion() {
         let data = b"zana quantum-ai";
         let hex_str = to_hex(data);
-        let bytes = from_hex(&hex_str);
+        let bytes = from_hex(&hex_str).unwrap();
 
         assert_eq!(bytes, data, "Hex conversion failed");
     }
+
+    #[test]
+    fn test_from_hex_rejects_bad_input() {
+        assert_eq!(from_hex("ABcd").unwrap(), vec![0xab, 0xcd]);
+        assert_eq!(from_hex("abc"), Err(EncodingError::InvalidLength(3)));
+        assert_eq!(from_hex("zz"), Err(EncodingError::InvalidCharacter { character: 'z', position: 0 }));
+        assert_eq!(from_hex("00éab"), Err(EncodingError::InvalidCharacter { character: 'é', position: 2 }));
+    }
+
+    #[test]
+    fn test_base58() {
+        assert_eq!(to_base58(b"Hello World!"), "2NEpo7TZRRrLZSi2U");
+        assert_eq!(to_base58(&[0, 0, 0x28, 0x7f, 0xb4, 0xcd]), "11233QC4");
+        assert_eq!(to_base58(&[]), "");
+        assert_eq!(from_base58("2NEpo7TZRRrLZSi2U").unwrap(), b"Hello World!");
+        assert_eq!(from_base58("11233QC4").unwrap(), vec![0, 0, 0x28, 0x7f, 0xb4, 0xcd]);
+        assert!(from_base58("0OIl").is_err());
+    }
+
+    #[test]
+    fn test_base58check() {
+        // The address of the hash160 of the all-zero public key, with version byte 0
+        let mut payload = vec![0u8];
+        payload.extend_from_slice(&from_hex("010966776006953d5567439e5e39f86a0d273bee").unwrap());
+        let address = to_base58check(&payload);
+        assert_eq!(address, "16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvM");
+        assert_eq!(from_base58check(&address).unwrap(), payload);
+
+        let tampered = address.replacen('U', "V", 1);
+        assert_eq!(from_base58check(&tampered), Err(EncodingError::ChecksumMismatch));
+        assert_eq!(from_base58check("1"), Err(EncodingError::TooShort { min: 4, actual: 1 }));
+        assert_eq!(from_base58("1l"), Err(EncodingError::InvalidCharacter { character: 'l', position: 1 }));
+    }
+
+    #[test]
+    fn test_base32_rfc4648_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "MY======"),
+            ("fo", "MZXQ===="),
+            ("foo", "MZXW6==="),
+            ("foob", "MZXW6YQ="),
+            ("fooba", "MZXW6YTB"),
+            ("foobar", "MZXW6YTBOI======"),
+        ];
+        for (plain, encoded) in vectors {
+            assert_eq!(to_base32(plain.as_bytes()), encoded);
+            assert_eq!(to_base32_unpadded(plain.as_bytes()), encoded.trim_end_matches('='));
+            assert_eq!(from_base32(encoded).unwrap(), plain.as_bytes());
+            assert_eq!(from_base32(&encoded.to_lowercase().replace('=', "")).unwrap(), plain.as_bytes());
+        }
+        assert_eq!(from_base32("MZXW6YT1"), Err(EncodingError::InvalidCharacter { character: '1', position: 7 }));
+        assert_eq!(from_base32("MZX"), Err(EncodingError::InvalidLength(3)));
+        assert_eq!(from_base32("MY==MY=="), Err(EncodingError::InvalidPadding));
+    }
+
+    #[test]
+    fn test_base64url() {
+        let bytes = [0xfb, 0xff, 0xbf, 0x01];
+        assert_eq!(to_base64url(&bytes), "-_-_AQ");
+        assert_eq!(from_base64url("-_-_AQ").unwrap(), bytes);
+        assert_eq!(from_base64url("-_-_AQ==").unwrap(), bytes);
+        assert_eq!(from_base64url("+/+/AQ"), Err(EncodingError::InvalidCharacter { character: '+', position: 0 }));
+        assert_eq!(from_base64url("A"), Err(EncodingError::InvalidLength(1)));
+    }
+
+    #[test]
+    fn test_length_prefixed() {
+        let encoded = encode_length_prefixed([&b"ssh-ed25519"[..], b"", b"key"]).unwrap();
+        assert_eq!(&encoded[..4], &[0, 0, 0, 11]);
+        assert_eq!(decode_length_prefixed(&encoded).unwrap(), vec![&b"ssh-ed25519"[..], b"", b"key"]);
+
+        let (field, rest) = read_length_prefixed(&encoded).unwrap();
+        assert_eq!((field, rest.len()), (&b"ssh-ed25519"[..], 11));
+        assert_eq!(read_length_prefixed(&[0, 0]), Err(EncodingError::TooShort { min: 4, actual: 2 }));
+        assert_eq!(
+            decode_length_prefixed(&encoded[..encoded.len() - 1]),
+            Err(EncodingError::TooShort { min: 7, actual: 6 })
+        );
+    }
 }
@@ -0,0 +1,69 @@
+//! Errors
+//!
+//! Every module returns its own error type: [`CircuitError`] while building circuits,
+//! [`SimulationError`] while simulating them, [`CryptoError`] from the `crypto` module,
+//! [`AgentError`] from agents and [`ConfigError`] while loading a `zana.toml`. [`ZanaError`]
+//! wraps any of them, for applications that call into several modules and propagate failures
+//! with `?`.
+
+use thiserror::Error;
+#[cfg(all(feature = "agents", not(target_arch = "wasm32")))]
+pub use crate::agents::AgentError;
+pub use crate::circuit::error::{CircuitError, SimulationError};
+pub use crate::config::ConfigError;
+pub use crate::crypto::error::{CryptoError, EncodingError};
+
+/// An error raised anywhere in the crate.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ZanaError {
+    #[error(transparent)]
+    Circuit(#[from] CircuitError),
+    #[error(transparent)]
+    Simulation(#[from] SimulationError),
+    #[error(transparent)]
+    Crypto(#[from] CryptoError),
+    #[cfg(all(feature = "agents", not(target_arch = "wasm32")))]
+    #[error(transparent)]
+    Agent(#[from] AgentError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+}
+
+impl From<EncodingError> for ZanaError {
+    fn from(error: EncodingError) -> Self {
+        ZanaError::Crypto(error.into())
+    }
+}
+
+/// A result whose error is a [`ZanaError`].
+pub type Result<T, E = ZanaError> = std::result::Result<T, E>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::{gates, QuantumCircuit};
+    use crate::crypto::utilities::from_hex;
+
+    fn bell_probability(qubits: usize, hex: &str) -> Result<f64> {
+        let mut circuit = QuantumCircuit::new(qubits);
+        circuit.try_add_gate(gates::hadamard(), vec![0])?;
+        circuit.try_add_gate(gates::cnot(), vec![1, 0])?;
+        let state = circuit.try_simulate()?.statevector;
+        let index = from_hex(hex)?[0] as usize;
+        Ok(state.probabilities().get(&index).copied().unwrap_or(0.0))
+    }
+
+    #[test]
+    fn test_errors_propagate_into_zana_error() {
+        assert!((bell_probability(2, "03").unwrap() - 0.5).abs() < 1e-12);
+        assert_eq!(
+            bell_probability(1, "03"),
+            Err(ZanaError::Circuit(CircuitError::QubitOutOfRange { qubit: 1, qubits: 1 }))
+        );
+        assert!(matches!(bell_probability(2, "zz"), Err(ZanaError::Crypto(CryptoError::Encoding(_)))));
+        assert_eq!(
+            bell_probability(0, "00").unwrap_err().to_string(),
+            "qubit 0 is out of bounds for a circuit with 0 qubits"
+        );
+    }
+}
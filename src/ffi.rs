@@ -0,0 +1,452 @@
+//! C Bindings
+//!
+//! `extern "C"` functions for embedding zana in C, C++, Go and other languages with a C foreign
+//! function interface, built with the `ffi` feature. The matching header is kept in
+//! `include/zana.h`; the build generates it as `zana.h` in its `OUT_DIR`, and refreshes a copy
+//! wherever `ZANA_HEADER_DIR` points:
+//!
+//! ```text
+//! ZANA_HEADER_DIR=include cargo build --features ffi
+//! ```
+//!
+//! Every function that can fail returns a [`ZanaStatus`], and [`zana_status_message`] describes it.
+//! Results are written to buffers the caller owns, and their lengths are checked. Circuits are
+//! opaque handles, created by [`zana_circuit_new`] and released by [`zana_circuit_free`].
+//!
+//! ```c
+//! #include "zana.h"
+//!
+//! ZanaCircuit *circuit = zana_circuit_new(2);
+//! zana_circuit_h(circuit, 0);
+//! zana_circuit_cnot(circuit, 0, 1);
+//! double probabilities[4];
+//! zana_circuit_probabilities(circuit, probabilities, 4); // 0.5, 0, 0, 0.5
+//! zana_circuit_free(circuit);
+//! ```
+
+use std::ffi::c_char;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::slice;
+use ed25519_dalek::{Signature, VerifyingKey};
+use crate::circuit::gates::{self, Gate};
+use crate::circuit::QuantumCircuit;
+use crate::crypto::hash;
+use crate::crypto::signatures;
+use crate::crypto::symmetric;
+
+/// The bytes AES-GCM adds to a plaintext for its authentication tag.
+pub const ZANA_AES_GCM_TAG_LENGTH: usize = 16;
+
+/// The result of a call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZanaStatus {
+    Ok = 0,
+    /// A required pointer was null.
+    NullPointer = 1,
+    /// A qubit index, key or nonce was invalid.
+    InvalidArgument = 2,
+    /// An output buffer had the wrong length.
+    BufferLength = 3,
+    /// A signature did not verify.
+    InvalidSignature = 4,
+    /// A ciphertext did not authenticate.
+    DecryptionFailed = 5,
+    /// zana panicked; this is a bug.
+    Internal = 6,
+}
+
+/// Describes a status, as a static NUL-terminated string that must not be freed.
+#[no_mangle]
+pub extern "C" fn zana_status_message(status: ZanaStatus) -> *const c_char {
+    let message: &'static [u8] = match status {
+        ZanaStatus::Ok => b"ok\0",
+        ZanaStatus::NullPointer => b"a required pointer was null\0",
+        ZanaStatus::InvalidArgument => b"invalid argument\0",
+        ZanaStatus::BufferLength => b"an output buffer has the wrong length\0",
+        ZanaStatus::InvalidSignature => b"the signature does not verify\0",
+        ZanaStatus::DecryptionFailed => b"the ciphertext could not be authenticated\0",
+        ZanaStatus::Internal => b"internal error\0",
+    };
+    message.as_ptr().cast()
+}
+
+/// Runs `body`, turning a panic into [`ZanaStatus::Internal`] instead of unwinding into C.
+fn guard(body: impl FnOnce() -> Result<(), ZanaStatus>) -> ZanaStatus {
+    match catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => ZanaStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => ZanaStatus::Internal,
+    }
+}
+
+/// The `len` bytes at `data`. Null is allowed for an empty input.
+unsafe fn input<'a>(data: *const u8, len: usize) -> Result<&'a [u8], ZanaStatus> {
+    match (data.is_null(), len) {
+        (_, 0) => Ok(&[]),
+        (true, _) => Err(ZanaStatus::NullPointer),
+        (false, _) => Ok(slice::from_raw_parts(data, len)),
+    }
+}
+
+/// The `N` bytes at `data`.
+unsafe fn input_array<'a, const N: usize>(data: *const u8) -> Result<&'a [u8; N], ZanaStatus> {
+    data.cast::<[u8; N]>().as_ref().ok_or(ZanaStatus::NullPointer)
+}
+
+/// Copies `bytes` to the `len`-byte buffer at `out`, which must fit them exactly.
+unsafe fn output(bytes: &[u8], out: *mut u8, len: usize) -> Result<(), ZanaStatus> {
+    if len != bytes.len() {
+        return Err(ZanaStatus::BufferLength);
+    }
+    if out.is_null() && len > 0 {
+        return Err(ZanaStatus::NullPointer);
+    }
+    if len > 0 {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), out, len);
+    }
+    Ok(())
+}
+
+/// A quantum circuit, simulated from |0…0⟩.
+pub struct ZanaCircuit {
+    inner: QuantumCircuit,
+}
+
+/// Creates a circuit of `qubits` qubits, to be released with [`zana_circuit_free`].
+#[no_mangle]
+pub extern "C" fn zana_circuit_new(qubits: usize) -> *mut ZanaCircuit {
+    Box::into_raw(Box::new(ZanaCircuit { inner: QuantumCircuit::new(qubits) }))
+}
+
+/// Releases a circuit. Does nothing for null.
+///
+/// # Safety
+/// `circuit` must be null or come from [`zana_circuit_new`], and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn zana_circuit_free(circuit: *mut ZanaCircuit) {
+    if !circuit.is_null() {
+        drop(Box::from_raw(circuit));
+    }
+}
+
+/// The number of qubits of a circuit, or 0 for null.
+///
+/// # Safety
+/// `circuit` must be null or a live circuit.
+#[no_mangle]
+pub unsafe extern "C" fn zana_circuit_qubits(circuit: *const ZanaCircuit) -> usize {
+    circuit.as_ref().map_or(0, |circuit| circuit.inner.qubits)
+}
+
+unsafe fn add_gate(circuit: *mut ZanaCircuit, gate: impl FnOnce() -> Gate, qubits: &[usize]) -> ZanaStatus {
+    guard(|| {
+        let circuit = circuit.as_mut().ok_or(ZanaStatus::NullPointer)?;
+        circuit.inner.try_add_gate(gate(), qubits.to_vec()).map_err(|_| ZanaStatus::InvalidArgument)
+    })
+}
+
+/// Adds a Hadamard gate.
+///
+/// # Safety
+/// `circuit` must be null or a live circuit.
+#[no_mangle]
+pub unsafe extern "C" fn zana_circuit_h(circuit: *mut ZanaCircuit, qubit: usize) -> ZanaStatus {
+    add_gate(circuit, gates::hadamard, &[qubit])
+}
+
+/// Adds a Pauli-X gate.
+///
+/// # Safety
+/// `circuit` must be null or a live circuit.
+#[no_mangle]
+pub unsafe extern "C" fn zana_circuit_x(circuit: *mut ZanaCircuit, qubit: usize) -> ZanaStatus {
+    add_gate(circuit, gates::pauli_x, &[qubit])
+}
+
+/// Adds a Pauli-Y gate.
+///
+/// # Safety
+/// `circuit` must be null or a live circuit.
+#[no_mangle]
+pub unsafe extern "C" fn zana_circuit_y(circuit: *mut ZanaCircuit, qubit: usize) -> ZanaStatus {
+    add_gate(circuit, gates::pauli_y, &[qubit])
+}
+
+/// Adds a Pauli-Z gate.
+///
+/// # Safety
+/// `circuit` must be null or a live circuit.
+#[no_mangle]
+pub unsafe extern "C" fn zana_circuit_z(circuit: *mut ZanaCircuit, qubit: usize) -> ZanaStatus {
+    add_gate(circuit, gates::pauli_z, &[qubit])
+}
+
+/// Adds a rotation of `theta` radians about the X axis.
+///
+/// # Safety
+/// `circuit` must be null or a live circuit.
+#[no_mangle]
+pub unsafe extern "C" fn zana_circuit_rx(circuit: *mut ZanaCircuit, qubit: usize, theta: f64) -> ZanaStatus {
+    add_gate(circuit, || gates::rotation_x(theta), &[qubit])
+}
+
+/// Adds a rotation of `theta` radians about the Y axis.
+///
+/// # Safety
+/// `circuit` must be null or a live circuit.
+#[no_mangle]
+pub unsafe extern "C" fn zana_circuit_ry(circuit: *mut ZanaCircuit, qubit: usize, theta: f64) -> ZanaStatus {
+    add_gate(circuit, || gates::rotation_y(theta), &[qubit])
+}
+
+/// Adds a rotation of `theta` radians about the Z axis.
+///
+/// # Safety
+/// `circuit` must be null or a live circuit.
+#[no_mangle]
+pub unsafe extern "C" fn zana_circuit_rz(circuit: *mut ZanaCircuit, qubit: usize, theta: f64) -> ZanaStatus {
+    add_gate(circuit, || gates::rotation_z(theta), &[qubit])
+}
+
+/// Adds a CNOT flipping `target` when `control` is set.
+///
+/// # Safety
+/// `circuit` must be null or a live circuit.
+#[no_mangle]
+pub unsafe extern "C" fn zana_circuit_cnot(circuit: *mut ZanaCircuit, control: usize, target: usize) -> ZanaStatus {
+    // `gates::cnot()` takes its qubits as `[target, control]`, like `algorithms::add_cnot`.
+    add_gate(circuit, gates::cnot, &[target, control])
+}
+
+/// Adds a SWAP gate.
+///
+/// # Safety
+/// `circuit` must be null or a live circuit.
+#[no_mangle]
+pub unsafe extern "C" fn zana_circuit_swap(circuit: *mut ZanaCircuit, first: usize, second: usize) -> ZanaStatus {
+    add_gate(circuit, gates::swap, &[first, second])
+}
+
+/// Simulates the circuit and writes the probability of measuring each basis state, indexed by
+/// the state, to `out`, which holds `len` = 2^qubits values.
+///
+/// # Safety
+/// `circuit` must be null or a live circuit, and `out` must be null or point to `len` doubles.
+#[no_mangle]
+pub unsafe extern "C" fn zana_circuit_probabilities(circuit: *const ZanaCircuit, out: *mut f64, len: usize) -> ZanaStatus {
+    guard(|| {
+        let circuit = circuit.as_ref().ok_or(ZanaStatus::NullPointer)?;
+        if circuit.inner.qubits >= usize::BITS as usize || len != 1 << circuit.inner.qubits {
+            return Err(ZanaStatus::BufferLength);
+        }
+        if out.is_null() {
+            return Err(ZanaStatus::NullPointer);
+        }
+        let state = circuit.inner.try_simulate().map_err(|_| ZanaStatus::InvalidArgument)?.statevector;
+        let out = slice::from_raw_parts_mut(out, len);
+        out.fill(0.0);
+        for (index, probability) in state.probabilities() {
+            out[index] = probability;
+        }
+        Ok(())
+    })
+}
+
+/// Writes the SHA-256 digest of `len` bytes at `data` to the 32 bytes at `out`.
+///
+/// # Safety
+/// `data` must point to `len` bytes (or be null if `len` is 0), and `out` to 32 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn zana_sha256(data: *const u8, len: usize, out: *mut u8) -> ZanaStatus {
+    guard(|| output(&hash::sha256(input(data, len)?), out, 32))
+}
+
+/// Writes the SHA3-256 digest of `len` bytes at `data` to the 32 bytes at `out`.
+///
+/// # Safety
+/// `data` must point to `len` bytes (or be null if `len` is 0), and `out` to 32 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn zana_sha3_256(data: *const u8, len: usize, out: *mut u8) -> ZanaStatus {
+    guard(|| output(&hash::sha3_256(input(data, len)?), out, 32))
+}
+
+/// Writes the BLAKE2b-512 digest of `len` bytes at `data` to the 64 bytes at `out`.
+///
+/// # Safety
+/// `data` must point to `len` bytes (or be null if `len` is 0), and `out` to 64 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn zana_blake2b512(data: *const u8, len: usize, out: *mut u8) -> ZanaStatus {
+    guard(|| output(&hash::blake2b512(input(data, len)?), out, 64))
+}
+
+/// Generates an Ed25519 key, writing its 32-byte secret to `secret_out` and its 32-byte public
+/// key to `public_out`.
+///
+/// # Safety
+/// `secret_out` and `public_out` must each point to 32 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn zana_ed25519_generate(secret_out: *mut u8, public_out: *mut u8) -> ZanaStatus {
+    guard(|| {
+        let keypair = signatures::generate_keypair();
+        output(&keypair.to_bytes(), secret_out, 32)?;
+        output(&keypair.verifying_key().to_bytes(), public_out, 32)
+    })
+}
+
+/// Signs `len` bytes at `message` with a 32-byte secret, writing the 64-byte signature to
+/// `signature_out`.
+///
+/// # Safety
+/// `secret` must point to 32 bytes, `message` to `len` bytes (or be null if `len` is 0), and
+/// `signature_out` to 64 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn zana_ed25519_sign(
+    secret: *const u8,
+    message: *const u8,
+    len: usize,
+    signature_out: *mut u8,
+) -> ZanaStatus {
+    guard(|| {
+        let keypair = signatures::keypair_from_seed(*input_array::<32>(secret)?);
+        let signature = signatures::sign_message(&keypair, input(message, len)?);
+        output(&signature.to_bytes(), signature_out, 64)
+    })
+}
+
+/// Checks a 64-byte signature of `len` bytes at `message` against a 32-byte public key, returning
+/// [`ZanaStatus::Ok`] if it verifies and [`ZanaStatus::InvalidSignature`] otherwise.
+///
+/// # Safety
+/// `public_key` must point to 32 bytes, `message` to `len` bytes (or be null if `len` is 0), and
+/// `signature` to 64 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn zana_ed25519_verify(
+    public_key: *const u8,
+    message: *const u8,
+    len: usize,
+    signature: *const u8,
+) -> ZanaStatus {
+    guard(|| {
+        let public_key = VerifyingKey::from_bytes(input_array::<32>(public_key)?).map_err(|_| ZanaStatus::InvalidArgument)?;
+        let signature = Signature::from_bytes(input_array::<64>(signature)?);
+        if signatures::verify_message(&public_key, input(message, len)?, &signature) {
+            Ok(())
+        } else {
+            Err(ZanaStatus::InvalidSignature)
+        }
+    })
+}
+
+/// Encrypts `len` bytes at `plaintext` with AES-256-GCM under a 32-byte key and a 12-byte nonce,
+/// writing the ciphertext to `out`, which holds `out_len` = `len` + 16 bytes.
+///
+/// # Safety
+/// `key` must point to 32 bytes, `nonce` to 12, `plaintext` to `len` bytes (or be null if `len`
+/// is 0), and `out` to `out_len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn zana_aes_gcm_encrypt(
+    key: *const u8,
+    nonce: *const u8,
+    plaintext: *const u8,
+    len: usize,
+    out: *mut u8,
+    out_len: usize,
+) -> ZanaStatus {
+    guard(|| {
+        let ciphertext = symmetric::encrypt(input_array::<32>(key)?, input_array::<12>(nonce)?, input(plaintext, len)?)
+            .map_err(|_| ZanaStatus::InvalidArgument)?;
+        output(&ciphertext, out, out_len)
+    })
+}
+
+/// Decrypts `len` bytes at `ciphertext` from [`zana_aes_gcm_encrypt`], writing the plaintext to
+/// `out`, which holds `out_len` = `len` - 16 bytes.
+///
+/// # Safety
+/// `key` must point to 32 bytes, `nonce` to 12, `ciphertext` to `len` bytes, and `out` to
+/// `out_len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn zana_aes_gcm_decrypt(
+    key: *const u8,
+    nonce: *const u8,
+    ciphertext: *const u8,
+    len: usize,
+    out: *mut u8,
+    out_len: usize,
+) -> ZanaStatus {
+    guard(|| {
+        if len.checked_sub(ZANA_AES_GCM_TAG_LENGTH) != Some(out_len) {
+            return Err(ZanaStatus::BufferLength);
+        }
+        let plaintext = symmetric::decrypt(input_array::<32>(key)?, input_array::<12>(nonce)?, input(ciphertext, len)?)
+            .map_err(|_| ZanaStatus::DecryptionFailed)?;
+        output(&plaintext, out, out_len)
+    })
+}
+
+/// Writes `len` random bytes from the operating system to `out`, for keys and nonces.
+///
+/// # Safety
+/// `out` must point to `len` writable bytes (or be null if `len` is 0).
+#[no_mangle]
+pub unsafe extern "C" fn zana_random_bytes(out: *mut u8, len: usize) -> ZanaStatus {
+    guard(|| {
+        let mut bytes = vec![0u8; len];
+        rand::RngCore::fill_bytes(&mut rand::rngs::OsRng, &mut bytes);
+        output(&bytes, out, len)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    #[test]
+    fn test_circuit() {
+        unsafe {
+            let circuit = zana_circuit_new(2);
+            assert_eq!(zana_circuit_h(circuit, 0), ZanaStatus::Ok);
+            assert_eq!(zana_circuit_cnot(circuit, 0, 1), ZanaStatus::Ok);
+            assert_eq!(zana_circuit_x(circuit, 2), ZanaStatus::InvalidArgument);
+            assert_eq!(zana_circuit_h(ptr::null_mut(), 0), ZanaStatus::NullPointer);
+
+            let mut probabilities = [0.0; 4];
+            assert_eq!(zana_circuit_probabilities(circuit, probabilities.as_mut_ptr(), 3), ZanaStatus::BufferLength);
+            assert_eq!(zana_circuit_probabilities(circuit, probabilities.as_mut_ptr(), 4), ZanaStatus::Ok);
+            assert!((probabilities[0] - 0.5).abs() < 1e-12 && (probabilities[3] - 0.5).abs() < 1e-12);
+            zana_circuit_free(circuit);
+        }
+    }
+
+    #[test]
+    fn test_crypto() {
+        unsafe {
+            let (mut secret, mut public_key, mut signature) = ([0u8; 32], [0u8; 32], [0u8; 64]);
+            assert_eq!(zana_ed25519_generate(secret.as_mut_ptr(), public_key.as_mut_ptr()), ZanaStatus::Ok);
+            let message = b"deed";
+            zana_ed25519_sign(secret.as_ptr(), message.as_ptr(), message.len(), signature.as_mut_ptr());
+            assert_eq!(zana_ed25519_verify(public_key.as_ptr(), message.as_ptr(), 4, signature.as_ptr()), ZanaStatus::Ok);
+            assert_eq!(
+                zana_ed25519_verify(public_key.as_ptr(), message.as_ptr(), 3, signature.as_ptr()),
+                ZanaStatus::InvalidSignature
+            );
+
+            let (key, nonce) = ([7u8; 32], [1u8; 12]);
+            let mut ciphertext = [0u8; 4 + ZANA_AES_GCM_TAG_LENGTH];
+            let status = zana_aes_gcm_encrypt(key.as_ptr(), nonce.as_ptr(), message.as_ptr(), 4, ciphertext.as_mut_ptr(), 20);
+            assert_eq!(status, ZanaStatus::Ok);
+            let mut plaintext = [0u8; 4];
+            let status = zana_aes_gcm_decrypt(key.as_ptr(), nonce.as_ptr(), ciphertext.as_ptr(), 20, plaintext.as_mut_ptr(), 4);
+            assert_eq!((status, &plaintext), (ZanaStatus::Ok, message));
+            ciphertext[0] ^= 1;
+            let status = zana_aes_gcm_decrypt(key.as_ptr(), nonce.as_ptr(), ciphertext.as_ptr(), 20, plaintext.as_mut_ptr(), 4);
+            assert_eq!(status, ZanaStatus::DecryptionFailed);
+
+            let mut digest = [0u8; 32];
+            assert_eq!(zana_sha256(ptr::null(), 0, digest.as_mut_ptr()), ZanaStatus::Ok);
+            assert_eq!(digest.to_vec(), hash::sha256(b""));
+        }
+    }
+}
@@ -1,7 +1,20 @@
 pub mod circuit;
+pub mod algorithms;
 pub mod crypto;
 pub mod gameplay;
+pub mod error;
+pub mod config;
+// Model providers need a native HTTP client and async runtime.
+#[cfg(all(feature = "agents", not(target_arch = "wasm32")))]
 pub mod agents;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "server")]
+pub mod server;
+
+pub use error::ZanaError;
 
 pub fn add(left: u64, right: u64) -> u64 {
     left + right
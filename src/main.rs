@@ -0,0 +1,69 @@
+//! The `zana` command line.
+//!
+//! ```text
+//! zana serve [--config <zana.toml>] [--address <host:port>] [--max-qubits <n>] [--max-shots <n>]
+//! ```
+//!
+//! Settings come from the configuration file, `ZANA_CONFIG` or `zana.toml` by default, then from
+//! `ZANA_*` environment variables, then from the options.
+
+use std::process::ExitCode;
+use zana::config::Config;
+use zana::server::Server;
+
+const USAGE: &str =
+    "usage: zana serve [--config <zana.toml>] [--address <host:port>] [--max-qubits <n>] [--max-shots <n>]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("serve") => match serve(&args[1..]) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("zana: {}", error);
+                ExitCode::FAILURE
+            }
+        },
+        Some("-h" | "--help" | "help") => {
+            println!("{}", USAGE);
+            ExitCode::SUCCESS
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Reads the file given by `--config`, or the default configuration.
+fn load_config(args: &[String]) -> Result<Config, String> {
+    let path = args.iter().position(|arg| arg == "--config").map(|index| args.get(index + 1));
+    let config = match path {
+        Some(Some(path)) => Config::from_file(path).and_then(|mut config| config.apply_env().map(|()| config)),
+        Some(None) => return Err(format!("--config needs a value\n{}", USAGE)),
+        None => Config::load(),
+    };
+    config.map_err(|error| error.to_string())
+}
+
+fn serve(args: &[String]) -> Result<(), String> {
+    let config = load_config(args)?;
+    let mut address = config.server.address.clone();
+    let mut server = Server::from_config(&config);
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let value = args.next().ok_or_else(|| format!("{} needs a value\n{}", flag, USAGE))?;
+        let number = || value.parse::<usize>().map_err(|_| format!("{} takes a number, got '{}'", flag, value));
+        match flag.as_str() {
+            "--config" => {}
+            "--address" => address = value.clone(),
+            "--max-qubits" => server = server.with_max_qubits(number()?),
+            "--max-shots" => server = server.with_max_shots(number()?),
+            _ => return Err(format!("unknown option '{}'\n{}", flag, USAGE)),
+        }
+    }
+
+    eprintln!("zana: serving on http://{}", address);
+    server.run(&address).map_err(|error| error.to_string())
+}
@@ -0,0 +1,97 @@
+//! The `/crypto` routes. Bytes are hex encoded in both directions.
+
+use axum::Json;
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use crate::crypto::hash::HashAlgorithm;
+use crate::crypto::signatures;
+use crate::crypto::symmetric;
+use crate::crypto::utilities::{from_hex, to_hex};
+use super::ApiError;
+
+#[derive(Deserialize)]
+pub(super) struct HashRequest {
+    algorithm: String,
+    data: String,
+}
+
+pub(super) async fn hash(Json(request): Json<HashRequest>) -> Result<Json<Value>, ApiError> {
+    let algorithm = HashAlgorithm::from_name(&request.algorithm)
+        .ok_or_else(|| ApiError::bad_request(format!("unknown hash algorithm '{}'", request.algorithm)))?;
+    let digest = algorithm.hash(&from_hex(&request.data)?);
+    Ok(Json(json!({ "algorithm": algorithm.name(), "digest": to_hex(&digest) })))
+}
+
+pub(super) async fn ed25519_keypair() -> Json<Value> {
+    let keypair = signatures::generate_keypair();
+    Json(json!({
+        "secret_key": to_hex(&keypair.to_bytes()),
+        "public_key": to_hex(keypair.verifying_key().as_bytes()),
+    }))
+}
+
+#[derive(Deserialize)]
+pub(super) struct SignRequest {
+    secret_key: String,
+    message: String,
+}
+
+pub(super) async fn ed25519_sign(Json(request): Json<SignRequest>) -> Result<Json<Value>, ApiError> {
+    let secret = from_hex(&request.secret_key)?
+        .try_into()
+        .map_err(|_| ApiError::bad_request("an Ed25519 secret key is 32 bytes"))?;
+    let keypair = signatures::keypair_from_seed(secret);
+    let signature = signatures::sign_message(&keypair, &from_hex(&request.message)?);
+    Ok(Json(json!({ "signature": to_hex(&signature.to_bytes()) })))
+}
+
+#[derive(Deserialize)]
+pub(super) struct VerifyRequest {
+    public_key: String,
+    message: String,
+    signature: String,
+}
+
+/// Answers `{"valid": false}` for malformed keys and signatures, like a forged signature.
+pub(super) async fn ed25519_verify(Json(request): Json<VerifyRequest>) -> Result<Json<Value>, ApiError> {
+    let public_key = from_hex(&request.public_key)?;
+    let signature = from_hex(&request.signature)?;
+    let message = from_hex(&request.message)?;
+    let public_key = <[u8; 32]>::try_from(public_key.as_slice()).ok().and_then(|key| VerifyingKey::from_bytes(&key).ok());
+    let valid = match (public_key, Signature::from_slice(&signature)) {
+        (Some(public_key), Ok(signature)) => signatures::verify_message(&public_key, &message, &signature),
+        _ => false,
+    };
+    Ok(Json(json!({ "valid": valid })))
+}
+
+#[derive(Deserialize)]
+pub(super) struct EncryptRequest {
+    key: String,
+    plaintext: String,
+    /// Drawn at random when missing.
+    #[serde(default)]
+    nonce: Option<String>,
+}
+
+pub(super) async fn aes_gcm_encrypt(Json(request): Json<EncryptRequest>) -> Result<Json<Value>, ApiError> {
+    let nonce = match &request.nonce {
+        Some(nonce) => from_hex(nonce)?,
+        None => symmetric::generate_random_nonce()?.to_vec(),
+    };
+    let ciphertext = symmetric::encrypt(&from_hex(&request.key)?, &nonce, &from_hex(&request.plaintext)?)?;
+    Ok(Json(json!({ "nonce": to_hex(&nonce), "ciphertext": to_hex(&ciphertext) })))
+}
+
+#[derive(Deserialize)]
+pub(super) struct DecryptRequest {
+    key: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+pub(super) async fn aes_gcm_decrypt(Json(request): Json<DecryptRequest>) -> Result<Json<Value>, ApiError> {
+    let plaintext = symmetric::decrypt(&from_hex(&request.key)?, &from_hex(&request.nonce)?, &from_hex(&request.ciphertext)?)?;
+    Ok(Json(json!({ "plaintext": to_hex(&plaintext) })))
+}
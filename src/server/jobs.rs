@@ -0,0 +1,169 @@
+//! Simulation jobs: the circuits submitted to the server, simulated on blocking threads and
+//! kept in memory until they are deleted.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use crate::circuit::error::CircuitError;
+use crate::circuit::qasm::{add_named_gate, parse_qasm};
+use crate::circuit::statevector::Statevector;
+use crate::circuit::QuantumCircuit;
+use crate::config::RunConfig;
+
+/// A circuit as JSON: its width and its gates by OpenQASM name.
+///
+/// ```json
+/// {"qubits": 2, "gates": [{"gate": "h", "qubits": [0]}, {"gate": "cx", "qubits": [0, 1]}]}
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct CircuitSpec {
+    pub qubits: usize,
+    #[serde(default)]
+    pub gates: Vec<GateSpec>,
+}
+
+/// A gate of a [`CircuitSpec`], named as in [`add_named_gate`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct GateSpec {
+    pub gate: String,
+    pub qubits: Vec<usize>,
+    /// Angles, for the rotation and phase gates.
+    #[serde(default)]
+    pub params: Vec<f64>,
+}
+
+impl CircuitSpec {
+    pub fn build(&self) -> Result<QuantumCircuit, CircuitError> {
+        let mut circuit = QuantumCircuit::new(self.qubits);
+        for gate in &self.gates {
+            add_named_gate(&mut circuit, &gate.gate, &gate.params, &gate.qubits)?;
+        }
+        Ok(circuit)
+    }
+}
+
+/// The body of `POST /jobs`: a circuit as JSON or as OpenQASM, and the number of shots to
+/// sample counts from.
+#[derive(Debug, Clone, Deserialize)]
+pub struct JobRequest {
+    #[serde(default)]
+    pub circuit: Option<CircuitSpec>,
+    #[serde(default)]
+    pub qasm: Option<String>,
+    #[serde(default = "default_shots")]
+    pub shots: usize,
+    /// Seeds the sampling of counts, which then repeat exactly.
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+fn default_shots() -> usize {
+    1024
+}
+
+impl JobRequest {
+    /// Builds the circuit, which must be given exactly one way.
+    pub fn circuit(&self) -> Result<QuantumCircuit, String> {
+        match (&self.circuit, &self.qasm) {
+            (Some(spec), None) => spec.build().map_err(|error| error.to_string()),
+            (None, Some(qasm)) => parse_qasm(qasm).map_err(|error| error.to_string()),
+            _ => Err("a job needs exactly one of 'circuit' or 'qasm'".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+/// A job and, once it is done, its final state and sampled counts.
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub status: JobStatus,
+    pub qubits: usize,
+    pub shots: usize,
+    pub seed: Option<u64>,
+    pub error: Option<String>,
+    /// How long the simulation and the sampling took.
+    pub duration: Option<Duration>,
+    pub state: Option<Statevector>,
+    /// Basis states as bitstrings, qubit 0 rightmost, with how often they were sampled.
+    pub counts: BTreeMap<String, usize>,
+}
+
+/// The jobs of a server, by id.
+#[derive(Default)]
+pub struct JobStore {
+    next_id: AtomicU64,
+    jobs: Mutex<HashMap<u64, Job>>,
+}
+
+impl JobStore {
+    /// Queues a job for `circuit` and returns its id.
+    pub fn submit(&self, circuit: &QuantumCircuit, shots: usize, seed: Option<u64>) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let job = Job {
+            status: JobStatus::Queued,
+            qubits: circuit.qubits,
+            shots,
+            seed,
+            error: None,
+            duration: None,
+            state: None,
+            counts: BTreeMap::new(),
+        };
+        self.lock().insert(id, job);
+        id
+    }
+
+    /// Simulates a queued job, recording the outcome. Blocks for the length of the simulation.
+    pub fn run(&self, id: u64, circuit: &QuantumCircuit) {
+        let (shots, seed) = match self.lock().get_mut(&id) {
+            Some(job) => {
+                job.status = JobStatus::Running;
+                (job.shots, job.seed)
+            }
+            None => return, // Deleted before it started.
+        };
+
+        let start = Instant::now();
+        let outcome = circuit
+            .try_simulate()
+            .map(|result| (RunConfig { seed }.run(|| result.statevector.sample_counts(shots)), result.statevector));
+        let duration = start.elapsed();
+
+        if let Some(job) = self.lock().get_mut(&id) {
+            job.duration = Some(duration);
+            match outcome {
+                Ok((counts, state)) => {
+                    job.counts = counts;
+                    job.state = Some(state);
+                    job.status = JobStatus::Done;
+                }
+                Err(error) => {
+                    job.error = Some(error.to_string());
+                    job.status = JobStatus::Failed;
+                }
+            }
+        }
+    }
+
+    pub fn get(&self, id: u64) -> Option<Job> {
+        self.lock().get(&id).cloned()
+    }
+
+    pub fn remove(&self, id: u64) -> Option<Job> {
+        self.lock().remove(&id)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<u64, Job>> {
+        self.jobs.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,322 @@
+//! HTTP Server
+//!
+//! `zana serve`, built with the `server` feature, runs a REST API over the simulator and the
+//! crypto module, so that a team can share one large machine for simulation:
+//!
+//! | Route | |
+//! |---|---|
+//! | `GET /health` | Answers `{"status": "ok"}`. |
+//! | `POST /jobs` | Queues a circuit, given as [`CircuitSpec`] JSON or OpenQASM ([`JobRequest`]), and answers its id. |
+//! | `GET /jobs/{id}` | The status of a job: `queued`, `running`, `done` or `failed`. |
+//! | `GET /jobs/{id}/counts` | Measurement counts sampled from the final state. |
+//! | `GET /jobs/{id}/statevector` | The amplitudes of the final state. |
+//! | `DELETE /jobs/{id}` | Forgets a job. |
+//! | `POST /crypto/hash` | Hashes `data` with the named `algorithm`, such as `SHA-256`. |
+//! | `POST /crypto/ed25519/keypair` | Generates an Ed25519 keypair. |
+//! | `POST /crypto/ed25519/sign` | Signs a `message` with a `secret_key`. |
+//! | `POST /crypto/ed25519/verify` | Checks a `signature` of a `message` under a `public_key`. |
+//! | `POST /crypto/aes-gcm/encrypt` | Encrypts a `plaintext` with a `key` and an optional `nonce`. |
+//! | `POST /crypto/aes-gcm/decrypt` | Decrypts a `ciphertext` with its `key` and `nonce`. |
+//!
+//! Bytes are hex encoded. Failures are answered as `{"error": "..."}` with a 4xx status.
+//! Jobs are simulated on blocking threads and kept in memory until they are deleted.
+//!
+//! ```no_run
+//! use zana::server::Server;
+//!
+//! Server::new().with_max_qubits(24).run("0.0.0.0:8080").unwrap();
+//! ```
+
+pub mod jobs;
+mod crypto;
+
+use std::sync::Arc;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use crate::circuit::error::CircuitError;
+use crate::circuit::statevector::bitstring;
+use crate::config::Config;
+use crate::crypto::error::{CryptoError, EncodingError};
+pub use jobs::{CircuitSpec, GateSpec, Job, JobRequest, JobStatus, JobStore};
+
+/// The REST API, with its limits and its jobs.
+#[derive(Clone)]
+pub struct Server {
+    jobs: Arc<JobStore>,
+    max_qubits: usize,
+    max_shots: usize,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Self { jobs: Arc::default(), max_qubits: 20, max_shots: 1_000_000 }
+    }
+}
+
+impl Server {
+    /// A server accepting circuits of up to 20 qubits and jobs of up to a million shots.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A server limited to the widest circuit the `[simulator]` section of `config` allows.
+    pub fn from_config(config: &Config) -> Self {
+        match config.simulator.qubit_limit() {
+            Some(max_qubits) => Self::new().with_max_qubits(max_qubits),
+            None => Self::new(),
+        }
+    }
+
+    /// Rejects circuits wider than `max_qubits`. Memory grows as `2^qubits` for dense states.
+    pub fn with_max_qubits(mut self, max_qubits: usize) -> Self {
+        self.max_qubits = max_qubits;
+        self
+    }
+
+    pub fn with_max_shots(mut self, max_shots: usize) -> Self {
+        self.max_shots = max_shots;
+        self
+    }
+
+    /// The routes of the API, for serving or for embedding in a larger application.
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/health", get(|| async { Json(json!({ "status": "ok" })) }))
+            .route("/jobs", post(submit_job))
+            .route("/jobs/{id}", get(job_status).delete(delete_job))
+            .route("/jobs/{id}/counts", get(job_counts))
+            .route("/jobs/{id}/statevector", get(job_statevector))
+            .route("/crypto/hash", post(crypto::hash))
+            .route("/crypto/ed25519/keypair", post(crypto::ed25519_keypair))
+            .route("/crypto/ed25519/sign", post(crypto::ed25519_sign))
+            .route("/crypto/ed25519/verify", post(crypto::ed25519_verify))
+            .route("/crypto/aes-gcm/encrypt", post(crypto::aes_gcm_encrypt))
+            .route("/crypto/aes-gcm/decrypt", post(crypto::aes_gcm_decrypt))
+            .with_state(self)
+    }
+
+    /// Serves the API on `listener` until Ctrl-C is pressed.
+    pub async fn serve(self, listener: tokio::net::TcpListener) -> std::io::Result<()> {
+        axum::serve(listener, self.router())
+            .with_graceful_shutdown(async {
+                let _ = tokio::signal::ctrl_c().await;
+            })
+            .await
+    }
+
+    /// Binds `address`, such as `127.0.0.1:8080`, and serves the API on a new runtime until
+    /// Ctrl-C is pressed.
+    pub fn run(self, address: &str) -> std::io::Result<()> {
+        let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build()?;
+        runtime.block_on(async {
+            let listener = tokio::net::TcpListener::bind(address).await?;
+            self.serve(listener).await
+        })
+    }
+}
+
+/// A failed request, answered as `{"error": "..."}`.
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self { status: StatusCode::BAD_REQUEST, message: message.into() }
+    }
+
+    fn not_found(id: u64) -> Self {
+        Self { status: StatusCode::NOT_FOUND, message: format!("job {} not found", id) }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({ "error": self.message }))).into_response()
+    }
+}
+
+impl From<CircuitError> for ApiError {
+    fn from(error: CircuitError) -> Self {
+        ApiError::bad_request(error.to_string())
+    }
+}
+
+impl From<CryptoError> for ApiError {
+    fn from(error: CryptoError) -> Self {
+        ApiError::bad_request(error.to_string())
+    }
+}
+
+impl From<EncodingError> for ApiError {
+    fn from(error: EncodingError) -> Self {
+        ApiError::bad_request(error.to_string())
+    }
+}
+
+async fn submit_job(State(server): State<Server>, Json(request): Json<JobRequest>) -> Result<Response, ApiError> {
+    let circuit = request.circuit().map_err(ApiError::bad_request)?;
+    if circuit.qubits > server.max_qubits {
+        return Err(ApiError::bad_request(format!(
+            "circuit has {} qubits, more than the {} this server accepts",
+            circuit.qubits, server.max_qubits
+        )));
+    }
+    if request.shots > server.max_shots {
+        return Err(ApiError::bad_request(format!(
+            "{} shots requested, more than the {} this server accepts",
+            request.shots, server.max_shots
+        )));
+    }
+
+    let id = server.jobs.submit(&circuit, request.shots, request.seed);
+    let jobs = server.jobs.clone();
+    tokio::task::spawn_blocking(move || jobs.run(id, &circuit));
+    Ok((StatusCode::ACCEPTED, Json(json!({ "id": id, "status": JobStatus::Queued }))).into_response())
+}
+
+fn find_job(server: &Server, id: u64) -> Result<Job, ApiError> {
+    server.jobs.get(id).ok_or(ApiError::not_found(id))
+}
+
+/// The job, if it has finished simulating.
+fn finished_job(server: &Server, id: u64) -> Result<Job, ApiError> {
+    let job = find_job(server, id)?;
+    match job.status {
+        JobStatus::Done => Ok(job),
+        JobStatus::Failed => Err(ApiError::bad_request(job.error.unwrap_or_default())),
+        status => Err(ApiError {
+            status: StatusCode::CONFLICT,
+            message: format!("job {} is {}", id, json!(status).as_str().unwrap_or_default()),
+        }),
+    }
+}
+
+async fn job_status(State(server): State<Server>, Path(id): Path<u64>) -> Result<Json<Value>, ApiError> {
+    let job = find_job(&server, id)?;
+    Ok(Json(json!({
+        "id": id,
+        "status": job.status,
+        "qubits": job.qubits,
+        "shots": job.shots,
+        "seed": job.seed,
+        "error": job.error,
+        "duration_ms": job.duration.map(|duration| duration.as_secs_f64() * 1000.0),
+    })))
+}
+
+async fn job_counts(State(server): State<Server>, Path(id): Path<u64>) -> Result<Json<Value>, ApiError> {
+    let job = finished_job(&server, id)?;
+    Ok(Json(json!({ "id": id, "shots": job.shots, "counts": job.counts })))
+}
+
+async fn job_statevector(State(server): State<Server>, Path(id): Path<u64>) -> Result<Json<Value>, ApiError> {
+    let job = finished_job(&server, id)?;
+    let state = job.state.unwrap_or_else(|| unreachable!("done jobs keep their state"));
+    let mut amplitudes: Vec<_> = state.vector.iter().collect();
+    amplitudes.sort_by_key(|(&index, _)| index);
+    let amplitudes: Vec<Value> = amplitudes
+        .into_iter()
+        .map(|(&index, amplitude)| {
+            json!({
+                "state": bitstring(index, job.qubits),
+                "re": amplitude.re,
+                "im": amplitude.im,
+                "probability": amplitude.norm_sqr(),
+            })
+        })
+        .collect();
+    Ok(Json(json!({ "id": id, "qubits": job.qubits, "amplitudes": amplitudes })))
+}
+
+async fn delete_job(State(server): State<Server>, Path(id): Path<u64>) -> Result<StatusCode, ApiError> {
+    server.jobs.remove(id).ok_or(ApiError::not_found(id))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    async fn call(router: &Router, method: &str, uri: &str, body: Value) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap()
+    }
+
+    #[test]
+    fn test_job_lifecycle() {
+        runtime().block_on(async {
+            let router = Server::new().with_max_qubits(4).router();
+            let qasm = "OPENQASM 2.0;\nqreg q[2];\nh q[0];\ncx q[0], q[1];";
+            let (status, body) = call(&router, "POST", "/jobs", json!({ "qasm": qasm, "shots": 200 })).await;
+            assert_eq!(status, StatusCode::ACCEPTED);
+            let id = body["id"].as_u64().unwrap();
+
+            let uri = format!("/jobs/{}", id);
+            while call(&router, "GET", &uri, Value::Null).await.1["status"] != "done" {
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            }
+            let (_, counts) = call(&router, "GET", &format!("{}/counts", uri), Value::Null).await;
+            let counts = counts["counts"].as_object().unwrap();
+            assert!(counts.keys().all(|state| state == "00" || state == "11"));
+            assert_eq!(counts.values().map(|count| count.as_u64().unwrap()).sum::<u64>(), 200);
+
+            let (_, state) = call(&router, "GET", &format!("{}/statevector", uri), Value::Null).await;
+            assert_eq!(state["amplitudes"][1]["state"], "11");
+            assert!((state["amplitudes"][1]["probability"].as_f64().unwrap() - 0.5).abs() < 1e-12);
+
+            assert_eq!(call(&router, "DELETE", &uri, Value::Null).await.0, StatusCode::NO_CONTENT);
+            assert_eq!(call(&router, "GET", &uri, Value::Null).await.0, StatusCode::NOT_FOUND);
+
+            let wide = json!({ "circuit": { "qubits": 5, "gates": [{ "gate": "h", "qubits": [0] }] } });
+            assert_eq!(call(&router, "POST", "/jobs", wide).await.0, StatusCode::BAD_REQUEST);
+            let unknown = json!({ "circuit": { "qubits": 1, "gates": [{ "gate": "warp", "qubits": [0] }] } });
+            let (status, body) = call(&router, "POST", "/jobs", unknown).await;
+            assert_eq!((status, body["error"].as_str().unwrap()), (StatusCode::BAD_REQUEST, "unknown gate 'warp'"));
+        });
+    }
+
+    #[test]
+    fn test_crypto_routes() {
+        runtime().block_on(async {
+            let router = Server::new().router();
+            let (_, digest) = call(&router, "POST", "/crypto/hash", json!({ "algorithm": "SHA-256", "data": "616263" })).await;
+            assert_eq!(digest["digest"], "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+
+            let (_, keypair) = call(&router, "POST", "/crypto/ed25519/keypair", Value::Null).await;
+            let sign = json!({ "secret_key": keypair["secret_key"], "message": "dead" });
+            let (_, signature) = call(&router, "POST", "/crypto/ed25519/sign", sign).await;
+            let verify = json!({ "public_key": keypair["public_key"], "message": "dead", "signature": signature["signature"] });
+            assert_eq!(call(&router, "POST", "/crypto/ed25519/verify", verify).await.1["valid"], true);
+
+            let key = "07".repeat(32);
+            let (_, sealed) = call(&router, "POST", "/crypto/aes-gcm/encrypt", json!({ "key": key, "plaintext": "beef" })).await;
+            let open = json!({ "key": key, "nonce": sealed["nonce"], "ciphertext": sealed["ciphertext"] });
+            assert_eq!(call(&router, "POST", "/crypto/aes-gcm/decrypt", open).await.1["plaintext"], "beef");
+
+            let (status, body) = call(&router, "POST", "/crypto/hash", json!({ "algorithm": "md5", "data": "" })).await;
+            assert_eq!((status, body["error"].as_str().unwrap()), (StatusCode::BAD_REQUEST, "unknown hash algorithm 'md5'"));
+        });
+    }
+}
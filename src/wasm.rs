@@ -0,0 +1,262 @@
+//! WebAssembly Bindings
+//!
+//! [wasm-bindgen](https://rustwasm.github.io/docs/wasm-bindgen/) wrappers for browsers and
+//! web apps, built with the `wasm` feature, for example with
+//! `wasm-pack build --target web -- --features wasm`:
+//! - [`Circuit`] builds and simulates quantum circuits;
+//! - [`sha256`], [`sha3_256`], [`blake2b512`] and [`hash`] hash bytes;
+//! - [`Ed25519Keypair`] and [`ed25519_verify`] sign and verify messages;
+//! - [`aes_gcm_encrypt`] and [`aes_gcm_decrypt`] encrypt with AES-256-GCM.
+//!
+//! Bytes cross the boundary as `Uint8Array`s. Invalid input is thrown as a JavaScript `Error`
+//! instead of panicking.
+//!
+//! ```js
+//! import init, { Circuit } from "./pkg/zana.js";
+//!
+//! await init();
+//! const circuit = new Circuit(2);
+//! circuit.h(0);
+//! circuit.cnot(0, 1);
+//! console.log(circuit.probabilities()); // Float64Array [0.5, 0, 0, 0.5]
+//! ```
+
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use wasm_bindgen::prelude::*;
+use crate::circuit::gates::{self, Gate};
+use crate::circuit::QuantumCircuit;
+use crate::crypto::hash::{self as hashes, HashAlgorithm};
+use crate::crypto::signatures;
+use crate::crypto::symmetric;
+
+/// The most qubits [`Circuit::amplitudes`] and [`Circuit::probabilities`] return every state of:
+/// `2^24` amplitudes already take 256 MiB as interleaved `f64`s, and a 32-bit address space runs
+/// out soon after.
+pub const MAX_ARRAY_QUBITS: usize = 24;
+
+/// A quantum circuit, simulated from |0…0⟩.
+#[wasm_bindgen]
+pub struct Circuit {
+    inner: QuantumCircuit,
+}
+
+#[wasm_bindgen]
+impl Circuit {
+    #[wasm_bindgen(constructor)]
+    pub fn new(qubits: usize) -> Circuit {
+        Circuit { inner: QuantumCircuit::new(qubits) }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn qubits(&self) -> usize {
+        self.inner.qubits
+    }
+
+    /// The number of gates added so far.
+    #[wasm_bindgen(getter, js_name = gateCount)]
+    pub fn gate_count(&self) -> usize {
+        self.inner.gates.len()
+    }
+
+    pub fn h(&mut self, qubit: usize) -> Result<(), JsError> {
+        self.add(gates::hadamard(), &[qubit])
+    }
+
+    pub fn x(&mut self, qubit: usize) -> Result<(), JsError> {
+        self.add(gates::pauli_x(), &[qubit])
+    }
+
+    pub fn y(&mut self, qubit: usize) -> Result<(), JsError> {
+        self.add(gates::pauli_y(), &[qubit])
+    }
+
+    pub fn z(&mut self, qubit: usize) -> Result<(), JsError> {
+        self.add(gates::pauli_z(), &[qubit])
+    }
+
+    pub fn rx(&mut self, qubit: usize, theta: f64) -> Result<(), JsError> {
+        self.add(gates::rotation_x(theta), &[qubit])
+    }
+
+    pub fn ry(&mut self, qubit: usize, theta: f64) -> Result<(), JsError> {
+        self.add(gates::rotation_y(theta), &[qubit])
+    }
+
+    pub fn rz(&mut self, qubit: usize, theta: f64) -> Result<(), JsError> {
+        self.add(gates::rotation_z(theta), &[qubit])
+    }
+
+    /// Flips `target` when `control` is set.
+    pub fn cnot(&mut self, control: usize, target: usize) -> Result<(), JsError> {
+        // `gates::cnot()` takes its qubits as `[target, control]`, like `algorithms::add_cnot`.
+        self.add(gates::cnot(), &[target, control])
+    }
+
+    pub fn swap(&mut self, first: usize, second: usize) -> Result<(), JsError> {
+        self.add(gates::swap(), &[first, second])
+    }
+
+    /// The probability of measuring each basis state, indexed by the state.
+    pub fn probabilities(&self) -> Result<Vec<f64>, JsError> {
+        let amplitudes = self.amplitudes()?;
+        Ok(amplitudes.chunks(2).map(|amplitude| amplitude[0] * amplitude[0] + amplitude[1] * amplitude[1]).collect())
+    }
+
+    /// The amplitude of each basis state, as interleaved real and imaginary parts. Throws for
+    /// circuits of more than [`MAX_ARRAY_QUBITS`] qubits.
+    pub fn amplitudes(&self) -> Result<Vec<f64>, JsError> {
+        self.try_amplitudes().map_err(|error| JsError::new(&error))
+    }
+
+    /// Reverses the circuit, so that running it after the original restores the initial state.
+    pub fn inverse(&self) -> Circuit {
+        Circuit { inner: self.inner.inverse() }
+    }
+}
+
+impl Circuit {
+    fn try_amplitudes(&self) -> Result<Vec<f64>, String> {
+        if self.inner.qubits > MAX_ARRAY_QUBITS {
+            return Err(format!(
+                "cannot list the amplitudes of {} qubits, at most {} are supported",
+                self.inner.qubits, MAX_ARRAY_QUBITS
+            ));
+        }
+        let state = self.inner.try_simulate().map_err(|error| error.to_string())?.statevector;
+        let mut amplitudes = vec![0.0; 2 << self.inner.qubits];
+        for (&index, amplitude) in &state.vector {
+            amplitudes[2 * index] = amplitude.re;
+            amplitudes[2 * index + 1] = amplitude.im;
+        }
+        Ok(amplitudes)
+    }
+
+    fn add(&mut self, gate: Gate, qubits: &[usize]) -> Result<(), JsError> {
+        self.inner.try_add_gate(gate, qubits.to_vec()).map_err(|error| JsError::new(&error.to_string()))
+    }
+}
+
+#[wasm_bindgen]
+pub fn sha256(data: &[u8]) -> Vec<u8> {
+    hashes::sha256(data)
+}
+
+#[wasm_bindgen]
+pub fn sha3_256(data: &[u8]) -> Vec<u8> {
+    hashes::sha3_256(data)
+}
+
+#[wasm_bindgen]
+pub fn blake2b512(data: &[u8]) -> Vec<u8> {
+    hashes::blake2b512(data)
+}
+
+/// Hashes with the algorithm of the given name, such as `"sha512"`.
+#[wasm_bindgen]
+pub fn hash(algorithm: &str, data: &[u8]) -> Result<Vec<u8>, JsError> {
+    let algorithm = HashAlgorithm::from_name(algorithm)
+        .ok_or_else(|| JsError::new(&format!("unknown hash algorithm '{}'", algorithm)))?;
+    Ok(algorithm.hash(data))
+}
+
+/// An Ed25519 signing key.
+#[wasm_bindgen]
+pub struct Ed25519Keypair {
+    inner: SigningKey,
+}
+
+#[wasm_bindgen]
+impl Ed25519Keypair {
+    /// Generates a key from the browser's random number generator.
+    pub fn generate() -> Ed25519Keypair {
+        Ed25519Keypair { inner: signatures::generate_keypair() }
+    }
+
+    /// Rebuilds a key from its 32-byte secret.
+    #[wasm_bindgen(js_name = fromSecret)]
+    pub fn from_secret(secret: &[u8]) -> Result<Ed25519Keypair, JsError> {
+        let secret = secret.try_into().map_err(|_| JsError::new("an Ed25519 secret key is 32 bytes"))?;
+        Ok(Ed25519Keypair { inner: signatures::keypair_from_seed(secret) })
+    }
+
+    /// The 32-byte secret; keep it out of storage the page does not control.
+    pub fn secret(&self) -> Vec<u8> {
+        self.inner.to_bytes().to_vec()
+    }
+
+    #[wasm_bindgen(js_name = publicKey)]
+    pub fn public_key(&self) -> Vec<u8> {
+        self.inner.verifying_key().to_bytes().to_vec()
+    }
+
+    /// Signs a message, returning the 64-byte signature.
+    pub fn sign(&self, message: &[u8]) -> Vec<u8> {
+        signatures::sign_message(&self.inner, message).to_bytes().to_vec()
+    }
+}
+
+/// Checks an Ed25519 signature. Malformed keys and signatures do not verify.
+#[wasm_bindgen]
+pub fn ed25519_verify(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    let Ok(public_key) = public_key.try_into().map(VerifyingKey::from_bytes) else {
+        return false;
+    };
+    match (public_key, Signature::from_slice(signature)) {
+        (Ok(public_key), Ok(signature)) => signatures::verify_message(&public_key, message, &signature),
+        _ => false,
+    }
+}
+
+/// Encrypts with AES-256-GCM, given a 32-byte key and a 12-byte nonce.
+#[wasm_bindgen]
+pub fn aes_gcm_encrypt(key: &[u8], nonce: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, JsError> {
+    symmetric::encrypt(key, nonce, plaintext).map_err(|error| JsError::new(&error.to_string()))
+}
+
+/// Decrypts a ciphertext of [`aes_gcm_encrypt`], throwing if it does not authenticate.
+#[wasm_bindgen]
+pub fn aes_gcm_decrypt(key: &[u8], nonce: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, JsError> {
+    symmetric::decrypt(key, nonce, ciphertext).map_err(|error| JsError::new(&error.to_string()))
+}
+
+/// A fresh random 12-byte nonce for [`aes_gcm_encrypt`].
+#[wasm_bindgen]
+pub fn aes_gcm_nonce() -> Result<Vec<u8>, JsError> {
+    let nonce = symmetric::generate_random_nonce().map_err(|error| JsError::new(&error.to_string()))?;
+    Ok(nonce.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).unwrap();
+        circuit.cnot(0, 1).unwrap();
+        let probabilities = circuit.probabilities().unwrap();
+        assert_eq!(probabilities.len(), 4);
+        assert!((probabilities[0] - 0.5).abs() < 1e-12 && (probabilities[3] - 0.5).abs() < 1e-12);
+        assert_eq!(circuit.gate_count(), 2);
+        assert!(circuit.inner.check_qubits(&[2]).is_err());
+        assert!(circuit.inner.check_qubits(&[1, 1]).is_err());
+        assert!(Circuit::new(64).try_amplitudes().is_err());
+        assert!(Circuit::new(0).try_amplitudes().is_err());
+    }
+
+    #[test]
+    fn test_crypto() {
+        let keypair = Ed25519Keypair::generate();
+        let signature = keypair.sign(b"deed");
+        assert!(ed25519_verify(&keypair.public_key(), b"deed", &signature));
+        assert!(!ed25519_verify(&keypair.public_key(), b"forged", &signature));
+        assert!(!ed25519_verify(&[1, 2, 3], b"deed", &signature));
+
+        let key = [7u8; 32];
+        let nonce = aes_gcm_nonce().unwrap();
+        let ciphertext = aes_gcm_encrypt(&key, &nonce, b"secret").unwrap();
+        assert_eq!(aes_gcm_decrypt(&key, &nonce, &ciphertext).unwrap(), b"secret");
+        assert_eq!(sha256(b"abc"), hashes::sha256(b"abc"));
+    }
+}
@@ -0,0 +1,4 @@
+//! Integration tests of circuits and gates through the public API.
+
+mod test_circuits;
+mod test_gates;
@@ -3,8 +3,14 @@ use zana::circuit::{gates, QuantumCircuit};
 #[test]
 fn test_circuit_with_gates() {
     let mut circuit = QuantumCircuit::new(2);
-    circuit.add_gate(gates::hadamard());
-    circuit.add_gate(gates::cnot());
+    circuit.add_gate(gates::hadamard(), vec![0]);
+    circuit.add_gate(gates::cnot(), vec![1, 0]);
 
-    assert_eq!(circuit.gates, vec!["H", "CNOT"]);
+    let qubits: Vec<&[usize]> = circuit.gates.iter().map(|(_, qubits)| qubits.as_slice()).collect();
+    assert_eq!(qubits, [&[0][..], &[1, 0][..]]);
+
+    let probabilities = circuit.simulate().statevector.probabilities();
+    assert_eq!(probabilities.len(), 2);
+    assert!((probabilities[&0b00] - 0.5).abs() < 1e-10);
+    assert!((probabilities[&0b11] - 0.5).abs() < 1e-10);
 }
@@ -1,11 +1,22 @@
-use zana::circuit::gates;
+use num_complex::Complex;
+use zana::circuit::gates::{self, Gate};
 
 #[test]
 fn test_hadamard_gate() {
-    assert_eq!(gates::hadamard(), "H");
+    let h = 1.0 / 2f64.sqrt();
+    let Gate::Single(matrix) = gates::hadamard() else { panic!("Hadamard should be a single-qubit gate") };
+    assert_eq!(matrix, [[Complex::new(h, 0.0), Complex::new(h, 0.0)], [Complex::new(h, 0.0), Complex::new(-h, 0.0)]]);
 }
 
 #[test]
 fn test_cnot_gate() {
-    assert_eq!(gates::cnot(), "CNOT");
+    let Gate::Two(matrix) = gates::cnot() else { panic!("CNOT should be a two-qubit gate") };
+    // Flips qubit 0 of the gate when qubit 1 is set, swapping |10⟩ and |11⟩.
+    let permutation = [0, 1, 3, 2];
+    for (row, &column) in permutation.iter().enumerate() {
+        for (j, &entry) in matrix[row].iter().enumerate() {
+            let expected = if j == column { 1.0 } else { 0.0 };
+            assert_eq!(entry, Complex::new(expected, 0.0), "entry ({}, {})", row, j);
+        }
+    }
 }